@@ -1,5 +1,7 @@
 use std::cmp;
 use std::env;
+use std::path::Path;
+use std::sync::OnceLock;
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
@@ -16,14 +18,22 @@ mod trash_prediction;
 
 mod modules;
 
+// Interposing malloc also sees the allocations of the C and C++ sorts.
+#[cfg(all(feature = "instrument_alloc", target_os = "linux", target_env = "gnu"))]
+sort_research_rs::interpose_malloc!();
+
+#[cfg(all(
+    feature = "instrument_alloc",
+    not(all(target_os = "linux", target_env = "gnu"))
+))]
+#[global_allocator]
+static ALLOC: sort_research_rs::instrument::alloc::TracingAllocator =
+    sort_research_rs::instrument::alloc::TracingAllocator;
+
 use crate::modules::bench_len_type_pattern_combo;
 
 fn shuffle_vec<T: Ord>(mut v: Vec<T>) -> Vec<T> {
-    use rand::seq::SliceRandom;
-    use rand::thread_rng;
-
-    let mut rng = thread_rng();
-    v.shuffle(&mut rng);
+    patterns::shuffle(&mut v);
 
     v
 }
@@ -48,17 +58,43 @@ fn random_x_percent(len: usize, percent: f64) -> Vec<i32> {
     shuffle_vec(v)
 }
 
-fn bench_patterns<T: Ord + std::fmt::Debug>(
+// BENCH_PATTERN_FILE=<file> benchmarks the input of a file written by patterns::export instead of
+// the generated patterns, at its own len and under its pattern name. Every iteration sees the same
+// values.
+fn imported_pattern() -> Option<&'static patterns::ExportedPattern> {
+    static IMPORTED: OnceLock<Option<patterns::ExportedPattern>> = OnceLock::new();
+
+    IMPORTED
+        .get_or_init(|| {
+            let path = env::var("BENCH_PATTERN_FILE").ok()?;
+            Some(patterns::import(Path::new(&path)).unwrap())
+        })
+        .as_ref()
+}
+
+fn bench_patterns<T: Ord + std::fmt::Debug + 'static>(
     c: &mut Criterion,
     test_len: usize,
     transform_name: &str,
     transform: fn(Vec<i32>) -> Vec<T>,
 ) {
-    if test_len > 100_000 && (transform_name == "string" || transform_name == "1k") {
+    if test_len > 100_000 && matches!(transform_name, "string" | "1k" | "bytes") {
         // These are just too expensive.
         return;
     }
 
+    if let Some(imported) = imported_pattern() {
+        bench_len_type_pattern_combo(
+            c,
+            test_len,
+            transform_name,
+            &transform,
+            &imported.name,
+            &((|_| imported_pattern().unwrap().values.clone()) as fn(usize) -> Vec<i32>),
+        );
+        return;
+    }
+
     let mut pattern_providers: Vec<(&'static str, fn(usize) -> Vec<i32>)> = vec![
         ("random", patterns::random),
         ("random_z1", |len| patterns::random_zipf(len, 1.0)),
@@ -273,11 +309,42 @@ fn extend_i32_to_u64(val: i32) -> u64 {
 
 #[allow(unused)]
 fn extend_i32_to_u128(val: i32) -> u128 {
-    // Extends the value into the 64 bit range,
+    // Extends the value into the 128 bit range,
     // while preserving input order.
     (shift_i32_to_u32(val) as u128) * i64::MAX as u128
 }
 
+#[allow(unused)]
+fn extend_i32_to_i128(val: i32) -> i128 {
+    // Extends the value into the 128 bit range, negative values set all bits of the upper half,
+    // while preserving input order.
+    (val as i128) * i64::MAX as i128
+}
+
+#[allow(unused)]
+fn bytes_from_i32(val: i32) -> Vec<u8> {
+    // A shared prefix and the big endian value keep the input order. The len, between 16 and 4096,
+    // and the filler are derived from the value, so equal values give equal byte strings that are
+    // compared over their whole len.
+    let key = shift_i32_to_u32(val);
+    let hash = (key as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let len = 16 + ((hash >> 32) % (4096 - 16 + 1)) as usize;
+
+    let mut bytes = Vec::with_capacity(len);
+    bytes.extend_from_slice(b"/var/lib/db/");
+    bytes.extend_from_slice(&key.to_be_bytes());
+    bytes.resize(len, b'a' + (hash >> 59) as u8);
+    bytes
+}
+
+#[allow(unused)]
+fn bytes64_from_i32(val: i32) -> [u8; 64] {
+    // Only the last 4 bytes differ, every comparison is a memcmp over the whole array.
+    let mut bytes = [0; 64];
+    bytes[60..].copy_from_slice(&shift_i32_to_u32(val).to_be_bytes());
+    bytes
+}
+
 fn ensure_true_random() {
     // Ensure that random vecs are actually different.
     let random_vec_a = patterns::random(5);
@@ -303,8 +370,30 @@ fn criterion_benchmark(c: &mut Criterion) {
         22_367, 50_000, 100_000, 183_845, 400_000, 1_000_000, 2_000_000, 4_281_332, 10_000_000,
     ];
 
-    patterns::use_random_seed_each_time();
+    // Every sort gets the same inputs for a pattern and len, see patterns::use_paired_seeds.
+    patterns::use_paired_seeds();
     ensure_true_random();
+    println!("Patterns: {}", patterns::random_source_info());
+
+    // The inputs in corpus/ bring their own len and type, see analysis::corpus.
+    if env::var("BENCH_CORPUS").is_ok() {
+        modules::corpus::bench(c);
+        return;
+    }
+
+    let test_sizes = match imported_pattern() {
+        Some(imported) => {
+            println!(
+                "Imported pattern: {}, len: {}, seed: {}, rng: {}",
+                imported.name,
+                imported.values.len(),
+                imported.seed,
+                imported.rng
+            );
+            vec![imported.values.len()]
+        }
+        None => test_sizes.to_vec(),
+    };
 
     for test_len in test_sizes {
         // Basic type often used to test sorting algorithms.
@@ -348,6 +437,22 @@ fn criterion_benchmark(c: &mut Criterion) {
             });
         }
 
+        // Byte strings compared by memcmp, the regime of real world string and blob keys, as opposed
+        // to the short zero padded numbers of "string".
+        #[cfg(feature = "bench_type_bytes")]
+        {
+            bench_patterns(c, test_len, "bytes", |values| -> Vec<Vec<u8>> {
+                values.into_iter().map(bytes_from_i32).collect()
+            });
+        }
+
+        #[cfg(feature = "bench_type_bytes64")]
+        {
+            bench_patterns(c, test_len, "bytes64", |values| -> Vec<[u8; 64]> {
+                values.into_iter().map(bytes64_from_i32).collect()
+            });
+        }
+
         #[cfg(feature = "bench_type_u8")]
         {
             bench_patterns(c, test_len, "u8", |values| -> Vec<u8> {
@@ -380,6 +485,28 @@ fn criterion_benchmark(c: &mut Criterion) {
             });
         }
 
+        #[cfg(feature = "bench_type_i128")]
+        {
+            bench_patterns(c, test_len, "i128", |values| -> Vec<i128> {
+                values.into_iter().map(extend_i32_to_i128).collect()
+            });
+        }
+
+        // Interior mutability keeps the compiler from caching values in registers across calls to
+        // the comparison function, and makes the Rust sorts skip their specializations for types
+        // that are Freeze.
+        #[cfg(feature = "bench_type_cell")]
+        {
+            use std::cell::Cell;
+
+            bench_patterns(c, test_len, "cell_u32", |values| -> Vec<Cell<u32>> {
+                values
+                    .into_iter()
+                    .map(|val| Cell::new(shift_i32_to_u32(val)))
+                    .collect()
+            });
+        }
+
         #[cfg(feature = "bench_type_val_with_mutex")]
         {
             use std::cmp::Ordering;
@@ -428,6 +555,26 @@ fn criterion_benchmark(c: &mut Criterion) {
                     .collect()
             });
         }
+
+        // Fat pointers, 16 bytes on 64-bit machines, compared through a vtable call. The values
+        // alternate between two types, so the indirect branch of the comparison is unpredictable.
+        #[cfg(feature = "bench_type_dyn")]
+        {
+            use std::rc::Rc;
+
+            use sort_test_tools::dyn_types::{dyn_val_from_i32, DynTrait};
+
+            bench_patterns(c, test_len, "rc_dyn", |values| {
+                values
+                    .into_iter()
+                    .map(|val| -> Rc<dyn DynTrait> { dyn_val_from_i32(val).into() })
+                    .collect()
+            });
+
+            bench_patterns(c, test_len, "box_dyn", |values| {
+                values.into_iter().map(dyn_val_from_i32).collect()
+            });
+        }
     }
 }
 
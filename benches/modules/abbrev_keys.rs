@@ -0,0 +1,100 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::abbrev_keys::{self, AbbrevKey};
+
+use crate::modules::util;
+
+// The keys are derived from the i32 pattern once per len and pattern. `str` has distinct leading
+// bytes, so the abbreviations decide most comparisons. `prefixed_str` starts with the same 8
+// bytes, which makes the abbreviations useless and shows their overhead.
+
+fn bench_abbrev_fn<T: AbbrevKey + Clone>(
+    c: &mut Criterion,
+    keys: &[T],
+    type_name: &str,
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [T]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = keys.len();
+    let name = format!("{bench_name}-hot-{type_name}-{pattern_name}-{test_len}");
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || keys.to_vec(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+fn bench_strategies<T: AbbrevKey + Clone>(
+    c: &mut Criterion,
+    keys: &[T],
+    type_name: &str,
+    pattern_name: &str,
+) {
+    bench_abbrev_fn(c, keys, type_name, pattern_name, "direct_stable", |v| {
+        v.sort()
+    });
+    bench_abbrev_fn(c, keys, type_name, pattern_name, "direct_unstable", |v| {
+        v.sort_unstable()
+    });
+    bench_abbrev_fn(c, keys, type_name, pattern_name, "abbrev_stable", |v| {
+        abbrev_keys::sort(v)
+    });
+    bench_abbrev_fn(c, keys, type_name, pattern_name, "abbrev_unstable", |v| {
+        abbrev_keys::sort_unstable(v)
+    });
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The key types are fixed, only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    let values = pattern_provider(test_len)
+        .into_iter()
+        .map(|val| (val as u32) ^ (1 << 31))
+        .collect::<Vec<_>>();
+
+    // Zero extend to maintain the input order, same as the rust_string type.
+    let strings = values
+        .iter()
+        .map(|val| format!("{val:010}"))
+        .collect::<Vec<_>>();
+    bench_strategies(c, &strings, "str", pattern_name);
+
+    let prefixed_strings = values
+        .iter()
+        .map(|val| format!("/home/user/{val:010}"))
+        .collect::<Vec<_>>();
+    bench_strategies(c, &prefixed_strings, "prefixed_str", pattern_name);
+
+    // Same mapping as the u128 type of the main benchmarks.
+    let wide = values
+        .iter()
+        .map(|&val| val as u128 * i64::MAX as u128)
+        .collect::<Vec<_>>();
+    bench_strategies(c, &wide, "u128", pattern_name);
+}
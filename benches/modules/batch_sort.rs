@@ -0,0 +1,72 @@
+use criterion::Criterion;
+
+use sort_research_rs::other::batch_sort;
+use sort_research_rs::registry;
+use sort_test_tools::xorshift::XorShift;
+
+use crate::modules::util::bench_fn;
+
+// Slice lengths of the batch, either fixed or varying between 4 and 32 like the group sizes of a
+// typical aggregation.
+const BATCH_SPECS: [(&str, usize, usize); 5] = [
+    ("4", 4, 4),
+    ("8", 8, 8),
+    ("16", 16, 16),
+    ("32", 32, 32),
+    ("4to32", 4, 32),
+];
+
+// The slice lengths have to be the same for every sort, so they are derived from a fixed seed
+// instead of the pattern.
+fn batch_lens(min_len: usize, max_len: usize) -> impl FnMut() -> usize {
+    let mut rng = XorShift::new(0x2545_F491_4F6C_DD1D);
+    move || min_len + rng.below(max_len - min_len + 1)
+}
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // test_len is the total number of elements, split into slices.
+    if test_len < 4 {
+        return;
+    }
+
+    let entries = registry::sorts::<T>();
+
+    for (spec_name, min_len, max_len) in BATCH_SPECS {
+        for entry in &entries {
+            bench_fn(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                &format!("{}_batch_{spec_name}", entry.name()),
+                |v| {
+                    let mut slices = batch_sort::split_into_batch(v, batch_lens(min_len, max_len));
+                    batch_sort::sort_batch_with(&mut slices, |slice| entry.sort(slice));
+                },
+            );
+        }
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("insertion_sort_batch_{spec_name}"),
+            |v| {
+                let mut slices = batch_sort::split_into_batch(v, batch_lens(min_len, max_len));
+                batch_sort::sort_batch_insertion(&mut slices);
+            },
+        );
+    }
+}
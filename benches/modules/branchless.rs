@@ -0,0 +1,65 @@
+use criterion::Criterion;
+
+use sort_research_rs::other::branchless;
+
+use crate::modules::util::bench_fn;
+
+// Each primitive is applied to every full chunk of the input, this keeps the measured work
+// proportional to test_len and makes results comparable across CPUs for the same len.
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    if test_len < 4 {
+        return;
+    }
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "branchless_sort2",
+        |v| branchless::sort2_chunks(v, &mut |a, b| a.lt(b)),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "branchless_sort3",
+        |v| branchless::sort3_chunks(v, &mut |a, b| a.lt(b)),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "branchless_sort4",
+        |v| branchless::sort4_chunks(v, &mut |a, b| a.lt(b)),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "branchless_sort4_stable",
+        |v| branchless::sort4_stable_chunks(v, &mut |a, b| a.lt(b)),
+    );
+}
@@ -0,0 +1,117 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::collation;
+use sort_research_rs::registry;
+
+use sort_test_tools::ffi_types::FFIString;
+
+use crate::modules::util;
+
+// Every sort of the registry with collation::compare as comparison function, and the std sort by
+// cached sort keys. The elements are FFIString so the C and C++ sorts take part as well. A
+// comparison walks both names up to four times, the runtime follows the comparison count of a sort
+// more than anything else.
+//
+// The names share a prefix, followed by the i32 pattern in base 26 while preserving its order.
+// Accents and case only decide on the later levels and are derived from the value, so equal values
+// give equal names and the sorted patterns stay sorted.
+
+const TYPE_NAME: &str = "locale_str";
+
+fn name_from_i32(val: i32) -> String {
+    let key = ((val as u32) ^ (1 << 31)) as u64;
+    let mut hash = key.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32;
+
+    let mut name = String::from("Müller-");
+    for digit in (0..7).rev() {
+        let letter = (b'a' + (key / 26u64.pow(digit) % 26) as u8) as char;
+        name.push(match hash % 4 {
+            0 => accented(letter).unwrap_or(letter),
+            1 => letter.to_ascii_uppercase(),
+            _ => letter,
+        });
+        hash /= 4;
+    }
+
+    name
+}
+
+fn accented(letter: char) -> Option<char> {
+    match letter {
+        'a' => Some('á'),
+        'c' => Some('ç'),
+        'e' => Some('é'),
+        'i' => Some('î'),
+        'n' => Some('ñ'),
+        'o' => Some('ö'),
+        'u' => Some('ü'),
+        'y' => Some('ÿ'),
+        _ => None,
+    }
+}
+
+fn compare(a: &FFIString, b: &FFIString) -> std::cmp::Ordering {
+    // SAFETY: The names are valid strings, see FFIString::as_str_unchecked.
+    unsafe { collation::compare(a.as_str_unchecked(), b.as_str_unchecked()) }
+}
+
+fn bench_collation_fn(
+    c: &mut Criterion,
+    names: &[FFIString],
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [FFIString]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = names.len();
+    let name = format!("{bench_name}-hot-{TYPE_NAME}-{pattern_name}-{test_len}");
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || names.to_vec(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The element type is fixed, only run once per len and pattern. Larger lens take too long.
+    if transform_name != "i32" || test_len > 100_000 {
+        return;
+    }
+
+    let names = pattern_provider(test_len)
+        .into_iter()
+        .map(|val| FFIString::new(name_from_i32(val)))
+        .collect::<Vec<_>>();
+
+    for entry in registry::general_sorts::<FFIString>() {
+        bench_collation_fn(c, &names, pattern_name, entry.name(), |v| {
+            entry.sort_by(v, compare)
+        });
+    }
+
+    bench_collation_fn(c, &names, pattern_name, "rust_std_cached_key_stable", |v| {
+        // SAFETY: See compare.
+        v.sort_by_cached_key(|name| unsafe { collation::sort_key(name.as_str_unchecked()) })
+    });
+}
@@ -0,0 +1,117 @@
+use std::cmp::Ordering;
+
+use criterion::{black_box, Criterion};
+
+use sort_test_tools::Sort;
+
+use sort_research_rs::registry::{self, SortVisitor};
+
+use crate::modules::util::bench_fn;
+
+// Every sort with the same comparison, handed over in three ways:
+//
+// - closure: a closure type of its own, the sort is instantiated for it and can inline it, what a
+//   call of sort_by with a closure literal gets.
+// - fn_ptr: a `fn(&T, &T) -> Ordering`, every comparison is an indirect call. What a comparison
+//   chosen at runtime or passed through a table gets, and the Rust side of the FFI callbacks.
+// - dyn: a `&mut dyn FnMut`, every comparison goes through the vtable. What the registry entries,
+//   plugins and other type-erased consumers get.
+//
+// The fn pointer and the trait object pass through black_box, otherwise LLVM may see through them
+// and devirtualize the calls, which a real consumer can't rely on. The differences grow with the
+// share of the comparison in the runtime, they are largest for cheap comparisons such as i32 and
+// vanish for expensive ones such as String.
+
+fn bench_sort_impl<T: Ord + std::fmt::Debug, S: Sort>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let name = S::name();
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        &format!("{name}_closure"),
+        |v| S::sort_by(v, |a, b| a.cmp(b)),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        &format!("{name}_fn_ptr"),
+        |v| {
+            let compare: fn(&T, &T) -> Ordering = T::cmp;
+            S::sort_by(v, black_box(compare));
+        },
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        &format!("{name}_dyn"),
+        |v| {
+            let compare: &mut dyn FnMut(&T, &T) -> Ordering = &mut |a, b| a.cmp(b);
+            S::sort_by(v, black_box(compare));
+        },
+    );
+}
+
+struct DispatchBench<'a, T> {
+    c: &'a mut Criterion,
+    test_len: usize,
+    transform_name: &'a str,
+    transform: &'a fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &'a str,
+    pattern_provider: &'a fn(usize) -> Vec<i32>,
+}
+
+impl<T: Ord + std::fmt::Debug> SortVisitor for DispatchBench<'_, T> {
+    fn visit<S: Sort>(&mut self, _stable: bool, general: bool) {
+        if general {
+            bench_sort_impl::<T, S>(
+                self.c,
+                self.test_len,
+                self.transform_name,
+                self.transform,
+                self.pattern_name,
+                self.pattern_provider,
+            );
+        }
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // Every sort of the registry. The C and C++ sorts call the comparison through a fn pointer in
+    // any case, for them the three variants only differ by the cost of the conversion.
+    registry::visit_sorts(&mut DispatchBench {
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+    });
+}
@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+
+use criterion::Criterion;
+
+use sort_research_rs::analysis::corpus::{self, CorpusEntry};
+use sort_research_rs::analysis::inputs::{self, TypeVisitor};
+use sort_research_rs::registry;
+
+use crate::modules::util::bench_fn;
+
+struct CorpusBench<'a> {
+    c: &'a mut Criterion,
+    corpus_entry: &'a CorpusEntry,
+}
+
+impl TypeVisitor for CorpusBench<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let snapshot = &self.corpus_entry.snapshot;
+        let pattern_name = format!("corpus_{}", self.corpus_entry.name);
+
+        // Descending inputs are sorted with sort_by, which only the general-purpose sorts have.
+        for entry in registry::sorts::<T>()
+            .into_iter()
+            .filter(|entry| !snapshot.descending || entry.is_general())
+        {
+            bench_fn(
+                self.c,
+                snapshot.input.len(),
+                &snapshot.type_name,
+                &transform,
+                &pattern_name,
+                |_| snapshot.input.clone(),
+                entry.name(),
+                |v| {
+                    if snapshot.descending {
+                        entry.sort_by(v, |a, b| b.cmp(a));
+                    } else {
+                        entry.sort(v);
+                    }
+                },
+            );
+        }
+    }
+}
+
+/// Benchmarks every sort on every input in corpus/, with the type the input was recorded with.
+/// The pattern name is `corpus_<name>`.
+pub fn bench(c: &mut Criterion) {
+    let corpus_entries = corpus::load(&corpus::dir()).unwrap();
+
+    for corpus_entry in &corpus_entries {
+        let corpus_bench = CorpusBench { c, corpus_entry };
+        if !inputs::with_type(&corpus_entry.snapshot.type_name, corpus_bench) {
+            panic!(
+                "Unknown type '{}' in corpus/{}.bin",
+                corpus_entry.snapshot.type_name, corpus_entry.name
+            );
+        }
+    }
+}
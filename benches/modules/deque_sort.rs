@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::deque_sort;
+
+use crate::modules::util;
+
+// Where the storage of the deque wraps around, as fraction of the len. 0 is already contiguous.
+const SPLITS: [(&str, usize, usize); 3] = [("split0", 0, 1), ("split10", 1, 10), ("split50", 1, 2)];
+
+fn bench_deque_fn<T>(
+    c: &mut Criterion,
+    test_len: usize,
+    input: &dyn Fn() -> Vec<T>,
+    split: usize,
+    bench_name: &str,
+    test_fn: impl Fn(&mut VecDeque<T>),
+) {
+    util::pin_thread_to_core();
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(bench_name) {
+        c.bench_function(bench_name, |b| {
+            b.iter_batched_ref(
+                || deque_sort::wrapped_deque(input(), split),
+                |test_data| {
+                    test_fn(black_box(test_data));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let input = || transform(pattern_provider(test_len));
+
+    for (split_name, num, denom) in SPLITS {
+        let split = test_len * num / denom;
+        let name = |sort_name: &str| {
+            format!("{sort_name}_{split_name}-hot-{transform_name}-{pattern_name}-{test_len}")
+        };
+
+        bench_deque_fn(
+            c,
+            test_len,
+            &input,
+            split,
+            &name("deque_sort_stable"),
+            |d| deque_sort::sort(d),
+        );
+        bench_deque_fn(
+            c,
+            test_len,
+            &input,
+            split,
+            &name("deque_sort_unstable"),
+            |d| deque_sort::sort_unstable(d),
+        );
+        bench_deque_fn(
+            c,
+            test_len,
+            &input,
+            split,
+            &name("make_contiguous_stable"),
+            |d| d.make_contiguous().sort(),
+        );
+        bench_deque_fn(
+            c,
+            test_len,
+            &input,
+            split,
+            &name("make_contiguous_unstable"),
+            |d| d.make_contiguous().sort_unstable(),
+        );
+        bench_deque_fn(
+            c,
+            test_len,
+            &input,
+            split,
+            &name("copy_to_vec_stable"),
+            |d| {
+                let mut v = d.drain(..).collect::<Vec<_>>();
+                v.sort();
+                d.extend(v);
+            },
+        );
+    }
+}
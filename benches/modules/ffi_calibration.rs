@@ -0,0 +1,85 @@
+use criterion::{black_box, Criterion};
+
+use sort_research_rs::other::ffi_calibration::{self, PanicStrategy};
+
+use crate::modules::util::bench_fn;
+
+// Roughly the number of comparisons a sort performs for test_len, so the measured overhead is
+// in the same ballpark as the one contained in a sort_by benchmark of the same len.
+fn comp_count(test_len: usize) -> usize {
+    test_len * (test_len.max(2).ilog2() as usize)
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    if !matches!(transform_name, "i32" | "u64") {
+        return;
+    }
+
+    let comp_count = comp_count(test_len);
+
+    // ffi_null_sort minus ffi_null_sort_abort_on_panic is the cost of catch_unwind per call, and
+    // rust_callback_null_sort minus rust_null_sort the cost of the wrapper without the language
+    // boundary.
+    for (panic_strategy, suffix) in [
+        (PanicStrategy::CatchUnwind, ""),
+        (PanicStrategy::AbortOnPanic, "_abort_on_panic"),
+    ] {
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("ffi_null_sort{suffix}"),
+            |v| {
+                black_box(ffi_calibration::ffi_null_sort_by(
+                    v,
+                    comp_count,
+                    panic_strategy,
+                    |a, b| a.cmp(b),
+                ));
+            },
+        );
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("rust_callback_null_sort{suffix}"),
+            |v| {
+                black_box(ffi_calibration::rust_callback_null_sort_by(
+                    v,
+                    comp_count,
+                    panic_strategy,
+                    |a, b| a.cmp(b),
+                ));
+            },
+        );
+    }
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "rust_null_sort",
+        |v| {
+            black_box(ffi_calibration::rust_null_sort_by(v, comp_count, |a, b| {
+                a.cmp(b)
+            }));
+        },
+    );
+}
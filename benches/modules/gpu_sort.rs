@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::gpu_sort::{self, GpuSorter, GpuTimings};
+use sort_research_rs::registry;
+
+use crate::modules::util;
+
+// The GPU sort only supports u32 and f32 keys, so the inputs are derived from the i32 patterns
+// directly. Besides the wall-clock time of a whole GPU sort, the time spent in the kernels and in
+// the transfers is reported as separate benchmarks, measured by the sort itself.
+
+type Measure = fn(&GpuTimings) -> Duration;
+
+fn shift_i32_to_u32(val: i32) -> u32 {
+    (val as i64 + (i32::MAX as i64 + 1)) as u32
+}
+
+fn bench_name(name: &str, type_name: &str, pattern_name: &str, test_len: usize) -> String {
+    format!("{name}-hot-{type_name}-{pattern_name}-{test_len}")
+}
+
+fn bench_gpu_fn<T: Copy>(
+    c: &mut Criterion,
+    input: &[T],
+    name: &str,
+    test_fn: impl Fn(&mut [T]) -> GpuTimings,
+    measure: impl Fn(&GpuTimings) -> Duration,
+) {
+    if util::should_run_benchmark(name) {
+        c.bench_function(name, |b| {
+            b.iter_custom(|iters| {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    let mut test_data = input.to_vec();
+                    let timings = test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(&test_data); // side-effect
+                    elapsed += measure(&timings);
+                }
+                elapsed
+            })
+        });
+    }
+}
+
+fn bench_gpu(c: &mut Criterion, sorter: &GpuSorter, pattern_name: &str, values: &[i32]) {
+    let test_len = values.len();
+    let keys_u32 = values
+        .iter()
+        .map(|&val| shift_i32_to_u32(val))
+        .collect::<Vec<_>>();
+    let keys_f32 = values.iter().map(|&val| val as f32).collect::<Vec<_>>();
+
+    let measures: [(&str, Measure); 3] = [
+        ("gpu_bitonic", GpuTimings::total),
+        ("gpu_bitonic_kernel", |timings| timings.sort),
+        ("gpu_bitonic_transfer", |timings| {
+            timings.upload + timings.download
+        }),
+    ];
+
+    for (name, measure) in measures {
+        bench_gpu_fn(
+            c,
+            &keys_u32,
+            &bench_name(name, "u32", pattern_name, test_len),
+            |v| sorter.sort_u32(v),
+            measure,
+        );
+        bench_gpu_fn(
+            c,
+            &keys_f32,
+            &bench_name(name, "f32", pattern_name, test_len),
+            |v| sorter.sort_f32(v),
+            measure,
+        );
+    }
+
+    // The CPU sorts on the same keys, to find the crossover.
+    util::pin_thread_to_core();
+    for entry in registry::sorts::<u32>() {
+        let name = bench_name(entry.name(), "u32", pattern_name, test_len);
+        if util::should_run_benchmark(&name) {
+            c.bench_function(&name, |b| {
+                b.iter_batched_ref(
+                    || keys_u32.clone(),
+                    |test_data| {
+                        entry.sort(black_box(test_data.as_mut_slice()));
+                        black_box(test_data); // side-effect
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The key types are fixed, only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    let Some(sorter) = gpu_sort::sorter() else {
+        eprintln!("No GPU adapter found, skipping the gpu_sort benchmarks.");
+        return;
+    };
+
+    static PRINT_ADAPTER: std::sync::Once = std::sync::Once::new();
+    PRINT_ADAPTER.call_once(|| eprintln!("GPU adapter: {}", sorter.adapter_name()));
+
+    bench_gpu(c, sorter, pattern_name, &pattern_provider(test_len));
+}
@@ -0,0 +1,107 @@
+use criterion::Criterion;
+
+use sort_research_rs::other::abbrev_keys::AbbrevKey;
+use sort_research_rs::other::hybrid_bucket_sort;
+use sort_test_tools::patterns;
+
+use crate::modules::util::bench_fn;
+
+// Keys with `bits` bits of entropy each, from 2 distinct keys up to 2^24, the random pattern has 32
+// bits. Same inputs as the random_dN patterns with N = 2^bits.
+const ENTROPY_BITS: [u32; 7] = [1, 2, 4, 8, 12, 16, 24];
+
+fn bench_type<T: AbbrevKey + Copy + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &dyn Fn(usize) -> Vec<i32>,
+) {
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "rust_std_unstable_direct",
+        |v| v.sort_unstable(),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "hybrid_bucket_unstable",
+        hybrid_bucket_sort::sort_unstable,
+    );
+}
+
+fn bench_pattern(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &dyn Fn(usize) -> Vec<i32>,
+) {
+    match transform_name {
+        "i32" => {
+            let transform: fn(Vec<i32>) -> Vec<i32> = |values| values;
+            bench_type(
+                c,
+                test_len,
+                transform_name,
+                &transform,
+                pattern_name,
+                pattern_provider,
+            );
+        }
+        "u64" => {
+            // Same mapping as the u64 type of the main benchmarks, the entropy is in the high bits.
+            let transform: fn(Vec<i32>) -> Vec<u64> = |values| {
+                values
+                    .into_iter()
+                    .map(|val| ((val as u32) ^ (1 << 31)) as u64 * i32::MAX as u64)
+                    .collect()
+            };
+            bench_type(
+                c,
+                test_len,
+                transform_name,
+                &transform,
+                pattern_name,
+                pattern_provider,
+            );
+        }
+        // Only the integer keys have an abbreviation that is the full key.
+        _ => {}
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    bench_pattern(c, test_len, transform_name, pattern_name, pattern_provider);
+
+    // The entropy sweep is run once per type and len, alongside the random pattern which is run
+    // for every len.
+    if pattern_name == "random" {
+        for bits in ENTROPY_BITS {
+            bench_pattern(
+                c,
+                test_len,
+                transform_name,
+                &format!("random_bits{bits}"),
+                &|len| patterns::random_uniform(len, 0..(1 << bits)),
+            );
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use criterion::{black_box, Criterion};
+
+use sort_research_rs::other::is_sorted;
+use sort_research_rs::registry;
+
+use crate::modules::util::bench_fn;
+
+// The checks alone, and every sort of the registry behind the check as `<sort>_checked`. Compared
+// with the plain sorts of the default benchmarks, the checked ones show what the pre-pass saves on
+// sorted inputs and what it costs on all others. The cost is highest for `ascending` inputs with
+// the last element out of place, the `ascending_last` pattern of this benchmark.
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    bench_pattern(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+    );
+
+    if pattern_name == "ascending" {
+        let ascending_last = |len| {
+            let mut v = pattern_provider(len);
+            if let Some(last) = v.last_mut() {
+                *last = i32::MIN;
+            }
+            v
+        };
+
+        bench_pattern(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            "ascending_last",
+            ascending_last,
+        );
+    }
+}
+
+fn bench_pattern<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: impl Fn(usize) -> Vec<i32> + Copy,
+) {
+    let checks: [(&str, fn(&[T]) -> bool); 3] = [
+        ("is_sorted_std", |v| v.is_sorted()),
+        ("is_sorted_chunked", |v| {
+            is_sorted::is_sorted_by_chunked(v, &mut |a, b| a.lt(b))
+        }),
+        ("is_sorted_simd", is_sorted::is_sorted_simd),
+    ];
+
+    for (name, check) in checks {
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            name,
+            |v| {
+                black_box(check(v));
+            },
+        );
+    }
+
+    for entry in registry::sorts::<T>() {
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("{}_checked", entry.name()),
+            |v| {
+                if !is_sorted::is_sorted_simd(v) {
+                    entry.sort(v);
+                }
+            },
+        );
+    }
+}
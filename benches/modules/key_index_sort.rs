@@ -0,0 +1,78 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::key_index_sort::{self, Record};
+
+use crate::modules::util;
+
+// The records are always sorted by their i32 key, so the element type of the pattern doesn't
+// matter.
+
+fn bench_record_fn<const N: usize>(
+    c: &mut Criterion,
+    records: &[Record<N>],
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [Record<N>]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = records.len();
+    let type_name = format!("record{}", std::mem::size_of::<Record<N>>());
+    let name = format!("{bench_name}-hot-{type_name}-{pattern_name}-{test_len}");
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || records.to_vec(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+fn bench_record<const N: usize>(c: &mut Criterion, keys: &[i32], pattern_name: &str) {
+    let records = keys
+        .iter()
+        .enumerate()
+        .map(|(i, &key)| Record::<N>::new(i as u32, key))
+        .collect::<Vec<_>>();
+
+    bench_record_fn(c, &records, pattern_name, "std_unstable_by_key", |v| {
+        v.sort_unstable_by_key(|record| record.key)
+    });
+    bench_record_fn(c, &records, pattern_name, "direct_unstable", |v| {
+        key_index_sort::sort_direct(v, |record| record.key)
+    });
+    bench_record_fn(c, &records, pattern_name, "key_index_unstable", |v| {
+        key_index_sort::sort_key_index(v, |record| record.key)
+    });
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // Only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    let keys = pattern_provider(test_len);
+
+    bench_record::<15>(c, &keys, pattern_name);
+    bench_record::<63>(c, &keys, pattern_name);
+    bench_record::<255>(c, &keys, pattern_name);
+}
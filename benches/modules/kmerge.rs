@@ -0,0 +1,104 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::kmerge::{self, galloping};
+
+use crate::modules::util;
+
+// The pattern is split into k runs that are sorted as part of the setup, which is not measured.
+// The baselines concatenate the runs and sort them, the stable sort detects and merges the runs
+// itself.
+
+fn bench_merge_fn<T>(
+    c: &mut Criterion,
+    test_len: usize,
+    runs: &dyn Fn() -> Vec<Vec<T>>,
+    bench_name: &str,
+    test_fn: impl Fn(Vec<Vec<T>>) -> Vec<T>,
+) {
+    util::pin_thread_to_core();
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(bench_name) {
+        c.bench_function(bench_name, |b| {
+            b.iter_batched(runs, |runs| black_box(test_fn(black_box(runs))), batch_size)
+        });
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    for run_count in [4, 16, 64, 256] {
+        if run_count > test_len {
+            continue;
+        }
+
+        let runs = || kmerge::split_into_runs(transform(pattern_provider(test_len)), run_count);
+        let name = |merge_name: &str| {
+            format!("{merge_name}_k{run_count}-hot-{transform_name}-{pattern_name}-{test_len}")
+        };
+
+        for (merge_name, merge_fn) in kmerge::strategies::<T>() {
+            bench_merge_fn(c, test_len, &runs, &name(merge_name), merge_fn);
+        }
+
+        bench_merge_fn(c, test_len, &runs, &name("concat_sort_stable"), |runs| {
+            let mut v = runs.into_iter().flatten().collect::<Vec<_>>();
+            v.sort();
+            v
+        });
+        bench_merge_fn(c, test_len, &runs, &name("concat_sort_unstable"), |runs| {
+            let mut v = runs.into_iter().flatten().collect::<Vec<_>>();
+            v.sort_unstable();
+            v
+        });
+    }
+}
+
+// From galloping after every win to never galloping, 7 is the default of timsort and
+// galloping::MIN_GALLOP.
+const MIN_GALLOPS: [usize; 9] = [1, 2, 3, 4, 7, 12, 24, 64, usize::MAX];
+
+/// Sweeps the galloping threshold of `kmerge::galloping`, the fastest value per pattern and run
+/// count is the one to pick.
+pub fn bench_min_gallop<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    for run_count in [4, 64] {
+        if run_count > test_len {
+            continue;
+        }
+
+        let runs = || kmerge::split_into_runs(transform(pattern_provider(test_len)), run_count);
+
+        for min_gallop in MIN_GALLOPS {
+            let gallop_name = if min_gallop == usize::MAX {
+                "off".to_string()
+            } else {
+                min_gallop.to_string()
+            };
+            let name = format!(
+                "galloping_g{gallop_name}_k{run_count}-hot-{transform_name}-{pattern_name}-{test_len}"
+            );
+
+            bench_merge_fn(c, test_len, &runs, &name, |runs| {
+                galloping::merge_with_min_gallop(runs, min_gallop)
+            });
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+
+use criterion::{black_box, Criterion};
+
+use sort_research_rs::other::memo_sort;
+
+use crate::modules::util::bench_fn;
+
+// Roughly a few hundred nanoseconds, in the range of comparing long strings with a locale aware
+// collation or keys that have to be computed on the fly.
+const EXPENSIVE_CMP_ROUNDS: usize = 500;
+
+type CmpFn<T> = fn(&T, &T) -> Ordering;
+
+fn expensive_cmp<T: Ord>(a: &T, b: &T) -> Ordering {
+    for i in 0..EXPENSIVE_CMP_ROUNDS {
+        black_box(i);
+    }
+
+    a.cmp(b)
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let cmp_fns: [(&str, CmpFn<T>); 2] = [("cheap", T::cmp), ("expensive", expensive_cmp::<T>)];
+
+    for (cmp_name, cmp_fn) in cmp_fns {
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("rust_std_stable_{cmp_name}_cmp"),
+            |v| v.sort_by(cmp_fn),
+        );
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("memo_sort_stable_{cmp_name}_cmp"),
+            |v| {
+                black_box(memo_sort::sort_by(v, cmp_fn));
+            },
+        );
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("rust_std_unstable_{cmp_name}_cmp"),
+            |v| v.sort_unstable_by(cmp_fn),
+        );
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("memo_sort_unstable_{cmp_name}_cmp"),
+            |v| {
+                black_box(memo_sort::sort_unstable_by(v, cmp_fn));
+            },
+        );
+    }
+}
@@ -4,14 +4,75 @@ use criterion::Criterion;
 
 pub mod sort;
 
+pub mod placement;
+
+pub mod noise;
+
+#[cfg(feature = "branchless")]
+pub mod branchless;
+
+pub mod memo_sort;
+
+pub mod batch_sort;
+
+pub mod workload;
+
+pub mod deque_sort;
+
+pub mod sorted_iter;
+
+pub mod permute;
+
+pub mod is_sorted;
+
+pub mod row_sort;
+
+pub mod key_index_sort;
+
+pub mod abbrev_keys;
+
+pub mod multi_key;
+
+pub mod hybrid_bucket_sort;
+
+pub mod time_limited_sort;
+
+pub mod top_k;
+
+pub mod type_erased;
+
+pub mod comparator_dispatch;
+
+pub mod kmerge;
+
+pub mod corpus;
+
+#[cfg(feature = "ffi_calibration")]
+pub mod ffi_calibration;
+
+#[cfg(feature = "cpp_str_sort")]
+pub mod str_sort;
+
+#[cfg(feature = "gpu")]
+pub mod gpu_sort;
+
 #[cfg(feature = "partition_point")]
 pub mod partition_point;
 
 #[cfg(feature = "partition")]
 pub mod partition;
 
+#[cfg(feature = "small_sort")]
+pub mod small_sort_zoo;
+
+#[cfg(feature = "rotate")]
+pub mod rotate;
+
+#[cfg(feature = "collation")]
+pub mod collation;
+
 #[allow(unused)]
-pub fn bench_len_type_pattern_combo<T: Ord + std::fmt::Debug>(
+pub fn bench_len_type_pattern_combo<T: Ord + std::fmt::Debug + 'static>(
     c: &mut Criterion,
     test_len: usize,
     transform_name: &str,
@@ -43,6 +104,213 @@ pub fn bench_len_type_pattern_combo<T: Ord + std::fmt::Debug>(
                     pattern_provider,
                 );
             }
+            #[cfg(feature = "small_sort")]
+            "small_sort_zoo" => {
+                small_sort_zoo::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            #[cfg(feature = "rotate")]
+            "rotate" => {
+                rotate::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            #[cfg(feature = "ffi_calibration")]
+            "ffi_calibration" => {
+                ffi_calibration::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            #[cfg(feature = "cpp_str_sort")]
+            "str_sort" => {
+                str_sort::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            #[cfg(feature = "collation")]
+            "collation" => {
+                collation::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            #[cfg(feature = "gpu")]
+            "gpu_sort" => {
+                gpu_sort::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            #[cfg(feature = "branchless")]
+            "branchless" => {
+                branchless::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "batch_sort" => {
+                batch_sort::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "workload" => {
+                workload::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "deque_sort" => {
+                deque_sort::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "sorted_iter" => {
+                sorted_iter::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "permute" => {
+                permute::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "is_sorted" => {
+                is_sorted::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "row_sort" => {
+                row_sort::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            "key_index_sort" => {
+                key_index_sort::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            "abbrev_keys" => {
+                abbrev_keys::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            "multi_key" => {
+                multi_key::bench(c, test_len, transform_name, pattern_name, pattern_provider);
+            }
+            "hybrid_bucket_sort" => {
+                hybrid_bucket_sort::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "time_limited_sort" => {
+                time_limited_sort::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "top_k" => {
+                top_k::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "type_erased" => {
+                type_erased::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "comparator_dispatch" => {
+                comparator_dispatch::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "kmerge" => {
+                kmerge::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "min_gallop" => {
+                kmerge::bench_min_gallop(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
+            "memo_sort" => {
+                memo_sort::bench(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                );
+            }
             _ => panic!(
                 "Unknown BENCH_OTHER value: '{}'. Make sure the feature is enabled.",
                 env_val
@@ -0,0 +1,93 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::multi_key;
+
+use crate::modules::util;
+
+// The first field only takes 16 distinct values, so most comparisons late in the sort have to look
+// at the second field. Both fields are derived from the i32 pattern while preserving its order, so
+// the sorted patterns stay sorted.
+
+type U32StrSort = fn(&mut [(u32, String)]);
+type F64U64Sort = fn(&mut [(f64, u64)]);
+
+fn bench_multi_key_fn<T: Clone>(
+    c: &mut Criterion,
+    keys: &[T],
+    type_name: &str,
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [T]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = keys.len();
+    let name = format!("{bench_name}-hot-{type_name}-{pattern_name}-{test_len}");
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || keys.to_vec(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The key types are fixed, only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    let values = pattern_provider(test_len)
+        .into_iter()
+        .map(|val| (val as u32) ^ (1 << 31))
+        .collect::<Vec<_>>();
+
+    let u32_str = values
+        .iter()
+        .map(|&val| (val >> 28, format!("{val:010}")))
+        .collect::<Vec<_>>();
+
+    let u32_str_fns: [(&str, U32StrSort); 4] = [
+        ("chained_stable", multi_key::sort_chained_u32_str),
+        ("chained_unstable", multi_key::sort_chained_u32_str_unstable),
+        ("encoded_stable", multi_key::sort_encoded_u32_str),
+        ("tuple_ord_stable", |v| v.sort()),
+    ];
+    for (bench_name, test_fn) in u32_str_fns {
+        bench_multi_key_fn(c, &u32_str, "u32_string", pattern_name, bench_name, test_fn);
+    }
+
+    let f64_u64 = values
+        .iter()
+        .map(|&val| ((val >> 28) as f64 * 0.5, val as u64))
+        .collect::<Vec<_>>();
+
+    let f64_u64_fns: [(&str, F64U64Sort); 4] = [
+        ("chained_stable", multi_key::sort_chained_f64_u64),
+        ("chained_unstable", multi_key::sort_chained_f64_u64_unstable),
+        ("encoded_stable", multi_key::sort_encoded_f64_u64),
+        ("encoded_unstable", multi_key::sort_encoded_f64_u64_unstable),
+    ];
+    for (bench_name, test_fn) in f64_u64_fns {
+        bench_multi_key_fn(c, &f64_u64, "f64_u64", pattern_name, bench_name, test_fn);
+    }
+}
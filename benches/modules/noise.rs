@@ -0,0 +1,140 @@
+//! Background load while a sort is measured, for `BENCH_NOISE`.
+//!
+//! A server rarely sorts on an idle machine. Noise threads that stream through a buffer larger
+//! than the last level cache take memory bandwidth and evict cache lines, sorts that depend on
+//! either slow down more than compute bound ones. Noise threads that only spin on registers take
+//! shared resources such as turbo headroom, and the other hyperthread if the core has SMT.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use criterion::black_box;
+
+use sort_test_tools::xorshift::XorShift;
+
+use crate::modules::util::BENCH_CORE_INDEX;
+
+/// Per thread buffer of memory noise, larger than the last level cache of most machines.
+pub const MEMORY_NOISE_BYTES: usize = 64 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    /// Read-modify-write passes over a [`MEMORY_NOISE_BYTES`] buffer.
+    Memory,
+    /// Integer hashing in registers.
+    Compute,
+}
+
+/// One `kind:threads` entry of `BENCH_NOISE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoiseConfig {
+    pub kind: NoiseKind,
+    pub threads: usize,
+}
+
+impl NoiseConfig {
+    /// Parses a list like `memory:3,compute:1`, panics on invalid entries.
+    pub fn parse_list(list: &str) -> Vec<Self> {
+        list.split(',')
+            .map(|entry| {
+                let invalid = || -> ! {
+                    panic!(
+                        "Invalid BENCH_NOISE entry '{entry}', expected memory:<threads> or \
+                         compute:<threads>"
+                    )
+                };
+
+                let (kind, threads) = entry.split_once(':').unwrap_or_else(|| invalid());
+                let kind = match kind {
+                    "memory" => NoiseKind::Memory,
+                    "compute" => NoiseKind::Compute,
+                    _ => invalid(),
+                };
+                let threads = threads.parse().unwrap_or_else(|_| invalid());
+
+                Self { kind, threads }
+            })
+            .collect()
+    }
+
+    /// The benchmark name suffix, eg. `noise_memory3`.
+    pub fn name(self) -> String {
+        let kind = match self.kind {
+            NoiseKind::Memory => "memory",
+            NoiseKind::Compute => "compute",
+        };
+        format!("noise_{kind}{}", self.threads)
+    }
+}
+
+/// Noise threads that run until the value is dropped.
+pub struct Noise {
+    stop: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Noise {
+    /// Starts the threads of `config`, pinned to other cores than the benchmark thread while
+    /// there are enough of them.
+    pub fn start(config: NoiseConfig) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut cores = core_affinity::get_core_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter(|&(index, _)| index != BENCH_CORE_INDEX)
+            .map(|(_, core)| core);
+
+        let handles = (0..config.threads)
+            .map(|_| {
+                let stop = Arc::clone(&stop);
+                let core = cores.next();
+
+                thread::spawn(move || {
+                    if let Some(core) = core {
+                        core_affinity::set_for_current(core);
+                    }
+
+                    match config.kind {
+                        NoiseKind::Memory => memory_noise(&stop),
+                        NoiseKind::Compute => compute_noise(&stop),
+                    }
+                })
+            })
+            .collect();
+
+        Self { stop, handles }
+    }
+}
+
+impl Drop for Noise {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for handle in self.handles.drain(..) {
+            handle.join().unwrap();
+        }
+    }
+}
+
+fn memory_noise(stop: &AtomicBool) {
+    let mut buf = vec![0u64; MEMORY_NOISE_BYTES / 8];
+
+    while !stop.load(Ordering::Relaxed) {
+        for val in buf.iter_mut() {
+            *val = val.wrapping_add(1);
+        }
+        black_box(&mut buf);
+    }
+}
+
+fn compute_noise(stop: &AtomicBool) {
+    let mut rng = XorShift::new(0x9E37_79B9_7F4A_7C15);
+
+    while !stop.load(Ordering::Relaxed) {
+        for _ in 0..(1 << 16) {
+            rng.next_u64();
+        }
+        black_box(&mut rng);
+    }
+}
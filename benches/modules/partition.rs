@@ -315,4 +315,6 @@ pub fn bench<T: Ord + std::fmt::Debug>(
     bench_inst!(small_partition);
     bench_inst!(stable_2side_fill);
     bench_inst!(sum_is_less);
+    bench_inst!(three_way_bentley_mcilroy);
+    bench_inst!(three_way_dijkstra);
 }
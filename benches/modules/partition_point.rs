@@ -1,10 +1,12 @@
+use std::cell::RefCell;
 use std::hint::black_box;
 
 use criterion::Criterion;
 
 use rand::prelude::*;
 
-use sort_research_rs::other::partition_point::{self, PartitionPoint};
+use sort_research_rs::other::partition_point::{self, merge_path, PartitionPoint};
+use sort_test_tools::xorshift::XorShift;
 
 use crate::modules::util::bench_fn;
 
@@ -51,6 +53,57 @@ fn bench_impl<T: Ord + std::fmt::Debug, P: PartitionPoint>(
     )
 }
 
+type MergePathFn<T> = fn(&[T], &[T], usize) -> usize;
+
+thread_local! {
+    static DIAGONAL_RNG: RefCell<XorShift> =
+        const { RefCell::new(XorShift::new(0x9E37_79B9_7F4A_7C15)) };
+}
+
+/// xorshift64, cheap enough to run inside the measurement and the same cost for every variant. A
+/// fixed diagonal would let the branch predictor learn the search path.
+fn random_diagonal(max: usize) -> usize {
+    DIAGONAL_RNG.with_borrow_mut(|rng| rng.below(max + 1))
+}
+
+#[inline(never)]
+fn bench_merge_path<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+    (bench_name, merge_path_fn): (&str, MergePathFn<T>),
+) {
+    // The even values followed by the odd values, two sorted halves that interleave fully.
+    let m_pattern_provider = |len: usize| -> Vec<i32> {
+        let v = pattern_provider(len);
+        let evens = v.iter().step_by(2);
+        let odds = v.iter().skip(1).step_by(2);
+
+        evens.chain(odds).copied().collect()
+    };
+
+    let m_test_fn = |v: &mut [T]| {
+        let (a, b) = v.split_at(v.len().div_ceil(2));
+        let diagonal = random_diagonal(v.len());
+
+        black_box(merge_path_fn(a, b, diagonal));
+    };
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        m_pattern_provider,
+        bench_name,
+        m_test_fn,
+    )
+}
+
 pub fn bench<T: Ord + std::fmt::Debug>(
     c: &mut Criterion,
     test_len: usize,
@@ -93,4 +146,54 @@ pub fn bench<T: Ord + std::fmt::Debug>(
         pattern_provider,
         partition_point::branchless_bitwise::PartitionPointImpl,
     );
+
+    bench_impl(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        partition_point::branchy::PartitionPointImpl,
+    );
+
+    bench_impl(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        partition_point::branchless_prefetch::PartitionPointImpl,
+    );
+
+    bench_impl(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        partition_point::gallop::PartitionPointImpl,
+    );
+
+    bench_merge_path(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        ("merge_path_branchy", merge_path::merge_path_branchy),
+    );
+
+    bench_merge_path(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        ("merge_path_branchless", merge_path::merge_path_branchless),
+    );
 }
@@ -0,0 +1,73 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::permute;
+
+use crate::modules::util;
+
+// The permutation is the one that sorts the pattern, as produced by an argsort, so its cycle
+// structure depends on the pattern. Computing it and creating the input are part of the setup.
+
+fn bench_permute_fn<T>(
+    c: &mut Criterion,
+    keys: &[i32],
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    indices: &[usize],
+    bench_name: &str,
+    test_fn: impl Fn(&mut [usize], &mut [T]),
+) {
+    util::pin_thread_to_core();
+
+    let batch_size = if keys.len() > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(bench_name) {
+        c.bench_function(bench_name, |b| {
+            b.iter_batched_ref(
+                || (indices.to_vec(), transform(keys.to_vec())),
+                |(indices, v)| {
+                    test_fn(indices, black_box(v.as_mut_slice()));
+                    black_box(v); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The same keys for every iteration, so the permutation fits the input.
+    let keys = pattern_provider(test_len);
+    let mut indices = (0..keys.len()).collect::<Vec<_>>();
+    indices.sort_by_key(|&i| keys[i]);
+
+    let name = |permute_name: &str| {
+        format!("{permute_name}-hot-{transform_name}-{pattern_name}-{test_len}")
+    };
+
+    bench_permute_fn(
+        c,
+        &keys,
+        transform,
+        &indices,
+        &name("permute_cycles"),
+        |indices, v| permute::apply_permutation(indices, v),
+    );
+    bench_permute_fn(
+        c,
+        &keys,
+        transform,
+        &indices,
+        &name("permute_out_of_place"),
+        |indices, v| permute::apply_permutation_out_of_place(indices, v),
+    );
+}
@@ -0,0 +1,402 @@
+//! Placement of benchmark inputs in memory: alignment, page size and NUMA node.
+//!
+//! [`BenchBuf`] owns the elements of an input at a chosen place. Misaligned buffers come from the
+//! global allocator. Buffers with page or NUMA placement are mapped fresh with `mmap`, so the
+//! placement is set before the first write faults in their pages, which the allocator can't
+//! guarantee for memory it reuses. The scratch memory of the sorts still comes from the global
+//! allocator.
+
+use std::alloc::{self, Layout};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+/// Alignment of the allocation behind a misaligned [`BenchBuf`], a cache line and the width of
+/// AVX-512 vectors, so offset 0 is aligned for every SIMD load.
+pub const MISALIGN_BASE_ALIGN: usize = 64;
+
+/// Size of a transparent huge page on x86-64 and on aarch64 with 4K base pages.
+pub const HUGE_PAGE_SIZE: usize = 2 << 20;
+
+/// The kind of pages behind a [`BenchBuf`], requested from the kernel with `madvise`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pages {
+    /// Transparent huge pages, `MADV_HUGEPAGE`.
+    Huge,
+    /// 4K pages only, `MADV_NOHUGEPAGE`.
+    Small,
+}
+
+impl Pages {
+    pub const ALL: [Pages; 2] = [Pages::Huge, Pages::Small];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pages::Huge => "hugepages",
+            Pages::Small => "smallpages",
+        }
+    }
+}
+
+/// The NUMA memory policy of a [`BenchBuf`], set with `mbind`. Local and remote are relative to
+/// the node of the core the benchmark thread is pinned to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Numa {
+    /// All pages on the node of the benchmark thread.
+    Local,
+    /// All pages on another node, the lowest numbered one.
+    Remote,
+    /// The pages round robin across all nodes.
+    Interleave,
+}
+
+impl Numa {
+    pub const ALL: [Numa; 3] = [Numa::Local, Numa::Remote, Numa::Interleave];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Numa::Local => "numa_local",
+            Numa::Remote => "numa_remote",
+            Numa::Interleave => "numa_interleave",
+        }
+    }
+
+    /// Whether the machine has more than one NUMA node, otherwise all policies are the same.
+    pub fn is_available() -> bool {
+        os::online_nodes().len() > 1
+    }
+}
+
+enum Backing {
+    Heap(Layout),
+    Mapped(os::Mapping),
+}
+
+/// The elements of a `Vec` moved to memory with a chosen placement, to see how a sort reacts to
+/// the placement of its input.
+pub struct BenchBuf<T> {
+    base: *mut u8,
+    backing: Backing,
+    offset: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T> BenchBuf<T> {
+    /// The elements at `offset` bytes past a [`MISALIGN_BASE_ALIGN`] aligned allocation.
+    ///
+    /// Panics if `offset` is not a multiple of the alignment of `T`.
+    pub fn misaligned(v: Vec<T>, offset: usize) -> Self {
+        assert_eq!(
+            offset % mem::align_of::<T>(),
+            0,
+            "offset {offset} misaligns T"
+        );
+
+        let layout = Layout::from_size_align(
+            (offset + v.len() * mem::size_of::<T>()).max(1),
+            MISALIGN_BASE_ALIGN.max(mem::align_of::<T>()),
+        )
+        .unwrap();
+
+        // SAFETY: The layout has a non-zero size.
+        let base = unsafe { alloc::alloc(layout) };
+        if base.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        // SAFETY: The allocation has room for `v` at `offset`, which is aligned for `T`.
+        unsafe { Self::fill(v, base, offset, Backing::Heap(layout)) }
+    }
+
+    /// The elements in whole huge pages, backed by `pages`.
+    pub fn paged(v: Vec<T>, pages: Pages) -> Self {
+        Self::mapped(v, |base, len| os::advise(base, len, pages))
+    }
+
+    /// The elements in whole huge pages, placed on the NUMA nodes of `numa`.
+    pub fn numa(v: Vec<T>, numa: Numa) -> Self {
+        Self::mapped(v, |base, len| os::bind(base, len, numa))
+    }
+
+    fn mapped(v: Vec<T>, place: impl FnOnce(*mut u8, usize)) -> Self {
+        assert!(mem::align_of::<T>() <= HUGE_PAGE_SIZE);
+
+        let len = (v.len() * mem::size_of::<T>())
+            .max(1)
+            .next_multiple_of(HUGE_PAGE_SIZE);
+        let mapping = os::map_aligned(len, HUGE_PAGE_SIZE);
+        let base = mapping.aligned_base(HUGE_PAGE_SIZE);
+
+        // Before the first write, which faults in the pages.
+        place(base, len);
+
+        // SAFETY: The mapping has room for `v` at its aligned base.
+        unsafe { Self::fill(v, base, 0, Backing::Mapped(mapping)) }
+    }
+
+    /// Moves the elements of `v` to `base + offset`, with its len set to 0 the `Vec` only frees its
+    /// allocation.
+    unsafe fn fill(mut v: Vec<T>, base: *mut u8, offset: usize, backing: Backing) -> Self {
+        let len = v.len();
+        ptr::copy_nonoverlapping(v.as_ptr(), base.add(offset).cast::<T>(), len);
+        v.set_len(0);
+
+        Self {
+            base,
+            backing,
+            offset,
+            len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: `fill` moved `len` elements to `base + offset`, which is aligned for `T`.
+        unsafe { std::slice::from_raw_parts_mut(self.base.add(self.offset).cast::<T>(), self.len) }
+    }
+}
+
+impl<T> Drop for BenchBuf<T> {
+    fn drop(&mut self) {
+        // SAFETY: The elements are initialized and owned by the buffer, see `fill`.
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+
+        match &self.backing {
+            // SAFETY: Allocated in `misaligned` with this layout.
+            Backing::Heap(layout) => unsafe { alloc::dealloc(self.base, *layout) },
+            Backing::Mapped(mapping) => os::unmap(mapping),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod os {
+    use std::ffi::{c_int, c_long, c_ulong, c_void};
+    use std::fs;
+    use std::io;
+    use std::ptr;
+    use std::sync::Once;
+
+    use super::{Numa, Pages};
+
+    // From <bits/mman-linux.h>, <linux/mempolicy.h> and <asm/unistd.h>.
+    const PROT_READ: c_int = 1;
+    const PROT_WRITE: c_int = 2;
+    const MAP_PRIVATE: c_int = 2;
+    const MAP_ANONYMOUS: c_int = 0x20;
+    const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+    const MADV_HUGEPAGE: c_int = 14;
+    const MADV_NOHUGEPAGE: c_int = 15;
+    const MPOL_BIND: c_int = 2;
+    const MPOL_INTERLEAVE: c_int = 3;
+    #[cfg(target_arch = "x86_64")]
+    const SYS_MBIND: Option<c_long> = Some(237);
+    #[cfg(target_arch = "aarch64")]
+    const SYS_MBIND: Option<c_long> = Some(235);
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const SYS_MBIND: Option<c_long> = None;
+
+    // Enough for 1024 nodes.
+    const NODEMASK_WORDS: usize = 16;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> c_int;
+        fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+        fn sched_getcpu() -> c_int;
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    pub struct Mapping {
+        addr: *mut c_void,
+        len: usize,
+    }
+
+    impl Mapping {
+        pub fn aligned_base(&self, align: usize) -> *mut u8 {
+            let addr = self.addr.cast::<u8>();
+            addr.wrapping_add(addr.align_offset(align))
+        }
+    }
+
+    /// A fresh anonymous mapping with room for `len` bytes at an `align` aligned address.
+    pub fn map_aligned(len: usize, align: usize) -> Mapping {
+        let len = len + align;
+
+        // SAFETY: A new private mapping doesn't alias any memory.
+        let addr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert_ne!(
+            addr,
+            MAP_FAILED,
+            "mmap failed: {}",
+            io::Error::last_os_error()
+        );
+
+        Mapping { addr, len }
+    }
+
+    pub fn unmap(mapping: &Mapping) {
+        // SAFETY: The mapping was created by `map_aligned` and is no longer used.
+        unsafe {
+            munmap(mapping.addr, mapping.len);
+        }
+    }
+
+    pub fn advise(base: *mut u8, len: usize, pages: Pages) {
+        let advice = match pages {
+            Pages::Huge => {
+                warn_if_thp_disabled();
+                MADV_HUGEPAGE
+            }
+            Pages::Small => MADV_NOHUGEPAGE,
+        };
+
+        // SAFETY: The range is page aligned and part of a mapping, the advice only changes how it
+        // is backed.
+        let ret = unsafe { madvise(base.cast(), len, advice) };
+        assert_eq!(ret, 0, "madvise failed: {}", io::Error::last_os_error());
+    }
+
+    pub fn bind(base: *mut u8, len: usize, numa: Numa) {
+        let sys_mbind = SYS_MBIND.expect("BENCH_NUMA is only supported on x86-64 and aarch64");
+        let nodes = online_nodes();
+        let local = current_node();
+        let (mode, policy_nodes) = match numa {
+            Numa::Local => (MPOL_BIND, vec![local]),
+            Numa::Remote => {
+                let remote = nodes.iter().copied().find(|&node| node != local);
+                (
+                    MPOL_BIND,
+                    vec![remote.expect("BENCH_NUMA needs more than one NUMA node")],
+                )
+            }
+            Numa::Interleave => (MPOL_INTERLEAVE, nodes),
+        };
+
+        let mut nodemask = [0 as c_ulong; NODEMASK_WORDS];
+        for node in policy_nodes {
+            nodemask[node / c_ulong::BITS as usize] |= 1 << (node % c_ulong::BITS as usize);
+        }
+
+        // SAFETY: The range is page aligned and part of a mapping, the policy only changes where
+        // its pages are allocated. The kernel reads `maxnode - 1` bits of `nodemask`.
+        let ret = unsafe {
+            syscall(
+                sys_mbind,
+                base,
+                len,
+                mode,
+                nodemask.as_ptr(),
+                NODEMASK_WORDS * c_ulong::BITS as usize,
+                0 as c_int,
+            )
+        };
+        assert_eq!(ret, 0, "mbind failed: {}", io::Error::last_os_error());
+    }
+
+    /// The online NUMA nodes, from a list like `0-1,3`. Machines without NUMA support have a
+    /// single node 0.
+    pub fn online_nodes() -> Vec<usize> {
+        let Ok(list) = fs::read_to_string("/sys/devices/system/node/online") else {
+            return vec![0];
+        };
+
+        list.trim()
+            .split(',')
+            .filter(|range| !range.is_empty())
+            .flat_map(|range| {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                start.parse::<usize>().unwrap()..=end.parse::<usize>().unwrap()
+            })
+            .collect()
+    }
+
+    // The benchmark thread is pinned, so the node of the current core stays the same.
+    fn current_node() -> usize {
+        // SAFETY: No preconditions.
+        let cpu = unsafe { sched_getcpu() };
+        assert!(
+            cpu >= 0,
+            "sched_getcpu failed: {}",
+            io::Error::last_os_error()
+        );
+
+        fs::read_dir(format!("/sys/devices/system/cpu/cpu{cpu}"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                entry
+                    .ok()?
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix("node")?
+                    .parse()
+                    .ok()
+            })
+            .next()
+            .unwrap_or(0)
+    }
+
+    // With transparent huge pages disabled madvise succeeds but has no effect, and both page kinds
+    // measure the same 4K pages.
+    fn warn_if_thp_disabled() {
+        static CHECKED: Once = Once::new();
+
+        CHECKED.call_once(|| {
+            let path = "/sys/kernel/mm/transparent_hugepage/enabled";
+            match fs::read_to_string(path) {
+                Ok(mode) if !mode.contains("[never]") => {}
+                Ok(_) => eprintln!(
+                    "Transparent huge pages are disabled in {path}, hugepages uses 4K pages"
+                ),
+                Err(err) => eprintln!("Unable to read {path}, hugepages may use 4K pages: {err}"),
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod os {
+    use super::{Numa, Pages};
+
+    pub struct Mapping;
+
+    impl Mapping {
+        pub fn aligned_base(&self, _align: usize) -> *mut u8 {
+            unreachable!()
+        }
+    }
+
+    pub fn map_aligned(_len: usize, _align: usize) -> Mapping {
+        panic!("BENCH_PAGES and BENCH_NUMA are only supported on Linux");
+    }
+
+    pub fn unmap(_mapping: &Mapping) {}
+
+    pub fn advise(_base: *mut u8, _len: usize, _pages: Pages) {}
+
+    pub fn bind(_base: *mut u8, _len: usize, _numa: Numa) {}
+
+    pub fn online_nodes() -> Vec<usize> {
+        vec![0]
+    }
+}
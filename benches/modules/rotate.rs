@@ -0,0 +1,76 @@
+use criterion::Criterion;
+
+use sort_research_rs::other::rotate::{self, Rotate};
+
+use crate::modules::util::bench_fn;
+
+// Where the rotation splits the slice. A shift by one is what insertion does, the half split is
+// the common case of a balanced merge, the sqrt split that of block merges that move a buffer of
+// sqrt(len) elements through the slice. The third split is usually coprime with len, then the
+// juggling rotation follows a single cycle through the whole slice.
+const SPLITS: &[(&str, fn(usize) -> usize)] = &[
+    ("mid_1", |len| len.min(1)),
+    ("mid_sqrt", |len| (len as f64).sqrt() as usize),
+    ("mid_third", |len| len / 3),
+    ("mid_half", |len| len / 2),
+    ("mid_last", |len| len.saturating_sub(1)),
+];
+
+fn bench_rotate<T: Ord + std::fmt::Debug, R: Rotate>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+    _rotate: R,
+) {
+    for (split_name, split) in SPLITS {
+        let mid = split(test_len);
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("{}_{split_name}", R::name()),
+            |v| R::rotate_left(v, mid),
+        );
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    if pattern_name != "random" {
+        // The values don't matter for a rotation.
+        return;
+    }
+
+    macro_rules! bench_inst {
+        ($rotate_impl:ident) => {
+            bench_rotate(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                rotate::$rotate_impl::RotateImpl,
+            );
+        };
+    }
+
+    bench_inst!(triple_reversal);
+    bench_inst!(gries_mills);
+    bench_inst!(juggling);
+    bench_inst!(piston);
+    bench_inst!(std);
+}
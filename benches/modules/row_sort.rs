@@ -0,0 +1,78 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::radix_key_sort;
+use sort_research_rs::other::row_sort::{self, Row};
+
+use crate::modules::util;
+
+// The rows are always sorted by their i32 key, so the element type of the pattern doesn't matter.
+
+fn bench_row_fn(
+    c: &mut Criterion,
+    rows: &[Row],
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [Row]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = rows.len();
+    let name = format!("{bench_name}-hot-row-{pattern_name}-{test_len}");
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || rows.to_vec(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // Only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    let rows = pattern_provider(test_len)
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| Row::new(i as u64, key))
+        .collect::<Vec<_>>();
+
+    bench_row_fn(c, &rows, pattern_name, "direct_stable", |v| {
+        row_sort::sort_direct(v, |row| row.key)
+    });
+    bench_row_fn(c, &rows, pattern_name, "direct_unstable", |v| {
+        v.sort_unstable_by_key(|row| row.key)
+    });
+    bench_row_fn(c, &rows, pattern_name, "argsort", |v| {
+        row_sort::sort_argsort(v, |row| row.key)
+    });
+    bench_row_fn(c, &rows, pattern_name, "key_cached", |v| {
+        row_sort::sort_key_cached(v, |row| row.key)
+    });
+    bench_row_fn(c, &rows, pattern_name, "std_sort_by_cached_key", |v| {
+        v.sort_by_cached_key(|row| row.key)
+    });
+    bench_row_fn(c, &rows, pattern_name, "radix_key", |v| {
+        radix_key_sort::sort_by_key(v, |row| row.key)
+    });
+}
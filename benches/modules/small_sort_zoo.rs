@@ -0,0 +1,63 @@
+use criterion::Criterion;
+
+use sort_research_rs::other::small_sort::zoo::{self, SmallSort};
+
+use crate::modules::util::bench_fn;
+
+// Each candidate is only benchmarked up to the len it is designed for, the lens of interest are
+// the ones a hybrid sort hands to its small-sort, eg. BENCH_REGEX="-(4|8|16|20|32)$".
+
+fn bench_candidate<T: Ord + std::fmt::Debug, S: SmallSort>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+    _small_sort: S,
+) {
+    if test_len > S::max_len() {
+        return;
+    }
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        &S::name(),
+        |v| S::sort(v),
+    );
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    macro_rules! bench_inst {
+        ($small_sort_impl:ident) => {
+            bench_candidate(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                zoo::$small_sort_impl::SmallSortImpl,
+            );
+        };
+    }
+
+    bench_inst!(insertion_left);
+    bench_inst!(insertion_binary);
+    bench_inst!(network_table);
+    bench_inst!(hybrid_sort4);
+    bench_inst!(hybrid_sort8);
+    bench_inst!(hybrid_sort16);
+}
@@ -1,16 +1,24 @@
 use std::env;
+use std::mem;
 
 use criterion::{black_box, Criterion};
 
-use sort_test_tools::Sort;
+use once_cell::sync::OnceCell;
+
+use sort_research_rs::analysis::{adversary, perf_fuzz};
+use sort_research_rs::instrument::comp::CountingCmp;
+use sort_research_rs::registry::{self, SortEntry, SortVisitor};
 
-#[allow(unused_imports)]
-use sort_research_rs::{other, stable, unstable};
+use sort_test_tools::patterns::{self, PairedInputs};
+use sort_test_tools::Sort;
 
+use crate::modules::noise::{Noise, NoiseConfig};
+use crate::modules::placement::{self, BenchBuf, Numa, Pages};
 use crate::modules::util;
 
-fn measure_comp_count<S: Sort, T: Ord + std::fmt::Debug>(
+fn measure_comp_count<T: Ord + std::fmt::Debug>(
     name: &str,
+    entry: &SortEntry<T>,
     test_len: usize,
     transform: &fn(Vec<i32>) -> Vec<T>,
     pattern_provider: impl Fn(usize) -> Vec<i32>,
@@ -29,226 +37,725 @@ fn measure_comp_count<S: Sort, T: Ord + std::fmt::Debug>(
         10
     };
 
-    let mut comp_count = 0u64;
+    let mut cmp = CountingCmp::new(T::cmp);
 
     // Instrument via sort_by to ensure the type properties such as Copy of the type
     // that is being sorted doesn't change. And we get representative numbers.
     for _ in 0..run_count {
         let mut test_data = transform(pattern_provider(test_len));
-        S::sort_by(black_box(test_data.as_mut_slice()), |a, b| {
-            comp_count += 1;
-            a.cmp(b)
+        entry.sort_by(black_box(test_data.as_mut_slice()), |a, b| {
+            cmp.compare(a, b)
         })
     }
 
     // If there is on average less than a single comparison this will be wrong.
     // But that's such a corner case I don't care about it.
-    let total = comp_count / (run_count as u64);
+    let total = cmp.count() / (run_count as u64);
     println!("{name}: mean comparisons: {total}");
 }
 
-pub fn bench_fn<S: Sort, T: Ord + std::fmt::Debug>(
-    c: &mut Criterion,
+#[cfg(target_os = "linux")]
+fn measure_instructions<T: Ord + std::fmt::Debug>(
+    name: &str,
+    direct_sort: impl Fn(&mut [T]),
     test_len: usize,
-    transform_name: &str,
     transform: &fn(Vec<i32>) -> Vec<T>,
-    pattern_name: &str,
     pattern_provider: impl Fn(usize) -> Vec<i32>,
 ) {
-    let bench_name = S::name();
+    use sort_research_rs::analysis::instructions::{self, InstructionCounter};
 
-    if env::var("MEASURE_COMP").is_ok() {
-        let name = format!(
-            "{}-comp-{}-{}-{}",
-            bench_name, transform_name, pattern_name, test_len
-        );
+    let mut counter = InstructionCounter::new().unwrap_or_else(|err| {
+        panic!("MEASURE_INSTRUCTIONS needs the perf instruction counter: {err}")
+    });
 
-        if util::should_run_benchmark(&name) {
-            measure_comp_count::<S, T>(&name, test_len, transform, pattern_provider);
-        }
-    } else {
-        util::bench_fn(
-            c,
-            test_len,
-            transform_name,
-            transform,
-            pattern_name,
-            pattern_provider,
-            &bench_name,
-            S::sort,
+    // A whole number of cycles through the paired inputs, the count is the same in every run for
+    // the same binary and OVERRIDE_SEED.
+    let run_count = patterns::PAIRED_SEED_COUNT as usize;
+    let mean = instructions::mean_instructions(&mut counter, direct_sort, run_count, || {
+        transform(pattern_provider(test_len))
+    });
+
+    println!("{name}: mean instructions: {mean}");
+}
+
+#[cfg(feature = "instrument_runs")]
+fn measure_run_stats<T: Ord + std::fmt::Debug>(
+    name: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use sort_research_rs::instrument::runs;
+
+    // Run detection is deterministic for a given input, but the patterns are not.
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
+
+    let mut total_runs = 0;
+    let mut total_run_len = 0;
+    let mut total_extended_runs = 0;
+    let mut total_merges = 0;
+
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), stats) = runs::record(|| entry.sort(black_box(test_data.as_mut_slice())));
+
+        total_runs += stats.run_lens.len();
+        total_run_len += stats.run_lens.iter().sum::<usize>();
+        total_extended_runs += stats.extended_runs;
+        total_merges += stats.merges;
+    }
+
+    if total_runs == 0 {
+        println!("{name}: no run data");
+        return;
+    }
+
+    let mean = |total: usize| total as f64 / run_count as f64;
+    println!(
+        "{name}: mean runs: {:.1} mean run len: {:.1} mean extended runs: {:.1} mean merges: {:.1}",
+        mean(total_runs),
+        total_run_len as f64 / total_runs as f64,
+        mean(total_extended_runs),
+        mean(total_merges),
+    );
+}
+
+#[cfg(feature = "instrument_branches")]
+fn measure_branch_stats<T: Ord + std::fmt::Debug>(
+    name: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use sort_research_rs::instrument::branches::{self, BranchStats};
+
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
+
+    let mut total = BranchStats::default();
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), stats) = branches::record(|| entry.sort(black_box(test_data.as_mut_slice())));
+        total.merge(&stats);
+    }
+
+    if total.sites.is_empty() {
+        println!("{name}: no branch data");
+        return;
+    }
+
+    for (site, stats) in &total.sites {
+        println!(
+            "{name}: site: {site} mean calls: {:.1} taken: {:.3} entropy: {:.3} conditional entropy: {:.3} mean run len: {:.2} predicted miss ratio: {:.3}",
+            stats.calls as f64 / run_count as f64,
+            stats.taken_ratio(),
+            stats.entropy(),
+            stats.conditional_entropy(),
+            stats.mean_run_len(),
+            stats.predicted_miss_ratio(),
         );
     }
 }
 
-pub fn bench<T: Ord + std::fmt::Debug>(
-    c: &mut Criterion,
+#[cfg(feature = "instrument_phases")]
+fn measure_phase_times<T: Ord + std::fmt::Debug>(
+    name: &str,
+    direct_sort: impl Fn(&mut [T]),
     test_len: usize,
-    transform_name: &str,
     transform: &fn(Vec<i32>) -> Vec<T>,
-    pattern_name: &str,
-    pattern_provider: &fn(usize) -> Vec<i32>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
 ) {
-    macro_rules! bench_inst {
-        ($sort_impl_path:path) => {{
-            use $sort_impl_path::*;
+    use sort_research_rs::instrument::phases::{self, Phase, PhaseStats};
 
-            bench_fn::<SortImpl, T>(
-                c,
-                test_len,
-                transform_name,
-                transform,
-                pattern_name,
-                pattern_provider,
-            );
-        }};
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
+
+    let mut total = PhaseStats::default();
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), stats) = phases::record(|| direct_sort(black_box(test_data.as_mut_slice())));
+        total.merge(&stats);
     }
 
-    // --- Stable sorts ---
+    if total.counts.iter().all(|&count| count == 0) {
+        println!("{name}: no phase data");
+        return;
+    }
 
-    bench_inst!(stable::rust_std);
+    let mut line = format!(
+        "{name}: mean total: {:.0}ns",
+        total.total.as_nanos() as f64 / run_count as f64
+    );
+    for phase in Phase::ALL {
+        line += &format!(" {}: {:.1}%", phase.name(), total.fraction(phase) * 100.0);
+    }
+    line += &format!(
+        " untimed: {:.1}%",
+        total.untimed().as_secs_f64() / total.total.as_secs_f64() * 100.0
+    );
 
-    #[cfg(feature = "cpp_std_sys")]
-    bench_inst!(stable::cpp_std_sys);
+    println!("{line}");
+}
 
-    #[cfg(feature = "cpp_std_libcxx")]
-    bench_inst!(stable::cpp_std_libcxx);
+#[cfg(feature = "instrument_alloc")]
+fn measure_alloc_stats<T: Ord + std::fmt::Debug>(
+    name: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use sort_research_rs::instrument::alloc::{self, AllocStats};
 
-    #[cfg(feature = "cpp_std_gcc4_3")]
-    bench_inst!(stable::cpp_std_gcc4_3);
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
 
-    #[cfg(feature = "cpp_powersort")]
-    bench_inst!(stable::cpp_powersort);
+    let mut total = AllocStats::default();
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), stats) = alloc::record(|| entry.sort(black_box(test_data.as_mut_slice())));
+        total.merge(&stats);
+    }
 
-    #[cfg(feature = "cpp_powersort")]
-    bench_inst!(stable::cpp_powersort_4way);
+    let small_share = if total.allocs == 0 {
+        0.0
+    } else {
+        total.allocs_below(4096) as f64 / total.allocs as f64 * 100.0
+    };
 
-    #[cfg(feature = "cpp_wikisort")]
-    bench_inst!(stable::cpp_wikisort);
+    println!(
+        "{name}: mean allocs: {:.1} mean bytes: {:.0} peak bytes: {} max alloc: {} below 4KiB: {:.1}% mean lifetime: {}ns",
+        total.allocs as f64 / run_count as f64,
+        total.total_bytes as f64 / run_count as f64,
+        total.peak_bytes,
+        total.max_alloc,
+        small_share,
+        total.mean_lifetime().as_nanos(),
+    );
+}
 
-    #[cfg(feature = "c_fluxsort")]
-    bench_inst!(stable::c_fluxsort);
+#[cfg(feature = "instrument_fallbacks")]
+fn measure_fallbacks<T: Ord + std::fmt::Debug>(
+    name: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use sort_research_rs::instrument::fallbacks::{self, Fallback, FallbackCounts};
 
-    #[cfg(feature = "golang_std")]
-    bench_inst!(stable::golang_std);
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
 
-    #[cfg(feature = "rust_wpwoodjr")]
-    bench_inst!(stable::rust_wpwoodjr);
+    let mut total = FallbackCounts::default();
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), counts) = fallbacks::record(|| entry.sort(black_box(test_data.as_mut_slice())));
+        total.merge(&counts);
+    }
 
-    #[cfg(feature = "rust_glidesort")]
-    bench_inst!(stable::rust_glidesort);
+    // Total count and the number of runs it triggered in, per fallback.
+    let mut line = format!("{name}: runs: {run_count}");
+    for fallback in Fallback::ALL {
+        line += &format!(
+            " {}: {} in {}",
+            fallback.name(),
+            total.count(fallback),
+            total.invocations(fallback)
+        );
+    }
 
-    // #[cfg(feature = "rust_driftsort")]
-    // bench_inst!(stable::rust_driftsort);
+    println!("{line}");
+}
 
-    #[cfg(feature = "rust_tinysort")]
-    bench_inst!(stable::rust_tinysort);
+#[cfg(feature = "instrument_insertion")]
+fn measure_insertion<T: Ord + std::fmt::Debug>(
+    name: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use sort_research_rs::instrument::insertion::{self, InsertionStats};
 
-    #[cfg(feature = "rust_grailsort")]
-    bench_inst!(stable::rust_grailsort);
+    let run_count: usize = if test_len < 10_000 { 100 } else { 10 };
 
-    // --- Unstable sorts ---
+    let mut total = InsertionStats::default();
+    for _ in 0..run_count {
+        let mut test_data = transform(pattern_provider(test_len));
+        let ((), stats) = insertion::record(|| entry.sort(black_box(test_data.as_mut_slice())));
+        total.merge(&stats);
+    }
 
-    bench_inst!(unstable::rust_ipnsort);
+    if total.insertions() == 0 {
+        println!("{name}: no insertion data");
+        return;
+    }
 
-    bench_inst!(unstable::rust_std);
+    // The share of insertions that moved by at most 0, 1, 2, 4, ... positions.
+    let mut line = format!(
+        "{name}: insertions: {} mean displacement: {:.2} max displacement: {}",
+        total.insertions(),
+        total.mean_displacement(),
+        total.max_displacement()
+    );
+    for distance in [0, 1, 2, 4, 8, 16] {
+        line += &format!(" within {distance}: {:.3}", total.share_within(distance));
+    }
 
-    #[cfg(feature = "rust_dmsort")]
-    bench_inst!(unstable::rust_dmsort);
+    println!("{line}");
+}
 
-    #[cfg(feature = "rust_crumsort_rs")]
-    bench_inst!(unstable::rust_crumsort_rs);
+#[cfg(feature = "instrument_mem")]
+fn trace_mem<T: Ord + std::fmt::Debug>(
+    name: &str,
+    out_dir: &str,
+    entry: &SortEntry<T>,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+) {
+    use std::fmt::Write;
 
-    #[cfg(feature = "rust_tinysort")]
-    bench_inst!(unstable::rust_tinysort);
+    use sort_research_rs::instrument::mem::{self, AccessKind};
 
-    #[cfg(feature = "rust_introsort")]
-    bench_inst!(unstable::rust_introsort);
+    let mut test_data = transform(pattern_provider(test_len));
+    let base = test_data.as_ptr();
+    let ((), trace) = mem::record(base, || {
+        entry.sort_by(black_box(test_data.as_mut_slice()), |a, b| {
+            mem::on_read(a);
+            mem::on_read(b);
+            a.cmp(b)
+        })
+    });
+
+    let mut csv = String::from("kind,offset,size\n");
+    for access in &trace.accesses {
+        let kind = match access.kind {
+            AccessKind::Read => 'r',
+            AccessKind::Write => 'w',
+        };
+        writeln!(csv, "{kind},{},{}", access.offset, access.size).unwrap();
+    }
 
-    #[cfg(feature = "cpp_pdqsort")]
-    bench_inst!(unstable::cpp_pdqsort);
+    std::fs::create_dir_all(out_dir).unwrap();
+    std::fs::write(format!("{out_dir}/{name}.csv"), csv).unwrap();
+    println!("{name}: accesses: {}", trace.accesses.len());
+}
 
-    #[cfg(feature = "cpp_ips4o")]
-    bench_inst!(unstable::cpp_ips4o);
+fn perf_fuzz_search(name: &str, entry_name: &str, test_len: usize, objective: &str) {
+    let objective = match objective {
+        "comparisons" => perf_fuzz::Objective::Comparisons,
+        "runtime" => perf_fuzz::Objective::Runtime,
+        _ => panic!("Unknown PERF_FUZZ value: '{objective}', expected comparisons or runtime"),
+    };
 
-    #[cfg(feature = "cpp_blockquicksort")]
-    bench_inst!(unstable::cpp_blockquicksort);
+    // The search works on i32 inputs, the found input can then be transformed like any pattern.
+    let entry = registry::find::<i32>(entry_name).unwrap();
+    let result = perf_fuzz::search(&entry, &perf_fuzz::SearchConfig::new(test_len, objective));
+
+    println!(
+        "{name}: initial pattern: {} initial score: {} found score: {} ({:.2}x)",
+        result.initial_pattern,
+        result.initial_score,
+        result.score,
+        result.score / result.initial_score.max(1.0),
+    );
+
+    if let Ok(out_dir) = env::var("PERF_FUZZ_OUT") {
+        let input = result
+            .input
+            .iter()
+            .map(|val| val.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        std::fs::write(format!("{out_dir}/{name}.txt"), input).unwrap();
+    }
+}
 
-    #[cfg(feature = "cpp_gerbens_qsort")]
-    bench_inst!(unstable::cpp_gerbens_qsort);
+fn numa_available() -> bool {
+    static AVAILABLE: OnceCell<bool> = OnceCell::new();
 
-    #[cfg(feature = "cpp_nanosort")]
-    bench_inst!(unstable::cpp_nanosort);
+    *AVAILABLE.get_or_init(|| {
+        let available = Numa::is_available();
+        if !available {
+            eprintln!("BENCH_NUMA needs a Linux machine with more than one NUMA node, skipping");
+        }
+        available
+    })
+}
 
-    #[cfg(feature = "c_std_sys")]
-    bench_inst!(unstable::c_std_sys);
+/// `direct_sort` calls the [`Sort`] impl of `entry` itself, `direct_sort_by` its `sort_by` with the
+/// `Ord` comparison. The measurements that depend on how the sort is called use them, the entry
+/// adds an indirect call per sort and, in `sort_by`, per comparison.
+fn bench_fn<T: Ord + std::fmt::Debug>(
+    bench: &mut SortBench<'_, T>,
+    entry: &SortEntry<T>,
+    direct_sort: impl Fn(&mut [T]) + Copy,
+    direct_sort_by: impl Fn(&mut [T]) + Copy,
+) {
+    let c = &mut *bench.c;
+    let test_len = bench.test_len;
+    let transform_name = bench.transform_name;
+    let transform = bench.transform;
+    let pattern_name = bench.pattern_name;
+    let pattern_provider = bench.pattern_provider;
+    let bench_name = entry.name();
+
+    // The measurements below run a fixed number of iterations over the same inputs, so every sort
+    // sees exactly the same ones. The criterion benchmarks in util::bench_fn make their own.
+    let paired_inputs = OnceCell::new();
+    let paired_provider = |len| {
+        paired_inputs
+            .get_or_init(|| PairedInputs::new(test_len, pattern_provider))
+            .next_input(len)
+    };
 
-    #[cfg(feature = "c_crumsort")]
-    bench_inst!(unstable::c_crumsort);
+    if env::var("MEASURE_COMP").is_ok() {
+        let name = format!(
+            "{}-comp-{}-{}-{}",
+            bench_name, transform_name, pattern_name, test_len
+        );
 
-    #[cfg(feature = "cpp_std_sys")]
-    bench_inst!(unstable::cpp_std_sys);
+        // Counted through sort_by, which only the general-purpose sorts have.
+        if util::should_run_benchmark(&name) && entry.is_general() {
+            measure_comp_count(&name, entry, test_len, transform, paired_provider);
+        }
+    } else if env::var("MEASURE_INSTRUCTIONS").is_ok() {
+        #[cfg(target_os = "linux")]
+        {
+            let name = format!(
+                "{}-instructions-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "cpp_std_libcxx")]
-    bench_inst!(unstable::cpp_std_libcxx);
+            if util::should_run_benchmark(&name) {
+                measure_instructions(&name, direct_sort, test_len, transform, paired_provider);
+            }
+        }
 
-    #[cfg(feature = "cpp_std_gcc4_3")]
-    bench_inst!(unstable::cpp_std_gcc4_3);
+        #[cfg(not(target_os = "linux"))]
+        panic!("MEASURE_INSTRUCTIONS is only supported on Linux");
+    } else if env::var("MEASURE_RUNS").is_ok() {
+        #[cfg(feature = "instrument_runs")]
+        {
+            let name = format!(
+                "{}-runs-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "golang_std")]
-    bench_inst!(unstable::golang_std);
+            if util::should_run_benchmark(&name) {
+                measure_run_stats(&name, entry, test_len, transform, paired_provider);
+            }
+        }
 
-    // --- Other sorts ---
+        #[cfg(not(feature = "instrument_runs"))]
+        panic!("MEASURE_RUNS requires the instrument_runs feature");
+    } else if env::var("MEASURE_BRANCHES").is_ok() {
+        #[cfg(feature = "instrument_branches")]
+        {
+            let name = format!(
+                "{}-branches-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "rust_radsort")]
-    bench_inst!(other::rust_radsort);
+            if util::should_run_benchmark(&name) {
+                measure_branch_stats(&name, entry, test_len, transform, paired_provider);
+            }
+        }
 
-    #[cfg(feature = "rust_afsort")]
-    bench_inst!(other::rust_afsort);
+        #[cfg(not(feature = "instrument_branches"))]
+        panic!("MEASURE_BRANCHES requires the instrument_branches feature");
+    } else if env::var("MEASURE_PHASES").is_ok() {
+        #[cfg(feature = "instrument_phases")]
+        {
+            let name = format!(
+                "{}-phases-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "cpp_simdsort")]
-    bench_inst!(other::cpp_simdsort);
+            if util::should_run_benchmark(&name) {
+                measure_phase_times(&name, direct_sort, test_len, transform, paired_provider);
+            }
+        }
 
-    #[cfg(feature = "cpp_vqsort")]
-    bench_inst!(other::cpp_vqsort);
+        #[cfg(not(feature = "instrument_phases"))]
+        panic!("MEASURE_PHASES requires the instrument_phases feature");
+    } else if env::var("MEASURE_ALLOC").is_ok() {
+        #[cfg(feature = "instrument_alloc")]
+        {
+            let name = format!(
+                "{}-alloc-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "cpp_intel_avx512")]
-    bench_inst!(other::cpp_intel_avx512);
+            if util::should_run_benchmark(&name) {
+                measure_alloc_stats(&name, entry, test_len, transform, paired_provider);
+            }
+        }
 
-    #[cfg(feature = "singeli_singelisort")]
-    bench_inst!(other::singeli_singelisort);
+        #[cfg(not(feature = "instrument_alloc"))]
+        panic!("MEASURE_ALLOC requires the instrument_alloc feature");
+    } else if env::var("MEASURE_FALLBACKS").is_ok() {
+        #[cfg(feature = "instrument_fallbacks")]
+        {
+            let name = format!(
+                "{}-fallbacks-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
 
-    #[cfg(feature = "evolution")]
-    {
-        bench_inst!(other::sort_evolution::stable::timsort_evo0);
-        bench_inst!(other::sort_evolution::stable::timsort_evo1);
-        bench_inst!(other::sort_evolution::stable::timsort_evo2);
-        bench_inst!(other::sort_evolution::stable::timsort_evo3);
-        bench_inst!(other::sort_evolution::stable::timsort_evo4);
+            if util::should_run_benchmark(&name) {
+                measure_fallbacks(&name, entry, test_len, transform, paired_provider);
+            }
+        }
+
+        #[cfg(not(feature = "instrument_fallbacks"))]
+        panic!("MEASURE_FALLBACKS requires the instrument_fallbacks feature");
+    } else if env::var("MEASURE_INSERTION").is_ok() {
+        #[cfg(feature = "instrument_insertion")]
+        {
+            let name = format!(
+                "{}-insertion-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
+
+            if util::should_run_benchmark(&name) {
+                measure_insertion(&name, entry, test_len, transform, paired_provider);
+            }
+        }
+
+        #[cfg(not(feature = "instrument_insertion"))]
+        panic!("MEASURE_INSERTION requires the instrument_insertion feature");
+    } else if let Ok(out_dir) = env::var("TRACE_MEM") {
+        #[cfg(feature = "instrument_mem")]
+        {
+            let name = format!(
+                "{}-trace-{}-{}-{}",
+                bench_name, transform_name, pattern_name, test_len
+            );
+
+            if util::should_run_benchmark(&name) {
+                trace_mem(&name, &out_dir, entry, test_len, transform, paired_provider);
+            }
+        }
 
-        bench_inst!(other::sort_evolution::unstable::quicksort_evo0);
-        bench_inst!(other::sort_evolution::unstable::quicksort_stack_evo0);
+        #[cfg(not(feature = "instrument_mem"))]
+        panic!("TRACE_MEM={out_dir} requires the instrument_mem feature");
+    } else if let Ok(objective) = env::var("PERF_FUZZ") {
+        // Search once per sort and len, independent of the type and pattern.
+        if transform_name == "i32" && pattern_name == "random" {
+            let name = format!("{bench_name}-perf_fuzz-{objective}-{test_len}");
 
-        bench_inst!(other::sort_evolution::other::bucket_btree);
-        bench_inst!(other::sort_evolution::other::bucket_hash);
-        bench_inst!(other::sort_evolution::other::bucket_match);
-        bench_inst!(other::sort_evolution::other::bucket_branchless);
-        bench_inst!(other::sort_evolution::other::bucket_phf);
+            if util::should_run_benchmark(&name) {
+                perf_fuzz_search(&name, bench_name, test_len, &objective);
+            }
+        }
+    } else {
+        util::bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            bench_name,
+            |v| entry.sort(v),
+        );
+
+        // The C and C++ sorts compare i32 and u64 natively in sort, but have to call back into
+        // Rust for every comparison in sort_by. Benchmarking sort_by with the Ord comparison
+        // shows how much of a difference is due to the callback overhead. The comparison is
+        // handed to the Sort impl directly, the entry would add a dyn call per comparison.
+        if entry.is_general() && env::var("BENCH_CALLBACK").is_ok() {
+            util::bench_fn(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                &format!("{bench_name}_callback"),
+                direct_sort_by,
+            );
+        }
+
+        // SIMD sorts may take a scalar path or split off a head and tail until the input is
+        // aligned to their vectors. Benchmarking every offset a `T` can have within a vector shows
+        // whether a sort depends on alignment and what it costs.
+        if env::var("BENCH_MISALIGN").is_ok() {
+            for offset in util::misalign_offsets::<T>() {
+                util::bench_fn_buf(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                    &format!("{bench_name}_misalign{offset}"),
+                    |v| BenchBuf::misaligned(v, offset),
+                    |v| entry.sort(v),
+                );
+            }
+        }
+
+        // Above some len the TLB misses of random accesses dominate, which huge pages reduce by a
+        // factor of 512. Inputs smaller than a huge page fit into a few TLB entries either way.
+        if env::var("BENCH_PAGES").is_ok()
+            && test_len * mem::size_of::<T>() >= placement::HUGE_PAGE_SIZE
+        {
+            for pages in Pages::ALL {
+                util::bench_fn_buf(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                    &format!("{bench_name}_{}", pages.name()),
+                    |v| BenchBuf::paged(v, pages),
+                    |v| entry.sort(v),
+                );
+            }
+        }
+
+        // Memory on another node costs latency and the bandwidth of the interconnect, interleaving
+        // spreads both across the nodes. Same lens as for BENCH_PAGES, the buffers are whole huge
+        // pages.
+        if env::var("BENCH_NUMA").is_ok()
+            && test_len * mem::size_of::<T>() >= placement::HUGE_PAGE_SIZE
+            && numa_available()
+        {
+            for numa in Numa::ALL {
+                util::bench_fn_buf(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                    &format!("{bench_name}_{}", numa.name()),
+                    |v| BenchBuf::numa(v, numa),
+                    |v| entry.sort(v),
+                );
+            }
+        }
+
+        // The noise only runs during its own benchmark, the regular one next to it is the
+        // baseline. With BENCH_ISOLATE the child process that runs the benchmark starts it.
+        if let Ok(noise_configs) = env::var("BENCH_NOISE") {
+            for config in NoiseConfig::parse_list(&noise_configs) {
+                let name = format!("{bench_name}_{}", config.name());
+                let hot_name = format!("{name}-hot-{transform_name}-{pattern_name}-{test_len}");
+                if !util::should_run_benchmark(&hot_name) {
+                    continue;
+                }
+
+                let _noise = (!util::isolates()).then(|| Noise::start(config));
+                util::bench_fn(
+                    c,
+                    test_len,
+                    transform_name,
+                    transform,
+                    pattern_name,
+                    pattern_provider,
+                    &name,
+                    |v| entry.sort(v),
+                );
+            }
+        }
+    }
+}
+
+struct SortBench<'a, T> {
+    c: &'a mut Criterion,
+    test_len: usize,
+    transform_name: &'a str,
+    transform: &'a fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &'a str,
+    pattern_provider: &'a dyn Fn(usize) -> Vec<i32>,
+}
+
+impl<T: Ord + std::fmt::Debug + 'static> SortBench<'_, T> {
+    /// Benchmarks a sort of the registry, `direct_sort` and `direct_sort_by` see [`bench_fn`].
+    fn bench_entry(
+        &mut self,
+        entry: &SortEntry<T>,
+        direct_sort: impl Fn(&mut [T]) + Copy,
+        direct_sort_by: impl Fn(&mut [T]) + Copy,
+    ) {
+        bench_fn(self, entry, direct_sort, direct_sort_by);
+
+        // The adversary input is specific to each sort. Bench it once per type and len, alongside
+        // the random pattern which is run for every len. It is synthesized through sort_by.
+        if self.pattern_name == "random"
+            && entry.is_general()
+            && env::var("BENCH_ADVERSARY").is_ok()
+        {
+            let adversary_entry = registry::find::<i32>(entry.name()).unwrap();
+            let input = adversary::synthesize_for(&adversary_entry, self.test_len);
+
+            let mut adversary_bench = SortBench {
+                c: &mut *self.c,
+                pattern_name: "adversary",
+                pattern_provider: &|_| input.clone(),
+                ..*self
+            };
+            bench_fn(&mut adversary_bench, entry, direct_sort, direct_sort_by);
+        }
     }
+}
 
-    #[cfg(feature = "small_sort")]
-    {
-        bench_inst!(other::small_sort::sort4_unstable_cmp_swap);
-        bench_inst!(other::small_sort::sort4_unstable_ptr_select);
-        bench_inst!(other::small_sort::sort4_unstable_branchy);
-        bench_inst!(other::small_sort::sort4_stable_orson);
-        bench_inst!(other::small_sort::sort10_unstable_cmp_swaps);
-        bench_inst!(other::small_sort::sort10_unstable_experimental);
-        bench_inst!(other::small_sort::sort10_unstable_ptr_select);
+impl<T: Ord + std::fmt::Debug + 'static> SortVisitor for SortBench<'_, T> {
+    fn visit<S: Sort>(&mut self, stable: bool, general: bool) {
+        self.bench_entry(
+            &SortEntry::from_sort_impl::<S>(stable, general),
+            |v| S::sort(v),
+            |v| S::sort_by(v, |a, b| a.cmp(b)),
+        );
     }
+}
 
-    #[cfg(feature = "selection")]
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let mut bench = SortBench {
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+    };
+
+    // The set of benchmarked sorts depends on the enabled cargo features, the same as
+    // registry::sorts. Visiting the Sort impls lets bench_fn call them directly.
+    registry::visit_sorts(&mut bench);
+
+    // Plugins have no Sort impl, they are only called through their entry.
+    #[cfg(all(feature = "plugin", unix))]
+    for entry in sort_research_rs::plugin::entries::<T>() {
+        bench.bench_entry(
+            &entry,
+            |v| entry.sort(v),
+            |v| entry.sort_by(v, |a, b| a.cmp(b)),
+        );
+    }
+
+    // The small-sort kernels panic for any other len than theirs.
+    #[cfg(feature = "small_sort")]
+    for (_, entry) in registry::small_sort_kernels::<T>()
+        .into_iter()
+        .filter(|(kernel_len, _)| *kernel_len == test_len)
     {
-        bench_inst!(other::selection::rust_ipnsort);
-        bench_inst!(other::selection::rust_std);
+        bench_fn(
+            &mut bench,
+            &entry,
+            |v| entry.sort(v),
+            |v| entry.sort_by(v, |a, b| a.cmp(b)),
+        );
     }
 }
@@ -0,0 +1,82 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::sorted_iter;
+
+use crate::modules::util;
+
+// The input is moved into the sort as iterator and every output element is consumed, so the
+// collect variants include the allocation of the Vec, same as sorted_iter has to allocate chunks.
+
+fn bench_iter_fn<T>(
+    c: &mut Criterion,
+    test_len: usize,
+    input: &dyn Fn() -> Vec<T>,
+    bench_name: &str,
+    test_fn: impl Fn(std::vec::Drain<T>),
+) {
+    util::pin_thread_to_core();
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    if util::should_run_benchmark(bench_name) {
+        c.bench_function(bench_name, |b| {
+            b.iter_batched_ref(
+                input,
+                |test_data| test_fn(black_box(test_data).drain(..)),
+                batch_size,
+            )
+        });
+    }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let input = || transform(pattern_provider(test_len));
+    let name =
+        |sort_name: &str| format!("{sort_name}-hot-{transform_name}-{pattern_name}-{test_len}");
+
+    for chunk_len in [256, 4096, 65536] {
+        bench_iter_fn(
+            c,
+            test_len,
+            &input,
+            &name(&format!("sorted_iter_{chunk_len}")),
+            |iter| {
+                for val in sorted_iter::sorted_iter_with_chunk_len(iter, chunk_len) {
+                    black_box(val);
+                }
+            },
+        );
+    }
+
+    bench_iter_fn(c, test_len, &input, &name("collect_sort_stable"), |iter| {
+        let mut v = iter.collect::<Vec<_>>();
+        v.sort();
+        for val in v {
+            black_box(val);
+        }
+    });
+    bench_iter_fn(
+        c,
+        test_len,
+        &input,
+        &name("collect_sort_unstable"),
+        |iter| {
+            let mut v = iter.collect::<Vec<_>>();
+            v.sort_unstable();
+            for val in v {
+                black_box(val);
+            }
+        },
+    );
+}
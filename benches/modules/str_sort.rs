@@ -0,0 +1,64 @@
+use criterion::{black_box, BatchSize, Criterion};
+
+use sort_research_rs::other::cpp_str_sort;
+
+use crate::modules::util;
+
+// The sorts only permute the &str values, so the backing Strings are created once per pattern and
+// each iteration sorts a fresh Vec<&str> pointing into them.
+
+fn bench_str_fn(
+    c: &mut Criterion,
+    strings: &[String],
+    pattern_name: &str,
+    bench_name: &str,
+    test_fn: impl Fn(&mut [&str]),
+) {
+    util::pin_thread_to_core();
+
+    let test_len = strings.len();
+    let name = format!("{bench_name}-hot-str-{pattern_name}-{test_len}");
+
+    if util::should_run_benchmark(&name) {
+        c.bench_function(&name, |b| {
+            b.iter_batched_ref(
+                || strings.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+}
+
+pub fn bench(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The element type is always &str, only run once per len and pattern.
+    if transform_name != "i32" {
+        return;
+    }
+
+    // Zero extend to maintain the input order, same as the rust_string type.
+    let strings = pattern_provider(test_len)
+        .iter()
+        .map(|val| format!("{:010}", val.saturating_abs()))
+        .collect::<Vec<_>>();
+
+    bench_str_fn(c, &strings, pattern_name, "rust_std_stable", |v| v.sort());
+    bench_str_fn(c, &strings, pattern_name, "rust_std_unstable", |v| {
+        v.sort_unstable()
+    });
+    bench_str_fn(c, &strings, pattern_name, "cpp_std_sort_str", |v| {
+        cpp_str_sort::std_sort(v)
+    });
+    bench_str_fn(c, &strings, pattern_name, "cpp_msd_radix_sort_str", |v| {
+        cpp_str_sort::msd_radix_sort(v)
+    });
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use criterion::{black_box, Criterion};
+
+use sort_research_rs::other::time_limited_sort;
+
+use crate::modules::util::{self, bench_fn};
+
+// From a fraction of a frame up to a full 60Hz frame.
+const BUDGETS_US: [u64; 6] = [50, 100, 250, 500, 1000, 2000];
+
+const RUN_COUNT: usize = 20;
+
+fn measure_sorted_prefix<T: Ord>(
+    name: &str,
+    budget: Duration,
+    test_len: usize,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let mut total_prefix = 0;
+    for _ in 0..RUN_COUNT {
+        let mut test_data = transform(pattern_provider(test_len));
+        let progress = time_limited_sort::sort_for(budget, black_box(test_data.as_mut_slice()));
+        total_prefix += progress.sorted_prefix;
+    }
+
+    let mean_prefix = total_prefix as f64 / RUN_COUNT as f64;
+    println!(
+        "{name}: mean sorted prefix: {mean_prefix:.0} ({:.1}%)",
+        mean_prefix * 100.0 / test_len.max(1) as f64
+    );
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // The sorted prefix len for each budget, named like the other measurements so BENCH_REGEX
+    // selects them.
+    for budget_us in BUDGETS_US {
+        let name = format!(
+            "time_limited_unstable_{budget_us}us-prefix-{transform_name}-{pattern_name}-{test_len}"
+        );
+        if util::should_run_benchmark(&name) {
+            measure_sorted_prefix(
+                &name,
+                Duration::from_micros(budget_us),
+                test_len,
+                transform,
+                pattern_provider,
+            );
+        }
+    }
+
+    // The cost of sorting incrementally, without a limit.
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "rust_std_unstable",
+        |v| v.sort_unstable(),
+    );
+
+    bench_fn(
+        c,
+        test_len,
+        transform_name,
+        transform,
+        pattern_name,
+        pattern_provider,
+        "time_limited_unstable_unlimited",
+        |v| {
+            black_box(time_limited_sort::sort_for(Duration::MAX, v));
+        },
+    );
+}
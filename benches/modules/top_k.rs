@@ -0,0 +1,40 @@
+use criterion::{black_box, Criterion};
+
+use sort_research_rs::other::top_k::{self, Strategy};
+
+use crate::modules::util::bench_fn;
+
+// k as a fraction of the len, from a handful of elements to half of them.
+const K_DIVISORS: [usize; 5] = [10_000, 1000, 100, 10, 2];
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    let mut ks = K_DIVISORS
+        .iter()
+        .map(|divisor| (test_len / divisor).max(1))
+        .collect::<Vec<_>>();
+    ks.dedup();
+
+    for k in ks {
+        for strategy in Strategy::ALL {
+            bench_fn(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                &format!("bottom_k_{}_k{k}", strategy.name()),
+                |v| {
+                    black_box(top_k::bottom_k_with(strategy, v, k, T::cmp));
+                },
+            );
+        }
+    }
+}
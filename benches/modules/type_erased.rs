@@ -0,0 +1,67 @@
+use std::cmp::Ordering;
+
+use criterion::Criterion;
+
+use sort_research_rs::other::type_erased::{self, Strategy};
+
+use crate::modules::util::bench_fn;
+
+// The comparison of the type-erased sorts casts the element pointers back to T, the same work an
+// interpreter does when it dispatches on the runtime type of its values.
+fn erased_cmp<T: Ord>(a: *const u8, b: *const u8) -> Ordering {
+    // SAFETY: type_erased only passes pointers to elements of the slice or copies of them, aligned
+    // for T because T's alignment divides both its size and the address of the slice.
+    unsafe { (*a.cast::<T>()).cmp(&*b.cast::<T>()) }
+}
+
+pub fn bench<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    for stable in [true, false] {
+        let stability = if stable { "stable" } else { "unstable" };
+
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("monomorphized_{stability}"),
+            |v| {
+                if stable {
+                    sort_research_rs::stable::rust_std::sort(v);
+                } else {
+                    sort_research_rs::unstable::rust_std::sort(v);
+                }
+            },
+        );
+
+        for strategy in Strategy::ALL {
+            bench_fn(
+                c,
+                test_len,
+                transform_name,
+                transform,
+                pattern_name,
+                pattern_provider,
+                &format!("type_erased_{stability}_{}", strategy.name()),
+                |v| unsafe {
+                    type_erased::sort_raw_with(
+                        strategy,
+                        stable,
+                        v.as_mut_ptr().cast(),
+                        v.len(),
+                        std::mem::size_of::<T>(),
+                        &mut erased_cmp::<T>,
+                    );
+                },
+            );
+        }
+    }
+}
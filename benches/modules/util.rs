@@ -1,4 +1,6 @@
 use std::env;
+use std::mem;
+use std::process::Command;
 use std::str::FromStr;
 
 use regex::Regex;
@@ -7,20 +9,34 @@ use criterion::{black_box, BatchSize, Criterion};
 
 use once_cell::sync::OnceCell;
 
+use sort_research_rs::analysis::shard::Shard;
+
+use sort_test_tools::patterns::PairedInputs;
+
+use crate::modules::placement::BenchBuf;
+
+/// Index into `core_affinity::get_core_ids` of the core the benchmarks are pinned to.
+pub const BENCH_CORE_INDEX: usize = 2;
+
 pub fn pin_thread_to_core() {
     use std::cell::Cell;
-    let pin_core_id: usize = 2;
 
     thread_local! {static AFFINITY_ALREADY_SET: Cell<bool> = Cell::new(false); }
 
     // Set affinity only once per thread.
     AFFINITY_ALREADY_SET.with(|affinity_already_set| {
         if !affinity_already_set.get() {
-            if let Some(core_id_2) = core_affinity::get_core_ids()
-                .as_ref()
-                .and_then(|ids| ids.get(pin_core_id))
+            #[cfg(target_os = "macos")]
+            set_core_class();
+
+            #[cfg(not(target_os = "macos"))]
             {
-                core_affinity::set_for_current(*core_id_2);
+                if let Some(core_id_2) = core_affinity::get_core_ids()
+                    .as_ref()
+                    .and_then(|ids| ids.get(BENCH_CORE_INDEX))
+                {
+                    core_affinity::set_for_current(*core_id_2);
+                }
             }
 
             affinity_already_set.set(true);
@@ -28,6 +44,33 @@ pub fn pin_thread_to_core() {
     });
 }
 
+// macOS doesn't pin threads to cores, on Apple Silicon the scheduler picks performance or
+// efficiency cores based on the QoS class of the thread. The default keeps the benchmark on the
+// performance cores, eg. Firestorm in an M1, `BENCH_CORE_CLASS=efficiency` moves it to the
+// efficiency cores, eg. Icestorm.
+#[cfg(target_os = "macos")]
+fn set_core_class() {
+    // From <sys/qos.h>.
+    const QOS_CLASS_USER_INTERACTIVE: u32 = 0x21;
+    const QOS_CLASS_BACKGROUND: u32 = 0x09;
+
+    extern "C" {
+        fn pthread_set_qos_class_self_np(qos_class: u32, relative_priority: i32) -> i32;
+    }
+
+    let qos_class = match env::var("BENCH_CORE_CLASS").as_deref() {
+        Err(_) | Ok("performance") => QOS_CLASS_USER_INTERACTIVE,
+        Ok("efficiency") => QOS_CLASS_BACKGROUND,
+        Ok(other) => {
+            panic!("Unknown BENCH_CORE_CLASS '{other}', expected performance or efficiency")
+        }
+    };
+
+    // SAFETY: Only changes the scheduling of the calling thread.
+    let ret = unsafe { pthread_set_qos_class_self_np(qos_class, 0) };
+    assert_eq!(ret, 0, "pthread_set_qos_class_self_np failed");
+}
+
 #[allow(unused)]
 pub fn cpu_max_freq_hz() -> Option<f64> {
     static MAX_FREQUENCY: OnceCell<Option<f64>> = OnceCell::new();
@@ -44,8 +87,21 @@ pub fn cpu_max_freq_hz() -> Option<f64> {
         })
 }
 
+/// Whether `name` matches `BENCH_REGEX` and belongs to the shard in `BENCH_SHARD`, see
+/// sort_research_rs::analysis::shard.
 pub fn should_run_benchmark(name: &str) -> bool {
     static FILTER_REGEX: OnceCell<Option<regex::Regex>> = OnceCell::new();
+    static SHARD: OnceCell<Shard> = OnceCell::new();
+
+    let shard = SHARD.get_or_init(|| {
+        env::var("BENCH_SHARD").map_or(Shard::ALL, |val| {
+            val.parse()
+                .unwrap_or_else(|err| panic!("Invalid BENCH_SHARD: {err}"))
+        })
+    });
+    if !shard.contains_bench(name) {
+        return false;
+    }
 
     let filter_regex = FILTER_REGEX.get_or_init(|| {
         env::var("BENCH_REGEX")
@@ -59,6 +115,43 @@ pub fn should_run_benchmark(name: &str) -> bool {
         .unwrap_or(true)
 }
 
+/// Whether this process runs its benchmarks in child processes, see [`run_isolated`].
+pub fn isolates() -> bool {
+    static ISOLATE: OnceCell<bool> = OnceCell::new();
+
+    *ISOLATE.get_or_init(|| {
+        env::var("BENCH_ISOLATE").is_ok_and(|val| val != "0")
+            && env::var("BENCH_ISOLATED_CHILD").is_err()
+    })
+}
+
+/// With `BENCH_ISOLATE=1` runs the benchmark `name` in a child process of the bench binary and
+/// returns true, otherwise returns false and the caller runs it. A sort that segfaults or aborts
+/// only takes down its child, the failure is printed as `<result_name>: failed: <status>` and the
+/// session goes on with the next benchmark.
+///
+/// The child is started with the same arguments and `BENCH_REGEX` matching only `name`, so it
+/// saves its results to the same criterion baseline.
+pub fn run_isolated(name: &str, result_name: &str) -> bool {
+    if !isolates() {
+        return false;
+    }
+
+    let status = Command::new(env::current_exe().unwrap())
+        .args(env::args_os().skip(1))
+        .env("BENCH_ISOLATED_CHILD", "1")
+        .env("BENCH_REGEX", format!("^{}$", regex::escape(name)))
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => println!("{result_name}: failed: {status}"),
+        Err(err) => println!("{result_name}: failed: {err}"),
+    }
+
+    true
+}
+
 #[inline(never)]
 pub fn bench_fn<T: Ord + std::fmt::Debug>(
     c: &mut Criterion,
@@ -98,10 +191,15 @@ pub fn bench_fn<T: Ord + std::fmt::Debug>(
     let bench_name_hot_with_overwrite =
         format!("{bech_name_with_overwrite}-hot-{transform_name}-{pattern_name}-{test_len}");
 
-    if should_run_benchmark(&bench_name_hot) {
+    if should_run_benchmark(&bench_name_hot)
+        && !run_isolated(&bench_name_hot, &bench_name_hot_with_overwrite)
+    {
+        let inputs = PairedInputs::new(test_len, &pattern_provider);
         c.bench_function(&bench_name_hot_with_overwrite, |b| {
+            // Every sample starts at the first input, whatever the warmup used.
+            inputs.restart();
             b.iter_batched_ref(
-                || transform(pattern_provider(test_len)),
+                || transform(inputs.next_input(test_len)),
                 |test_data| {
                     test_fn(black_box(test_data.as_mut_slice()));
                     black_box(test_data); // side-effect
@@ -118,11 +216,15 @@ pub fn bench_fn<T: Ord + std::fmt::Debug>(
         let bench_name_cold_with_overwrite =
             format!("{bech_name_with_overwrite}-cold-{transform_name}-{pattern_name}-{test_len}");
 
-        if should_run_benchmark(&bench_name_cold) {
+        if should_run_benchmark(&bench_name_cold)
+            && !run_isolated(&bench_name_cold, &bench_name_cold_with_overwrite)
+        {
+            let inputs = PairedInputs::new(test_len, &pattern_provider);
             c.bench_function(&bench_name_cold_with_overwrite, |b| {
+                inputs.restart();
                 b.iter_batched_ref(
                     || {
-                        let mut test_ints = pattern_provider(test_len);
+                        let mut test_ints = inputs.next_input(test_len);
 
                         if test_ints.len() == 0 {
                             return vec![];
@@ -152,3 +254,50 @@ pub fn bench_fn<T: Ord + std::fmt::Debug>(
         }
     }
 }
+
+/// The byte offsets `BENCH_MISALIGN` runs for `T`, every multiple of its alignment below 16.
+/// Offset 0 is the aligned baseline, the input of the regular benchmarks is only as aligned as the
+/// allocator makes it.
+pub fn misalign_offsets<T>() -> impl Iterator<Item = usize> {
+    (0..16).step_by(mem::align_of::<T>())
+}
+
+/// [`bench_fn`] with the input moved into the [`BenchBuf`] made by `make_buf`, as
+/// `<bench_name>-hot-...`. Only the hot benchmark is run.
+#[inline(never)]
+pub fn bench_fn_buf<T: Ord + std::fmt::Debug>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: impl Fn(usize) -> Vec<i32>,
+    bench_name: &str,
+    make_buf: impl Fn(Vec<T>) -> BenchBuf<T>,
+    test_fn: impl Fn(&mut [T]),
+) {
+    pin_thread_to_core();
+
+    let batch_size = if test_len > 30 {
+        BatchSize::LargeInput
+    } else {
+        BatchSize::SmallInput
+    };
+
+    let name = format!("{bench_name}-hot-{transform_name}-{pattern_name}-{test_len}");
+
+    if should_run_benchmark(&name) && !run_isolated(&name, &name) {
+        let inputs = PairedInputs::new(test_len, &pattern_provider);
+        c.bench_function(&name, |b| {
+            inputs.restart();
+            b.iter_batched_ref(
+                || make_buf(transform(inputs.next_input(test_len))),
+                |test_data| {
+                    test_fn(black_box(test_data.as_mut_slice()));
+                    black_box(test_data); // side-effect
+                },
+                batch_size,
+            )
+        });
+    }
+}
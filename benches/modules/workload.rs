@@ -0,0 +1,61 @@
+use std::env;
+
+use criterion::Criterion;
+
+use once_cell::sync::OnceCell;
+
+use sort_research_rs::other::{batch_sort, workload};
+use sort_research_rs::registry;
+
+use crate::modules::util::bench_fn;
+
+// The sizes have to be the same for every sort, so they are drawn with a fixed seed instead of the
+// pattern seed.
+const SIZE_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+fn histogram() -> &'static workload::SizeHistogram {
+    static HISTOGRAM: OnceCell<workload::SizeHistogram> = OnceCell::new();
+
+    HISTOGRAM.get_or_init(|| {
+        let spec =
+            env::var("BENCH_WORKLOAD").unwrap_or_else(|_| workload::DEFAULT_HISTOGRAM.into());
+        let histogram = spec
+            .parse::<workload::SizeHistogram>()
+            .unwrap_or_else(|err| panic!("Invalid BENCH_WORKLOAD: {err}"));
+
+        println!("Workload: {spec}");
+        histogram
+    })
+}
+
+pub fn bench<T: Ord + std::fmt::Debug + 'static>(
+    c: &mut Criterion,
+    test_len: usize,
+    transform_name: &str,
+    transform: &fn(Vec<i32>) -> Vec<T>,
+    pattern_name: &str,
+    pattern_provider: &fn(usize) -> Vec<i32>,
+) {
+    // test_len is the total number of elements, split into calls. Below the largest size the last
+    // call would be cut short too often to follow the histogram.
+    let histogram = histogram();
+    if test_len < histogram.max_len() {
+        return;
+    }
+
+    for entry in registry::sorts::<T>() {
+        bench_fn(
+            c,
+            test_len,
+            transform_name,
+            transform,
+            pattern_name,
+            pattern_provider,
+            &format!("{}_workload", entry.name()),
+            |v| {
+                let mut slices = batch_sort::split_into_batch(v, histogram.sampler(SIZE_SEED));
+                batch_sort::sort_batch_with(&mut slices, |slice| entry.sort(slice));
+            },
+        );
+    }
+}
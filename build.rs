@@ -5,6 +5,254 @@ use std::path::PathBuf;
 #[allow(unused)]
 const CLANG_PATH: &str = "clang++";
 
+// Used instead of CLANG_PATH for msvc targets, clang-cl understands the cl.exe flags cc passes.
+#[allow(unused)]
+const CLANG_CL_PATH: &str = "clang-cl";
+
+#[allow(unused)]
+fn is_msvc_target() -> bool {
+    env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("msvc")
+}
+
+#[allow(unused)]
+fn clang_path() -> &'static str {
+    if is_msvc_target() {
+        CLANG_CL_PATH
+    } else {
+        CLANG_PATH
+    }
+}
+
+#[allow(unused)]
+fn target_arch() -> String {
+    env::var("CARGO_CFG_TARGET_ARCH").unwrap()
+}
+
+#[allow(unused)]
+fn is_x86_target() -> bool {
+    matches!(target_arch().as_str(), "x86" | "x86_64")
+}
+
+// Target features of the Rust side, eg. `avx2` or `neon`, including the ones enabled with
+// -Ctarget-cpu or -Ctarget-feature in RUSTFLAGS.
+#[allow(unused)]
+fn has_target_feature(feature: &str) -> bool {
+    env::var("CARGO_CFG_TARGET_FEATURE")
+        .is_ok_and(|features| features.split(',').any(|enabled| enabled == feature))
+}
+
+// Must be called after setting the compiler.
+#[allow(unused)]
+fn add_march_native(builder: &mut cc::Build) {
+    let compiler = builder.get_compiler();
+    let is_clang_cl = compiler.is_like_msvc()
+        && compiler
+            .path()
+            .file_stem()
+            .is_some_and(|stem| stem.to_string_lossy().contains("clang"));
+
+    // On aarch64 gcc and clang tune for the host with -mcpu, Apple clang doesn't know
+    // -march=native there at all.
+    let native_flag = if is_x86_target() {
+        "-march=native"
+    } else {
+        "-mcpu=native"
+    };
+
+    if is_clang_cl {
+        builder.flag(format!("/clang:{native_flag}"));
+    } else if compiler.is_like_msvc() {
+        // cl.exe has no equivalent of -march=native, AVX2 is the closest common denominator. NEON
+        // is always enabled on arm64.
+        if is_x86_target() {
+            builder.flag("/arch:AVX2");
+        }
+    } else {
+        builder.flag(native_flag);
+    }
+}
+
+// The C++ SIMD sorts select their instruction set at compile time. Native builds use everything the
+// host supports. Cross builds, eg. for an Apple Silicon or Graviton machine, can't ask the host and
+// enable what the Rust side is compiled with instead, eg. RUSTFLAGS="-Ctarget-cpu=neoverse-v1".
+// Must be called after setting the compiler.
+#[allow(unused)]
+fn add_simd_flags(builder: &mut cc::Build) {
+    if env::var("HOST") == env::var("TARGET") {
+        add_march_native(builder);
+        return;
+    }
+
+    if builder.get_compiler().is_like_msvc() {
+        if has_target_feature("avx512f") {
+            builder.flag("/arch:AVX512");
+        } else if has_target_feature("avx2") {
+            builder.flag("/arch:AVX2");
+        }
+        return;
+    }
+
+    // NEON is part of the aarch64 baseline, later entries imply the earlier ones.
+    let feature_flags: &[(&str, &str)] = match target_arch().as_str() {
+        "x86" | "x86_64" => &[
+            ("sse4.2", "-msse4.2"),
+            ("popcnt", "-mpopcnt"),
+            ("bmi2", "-mbmi2"),
+            ("avx2", "-mavx2"),
+            ("fma", "-mfma"),
+            ("avx512f", "-mavx512f"),
+            ("avx512vl", "-mavx512vl"),
+            ("avx512dq", "-mavx512dq"),
+            ("avx512bw", "-mavx512bw"),
+            ("avx512vbmi2", "-mavx512vbmi2"),
+        ],
+        "aarch64" => &[
+            ("sve", "-march=armv8.2-a+sve"),
+            ("sve2", "-march=armv9-a+sve2"),
+        ],
+        _ => &[],
+    };
+
+    for (feature, flag) in feature_flags {
+        if has_target_feature(feature) {
+            builder.flag(flag);
+        }
+    }
+}
+
+// panic=abort builds of the Rust side, RUSTFLAGS=-Cpanic=abort. The panic setting of a cargo
+// profile is not visible here. A panic in the Rust comparison function aborts before it returns to
+// the C++ sort, so there is nothing left to catch and the C++ side is built without exceptions as
+// well, see FFI_TRY in shared.h.
+#[allow(unused)]
+fn is_panic_abort() -> bool {
+    env::var("CARGO_CFG_PANIC").as_deref() == Ok("abort")
+}
+
+// Must be called after setting the compiler.
+#[allow(unused)]
+fn add_exception_flags(builder: &mut cc::Build, file_name: &str) {
+    // Powersort throws if it can't build a sentinel value for a type.
+    let needs_exceptions = file_name == "cpp_powersort";
+
+    if !is_panic_abort() || needs_exceptions {
+        builder.flag_if_supported("/EHsc");
+    } else if !builder.get_compiler().is_like_msvc() {
+        // cl.exe has exceptions disabled as long as /EHsc is not set.
+        builder.flag("-fno-exceptions");
+    }
+}
+
+// Optional LTO and PGO builds of the C and C++ sorts, to check whether they were compiled fairly.
+// The FFI sorts get a name suffix, eg. cpp_pdqsort_unstable_lto_pgo, so that results of the
+// different builds can be compared side by side.
+//
+// CPP_LTO=1 compiles with -flto=thin. This only has an effect if the Rust side is linked with the
+// LLVM linker plugin, which allows inlining the Rust comparison function into the C++ sorts:
+// RUSTFLAGS="-Clinker-plugin-lto -Clinker=clang -Clink-arg=-fuse-ld=lld"
+// All enabled C and C++ sorts have to be built with clang, eg. CXX=clang++.
+//
+// CPP_PGO=gen:<dir> builds instrumented sorts that write profiles to <dir> when run, eg. by
+// running the benchmarks. CPP_PGO=use:<dir> then builds with those profiles. With clang the raw
+// profiles have to be merged first: llvm-profdata merge -o <dir>/default.profdata <dir>
+// The instrumented build has to be linked with the same compiler driver, eg. -Clinker=clang.
+#[allow(unused)]
+enum Pgo {
+    Gen(String),
+    Use(String),
+}
+
+#[allow(unused)]
+struct BuildVariant {
+    lto: bool,
+    pgo: Option<Pgo>,
+}
+
+#[allow(unused)]
+impl BuildVariant {
+    fn from_env() -> Self {
+        let lto = env::var("CPP_LTO").is_ok_and(|val| val == "1");
+        let pgo = env::var("CPP_PGO")
+            .ok()
+            .map(|val| match val.split_once(':') {
+                Some(("gen", dir)) => Pgo::Gen(dir.into()),
+                Some(("use", dir)) => Pgo::Use(dir.into()),
+                _ => panic!("Invalid CPP_PGO value '{val}', expected gen:<dir> or use:<dir>"),
+            });
+
+        Self { lto, pgo }
+    }
+
+    fn name_suffix(&self) -> String {
+        let mut suffix = String::new();
+
+        if self.lto {
+            suffix.push_str("_lto");
+        }
+
+        match self.pgo {
+            Some(Pgo::Gen(_)) => suffix.push_str("_pgo_gen"),
+            Some(Pgo::Use(_)) => suffix.push_str("_pgo"),
+            None => {}
+        }
+
+        suffix
+    }
+
+    // Must be called after setting the compiler.
+    fn apply(&self, builder: &mut cc::Build, artifact_name: &str) {
+        if self.lto {
+            let compiler = builder.get_compiler();
+            if !compiler.is_like_clang() {
+                panic!(
+                    "CPP_LTO requires clang, but {artifact_name} is built with {}",
+                    compiler.path().display()
+                );
+            }
+
+            builder.flag("-flto=thin");
+        }
+
+        match &self.pgo {
+            Some(Pgo::Gen(dir)) => {
+                builder.flag(format!("-fprofile-generate={dir}"));
+            }
+            Some(Pgo::Use(dir)) => {
+                builder.flag(format!("-fprofile-use={dir}"));
+                // Not every sort function is covered by the profiling run.
+                builder.flag_if_supported("-Wno-missing-profile");
+                builder.flag_if_supported("-Wno-profile-instr-unprofiled");
+            }
+            None => {}
+        }
+    }
+}
+
+fn link_build_variant() {
+    println!("cargo:rerun-if-env-changed=CPP_LTO");
+    println!("cargo:rerun-if-env-changed=CPP_PGO");
+
+    let variant = BuildVariant::from_env();
+
+    // Used by ffi_sort_impl to name the sorts.
+    println!(
+        "cargo:rustc-env=CPP_SORT_VARIANT_SUFFIX={}",
+        variant.name_suffix()
+    );
+
+    if variant.lto
+        && !env::var("CARGO_ENCODED_RUSTFLAGS")
+            .is_ok_and(|flags| flags.contains("linker-plugin-lto"))
+    {
+        println!("cargo:warning=CPP_LTO=1 without -Clinker-plugin-lto in RUSTFLAGS has no effect");
+    }
+
+    if let Some(Pgo::Gen(_)) = variant.pgo {
+        // Pulls in the profiling runtime of the compiler driver used for linking.
+        println!("cargo:rustc-link-arg=-fprofile-generate");
+    }
+}
+
 #[allow(dead_code)]
 fn build_and_link_cpp_sort(
     file_name: &str,
@@ -37,7 +285,6 @@ fn build_and_link_cpp_sort(
         .file(file_path)
         .cpp(true)
         .warnings(false) // The thirdparties just have too many.
-        .flag_if_supported("/EHsc")
         .flag_if_supported("/Zc:__cplusplus")
         .flag_if_supported("/std:c++20")
         .flag_if_supported("-std=c++20")
@@ -54,6 +301,9 @@ fn build_and_link_cpp_sort(
         }
     }
 
+    add_exception_flags(&mut builder, file_name);
+    BuildVariant::from_env().apply(&mut builder, &artifact_name);
+
     builder.compile(&artifact_name);
 
     println!("cargo:rustc-link-search={}", out_dir.display());
@@ -81,8 +331,12 @@ fn build_and_link_cpp_simdsort() {
     build_and_link_cpp_sort(
         "cpp_simdsort",
         Some(|builder: &mut cc::Build| {
+            if !is_x86_target() {
+                panic!("cpp_simdsort requires an x86 target, x86-simd-sort has no NEON version");
+            }
+
             // Make an exception for march=native here because AVX2 will not work without it.
-            builder.flag_if_supported("-march=native");
+            add_simd_flags(builder);
 
             None
         }),
@@ -102,9 +356,11 @@ fn build_and_link_cpp_vqsort() {
     build_and_link_cpp_sort(
         "cpp_vqsort",
         Some(|builder: &mut cc::Build| {
-            // Make an exception for march=native here because AVX2 will not work without it.
-            builder.flag("-march=native");
-            builder.compiler(CLANG_PATH); // gcc yields significantly worse code-gen here.
+            builder.compiler(clang_path()); // gcc yields significantly worse code-gen here.
+
+            // Make an exception for march=native here because AVX2 will not work without it. On
+            // aarch64 vqsort uses NEON, or SVE if enabled.
+            add_simd_flags(builder);
 
             None
         }),
@@ -119,9 +375,14 @@ fn build_and_link_cpp_intel_avx512() {
     build_and_link_cpp_sort(
         "cpp_intel_avx512",
         Some(|builder: &mut cc::Build| {
+            if !is_x86_target() {
+                panic!("cpp_intel_avx512 requires an x86 target");
+            }
+
+            builder.compiler(clang_path()); // gcc yields significantly worse code-gen here.
+
             // Make an exception for march=native here because AVX512 will not work without it.
-            builder.flag("-march=native");
-            builder.compiler(CLANG_PATH); // gcc yields significantly worse code-gen here.
+            add_simd_flags(builder);
 
             None
         }),
@@ -137,7 +398,7 @@ fn build_and_link_singelisort() {
         "singeli_singelisort",
         Some(|builder: &mut cc::Build| {
             // Clang seems to produce slightly better perf.
-            builder.compiler(CLANG_PATH);
+            builder.compiler(clang_path());
 
             None
         }),
@@ -220,7 +481,7 @@ fn build_and_link_cpp_gerbens_qsort() {
     build_and_link_cpp_sort(
         "cpp_gerbens_qsort",
         Some(|builder: &mut cc::Build| {
-            builder.compiler(CLANG_PATH); // gcc yields significantly worse code-gen here.
+            builder.compiler(clang_path()); // gcc yields significantly worse code-gen here.
 
             None
         }),
@@ -235,7 +496,7 @@ fn build_and_link_cpp_nanosort() {
     build_and_link_cpp_sort(
         "cpp_nanosort",
         Some(|builder: &mut cc::Build| {
-            builder.compiler(CLANG_PATH); // gcc yields significantly worse code-gen here.
+            builder.compiler(clang_path()); // gcc yields significantly worse code-gen here.
 
             None
         }),
@@ -251,7 +512,7 @@ fn build_and_link_cpp_wikisort() {
         "cpp_wikisort",
         Some(|builder: &mut cc::Build| {
             // clang yields better code-gen for random patterns, gcc for partially sorted ones.
-            builder.compiler(CLANG_PATH);
+            builder.compiler(clang_path());
 
             None
         }),
@@ -274,7 +535,7 @@ fn build_and_link_c_crumsort() {
     build_and_link_cpp_sort(
         "c_crumsort",
         Some(|builder: &mut cc::Build| {
-            builder.compiler(CLANG_PATH); // clang can generate cmov which yields better perf.
+            builder.compiler(clang_path()); // clang can generate cmov which yields better perf.
 
             None
         }),
@@ -289,7 +550,7 @@ fn build_and_link_c_fluxsort() {
     build_and_link_cpp_sort(
         "c_fluxsort",
         Some(|builder: &mut cc::Build| {
-            builder.compiler(CLANG_PATH); // clang can generate cmov which yields better perf.
+            builder.compiler(clang_path()); // clang can generate cmov which yields better perf.
 
             None
         }),
@@ -321,10 +582,11 @@ fn build_and_link_cpp_std_libcxx() {
         Some(|builder| {
             builder
                 .define("STD_LIB_LIBCXX", None)
-                .compiler(CLANG_PATH)
-                .flag("-march=native")
+                .compiler(clang_path())
                 .cpp_set_stdlib("c++"); // Use libcxx
 
+            add_march_native(builder);
+
             Some("cpp_std_sort_libcxx".into())
         }),
     );
@@ -333,6 +595,49 @@ fn build_and_link_cpp_std_libcxx() {
 #[cfg(not(feature = "cpp_std_libcxx"))]
 fn build_and_link_cpp_std_libcxx() {}
 
+// Template instantiations with the same mangled name in cpp_std_sys and cpp_std_gnu can be
+// merged by the linker. Avoid enabling both if the system compiler is also g++.
+#[cfg(feature = "cpp_std_gnu")]
+fn build_and_link_cpp_std_gnu() {
+    build_and_link_cpp_sort(
+        "cpp_std_sort",
+        Some(|builder| {
+            builder
+                .define("STD_LIB_GNU", None)
+                .compiler("g++")
+                .cpp_set_stdlib("stdc++");
+
+            Some("cpp_std_sort_gnu".into())
+        }),
+    );
+}
+
+#[cfg(not(feature = "cpp_std_gnu"))]
+fn build_and_link_cpp_std_gnu() {}
+
+#[cfg(feature = "cpp_std_msvc")]
+fn build_and_link_cpp_std_msvc() {
+    if !is_msvc_target() {
+        panic!("cpp_std_msvc requires an msvc target, eg. x86_64-pc-windows-msvc");
+    }
+
+    build_and_link_cpp_sort(
+        "cpp_std_sort",
+        Some(|builder| {
+            // cc picks cl.exe from the Visual Studio installation, or the compiler set via CXX.
+            // Either way it has to use the MSVC STL.
+            assert!(builder.get_compiler().is_like_msvc());
+
+            builder.define("STD_LIB_MSVC", None);
+
+            Some("cpp_std_sort_msvc".into())
+        }),
+    );
+}
+
+#[cfg(not(feature = "cpp_std_msvc"))]
+fn build_and_link_cpp_std_msvc() {}
+
 #[cfg(feature = "cpp_std_gcc4_3")]
 fn build_and_link_cpp_std_gcc4_3() {
     build_and_link_cpp_sort(
@@ -356,6 +661,22 @@ fn build_and_link_cpp_std_gcc4_3() {
 #[cfg(not(feature = "cpp_std_gcc4_3"))]
 fn build_and_link_cpp_std_gcc4_3() {}
 
+#[cfg(feature = "cpp_str_sort")]
+fn build_and_link_cpp_str_sort() {
+    build_and_link_cpp_sort("cpp_str_sort", None);
+}
+
+#[cfg(not(feature = "cpp_str_sort"))]
+fn build_and_link_cpp_str_sort() {}
+
+#[cfg(feature = "ffi_calibration")]
+fn build_and_link_ffi_calibration() {
+    build_and_link_cpp_sort("ffi_calibration", None);
+}
+
+#[cfg(not(feature = "ffi_calibration"))]
+fn build_and_link_ffi_calibration() {}
+
 fn main() {
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     let build_rs_path = manifest_dir.join("build.rs").canonicalize().unwrap();
@@ -363,6 +684,8 @@ fn main() {
     // By default without this line, cargo re-runs the build script for all source changes.
     println!("cargo:rerun-if-changed={}", build_rs_path.display());
 
+    link_build_variant();
+
     build_and_link_cpp_pdqsort();
     build_and_link_cpp_powersort();
     build_and_link_cpp_simdsort();
@@ -380,5 +703,9 @@ fn main() {
     build_and_link_c_fluxsort();
     build_and_link_cpp_std_sys();
     build_and_link_cpp_std_libcxx();
+    build_and_link_cpp_std_gnu();
+    build_and_link_cpp_std_msvc();
     build_and_link_cpp_std_gcc4_3();
+    build_and_link_cpp_str_sort();
+    build_and_link_ffi_calibration();
 }
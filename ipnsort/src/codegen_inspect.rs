@@ -0,0 +1,35 @@
+//! Monomorphic instances of the hot kernels, with unmangled names and never inlined, so that their
+//! assembly can be inspected in isolation. See `cargo xtask codegen` in the repository root.
+
+use crate::quicksort::partition_lomuto_branchless_cyclic;
+use crate::smallsort::{bidirectional_merge, small_sort_network, sort4_stable, swap_if_less};
+
+#[no_mangle]
+#[inline(never)]
+pub unsafe fn ipnsort_codegen_swap_if_less_u64(v_base: *mut u64, a_pos: usize, b_pos: usize) {
+    swap_if_less(v_base, a_pos, b_pos, &mut |a, b| a < b);
+}
+
+#[no_mangle]
+#[inline(never)]
+pub unsafe fn ipnsort_codegen_sort4_stable_u64(v_base: *const u64, dst: *mut u64) {
+    sort4_stable(v_base, dst, &mut |a, b| a < b);
+}
+
+#[no_mangle]
+#[inline(never)]
+pub fn ipnsort_codegen_small_sort_network_u64(v: &mut [u64]) {
+    small_sort_network(v, &mut |a, b| a < b);
+}
+
+#[no_mangle]
+#[inline(never)]
+pub fn ipnsort_codegen_partition_lomuto_branchless_cyclic_u64(v: &mut [u64], pivot: &u64) -> usize {
+    partition_lomuto_branchless_cyclic(v, pivot, &mut |a, b| a < b)
+}
+
+#[no_mangle]
+#[inline(never)]
+pub unsafe fn ipnsort_codegen_bidirectional_merge_u64(v: &[u64], dst: *mut u64) {
+    bidirectional_merge(v, dst, &mut |a, b| a < b);
+}
@@ -9,16 +9,40 @@
     sized_type_properties,
     const_mut_refs
 )]
+#![cfg_attr(feature = "strict_provenance", feature(strict_provenance_lints))]
+#![cfg_attr(
+    feature = "strict_provenance",
+    deny(fuzzy_provenance_casts, lossy_provenance_casts)
+)]
 
 use core::cmp::Ordering;
 use core::intrinsics;
 use core::mem::SizedTypeProperties;
 
+/// Reports that `specialization::Path::$path` was taken if the `specialization_counters` feature
+/// is enabled.
+macro_rules! taken_path {
+    ($path:ident) => {
+        #[cfg(feature = "specialization_counters")]
+        crate::specialization::on_path(crate::specialization::Path::$path);
+    };
+}
+
+#[cfg(all(feature = "inline_kernels", feature = "outline_kernels"))]
+compile_error!("The inline_kernels and outline_kernels features are mutually exclusive");
+
 mod heapsort;
 mod pivot;
 mod quicksort;
 mod smallsort;
 
+#[cfg(feature = "codegen_inspect")]
+#[doc(hidden)]
+pub mod codegen_inspect;
+
+#[cfg(feature = "specialization_counters")]
+pub mod specialization;
+
 /// Sorts the slice, but might not preserve the order of equal elements.
 ///
 /// This sort is unstable (i.e., may reorder equal elements), in-place
@@ -117,6 +141,7 @@ where
 {
     // Arrays of zero-sized types are always all-equal, and thus sorted.
     if T::IS_ZST {
+        taken_path!(ZeroSized);
         return;
     }
 
@@ -135,6 +160,7 @@ where
     // misses during the sort, and thrashing the i-cache for surrounding code.
     const MAX_LEN_ALWAYS_INSERTION_SORT: usize = 20;
     if intrinsics::likely(len <= MAX_LEN_ALWAYS_INSERTION_SORT) {
+        taken_path!(InsertionSortOnly);
         smallsort::insertion_sort_shift_left(v, 1, &mut is_less);
         return;
     }
@@ -155,6 +181,7 @@ where
     unsafe { intrinsics::assume(run_len <= len) };
 
     if run_len == len {
+        taken_path!(ExistingRun);
         if was_reversed {
             v.reverse();
         }
@@ -151,7 +151,7 @@ where
 
 const fn inst_partition<T, F: FnMut(&T, &T) -> bool>() -> fn(&mut [T], &T, &mut F) -> usize {
     const MAX_BRANCHLESS_PARTITION_SIZE: usize = 96;
-    if mem::size_of::<T>() <= MAX_BRANCHLESS_PARTITION_SIZE {
+    if cfg!(feature = "deterministic") || mem::size_of::<T>() <= MAX_BRANCHLESS_PARTITION_SIZE {
         // Specialize for types that are relatively cheap to copy, where branchless optimizations
         // have large leverage e.g. `u64` and `String`.
         partition_lomuto_branchless_cyclic::<T, F>
@@ -165,6 +165,8 @@ fn partition_hoare_branchy_cyclic<T, F>(v: &mut [T], pivot: &T, is_less: &mut F)
 where
     F: FnMut(&T, &T) -> bool,
 {
+    taken_path!(PartitionBranchy);
+
     let len = v.len();
 
     if len == 0 {
@@ -253,10 +255,16 @@ struct PartitionState<T> {
     gap: GapGuardRaw<T>,
 }
 
-fn partition_lomuto_branchless_cyclic<T, F>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize
+pub(crate) fn partition_lomuto_branchless_cyclic<T, F>(
+    v: &mut [T],
+    pivot: &T,
+    is_less: &mut F,
+) -> usize
 where
     F: FnMut(&T, &T) -> bool,
 {
+    taken_path!(PartitionBranchless);
+
     // Novel partition implementation by Lukas Bergdoll and Orson Peters. Branchless Lomuto
     // partition paired with a cyclic permutation. TODO link writeup.
 
@@ -81,6 +81,11 @@ enum UnstalbeSmallSort {
 }
 
 const fn choose_unstable_small_sort<T: Freeze>() -> UnstalbeSmallSort {
+    if cfg!(feature = "deterministic") {
+        // The same small-sort as for types that aren't `Freeze`, see the deterministic feature.
+        return UnstalbeSmallSort::Fallback;
+    }
+
     if T::IS_COPY
         && has_efficient_in_place_swap::<T>()
         && (mem::size_of::<T>() * SMALL_SORT_NETWORK_SCRATCH_LEN) <= MAX_STACK_ARRAY_SIZE
@@ -105,12 +110,16 @@ const fn inst_unstable_small_sort<T: Freeze, F: FnMut(&T, &T) -> bool>() -> fn(&
 }
 
 fn small_sort_fallback<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    taken_path!(SmallSortFallback);
+
     if v.len() >= 2 {
         insertion_sort_shift_left(v, 1, is_less);
     }
 }
 
 fn small_sort_general<T: Freeze, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    taken_path!(SmallSortGeneral);
+
     let mut stack_array = MaybeUninit::<[T; SMALL_SORT_GENERAL_SCRATCH_LEN]>::uninit();
 
     let scratch = unsafe {
@@ -123,6 +132,8 @@ fn small_sort_general<T: Freeze, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less:
     small_sort_general_with_scratch(v, scratch, is_less);
 }
 
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
 fn small_sort_general_with_scratch<T: Freeze, F: FnMut(&T, &T) -> bool>(
     v: &mut [T],
     scratch: &mut [MaybeUninit<T>],
@@ -222,11 +233,15 @@ impl<T> Drop for CopyOnDrop<T> {
     }
 }
 
-fn small_sort_network<T, F>(v: &mut [T], is_less: &mut F)
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
+pub(crate) fn small_sort_network<T, F>(v: &mut [T], is_less: &mut F)
 where
     T: Freeze,
     F: FnMut(&T, &T) -> bool,
 {
+    taken_path!(SmallSortNetwork);
+
     // This implementation is tuned to be efficient for integer types.
 
     let len = v.len();
@@ -440,6 +455,8 @@ where
 ///
 /// # Safety
 /// begin < tail and p must be valid and initialized for all begin <= p <= tail.
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
 unsafe fn insert_tail<T, F: FnMut(&T, &T) -> bool>(begin: *mut T, tail: *mut T, is_less: &mut F) {
     // SAFETY: see individual comments.
     unsafe {
@@ -514,6 +531,8 @@ pub fn insertion_sort_shift_left<T, F: FnMut(&T, &T) -> bool>(
 
 /// SAFETY: The caller MUST guarantee that `v_base` is valid for 4 reads and
 /// `dst` is valid for 4 writes. The result will be stored in `dst[0..4]`.
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
 pub unsafe fn sort4_stable<T, F: FnMut(&T, &T) -> bool>(
     v_base: *const T,
     dst: *mut T,
@@ -574,6 +593,8 @@ pub unsafe fn sort4_stable<T, F: FnMut(&T, &T) -> bool>(
 /// SAFETY: The caller MUST guarantee that `v_base` is valid for 8 reads and
 /// writes, `scratch_base` and `dst` MUST be valid for 8 writes. The result will
 /// be stored in `dst[0..8]`.
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
 unsafe fn sort8_stable<T: Freeze, F: FnMut(&T, &T) -> bool>(
     v_base: *mut T,
     dst: *mut T,
@@ -671,7 +692,9 @@ unsafe fn merge_down<T, F: FnMut(&T, &T) -> bool>(
 ///
 /// Note that T must be Freeze, the comparison function is evaluated on outdated
 /// temporary 'copies' that may not end up in the final array.
-unsafe fn bidirectional_merge<T: Freeze, F: FnMut(&T, &T) -> bool>(
+#[cfg_attr(feature = "inline_kernels", inline(always))]
+#[cfg_attr(feature = "outline_kernels", inline(never))]
+pub(crate) unsafe fn bidirectional_merge<T: Freeze, F: FnMut(&T, &T) -> bool>(
     v: &[T],
     dst: *mut T,
     is_less: &mut F,
@@ -744,7 +767,10 @@ unsafe fn bidirectional_merge<T: Freeze, F: FnMut(&T, &T) -> bool>(
         // We now should have consumed the full input exactly once. This can
         // only fail if the comparison operator fails to be Ord, in which case
         // we will panic and never access the inconsistent state in dst.
-        if left != left_end || right != right_end {
+        if cfg!(feature = "unchecked_ord") {
+            // Unsound, only to measure what the check costs, see the unchecked_ord feature.
+            debug_assert!(left == left_end && right == right_end, "Ord violation");
+        } else if left != left_end || right != right_end {
             panic_on_ord_violation();
         }
     }
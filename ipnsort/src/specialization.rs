@@ -0,0 +1,114 @@
+//! Counts which of the type dependent code paths of ipnsort were taken.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), counts) = specialization::record(|| ipnsort::sort(&mut v));
+//! println!("{}", counts.count(Path::SmallSortNetwork));
+//! ```
+//!
+//! Most paths are chosen at compile time from the size of the type and whether it is `Copy` and
+//! `Freeze`, the counters show whether the path intended for a type is the one it gets. A path is
+//! counted each time it is entered, eg. once per small-sort call.
+
+use std::cell::RefCell;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Path {
+    /// Zero sized types are sorted by definition.
+    ZeroSized,
+    /// Inputs of up to 20 elements only use insertion sort.
+    InsertionSortOnly,
+    /// The whole input is one ascending or descending run.
+    ExistingRun,
+    /// Insertion sort small-sort, for types that are not `Freeze` or too large for the others.
+    SmallSortFallback,
+    /// Small-sort with sorting networks and merges through a stack scratch buffer.
+    SmallSortGeneral,
+    /// Branchless sorting network small-sort, for small `Copy` types such as integers.
+    SmallSortNetwork,
+    /// Branchless Lomuto partition, for types of up to 96 bytes.
+    PartitionBranchless,
+    /// Branchy Hoare partition, for larger types.
+    PartitionBranchy,
+}
+
+impl Path {
+    pub const ALL: [Path; 8] = [
+        Path::ZeroSized,
+        Path::InsertionSortOnly,
+        Path::ExistingRun,
+        Path::SmallSortFallback,
+        Path::SmallSortGeneral,
+        Path::SmallSortNetwork,
+        Path::PartitionBranchless,
+        Path::PartitionBranchy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Path::ZeroSized => "zero_sized",
+            Path::InsertionSortOnly => "insertion_sort_only",
+            Path::ExistingRun => "existing_run",
+            Path::SmallSortFallback => "small_sort_fallback",
+            Path::SmallSortGeneral => "small_sort_general",
+            Path::SmallSortNetwork => "small_sort_network",
+            Path::PartitionBranchless => "partition_branchless",
+            Path::PartitionBranchy => "partition_branchy",
+        }
+    }
+}
+
+/// Counters collected for one or more sort invocations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathCounts {
+    /// Indexed by the position in [`Path::ALL`].
+    pub counts: [usize; Path::ALL.len()],
+}
+
+impl PathCounts {
+    pub fn count(&self, path: Path) -> usize {
+        self.counts[path as usize]
+    }
+
+    /// Paths that were taken at least once.
+    pub fn taken(&self) -> impl Iterator<Item = Path> + '_ {
+        Path::ALL.into_iter().filter(|&path| self.count(path) > 0)
+    }
+
+    /// Adds the counts of `other`, to aggregate several invocations.
+    pub fn merge(&mut self, other: &PathCounts) {
+        for i in 0..self.counts.len() {
+            self.counts[i] += other.counts[i];
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<PathCounts>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the paths taken by all ipnsort calls inside it on this thread.
+///
+/// Calls to `record` do not nest, the inner call takes all paths recorded while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, PathCounts) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(PathCounts::default()));
+    let result = f();
+    let counts = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let counts = current.take().unwrap_or_default();
+        *current = prev;
+        counts
+    });
+
+    (result, counts)
+}
+
+#[inline(always)]
+pub(crate) fn on_path(path: Path) {
+    CURRENT.with(|current| {
+        if let Some(counts) = current.borrow_mut().as_mut() {
+            counts.counts[path as usize] += 1;
+        }
+    });
+}
@@ -23,3 +23,80 @@ impl Sort for SortImpl {
 }
 
 instantiate_sort_tests!(SortImpl);
+
+/// Sorts `keys` tagged with their index as `T` and returns the indices in the sorted order. The
+/// indices show where the equal keys ended up.
+fn equal_key_order<T>(
+    keys: &[i32],
+    make: impl Fn(i32, usize) -> T,
+    unpack: impl Fn(&T) -> (i32, usize),
+) -> Vec<usize> {
+    let mut v = keys
+        .iter()
+        .enumerate()
+        .map(|(i, &key)| make(key, i))
+        .collect::<Vec<_>>();
+
+    ipnsort::sort_by(&mut v, |a, b| unpack(a).0.cmp(&unpack(b).0));
+
+    v.iter().map(|elem| unpack(elem).1).collect()
+}
+
+fn equal_key_inputs() -> impl Iterator<Item = Vec<i32>> {
+    let lens = (0..=40).chain([100, 1_000, 10_000]);
+    lens.flat_map(|len| {
+        [
+            sort_test_tools::patterns::random_uniform(len, 0..=3),
+            sort_test_tools::patterns::random_uniform(len, 0..=(len as i32 / 8)),
+            sort_test_tools::patterns::all_equal(len),
+        ]
+    })
+}
+
+#[test]
+fn equal_key_order_reproducible() {
+    for keys in equal_key_inputs() {
+        let make = |key, i| (key, i);
+        let unpack = |elem: &(i32, usize)| *elem;
+
+        let expected = equal_key_order(&keys, make, unpack);
+        for _ in 0..3 {
+            assert_eq!(equal_key_order(&keys, make, unpack), expected);
+        }
+    }
+}
+
+#[cfg(feature = "deterministic")]
+#[test]
+fn equal_key_order_type_independent() {
+    use std::cell::Cell;
+
+    for keys in equal_key_inputs() {
+        // Copy and small, takes the network small-sort without the feature.
+        let expected = equal_key_order(&keys, |key, i| (key, i), |elem| *elem);
+
+        // Not Copy.
+        let string = equal_key_order(
+            &keys,
+            |key, i| (key, i.to_string()),
+            |elem| (elem.0, elem.1.parse().unwrap()),
+        );
+        assert_eq!(string, expected);
+
+        // Too large for the branchless partition.
+        let large = equal_key_order(
+            &keys,
+            |key, i| (key, i, [0u64; 32]),
+            |elem| (elem.0, elem.1),
+        );
+        assert_eq!(large, expected);
+
+        // Not Freeze.
+        let cell = equal_key_order(
+            &keys,
+            |key, i| (Cell::new(key), i),
+            |elem| (elem.0.get(), elem.1),
+        );
+        assert_eq!(cell, expected);
+    }
+}
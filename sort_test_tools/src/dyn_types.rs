@@ -0,0 +1,83 @@
+//! Trait objects compared through a vtable call, shared by the tests, the benchmarks and the
+//! specialization report.
+//!
+//! The values alternate between two types of different size, so the indirect branch of the
+//! comparison is unpredictable.
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+pub trait DynTrait: Debug {
+    fn get_val(&self) -> i32;
+
+    fn clone_box(&self) -> Box<dyn DynTrait>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynValA {
+    pub value: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynValB {
+    pub value: u64,
+}
+
+impl DynTrait for DynValA {
+    fn get_val(&self) -> i32 {
+        self.value
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTrait> {
+        Box::new(self.clone())
+    }
+}
+impl DynTrait for DynValB {
+    fn get_val(&self) -> i32 {
+        let bytes = self.value.to_ne_bytes();
+        i32::from_ne_bytes([bytes[0], bytes[1], bytes[6], bytes[7]])
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTrait> {
+        Box::new(self.clone())
+    }
+}
+
+impl PartialOrd for dyn DynTrait {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for dyn DynTrait {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get_val().cmp(&other.get_val())
+    }
+}
+
+impl PartialEq for dyn DynTrait {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_val() == other.get_val()
+    }
+}
+
+impl Eq for dyn DynTrait {}
+
+impl Clone for Box<dyn DynTrait> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A [`DynValA`] for even and a [`DynValB`] for odd values. Use `.into()` for an `Rc`.
+pub fn dyn_val_from_i32(val: i32) -> Box<dyn DynTrait> {
+    if val % 2 == 0 {
+        Box::new(DynValA { value: val })
+    } else {
+        // Extends the value into the 64 bit range, while preserving input order.
+        let shifted = (val as i64 - i32::MIN as i64) as u64;
+        Box::new(DynValB {
+            value: shifted * i32::MAX as u64,
+        })
+    }
+}
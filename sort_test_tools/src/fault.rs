@@ -0,0 +1,168 @@
+//! Faults injected into user provided comparison functions, to test that a sort keeps its safety
+//! guarantees when the comparison panics, returns wrong results or is slow.
+//!
+//! A [`FaultInjector`] counts the comparisons it wraps and applies every [`Fault`] whose
+//! [`Trigger`] matches the index of the comparison:
+//!
+//! ```
+//! use sort_test_tools::fault::{Fault, FaultInjector, Trigger};
+//!
+//! let mut injector = FaultInjector::new()
+//!     .inject(Trigger::Probability(0.01), Fault::Reverse)
+//!     .inject(Trigger::At(500), Fault::Panic);
+//!
+//! let mut v = sort_test_tools::patterns::random(1_000);
+//! let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+//!     v.sort_by(injector.wrap(|a: &i32, b: &i32| a.cmp(b)));
+//! }));
+//! assert!(res.is_err() || injector.calls() <= 500);
+//! ```
+//!
+//! The probabilities use a generator seeded from [`patterns::random_init_seed`], a run is
+//! reproduced with `OVERRIDE_SEED` like the patterns, or with [`FaultInjector::with_seed`].
+
+use std::cmp::Ordering;
+use std::panic;
+use std::thread;
+use std::time::Duration;
+
+use crate::patterns;
+use crate::xorshift::XorShift;
+
+/// What happens to a comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fault {
+    /// Panics with an [`InjectedPanic`] payload, before calling the comparison function.
+    Panic,
+    /// Returns the reverse of the result, which makes the order inconsistent.
+    Reverse,
+    /// Returns this result regardless of the elements.
+    Result(Ordering),
+    /// Sleeps before comparing, eg. to widen the window for races with other threads.
+    Slow(Duration),
+}
+
+/// Which comparisons a [`Fault`] applies to, by their index starting at 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    At(u64),
+    /// Every comparison from this index on.
+    From(u64),
+    /// Every n-th comparison, `Every(3)` matches the indices 2, 5, 8, ...
+    Every(u64),
+    /// Each comparison with this probability, between 0 and 1.
+    Probability(f64),
+}
+
+/// The panic payload of [`Fault::Panic`], so that tests can tell it apart from other panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InjectedPanic {
+    /// The index of the comparison that panicked.
+    pub call: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FaultInjector {
+    rules: Vec<(Trigger, Fault)>,
+    calls: u64,
+    injected: u64,
+    rng: XorShift,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaultInjector {
+    /// An injector without faults, seeded from the pattern seed.
+    pub fn new() -> Self {
+        Self::with_seed(patterns::random_init_seed())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rules: Vec::new(),
+            calls: 0,
+            injected: 0,
+            rng: XorShift::new(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15)),
+        }
+    }
+
+    /// Adds a rule, the rules are applied in the order they were added.
+    pub fn inject(mut self, trigger: Trigger, fault: Fault) -> Self {
+        self.rules.push((trigger, fault));
+        self
+    }
+
+    /// An injector that panics in one comparison picked uniformly from the first `calls`, as
+    /// counted by a previous run of the sort with the same input. Panics if `calls` is 0.
+    pub fn panic_in_first(calls: u64) -> Self {
+        assert!(calls > 0);
+
+        let call = patterns::random_uniform(1, 0..calls.min(i32::MAX as u64) as i32)[0] as u64;
+        Self::new().inject(Trigger::At(call), Fault::Panic)
+    }
+
+    /// The number of comparisons so far, including the one that panicked.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// The number of faults applied so far.
+    pub fn injected(&self) -> u64 {
+        self.injected
+    }
+
+    /// Compares `a` and `b` with `compare`, unless a fault replaces the result.
+    pub fn compare<T>(
+        &mut self,
+        a: &T,
+        b: &T,
+        compare: impl FnOnce(&T, &T) -> Ordering,
+    ) -> Ordering {
+        let call = self.calls;
+        self.calls += 1;
+
+        let mut reverse = false;
+        let mut result = None;
+        for &(trigger, fault) in &self.rules {
+            if !matches(trigger, call, &mut self.rng) {
+                continue;
+            }
+
+            self.injected += 1;
+            match fault {
+                Fault::Panic => panic::panic_any(InjectedPanic { call }),
+                Fault::Reverse => reverse = !reverse,
+                Fault::Result(ord) => result = Some(ord),
+                Fault::Slow(duration) => thread::sleep(duration),
+            }
+        }
+
+        let ord = result.unwrap_or_else(|| compare(a, b));
+        if reverse {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+
+    /// Wraps `compare` for `sort_by` and similar functions.
+    pub fn wrap<'a, T>(
+        &'a mut self,
+        mut compare: impl FnMut(&T, &T) -> Ordering + 'a,
+    ) -> impl FnMut(&T, &T) -> Ordering + 'a {
+        move |a, b| self.compare(a, b, &mut compare)
+    }
+}
+
+fn matches(trigger: Trigger, call: u64, rng: &mut XorShift) -> bool {
+    match trigger {
+        Trigger::At(at) => call == at,
+        Trigger::From(from) => call >= from,
+        Trigger::Every(n) => n != 0 && (call + 1).is_multiple_of(n),
+        Trigger::Probability(p) => rng.unit() < p,
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::ffi::c_char;
+use std::marker::PhantomData;
 use std::ptr;
 use std::str;
 
@@ -98,6 +99,38 @@ impl Drop for FFIString {
     }
 }
 
+/// Borrowed `&str` with a defined layout, passed to C and C++ as a (pointer, len) pair.
+///
+/// Can only be created from a `&str`, so as long as the foreign side only moves values around,
+/// every value is valid UTF-8.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FFIStr<'a> {
+    data: *const c_char,
+    len: usize,
+    _marker: PhantomData<&'a str>,
+}
+
+impl<'a> FFIStr<'a> {
+    pub fn new(val: &'a str) -> Self {
+        Self {
+            data: val.as_ptr() as *const c_char,
+            len: val.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        // SAFETY: data and len were taken from a &'a str in `new`.
+        unsafe {
+            str::from_utf8_unchecked(&*ptr::slice_from_raw_parts(
+                self.data as *const u8,
+                self.len,
+            ))
+        }
+    }
+}
+
 // Very large stack value.
 #[repr(C)]
 #[derive(PartialEq, Eq, Debug, Clone)]
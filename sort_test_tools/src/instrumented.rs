@@ -0,0 +1,187 @@
+//! Element types that count what a sort does with them, generated for any wrapped type by
+//! [`instrumented_type!`](crate::instrumented_type).
+//!
+//! ```
+//! sort_test_tools::instrumented_type!(Tracked);
+//!
+//! let mut v = sort_test_tools::patterns::random(1_000)
+//!     .into_iter()
+//!     .map(Tracked::new)
+//!     .collect::<Vec<_>>();
+//!
+//! Tracked::<i32>::observe_all(&v);
+//! Tracked::<i32>::reset_counts();
+//! v.sort();
+//! Tracked::observe_all(&v);
+//!
+//! let counts = Tracked::<i32>::counts();
+//! assert_eq!(counts.comparisons, Tracked::total_comp_count(&v));
+//! assert_eq!(counts.clones + counts.drops, 0);
+//! ```
+//!
+//! Clones, comparisons through `Ord` and drops are counted exactly. Moves are not visible to the
+//! moved value, they are detected by pin tracking instead: every element remembers the address
+//! at which it was last observed, and counts a move whenever it is observed at another one. An
+//! element is observed in every comparison, when it is dropped and by `observe_all`. Moves between
+//! two observations count once, and an element that is moved and moved back counts none, so the
+//! move count is a lower bound. It is exact for sorts that compare every element after each move,
+//! and is a good relative measure for all others.
+//!
+//! The counts of a generated type are thread local, the tests of a sort can run in parallel.
+//! Every macro invocation has its own counts, which all instantiations of the generated type share:
+//! `Tracked<i32>` and `Tracked<u64>` count into the same [`Counts`]. Define one type per purpose to
+//! keep them apart.
+
+/// The counts of one generated type since the last reset, summed over all its instantiations, see
+/// [`crate::instrumented_type`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub clones: u64,
+    /// Changes of address between two observations of an element, see the module docs.
+    pub moves: u64,
+    /// Calls of `Ord::cmp` or `PartialOrd::partial_cmp`, equality is not counted.
+    pub comparisons: u64,
+    pub drops: u64,
+}
+
+/// Defines `$name<T>`, a wrapper of any element type `T` with the order and equality of `T`, that
+/// counts its clones, moves, comparisons and drops, see [`crate::instrumented`].
+///
+/// Every element additionally counts the comparisons it took part in, in a `Cell`. A sort that
+/// compares a copy of an element and then moves the stale copy back loses these counts, which is
+/// what the observability tests check.
+#[macro_export]
+macro_rules! instrumented_type {
+    ($(#[$attr:meta])* $vis:vis $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        $vis struct $name<T> {
+            val: T,
+            comp_count: ::std::cell::Cell<u32>,
+            // The address at which the element was last observed, 0 if it never was.
+            addr: ::std::cell::Cell<usize>,
+        }
+
+        #[allow(dead_code)]
+        impl<T> $name<T> {
+            pub fn new(val: T) -> Self {
+                Self {
+                    val,
+                    comp_count: ::std::cell::Cell::new(0),
+                    addr: ::std::cell::Cell::new(0),
+                }
+            }
+
+            pub fn value(&self) -> &T {
+                &self.val
+            }
+
+            /// Unwraps the value, this is not counted as a drop.
+            pub fn into_inner(self) -> T {
+                let this = ::std::mem::ManuallyDrop::new(self);
+                // SAFETY: `this` is not used or dropped afterwards, `val` is read exactly once.
+                unsafe { ::std::ptr::read(&this.val) }
+            }
+
+            /// Number of comparisons this element took part in.
+            pub fn comp_count(&self) -> u32 {
+                self.comp_count.get()
+            }
+
+            /// Total number of comparisons in `v`. Every comparison is counted by both elements.
+            pub fn total_comp_count(v: &[Self]) -> u64 {
+                v.iter().map(|elem| elem.comp_count() as u64).sum::<u64>() / 2
+            }
+
+            /// The counts of this type on the current thread since the last reset, for every `T`.
+            pub fn counts() -> $crate::instrumented::Counts {
+                Self::with_counts(|counts| counts.get())
+            }
+
+            pub fn reset_counts() {
+                Self::with_counts(|counts| counts.set($crate::instrumented::Counts::default()));
+            }
+
+            /// Observes every element of `v` at its current address. Call it before the sort, so
+            /// that the first move of every element is counted, and after it, for the last one.
+            pub fn observe_all(v: &[Self]) {
+                for elem in v {
+                    elem.observe();
+                }
+            }
+
+            fn observe(&self) {
+                let addr = self as *const Self as usize;
+                let prev = self.addr.replace(addr);
+                if prev != 0 && prev != addr {
+                    Self::count(|counts| counts.moves += 1);
+                }
+            }
+
+            fn count(f: impl FnOnce(&mut $crate::instrumented::Counts)) {
+                Self::with_counts(|counts| {
+                    let mut val = counts.get();
+                    f(&mut val);
+                    counts.set(val);
+                });
+            }
+
+            fn with_counts<R>(
+                f: impl FnOnce(&::std::cell::Cell<$crate::instrumented::Counts>) -> R,
+            ) -> R {
+                // A static in a generic function exists once, not once per `T`.
+                ::std::thread_local! {
+                    static COUNTS: ::std::cell::Cell<$crate::instrumented::Counts> =
+                        ::std::cell::Cell::new($crate::instrumented::Counts::default());
+                }
+
+                COUNTS.with(f)
+            }
+        }
+
+        // The clone is a new element, it is first observed wherever it ends up.
+        impl<T: Clone> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                Self::count(|counts| counts.clones += 1);
+                Self {
+                    val: self.val.clone(),
+                    comp_count: self.comp_count.clone(),
+                    addr: ::std::cell::Cell::new(0),
+                }
+            }
+        }
+
+        impl<T> Drop for $name<T> {
+            fn drop(&mut self) {
+                self.observe();
+                Self::count(|counts| counts.drops += 1);
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $name<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.val == other.val
+            }
+        }
+
+        impl<T: Eq> Eq for $name<T> {}
+
+        impl<T: Ord> PartialOrd for $name<T> {
+            fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T: Ord> Ord for $name<T> {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                self.observe();
+                other.observe();
+                self.comp_count.set(self.comp_count.get() + 1);
+                other.comp_count.set(other.comp_count.get() + 1);
+                Self::count(|counts| counts.comparisons += 1);
+
+                self.val.cmp(&other.val)
+            }
+        }
+    };
+}
@@ -12,6 +12,14 @@ pub trait Sort {
         F: FnMut(&T, &T) -> std::cmp::Ordering;
 }
 
+pub mod dyn_types;
+pub mod fault;
 pub mod ffi_types;
+pub mod instrumented;
 pub mod patterns;
+pub mod reference_sort;
+pub mod test_sizes;
 pub mod tests;
+pub mod trace;
+pub mod verify;
+pub mod xorshift;
@@ -1,11 +1,18 @@
+use std::cell::Cell;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::env;
+use std::fs::File;
 use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use rand::prelude::*;
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
 
 use zipf::ZipfDistribution;
 
@@ -31,7 +38,7 @@ where
     static CACHE: KeyedVecCache = KeyedVecCache::new();
 
     CACHE.copy_cached_or_gen(len, range, |len, seed, range| {
-        let mut rng: StdRng = rand::SeedableRng::seed_from_u64(seed);
+        let mut rng = pattern_rng(seed);
 
         // Abstracting over ranges in Rust :(
         let dist: rand::distributions::Uniform<i32> = range.into();
@@ -45,7 +52,7 @@ pub fn random_zipf(len: usize, exponent: f64) -> Vec<i32> {
     static CACHE: KeyedVecCache = KeyedVecCache::new();
 
     CACHE.copy_cached_or_gen(len, exponent.to_bits(), |len, seed, exponent_bits| {
-        let mut rng: StdRng = rand::SeedableRng::seed_from_u64(seed);
+        let mut rng = pattern_rng(seed);
 
         // Abstracting over ranges in Rust :(
         let dist = ZipfDistribution::new(len, f64::from_bits(exponent_bits)).unwrap();
@@ -247,6 +254,215 @@ pub fn pipe_organ(len: usize) -> Vec<i32> {
     })
 }
 
+pub fn few_keys_blocks(len: usize, key_count: usize) -> Vec<i32> {
+    assert!(key_count > 0, "few_keys_blocks needs at least one key");
+
+    // ::::..::::....::
+    // ::::::::::::::::
+
+    // Runs of equal keys with random lengths, each run picks one of `key_count` keys at random.
+
+    static CACHE: KeyedVecCache = KeyedVecCache::new();
+
+    CACHE.copy_cached_or_gen(len, key_count, |len, seed, key_count| {
+        let mut rng = pattern_rng(seed);
+        let max_run_len = (len / 16).max(1);
+
+        let mut vals = Vec::with_capacity(len);
+        while vals.len() < len {
+            let key = rng.gen_range(0..key_count) as i32;
+            let run_len = rng.gen_range(1..=max_run_len).min(len - vals.len());
+            vals.extend(std::iter::repeat_n(key, run_len));
+        }
+
+        vals
+    })
+}
+
+pub fn few_keys_alternating(len: usize, key_count: usize) -> Vec<i32> {
+    assert!(key_count > 0, "few_keys_alternating needs at least one key");
+
+    // .:.:.:.:
+    // ::::::::
+
+    (0..len).map(|i| (i % key_count) as i32).collect()
+}
+
+pub fn few_keys_single_intruder(len: usize, key_count: usize) -> Vec<i32> {
+    assert!(
+        key_count > 0,
+        "few_keys_single_intruder needs at least one key"
+    );
+
+    // ...:....
+    // ::::::::
+
+    // All elements share the largest key, except one element per other key at a distinct random
+    // position. Each intruder has to move past a long run of equal elements. If there are more
+    // keys than elements, the smallest keys that fit are used.
+
+    let mut vals = vec![(key_count - 1) as i32; len];
+
+    let positions = sample(len, (key_count - 1).min(len));
+    for (key, pos) in positions.into_iter().enumerate() {
+        vals[pos] = key as i32;
+    }
+
+    vals
+}
+
+/// Shuffles `v` uniformly at random, with the seed and generator of the random patterns.
+///
+/// Like the patterns, every call with the same len gives the same permutation for a fixed seed, and
+/// a new one with [`use_random_seed_each_time`]. Use it instead of shuffling with a generator of
+/// its own, which would not follow `OVERRIDE_SEED`.
+pub fn shuffle<T>(v: &mut [T]) {
+    let (_, seed) = get_or_init_seed_type_and_value();
+    let mut rng = pattern_rng(derived_seed(seed, SHUFFLE_STREAM));
+
+    v.shuffle(&mut rng);
+}
+
+/// `k` distinct indices of `0..n`, uniformly at random and in random order, with the seed and
+/// generator of the random patterns, see [`shuffle`].
+///
+/// Panics if `k > n`.
+pub fn sample(n: usize, k: usize) -> Vec<usize> {
+    assert!(k <= n, "Can't sample {k} distinct indices from 0..{n}");
+
+    let (_, seed) = get_or_init_seed_type_and_value();
+    let mut rng = pattern_rng(derived_seed(seed, SAMPLE_STREAM));
+
+    rand::seq::index::sample(&mut rng, n, k).into_vec()
+}
+
+/// The values of `pattern` for `len` generated with `seed`, independent of the seed of the process.
+///
+/// Only the calling thread uses `seed`, and only until `pattern` returns.
+pub fn with_seed(pattern: fn(usize) -> Vec<i32>, len: usize, seed: u64) -> Vec<i32> {
+    struct RestoreOnDrop(Option<u64>);
+
+    impl Drop for RestoreOnDrop {
+        fn drop(&mut self) {
+            SCOPED_SEED.set(self.0);
+        }
+    }
+
+    let _restore = RestoreOnDrop(SCOPED_SEED.replace(Some(seed)));
+    pattern(len)
+}
+
+/// An input written by [`export`], with the parameters it was generated from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportedPattern {
+    pub name: String,
+    pub seed: u64,
+    /// Name of the generator the values were generated with, see [`PatternRng::name`].
+    pub rng: String,
+    pub values: Vec<i32>,
+}
+
+const EXPORT_HEADER: &str = "# sort-research pattern v1";
+
+/// Generates `pattern` for `len` with `seed`, see [`with_seed`], and writes the values to `path`.
+///
+/// The values depend on the random generator and how the pattern draws from it, which may change
+/// between versions of rand and of this crate. The file holds the values themselves, so [`import`]
+/// gives the exact same input on any machine and version. It is a text file: a header line, the
+/// `pattern`, `len`, `seed` and `rng` as `<key>: <value>` lines and one value per line after that.
+pub fn export(
+    path: &Path,
+    pattern: (&str, fn(usize) -> Vec<i32>),
+    len: usize,
+    seed: u64,
+) -> io::Result<ExportedPattern> {
+    let (name, pattern_fn) = pattern;
+    let exported = ExportedPattern {
+        name: name.to_string(),
+        seed,
+        rng: pattern_rng_kind().name().to_string(),
+        values: with_seed(pattern_fn, len, seed),
+    };
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "{EXPORT_HEADER}")?;
+    writeln!(writer, "pattern: {}", exported.name)?;
+    writeln!(writer, "len: {}", exported.values.len())?;
+    writeln!(writer, "seed: {}", exported.seed)?;
+    writeln!(writer, "rng: {}", exported.rng)?;
+    for val in &exported.values {
+        writeln!(writer, "{val}")?;
+    }
+    writer.flush()?;
+
+    Ok(exported)
+}
+
+/// Reads a file written by [`export`].
+pub fn import(path: &Path) -> io::Result<ExportedPattern> {
+    let invalid = |line_idx: usize, expected: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{}: expected {expected}", path.display(), line_idx + 1),
+        )
+    };
+
+    const HEADER_LINES: usize = 5;
+
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let header = lines
+        .by_ref()
+        .take(HEADER_LINES)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    if header.first().map(String::as_str) != Some(EXPORT_HEADER) {
+        return Err(invalid(0, EXPORT_HEADER));
+    }
+
+    let field = |line_idx: usize, key: &str| {
+        header
+            .get(line_idx)
+            .and_then(|line| line.strip_prefix(key)?.strip_prefix(": "))
+            .ok_or_else(|| invalid(line_idx, &format!("'{key}: <value>'")))
+    };
+
+    let name = field(1, "pattern")?.to_string();
+    let len = field(2, "len")?
+        .parse::<usize>()
+        .map_err(|_| invalid(2, "a number for len"))?;
+    let seed = field(3, "seed")?
+        .parse::<u64>()
+        .map_err(|_| invalid(3, "a number for seed"))?;
+    let rng = field(4, "rng")?.to_string();
+
+    let values = lines
+        .enumerate()
+        .map(|(idx, line)| {
+            line?
+                .parse::<i32>()
+                .map_err(|_| invalid(HEADER_LINES + idx, "an i32 value"))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    if values.len() != len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{}: expected {len} values, found {}",
+                path.display(),
+                values.len()
+            ),
+        ));
+    }
+
+    Ok(ExportedPattern {
+        name,
+        seed,
+        rng,
+        values,
+    })
+}
+
 /// Overwrites the default behavior so that each call to a random derived pattern yields new random
 /// values.
 ///
@@ -261,38 +477,312 @@ pub fn use_random_seed_each_time() {
     *SEED_TYPE_AND_VALUE.lock().unwrap() = Some((SeedType::RandomEachTime, 0));
 }
 
+/// Length of the seed cycle of [`use_paired_seeds`].
+pub const PAIRED_SEED_COUNT: u64 = 64;
+
+/// Makes the random derived patterns cycle through [`PAIRED_SEED_COUNT`] seeds derived from the
+/// process seed, [`restart_paired_seeds`] goes back to the first one.
+///
+/// Every seed drawn while the cycle runs advances it, including the ones of [`random_init_seed`]
+/// and the shuffling helpers. Benchmarks therefore generate their inputs up front with
+/// [`PairedInputs`], so for a given pattern, len and seed every sort sees the same inputs, in the
+/// same order, while consecutive iterations still get different values. Unlike
+/// [`use_random_seed_each_time`] this respects `OVERRIDE_SEED`, which makes the inputs reproducible
+/// across runs.
+pub fn use_paired_seeds() {
+    let (seed_type, seed) = get_or_init_seed_type_and_value();
+    if seed_type == SeedType::RandomEachTime {
+        panic!("Using use_paired_seeds conflicts with use_random_seed_each_time.");
+    }
+
+    PAIRED_SEED_INDEX.store(0, Ordering::Relaxed);
+    *SEED_TYPE_AND_VALUE.lock().unwrap() = Some((SeedType::Paired, seed));
+}
+
+/// Starts the seed cycle of [`use_paired_seeds`] over.
+pub fn restart_paired_seeds() {
+    PAIRED_SEED_INDEX.store(0, Ordering::Relaxed);
+}
+
+/// The inputs of one pattern and len, generated once from the start of the seed cycle of
+/// [`use_paired_seeds`] and handed out in a fixed order.
+///
+/// Generating the inputs while measuring ties them to everything else that draws a seed in
+/// between, and criterion runs a different number of warmup iterations for every sort. Iterating
+/// over a fixed set instead, from the start with [`PairedInputs::restart`], gives every sort the
+/// same sequence.
+pub struct PairedInputs {
+    inputs: Vec<Vec<i32>>,
+    next: Cell<usize>,
+}
+
+impl PairedInputs {
+    /// Upper bound of the total elements of all inputs, large lens get fewer inputs.
+    const MAX_TOTAL_LEN: usize = 1 << 24;
+
+    pub fn new(len: usize, pattern_provider: impl Fn(usize) -> Vec<i32>) -> Self {
+        // A power of two, so [`PAIRED_SEED_COUNT`] iterations use every input equally often.
+        let count = (Self::MAX_TOTAL_LEN / len.max(1)).clamp(1, PAIRED_SEED_COUNT as usize);
+        let count = 1 << count.ilog2();
+
+        restart_paired_seeds();
+        let inputs = (0..count).map(|_| pattern_provider(len)).collect();
+
+        Self {
+            inputs,
+            next: Cell::new(0),
+        }
+    }
+
+    /// Number of distinct inputs, they repeat after that.
+    pub fn count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Goes back to the first input.
+    pub fn restart(&self) {
+        self.next.set(0);
+    }
+
+    /// A copy of the next input. Takes a len only to fit where a pattern provider is expected, the
+    /// inputs already have theirs.
+    pub fn next_input(&self, _len: usize) -> Vec<i32> {
+        let index = self.next.get();
+        self.next.set((index + 1) % self.inputs.len());
+
+        self.inputs[index].clone()
+    }
+}
+
 pub fn random_init_seed() -> u64 {
     get_or_init_seed_type_and_value().1
 }
 
+/// Random number generators for the random patterns, selected with the `PATTERN_RNG` environment
+/// variable. An effect that only shows up with one of them is caused by the structure of its
+/// output, not by the pattern.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PatternRng {
+    /// `PATTERN_RNG=std`, the default. `StdRng` of rand, currently ChaCha12.
+    Std,
+    /// `PATTERN_RNG=chacha20`.
+    ChaCha20,
+    /// `PATTERN_RNG=wyrand`, a 64-bit multiply based generator with a very different structure.
+    WyRand,
+}
+
+impl PatternRng {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Std => "std",
+            Self::ChaCha20 => "chacha20",
+            Self::WyRand => "wyrand",
+        }
+    }
+}
+
+pub fn pattern_rng_kind() -> PatternRng {
+    static KIND: OnceLock<PatternRng> = OnceLock::new();
+
+    *KIND.get_or_init(|| match env::var("PATTERN_RNG").as_deref() {
+        Err(_) | Ok("std") => PatternRng::Std,
+        Ok("chacha20") => PatternRng::ChaCha20,
+        Ok("wyrand") => PatternRng::WyRand,
+        Ok(other) => panic!("Unknown PATTERN_RNG '{other}', expected one of std, chacha20, wyrand"),
+    })
+}
+
+/// Describes the seed and generator of the random patterns, to be included in test and benchmark
+/// output.
+///
+/// The seed is picked once per process, `OVERRIDE_SEED=<u64>` sets it and
+/// `RANDOM_SEED_FROM_OS=1` reads it directly from the OS entropy source instead of the thread
+/// local generator of rand.
+pub fn random_source_info() -> String {
+    let (seed_type, seed) = *SEED_TYPE_AND_VALUE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(init_seed_type_and_value);
+    let seed = match seed_type {
+        SeedType::RandomOncePerProcess => format!("{seed}, random once per process"),
+        SeedType::OsOncePerProcess => format!("{seed}, from the OS once per process"),
+        SeedType::ExternalOverride => format!("{seed}, from OVERRIDE_SEED"),
+        SeedType::Paired => {
+            let source = if env::var("OVERRIDE_SEED").is_ok() {
+                "from OVERRIDE_SEED"
+            } else if from_os_requested() {
+                "from the OS"
+            } else {
+                "random"
+            };
+            format!("{seed}, {source} and paired per benchmark")
+        }
+        SeedType::RandomEachTime if from_os_requested() => "from the OS each time".into(),
+        SeedType::RandomEachTime => "random each time".into(),
+        SeedType::Scoped => unreachable!("Scoped seeds are only set per thread"),
+    };
+
+    format!("seed: {seed}, rng: {}", pattern_rng_kind().name())
+}
+
 // --- Private ---
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum SeedType {
     RandomOncePerProcess,
+    OsOncePerProcess,
     ExternalOverride,
     RandomEachTime,
+    /// The seeds are derived from the process seed and [`PAIRED_SEED_INDEX`].
+    Paired,
+    /// Set for the calling thread by [`with_seed`].
+    Scoped,
 }
 
 static SEED_TYPE_AND_VALUE: Mutex<Option<(SeedType, u64)>> = Mutex::new(None);
 
+static PAIRED_SEED_INDEX: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static SCOPED_SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
 fn get_or_init_seed_type_and_value() -> (SeedType, u64) {
-    let (seed_type, seed_val) = *SEED_TYPE_AND_VALUE.lock().unwrap().get_or_insert_with(|| {
-        if let Some(override_seed) = env::var("OVERRIDE_SEED")
-            .ok()
-            .map(|seed| u64::from_str(&seed).unwrap())
-        {
-            (SeedType::ExternalOverride, override_seed)
-        } else {
-            let per_process_seed = thread_rng().gen();
-            (SeedType::RandomOncePerProcess, per_process_seed)
+    if let Some(seed) = SCOPED_SEED.get() {
+        return (SeedType::Scoped, seed);
+    }
+
+    let (seed_type, seed_val) = *SEED_TYPE_AND_VALUE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(init_seed_type_and_value);
+
+    match seed_type {
+        SeedType::RandomEachTime => {
+            let seed = if from_os_requested() {
+                OsRng.gen()
+            } else {
+                thread_rng().gen()
+            };
+            (SeedType::RandomEachTime, seed)
         }
-    });
+        SeedType::Paired => {
+            let index = PAIRED_SEED_INDEX.fetch_add(1, Ordering::Relaxed) % PAIRED_SEED_COUNT;
+            (SeedType::Paired, paired_seed(seed_val, index))
+        }
+        _ => (seed_type, seed_val),
+    }
+}
 
-    if seed_type == SeedType::RandomEachTime {
-        (SeedType::RandomEachTime, thread_rng().gen())
-    } else {
-        (seed_type, seed_val)
+fn init_seed_type_and_value() -> (SeedType, u64) {
+    let override_seed = env::var("OVERRIDE_SEED")
+        .ok()
+        .map(|seed| u64::from_str(&seed).unwrap());
+
+    match (override_seed, from_os_requested()) {
+        (Some(_), true) => {
+            panic!("OVERRIDE_SEED and RANDOM_SEED_FROM_OS are mutually exclusive.")
+        }
+        (Some(override_seed), false) => (SeedType::ExternalOverride, override_seed),
+        (None, true) => (SeedType::OsOncePerProcess, OsRng.gen()),
+        (None, false) => (SeedType::RandomOncePerProcess, thread_rng().gen()),
+    }
+}
+
+/// The `index`-th seed of the paired cycle of `base`, mixed with splitmix64 so that neighboring
+/// indices give unrelated seeds.
+fn paired_seed(base: u64, index: u64) -> u64 {
+    let mut z = base.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Shuffle and sample draw from streams derived from the seed, so that their choices are
+// independent of the values of the patterns generated from the same seed.
+const SHUFFLE_STREAM: u64 = 0;
+const SAMPLE_STREAM: u64 = 1;
+
+fn derived_seed(seed: u64, stream: u64) -> u64 {
+    // Past the indices of the paired cycle, which derives seeds the same way.
+    paired_seed(seed, PAIRED_SEED_COUNT + stream)
+}
+
+fn from_os_requested() -> bool {
+    static FROM_OS: OnceLock<bool> = OnceLock::new();
+
+    *FROM_OS.get_or_init(|| env::var("RANDOM_SEED_FROM_OS").is_ok_and(|val| val != "0"))
+}
+
+enum PatternRngState {
+    Std(StdRng),
+    ChaCha20(ChaCha20Rng),
+    WyRand(WyRand),
+}
+
+fn pattern_rng(seed: u64) -> PatternRngState {
+    match pattern_rng_kind() {
+        PatternRng::Std => PatternRngState::Std(StdRng::seed_from_u64(seed)),
+        PatternRng::ChaCha20 => PatternRngState::ChaCha20(ChaCha20Rng::seed_from_u64(seed)),
+        PatternRng::WyRand => PatternRngState::WyRand(WyRand(seed)),
+    }
+}
+
+impl RngCore for PatternRngState {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Std(rng) => rng.next_u32(),
+            Self::ChaCha20(rng) => rng.next_u32(),
+            Self::WyRand(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Std(rng) => rng.next_u64(),
+            Self::ChaCha20(rng) => rng.next_u64(),
+            Self::WyRand(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Std(rng) => rng.fill_bytes(dest),
+            Self::ChaCha20(rng) => rng.fill_bytes(dest),
+            Self::WyRand(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// https://github.com/wangyi-fudan/wyhash
+struct WyRand(u64);
+
+impl RngCore for WyRand {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0xa076_1d64_78bd_642f);
+        let t = (self.0 as u128).wrapping_mul((self.0 ^ 0xe703_7ed1_a0b4_28db) as u128);
+        ((t >> 64) as u64) ^ (t as u64)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
     }
 }
 
@@ -312,7 +802,10 @@ impl VecCache {
     fn copy_cached_or_gen(&self, len: usize, gen_fn: fn(usize, u64) -> Vec<i32>) -> Vec<i32> {
         let (seed_type, seed_value) = get_or_init_seed_type_and_value();
 
-        if seed_type == SeedType::RandomEachTime {
+        if matches!(
+            seed_type,
+            SeedType::RandomEachTime | SeedType::Paired | SeedType::Scoped
+        ) {
             return gen_fn(len, seed_value);
         }
 
@@ -384,7 +877,10 @@ impl KeyedVecCache {
         let (seed_type, seed_value) = get_or_init_seed_type_and_value();
 
         // Do this early to avoid penalizing the benchmark use-case.
-        if seed_type == SeedType::RandomEachTime {
+        if matches!(
+            seed_type,
+            SeedType::RandomEachTime | SeedType::Paired | SeedType::Scoped
+        ) {
             return gen_fn(len, seed_value, key);
         }
 
@@ -436,7 +932,7 @@ fn random_vec(len: usize) -> Vec<i32> {
     static CACHE: VecCache = VecCache::new();
 
     CACHE.copy_cached_or_gen(len, |len, seed| {
-        let mut rng: StdRng = rand::SeedableRng::seed_from_u64(seed);
+        let mut rng = pattern_rng(seed);
         (0..len).map(|_| rng.gen::<i32>()).collect()
     })
 }
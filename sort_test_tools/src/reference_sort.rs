@@ -0,0 +1,69 @@
+//! A stable merge sort written to be obviously correct, not fast, used by the oracles in
+//! [`crate::verify`] and the tests instead of the std sorts.
+//!
+//! With the std sorts as oracle, a bug in std that also shows up in the sort under test would pass
+//! every test, and the std sorts themselves could not be tested at all. This sort has no unsafe
+//! code, no special cases for short or presorted inputs and no state between the calls of the
+//! comparison function, every step can be checked by reading it. It moves the elements by value
+//! between vecs, so it needs neither `Clone` nor `Copy` for sorting owned elements.
+//!
+//! It takes *O*(*n* \* log(*n*)) comparisons and time, and allocates about *n* vecs.
+
+use std::cmp::Ordering;
+
+/// Sorts `v` stably, see the module docs.
+pub fn sort<T: Ord + Clone>(v: &mut [T]) {
+    sort_by(v, T::cmp);
+}
+
+/// Sorts `v` stably by `compare`, see the module docs.
+pub fn sort_by<T: Clone>(v: &mut [T], compare: impl FnMut(&T, &T) -> Ordering) {
+    let sorted = sorted_by(v.to_vec(), compare);
+    v.clone_from_slice(&sorted);
+}
+
+/// Sorts `v` stably by the key `f` returns, see the module docs.
+pub fn sort_by_key<T: Clone, K: Ord>(v: &mut [T], mut f: impl FnMut(&T) -> K) {
+    sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// Returns the elements of `v` sorted stably by `compare`, see the module docs.
+pub fn sorted_by<T>(v: Vec<T>, mut compare: impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+    merge_sort(v, &mut compare)
+}
+
+fn merge_sort<T>(mut v: Vec<T>, compare: &mut impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+    if v.len() <= 1 {
+        return v;
+    }
+
+    let right = v.split_off(v.len() / 2);
+    let left = merge_sort(v, compare);
+    let right = merge_sort(right, compare);
+
+    merge(left, right, compare)
+}
+
+/// Merges the sorted `left` and `right`, equal elements of `left` go first.
+fn merge<T>(left: Vec<T>, right: Vec<T>, compare: &mut impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // Only a strictly smaller right element goes before the left one, that keeps equal
+        // elements in their original order.
+        let next = if compare(r, l) == Ordering::Less {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+
+    // At most one of them has elements left.
+    merged.extend(left);
+    merged.extend(right);
+
+    merged
+}
@@ -0,0 +1,168 @@
+//! The input lengths the tests and the fuzzers use, picked by size profile instead of editing the
+//! test source:
+//!
+//! - `TEST_SIZE_PROFILE=quick` up to 10k elements.
+//! - `TEST_SIZE_PROFILE=default` up to 1.1m elements.
+//! - `TEST_SIZE_PROFILE=thorough` adds the sizes around powers of two and up to 10m elements.
+//! - `TEST_SIZE_PROFILE=miri` up to 400 elements, for miri and emulated targets.
+//!
+//! `TEST_SIZES=10,100,1_000` overrides the profile with explicit lengths. Without either variable
+//! the profile follows the features, `miri` under miri or with `reduced_test_sizes`, `default`
+//! with `large_test_sizes` and `quick` otherwise. Under miri the variables are only visible with
+//! `-Zmiri-env-forward`.
+
+use std::env;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeProfile {
+    Quick,
+    Default,
+    Thorough,
+    Miri,
+}
+
+impl SizeProfile {
+    /// Sorted lengths of the profile.
+    pub fn lengths(self) -> &'static [usize] {
+        match self {
+            Self::Quick => &[
+                2, 3, 4, 5, 6, 7, 8, 9, 10, 15, 16, 17, 20, 24, 30, 32, 33, 35, 50, 100, 200, 500,
+                1_000, 2_048, 5_000, 10_000,
+            ],
+            Self::Default => &[
+                2, 3, 4, 5, 6, 7, 8, 9, 10, 15, 16, 17, 20, 24, 30, 32, 33, 35, 50, 100, 200, 500,
+                1_000, 2_048, 5_000, 10_000, 100_000, 1_100_000,
+            ],
+            Self::Thorough => &[
+                2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+                25, 30, 31, 32, 33, 35, 40, 48, 50, 63, 64, 65, 100, 127, 128, 129, 200, 255, 256,
+                257, 500, 511, 512, 513, 1_000, 1_023, 1_024, 1_025, 2_048, 4_095, 4_097, 5_000,
+                10_000, 65_537, 100_000, 1_100_000, 4_194_305, 10_000_000,
+            ],
+            Self::Miri => &[2, 3, 4, 7, 10, 15, 20, 24, 33, 50, 100, 280, 400],
+        }
+    }
+
+    /// Sorted lengths of the profile for tests that need long inputs to reach rare code paths,
+    /// such as the stability stress test.
+    pub fn stress_lengths(self) -> &'static [usize] {
+        match self {
+            Self::Quick => &[2_048, 100_000],
+            Self::Default => &[2_048, 100_000, 4_000_000],
+            Self::Thorough => &[2_048, 100_000, 4_000_000, 10_000_000],
+            Self::Miri => &[2_048],
+        }
+    }
+
+    /// The profile selected by the features, used if `TEST_SIZE_PROFILE` is not set.
+    pub const fn from_features() -> Self {
+        if cfg!(any(miri, feature = "reduced_test_sizes")) {
+            Self::Miri
+        } else if cfg!(feature = "large_test_sizes") {
+            Self::Default
+        } else {
+            Self::Quick
+        }
+    }
+}
+
+impl FromStr for SizeProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quick" => Ok(Self::Quick),
+            "default" => Ok(Self::Default),
+            "thorough" => Ok(Self::Thorough),
+            "miri" => Ok(Self::Miri),
+            _ => Err(format!(
+                "Unknown size profile '{s}', expected quick, default, thorough or miri"
+            )),
+        }
+    }
+}
+
+/// Parses comma separated lengths like `10,100,1_000`, returned sorted and without duplicates.
+///
+/// Every len has to be at least 2, like in the profiles. A sort makes no comparison below that,
+/// and the panic tests rely on the comparison function being called.
+pub fn parse_lengths(s: &str) -> Result<Vec<usize>, String> {
+    let mut lengths = s
+        .split(',')
+        .map(|len| {
+            let parsed = len
+                .trim()
+                .replace('_', "")
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid len '{len}' in '{s}'"))?;
+
+            if parsed < 2 {
+                return Err(format!(
+                    "Len {parsed} in '{s}' is below 2, a sort makes no comparison for it"
+                ));
+            }
+
+            Ok(parsed)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    lengths.sort_unstable();
+    lengths.dedup();
+    Ok(lengths)
+}
+
+/// The profile from `TEST_SIZE_PROFILE`, or `None` if it isn't set. Panics if it is invalid.
+pub fn profile_from_env() -> Option<SizeProfile> {
+    let profile = env::var("TEST_SIZE_PROFILE").ok()?;
+
+    match profile.parse() {
+        Ok(profile) => Some(profile),
+        Err(err) => panic!("TEST_SIZE_PROFILE: {err}"),
+    }
+}
+
+/// The lengths from `TEST_SIZES` or `TEST_SIZE_PROFILE`, or `None` if neither is set. Panics if
+/// the value is invalid. For tools that have their own default lengths, such as the cli.
+pub fn from_env() -> Option<&'static [usize]> {
+    static FROM_ENV: OnceLock<Option<Vec<usize>>> = OnceLock::new();
+
+    FROM_ENV
+        .get_or_init(|| match env::var("TEST_SIZES") {
+            Ok(sizes) => {
+                Some(parse_lengths(&sizes).unwrap_or_else(|err| panic!("TEST_SIZES: {err}")))
+            }
+            Err(_) => profile_from_env().map(|profile| profile.lengths().to_vec()),
+        })
+        .as_deref()
+}
+
+/// The sorted lengths the tests use.
+pub fn lengths() -> &'static [usize] {
+    from_env().unwrap_or(SizeProfile::from_features().lengths())
+}
+
+/// The stress test lengths of the profile, or the `TEST_SIZES` if set.
+pub fn stress_lengths() -> &'static [usize] {
+    if env::var("TEST_SIZES").is_ok() {
+        return lengths();
+    }
+
+    profile_from_env()
+        .unwrap_or(SizeProfile::from_features())
+        .stress_lengths()
+}
+
+/// [`lengths`] without the `n` largest ones, which some tests skip because they are too slow for
+/// the tested type. Keeps at least the smallest length.
+pub fn without_largest(n: usize) -> &'static [usize] {
+    let lengths = lengths();
+    &lengths[..lengths.len().saturating_sub(n).max(1)]
+}
+
+/// Whether the selected sizes are the ones for miri and emulated targets, tests use this to also
+/// limit the number of rounds and comparison functions.
+pub fn is_reduced() -> bool {
+    profile_from_env().unwrap_or(SizeProfile::from_features()) == SizeProfile::Miri
+}
@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::env;
 use std::fmt::Debug;
@@ -6,30 +6,25 @@ use std::fs;
 use std::io::{self, Write};
 use std::mem;
 use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Mutex;
 use std::sync::OnceLock;
 
+use crate::dyn_types::{dyn_val_from_i32, DynTrait};
+use crate::fault::FaultInjector;
 use crate::ffi_types::{FFIOneKibiByte, FFIString, F128};
 use crate::patterns;
+use crate::reference_sort;
+use crate::test_sizes;
+use crate::trace::{self, ComparisonTrace};
+use crate::verify;
 use crate::Sort;
 
-#[cfg(miri)]
-const TEST_LENGTHS: &[usize] = &[2, 3, 4, 7, 10, 15, 20, 24, 33, 50, 100, 280, 400];
-
-#[cfg(feature = "large_test_sizes")]
-#[cfg(not(miri))]
-const TEST_LENGTHS: &[usize] = &[
-    2, 3, 4, 5, 6, 7, 8, 9, 10, 15, 16, 17, 20, 24, 30, 32, 33, 35, 50, 100, 200, 500, 1_000,
-    2_048, 5_000, 10_000, 100_000, 1_100_000,
-];
-
-#[cfg(not(feature = "large_test_sizes"))]
-#[cfg(not(miri))]
-const TEST_LENGTHS: &[usize] = &[
-    2, 3, 4, 5, 6, 7, 8, 9, 10, 15, 16, 17, 20, 24, 30, 32, 33, 35, 50, 100, 200, 500, 1_000,
-    2_048, 5_000, 10_000,
-];
+// The panic tests need to observe the slice after the sort panicked. With panic=abort the first
+// panic ends the test process, so they are skipped. Ord violations may or may not panic, depending
+// on the sort.
+const CAN_CATCH_PANIC: bool = cfg!(panic = "unwind");
 
 fn write_info_to_stdout<S: Sort>() -> u64 {
     static SEED_WRITTEN: Mutex<bool> = Mutex::new(false);
@@ -39,7 +34,14 @@ fn write_info_to_stdout<S: Sort>() -> u64 {
     if !*seed_writer {
         // Always write the seed before doing anything to ensure reproducibility of crashes.
         io::stdout()
-            .write_all(format!("\nSeed: {seed}\nTesting: {}\n\n", <S as Sort>::name()).as_bytes())
+            .write_all(
+                format!(
+                    "\nSeed: {seed}\nPatterns: {}\nTesting: {}\n\n",
+                    patterns::random_source_info(),
+                    <S as Sort>::name()
+                )
+                .as_bytes(),
+            )
             .unwrap();
         io::stdout().flush().unwrap();
 
@@ -55,44 +57,68 @@ fn check_is_sorted<T: Ord + Clone + Debug, S: Sort>(v: &mut [T]) {
     let is_small_test = v.len() <= 100;
     let v_orig = v.to_vec();
 
-    <S as Sort>::sort(v);
+    // The trace is of sort_by, which may take another path than sort.
+    let trace = if trace::is_requested() {
+        let (trace, result) = ComparisonTrace::record::<T, S>(v);
+        if let Err(payload) = result {
+            write_trace::<S>(&trace, seed);
+            panic::resume_unwind(payload);
+        }
+        Some(trace)
+    } else {
+        <S as Sort>::sort(v);
+        None
+    };
 
     assert_eq!(v.len(), v_orig.len());
 
-    for window in v.windows(2) {
-        if window[0] > window[1] {
-            let mut stdlib_sorted_vec = v_orig.clone();
-            let stdlib_sorted = stdlib_sorted_vec.as_mut_slice();
-            stdlib_sorted.sort();
+    if verify::is_sorted_by(v, T::cmp) && verify::same_elements(&v_orig, v) {
+        return;
+    }
 
-            if is_small_test {
-                eprintln!("Orginal:  {:?}", v_orig);
-                eprintln!("Expected: {:?}", stdlib_sorted);
-                eprintln!("Got:      {:?}", v);
-            } else {
-                if env::var("WRITE_LARGE_FAILURE").is_ok() {
-                    // Large arrays output them as files.
-                    let original_name = format!("original_{}.txt", seed);
-                    let std_name = format!("stdlib_sorted_{}.txt", seed);
-                    let testsort_name = format!("{}_sorted_{}.txt", S::name(), seed);
-
-                    fs::write(&original_name, format!("{:?}", v_orig)).unwrap();
-                    fs::write(&std_name, format!("{:?}", stdlib_sorted)).unwrap();
-                    fs::write(&testsort_name, format!("{:?}", v)).unwrap();
-
-                    eprintln!(
-                        "Failed comparison, see files {original_name}, {std_name}, and {testsort_name}"
-                    );
-                } else {
-                    eprintln!(
-                    "Failed comparison, re-run with WRITE_LARGE_FAILURE env var set, to get output."
-                );
-                }
-            }
+    if let Some(trace) = &trace {
+        write_trace::<S>(trace, seed);
+    }
+
+    let mut reference_sorted = v_orig.clone();
+    reference_sort::sort(&mut reference_sorted);
 
-            panic!("Test assertion failed!")
+    if is_small_test {
+        eprintln!("Orginal:  {:?}", v_orig);
+        eprintln!("Expected: {:?}", reference_sorted);
+        eprintln!("Got:      {:?}", v);
+    } else {
+        if env::var("WRITE_LARGE_FAILURE").is_ok() {
+            // Large arrays output them as files.
+            let original_name = format!("original_{}.txt", seed);
+            let reference_name = format!("reference_sorted_{}.txt", seed);
+            let testsort_name = format!("{}_sorted_{}.txt", S::name(), seed);
+
+            fs::write(&original_name, format!("{:?}", v_orig)).unwrap();
+            fs::write(&reference_name, format!("{:?}", reference_sorted)).unwrap();
+            fs::write(&testsort_name, format!("{:?}", v)).unwrap();
+
+            eprintln!(
+                "Failed comparison, see files {original_name}, {reference_name}, and {testsort_name}"
+            );
+        } else {
+            eprintln!(
+                "Failed comparison, re-run with WRITE_LARGE_FAILURE env var set, to get output."
+            );
         }
     }
+
+    panic!("Test assertion failed!")
+}
+
+fn write_trace<S: Sort>(trace: &ComparisonTrace, seed: u64) {
+    let trace_name = format!("{}_comparisons_{}.txt", S::name(), seed);
+    trace.save(Path::new(&trace_name)).unwrap();
+
+    eprintln!(
+        "Comparison trace of {} calls, see file {trace_name}",
+        trace.calls.len()
+    );
 }
 
 fn test_is_sorted<T: Ord + Clone + Debug, S: Sort>(
@@ -104,52 +130,6 @@ fn test_is_sorted<T: Ord + Clone + Debug, S: Sort>(
     check_is_sorted::<T, S>(test_data.as_mut_slice());
 }
 
-trait DynTrait: Debug {
-    fn get_val(&self) -> i32;
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct DynValA {
-    value: i32,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct DynValB {
-    value: u64,
-}
-
-impl DynTrait for DynValA {
-    fn get_val(&self) -> i32 {
-        self.value
-    }
-}
-impl DynTrait for DynValB {
-    fn get_val(&self) -> i32 {
-        let bytes = self.value.to_ne_bytes();
-        i32::from_ne_bytes([bytes[0], bytes[1], bytes[6], bytes[7]])
-    }
-}
-
-impl PartialOrd for dyn DynTrait {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for dyn DynTrait {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.get_val().cmp(&other.get_val())
-    }
-}
-
-impl PartialEq for dyn DynTrait {
-    fn eq(&self, other: &Self) -> bool {
-        self.get_val() == other.get_val()
-    }
-}
-
-impl Eq for dyn DynTrait {}
-
 fn shift_i32_to_u32(val: i32) -> u32 {
     (val as i64 + (i32::MAX as i64 + 1)) as u32
 }
@@ -165,19 +145,19 @@ fn extend_i32_to_u64(val: i32) -> u64 {
 }
 
 fn extend_i32_to_u128(val: i32) -> u128 {
-    // Extends the value into the 64 bit range,
+    // Extends the value into the 128 bit range,
     // while preserving input order.
     (shift_i32_to_u32(val) as u128) * i64::MAX as u128
 }
 
+fn extend_i32_to_i128(val: i32) -> i128 {
+    // Extends the value into the 128 bit range, negative values set all bits of the upper half,
+    // while preserving input order.
+    (val as i128) * i64::MAX as i128
+}
+
 fn dyn_trait_from_i32(val: i32) -> Rc<dyn DynTrait> {
-    if val % 2 == 0 {
-        Rc::new(DynValA { value: val })
-    } else {
-        Rc::new(DynValB {
-            value: extend_i32_to_u64(val),
-        })
-    }
+    dyn_val_from_i32(val).into()
 }
 
 fn i32_from_i32(val: i32) -> i32 {
@@ -222,21 +202,7 @@ fn should_test_for_strong_exception_safety() -> bool {
     *VAL.get_or_init(|| env::var("ONLY_CHECK_BASIC_EXCEPTION_SAFETY").is_err())
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-#[repr(C)]
-struct CompCount {
-    val: i32,
-    comp_count: Cell<u32>,
-}
-
-impl CompCount {
-    fn new(val: i32) -> Self {
-        Self {
-            val,
-            comp_count: Cell::new(0),
-        }
-    }
-}
+crate::instrumented_type!(CompCount);
 
 /// Generates $base_name_pattern_name_impl functions calling the test_fns for all test_len.
 macro_rules! gen_sort_test_fns {
@@ -299,21 +265,21 @@ macro_rules! gen_sort_test_fns_with_default_patterns_3_ty {
         gen_sort_test_fns_with_default_patterns!(
             ${concat($base_name, _i32)},
             |len, pattern_fn| $test_fn::<i32, S>(len, i32_from_i32, i32_from_i32_ref, pattern_fn),
-            &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+            test_sizes::without_largest(2),
             [$(($pattern_name, $pattern_fn),)*],
         );
 
         gen_sort_test_fns_with_default_patterns!(
             ${concat($base_name, _cell_i32)},
             |len, pattern_fn| $test_fn::<Cell<i32>, S>(len, cell_i32_from_i32, i32_from_cell_i32, pattern_fn),
-            &TEST_LENGTHS[..TEST_LENGTHS.len() - 3],
+            test_sizes::without_largest(3),
             [$(($pattern_name, $pattern_fn),)*],
         );
 
         gen_sort_test_fns_with_default_patterns!(
             ${concat($base_name, _ffi_string)},
             |len, pattern_fn| $test_fn::<FFIString, S>(len, ffi_string_from_i32, i32_from_ffi_string, pattern_fn),
-            &TEST_LENGTHS[..TEST_LENGTHS.len() - 3],
+            test_sizes::without_largest(3),
             [$(($pattern_name, $pattern_fn),)*],
         );
     };
@@ -388,7 +354,15 @@ fn int_edge_impl<S: Sort>() {
         10,
     ]);
 
-    let mut large = patterns::random(TEST_LENGTHS[TEST_LENGTHS.len() - 2]);
+    // 128-bit values are compared as two words, the upper ones decide only if they differ.
+    check_is_sorted::<u128, S>(&mut [u128::MIN, u128::MAX]);
+    check_is_sorted::<u128, S>(&mut [u128::MAX, u128::MIN, u64::MAX as u128, 1 << 64, 3]);
+    check_is_sorted::<u128, S>(&mut [1 << 64, u64::MAX as u128, u128::MAX - 3, 5, (1 << 64) + 1]);
+    check_is_sorted::<i128, S>(&mut [i128::MIN, i128::MAX]);
+    check_is_sorted::<i128, S>(&mut [i128::MAX, -1, i128::MIN, 0, u64::MAX as i128, -(1 << 64)]);
+    check_is_sorted::<i128, S>(&mut [i128::MIN + 1, i64::MIN as i128, -3, i128::MIN, 1 << 64]);
+
+    let mut large = patterns::random(*test_sizes::without_largest(1).last().unwrap());
     large.push(i32::MAX);
     large.push(i32::MIN);
     large.push(i32::MAX);
@@ -414,7 +388,7 @@ fn sort_vs_sort_by_impl<S: Sort>() {
 gen_sort_test_fns_with_default_patterns!(
     correct_i32,
     |len, pattern_fn| test_is_sorted::<i32, S>(len, |val| val, pattern_fn),
-    TEST_LENGTHS,
+    test_sizes::lengths(),
     [
         (random_d4, |len| patterns::random_uniform(len, 0..4)),
         (random_d8, |len| patterns::random_uniform(len, 0..8)),
@@ -439,21 +413,28 @@ gen_sort_test_fns_with_default_patterns!(
 gen_sort_test_fns_with_default_patterns!(
     correct_u64,
     |len, pattern_fn| test_is_sorted::<u64, S>(len, extend_i32_to_u64, pattern_fn),
-    TEST_LENGTHS,
+    test_sizes::lengths(),
     []
 );
 
 gen_sort_test_fns_with_default_patterns!(
     correct_u128,
     |len, pattern_fn| test_is_sorted::<u128, S>(len, extend_i32_to_u128, pattern_fn),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
+    []
+);
+
+gen_sort_test_fns_with_default_patterns!(
+    correct_i128,
+    |len, pattern_fn| test_is_sorted::<i128, S>(len, extend_i32_to_i128, pattern_fn),
+    test_sizes::without_largest(2),
     []
 );
 
 gen_sort_test_fns_with_default_patterns!(
     correct_cell_i32,
     |len, pattern_fn| test_is_sorted::<Cell<i32>, S>(len, Cell::new, pattern_fn),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
@@ -464,21 +445,21 @@ gen_sort_test_fns_with_default_patterns!(
         |val| FFIString::new(format!("{:010}", shift_i32_to_u32(val))),
         pattern_fn
     ),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
 gen_sort_test_fns_with_default_patterns!(
     correct_f128,
     |len, pattern_fn| test_is_sorted::<F128, S>(len, F128::new, pattern_fn),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
 gen_sort_test_fns_with_default_patterns!(
     correct_1k,
     |len, pattern_fn| test_is_sorted::<FFIOneKibiByte, S>(len, FFIOneKibiByte::new, pattern_fn),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
@@ -489,7 +470,7 @@ gen_sort_test_fns_with_default_patterns!(
         |val| format!("{:010}", shift_i32_to_u32(val)),
         pattern_fn
     ),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
@@ -497,7 +478,15 @@ gen_sort_test_fns_with_default_patterns!(
 gen_sort_test_fns_with_default_patterns!(
     correct_dyn_val,
     |len, pattern_fn| test_is_sorted::<Rc<dyn DynTrait>, S>(len, dyn_trait_from_i32, pattern_fn),
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
+    []
+);
+
+// Same as dyn_val, but the elements own their value instead of sharing it through an Rc.
+gen_sort_test_fns_with_default_patterns!(
+    correct_dyn_box,
+    |len, pattern_fn| test_is_sorted::<Box<dyn DynTrait>, S>(len, dyn_val_from_i32, pattern_fn),
+    test_sizes::without_largest(2),
     []
 );
 
@@ -512,8 +501,12 @@ fn stability_legacy_impl<S: Sort>() {
         return;
     }
 
-    let large_range = if cfg!(miri) { 100..110 } else { 3000..3010 };
-    let rounds = if cfg!(miri) { 1 } else { 10 };
+    let large_range = if test_sizes::is_reduced() {
+        100..110
+    } else {
+        3000..3010
+    };
+    let rounds = if test_sizes::is_reduced() { 1 } else { 10 };
 
     let rand_vals = patterns::random_uniform(5_000, 0..=9);
     let mut rand_idx = 0;
@@ -555,9 +548,9 @@ fn stability_legacy_impl<S: Sort>() {
             // will need to be ordered with increasing
             // counts... i.e., exactly asserting that this sort is
             // stable.
-            assert!(v
-                .windows(2)
-                .all(|w| i32_tup_from_u64(w[0]) <= i32_tup_from_u64(w[1])));
+            assert!(verify::is_stable_sorted(&orig, &v, |a, b| {
+                i32_tup_from_u64(*a).0.cmp(&i32_tup_from_u64(*b).0)
+            }));
         }
     }
 
@@ -618,11 +611,55 @@ fn stability_with_patterns<T: Ord + Clone, S: Sort>(
     // will need to be ordered with increasing
     // counts... i.e., exactly asserting that this sort is
     // stable.
-    assert!(v.windows(2).all(|w| w[0] <= w[1]));
+    assert!(verify::is_stable_sorted(&orig, &v, |a, b| a.0.cmp(&b.0)));
 }
 
 gen_sort_test_fns_with_default_patterns_3_ty!(stability, stability_with_patterns, []);
 
+fn stability_stress_impl<S: Sort>() {
+    // Stability bugs in block merges tend to only show up with very few distinct keys and long
+    // inputs, where the merges move long runs of equal elements around. The other stability tests
+    // stay at lengths where these code paths might not be reached.
+
+    write_info_to_stdout::<S>();
+
+    if <S as Sort>::name().contains("unstable") {
+        // It would be great to mark the test as skipped, but that isn't possible as of now.
+        return;
+    }
+
+    let pattern_fns: [fn(usize, usize) -> Vec<i32>; 3] = [
+        patterns::few_keys_blocks,
+        patterns::few_keys_alternating,
+        patterns::few_keys_single_intruder,
+    ];
+
+    for &len in test_sizes::stress_lengths() {
+        for key_count in 2..=4 {
+            for pattern_fn in pattern_fns {
+                // The key goes into the upper and the occurrence into the lower 32 bits, so that
+                // the sorts that only support u64 can be tested as well.
+                let mut counts = [0u64; 4];
+                let orig = pattern_fn(len, key_count)
+                    .into_iter()
+                    .map(|key| {
+                        counts[key as usize] += 1;
+                        ((key as u64) << 32) | counts[key as usize]
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut v = orig.clone();
+                <S as Sort>::sort_by(&mut v, |a, b| (a >> 32).cmp(&(b >> 32)));
+
+                assert!(
+                    verify::is_stable_sorted(&orig, &v, |a, b| (a >> 32).cmp(&(b >> 32))),
+                    "len: {len} key_count: {key_count}"
+                );
+            }
+        }
+    }
+}
+
 fn observable_is_less_u64<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>) {
     // Technically this is unsound as per Rust semantics, but the only way to do this that works
     // across C FFI. In C and C++ it would be valid to have some trivial POD containing an int that
@@ -710,7 +747,7 @@ fn observable_is_less_u64<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>
 gen_sort_test_fns_with_default_patterns!(
     observable_is_less_u64,
     observable_is_less_u64::<S>,
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
@@ -723,22 +760,17 @@ fn observable_is_less<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>) {
     // function, which would not be observed in the original slice and would lead to a double free.
 
     let pattern = pattern_fn(len);
-    let mut test_input = pattern
-        .into_iter()
-        .map(|val| CompCount::new(val))
-        .collect::<Vec<_>>();
+    let mut test_input = pattern.into_iter().map(CompCount::new).collect::<Vec<_>>();
 
     let mut comp_count_global = 0;
 
+    // CompCount::cmp counts the comparison in both elements.
     <S as Sort>::sort_by(&mut test_input, |a, b| {
-        a.comp_count.replace(a.comp_count.get() + 1);
-        b.comp_count.replace(b.comp_count.get() + 1);
         comp_count_global += 1;
-
-        a.val.cmp(&b.val)
+        a.cmp(b)
     });
 
-    let total_inner: u64 = test_input.iter().map(|c| c.comp_count.get() as u64).sum();
+    let total_inner: u64 = test_input.iter().map(|c| c.comp_count() as u64).sum();
 
     assert_eq!(total_inner, comp_count_global * 2);
 }
@@ -746,7 +778,7 @@ fn observable_is_less<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>) {
 gen_sort_test_fns_with_default_patterns!(
     observable_is_less,
     observable_is_less::<S>,
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
@@ -756,6 +788,10 @@ fn panic_retain_orig_set<T: Ord + Clone, S: Sort>(
     type_from_fn: impl Fn(&T) -> i32,
     pattern_fn: fn(usize) -> Vec<i32>,
 ) {
+    if !CAN_CATCH_PANIC {
+        return;
+    }
+
     let mut test_data: Vec<T> = pattern_fn(len).into_iter().map(type_into_fn).collect();
 
     let sum_before: i64 = test_data.iter().map(|x| type_from_fn(x) as i64).sum();
@@ -763,22 +799,12 @@ fn panic_retain_orig_set<T: Ord + Clone, S: Sort>(
     // Calculate a specific comparison that should panic.
     // Ensure that it can be any of the possible comparisons and that it always panics.
     let required_comps = calc_comps_required::<T, S>(&mut test_data.clone(), |a, b| a.cmp(b));
-    let panic_threshold = patterns::random_uniform(1, 1..=required_comps as i32)[0] as usize - 1;
-
-    let mut comp_counter = 0;
+    // Make the panic dependent on the test len and some random factor. We want to make sure that
+    // panicking may also happen when comparing elements a second time.
+    let mut injector = FaultInjector::panic_in_first(required_comps as u64);
 
     let res = panic::catch_unwind(AssertUnwindSafe(|| {
-        <S as Sort>::sort_by(&mut test_data, |a, b| {
-            if comp_counter == panic_threshold {
-                // Make the panic dependent on the test len and some random factor. We want to
-                // make sure that panicking may also happen when comparing elements a second
-                // time.
-                panic!();
-            }
-            comp_counter += 1;
-
-            a.cmp(b)
-        });
+        <S as Sort>::sort_by(&mut test_data, injector.wrap(|a: &T, b| a.cmp(b)));
     }));
 
     assert!(res.is_err());
@@ -796,6 +822,10 @@ fn panic_retain_orig_set<T: Ord + Clone, S: Sort>(
 gen_sort_test_fns_with_default_patterns_3_ty!(panic_retain_orig_set, panic_retain_orig_set, []);
 
 fn panic_observable_is_less<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>) {
+    if !CAN_CATCH_PANIC {
+        return;
+    }
+
     // This test, tests that every is_less is actually observable. Ie. this can go wrong if a hole
     // is created using temporary memory and, the whole is used as comparison but not copied back.
     // This property must also hold if the user provided comparison panics.
@@ -806,56 +836,172 @@ fn panic_observable_is_less<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i3
 
     let mut test_input = pattern_fn(len)
         .into_iter()
-        .map(|val| CompCount::new(val))
+        .map(CompCount::new)
         .collect::<Vec<_>>();
 
-    let sum_before: i64 = test_input.iter().map(|x| x.val as i64).sum();
+    let sum_before: i64 = test_input.iter().map(|x| *x.value() as i64).sum();
 
-    // Calculate a specific comparison that should panic.
-    // Ensure that it can be any of the possible comparisons and that it always panics.
+    // Panic in a specific comparison. Ensure that it can be any of the possible comparisons and
+    // that it always panics.
     let required_comps =
-        calc_comps_required::<CompCount, S>(&mut test_input.clone(), |a, b| a.val.cmp(&b.val));
-
-    let panic_threshold = patterns::random_uniform(1, 1..=required_comps as i32)[0] as u64 - 1;
+        calc_comps_required::<CompCount<i32>, S>(&mut test_input.clone(), |a, b| {
+            a.value().cmp(b.value())
+        });
+    let mut injector = FaultInjector::panic_in_first(required_comps as u64);
 
     let mut comp_count_global = 0;
 
     let res = panic::catch_unwind(AssertUnwindSafe(|| {
-        <S as Sort>::sort_by(&mut test_input, |a, b| {
-            if comp_count_global == panic_threshold {
-                // Make the panic dependent on the test len and some random factor. We want to
-                // make sure that panicking may also happen when comparing elements a second
-                // time.
-                panic!();
-            }
-
-            a.comp_count.replace(a.comp_count.get() + 1);
-            b.comp_count.replace(b.comp_count.get() + 1);
-            comp_count_global += 1;
-
-            a.val.cmp(&b.val)
-        });
+        <S as Sort>::sort_by(
+            &mut test_input,
+            injector.wrap(|a: &CompCount<i32>, b: &CompCount<i32>| {
+                comp_count_global += 1;
+                a.cmp(b)
+            }),
+        );
     }));
 
     assert!(res.is_err());
 
-    let total_inner: u64 = test_input.iter().map(|c| c.comp_count.get() as u64).sum();
+    let total_inner: u64 = test_input.iter().map(|c| c.comp_count() as u64).sum();
 
     assert_eq!(total_inner, comp_count_global * 2);
 
     // If the sum before and after don't match, it means the set of elements hasn't remained the
     // same.
-    let sum_after: i64 = test_input.iter().map(|x| x.val as i64).sum();
+    let sum_after: i64 = test_input.iter().map(|x| *x.value() as i64).sum();
     assert_eq!(sum_before, sum_after);
 }
 
 gen_sort_test_fns_with_default_patterns!(
     panic_observable_is_less,
     panic_observable_is_less::<S>,
-    &TEST_LENGTHS[..TEST_LENGTHS.len() - 2],
+    test_sizes::without_largest(2),
     []
 );
 
+/// Appends its id to a shared log when dropped, ordered by `val`.
+#[derive(Debug)]
+struct DropLogged {
+    val: i32,
+    id: usize,
+    log: Rc<RefCell<Vec<usize>>>,
+}
+
+impl Drop for DropLogged {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.id);
+    }
+}
+
+fn drop_logged_input(
+    len: usize,
+    pattern_fn: fn(usize) -> Vec<i32>,
+) -> (Vec<DropLogged>, Rc<RefCell<Vec<usize>>>) {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let input = pattern_fn(len)
+        .into_iter()
+        .enumerate()
+        .map(|(id, val)| DropLogged {
+            val,
+            id,
+            log: log.clone(),
+        })
+        .collect();
+
+    (input, log)
+}
+
+fn assert_dropped_once(log: &RefCell<Vec<usize>>, len: usize) {
+    let mut dropped = log.borrow().clone();
+    reference_sort::sort(&mut dropped);
+    assert!(dropped.iter().copied().eq(0..len), "{dropped:?}");
+}
+
+fn drop_order<S: Sort>(len: usize, pattern_fn: fn(usize) -> Vec<i32>) {
+    // The sum checks of the other tests can't tell whether an element was dropped and a bitwise
+    // copy of it left in the slice. Elements that log their drops can: the sort only moves them,
+    // so no drop happens during the sort, and every element has to be dropped exactly once
+    // afterwards, also if the vec is dropped while unwinding from a panic in the comparison.
+    let (mut test_input, log) = drop_logged_input(len, pattern_fn);
+    let mut injector = FaultInjector::new();
+
+    <S as Sort>::sort_by(
+        &mut test_input,
+        injector.wrap(|a: &DropLogged, b: &DropLogged| a.val.cmp(&b.val)),
+    );
+    assert_eq!(*log.borrow(), [], "dropped during sort");
+    assert!(test_input.windows(2).all(|w| w[0].val <= w[1].val));
+
+    drop(test_input);
+    assert_dropped_once(&log, len);
+
+    if !CAN_CATCH_PANIC || injector.calls() == 0 {
+        return;
+    }
+
+    // Same input, so the panic can be in any of the comparisons.
+    let (mut test_input, log) = drop_logged_input(len, pattern_fn);
+    let mut injector = FaultInjector::panic_in_first(injector.calls());
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        <S as Sort>::sort_by(
+            &mut test_input,
+            injector.wrap(|a: &DropLogged, b: &DropLogged| {
+                // Nothing may have been dropped before the panic either.
+                assert!(log.borrow().is_empty());
+                a.val.cmp(&b.val)
+            }),
+        );
+    }));
+    assert!(res.is_err());
+    assert_eq!(*log.borrow(), [], "dropped during sort");
+
+    // Drop the elements while unwinding.
+    let res = panic::catch_unwind(AssertUnwindSafe(move || {
+        let _test_input = test_input;
+        panic::panic_any(());
+    }));
+    assert!(res.is_err());
+    assert_dropped_once(&log, len);
+}
+
+gen_sort_test_fns_with_default_patterns!(
+    drop_order,
+    drop_order::<S>,
+    test_sizes::without_largest(2),
+    []
+);
+
+fn panic_payload_impl<S: Sort>() {
+    write_info_to_stdout::<S>();
+
+    if !CAN_CATCH_PANIC {
+        return;
+    }
+
+    // The panic raised by the user provided comparison function should reach the caller as is,
+    // even if it has to pass through a sort implemented in another language.
+    #[derive(Debug, PartialEq, Eq)]
+    struct PanicMarker(usize);
+
+    for test_len in test_sizes::without_largest(2) {
+        let mut test_data = patterns::random(*test_len);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            <S as Sort>::sort_by(&mut test_data, |_, _| {
+                panic::panic_any(PanicMarker(*test_len));
+            });
+        }));
+
+        let payload = res.expect_err("Comparison function panic was swallowed");
+        assert_eq!(
+            payload.downcast_ref::<PanicMarker>(),
+            Some(&PanicMarker(*test_len))
+        );
+    }
+}
+
 fn deterministic<T: Ord + Clone + Debug, S: Sort>(
     len: usize,
     type_into_fn: impl Fn(i32) -> T + Copy,
@@ -930,6 +1076,10 @@ fn violate_ord_retain_orig_set<T: Ord, S: Sort>(
     type_from_fn: impl Fn(&T) -> i32,
     pattern_fn: fn(usize) -> Vec<i32>,
 ) {
+    if !CAN_CATCH_PANIC {
+        return;
+    }
+
     // A user may implement Ord incorrectly for a type or violate it by calling sort_by with a
     // comparison function that violates Ord with the orderings it returns. Even under such
     // circumstances the input must retain its original set of elements.
@@ -937,7 +1087,11 @@ fn violate_ord_retain_orig_set<T: Ord, S: Sort>(
     // Ord implies a strict total order see https://en.wikipedia.org/wiki/Total_order.
 
     // Generating random numbers with miri is quite expensive.
-    let random_orderings_len = if cfg!(miri) { 200 } else { 10_000 };
+    let random_orderings_len = if test_sizes::is_reduced() {
+        200
+    } else {
+        10_000
+    };
 
     // Make sure we get a good distribution of random orderings, that are repeatable with the seed.
     // Just using random_uniform with the same len and range will always yield the same value.
@@ -1083,9 +1237,9 @@ fn violate_ord_retain_orig_set<T: Ord, S: Sort>(
         let sum_after: i64 = test_data.iter().map(|x| type_from_fn(x) as i64).sum();
         assert_eq!(sum_before, sum_after);
 
-        if cfg!(miri) {
-            // This test is prohibitively expensive in miri, so only run one of the comparison
-            // functions. This test is not expected to yield direct UB, but rather surface potential
+        if test_sizes::is_reduced() {
+            // This test is prohibitively expensive in miri and emulation, so only run one of the
+            // comparison functions. This test is not expected to yield direct UB, but rather surface potential
             // UB by showing that the sum is different now.
             break;
         }
@@ -1183,6 +1337,14 @@ define_instantiate_sort_tests!(
     [miri_no, correct_u128_ascending],
     [miri_no, correct_u128_descending],
     [miri_no, correct_u128_saw_mixed],
+    [miri_no, correct_i128_random],
+    [miri_yes, correct_i128_random_z1],
+    [miri_no, correct_i128_random_d2],
+    [miri_no, correct_i128_random_d20],
+    [miri_no, correct_i128_random_s95],
+    [miri_no, correct_i128_ascending],
+    [miri_no, correct_i128_descending],
+    [miri_no, correct_i128_saw_mixed],
     [miri_yes, correct_cell_i32_random],
     [miri_yes, correct_cell_i32_random_z1],
     [miri_yes, correct_cell_i32_random_d2],
@@ -1231,6 +1393,14 @@ define_instantiate_sort_tests!(
     [miri_no, correct_dyn_val_ascending],
     [miri_no, correct_dyn_val_descending],
     [miri_no, correct_dyn_val_saw_mixed],
+    [miri_no, correct_dyn_box_random],
+    [miri_yes, correct_dyn_box_random_z1],
+    [miri_no, correct_dyn_box_random_d2],
+    [miri_no, correct_dyn_box_random_d20],
+    [miri_no, correct_dyn_box_random_s95],
+    [miri_no, correct_dyn_box_ascending],
+    [miri_no, correct_dyn_box_descending],
+    [miri_no, correct_dyn_box_saw_mixed],
     [miri_no, stability_legacy],
     [miri_no, stability_i32_random],
     [miri_yes, stability_i32_random_z1],
@@ -1256,6 +1426,7 @@ define_instantiate_sort_tests!(
     [miri_no, stability_ffi_string_ascending],
     [miri_no, stability_ffi_string_descending],
     [miri_no, stability_ffi_string_saw_mixed],
+    [miri_no, stability_stress],
     [miri_no, observable_is_less_u64_random],
     [miri_no, observable_is_less_u64_random_z1],
     [miri_no, observable_is_less_u64_random_d2],
@@ -1304,6 +1475,15 @@ define_instantiate_sort_tests!(
     [miri_no, panic_observable_is_less_ascending],
     [miri_no, panic_observable_is_less_descending],
     [miri_no, panic_observable_is_less_saw_mixed],
+    [miri_yes, panic_payload],
+    [miri_no, drop_order_random],
+    [miri_yes, drop_order_random_z1],
+    [miri_no, drop_order_random_d2],
+    [miri_no, drop_order_random_d20],
+    [miri_no, drop_order_random_s95],
+    [miri_no, drop_order_ascending],
+    [miri_no, drop_order_descending],
+    [miri_no, drop_order_saw_mixed],
     [miri_no, deterministic_i32_random],
     [miri_yes, deterministic_i32_random_z1],
     [miri_no, deterministic_i32_random_d2],
@@ -0,0 +1,183 @@
+//! Traces of the calls a sort makes to the comparison function, to replay the exact decision path
+//! of a failing test offline.
+//!
+//! With `WRITE_COMPARISON_TRACE=1` the correctness tests sort through `sort_by` with `T::cmp` and
+//! record every call, and a failing test writes the trace next to the input dumps, see
+//! `check_is_sorted` in [`crate::tests`]. Every call is recorded as the positions of both elements
+//! in the slice at the time of the call and the result. Elements outside the slice, a pivot copied
+//! to the stack or the scratch buffer of a merge, have no position.
+//!
+//! A sort with randomized behavior takes a different path on every run, the trace is the path of
+//! the run that failed. [`ComparisonTrace::replay`] answers a new run with the recorded results and
+//! panics at the first call that differs from the recording, which shows where two runs diverge.
+//!
+//! The trace file has one call per line, `<a> <b> <result>`, with `-` for a missing position and
+//! `<`, `=` or `>` for the result.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::env;
+use std::fs;
+use std::io;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::thread;
+
+use crate::Sort;
+
+/// One call of the comparison function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Call {
+    /// Position of the first argument in the sorted slice, `None` if it was outside.
+    pub a: Option<usize>,
+    pub b: Option<usize>,
+    pub result: Ordering,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComparisonTrace {
+    pub calls: Vec<Call>,
+}
+
+/// Whether `WRITE_COMPARISON_TRACE` asks the tests to record traces.
+pub fn is_requested() -> bool {
+    env::var("WRITE_COMPARISON_TRACE").is_ok_and(|val| val != "0")
+}
+
+impl ComparisonTrace {
+    /// Sorts `v` with `S::sort_by` and `T::cmp`, recording every call. A panic of the sort is
+    /// caught and returned, the trace up to the panic is kept. With panic=abort a panic ends the
+    /// process before the trace can be written.
+    pub fn record<T: Ord, S: Sort>(v: &mut [T]) -> (Self, thread::Result<()>) {
+        let positions = Positions::of(v);
+        let calls = RefCell::new(Vec::new());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            S::sort_by(v, |a, b| {
+                let result = a.cmp(b);
+                calls.borrow_mut().push(Call {
+                    a: positions.get(a),
+                    b: positions.get(b),
+                    result,
+                });
+                result
+            });
+        }));
+
+        let trace = Self {
+            calls: calls.into_inner(),
+        };
+
+        (trace, result)
+    }
+
+    /// A comparison function for sorting `v` that returns the recorded results in order, without
+    /// looking at the elements.
+    ///
+    /// Panics at the first call whose positions differ from the recording, and at the first call
+    /// past its end.
+    pub fn replay<'a, T>(&'a self, v: &[T]) -> impl FnMut(&T, &T) -> Ordering + 'a {
+        let positions = Positions::of(v);
+        let mut next = 0;
+
+        move |a, b| {
+            let (a, b) = (positions.get(a), positions.get(b));
+            let Some(call) = self.calls.get(next) else {
+                panic!("Replay diverged at call {next}: ({a:?}, {b:?}), the trace has ended");
+            };
+
+            if (call.a, call.b) != (a, b) {
+                panic!(
+                    "Replay diverged at call {next}: ({a:?}, {b:?}), recorded ({:?}, {:?})",
+                    call.a, call.b
+                );
+            }
+
+            next += 1;
+            call.result
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let position =
+            |pos: Option<usize>| pos.map_or_else(|| "-".to_string(), |pos| pos.to_string());
+
+        let mut text = String::new();
+        for call in &self.calls {
+            let result = match call.result {
+                Ordering::Less => '<',
+                Ordering::Equal => '=',
+                Ordering::Greater => '>',
+            };
+            text += &format!("{} {} {result}\n", position(call.a), position(call.b));
+        }
+
+        fs::write(path, text)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |line_idx: usize| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{}:{}: expected '<a> <b> <result>'",
+                    path.display(),
+                    line_idx + 1
+                ),
+            )
+        };
+
+        let position = |field: &str| match field {
+            "-" => Some(None),
+            _ => field.parse().ok().map(Some),
+        };
+
+        let mut calls = Vec::new();
+        for (line_idx, line) in fs::read_to_string(path)?.lines().enumerate() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [a, b, result] = fields[..] else {
+                return Err(invalid(line_idx));
+            };
+
+            let result = match result {
+                "<" => Ordering::Less,
+                "=" => Ordering::Equal,
+                ">" => Ordering::Greater,
+                _ => return Err(invalid(line_idx)),
+            };
+
+            calls.push(Call {
+                a: position(a).ok_or_else(|| invalid(line_idx))?,
+                b: position(b).ok_or_else(|| invalid(line_idx))?,
+                result,
+            });
+        }
+
+        Ok(Self { calls })
+    }
+}
+
+/// Maps references into a slice to their position, by address.
+struct Positions {
+    start: usize,
+    len: usize,
+    size: usize,
+}
+
+impl Positions {
+    fn of<T>(v: &[T]) -> Self {
+        Self {
+            start: v.as_ptr() as usize,
+            len: v.len(),
+            size: mem::size_of::<T>(),
+        }
+    }
+
+    // Zero sized elements all share one address, they have no position.
+    fn get<T>(&self, elem: &T) -> Option<usize> {
+        let offset = (elem as *const T as usize).checked_sub(self.start)?;
+        let pos = offset.checked_div(self.size)?;
+        (pos < self.len && offset % self.size == 0).then_some(pos)
+    }
+}
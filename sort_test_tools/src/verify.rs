@@ -0,0 +1,48 @@
+//! Oracles for the result of a sort, shared by the tests, the fuzzer in the command line tool and
+//! plugins.
+//!
+//! They only report whether the result is correct, printing diagnostics is up to the caller. The
+//! reference is [`crate::reference_sort`], not the std sorts, so the std sorts can be checked too.
+
+use std::cmp::Ordering;
+
+use crate::reference_sort;
+
+/// Returns `true` if no element of `v` is greater than the element after it according to
+/// `compare`.
+pub fn is_sorted_by<T>(v: &[T], mut compare: impl FnMut(&T, &T) -> Ordering) -> bool {
+    v.windows(2)
+        .all(|w| compare(&w[0], &w[1]) != Ordering::Greater)
+}
+
+/// Returns `true` if `sorted` contains the same elements as `orig`, including duplicates, in any
+/// order.
+pub fn same_elements<T: Ord>(orig: &[T], sorted: &[T]) -> bool {
+    if orig.len() != sorted.len() {
+        return false;
+    }
+
+    let orig = reference_sort::sorted_by(orig.iter().collect(), |a, b| a.cmp(b));
+    let sorted = reference_sort::sorted_by(sorted.iter().collect(), |a, b| a.cmp(b));
+
+    orig == sorted
+}
+
+/// Returns `true` if `sorted` is `orig` sorted by `compare`, with equal elements in the same order
+/// as in `orig`.
+///
+/// Elements that are equal according to `compare` have to be distinguishable with `==`, eg. by
+/// carrying their original position, otherwise any order of them passes.
+pub fn is_stable_sorted<T: PartialEq>(
+    orig: &[T],
+    sorted: &[T],
+    mut compare: impl FnMut(&T, &T) -> Ordering,
+) -> bool {
+    if orig.len() != sorted.len() {
+        return false;
+    }
+
+    let expected = reference_sort::sorted_by(orig.iter().collect(), |a, b| compare(a, b));
+
+    expected.into_iter().eq(sorted.iter())
+}
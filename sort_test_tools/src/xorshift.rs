@@ -0,0 +1,33 @@
+//! Small and fast generator for picking inputs, mutations and faults reproducibly from a seed.
+//!
+//! Not suitable where the quality of the randomness matters, use [`crate::patterns`] for test
+//! inputs that should follow `OVERRIDE_SEED`.
+
+/// xorshift64, see <https://www.jstatsoft.org/article/view/v008i14>.
+#[derive(Clone, Debug)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    /// A seed of 0 is replaced by 1, xorshift never leaves the zero state.
+    pub const fn new(seed: u64) -> Self {
+        Self(if seed == 0 { 1 } else { seed })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value in `0..limit`, or 0 if `limit` is 0. The bias of the modulo is negligible for the
+    /// limits used in this repository.
+    pub fn below(&mut self, limit: usize) -> usize {
+        (self.next_u64() % limit.max(1) as u64) as usize
+    }
+
+    /// The top 53 bits as uniform value in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,100 @@
+//! Comparison adversary that synthesizes worst-case inputs for comparison sorts.
+//!
+//! Based on "A Killer Adversary for Quicksort" by M. D. McIlroy (1999). The sort is run on
+//! placeholder elements whose values are decided lazily. All elements start out as "gas", which
+//! compares greater than every decided, "solid", value. When two gas elements are compared one of
+//! them is frozen to the next smallest solid value, preferring the element the sort most recently
+//! compared while it was gas, which is likely the pivot candidate. This keeps as many elements
+//! undecided as possible, and so maximizes the work left for the sort.
+//!
+//! Once the sort is done every element has a value, and sorting those values with the same
+//! implementation performs exactly the same comparisons. This only holds for deterministic sorts,
+//! sorts with randomized pivot selection see a different input on every run.
+//!
+//! The adversary is greedy and targets the pivot selection of quicksort like algorithms. Sorts that
+//! start by scanning for existing runs freeze the elements in order, and get an ascending input.
+
+use std::cmp::Ordering;
+
+use crate::registry::SortEntry;
+
+const GAS: usize = usize::MAX;
+
+struct Adversary {
+    values: Vec<usize>,
+    solid_count: usize,
+    candidate: usize,
+}
+
+impl Adversary {
+    fn new(len: usize) -> Self {
+        Self {
+            values: vec![GAS; len],
+            solid_count: 0,
+            candidate: 0,
+        }
+    }
+
+    fn freeze(&mut self, idx: usize) {
+        self.values[idx] = self.solid_count;
+        self.solid_count += 1;
+    }
+
+    fn compare(&mut self, a: usize, b: usize) -> Ordering {
+        if self.values[a] == GAS && self.values[b] == GAS {
+            if a == self.candidate {
+                self.freeze(a);
+            } else {
+                self.freeze(b);
+            }
+        }
+
+        if self.values[a] == GAS {
+            self.candidate = a;
+        } else if self.values[b] == GAS {
+            self.candidate = b;
+        }
+
+        self.values[a].cmp(&self.values[b])
+    }
+
+    fn into_input(mut self) -> Vec<i32> {
+        // Elements that are still gas were never compared to each other, any order between them is
+        // consistent with the comparisons the sort observed.
+        for idx in 0..self.values.len() {
+            if self.values[idx] == GAS {
+                self.freeze(idx);
+            }
+        }
+
+        self.values.into_iter().map(|val| val as i32).collect()
+    }
+}
+
+/// Synthesizes a worst-case input of length `len` for the sort called by `sort_by`.
+///
+/// `sort_by` has to sort the given slice with the given comparison function. The returned values
+/// are a permutation of `0..len`.
+pub fn synthesize(
+    len: usize,
+    sort_by: impl FnOnce(&mut [i32], &mut dyn FnMut(&i32, &i32) -> Ordering),
+) -> Vec<i32> {
+    assert!(len <= i32::MAX as usize);
+
+    let mut adversary = Adversary::new(len);
+    let mut placeholders = (0..len as i32).collect::<Vec<_>>();
+
+    sort_by(&mut placeholders, &mut |a, b| {
+        adversary.compare(*a as usize, *b as usize)
+    });
+
+    adversary.into_input()
+}
+
+/// Synthesizes a worst-case input of length `len` for a sort from the registry.
+///
+/// The input is derived via `sort_by`. For C and C++ sorts `sort` may use a different, natively
+/// comparing, instantiation of the algorithm, which usually but not necessarily behaves the same.
+pub fn synthesize_for(entry: &SortEntry<i32>, len: usize) -> Vec<i32> {
+    synthesize(len, |v, compare| entry.sort_by(v, compare))
+}
@@ -0,0 +1,79 @@
+//! How the runtime of a sort depends on the number of distinct values in the input.
+//!
+//! At a fixed len the input has 1, 2, 4, ... up to len distinct values, drawn uniformly at random.
+//! Sorts without special handling of equal elements get faster as the cardinality drops, since
+//! there is less to sort, but some degrade, eg. quicksorts without three way partitioning or
+//! merge sorts that gallop into many short streaks. The curve of the runtime relative to the all
+//! distinct input answers most questions about equal elements with a single chart per sort.
+
+use sort_test_tools::patterns;
+
+use crate::registry::SortEntry;
+
+use super::timing::{self, time_batch};
+
+/// One point of the curve.
+#[derive(Clone, Copy, Debug)]
+pub struct CardinalityPoint {
+    pub distinct: usize,
+    /// Mean runtime per element, in nanoseconds.
+    pub ns_per_elem: f64,
+    /// Runtime relative to the input with `len` distinct values, below 1 means faster.
+    pub vs_all_distinct: f64,
+}
+
+/// The distinct value counts of the sweep at `len`, the powers of two below `len` and `len`
+/// itself.
+pub fn distinct_counts(len: usize) -> Vec<usize> {
+    let mut counts = std::iter::successors(Some(1usize), |count| count.checked_mul(2))
+        .take_while(|&count| count < len)
+        .collect::<Vec<_>>();
+    counts.push(len.max(1));
+
+    counts
+}
+
+/// Input of `len` values drawn uniformly from `distinct` values.
+pub fn input(len: usize, distinct: usize) -> Vec<i32> {
+    let distinct = distinct.clamp(1, i32::MAX as usize) as i32;
+    patterns::random_uniform(len, 0..distinct)
+}
+
+/// Measures `entry` at every count of [`distinct_counts`], with the median of `samples` timings
+/// per point. `transform` turns the `i32` values into the element type, it has to keep equal
+/// values equal and distinct ones distinct.
+pub fn sweep<T>(
+    entry: &SortEntry<T>,
+    transform: &dyn Fn(Vec<i32>) -> Vec<T>,
+    len: usize,
+    samples: usize,
+) -> Vec<CardinalityPoint> {
+    let inputs = timing::inputs_per_sample(len);
+
+    let ns_per_elem = distinct_counts(len)
+        .into_iter()
+        .map(|distinct| {
+            let mut timings = (0..samples.max(1))
+                .map(|_| {
+                    let batch = (0..inputs)
+                        .map(|_| transform(input(len, distinct)))
+                        .collect::<Vec<_>>();
+                    time_batch(entry, batch) * 1e9 / (inputs * len.max(1)) as f64
+                })
+                .collect::<Vec<_>>();
+            timings.sort_by(f64::total_cmp);
+
+            (distinct, timings[timings.len() / 2])
+        })
+        .collect::<Vec<_>>();
+
+    let all_distinct = ns_per_elem.last().unwrap().1;
+    ns_per_elem
+        .into_iter()
+        .map(|(distinct, ns_per_elem)| CardinalityPoint {
+            distinct,
+            ns_per_elem,
+            vs_all_distinct: ns_per_elem / all_distinct,
+        })
+        .collect()
+}
@@ -0,0 +1,315 @@
+//! Certificates of fuzz and stress runs, so a campaign spread over many machines can be audited
+//! without trusting the machines that ran it.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let run = certificate::run(&entry, &input, descending);
+//! let certificate = Certificate::new(source, entry.name(), "i32", descending, &input, &run);
+//! writer.write(&certificate)?;
+//! ```
+//!
+//! A certificate is one line per sort call, with the generator and seed that derive the input, and
+//! hashes of the input and of the sorted output, the comparison count and, with the
+//! `instrument_mem` feature, the element moves. `sort-research verify <file>` derives every input
+//! again, sorts it with the same sort and reports every field that differs. A mismatch of the input
+//! hash means the generators differ between the builds, a mismatch of the output or the counts
+//! that the sort behaves differently, or that the certificate was not produced by a run at all.
+//!
+//! The hashes are FNV-1a over the `Debug` representation of the elements, so they are the same on
+//! every platform and for every type the tools support. Sorts that pick pivots at random produce
+//! different comparison counts on every run, their certificates only verify the hashes.
+
+use std::fmt::{self, Debug, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::registry::SortEntry;
+
+/// Which generator derived the input from the seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Generator {
+    /// `fuzz_input` of `sort-research fuzz`.
+    Fuzz,
+    /// [`crate::analysis::stress::Case`], without the fault.
+    Stress,
+}
+
+impl Generator {
+    pub fn name(self) -> &'static str {
+        match self {
+            Generator::Fuzz => "fuzz",
+            Generator::Stress => "stress",
+        }
+    }
+}
+
+/// Everything needed to derive the input again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Source {
+    pub generator: Generator,
+    pub seed: u64,
+    /// The max len passed to the generator, which changes the input of a seed.
+    pub max_len: usize,
+}
+
+/// The result of one sort call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    pub source: Source,
+    pub sort_name: String,
+    pub type_name: String,
+    pub descending: bool,
+    pub len: usize,
+    pub input_hash: u64,
+    pub output_hash: u64,
+    pub comparisons: u64,
+    /// Element moves reported by the Rust sorts, `None` without the `instrument_mem` feature.
+    pub moves: Option<u64>,
+}
+
+/// The sorted copy of the input and what it took to get there.
+pub struct Run<T> {
+    pub output: Vec<T>,
+    pub comparisons: u64,
+    pub moves: Option<u64>,
+}
+
+/// Sorts a copy of `input` with `entry`, through `sort_by` so the comparisons of every sort can be
+/// counted.
+pub fn run<T: Ord + Clone>(entry: &SortEntry<T>, input: &[T], descending: bool) -> Run<T> {
+    let mut output = input.to_vec();
+    let mut comparisons = 0;
+
+    let base = output.as_ptr();
+    let moves = count_moves(base, || {
+        entry.sort_by(&mut output, |a, b| {
+            comparisons += 1;
+            if descending {
+                b.cmp(a)
+            } else {
+                a.cmp(b)
+            }
+        });
+    });
+
+    Run {
+        output,
+        comparisons,
+        moves,
+    }
+}
+
+// The writes of mem::record are element copies and swaps, a swap counts as two moves. The trace
+// holds every access, which limits this to the input sizes of the fuzzer.
+#[cfg(feature = "instrument_mem")]
+fn count_moves<T>(base: *const T, f: impl FnOnce()) -> Option<u64> {
+    use crate::instrument::mem::{self, AccessKind};
+
+    let ((), trace) = mem::record(base, f);
+    let written_bytes = trace
+        .accesses
+        .iter()
+        .filter(|access| access.kind == AccessKind::Write)
+        .map(|access| access.size)
+        .sum::<usize>();
+
+    Some((written_bytes / std::mem::size_of::<T>().max(1)) as u64)
+}
+
+#[cfg(not(feature = "instrument_mem"))]
+fn count_moves<T>(_base: *const T, f: impl FnOnce()) -> Option<u64> {
+    f();
+    None
+}
+
+impl Certificate {
+    pub fn new<T: Debug>(
+        source: Source,
+        sort_name: &str,
+        type_name: &str,
+        descending: bool,
+        input: &[T],
+        run: &Run<T>,
+    ) -> Self {
+        Self {
+            source,
+            sort_name: sort_name.to_string(),
+            type_name: type_name.to_string(),
+            descending,
+            len: input.len(),
+            input_hash: hash_elements(input),
+            output_hash: hash_elements(&run.output),
+            comparisons: run.comparisons,
+            moves: run.moves,
+        }
+    }
+
+    /// The fields in which `recomputed` differs from `self`. Moves are only compared if both
+    /// certificates have them.
+    pub fn differences(&self, recomputed: &Self) -> Vec<String> {
+        let mut differences = Vec::new();
+        let mut check = |field: &str, expected: String, actual: String| {
+            if expected != actual {
+                differences.push(format!("{field}: expected {expected}, got {actual}"));
+            }
+        };
+
+        check("len", self.len.to_string(), recomputed.len.to_string());
+        check(
+            "input hash",
+            format!("{:016x}", self.input_hash),
+            format!("{:016x}", recomputed.input_hash),
+        );
+        check(
+            "output hash",
+            format!("{:016x}", self.output_hash),
+            format!("{:016x}", recomputed.output_hash),
+        );
+        check(
+            "comparisons",
+            self.comparisons.to_string(),
+            recomputed.comparisons.to_string(),
+        );
+        if let (Some(expected), Some(actual)) = (self.moves, recomputed.moves) {
+            check("moves", expected.to_string(), actual.to_string());
+        }
+
+        differences
+    }
+}
+
+/// FNV-1a over the `Debug` representation of the elements, separated by commas.
+pub fn hash_elements<T: Debug>(v: &[T]) -> u64 {
+    let mut hasher = Fnv1a(0xcbf2_9ce4_8422_2325);
+    for elem in v {
+        // Writing to the hasher can't fail.
+        let _ = write!(hasher, "{elem:?},");
+    }
+
+    hasher.0
+}
+
+struct Fnv1a(u64);
+
+impl fmt::Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
+// One line of the certificate file, tab separated: generator, seed, max len, sort name, type name,
+// ascending or descending, len, input hash, output hash, comparisons, moves or `-`.
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:016x}\t{:016x}\t{}\t",
+            self.source.generator.name(),
+            self.source.seed,
+            self.source.max_len,
+            self.sort_name,
+            self.type_name,
+            if self.descending {
+                "descending"
+            } else {
+                "ascending"
+            },
+            self.len,
+            self.input_hash,
+            self.output_hash,
+            self.comparisons,
+        )?;
+
+        match self.moves {
+            Some(moves) => write!(f, "{moves}"),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+impl FromStr for Certificate {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid certificate line '{line}'");
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [generator, seed, max_len, sort_name, type_name, order, len, input_hash, output_hash, comparisons, moves] =
+            fields[..]
+        else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            source: Source {
+                generator: match generator {
+                    "fuzz" => Generator::Fuzz,
+                    "stress" => Generator::Stress,
+                    _ => return Err(invalid()),
+                },
+                seed: seed.parse().map_err(|_| invalid())?,
+                max_len: max_len.parse().map_err(|_| invalid())?,
+            },
+            sort_name: sort_name.to_string(),
+            type_name: type_name.to_string(),
+            descending: match order {
+                "ascending" => false,
+                "descending" => true,
+                _ => return Err(invalid()),
+            },
+            len: len.parse().map_err(|_| invalid())?,
+            input_hash: u64::from_str_radix(input_hash, 16).map_err(|_| invalid())?,
+            output_hash: u64::from_str_radix(output_hash, 16).map_err(|_| invalid())?,
+            comparisons: comparisons.parse().map_err(|_| invalid())?,
+            moves: match moves {
+                "-" => None,
+                moves => Some(moves.parse().map_err(|_| invalid())?),
+            },
+        })
+    }
+}
+
+/// Appends certificates to a file, one line each, so concurrent campaigns can share one file per
+/// machine and the files can be concatenated.
+pub struct CertificateWriter {
+    file: LineWriter<File>,
+}
+
+impl CertificateWriter {
+    pub fn append(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: LineWriter::new(file),
+        })
+    }
+
+    pub fn write(&mut self, certificate: &Certificate) -> io::Result<()> {
+        writeln!(self.file, "{certificate}")
+    }
+}
+
+/// Reads a file written by [`CertificateWriter`], empty lines are skipped.
+pub fn read(path: &Path) -> io::Result<Vec<Certificate>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    let mut certificates = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let certificate = line
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        certificates.push(certificate);
+    }
+
+    Ok(certificates)
+}
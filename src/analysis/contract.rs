@@ -0,0 +1,433 @@
+//! The behavioral contract of a sort implementation, checked clause by clause for every sort in the
+//! registry, and written to a conformance report.
+//!
+//! The test suite of sort_test_tools checks one sort at a time and in depth, see tests/main.rs.
+//! This checks every sort of the build on the same bounded set of inputs, and tells for every
+//! [`Clause`] whether the sort keeps it, breaks it, doesn't claim it or can't be checked in this
+//! build. What a sort claims follows the conventions of the other analyses, from its name, see
+//! [`Claims`].
+//!
+//! The inputs are tagged u64 like in [`crate::analysis::stability`], the key in the upper and the
+//! original position in the lower half, so the FFI sorts take part and equal keys can be told
+//! apart. The reference is [`crate::verify`], which doesn't rely on any sort under test.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use sort_test_tools::patterns;
+
+use crate::fault::{Fault, FaultInjector, InjectedPanic, Trigger};
+use crate::registry::SortEntry;
+use crate::verify;
+
+const TEST_LENS: &[usize] = &[
+    0, 1, 2, 3, 5, 8, 16, 17, 20, 32, 50, 100, 500, 1_000, 10_000,
+];
+
+/// Every fault runs once per input of these lens, a panic unwinds through all of the sort.
+const FAULT_LENS: &[usize] = &[20, 100, 1_000];
+
+/// Allocated memory a sort may use beyond a copy of its input, for buffers of a fixed size such as
+/// the stack of pending runs or the bucket buffers of a sample sort.
+pub const ALLOC_SLACK: usize = 1 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Clause {
+    /// `sort` and `sort_by` return the input in ascending order.
+    Sorted,
+    /// Equal elements keep their order, for sorts that claim to be stable.
+    Stable,
+    /// The result holds every element of the input exactly once.
+    SameElements,
+    /// A panic of the comparison function leaves every element in the slice exactly once.
+    PanicSafe,
+    /// A comparison function that is not a total order leaves every element in the slice exactly
+    /// once. The sort may panic or return any order.
+    OrdViolationSafe,
+    /// The sort allocates at most a copy of its input plus [`ALLOC_SLACK`] at a time.
+    AllocBound,
+    /// The same input gives the same result, also for equal elements.
+    Deterministic,
+}
+
+impl Clause {
+    pub const ALL: [Clause; 7] = [
+        Clause::Sorted,
+        Clause::Stable,
+        Clause::SameElements,
+        Clause::PanicSafe,
+        Clause::OrdViolationSafe,
+        Clause::AllocBound,
+        Clause::Deterministic,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Clause::Sorted => "sorted",
+            Clause::Stable => "stable",
+            Clause::SameElements => "same elements",
+            Clause::PanicSafe => "panic safe",
+            Clause::OrdViolationSafe => "Ord violation safe",
+            Clause::AllocBound => "alloc bound",
+            Clause::Deterministic => "deterministic",
+        }
+    }
+}
+
+/// The clauses a sort claims beyond the ones every sort has to keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Claims {
+    /// Declared per sort in the registry, see [`SortEntry::is_stable`].
+    pub stable: bool,
+    /// The Rust sorts. A panic can't unwind through the C and C++ sorts and an inconsistent
+    /// comparison function can make them read out of bounds, see [`crate::analysis::stress`].
+    pub fault_safe: bool,
+    /// Every sort except the ones that pick their pivots at random.
+    pub deterministic: bool,
+}
+
+impl Claims {
+    pub fn of<T>(entry: &SortEntry<T>) -> Self {
+        Self {
+            stable: entry.is_stable(),
+            fault_safe: entry.name().starts_with("rust_"),
+            deterministic: !entry.name().contains("random"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// The first violation that was found.
+    Fail(String),
+    NotClaimed,
+    /// The clause can't be checked in this build, for the given reason.
+    NotChecked(&'static str),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail(_) => write!(f, "FAIL"),
+            Self::NotClaimed => write!(f, "-"),
+            Self::NotChecked(_) => write!(f, "n/a"),
+        }
+    }
+}
+
+/// The verdicts of one sort, in the order of [`Clause::ALL`].
+#[derive(Clone, Debug)]
+pub struct Conformance {
+    pub name: String,
+    pub claims: Claims,
+    pub verdicts: Vec<(Clause, Verdict)>,
+}
+
+impl Conformance {
+    pub fn verdict(&self, clause: Clause) -> &Verdict {
+        &self.verdicts.iter().find(|(c, _)| *c == clause).unwrap().1
+    }
+
+    /// The clauses the sort claims and breaks.
+    pub fn failures(&self) -> impl Iterator<Item = (Clause, &str)> {
+        self.verdicts
+            .iter()
+            .filter_map(|(clause, verdict)| match verdict {
+                Verdict::Fail(reason) => Some((*clause, reason.as_str())),
+                _ => None,
+            })
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.failures().next().is_some()
+    }
+}
+
+impl fmt::Display for Conformance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name)?;
+        for (clause, verdict) in &self.verdicts {
+            write!(f, " {}: {verdict},", clause.name())?;
+        }
+        for (clause, reason) in self.failures() {
+            write!(f, "\n  {}: {reason}", clause.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every clause of the contract for `entry`.
+pub fn check(entry: &SortEntry<u64>) -> Conformance {
+    let claims = Claims::of(entry);
+    let mut failures = BTreeMap::new();
+
+    let supported = check_valid_inputs(entry, claims, &mut failures);
+    if supported && claims.fault_safe && cfg!(panic = "unwind") {
+        check_faults(entry, &mut failures);
+    }
+
+    let verdicts = Clause::ALL
+        .into_iter()
+        .map(|clause| {
+            let claimed = match clause {
+                Clause::Stable => claims.stable,
+                Clause::PanicSafe | Clause::OrdViolationSafe => claims.fault_safe,
+                Clause::Deterministic => claims.deterministic,
+                _ => true,
+            };
+
+            let verdict = if !claimed {
+                Verdict::NotClaimed
+            } else if !supported {
+                Verdict::NotChecked("sort panicked on a valid input, eg. no support for u64")
+            } else if let Some(reason) = failures.remove(&clause) {
+                Verdict::Fail(reason)
+            } else if matches!(clause, Clause::PanicSafe | Clause::OrdViolationSafe)
+                && !cfg!(panic = "unwind")
+            {
+                Verdict::NotChecked("built with panic=abort")
+            } else if clause == Clause::AllocBound && !cfg!(feature = "instrument_alloc") {
+                Verdict::NotChecked("needs the instrument_alloc feature")
+            } else {
+                Verdict::Pass
+            };
+
+            (clause, verdict)
+        })
+        .collect();
+
+    Conformance {
+        name: entry.name().to_string(),
+        claims,
+        verdicts,
+    }
+}
+
+/// Writes the verdicts of all sorts as a markdown table, followed by the reason of every failure
+/// and every clause that was not checked.
+pub fn write_report(path: &Path, results: &[Conformance]) -> io::Result<()> {
+    let mut report = String::from("# Sort correctness contract\n\n");
+    report += &format!(
+        "Patterns: {}\n\n`pass` and `FAIL` for the claimed clauses, `-` if the sort doesn't claim \
+         the clause and `n/a` if this build can't check it.\n\n",
+        patterns::random_source_info()
+    );
+
+    report += "| sort |";
+    for clause in Clause::ALL {
+        report += &format!(" {} |", clause.name());
+    }
+    report += "\n|---|";
+    report += &"---|".repeat(Clause::ALL.len());
+    report += "\n";
+
+    for result in results {
+        report += &format!("| {} |", result.name);
+        for (_, verdict) in &result.verdicts {
+            report += &format!(" {verdict} |");
+        }
+        report += "\n";
+    }
+
+    let notes = results
+        .iter()
+        .flat_map(|result| {
+            result
+                .verdicts
+                .iter()
+                .filter_map(move |(clause, verdict)| match verdict {
+                    Verdict::Fail(reason) => Some(format!(
+                        "- {}, {}: FAIL, {reason}",
+                        result.name,
+                        clause.name()
+                    )),
+                    Verdict::NotChecked(reason) => Some(format!(
+                        "- {}, {}: n/a, {reason}",
+                        result.name,
+                        clause.name()
+                    )),
+                    _ => None,
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if !notes.is_empty() {
+        report += "\n## Notes\n\n";
+        report += &notes.join("\n");
+        report += "\n";
+    }
+
+    fs::write(path, report)
+}
+
+/// Records the first failure of every clause on the inputs without faults. Returns `false` if the
+/// sort panicked, which makes all clauses unsupported.
+fn check_valid_inputs(
+    entry: &SortEntry<u64>,
+    claims: Claims,
+    failures: &mut BTreeMap<Clause, String>,
+) -> bool {
+    let mut fail = |clause, reason: String| {
+        failures.entry(clause).or_insert(reason);
+    };
+
+    for &len in TEST_LENS {
+        for (pattern_name, input) in tagged_inputs(len) {
+            let at = format!("{pattern_name} len {len}");
+
+            // sort compares the whole u64, every element is distinct.
+            let mut v = input.clone();
+            let Some(peak_bytes) = sort_recording_peak(|| entry.sort(&mut v)) else {
+                return false;
+            };
+            if !verify::is_sorted_by(&v, u64::cmp) {
+                fail(Clause::Sorted, format!("sort: not sorted, {at}"));
+            }
+            if !verify::same_elements(&input, &v) {
+                fail(Clause::SameElements, format!("sort: elements differ, {at}"));
+            }
+            let alloc_bound = len * std::mem::size_of::<u64>() + ALLOC_SLACK;
+            if peak_bytes > alloc_bound {
+                fail(
+                    Clause::AllocBound,
+                    format!("{peak_bytes} bytes at once, bound {alloc_bound}, {at}"),
+                );
+            }
+
+            let mut results = Vec::with_capacity(2);
+            for _ in 0..2 {
+                let mut v = input.clone();
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| entry.sort_by(&mut v, cmp_key)));
+                if result.is_err() {
+                    return false;
+                }
+                results.push(v);
+            }
+
+            let v = &results[0];
+            if !verify::is_sorted_by(v, cmp_key) {
+                fail(Clause::Sorted, format!("sort_by: not sorted, {at}"));
+            } else if claims.stable && !verify::is_stable_sorted(&input, v, cmp_key) {
+                fail(Clause::Stable, format!("equal elements reordered, {at}"));
+            }
+            if !verify::same_elements(&input, v) {
+                fail(
+                    Clause::SameElements,
+                    format!("sort_by: elements differ, {at}"),
+                );
+            }
+            if claims.deterministic && results[0] != results[1] {
+                fail(Clause::Deterministic, format!("two runs differ, {at}"));
+            }
+        }
+    }
+
+    true
+}
+
+fn check_faults(entry: &SortEntry<u64>, failures: &mut BTreeMap<Clause, String>) {
+    let mut fail = |clause, reason: String| {
+        failures.entry(clause).or_insert(reason);
+    };
+
+    let inconsistent = [
+        (Trigger::Every(3), Fault::Reverse),
+        (Trigger::From(0), Fault::Result(Ordering::Less)),
+    ];
+
+    for &len in FAULT_LENS {
+        for (pattern_name, input) in tagged_inputs(len) {
+            let at = format!("{pattern_name} len {len}");
+
+            // A panic in one of the comparisons the sort makes on this input.
+            let mut counter = FaultInjector::new();
+            entry.sort_by(&mut input.clone(), counter.wrap(cmp_key));
+            if counter.calls() > 0 {
+                let mut injector = FaultInjector::panic_in_first(counter.calls());
+                let mut v = input.clone();
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    entry.sort_by(&mut v, injector.wrap(cmp_key))
+                }));
+
+                let reason = match result {
+                    Ok(()) if !verify::is_sorted_by(&v, cmp_key) => {
+                        Some("returned unsorted after a skipped panic")
+                    }
+                    Err(payload) if !payload.is::<InjectedPanic>() => Some("panicked on its own"),
+                    _ if !verify::same_elements(&input, &v) => Some("elements differ"),
+                    _ => None,
+                };
+                if let Some(reason) = reason {
+                    fail(Clause::PanicSafe, format!("{reason}, {at}"));
+                }
+            }
+
+            for (trigger, fault) in inconsistent {
+                let mut injector = FaultInjector::new().inject(trigger, fault);
+                let mut v = input.clone();
+                // The sort may panic, as ipnsort does when it detects the violation.
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                    entry.sort_by(&mut v, injector.wrap(cmp_key))
+                }));
+
+                if !verify::same_elements(&input, &v) {
+                    fail(
+                        Clause::OrdViolationSafe,
+                        format!("elements differ with {fault:?} at {trigger:?}, {at}"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Calls `sort` and returns the most memory it had allocated at once, 0 without the
+/// instrument_alloc feature, or `None` if it panicked.
+fn sort_recording_peak(sort: impl FnOnce()) -> Option<usize> {
+    #[cfg(feature = "instrument_alloc")]
+    {
+        use crate::instrument::alloc;
+
+        let (result, stats) = alloc::record(|| panic::catch_unwind(AssertUnwindSafe(sort)));
+        result.ok().map(|()| stats.peak_bytes)
+    }
+
+    #[cfg(not(feature = "instrument_alloc"))]
+    {
+        panic::catch_unwind(AssertUnwindSafe(sort)).ok().map(|()| 0)
+    }
+}
+
+fn cmp_key(a: &u64, b: &u64) -> Ordering {
+    (a >> 32).cmp(&(b >> 32))
+}
+
+fn tagged_inputs(len: usize) -> [(&'static str, Vec<u64>); 6] {
+    [
+        ("random", patterns::random(len)),
+        ("random_d10", patterns::random_uniform(len, 0..=9)),
+        ("ascending", patterns::ascending(len)),
+        ("descending", patterns::descending(len)),
+        ("saw_mixed", patterns::saw_mixed(len, (len / 20).max(1))),
+        ("all_equal", patterns::all_equal(len)),
+    ]
+    .map(|(pattern_name, keys)| {
+        let tagged = keys
+            .into_iter()
+            .enumerate()
+            // Flipping the sign bit keeps the order of the negative keys.
+            .map(|(i, key)| (((key as u32 ^ (1 << 31)) as u64) << 32) | i as u64)
+            .collect();
+
+        (pattern_name, tagged)
+    })
+}
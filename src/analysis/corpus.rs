@@ -0,0 +1,77 @@
+//! Inputs that once made a sort fail, run slow or hit its adversary, kept in corpus/ so they are
+//! replayed after the fix lands. The `corpus` test checks every input against every sort of its
+//! type, and `BENCH_CORPUS=1 cargo bench` benchmarks them.
+//!
+//! Every input is a [`Snapshot`] file named `<name>.bin`. The sort name of the snapshot records
+//! which sort the input was found with, it is still checked and benchmarked with all sorts. Add
+//! inputs with `sort-research corpus add`, eg. from the snapshot dir of a crashed fuzz run.
+
+use std::fs;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::analysis::snapshot::Snapshot;
+
+#[derive(Clone, Debug)]
+pub struct CorpusEntry {
+    /// File name without the extension, used in the benchmark names.
+    pub name: String,
+    pub snapshot: Snapshot,
+}
+
+/// The corpus/ dir of the repository.
+pub fn dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus")
+}
+
+/// Loads all inputs in `dir` ordered by name, or none if `dir` doesn't exist.
+pub fn load(dir: &Path) -> io::Result<Vec<CorpusEntry>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(dir)? {
+        let path = dir_entry?.path();
+        if path.extension().is_none_or(|ext| ext != "bin") {
+            continue;
+        }
+
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let snapshot = Snapshot::load(&path)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+
+        entries.push(CorpusEntry { name, snapshot });
+    }
+
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// Writes `snapshot` as `<name>.bin` into `dir`. The name is part of benchmark names, so it may only
+/// contain lowercase letters, digits and `_`. Existing inputs are never overwritten.
+pub fn add(dir: &Path, name: &str, snapshot: &Snapshot) -> io::Result<PathBuf> {
+    let is_valid_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !is_valid_name {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid corpus name '{name}', expected [a-z0-9_]+"),
+        ));
+    }
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{name}.bin"));
+
+    let file = fs::File::create_new(&path)?;
+    let mut snapshot = snapshot.clone();
+    snapshot.sequence = 0;
+
+    let mut writer = BufWriter::new(file);
+    snapshot.write_to(&mut writer)?;
+    writer.into_inner().map_err(|err| err.into_error())?;
+
+    Ok(path)
+}
@@ -0,0 +1,172 @@
+//! Finds the input length at which one sort overtakes another.
+//!
+//! Both sorts are timed on the same inputs, and the per input ratio of their runtimes is averaged
+//! in log space, which gives a geometric mean ratio with a confidence interval. The crossover is
+//! found by binary search over the length, assuming that the ratio changes monotonically. The
+//! reported range is the set of lengths where the confidence interval of the ratio includes 1,
+//! ie. where neither sort is measurably faster.
+
+use crate::registry::SortEntry;
+
+use super::timing::{self, time_batch, Ratio};
+
+type RatioPredicate = fn(&Ratio) -> bool;
+
+impl Ratio {
+    fn a_faster(&self) -> bool {
+        self.ci_high < 1.0
+    }
+
+    fn b_faster(&self) -> bool {
+        self.ci_low > 1.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Crossover {
+    /// The ratio is on the same side of 1 for the smallest and largest length.
+    None { at_min: Ratio, at_max: Ratio },
+    Found {
+        /// Point estimate, the first length at which the sort that is faster for short inputs is
+        /// no longer faster.
+        len: usize,
+        /// Lengths between which neither sort is measurably faster.
+        len_low: usize,
+        len_high: usize,
+        at_len: Ratio,
+        /// Whether `a` is the faster sort for short inputs.
+        a_faster_below: bool,
+    },
+}
+
+impl Crossover {
+    /// One line summary, `sort_a` and `sort_b` are the names of `a` and `b` of the finder.
+    pub fn describe(&self, sort_a: &str, sort_b: &str) -> String {
+        match *self {
+            Crossover::None { at_min, at_max } => {
+                let faster = if at_min.ratio < 1.0 { sort_a } else { sort_b };
+                format!(
+                    "No crossover, {faster} is faster for all lengths. ratio {sort_a}/{sort_b} at len {}: {:.3} [{:.3}, {:.3}], at len {}: {:.3} [{:.3}, {:.3}]",
+                    at_min.len,
+                    at_min.ratio,
+                    at_min.ci_low,
+                    at_min.ci_high,
+                    at_max.len,
+                    at_max.ratio,
+                    at_max.ci_low,
+                    at_max.ci_high
+                )
+            }
+            Crossover::Found {
+                len,
+                len_low,
+                len_high,
+                at_len,
+                a_faster_below,
+            } => {
+                let (below, above) = if a_faster_below {
+                    (sort_a, sort_b)
+                } else {
+                    (sort_b, sort_a)
+                };
+                format!(
+                    "{above} overtakes {below} at len {len}, neither is measurably faster in [{len_low}, {len_high}]. ratio {sort_a}/{sort_b} at len {len}: {:.3} [{:.3}, {:.3}]",
+                    at_len.ratio, at_len.ci_low, at_len.ci_high
+                )
+            }
+        }
+    }
+}
+
+pub struct CrossoverFinder<'a, T> {
+    pub a: &'a SortEntry<T>,
+    pub b: &'a SortEntry<T>,
+    pub make_input: &'a dyn Fn(usize) -> Vec<T>,
+    /// Number of paired samples per measured length.
+    pub samples: usize,
+}
+
+impl<T: Clone> CrossoverFinder<'_, T> {
+    /// Runtime of `a` divided by runtime of `b` at `len`, below 1 means `a` is faster.
+    pub fn measure(&self, len: usize) -> Ratio {
+        let inputs_per_sample = timing::inputs_per_sample(len);
+
+        let log_ratios = (0..self.samples.max(2))
+            .map(|sample| {
+                let batch = (0..inputs_per_sample)
+                    .map(|_| (self.make_input)(len))
+                    .collect::<Vec<_>>();
+
+                // Alternate the order to cancel out cache warming effects.
+                let (time_a, time_b) = if sample % 2 == 0 {
+                    let time_a = time_batch(self.a, batch.clone());
+                    (time_a, time_batch(self.b, batch))
+                } else {
+                    let time_b = time_batch(self.b, batch.clone());
+                    (time_batch(self.a, batch), time_b)
+                };
+
+                (time_a / time_b).ln()
+            })
+            .collect::<Vec<_>>();
+
+        Ratio::from_log_ratios(len, &log_ratios)
+    }
+
+    // Smallest len in (low, high] for which pred is true, given that it is false for low and true
+    // for high.
+    fn bisect(&self, mut low: usize, mut high: usize, pred: impl Fn(&Ratio) -> bool) -> usize {
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if pred(&self.measure(mid)) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        high
+    }
+
+    pub fn find(&self, min_len: usize, max_len: usize) -> Crossover {
+        assert!(min_len < max_len);
+
+        let at_min = self.measure(min_len);
+        let at_max = self.measure(max_len);
+
+        let a_faster_below = at_min.ratio < 1.0;
+        if a_faster_below == (at_max.ratio < 1.0) {
+            return Crossover::None { at_min, at_max };
+        }
+
+        let crossed = |ratio: &Ratio| (ratio.ratio < 1.0) != a_faster_below;
+        let len = self.bisect(min_len, max_len, crossed);
+
+        // Where the sort that is faster for short inputs stops being measurably faster, and where
+        // the other one starts being measurably faster.
+        let (below_faster, above_faster): (RatioPredicate, RatioPredicate) = if a_faster_below {
+            (Ratio::a_faster, Ratio::b_faster)
+        } else {
+            (Ratio::b_faster, Ratio::a_faster)
+        };
+
+        let len_low = if below_faster(&at_min) {
+            self.bisect(min_len, len, |ratio| !below_faster(ratio))
+        } else {
+            min_len
+        };
+        let len_high = if above_faster(&at_max) {
+            self.bisect(len - 1, max_len, above_faster)
+        } else {
+            max_len
+        };
+
+        Crossover::Found {
+            len,
+            len_low,
+            len_high,
+            at_len: self.measure(len),
+            a_faster_below,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Input patterns and element types by name, for the command line tools in src/bin.
+//!
+//! The names and transforms match the benchmarks, so results of the tools are comparable to
+//! benchmark results.
+
+use std::fmt::Debug;
+
+use sort_test_tools::ffi_types::{FFIOneKibiByte, FFIString, F128};
+use sort_test_tools::patterns;
+
+pub type PatternFn = fn(usize) -> Vec<i32>;
+
+pub const TYPE_NAMES: &[&str] = &["i32", "u64", "u128", "i128", "string", "1k", "f128"];
+
+pub const PATTERN_NAMES: &[&str] = &[
+    "random",
+    "random_z1",
+    "random_d20",
+    "random_s95",
+    "ascending",
+    "descending",
+    "saws_long",
+    "pipe_organ",
+    "all_equal",
+];
+
+pub fn pattern_by_name(name: &str) -> Option<PatternFn> {
+    let pattern: PatternFn = match name {
+        "random" => patterns::random,
+        "random_z1" => |len| patterns::random_zipf(len, 1.0),
+        "random_d20" => |len| patterns::random_uniform(len, 0..20),
+        "random_s95" => |len| patterns::random_sorted(len, 95.0),
+        "ascending" => patterns::ascending,
+        "descending" => patterns::descending,
+        "saws_long" => |len| patterns::saw_mixed(len, ((len as f64).log2().round()) as usize),
+        "pipe_organ" => patterns::pipe_organ,
+        "all_equal" => patterns::all_equal,
+        _ => return None,
+    };
+
+    Some(pattern)
+}
+
+fn shift_i32_to_u32(val: i32) -> u32 {
+    (val as i64 + (i32::MAX as i64 + 1)) as u32
+}
+
+/// Generic code that is run for the element type selected by name, see [`with_type`].
+pub trait TypeVisitor {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>);
+}
+
+/// Calls `visitor` with the transform from the i32 patterns to the type called `type_name`.
+/// Returns `false` if there is no such type.
+pub fn with_type(type_name: &str, visitor: impl TypeVisitor) -> bool {
+    match type_name {
+        "i32" => visitor.visit::<i32>(|v| v),
+        "u64" => visitor.visit::<u64>(|v| {
+            v.into_iter()
+                .map(|val| (shift_i32_to_u32(val) as u64) * i32::MAX as u64)
+                .collect()
+        }),
+        "u128" => visitor.visit::<u128>(|v| {
+            v.into_iter()
+                .map(|val| (shift_i32_to_u32(val) as u128) * i64::MAX as u128)
+                .collect()
+        }),
+        "i128" => visitor.visit::<i128>(|v| {
+            v.into_iter()
+                .map(|val| (val as i128) * i64::MAX as i128)
+                .collect()
+        }),
+        "string" => visitor.visit::<FFIString>(|v| {
+            v.into_iter()
+                .map(|val| FFIString::new(format!("{:010}", shift_i32_to_u32(val))))
+                .collect()
+        }),
+        "1k" => visitor
+            .visit::<FFIOneKibiByte>(|v| v.iter().map(|val| FFIOneKibiByte::new(*val)).collect()),
+        "f128" => visitor.visit::<F128>(|v| v.iter().map(|val| F128::new(*val)).collect()),
+        _ => return false,
+    }
+
+    true
+}
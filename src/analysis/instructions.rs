@@ -0,0 +1,159 @@
+//! Counts the instructions a sort call retires, with the hardware counter of the Linux perf
+//! subsystem.
+//!
+//! Unlike runtimes the counts don't depend on the clock frequency, the other load on the machine or
+//! the state of the caches and branch predictors, so the same binary sorting the same input gives
+//! the same count on every run and on every machine with the same instruction set. That makes them
+//! a good signal for regression checks in CI, see util/instruction_counts.py. Only user space
+//! instructions are counted, which works with the default `perf_event_paranoid` level of 2. It
+//! doesn't work in most virtual machines without a virtual PMU, use the callgrind backend of the
+//! script there.
+
+use std::ffi::{c_int, c_long, c_ulong};
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd};
+
+// Not using a crate for this, as in plugin.rs. The values are from <linux/perf_event.h>.
+extern "C" {
+    fn syscall(number: c_long, ...) -> c_long;
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+const SYS_PERF_EVENT_OPEN: Option<c_long> = if cfg!(target_arch = "x86_64") {
+    Some(298)
+} else if cfg!(target_arch = "x86") {
+    Some(336)
+} else if cfg!(target_arch = "arm") {
+    Some(364)
+} else if cfg!(any(target_arch = "aarch64", target_arch = "riscv64")) {
+    Some(241)
+} else {
+    None
+};
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+
+const ATTR_FLAG_DISABLED: u64 = 1 << 0;
+const ATTR_FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+const PERF_FLAG_FD_CLOEXEC: c_ulong = 1 << 3;
+
+const PERF_EVENT_IOC_ENABLE: c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: c_ulong = 0x2403;
+
+/// `struct perf_event_attr` up to `PERF_ATTR_SIZE_VER8`, older kernels accept it as long as the
+/// fields they don't know are zero.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved_2: u16,
+    aux_sample_size: u32,
+    reserved_3: u32,
+    sig_data: u64,
+}
+
+/// A retired instruction counter for the calling thread.
+pub struct InstructionCounter {
+    file: File,
+}
+
+impl InstructionCounter {
+    /// Fails if the kernel or the machine don't provide the counter, `perf_event_paranoid` is above
+    /// 2, or the syscall number for the target architecture isn't known.
+    pub fn new() -> io::Result<Self> {
+        let Some(syscall_number) = SYS_PERF_EVENT_OPEN else {
+            return Err(io::ErrorKind::Unsupported.into());
+        };
+
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config: PERF_COUNT_HW_INSTRUCTIONS,
+            flags: ATTR_FLAG_DISABLED | ATTR_FLAG_EXCLUDE_KERNEL | ATTR_FLAG_EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+
+        // SAFETY: attr is a valid perf_event_attr that outlives the call, pid 0 and cpu -1 count
+        // the calling thread on any cpu, without a group leader.
+        let fd = unsafe {
+            syscall(
+                syscall_number,
+                &attr as *const PerfEventAttr,
+                0 as c_int,
+                -1 as c_int,
+                -1 as c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // SAFETY: fd is a new file descriptor owned by nothing else.
+        let file = unsafe { File::from_raw_fd(fd as c_int) };
+        Ok(Self { file })
+    }
+
+    /// Runs `f` and returns the number of user space instructions it retired.
+    pub fn measure(&mut self, f: impl FnOnce()) -> u64 {
+        let fd = self.file.as_raw_fd();
+        let ioctl_checked = |request| {
+            // SAFETY: fd is the perf event of this counter, the requests take no argument.
+            let ret = unsafe { ioctl(fd, request, 0 as c_ulong) };
+            if ret != 0 {
+                panic!("perf event ioctl failed: {}", io::Error::last_os_error());
+            }
+        };
+
+        ioctl_checked(PERF_EVENT_IOC_RESET);
+        ioctl_checked(PERF_EVENT_IOC_ENABLE);
+        f();
+        ioctl_checked(PERF_EVENT_IOC_DISABLE);
+
+        let mut count = [0u8; 8];
+        self.file
+            .read_exact(&mut count)
+            .expect("Reading the perf event failed");
+        u64::from_ne_bytes(count)
+    }
+}
+
+/// Mean instructions of sorting `run_count` inputs made by `make_input` with `sort`. The inputs
+/// are made outside of the measured region.
+pub fn mean_instructions<T>(
+    counter: &mut InstructionCounter,
+    mut sort: impl FnMut(&mut [T]),
+    run_count: usize,
+    mut make_input: impl FnMut() -> Vec<T>,
+) -> u64 {
+    let mut total = 0;
+    for _ in 0..run_count {
+        let mut v = make_input();
+        total += counter.measure(|| sort(std::hint::black_box(v.as_mut_slice())));
+        std::hint::black_box(v);
+    }
+
+    total / run_count.max(1) as u64
+}
@@ -0,0 +1,342 @@
+//! Offline simulation of the merge policies of run-adaptive merge sorts.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let runs = merge_sim::natural_run_lens(&input);
+//! for policy in Policy::ALL {
+//!     println!("{}: {:?}", policy.name(), merge_sim::simulate(policy, &runs));
+//! }
+//! ```
+//!
+//! A merge policy only sees the lens of the runs, so the cost of a policy can be computed from the
+//! run lens alone, without any elements. That makes it possible to explore policies at input sizes
+//! that could never be sorted in a benchmark, and to replay the runs recorded from real inputs, eg.
+//! the `run_lens` of [`crate::instrument::runs`].
+//!
+//! The cost of a merge is the len of the merged region, the number of elements a plain merge moves.
+//! Galloping and the run detection are not modeled. The runs are merged as given, the sorts extend
+//! short runs with insertion sort first, see [`with_min_run`].
+
+use std::fmt;
+use std::str::FromStr;
+
+use sort_test_tools::xorshift::XorShift;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// The merge rules of CPython's listsort, with the 2015 fix that checks the top four runs.
+    Timsort,
+    /// The rules of `stable::rust_std`, which check the same invariants as timsort but pick the
+    /// lower pair whenever the third run is shorter than the top one. The runs are found from the
+    /// end of the input, so the simulation pushes them in reverse order.
+    RustStd,
+    /// Powersort of Munro and Wild, merges by the node power of the boundary between two runs, as
+    /// used by CPython since 3.11.
+    Powersort,
+    /// Adaptive Shivers sort of Jugé, merges the second and third run while the floor of the log2
+    /// of the third run is at most that of one of the two runs above it.
+    AdaptiveShivers,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 4] = [
+        Policy::Timsort,
+        Policy::RustStd,
+        Policy::Powersort,
+        Policy::AdaptiveShivers,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Policy::Timsort => "timsort",
+            Policy::RustStd => "rust_std",
+            Policy::Powersort => "powersort",
+            Policy::AdaptiveShivers => "adaptive_shivers",
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Policy {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Policy::ALL
+            .into_iter()
+            .find(|policy| policy.name() == val)
+            .ok_or_else(|| {
+                let names = Policy::ALL.map(Policy::name);
+                format!(
+                    "Unknown merge policy '{val}', expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The result of merging one sequence of runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MergeCost {
+    /// Sum of the lens of all runs.
+    pub len: u64,
+    pub merges: u64,
+    /// Sum of the lens of all merged regions.
+    pub merged_elements: u64,
+    /// The most runs that were on the stack at once.
+    pub max_stack_len: usize,
+}
+
+impl MergeCost {
+    /// The merge cost relative to `len * H`, where `H` is the entropy of the run lens. No policy
+    /// that only merges adjacent runs can do better than 1, except for rounding. `None` for a
+    /// single run, where `H` is 0.
+    pub fn vs_entropy_bound(&self, run_lens: &[u64]) -> Option<f64> {
+        let bound = entropy_bound(run_lens);
+        (bound > 0.0).then(|| self.merged_elements as f64 / bound)
+    }
+}
+
+/// `len * H` of the run lens, the lower bound of the merge cost.
+pub fn entropy_bound(run_lens: &[u64]) -> f64 {
+    let len = run_lens.iter().sum::<u64>() as f64;
+    run_lens
+        .iter()
+        .filter(|run_len| **run_len > 0)
+        .map(|run_len| {
+            let run_len = *run_len as f64;
+            run_len * (len / run_len).log2()
+        })
+        .sum()
+}
+
+#[derive(Clone, Copy)]
+struct Run {
+    start: u64,
+    len: u64,
+    /// The node power of the boundary to the run below on the stack, only used by powersort.
+    power: u32,
+}
+
+struct Stack {
+    runs: Vec<Run>,
+    cost: MergeCost,
+}
+
+impl Stack {
+    fn push(&mut self, run: Run) {
+        self.runs.push(run);
+        self.cost.max_stack_len = self.cost.max_stack_len.max(self.runs.len());
+    }
+
+    fn len_at(&self, idx: usize) -> u64 {
+        self.runs[idx].len
+    }
+
+    /// Merges the runs at `idx` and `idx + 1`.
+    fn merge_at(&mut self, idx: usize) {
+        let right = self.runs.remove(idx + 1);
+        let left = &mut self.runs[idx];
+        left.start = left.start.min(right.start);
+        left.len += right.len;
+
+        self.cost.merges += 1;
+        self.cost.merged_elements += left.len;
+    }
+}
+
+/// Simulates `policy` on runs of the given lens, in input order. Empty runs are ignored.
+pub fn simulate(policy: Policy, run_lens: &[u64]) -> MergeCost {
+    let len = run_lens.iter().sum::<u64>();
+    let mut stack = Stack {
+        runs: Vec::new(),
+        cost: MergeCost {
+            len,
+            ..MergeCost::default()
+        },
+    };
+
+    let mut push_run = |start: u64, run_len: u64| {
+        let power = match stack.runs.last() {
+            Some(prev) if policy == Policy::Powersort => node_power(len, prev, start, run_len),
+            _ => 0,
+        };
+
+        if policy == Policy::Powersort {
+            // The powers on the stack are increasing, merge everything above the new boundary.
+            while stack.runs.len() >= 2 && stack.runs[stack.runs.len() - 1].power > power {
+                stack.merge_at(stack.runs.len() - 2);
+            }
+        }
+
+        stack.push(Run {
+            start,
+            len: run_len,
+            power,
+        });
+
+        match policy {
+            Policy::Timsort => {
+                while let Some(idx) = timsort_collapse(&stack) {
+                    stack.merge_at(idx);
+                }
+            }
+            Policy::RustStd => {
+                while let Some(idx) = rust_std_collapse(&stack) {
+                    stack.merge_at(idx);
+                }
+            }
+            Policy::AdaptiveShivers => {
+                while let Some(idx) = adaptive_shivers_collapse(&stack) {
+                    stack.merge_at(idx);
+                }
+            }
+            Policy::Powersort => {}
+        }
+    };
+
+    let runs = run_lens.iter().filter(|run_len| **run_len > 0);
+    if policy == Policy::RustStd {
+        let mut end = len;
+        for &run_len in runs.rev() {
+            end -= run_len;
+            push_run(end, run_len);
+        }
+    } else {
+        let mut start = 0;
+        for &run_len in runs {
+            push_run(start, run_len);
+            start += run_len;
+        }
+    }
+
+    // CPython's merge_force_collapse, and rust_std once the top run starts at 0, still prefer the
+    // lower pair if the third run is shorter than the top one.
+    while stack.runs.len() >= 2 {
+        let n = stack.runs.len();
+        let prefer_lower = matches!(policy, Policy::Timsort | Policy::RustStd)
+            && n >= 3
+            && stack.len_at(n - 3) < stack.len_at(n - 1);
+        stack.merge_at(if prefer_lower { n - 3 } else { n - 2 });
+    }
+
+    stack.cost
+}
+
+// The collapse functions return the index of the lower run of the pair to merge next.
+
+// CPython's merge_collapse, with the runs X, A, B, C from the bottom to the top.
+fn timsort_collapse(stack: &Stack) -> Option<usize> {
+    let n = stack.runs.len();
+    let len = |idx| stack.len_at(idx);
+
+    if (n >= 3 && len(n - 3) <= len(n - 2) + len(n - 1))
+        || (n >= 4 && len(n - 4) <= len(n - 3) + len(n - 2))
+    {
+        if len(n - 3) < len(n - 1) {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else if n >= 2 && len(n - 2) <= len(n - 1) {
+        Some(n - 2)
+    } else {
+        None
+    }
+}
+
+// The collapse of stable::rust_std, without the final merges.
+fn rust_std_collapse(stack: &Stack) -> Option<usize> {
+    let n = stack.runs.len();
+    let len = |idx| stack.len_at(idx);
+
+    if n >= 2
+        && (len(n - 2) <= len(n - 1)
+            || (n >= 3 && len(n - 3) <= len(n - 2) + len(n - 1))
+            || (n >= 4 && len(n - 4) <= len(n - 3) + len(n - 2)))
+    {
+        if n >= 3 && len(n - 3) < len(n - 1) {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+fn adaptive_shivers_collapse(stack: &Stack) -> Option<usize> {
+    let n = stack.runs.len();
+    let level = |idx| stack.len_at(idx).ilog2();
+
+    (n >= 3 && level(n - 3) <= level(n - 2).max(level(n - 1))).then(|| n - 3)
+}
+
+/// The node power of the boundary between `prev` and the run at `start` with `run_len`: the first
+/// bit in which the midpoints of the two runs differ, as fractions of `len`.
+fn node_power(len: u64, prev: &Run, start: u64, run_len: u64) -> u32 {
+    // Twice the midpoints, shifted so the division by `2 * len` leaves 64 fractional bits.
+    let mid_a = ((prev.start * 2 + prev.len) as u128) << 63;
+    let mid_b = ((start * 2 + run_len) as u128) << 63;
+    let a = (mid_a / len as u128) as u64;
+    let b = (mid_b / len as u128) as u64;
+
+    (a ^ b).leading_zeros() + 1
+}
+
+/// The lens of the natural runs of `v`, non-descending or strictly descending, like the run
+/// detection of the merge sorts.
+pub fn natural_run_lens<T: Ord>(v: &[T]) -> Vec<u64> {
+    let mut run_lens = Vec::new();
+
+    let mut start = 0;
+    while start < v.len() {
+        let mut end = start + 1;
+        if end < v.len() {
+            let descending = v[end] < v[end - 1];
+            while end < v.len() && (v[end] < v[end - 1]) == descending {
+                end += 1;
+            }
+        }
+
+        run_lens.push((end - start) as u64);
+        start = end;
+    }
+
+    run_lens
+}
+
+/// Joins adjacent runs until each is at least `min_run` long, like the insertion sort extension
+/// of the merge sorts. The last run may stay shorter.
+pub fn with_min_run(run_lens: &[u64], min_run: u64) -> Vec<u64> {
+    let mut extended = Vec::new();
+
+    let mut pending = 0;
+    for &run_len in run_lens {
+        pending += run_len;
+        if pending >= min_run {
+            extended.push(pending);
+            pending = 0;
+        }
+    }
+
+    if pending > 0 {
+        extended.push(pending);
+    }
+
+    extended
+}
+
+/// `count` run lens drawn uniformly from `1..=max_run_len`, from a fixed seed, for simulations at
+/// sizes that can't be generated as inputs.
+pub fn random_run_lens(count: usize, max_run_len: u64, seed: u64) -> Vec<u64> {
+    let mut rng = XorShift::new(seed | 1);
+    (0..count)
+        .map(|_| rng.next_u64() % max_run_len.max(1) + 1)
+        .collect()
+}
@@ -0,0 +1,41 @@
+//! Tools that derive inputs or properties from the behavior of a sort implementation, as opposed to
+//! the fixed patterns used by the tests and benchmarks.
+
+pub mod adversary;
+
+pub mod cardinality;
+
+pub mod certificate;
+
+pub mod contract;
+
+pub mod perf_fuzz;
+
+pub mod permutation;
+
+pub mod crossover;
+
+pub mod inputs;
+
+pub mod merge_sim;
+
+#[cfg(target_os = "linux")]
+pub mod instructions;
+
+pub mod pow2_bias;
+
+pub mod shard;
+
+pub mod snapshot;
+
+pub mod corpus;
+
+pub mod stability;
+
+pub mod stack_depth;
+
+pub mod stress;
+
+pub mod tie_order;
+
+pub mod timing;
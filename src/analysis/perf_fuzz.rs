@@ -0,0 +1,187 @@
+//! Search for inputs that make a sort slow, complementing the analytic [`super::adversary`].
+//!
+//! Simulated annealing over explicit inputs of a fixed length. The search starts from the best of
+//! a set of parameterized patterns, and then mutates the input, eg. by swapping or reversing
+//! sub-ranges or by replacing it with a freshly generated pattern with different parameters.
+//! Worse inputs are accepted with a probability that decreases over the course of the search, to
+//! escape local maxima.
+//!
+//! Counting comparisons is deterministic for deterministic sorts and should be preferred. Measuring
+//! runtime is noisy, every evaluation takes the minimum of a few runs.
+
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use sort_test_tools::patterns;
+use sort_test_tools::xorshift::XorShift;
+
+use crate::instrument::comp::CountingCmp;
+use crate::registry::SortEntry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    Comparisons,
+    Runtime,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchConfig {
+    pub len: usize,
+    pub iterations: usize,
+    pub objective: Objective,
+    /// Probability of accepting an input that is worse by 1% at the start of the search.
+    pub initial_acceptance: f64,
+    pub seed: u64,
+}
+
+impl SearchConfig {
+    pub fn new(len: usize, objective: Objective) -> Self {
+        Self {
+            len,
+            iterations: 2_000,
+            objective,
+            initial_acceptance: 0.5,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub input: Vec<i32>,
+    /// Comparison count, or runtime in nanoseconds, of `input`.
+    pub score: f64,
+    /// Score of the starting input, for comparison.
+    pub initial_score: f64,
+    /// Name of the pattern the search started from.
+    pub initial_pattern: &'static str,
+}
+
+type PatternFn = fn(usize, usize) -> Vec<i32>;
+
+// The second argument is a free parameter, each pattern maps it into a small set of values. The
+// patterns cache their output per parameter, so an unbounded set would grow memory usage.
+const PATTERNS: &[(&str, PatternFn)] = &[
+    ("random", |len, _| patterns::random(len)),
+    ("random_d", |len, param| {
+        patterns::random_uniform(len, 0..(2i32 << (param % 16)))
+    }),
+    ("random_s", |len, param| {
+        patterns::random_sorted(len, ((param % 20) * 5) as f64)
+    }),
+    ("saw_ascending", |len, param| {
+        patterns::saw_ascending(len, saw_count(len, param))
+    }),
+    ("saw_descending", |len, param| {
+        patterns::saw_descending(len, saw_count(len, param))
+    }),
+    ("saw_mixed", |len, param| {
+        patterns::saw_mixed(len, saw_count(len, param))
+    }),
+    ("pipe_organ", |len, _| patterns::pipe_organ(len)),
+    ("ascending", |len, _| patterns::ascending(len)),
+    ("descending", |len, _| patterns::descending(len)),
+];
+
+fn saw_count(len: usize, param: usize) -> usize {
+    (1 << (param % 16)).clamp(1, len.max(1))
+}
+
+fn evaluate(entry: &SortEntry<i32>, input: &[i32], objective: Objective) -> f64 {
+    match objective {
+        Objective::Comparisons => {
+            let mut v = input.to_vec();
+            let mut cmp = CountingCmp::new(i32::cmp);
+            entry.sort_by(&mut v, |a, b| cmp.compare(a, b));
+            cmp.count() as f64
+        }
+        Objective::Runtime => (0..5)
+            .map(|_| {
+                let mut v = input.to_vec();
+                let start = Instant::now();
+                entry.sort(std::hint::black_box(&mut v));
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or(Duration::ZERO)
+            .as_nanos() as f64,
+    }
+}
+
+fn mutate(input: &mut Vec<i32>, rng: &mut XorShift) {
+    let len = input.len();
+    let a = rng.below(len);
+    let b = rng.below(len);
+    let (lo, hi) = (a.min(b), a.max(b));
+
+    match rng.below(6) {
+        0 => input.swap(a, b),
+        1 => input[lo..=hi].reverse(),
+        2 => input[lo..=hi].rotate_left(1),
+        // Introduce duplicates.
+        3 => input[a] = input[b],
+        4 => input[lo..=hi].sort_by(|x, y| y.cmp(x)),
+        _ => {
+            let (_, pattern_fn) = PATTERNS[rng.below(PATTERNS.len())];
+            *input = pattern_fn(len, rng.below(usize::MAX));
+        }
+    }
+}
+
+/// Searches for an input of `config.len` that maximizes `config.objective` for `entry`.
+pub fn search(entry: &SortEntry<i32>, config: &SearchConfig) -> SearchResult {
+    let mut rng = XorShift::new(config.seed);
+
+    let (initial_pattern, initial_input, initial_score) = PATTERNS
+        .iter()
+        .map(|(name, pattern_fn)| {
+            let input = pattern_fn(config.len, rng.below(usize::MAX));
+            let score = evaluate(entry, &input, config.objective);
+            (*name, input, score)
+        })
+        .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal))
+        .unwrap();
+
+    let mut result = SearchResult {
+        input: initial_input.clone(),
+        score: initial_score,
+        initial_score,
+        initial_pattern,
+    };
+
+    if config.len < 2 {
+        return result;
+    }
+
+    // Temperature is relative to the current score, t_0 is chosen so that a 1% worse input is
+    // accepted with initial_acceptance, and it then linearly cools down to 0.
+    let initial_temperature = -0.01 / config.initial_acceptance.clamp(1e-9, 1.0 - 1e-9).ln();
+
+    let mut current = initial_input;
+    let mut current_score = initial_score;
+
+    for iteration in 0..config.iterations {
+        let temperature =
+            initial_temperature * (1.0 - (iteration as f64 / config.iterations as f64));
+
+        let mut candidate = current.clone();
+        mutate(&mut candidate, &mut rng);
+        let candidate_score = evaluate(entry, &candidate, config.objective);
+
+        let relative_delta = (candidate_score - current_score) / current_score.max(1.0);
+        let accept = relative_delta >= 0.0
+            || (temperature > 0.0 && rng.unit() < (relative_delta / temperature).exp());
+
+        if accept {
+            current = candidate;
+            current_score = candidate_score;
+
+            if current_score > result.score {
+                result.input = current.clone();
+                result.score = current_score;
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,140 @@
+//! Measures the permutation a sort applies to its input, as cycle statistics, for the whole input
+//! and for the elements with equal keys alone.
+//!
+//! The permutation of the whole input is mostly fixed by the keys, its cycles are what an in-place
+//! permutation has to follow, see [`crate::other::permute`], and the displacement is how far the
+//! elements travel from their original position. The permutation of the ties is relative to the
+//! stable order and is the only freedom an unstable sort has. If it is the identity on a pattern,
+//! the sort is accidentally stable there, which users may start to rely on. Unlike
+//! [`crate::analysis::tie_order`] this also shows whether the ties are reordered in a few long
+//! cycles or in many swaps, and how many groups of ties are left in order.
+//!
+//! The inputs are tagged u64 like in [`crate::analysis::stability`], so the FFI sorts can be
+//! measured as well.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::analysis::tie_order::{cmp_key, tag};
+use crate::registry::SortEntry;
+use crate::verify;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CycleStats {
+    pub len: u64,
+    /// Elements that stay at their position.
+    pub fixed_points: u64,
+    /// Cycles of two or more elements.
+    pub cycles: u64,
+    /// Len of the longest cycle, 1 if every element is a fixed point, 0 for no elements.
+    pub longest_cycle: u64,
+    /// Sum of the distances between the old and the new position of every element.
+    pub displacement: u64,
+}
+
+impl CycleStats {
+    /// The cycles of `perm`, where `perm[i]` is the old position of the element at `i`. Panics if
+    /// `perm` is not a permutation of `0..perm.len()`.
+    pub fn of(perm: &[u32]) -> Self {
+        let mut stats = CycleStats {
+            len: perm.len() as u64,
+            ..CycleStats::default()
+        };
+
+        let mut visited = vec![false; perm.len()];
+        for start in 0..perm.len() {
+            stats.displacement += (start as u64).abs_diff(perm[start] as u64);
+            if visited[start] {
+                continue;
+            }
+
+            let mut cycle_len = 0;
+            let mut pos = start;
+            while !visited[pos] {
+                visited[pos] = true;
+                pos = perm[pos] as usize;
+                cycle_len += 1;
+            }
+            assert_eq!(pos, start, "Not a permutation");
+
+            stats.longest_cycle = stats.longest_cycle.max(cycle_len);
+            if cycle_len == 1 {
+                stats.fixed_points += 1;
+            } else {
+                stats.cycles += 1;
+            }
+        }
+
+        stats
+    }
+
+    /// Mean distance an element travels, 0 for no elements.
+    pub fn mean_displacement(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+
+        self.displacement as f64 / self.len as f64
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutputPermutation {
+    /// From the input to the output.
+    pub whole: CycleStats,
+    /// From the stable order to the output. Cycles never leave a group of ties.
+    pub ties: CycleStats,
+    /// Groups of two or more elements with equal keys.
+    pub tie_groups: u64,
+    /// Tie groups that are in their original order after sorting.
+    pub stable_tie_groups: u64,
+}
+
+/// Sorts `keys` tagged with their original position by `entry` and measures the permutation it
+/// applied. Returns `None` if the sort panicked or the result is not sorted.
+pub fn measure(entry: &SortEntry<u64>, keys: &[i32]) -> Option<OutputPermutation> {
+    let input = tag(keys);
+    let mut v = input.clone();
+
+    panic::catch_unwind(AssertUnwindSafe(|| entry.sort_by(&mut v, cmp_key))).ok()?;
+
+    if !verify::is_sorted_by(&v, cmp_key) || !verify::same_elements(&input, &v) {
+        return None;
+    }
+
+    Some(output_permutation(&v))
+}
+
+/// Measures the permutation of the sorted and tagged `v`.
+pub fn output_permutation(v: &[u64]) -> OutputPermutation {
+    let whole = v.iter().map(|tagged| *tagged as u32).collect::<Vec<_>>();
+
+    let mut result = OutputPermutation::default();
+    let mut ties = Vec::with_capacity(v.len());
+    let mut sorted_positions = Vec::new();
+
+    let mut start = 0;
+    for group in v.chunk_by(|a, b| cmp_key(a, b).is_eq()) {
+        let positions = &whole[start..start + group.len()];
+
+        // In the stable order the group is sorted by original position, the element at `i` goes
+        // to the rank of its original position.
+        sorted_positions.clear();
+        sorted_positions.extend_from_slice(positions);
+        sorted_positions.sort_unstable();
+        ties.extend(positions.iter().map(|pos| {
+            let rank = sorted_positions.binary_search(pos).unwrap();
+            (start + rank) as u32
+        }));
+
+        if group.len() >= 2 {
+            result.tie_groups += 1;
+            result.stable_tie_groups += (positions == sorted_positions) as u64;
+        }
+
+        start += group.len();
+    }
+
+    result.whole = CycleStats::of(&whole);
+    result.ties = CycleStats::of(&ties);
+    result
+}
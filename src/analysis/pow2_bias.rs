@@ -0,0 +1,109 @@
+//! Detects runtime cliffs at power of two input lengths.
+//!
+//! Buffer sizes that are rounded to powers of two, or strides that alias in the cache, can make a
+//! sort behave very differently at exactly 2^k elements than at 2^k - 1. The fixed lengths of the
+//! benchmarks are often powers of two or round decimal numbers, and hide such cliffs.
+//!
+//! For every 2^k the runtime is compared to the neighboring lengths 2^k +- 1 and 2^k +- 17. The
+//! runtimes are normalized by n * log2(n) first, so that the growth between the lengths doesn't
+//! count as a cliff.
+
+use crate::registry::SortEntry;
+
+use super::timing::{self, time_batch, Ratio};
+
+const NEIGHBOR_OFFSETS: [isize; 4] = [-17, -1, 1, 17];
+
+#[derive(Clone, Debug)]
+pub struct Pow2Config {
+    pub min_exp: u32,
+    pub max_exp: u32,
+    /// Number of samples per power of two.
+    pub samples: usize,
+    /// Relative difference to the neighbors above which a significant difference is flagged.
+    pub threshold: f64,
+}
+
+impl Default for Pow2Config {
+    fn default() -> Self {
+        // Below 2^7 the +- 17 neighbors are far enough away to cross small-sort thresholds.
+        Self {
+            min_exp: 7,
+            max_exp: 20,
+            samples: 20,
+            threshold: 0.05,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pow2Result {
+    /// Normalized runtime at 2^k divided by the geometric mean of the normalized runtimes at the
+    /// neighboring lengths, above 1 means 2^k is slower.
+    pub vs_neighbors: Ratio,
+    pub is_cliff: bool,
+}
+
+fn normalized_cost(seconds: f64, len: usize, inputs: usize) -> f64 {
+    let len = len as f64;
+    seconds / (inputs as f64 * len * len.log2())
+}
+
+fn measure_pow2<T: Clone>(
+    entry: &SortEntry<T>,
+    make_input: &dyn Fn(usize) -> Vec<T>,
+    pow2_len: usize,
+    samples: usize,
+) -> Ratio {
+    let lens = std::iter::once(pow2_len)
+        .chain(
+            NEIGHBOR_OFFSETS
+                .iter()
+                .map(|offset| pow2_len.checked_add_signed(*offset).unwrap()),
+        )
+        .collect::<Vec<_>>();
+
+    let log_ratios = (0..samples.max(2))
+        .map(|sample| {
+            // Rotate the order of the lengths to cancel out cache warming effects.
+            let mut log_costs = vec![0.0; lens.len()];
+            for i in 0..lens.len() {
+                let idx = (i + sample) % lens.len();
+                let len = lens[idx];
+                let inputs = timing::inputs_per_sample(len);
+                let batch = (0..inputs).map(|_| make_input(len)).collect::<Vec<_>>();
+
+                log_costs[idx] = normalized_cost(time_batch(entry, batch), len, inputs).ln();
+            }
+
+            let neighbors_mean = log_costs[1..].iter().sum::<f64>() / (lens.len() - 1) as f64;
+            log_costs[0] - neighbors_mean
+        })
+        .collect::<Vec<_>>();
+
+    Ratio::from_log_ratios(pow2_len, &log_ratios)
+}
+
+/// Compares the runtime of `entry` at every power of two length in the configured range to the
+/// neighboring lengths.
+pub fn scan<T: Clone>(
+    entry: &SortEntry<T>,
+    make_input: &dyn Fn(usize) -> Vec<T>,
+    config: &Pow2Config,
+) -> Vec<Pow2Result> {
+    // The smallest neighbor has to be a length that can be sorted.
+    let min_exp = config.min_exp.max(5);
+
+    (min_exp..=config.max_exp)
+        .map(|exp| {
+            let vs_neighbors = measure_pow2(entry, make_input, 1 << exp, config.samples);
+            let is_cliff = vs_neighbors.is_significant()
+                && (vs_neighbors.ratio - 1.0).abs() > config.threshold;
+
+            Pow2Result {
+                vs_neighbors,
+                is_cliff,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+//! Deterministic splitting of fuzz, stress and benchmark campaigns across machines.
+//!
+//! A [`Shard`] is written as `<index>/<count>`, eg. `3/16`, with the index counting from 1. Every
+//! machine runs the same command with its own index, the shards are disjoint and together cover the
+//! whole campaign, without any coordination between the machines:
+//!
+//! - `sort-research fuzz --shard=3/16` and `STRESS_SHARD=3/16` take every seed `s` with
+//!   `s % 16 == 2`. `--iterations` counts the seeds of the whole campaign, `STRESS_CASES` the cases
+//!   of the shard.
+//! - `BENCH_SHARD=3/16`, or `--shard` of `sort-research bench` and util/run_benchmarks.py, takes a
+//!   benchmark by a hash of its name without the sort, so all sorts of one type, pattern and len
+//!   run on the same machine and stay comparable to each other.
+//!
+//! Fuzz and stress shards are merged by concatenating their certificate files, see
+//! [`super::certificate`], benchmark shards with `util/run_benchmarks.py --merge`.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// Counting from 0, the 1 based index is only used in the text form.
+    index: u64,
+    count: u64,
+}
+
+impl Shard {
+    /// The shard that covers everything.
+    pub const ALL: Shard = Shard { index: 0, count: 1 };
+
+    /// Shard `index` of `count`, counting from 0. Panics if `index >= count`.
+    pub fn new(index: u64, count: u64) -> Self {
+        assert!(
+            index < count,
+            "shard {index} out of range for {count} shards"
+        );
+        Self { index, count }
+    }
+
+    pub fn index(self) -> u64 {
+        self.index
+    }
+
+    pub fn count(self) -> u64 {
+        self.count
+    }
+
+    pub fn contains_seed(self, seed: u64) -> bool {
+        seed % self.count == self.index
+    }
+
+    /// The smallest seed of this shard that is `>= seed`, saturating at `u64::MAX`.
+    pub fn next_seed(self, seed: u64) -> u64 {
+        let skip = (self.index + self.count - seed % self.count) % self.count;
+        seed.saturating_add(skip)
+    }
+
+    /// Whether the benchmark `name`, `<sort>-<mode>-<type>-<pattern>-<len>`, belongs to this shard.
+    /// The sort is not part of the key.
+    pub fn contains_bench(self, name: &str) -> bool {
+        let key = name.split_once('-').map_or(name, |(_, rest)| rest);
+        mix(fnv1a(key.as_bytes())) % self.count == self.index
+    }
+}
+
+// Stable across platforms and Rust versions, unlike the std hashers.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// The splitmix64 finalizer. FNV-1a alone mixes the last bytes poorly, names that only differ in
+// the len would land in few shards.
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index + 1, self.count)
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid shard '{val}', expected <index>/<count> eg. 3/16");
+        let (index, count) = val.split_once('/').ok_or_else(invalid)?;
+        let index = index.parse::<u64>().map_err(|_| invalid())?;
+        let count = count.parse::<u64>().map_err(|_| invalid())?;
+
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+
+        Ok(Self::new(index - 1, count))
+    }
+}
@@ -0,0 +1,199 @@
+//! Ring buffer of the last inputs handed to the sorts, written to disk before each sort call.
+//!
+//! A sort that segfaults or aborts takes the process down before anything can be reported, the
+//! panic hook never runs and the output of the fuzzer stops mid-line. With snapshots enabled the
+//! newest file in the snapshot dir is the input that crashed, and the older ones are the inputs
+//! right before it, in case the crash is caused by state left behind by an earlier call.
+//!
+//! The inputs are stored as the i32 pattern values, before the transform to the element type, so
+//! they can be replayed through [`crate::analysis::inputs::with_type`].
+//!
+//! Format, all integers little endian: the magic `SRSNAP01`, u64 sequence number, u8 descending
+//! flag, the sort name, type name and label as u32 len followed by UTF-8 bytes, u64 input len
+//! followed by the i32 values.
+
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"SRSNAP01";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Increases with every recorded snapshot, the highest one is the most recent input.
+    pub sequence: u64,
+    pub sort_name: String,
+    pub type_name: String,
+    /// Sorted with `sort_by` in descending order instead of `sort`.
+    pub descending: bool,
+    /// Free-form description of the input, eg. the seed or pattern it was generated from.
+    pub label: String,
+    pub input: Vec<i32>,
+}
+
+impl Snapshot {
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&self.sequence.to_le_bytes())?;
+        w.write_all(&[self.descending as u8])?;
+
+        for text in [&self.sort_name, &self.type_name, &self.label] {
+            w.write_all(&(text.len() as u32).to_le_bytes())?;
+            w.write_all(text.as_bytes())?;
+        }
+
+        w.write_all(&(self.input.len() as u64).to_le_bytes())?;
+        for val in &self.input {
+            w.write_all(&val.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from(r: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a snapshot file"));
+        }
+
+        let sequence = u64::from_le_bytes(read_array(r)?);
+        let descending = read_array::<1>(r)?[0] != 0;
+
+        let mut read_text = || -> io::Result<String> {
+            let len = u32::from_le_bytes(read_array(r)?) as u64;
+            let bytes = read_exact_len(r, len)?;
+            String::from_utf8(bytes).map_err(|_| invalid_data("text is not valid UTF-8"))
+        };
+        let sort_name = read_text()?;
+        let type_name = read_text()?;
+        let label = read_text()?;
+
+        let len = u64::from_le_bytes(read_array(r)?);
+        let byte_len = len
+            .checked_mul(size_of::<i32>() as u64)
+            .ok_or_else(|| invalid_data("input len out of range"))?;
+        let input = read_exact_len(r, byte_len)?
+            .chunks_exact(size_of::<i32>())
+            .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+
+        Ok(Self {
+            sequence,
+            sort_name,
+            type_name,
+            descending,
+            label,
+            input,
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::read_from(&mut BufReader::new(fs::File::open(path)?))
+    }
+}
+
+/// Keeps the last `capacity` snapshots in `dir`, as `input-<slot>.bin` files.
+pub struct SnapshotRing {
+    dir: PathBuf,
+    capacity: usize,
+    sequence: u64,
+}
+
+impl SnapshotRing {
+    /// Creates `dir` if needed. Snapshots of an earlier run in the same dir are removed, otherwise
+    /// their higher sequence numbers would hide the newest snapshot of this run.
+    pub fn new(dir: impl Into<PathBuf>, capacity: usize) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        for dir_entry in fs::read_dir(&dir)? {
+            let path = dir_entry?.path();
+            if is_snapshot_file(&path) {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(Self {
+            dir,
+            capacity: capacity.max(1),
+            sequence: 0,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes `snapshot` with the next sequence number into the oldest slot and returns its path.
+    ///
+    /// The file is written under a temporary name and then renamed, so a crash while writing
+    /// leaves the previous contents of the slot intact.
+    pub fn record(&mut self, snapshot: &mut Snapshot) -> io::Result<PathBuf> {
+        snapshot.sequence = self.sequence;
+        let slot = (self.sequence % self.capacity as u64) as usize;
+        self.sequence += 1;
+
+        let path = self.dir.join(format!("input-{slot:03}.bin"));
+        let tmp_path = path.with_extension("tmp");
+
+        let mut writer = BufWriter::new(fs::File::create(&tmp_path)?);
+        snapshot.write_to(&mut writer)?;
+        writer.into_inner().map_err(|err| err.into_error())?;
+        fs::rename(&tmp_path, &path)?;
+
+        Ok(path)
+    }
+
+    /// Most recent snapshot in `dir`, the input that was being sorted when the process died.
+    pub fn latest(dir: &Path) -> io::Result<Option<Snapshot>> {
+        let mut latest: Option<Snapshot> = None;
+
+        for dir_entry in fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            if is_snapshot_file(&path) {
+                let snapshot = Snapshot::load(&path)?;
+                if latest
+                    .as_ref()
+                    .is_none_or(|latest| snapshot.sequence > latest.sequence)
+                {
+                    latest = Some(snapshot);
+                }
+            }
+        }
+
+        Ok(latest)
+    }
+}
+
+fn is_snapshot_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("input-") && name.ends_with(".bin"))
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads exactly `len` bytes. The buffer grows with the bytes that are actually there, a corrupt
+/// len prefix can't make it allocate more than the rest of the input.
+fn read_exact_len(r: &mut impl Read, len: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    r.take(len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "len prefix is longer than the rest of the snapshot",
+        ));
+    }
+
+    Ok(bytes)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
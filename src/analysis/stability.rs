@@ -0,0 +1,115 @@
+//! Classifies sorts as stable or unstable by their results, and cross-checks the result against
+//! the declared [`SortEntry::is_stable`].
+//!
+//! The results of a sort look the same whether it is stable or not, as long as equal elements
+//! can't be told apart. Registering the unstable entry point of an FFI sort under the stable name
+//! would only be noticed by the stability tests, and the test suite is instantiated for one sort
+//! at a time, see tests/main.rs. Here every sort in the registry runs on inputs with many
+//! duplicates, tagged with their original position, and the equal elements have to keep their
+//! order.
+//!
+//! A single reordering proves that a sort is unstable. Keeping the order is only evidence of
+//! stability, but the longer inputs have enough duplicates that every unstable sort in this
+//! repository reorders some of them.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use sort_test_tools::patterns;
+
+use crate::registry::SortEntry;
+use crate::verify;
+
+const TEST_LENS: &[usize] = &[2, 3, 5, 8, 16, 17, 20, 32, 50, 100, 500, 1_000, 10_000];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stability {
+    /// Equal elements kept their order on all inputs.
+    Stable,
+    /// Equal elements were reordered, first at an input of this len.
+    Unstable { len: usize },
+    /// The result of an input of this len was not sorted, so the stability can't be judged.
+    Incorrect { len: usize },
+    /// The sort panicked, eg. because it has no `sort_by` for `u64`.
+    Unsupported,
+}
+
+impl fmt::Display for Stability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stable => write!(f, "stable"),
+            Self::Unstable { len } => write!(f, "unstable at len {len}"),
+            Self::Incorrect { len } => write!(f, "not sorted at len {len}"),
+            Self::Unsupported => write!(f, "unsupported, sort_by panicked"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Classification {
+    pub declared_stable: bool,
+    pub observed: Stability,
+}
+
+impl Classification {
+    /// Returns `true` if the observed stability contradicts the declared one, or the sort didn't
+    /// sort at all. Unsupported sorts are skipped, they can't fail.
+    pub fn is_failure(&self) -> bool {
+        match self.observed {
+            Stability::Stable => !self.declared_stable,
+            Stability::Unstable { .. } => self.declared_stable,
+            Stability::Incorrect { .. } => true,
+            Stability::Unsupported => false,
+        }
+    }
+}
+
+pub fn classify(entry: &SortEntry<u64>) -> Classification {
+    Classification {
+        declared_stable: entry.is_stable(),
+        observed: observe(entry),
+    }
+}
+
+fn observe(entry: &SortEntry<u64>) -> Stability {
+    for &len in TEST_LENS {
+        for input in tagged_inputs(len) {
+            let mut v = input.clone();
+            let result = panic::catch_unwind(AssertUnwindSafe(|| entry.sort_by(&mut v, cmp_key)));
+
+            if result.is_err() {
+                return Stability::Unsupported;
+            }
+
+            if !verify::is_sorted_by(&v, cmp_key) || !verify::same_elements(&input, &v) {
+                return Stability::Incorrect { len };
+            }
+
+            if !verify::is_stable_sorted(&input, &v, cmp_key) {
+                return Stability::Unstable { len };
+            }
+        }
+    }
+
+    Stability::Stable
+}
+
+// The key is in the upper and the original position in the lower half. Packing both into a u64
+// instead of using a tuple allows testing the FFI sorts, which only support a few types.
+fn cmp_key(a: &u64, b: &u64) -> std::cmp::Ordering {
+    (a >> 32).cmp(&(b >> 32))
+}
+
+fn tagged_inputs(len: usize) -> [Vec<u64>; 3] {
+    let few_keys = patterns::random_uniform(len, 0..=9);
+    let many_keys = patterns::random_uniform(len, 0..=(len / 4) as i32);
+    // Descending pairs of equal keys, a sort that reverses descending runs reorders every pair.
+    let descending_pairs = (0..len).map(|i| ((len - i) / 2) as i32).collect();
+
+    [few_keys, many_keys, descending_pairs].map(|keys: Vec<i32>| {
+        keys.into_iter()
+            .enumerate()
+            .map(|(i, key)| ((key as u64) << 32) | i as u64)
+            .collect()
+    })
+}
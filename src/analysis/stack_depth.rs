@@ -0,0 +1,182 @@
+//! Measures how deep into the stack a sort goes, as seen from the comparison function.
+//!
+//! Every comparison records the address of a local variable, the distance between the lowest one
+//! and a local of the caller is the stack the sort used at that point, plus the constant frames of
+//! the comparison call itself. Sorts spend the deepest part of their recursion comparing elements,
+//! so this is close to the peak, and unlike recursion counters it works for every sort in the
+//! registry, including the C and C++ ones. Embedded and kernel users run with small fixed stacks,
+//! a sort that recurses on the larger side of a partition, or without a depth limit, overflows them
+//! on adversarial inputs.
+//!
+//! The comparison function only sees the frames that are live while comparing, stack a sort uses
+//! for something else, such as the buffers C sorts put on the stack with alloca or VLAs, is missed.
+//! [`measure_painted`] covers that, it runs the sort on a dedicated thread after filling the stack
+//! below it with a known byte and afterwards checks how far down it was overwritten.
+//!
+//! For an exact recursion depth of the instrumented Rust sorts see `instrument::depth`.
+
+use std::hint::black_box;
+use std::ptr;
+use std::thread;
+
+use crate::analysis::{adversary, inputs};
+use crate::registry::{self, SortEntry};
+
+/// Bytes of stack `entry` used below the caller while sorting `v`, 0 if it made no comparison.
+/// Assumes the stack grows downwards, as on every platform this crate supports.
+#[inline(never)]
+pub fn measure<T>(entry: &SortEntry<T>, v: &mut [T]) -> usize
+where
+    T: Ord,
+{
+    let base_marker = 0u8;
+    let base = black_box(&base_marker) as *const u8 as usize;
+    let mut lowest = base;
+
+    entry.sort_by(v, |a, b| {
+        let marker = 0u8;
+        lowest = lowest.min(black_box(&marker) as *const u8 as usize);
+        a.cmp(b)
+    });
+
+    base - lowest
+}
+
+/// Stack size of the thread [`measure_painted`] sorts on.
+pub const THREAD_STACK_BYTES: usize = 8 << 20;
+
+/// Bytes below the sorting frame that [`measure_painted`] paints, it reports `None` for sorts that
+/// use more. Half the thread stack leaves room for the frames std puts above the closure.
+pub const PAINTED_BYTES: usize = THREAD_STACK_BYTES / 2;
+
+// Left unpainted directly below the sorting frame, so that painting doesn't overwrite the frame of
+// `paint` itself. Any stack use up to this is reported as `PAINT_GAP`.
+const PAINT_GAP: usize = 1024;
+
+const PAINT: u8 = 0xA5;
+
+/// Bytes of stack the sort `sort_name` used while sorting `v`, measured by painting the stack of a
+/// dedicated thread, or `None` if it used more than [`PAINTED_BYTES`]. Unlike [`measure`] this sees
+/// all stack use, not only what is live during comparisons. It has a resolution of 1 KiB at the
+/// low end and can undercount by the bytes the sort happens to write with the paint value.
+///
+/// The entry is looked up in the sort thread, as [`SortEntry`] is not `Send`. A sort that uses more
+/// than [`THREAD_STACK_BYTES`] overflows the thread stack and aborts the process.
+pub fn measure_painted<T>(sort_name: &str, mut v: Vec<T>) -> Option<usize>
+where
+    T: Ord + Send + 'static,
+{
+    let sort_name = sort_name.to_string();
+
+    thread::Builder::new()
+        .stack_size(THREAD_STACK_BYTES)
+        .spawn(move || {
+            let entry = registry::find::<T>(&sort_name)
+                .unwrap_or_else(|| panic!("Unknown sort '{sort_name}'"));
+
+            let base = stack_address();
+            let top = base - PAINT_GAP;
+            let bottom = top - PAINTED_BYTES;
+
+            // SAFETY: [bottom, top) lies inside the stack of this thread, which is at least
+            // THREAD_STACK_BYTES large, below every live frame and above the guard page. Accessing
+            // memory below the stack pointer is outside of what Rust defines, the volatile accesses
+            // keep the compiler from reasoning about it and the platforms we run on don't touch it.
+            unsafe { paint(bottom, top) };
+            entry.sort(black_box(&mut v));
+            // SAFETY: See above.
+            let lowest_used = unsafe { lowest_overwritten(bottom, top) };
+
+            match lowest_used {
+                Some(addr) if addr == bottom => None,
+                Some(addr) => Some(base - addr),
+                None => Some(PAINT_GAP),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    black_box(&marker) as *const u8 as usize
+}
+
+// Writes from the top down, so the pages are touched in the order the stack would grow into them,
+// which Windows needs to commit them through its guard page.
+#[inline(never)]
+unsafe fn paint(bottom: usize, top: usize) {
+    for addr in (bottom..top).rev() {
+        ptr::write_volatile(addr as *mut u8, PAINT);
+    }
+}
+
+#[inline(never)]
+unsafe fn lowest_overwritten(bottom: usize, top: usize) -> Option<usize> {
+    (bottom..top).find(|&addr| ptr::read_volatile(addr as *const u8) != PAINT)
+}
+
+/// The most stack a sort that used `small_bytes` at a smaller len may use at `len`, if it limits
+/// its recursion to `2 * log2(len)` levels like introsort and the sorts derived from it. The stack
+/// used at the smaller len covers the constant frames and at least one recursion level, so every
+/// further level adds at most `small_bytes`, whatever the frame size of the sort.
+pub fn depth_limit_bound(small_bytes: usize, len: usize) -> usize {
+    small_bytes * 2 * len.max(2).ilog2() as usize
+}
+
+/// Patterns of [`inputs::pattern_by_name`] that [`worst_case`] tries in addition to the adversary
+/// input. Sorted and reverse sorted inputs make naive quicksorts degenerate, few distinct values
+/// the ones without three way partitioning.
+pub const PATTERNS: &[&str] = &[
+    "random",
+    "ascending",
+    "descending",
+    "random_d20",
+    "saws_long",
+    "pipe_organ",
+    "all_equal",
+];
+
+/// The largest [`measure`] of `entry` over the [`PATTERNS`] and the input synthesized against it
+/// by [`adversary::synthesize_for`], as `(input name, bytes)`.
+pub fn worst_case(entry: &SortEntry<i32>, len: usize) -> (&'static str, usize) {
+    let mut worst = ("adversary", 0);
+
+    for (name, mut v) in worst_case_inputs(entry, len) {
+        let bytes = measure(entry, &mut v);
+        if bytes > worst.1 {
+            worst = (name, bytes);
+        }
+    }
+
+    worst
+}
+
+/// The largest [`measure_painted`] of `entry` over the same inputs as [`worst_case`], `None` if
+/// one of them used more than [`PAINTED_BYTES`].
+pub fn worst_case_painted(entry: &SortEntry<i32>, len: usize) -> (&'static str, Option<usize>) {
+    let mut worst = ("adversary", 0);
+
+    for (name, v) in worst_case_inputs(entry, len) {
+        match measure_painted(entry.name(), v) {
+            Some(bytes) if bytes > worst.1 => worst = (name, bytes),
+            Some(_) => {}
+            None => return (name, None),
+        }
+    }
+
+    (worst.0, Some(worst.1))
+}
+
+fn worst_case_inputs(entry: &SortEntry<i32>, len: usize) -> Vec<(&'static str, Vec<i32>)> {
+    let mut cases = vec![("adversary", adversary::synthesize_for(entry, len))];
+    cases.extend(
+        PATTERNS
+            .iter()
+            .map(|&name| (name, inputs::pattern_by_name(name).unwrap()(len))),
+    );
+
+    cases
+}
@@ -0,0 +1,271 @@
+//! Randomized long running checks of the registered sorts, for the `stress` test binary in
+//! tests/stress.rs.
+//!
+//! The test suite checks a bounded set of patterns and lengths, bugs that need a rare combination
+//! of both slip through. Every stress case is derived from its seed alone: a length picked
+//! uniformly in log space up to [`DEFAULT_MAX_LEN`], and an input composed of one to four segments
+//! of different patterns, eg. a descending run followed by few distinct random values. Half of the
+//! cases also inject a fault into the comparison function of the Rust sorts with a
+//! [`fault::FaultInjector`], see [`Fault`]. The C and C++ sorts are not meant to survive either, an
+//! inconsistent comparator can make them read out of bounds and a panic can't unwind through them.
+//!
+//! The progress is written to a [`Checkpoint`] file before every sort call, so a run that is
+//! interrupted or crashes continues with the next case, and reports the one it crashed on.
+
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use sort_test_tools::xorshift::XorShift;
+
+use crate::fault::{self, FaultInjector, Trigger};
+use crate::registry::SortEntry;
+
+/// 10^8, 400MB of i32 per input. The input, the expected result, the copy that is sorted and
+/// the check need four times that.
+pub const DEFAULT_MAX_LEN: usize = 100_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Inverts the result of every `period`-th comparison, which makes the order inconsistent.
+    Inconsistent { period: u64 },
+    /// Panics in comparison number `at`, counting from 0.
+    Panic { at: u64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct Case {
+    pub seed: u64,
+    /// The patterns of the segments, eg. `descending:1000+random_d4:24`.
+    pub label: String,
+    pub input: Vec<i32>,
+    /// The input sorted by the std sort.
+    pub expected: Vec<i32>,
+    pub fault: Option<Fault>,
+}
+
+impl Case {
+    /// The case for `seed`, with at most `max_len` elements.
+    pub fn new(seed: u64, max_len: usize) -> Self {
+        let mut rng = XorShift::new(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+
+        let len_log = rng.below(max_len.max(1).ilog2() as usize + 2);
+        let len = rng.below((1 << len_log).min(max_len + 1));
+
+        let segment_count = 1 + rng.below(4).min(len.saturating_sub(1));
+        let mut input = Vec::with_capacity(len);
+        let mut labels = Vec::with_capacity(segment_count);
+        for segment in 0..segment_count {
+            let segment_len = if segment + 1 == segment_count {
+                len - input.len()
+            } else {
+                rng.below(len - input.len() + 1)
+            };
+
+            labels.push(format!(
+                "{}:{segment_len}",
+                push_segment(&mut input, segment_len, &mut rng)
+            ));
+        }
+
+        let fault = match rng.below(4) {
+            0 => Some(Fault::Inconsistent {
+                period: 1 + rng.below(64) as u64,
+            }),
+            1 => Some(Fault::Panic {
+                at: rng.below(len * (len.max(2).ilog2() as usize + 1) + 1) as u64,
+            }),
+            _ => None,
+        };
+
+        let mut expected = input.clone();
+        expected.sort_unstable();
+
+        Self {
+            seed,
+            label: labels.join("+"),
+            input,
+            expected,
+            fault,
+        }
+    }
+
+    /// The fault if it applies to `entry`, the C and C++ sorts only get correct comparators.
+    pub fn fault_for(&self, entry: &SortEntry<i32>) -> Option<Fault> {
+        self.fault.filter(|_| entry.name().starts_with("rust_"))
+    }
+
+    /// Sorts a copy of the input with `entry` and returns why the result is wrong, or `None` if it
+    /// is correct. With a fault the sort may panic and the order is unspecified, but the result has
+    /// to be a permutation of the input.
+    pub fn check(&self, entry: &SortEntry<i32>) -> Option<String> {
+        let fault = self.fault_for(entry);
+        let mut actual = self.input.clone();
+
+        let mut injector = FaultInjector::with_seed(self.seed);
+        match fault {
+            None => {}
+            Some(Fault::Inconsistent { period }) => {
+                injector = injector.inject(Trigger::Every(period), fault::Fault::Reverse);
+            }
+            Some(Fault::Panic { at }) => {
+                injector = injector.inject(Trigger::At(at), fault::Fault::Panic);
+            }
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match fault {
+            None => entry.sort(&mut actual),
+            Some(_) => entry.sort_by(&mut actual, injector.wrap(|a: &i32, b| a.cmp(b))),
+        }));
+
+        if result.is_ok() && fault.is_none_or(|fault| matches!(fault, Fault::Panic { .. })) {
+            // Without a fault, or if the sort finished before the injected panic, the result has
+            // to be sorted.
+            if actual != self.expected {
+                return Some(format!("result is not the sorted input, fault: {fault:?}"));
+            }
+            return None;
+        }
+
+        if result.is_err() && fault.is_none() {
+            return Some("panic without fault".to_string());
+        }
+
+        // The order is unspecified and the sort may panic, as ipnsort does when it detects the
+        // inconsistent comparator, but no element may be lost or duplicated.
+        actual.sort_unstable();
+        if actual != self.expected {
+            return Some(format!(
+                "result has different elements than the input, fault: {fault:?}"
+            ));
+        }
+
+        None
+    }
+}
+
+fn push_segment(input: &mut Vec<i32>, len: usize, rng: &mut XorShift) -> String {
+    let start = input.len();
+
+    let name = match rng.below(8) {
+        0 => {
+            input.extend((0..len).map(|_| rng.next_u64() as i32));
+            "random".to_string()
+        }
+        1 => {
+            let distinct = 1 << rng.below(12);
+            input.extend((0..len).map(|_| rng.below(distinct) as i32));
+            format!("random_d{distinct}")
+        }
+        2 => {
+            let base = rng.next_u64() as i32 / 2;
+            input.extend((0..len).map(|i| base.wrapping_add(i as i32)));
+            "ascending".to_string()
+        }
+        3 => {
+            let base = rng.next_u64() as i32 / 2;
+            input.extend((0..len).map(|i| base.wrapping_sub(i as i32)));
+            "descending".to_string()
+        }
+        4 => {
+            input.extend(std::iter::repeat_n(rng.next_u64() as i32, len));
+            "all_equal".to_string()
+        }
+        5 => {
+            let saw_len = 2 + rng.below(64);
+            input.extend((0..len).map(|i| (i % saw_len) as i32));
+            format!("saw{saw_len}")
+        }
+        6 => {
+            input.extend((0..len).map(|i| i.min(len - i) as i32));
+            "pipe_organ".to_string()
+        }
+        _ => {
+            // Sorted with a few random elements swapped in.
+            input.extend(0..len as i32);
+            for _ in 0..(len / 20) {
+                let (a, b) = (rng.below(len), rng.below(len));
+                input.swap(start + a, start + b);
+            }
+            "random_s95".to_string()
+        }
+    };
+
+    debug_assert_eq!(input.len(), start + len);
+    name
+}
+
+/// Progress of a stress run, written as `key value` lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The seed of the first case that hasn't been completed.
+    pub next_seed: u64,
+    pub cases: u64,
+    pub sort_calls: u64,
+    pub elements: u64,
+    pub failures: u64,
+    pub elapsed_secs: u64,
+    /// `<seed> <sort name>` of the sort call in progress, set if the run crashed.
+    pub running: Option<String>,
+}
+
+impl Checkpoint {
+    /// Loads the checkpoint at `path`, or `None` if there is no file.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: invalid line '{line}'", path.display()),
+            )
+        };
+
+        let mut checkpoint = Self::default();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').ok_or_else(|| invalid(line))?;
+            if key == "running" {
+                checkpoint.running = Some(value.to_string());
+                continue;
+            }
+
+            let value = value.parse::<u64>().map_err(|_| invalid(line))?;
+            match key {
+                "next_seed" => checkpoint.next_seed = value,
+                "cases" => checkpoint.cases = value,
+                "sort_calls" => checkpoint.sort_calls = value,
+                "elements" => checkpoint.elements = value,
+                "failures" => checkpoint.failures = value,
+                "elapsed_secs" => checkpoint.elapsed_secs = value,
+                _ => return Err(invalid(line)),
+            }
+        }
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Writes the checkpoint to `path`, replacing the previous one atomically.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut text = format!(
+            "next_seed {}\ncases {}\nsort_calls {}\nelements {}\nfailures {}\nelapsed_secs {}\n",
+            self.next_seed,
+            self.cases,
+            self.sort_calls,
+            self.elements,
+            self.failures,
+            self.elapsed_secs
+        );
+        if let Some(running) = &self.running {
+            text.push_str(&format!("running {running}\n"));
+        }
+
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, text)?;
+        fs::rename(&tmp_path, path)
+    }
+}
@@ -0,0 +1,110 @@
+//! Measures how much unstable sorts permute equal elements, as the normalized Kendall tau distance
+//! between the original and the sorted order within every group of equal keys.
+//!
+//! A stable sort has distance 0. A sort that shuffles every tie group uniformly has an expected
+//! distance of 0.5, one that reverses every group has 1. Some unstable sorts are "practically
+//! stable" on some patterns, eg. because they only partition inputs with few equal elements, and
+//! this quantifies by how much.
+//!
+//! The inputs are tagged u64 like in [`crate::analysis::stability`], so the FFI sorts can be
+//! measured as well.
+
+use std::cmp::Ordering;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::registry::SortEntry;
+use crate::verify;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TieOrder {
+    /// Pairs of elements with equal keys.
+    pub tie_pairs: u64,
+    /// Pairs of elements with equal keys that are in the opposite order after sorting.
+    pub inverted_pairs: u64,
+}
+
+impl TieOrder {
+    /// Normalized Kendall tau distance, 0 if there are no ties.
+    pub fn distance(&self) -> f64 {
+        if self.tie_pairs == 0 {
+            return 0.0;
+        }
+
+        self.inverted_pairs as f64 / self.tie_pairs as f64
+    }
+}
+
+/// Sorts `keys` tagged with their original position by `entry` and measures how the tie groups
+/// were permuted. Returns `None` if the sort panicked or the result is not sorted.
+pub fn measure(entry: &SortEntry<u64>, keys: &[i32]) -> Option<TieOrder> {
+    let input = tag(keys);
+    let mut v = input.clone();
+
+    panic::catch_unwind(AssertUnwindSafe(|| entry.sort_by(&mut v, cmp_key))).ok()?;
+
+    if !verify::is_sorted_by(&v, cmp_key) || !verify::same_elements(&input, &v) {
+        return None;
+    }
+
+    Some(tie_order(&v))
+}
+
+/// Measures the order of the tie groups in the sorted and tagged `v`.
+pub fn tie_order(v: &[u64]) -> TieOrder {
+    let mut result = TieOrder::default();
+    let mut positions = Vec::new();
+
+    for group in v.chunk_by(|a, b| cmp_key(a, b) == Ordering::Equal) {
+        let len = group.len() as u64;
+        result.tie_pairs += len * (len - 1) / 2;
+
+        positions.clear();
+        positions.extend(group.iter().map(|tagged| *tagged as u32));
+        result.inverted_pairs += count_inversions(&mut positions);
+    }
+
+    result
+}
+
+// The key is in the upper and the original position in the lower half, see stability::cmp_key.
+pub(crate) fn cmp_key(a: &u64, b: &u64) -> Ordering {
+    (a >> 32).cmp(&(b >> 32))
+}
+
+pub(crate) fn tag(keys: &[i32]) -> Vec<u64> {
+    keys.iter()
+        .enumerate()
+        // Flipping the sign bit keeps the order of negative keys.
+        .map(|(i, &key)| (((key as u32 ^ (1 << 31)) as u64) << 32) | i as u64)
+        .collect()
+}
+
+/// Counts the pairs `i < j` with `v[i] > v[j]` with a merge sort, in `O(N x log(N))`. Sorts `v`.
+fn count_inversions(v: &mut [u32]) -> u64 {
+    if v.len() < 2 {
+        return 0;
+    }
+
+    let mid = v.len() / 2;
+    let mut inversions = count_inversions(&mut v[..mid]) + count_inversions(&mut v[mid..]);
+
+    let mut merged = Vec::with_capacity(v.len());
+    let (left, right) = v.split_at(mid);
+    let (mut l, mut r) = (0, 0);
+    while l < left.len() && r < right.len() {
+        if right[r] < left[l] {
+            // Every remaining element of the left half is larger.
+            inversions += (left.len() - l) as u64;
+            merged.push(right[r]);
+            r += 1;
+        } else {
+            merged.push(left[l]);
+            l += 1;
+        }
+    }
+    merged.extend_from_slice(&left[l..]);
+    merged.extend_from_slice(&right[r..]);
+
+    v.copy_from_slice(&merged);
+    inversions
+}
@@ -0,0 +1,60 @@
+//! Runtime measurements shared by the analyses that compare timings.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use crate::registry::SortEntry;
+
+// Two-sided 95% confidence.
+const Z_95: f64 = 1.96;
+
+// Sorting a small input takes less time than the timer resolution warrants, so each timed sample
+// sorts several inputs.
+const MIN_ELEMENTS_PER_SAMPLE: usize = 4096;
+
+/// Ratio of two runtimes, as geometric mean over paired samples with a 95% confidence interval.
+#[derive(Clone, Copy, Debug)]
+pub struct Ratio {
+    pub len: usize,
+    pub ratio: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+impl Ratio {
+    /// `log_ratios` are the natural logarithms of the per sample ratios.
+    pub(crate) fn from_log_ratios(len: usize, log_ratios: &[f64]) -> Self {
+        let n = log_ratios.len() as f64;
+        let mean = log_ratios.iter().sum::<f64>() / n;
+        let variance = log_ratios.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let std_err = (variance / n).sqrt();
+
+        Self {
+            len,
+            ratio: mean.exp(),
+            ci_low: (mean - Z_95 * std_err).exp(),
+            ci_high: (mean + Z_95 * std_err).exp(),
+        }
+    }
+
+    /// The confidence interval excludes 1.
+    pub fn is_significant(&self) -> bool {
+        self.ci_high < 1.0 || self.ci_low > 1.0
+    }
+}
+
+pub(crate) fn inputs_per_sample(len: usize) -> usize {
+    (MIN_ELEMENTS_PER_SAMPLE / len.max(1)).max(1)
+}
+
+/// Seconds it takes `entry` to sort all inputs in `batch`.
+pub(crate) fn time_batch<T>(entry: &SortEntry<T>, mut batch: Vec<Vec<T>>) -> f64 {
+    let start = Instant::now();
+    for v in batch.iter_mut() {
+        entry.sort(black_box(v.as_mut_slice()));
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    black_box(batch);
+
+    elapsed.max(f64::MIN_POSITIVE)
+}
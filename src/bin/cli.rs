@@ -0,0 +1,1476 @@
+//! Single entry point for the tooling in this repository. Every command works on the sorts in the
+//! registry, so they all accept the same sort, type, pattern and len selection.
+//!
+//! cargo run --release --bin sort-research -- <command> [args] [--option=value]
+//!
+//! `bench` and `graph` drive the benchmark harness and the Python scripts in util, everything else
+//! runs in-process. Run without arguments for the list of commands and options.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process::{self, Command};
+
+use sort_research_rs::analysis::certificate::{
+    self, Certificate, CertificateWriter, Generator, Source,
+};
+use sort_research_rs::analysis::crossover::CrossoverFinder;
+use sort_research_rs::analysis::inputs::{self, PatternFn, TypeVisitor};
+use sort_research_rs::analysis::merge_sim::{self, Policy};
+use sort_research_rs::analysis::shard::Shard;
+use sort_research_rs::analysis::snapshot::{Snapshot, SnapshotRing};
+use sort_research_rs::analysis::{
+    adversary, cardinality, corpus, perf_fuzz, permutation, pow2_bias, stability, stack_depth,
+    stress, tie_order,
+};
+use sort_research_rs::instrument::comp::CountingCmp;
+use sort_research_rs::registry::{self, SortEntry};
+use sort_research_rs::verify;
+use sort_test_tools::xorshift::XorShift;
+use sort_test_tools::{patterns, test_sizes};
+
+const USAGE: &str = "Usage: sort-research <command> [args] [--option=value]
+
+Commands:
+  bench                     Run the criterion benchmarks, see benches/bench.rs
+  test                      Check every selected sort on every type, pattern and len
+  fuzz                      Check the sorts on random inputs against the std sort
+  replay <snapshot>         Sort the input of a snapshot file, or the newest one in a dir
+  corpus list               Print the regression inputs in corpus/
+  corpus add <file> <name>  Add a snapshot file, or the newest one in a dir, to corpus/
+  corpus adversary <sort>   Add the adversary inputs of a sort to corpus/
+  export <pattern> <len> <file>
+                            Write the values of a pattern to a file, for BENCH_PATTERN_FILE
+  analyze cardinality       Runtime per number of distinct values, from 1 to len
+  analyze comp              Print the mean comparison count per input
+  analyze crossover <a> <b> Find the len at which one sort overtakes the other
+  analyze merges            Simulate the merge cost of the merge policies on the runs of inputs
+  analyze moves             Count the element moves, clones and drops of the sorts
+  analyze permutation       Cycle statistics of the permutation the sorts apply to their input
+  analyze pow2              Find runtime cliffs at power of two lengths
+  analyze stability         Check the declared stability of the sorts against their results
+  analyze stack             Measure the deepest stack use of the sorts on worst-case inputs
+  analyze ties              Measure how much the unstable sorts reorder equal elements
+  compare <a> <b>           Runtime ratio of two sorts per type, pattern and len
+  graph <result.json>...    Create graphs from results of util/run_benchmarks.py
+  list                      Print the sorts enabled in this build
+  workload <audit log>      Turn a SORT_AUDIT_LOG file into a BENCH_WORKLOAD histogram
+  verify <certificates>     Run the sort calls of a certificate file again and compare the results
+
+Options:
+  --sorts=a,b     Sort names, defaults to all sorts enabled in the build
+  --types=a,b     Element types, one of i32, u64, string, 1k, f128
+  --patterns=a,b  Input patterns, eg. random, ascending, saws_long
+  --lens=a,b      Input lengths
+  --shard=i/n     Only the i-th of n disjoint parts of a fuzz or bench run, for splitting a
+                  campaign across machines, see src/analysis/shard.rs
+
+  bench:   --mode=time|comp|instructions|runs|branches|phases|alloc|fallbacks|insertion
+           --features=a,b
+           --save=<test_name>
+  test, fuzz: --snapshot-dir[=<dir>]  --snapshot-count=N
+           --lens and --max-len default to the TEST_SIZES or TEST_SIZE_PROFILE lengths if set
+  fuzz:    --iterations=N  --max-len=N  --seed=N  --perf=comparisons|runtime
+           --save-corpus=<name>, adds the inputs found by --perf to corpus/
+           --certificates=<file>, appends a certificate of every sort call to the file
+  export:  --seed=N, defaults to the seed of the patterns of this process
+  analyze: --min-len=N  --max-len=N
+  analyze stack: --painted, paint the stack of a sort thread instead of sampling it in comparisons
+  analyze cardinality: --samples=N
+  analyze merges: --policies=a,b  --min-run=N  --runs=<file> of run lens, or random runs with
+           --run-count=N  --max-run-len=N  --seed=N
+  compare: --samples=N";
+
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> Self {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+
+        for arg in args {
+            match arg.strip_prefix("--") {
+                Some(option) => {
+                    let (key, val) = option.split_once('=').unwrap_or((option, ""));
+                    options.insert(key.to_string(), val.to_string());
+                }
+                None => positional.push(arg),
+            }
+        }
+
+        Self {
+            positional,
+            options,
+        }
+    }
+
+    fn positional(&self, idx: usize, name: &str) -> &str {
+        self.positional
+            .get(idx)
+            .unwrap_or_else(|| exit_with_error(&format!("Missing argument <{name}>")))
+    }
+
+    fn option(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    fn list(&self, key: &str, default: &[&str]) -> Vec<String> {
+        match self.option(key) {
+            Some(val) => val.split(',').map(String::from).collect(),
+            None => default.iter().map(|val| val.to_string()).collect(),
+        }
+    }
+
+    fn num<N: std::str::FromStr>(&self, key: &str, default: N) -> N {
+        match self.option(key) {
+            Some(val) => val
+                .parse()
+                .unwrap_or_else(|_| exit_with_error(&format!("Invalid value for --{key}: {val}"))),
+            None => default,
+        }
+    }
+
+    fn lens(&self, default: &[usize]) -> Vec<usize> {
+        match self.option("lens") {
+            Some(val) => val
+                .split(',')
+                .map(|len| {
+                    len.parse()
+                        .unwrap_or_else(|_| exit_with_error(&format!("Invalid len: {len}")))
+                })
+                .collect(),
+            None => default.to_vec(),
+        }
+    }
+
+    fn shard(&self) -> Shard {
+        self.option("shard").map_or(Shard::ALL, |val| {
+            val.parse()
+                .unwrap_or_else(|err: String| exit_with_error(&err))
+        })
+    }
+
+    // Without --sorts only the general-purpose sorts, the others can be selected by name.
+    fn sorts<T: Ord + 'static>(&self) -> Vec<SortEntry<T>> {
+        let Some(names) = self.option("sorts") else {
+            return registry::general_sorts::<T>();
+        };
+
+        let entries = registry::sorts::<T>();
+
+        let names = names.split(',').collect::<Vec<_>>();
+        for name in &names {
+            if !entries.iter().any(|entry| entry.name() == *name) {
+                exit_with_error(&format!(
+                    "Unknown sort '{name}', make sure its feature is enabled"
+                ));
+            }
+        }
+
+        entries
+            .into_iter()
+            .filter(|entry| names.contains(&entry.name()))
+            .collect()
+    }
+}
+
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{msg}");
+    process::exit(1);
+}
+
+fn pattern(name: &str) -> PatternFn {
+    inputs::pattern_by_name(name).unwrap_or_else(|| {
+        exit_with_error(&format!(
+            "Unknown pattern '{name}', expected one of {}",
+            inputs::PATTERN_NAMES.join(", ")
+        ))
+    })
+}
+
+fn for_each_type(args: &Args, default: &[&str], mut visit: impl FnMut(&str)) {
+    for type_name in args.list("types", default) {
+        if !inputs::TYPE_NAMES.contains(&type_name.as_str()) {
+            exit_with_error(&format!(
+                "Unknown type '{type_name}', expected one of {}",
+                inputs::TYPE_NAMES.join(", ")
+            ));
+        }
+
+        visit(&type_name);
+    }
+}
+
+fn find_sort<T: Ord + 'static>(name: &str) -> SortEntry<T> {
+    registry::find::<T>(name).unwrap_or_else(|| {
+        exit_with_error(&format!(
+            "Unknown sort '{name}', make sure its feature is enabled"
+        ))
+    })
+}
+
+/// Returns why `actual` is not a correct sort of `input` by `compare`, or `None` if it is.
+fn check_result<T: Ord>(
+    input: &[T],
+    actual: &[T],
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Option<String> {
+    if !verify::same_elements(input, actual) {
+        Some("result has different elements than the input".to_string())
+    } else if !verify::is_sorted_by(actual, compare) {
+        Some("result is not sorted".to_string())
+    } else {
+        None
+    }
+}
+
+/// Sorts a copy of `input` and returns why the result is wrong, including panics, or `None` if it is
+/// correct.
+fn run_checked<T: Ord + Clone>(
+    entry: &SortEntry<T>,
+    input: &[T],
+    descending: bool,
+) -> Option<String> {
+    let mut actual = input.to_vec();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if descending {
+            entry.sort_by(&mut actual, |a, b| b.cmp(a));
+        } else {
+            entry.sort(&mut actual);
+        }
+    }));
+
+    match result {
+        Ok(()) => {
+            let compare: fn(&T, &T) -> Ordering = if descending { |a, b| b.cmp(a) } else { T::cmp };
+            check_result(input, &actual, compare)
+        }
+        Err(payload) => Some(format!("panic: {}", panic_message(&*payload))),
+    }
+}
+
+/// Like run_checked, but sorts through a counting comparison function and returns the certificate
+/// of the run if the result is correct, see analysis::certificate.
+fn run_certified<T: Ord + Clone + Debug>(
+    entry: &SortEntry<T>,
+    type_name: &str,
+    source: Source,
+    input: &[T],
+    descending: bool,
+) -> Result<Certificate, String> {
+    let run = panic::catch_unwind(AssertUnwindSafe(|| {
+        certificate::run(entry, input, descending)
+    }))
+    .map_err(|payload| format!("panic: {}", panic_message(&*payload)))?;
+
+    let compare: fn(&T, &T) -> Ordering = if descending { |a, b| b.cmp(a) } else { T::cmp };
+    if let Some(error) = check_result(input, &run.output, compare) {
+        return Err(error);
+    }
+
+    Ok(Certificate::new(
+        source,
+        entry.name(),
+        type_name,
+        descending,
+        input,
+        &run,
+    ))
+}
+
+/// The snapshot ring selected by --snapshot-dir, defaults to a dir in the temp dir if the option
+/// has no value.
+fn snapshot_ring(args: &Args) -> Option<SnapshotRing> {
+    let dir = match args.option("snapshot-dir")? {
+        "" => env::temp_dir().join("sort-research-snapshots"),
+        dir => dir.into(),
+    };
+
+    let ring = SnapshotRing::new(&dir, args.num("snapshot-count", 16)).unwrap_or_else(|err| {
+        exit_with_error(&format!("Failed to create {}: {err}", dir.display()))
+    });
+    println!("Snapshots: {}", ring.dir().display());
+
+    Some(ring)
+}
+
+/// Records the input before it is handed to the sort, see analysis::snapshot.
+fn record_snapshot(
+    snapshots: Option<&mut SnapshotRing>,
+    sort_name: &str,
+    type_name: &str,
+    descending: bool,
+    label: String,
+    input: &[i32],
+) {
+    let Some(snapshots) = snapshots else {
+        return;
+    };
+
+    let mut snapshot = Snapshot {
+        sequence: 0,
+        sort_name: sort_name.to_string(),
+        type_name: type_name.to_string(),
+        descending,
+        label,
+        input: input.to_vec(),
+    };
+
+    if let Err(err) = snapshots.record(&mut snapshot) {
+        exit_with_error(&format!("Failed to write snapshot: {err}"));
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload")
+}
+
+// --- bench ---
+
+fn bench(args: &Args) {
+    // The benchmark names are <sort>-<mode>-<type>-<pattern>-<len>, none of the parts contain '-'.
+    let alternatives = |key: &str| {
+        args.option(key)
+            .map(|val| format!("({})", val.replace(',', "|")))
+            .unwrap_or_else(|| "[^-]+".into())
+    };
+
+    let (mode_env, mode_name, mode_feature) = match args.option("mode").unwrap_or("time") {
+        "time" => (None, "hot", None),
+        "comp" => (Some("MEASURE_COMP"), "comp", None),
+        "instructions" => (Some("MEASURE_INSTRUCTIONS"), "instructions", None),
+        "runs" => (Some("MEASURE_RUNS"), "runs", Some("instrument_runs")),
+        "branches" => (
+            Some("MEASURE_BRANCHES"),
+            "branches",
+            Some("instrument_branches"),
+        ),
+        "phases" => (Some("MEASURE_PHASES"), "phases", Some("instrument_phases")),
+        "alloc" => (Some("MEASURE_ALLOC"), "alloc", Some("instrument_alloc")),
+        "fallbacks" => (
+            Some("MEASURE_FALLBACKS"),
+            "fallbacks",
+            Some("instrument_fallbacks"),
+        ),
+        "insertion" => (
+            Some("MEASURE_INSERTION"),
+            "insertion",
+            Some("instrument_insertion"),
+        ),
+        mode => exit_with_error(&format!(
+            "Unknown mode '{mode}', expected one of time, comp, instructions, runs, branches, \
+             phases, alloc, fallbacks, insertion"
+        )),
+    };
+
+    let bench_regex = format!(
+        "^{}-{mode_name}-{}-{}-{}$",
+        alternatives("sorts"),
+        alternatives("types"),
+        alternatives("patterns"),
+        alternatives("lens")
+    );
+
+    let mut features = args.list("features", &[]);
+    features.extend(mode_feature.map(String::from));
+
+    let mut cmd = if let Some(test_name) = args.option("save") {
+        if mode_env.is_some() {
+            exit_with_error("--save only works with --mode=time");
+        }
+        if !features.is_empty() {
+            exit_with_error("--save uses the features of Cargo.toml, --features is not supported");
+        }
+
+        let mut cmd = Command::new("python");
+        cmd.args(["util/run_benchmarks.py", test_name]);
+        if let Some(shard) = args.option("shard") {
+            cmd.args(["--shard", shard]);
+        }
+        cmd
+    } else {
+        let mut cmd = Command::new("cargo");
+        cmd.args(["bench", "--bench", "bench"]);
+        if !features.is_empty() {
+            cmd.args(["--features", &features.join(",")]);
+        }
+        cmd
+    };
+
+    cmd.env("BENCH_REGEX", &bench_regex);
+    cmd.env("BENCH_SHARD", args.shard().to_string());
+    if let Some(mode_env) = mode_env {
+        cmd.env(mode_env, "1");
+    }
+
+    println!("BENCH_REGEX={bench_regex}");
+    run_in_repo_root(cmd);
+}
+
+fn run_in_repo_root(mut cmd: Command) {
+    if !Path::new("build.rs").exists() {
+        exit_with_error(
+            "Please make sure to run this program with the repo root dir as the current working directory.",
+        );
+    }
+
+    let status = cmd
+        .status()
+        .unwrap_or_else(|err| exit_with_error(&format!("Failed to run {cmd:?}: {err}")));
+
+    process::exit(status.code().unwrap_or(1));
+}
+
+// --- test ---
+
+const TEST_LENS: &[usize] = &[
+    0, 1, 2, 3, 4, 5, 8, 15, 16, 17, 20, 21, 24, 33, 50, 64, 100, 257, 1_000, 2_048, 10_000,
+];
+
+struct Test<'a> {
+    args: &'a Args,
+    type_name: &'a str,
+    failures: &'a mut usize,
+    snapshots: Option<&'a mut SnapshotRing>,
+}
+
+impl TypeVisitor for Test<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let patterns = self.args.list("patterns", inputs::PATTERN_NAMES);
+        let lens = self.args.lens(test_sizes::from_env().unwrap_or(TEST_LENS));
+        let mut snapshots = self.snapshots;
+
+        for entry in self.args.sorts::<T>() {
+            let mut checked = 0;
+            let mut failed = false;
+
+            'patterns: for pattern_name in &patterns {
+                let pattern = pattern(pattern_name);
+
+                for &len in &lens {
+                    let pattern_input = pattern(len);
+                    record_snapshot(
+                        snapshots.as_deref_mut(),
+                        entry.name(),
+                        self.type_name,
+                        false,
+                        format!("{pattern_name}-{len}"),
+                        &pattern_input,
+                    );
+
+                    let input = transform(pattern_input);
+                    let mut actual = input.clone();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| entry.sort(&mut actual)));
+
+                    let error = match result {
+                        Ok(()) => check_result(&input, &actual, T::cmp),
+                        Err(payload) => Some(format!("panic: {}", panic_message(&*payload))),
+                    };
+
+                    if let Some(error) = error {
+                        println!(
+                            "{}-{}-{pattern_name}-{len}: FAILED, {error}",
+                            entry.name(),
+                            self.type_name
+                        );
+                        failed = true;
+                        // Further failures of the same sort are usually the same bug.
+                        break 'patterns;
+                    }
+
+                    checked += 1;
+                }
+            }
+
+            if failed {
+                *self.failures += 1;
+            } else {
+                println!("{}-{}: ok, {checked} inputs", entry.name(), self.type_name);
+            }
+        }
+    }
+}
+
+fn test(args: &Args) {
+    let mut failures = 0;
+    let mut snapshots = snapshot_ring(args);
+
+    for_each_type(args, inputs::TYPE_NAMES, |type_name| {
+        let test = Test {
+            args,
+            type_name,
+            failures: &mut failures,
+            snapshots: snapshots.as_mut(),
+        };
+        inputs::with_type(type_name, test);
+    });
+
+    if failures > 0 {
+        exit_with_error(&format!("{failures} sort and type combinations failed"));
+    }
+}
+
+// --- fuzz ---
+
+/// The largest of the `TEST_SIZES` or `TEST_SIZE_PROFILE` lengths if set, see
+/// sort_test_tools::test_sizes.
+fn fuzz_max_len() -> usize {
+    test_sizes::from_env()
+        .and_then(|lens| lens.last().copied())
+        .unwrap_or(1_000)
+}
+
+/// Random input that is derived from `seed` alone, so a failure can be reproduced with
+/// `--seed=<seed> --iterations=1`.
+fn fuzz_input(seed: u64, max_len: usize) -> Vec<i32> {
+    let mut rng = XorShift::new(seed | 1);
+
+    // Favor short inputs, which is where most of the special cases are, by picking the len
+    // uniformly in log space.
+    let len_log = rng.below(max_len.max(1).ilog2() as usize + 2);
+    let len = rng.below((1 << len_log).min(max_len + 1));
+
+    // Few distinct values, to stress the handling of equal elements, up to all values distinct.
+    let distinct = [2, 16, len.max(1), usize::MAX][rng.below(4)];
+    let mut v = (0..len)
+        .map(|_| (rng.next_u64() % distinct.min(u32::MAX as usize) as u64) as i32)
+        .collect::<Vec<_>>();
+
+    // Partially presorted inputs, as natural runs and pivot selection have their own paths.
+    match rng.below(4) {
+        0 => v.sort(),
+        1 => v.sort_by(|a, b| b.cmp(a)),
+        2 => {
+            let prefix = rng.below(len + 1);
+            v[..prefix].sort();
+        }
+        _ => {}
+    }
+
+    v
+}
+
+struct Fuzz<'a> {
+    args: &'a Args,
+    type_name: &'a str,
+    snapshots: Option<&'a mut SnapshotRing>,
+    certificates: Option<&'a mut CertificateWriter>,
+}
+
+impl TypeVisitor for Fuzz<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let iterations = self.args.num("iterations", 10_000u64);
+        let max_len = self.args.num("max-len", fuzz_max_len());
+        let start_seed = self.args.num("seed", 0u64);
+        let shard = self.args.shard();
+        let mut snapshots = self.snapshots;
+        let mut certificates = self.certificates;
+
+        for entry in self.args.sorts::<T>() {
+            let mut checked = 0;
+            for iteration in 0..iterations {
+                let seed = start_seed.wrapping_add(iteration);
+                if !shard.contains_seed(seed) {
+                    continue;
+                }
+                let pattern_input = fuzz_input(seed, max_len);
+
+                // Every other input is sorted in descending order via sort_by, which exercises the
+                // comparison function path of the FFI sorts.
+                let descending = seed % 2 == 1;
+                record_snapshot(
+                    snapshots.as_deref_mut(),
+                    entry.name(),
+                    self.type_name,
+                    descending,
+                    format!("--seed={seed}"),
+                    &pattern_input,
+                );
+
+                let input = transform(pattern_input);
+                let error = match certificates.as_deref_mut() {
+                    Some(certificates) => {
+                        let source = Source {
+                            generator: Generator::Fuzz,
+                            seed,
+                            max_len,
+                        };
+                        run_certified(&entry, self.type_name, source, &input, descending)
+                            .map(|certificate| write_certificate(certificates, &certificate))
+                            .err()
+                    }
+                    None => run_checked(&entry, &input, descending),
+                };
+                checked += 1;
+                let Some(error) = error else {
+                    continue;
+                };
+
+                println!(
+                    "{}-{}: FAILED with --seed={seed}, len {}: {error}",
+                    entry.name(),
+                    self.type_name,
+                    input.len()
+                );
+                if input.len() <= 64 {
+                    println!("input: {input:?}");
+                }
+                process::exit(1);
+            }
+
+            println!("{}-{}: ok, {checked} inputs", entry.name(), self.type_name);
+        }
+    }
+}
+
+fn write_certificate(certificates: &mut CertificateWriter, certificate: &Certificate) {
+    if let Err(err) = certificates.write(certificate) {
+        exit_with_error(&format!("Failed to write certificate: {err}"));
+    }
+}
+
+fn perf_fuzz(args: &Args, objective_name: &str) {
+    let objective = match objective_name {
+        "comparisons" => perf_fuzz::Objective::Comparisons,
+        "runtime" => perf_fuzz::Objective::Runtime,
+        _ => exit_with_error(&format!(
+            "Unknown --perf value '{objective_name}', expected comparisons or runtime"
+        )),
+    };
+
+    // The search works on i32 inputs.
+    for len in args.lens(&[1_000]) {
+        for entry in args.sorts::<i32>() {
+            let mut config = perf_fuzz::SearchConfig::new(len, objective);
+            config.iterations = args.num("iterations", config.iterations);
+            config.seed = args.num("seed", config.seed);
+
+            let result = perf_fuzz::search(&entry, &config);
+            println!(
+                "{}-{len}: initial pattern: {} initial score: {} found score: {} ({:.2}x)",
+                entry.name(),
+                result.initial_pattern,
+                result.initial_score,
+                result.score,
+                result.score / result.initial_score.max(1.0),
+            );
+
+            if let Some(name) = args.option("save-corpus") {
+                let snapshot = Snapshot {
+                    sequence: 0,
+                    sort_name: entry.name().to_string(),
+                    type_name: "i32".to_string(),
+                    descending: false,
+                    label: format!("--perf={} --seed={}", objective_name, config.seed),
+                    input: result.input,
+                };
+                add_to_corpus(&format!("{name}_{}_{len}", entry.name()), &snapshot);
+            }
+        }
+    }
+}
+
+fn fuzz(args: &Args) {
+    if let Some(objective) = args.option("perf") {
+        perf_fuzz(args, objective);
+        return;
+    }
+
+    let mut snapshots = snapshot_ring(args);
+    let mut certificates = args.option("certificates").map(|path| {
+        CertificateWriter::append(Path::new(path))
+            .unwrap_or_else(|err| exit_with_error(&format!("Failed to open '{path}': {err}")))
+    });
+
+    for_each_type(args, &["i32"], |type_name| {
+        let fuzz = Fuzz {
+            args,
+            type_name,
+            snapshots: snapshots.as_mut(),
+            certificates: certificates.as_mut(),
+        };
+        inputs::with_type(type_name, fuzz);
+    });
+}
+
+// --- replay ---
+
+struct Replay<'a> {
+    snapshot: &'a Snapshot,
+}
+
+impl TypeVisitor for Replay<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let entry = find_sort::<T>(&self.snapshot.sort_name);
+        let input = transform(self.snapshot.input.clone());
+
+        match run_checked(&entry, &input, self.snapshot.descending) {
+            Some(error) => exit_with_error(&format!("FAILED: {error}")),
+            None => println!("ok"),
+        }
+    }
+}
+
+/// Loads the snapshot file at `path`, or the newest snapshot if it is a dir.
+fn load_snapshot(path: &Path) -> Snapshot {
+    if path.is_dir() {
+        SnapshotRing::latest(path).map(|snapshot| {
+            snapshot
+                .unwrap_or_else(|| exit_with_error(&format!("No snapshots in {}", path.display())))
+        })
+    } else {
+        Snapshot::load(path)
+    }
+    .unwrap_or_else(|err| exit_with_error(&format!("Failed to read {}: {err}", path.display())))
+}
+
+fn replay(args: &Args) {
+    let snapshot = load_snapshot(Path::new(args.positional(1, "snapshot")));
+
+    let order = if snapshot.descending {
+        "descending"
+    } else {
+        "ascending"
+    };
+    println!(
+        "{}-{} {} {order}, len {}",
+        snapshot.sort_name,
+        snapshot.type_name,
+        snapshot.label,
+        snapshot.input.len()
+    );
+    if snapshot.input.len() <= 64 {
+        println!("input: {:?}", snapshot.input);
+    }
+
+    let replay = Replay {
+        snapshot: &snapshot,
+    };
+    if !inputs::with_type(&snapshot.type_name, replay) {
+        exit_with_error(&format!("Unknown type '{}'", snapshot.type_name));
+    }
+}
+
+// --- corpus ---
+
+fn add_to_corpus(name: &str, snapshot: &Snapshot) {
+    match corpus::add(&corpus::dir(), name, snapshot) {
+        Ok(path) => println!("Added {}", path.display()),
+        Err(err) => exit_with_error(&format!("Failed to add '{name}' to the corpus: {err}")),
+    }
+}
+
+fn corpus(args: &Args) {
+    match args.positional(1, "corpus command") {
+        "list" => {
+            let entries = corpus::load(&corpus::dir())
+                .unwrap_or_else(|err| exit_with_error(&format!("Failed to read corpus: {err}")));
+
+            for entry in entries {
+                let snapshot = &entry.snapshot;
+                println!(
+                    "{}: {}-{} {}, len {}",
+                    entry.name,
+                    snapshot.sort_name,
+                    snapshot.type_name,
+                    snapshot.label,
+                    snapshot.input.len()
+                );
+            }
+        }
+        "add" => {
+            let snapshot = load_snapshot(Path::new(args.positional(2, "snapshot")));
+            add_to_corpus(args.positional(3, "name"), &snapshot);
+        }
+        "adversary" => {
+            let entry = find_sort::<i32>(args.positional(2, "sort"));
+
+            for len in args.lens(&[1_000]) {
+                let snapshot = Snapshot {
+                    sequence: 0,
+                    sort_name: entry.name().to_string(),
+                    type_name: "i32".to_string(),
+                    descending: false,
+                    label: "adversary".to_string(),
+                    input: adversary::synthesize_for(&entry, len),
+                };
+                add_to_corpus(&format!("adversary_{}_{len}", entry.name()), &snapshot);
+            }
+        }
+        command => exit_with_error(&format!(
+            "Unknown corpus command '{command}', expected one of list, add, adversary"
+        )),
+    }
+}
+
+// --- export ---
+
+fn export(args: &Args) {
+    let pattern_name = args.positional(1, "pattern");
+    let len = args.positional(2, "len");
+    let len = len
+        .parse()
+        .unwrap_or_else(|_| exit_with_error(&format!("Invalid len: {len}")));
+    let path = Path::new(args.positional(3, "file"));
+    let seed = args.num("seed", patterns::random_init_seed());
+
+    match patterns::export(path, (pattern_name, pattern(pattern_name)), len, seed) {
+        Ok(exported) => println!(
+            "Wrote {pattern_name}, len {len}, seed {seed}, rng {} to {}",
+            exported.rng,
+            path.display()
+        ),
+        Err(err) => exit_with_error(&format!("Failed to write {}: {err}", path.display())),
+    }
+}
+
+// --- analyze ---
+
+const ANALYZE_LENS: &[usize] = &[100, 1_000, 10_000, 100_000];
+
+const ANALYZE_PATTERNS: &[&str] = &[
+    "random",
+    "random_d20",
+    "ascending",
+    "descending",
+    "saws_long",
+];
+
+struct CompCount<'a> {
+    args: &'a Args,
+    type_name: &'a str,
+}
+
+impl TypeVisitor for CompCount<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let patterns = self.args.list("patterns", ANALYZE_PATTERNS);
+        let lens = self.args.lens(ANALYZE_LENS);
+
+        for entry in self.args.sorts::<T>() {
+            for pattern_name in &patterns {
+                let pattern = pattern(pattern_name);
+
+                for &len in &lens {
+                    let run_count = (100_000 / len.max(1)).clamp(10, 1000);
+
+                    let mut cmp = CountingCmp::new(T::cmp);
+                    for _ in 0..run_count {
+                        let mut v = transform(pattern(len));
+                        entry.sort_by(&mut v, |a, b| cmp.compare(a, b));
+                    }
+
+                    // Same format as MEASURE_COMP, so the output works with the same scripts.
+                    println!(
+                        "{}-comp-{}-{pattern_name}-{len}: mean comparisons: {}",
+                        entry.name(),
+                        self.type_name,
+                        cmp.count() / run_count as u64
+                    );
+                }
+            }
+        }
+    }
+}
+
+struct Crossover<'a> {
+    args: &'a Args,
+}
+
+impl TypeVisitor for Crossover<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let (name_a, name_b) = (
+            self.args.positional(2, "sort_a"),
+            self.args.positional(3, "sort_b"),
+        );
+        let (a, b) = (find_sort::<T>(name_a), find_sort::<T>(name_b));
+
+        for pattern_name in self.args.list("patterns", &["random"]) {
+            let pattern = pattern(&pattern_name);
+            let make_input = |len| transform(pattern(len));
+
+            let finder = CrossoverFinder {
+                a: &a,
+                b: &b,
+                make_input: &make_input,
+                samples: 30,
+            };
+
+            let crossover = finder.find(
+                self.args.num("min-len", 2),
+                self.args.num("max-len", 1_000_000),
+            );
+            println!("{pattern_name}: {}", crossover.describe(name_a, name_b));
+        }
+    }
+}
+
+struct Pow2<'a> {
+    args: &'a Args,
+}
+
+impl TypeVisitor for Pow2<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let config = pow2_bias::Pow2Config::default();
+
+        for entry in self.args.sorts::<T>() {
+            for pattern_name in self.args.list("patterns", &["random"]) {
+                let pattern = pattern(&pattern_name);
+                let make_input = |len| transform(pattern(len));
+
+                let results = pow2_bias::scan(&entry, &make_input, &config);
+                let cliffs = results
+                    .iter()
+                    .filter(|result| result.is_cliff)
+                    .map(|result| {
+                        let ratio = result.vs_neighbors;
+                        format!("{} ({:.3})", ratio.len, ratio.ratio)
+                    })
+                    .collect::<Vec<_>>();
+
+                println!(
+                    "{}-{pattern_name}: {} cliffs {}",
+                    entry.name(),
+                    cliffs.len(),
+                    cliffs.join(" ")
+                );
+            }
+        }
+    }
+}
+
+struct Cardinality<'a> {
+    args: &'a Args,
+    type_name: &'a str,
+}
+
+// The inputs are random values with a given number of distinct ones, --patterns doesn't apply.
+impl TypeVisitor for Cardinality<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let samples = self.args.num("samples", 11);
+
+        for entry in self.args.sorts::<T>() {
+            for len in self.args.lens(&[10_000, 1_000_000]) {
+                for point in cardinality::sweep(&entry, &transform, len, samples) {
+                    println!(
+                        "{}-{}-{len}: distinct: {} ns per elem: {:.3} vs all distinct: {:.3}",
+                        entry.name(),
+                        self.type_name,
+                        point.distinct,
+                        point.ns_per_elem,
+                        point.vs_all_distinct
+                    );
+                }
+            }
+        }
+    }
+}
+
+// The inputs are tagged u64, --types and --patterns don't apply.
+fn check_stability(args: &Args) {
+    let mut failures = 0;
+
+    for entry in args.sorts::<u64>() {
+        let classification = stability::classify(&entry);
+        let declared = if classification.declared_stable {
+            "stable"
+        } else {
+            "unstable"
+        };
+
+        let verdict = if classification.is_failure() {
+            failures += 1;
+            "FAILED"
+        } else {
+            "ok"
+        };
+
+        println!(
+            "{}: declared {declared}, observed {}: {verdict}",
+            entry.name(),
+            classification.observed
+        );
+    }
+
+    if failures > 0 {
+        exit_with_error(&format!(
+            "{failures} sorts don't behave as their declared stability"
+        ));
+    }
+}
+
+// Patterns with many equal elements, random has almost none.
+const TIE_PATTERNS: &[&str] = &["random_d20", "random_z1", "all_equal"];
+
+// The inputs are tagged u64, --types doesn't apply. Stable sorts would all measure 0, they are only
+// included if selected with --sorts.
+fn measure_tie_order(args: &Args) {
+    let patterns = args.list("patterns", TIE_PATTERNS);
+    let lens = args.lens(&[20, 100, 1_000, 10_000]);
+
+    let mut entries = args.sorts::<u64>();
+    if args.option("sorts").is_none() {
+        entries.retain(|entry| !entry.is_stable());
+    }
+
+    for entry in &entries {
+        for pattern_name in &patterns {
+            let pattern = pattern(pattern_name);
+
+            for &len in &lens {
+                let name = format!("{}-{pattern_name}-{len}", entry.name());
+                match tie_order::measure(entry, &pattern(len)) {
+                    Some(order) => println!(
+                        "{name}: kendall tau distance: {:.4} of {} tie pairs",
+                        order.distance(),
+                        order.tie_pairs
+                    ),
+                    None => println!("{name}: panicked or not sorted"),
+                }
+            }
+        }
+    }
+}
+
+// Random has almost no ties, it shows the permutation of the whole input alone.
+const PERMUTATION_PATTERNS: &[&str] = &["random", "random_d20", "random_z1", "saws_long"];
+
+// The inputs are tagged u64 like for analyze ties, --types doesn't apply.
+fn measure_permutation(args: &Args) {
+    let patterns = args.list("patterns", PERMUTATION_PATTERNS);
+    let lens = args.lens(&[20, 100, 1_000, 10_000]);
+
+    let mut entries = args.sorts::<u64>();
+    if args.option("sorts").is_none() {
+        entries.retain(|entry| !entry.is_stable());
+    }
+
+    for entry in &entries {
+        for pattern_name in &patterns {
+            let pattern = pattern(pattern_name);
+
+            for &len in &lens {
+                let name = format!("{}-{pattern_name}-{len}", entry.name());
+                let Some(perm) = permutation::measure(entry, &pattern(len)) else {
+                    println!("{name}: panicked or not sorted");
+                    continue;
+                };
+
+                let (whole, ties) = (perm.whole, perm.ties);
+                println!(
+                    "{name}: whole: {} cycles, longest {}, mean displacement {:.1}; ties: {}/{} \
+                     groups in order, {}/{len} at their stable position, {} cycles, longest {}, \
+                     mean displacement {:.2}",
+                    whole.cycles,
+                    whole.longest_cycle,
+                    whole.mean_displacement(),
+                    perm.stable_tie_groups,
+                    perm.tie_groups,
+                    ties.fixed_points,
+                    ties.cycles,
+                    ties.longest_cycle,
+                    ties.mean_displacement(),
+                );
+            }
+        }
+    }
+}
+
+// The inputs are i32, the worst of the stack_depth patterns and the adversary input, --types and
+// --patterns don't apply. --painted measures by painting the stack of a sort thread instead of from
+// the comparison function, which also sees stack buffers but runs a thread per input.
+fn measure_stack_depth(args: &Args) {
+    let painted = args.option("painted").is_some();
+
+    for entry in args.sorts::<i32>() {
+        for len in args.lens(ANALYZE_LENS) {
+            if painted {
+                match stack_depth::worst_case_painted(&entry, len) {
+                    (input_name, Some(bytes)) => println!(
+                        "{}-{len}: peak stack use: {bytes} bytes, on {input_name}",
+                        entry.name()
+                    ),
+                    (input_name, None) => println!(
+                        "{}-{len}: peak stack use: more than {} bytes, on {input_name}",
+                        entry.name(),
+                        stack_depth::PAINTED_BYTES
+                    ),
+                }
+            } else {
+                let (input_name, bytes) = stack_depth::worst_case(&entry, len);
+                println!(
+                    "{}-{len}: max stack depth: {bytes} bytes, on {input_name}",
+                    entry.name()
+                );
+            }
+        }
+    }
+}
+
+sort_test_tools::instrumented_type!(Tracked);
+
+// The inputs are i32 wrapped in Tracked, --types doesn't apply. The moves are those seen by the
+// elements between two comparisons, see sort_test_tools::instrumented. The FFI sorts only support
+// their fixed types and panic.
+fn count_moves(args: &Args) {
+    for entry in args.sorts::<Tracked<i32>>() {
+        for pattern_name in args.list("patterns", ANALYZE_PATTERNS) {
+            let pattern = pattern(&pattern_name);
+
+            for len in args.lens(ANALYZE_LENS) {
+                let name = format!("{}-{pattern_name}-{len}", entry.name());
+                let mut v = pattern(len)
+                    .into_iter()
+                    .map(Tracked::new)
+                    .collect::<Vec<_>>();
+
+                Tracked::observe_all(&v);
+                Tracked::<i32>::reset_counts();
+                let result = panic::catch_unwind(AssertUnwindSafe(|| entry.sort(&mut v)));
+                Tracked::observe_all(&v);
+
+                if result.is_err() {
+                    println!("{name}: panicked");
+                    continue;
+                }
+
+                let counts = Tracked::<i32>::counts();
+                println!(
+                    "{name}: moves: {} ({:.2} per element), comparisons: {}, clones: {}, drops: {}",
+                    counts.moves,
+                    counts.moves as f64 / len.max(1) as f64,
+                    counts.comparisons,
+                    counts.clones,
+                    counts.drops
+                );
+            }
+        }
+    }
+}
+
+const MERGE_PATTERNS: &[&str] = &["random", "random_s95", "saws_long", "pipe_organ"];
+
+// The run lens come from a --runs file of whitespace separated lens, eg. the run_lens recorded by
+// instrument::runs, from --run-count random runs, or from the natural runs of the i32 patterns. No
+// element is sorted, so --run-count can go far beyond the lens that fit in memory.
+fn simulate_merges(args: &Args) {
+    let policies = args
+        .list("policies", &Policy::ALL.map(Policy::name))
+        .iter()
+        .map(|name| {
+            name.parse()
+                .unwrap_or_else(|err: String| exit_with_error(&err))
+        })
+        .collect::<Vec<Policy>>();
+    let min_run = args.num("min-run", 0);
+
+    let mut inputs = Vec::new();
+    if let Some(path) = args.option("runs") {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| exit_with_error(&format!("Failed to read {path}: {err}")));
+        let run_lens = text
+            .split_whitespace()
+            .map(|len| {
+                len.parse()
+                    .unwrap_or_else(|_| exit_with_error(&format!("Invalid run len: {len}")))
+            })
+            .collect();
+        inputs.push((path.to_string(), run_lens));
+    } else if args.option("run-count").is_some() {
+        let count = args.num("run-count", 0);
+        let max_run_len = args.num("max-run-len", 1_000);
+        let run_lens = merge_sim::random_run_lens(count, max_run_len, args.num("seed", 0));
+        inputs.push((format!("random_runs-{count}"), run_lens));
+    } else {
+        for pattern_name in args.list("patterns", MERGE_PATTERNS) {
+            let pattern = pattern(&pattern_name);
+            for len in args.lens(ANALYZE_LENS) {
+                let run_lens = merge_sim::natural_run_lens(&pattern(len));
+                inputs.push((format!("{pattern_name}-{len}"), run_lens));
+            }
+        }
+    }
+
+    for (input_name, run_lens) in &inputs {
+        let run_lens = merge_sim::with_min_run(run_lens, min_run);
+
+        for &policy in &policies {
+            let cost = merge_sim::simulate(policy, &run_lens);
+            let vs_bound = cost
+                .vs_entropy_bound(&run_lens)
+                .map_or_else(|| "-".to_string(), |ratio| format!("{ratio:.3}"));
+
+            println!(
+                "{policy}-{input_name}: merge cost: {} ({vs_bound} of n*H), merges: {}, runs: {}, \
+                 max stack: {}",
+                cost.merged_elements,
+                cost.merges,
+                run_lens.len(),
+                cost.max_stack_len
+            );
+        }
+    }
+}
+
+fn analyze(args: &Args) {
+    match args.positional(1, "analysis") {
+        "cardinality" => for_each_type(args, &["i32"], |type_name| {
+            inputs::with_type(type_name, Cardinality { args, type_name });
+        }),
+        "comp" => for_each_type(args, &["i32"], |type_name| {
+            inputs::with_type(type_name, CompCount { args, type_name });
+        }),
+        "crossover" => for_each_type(args, &["u64"], |type_name| {
+            inputs::with_type(type_name, Crossover { args });
+        }),
+        "merges" => simulate_merges(args),
+        "moves" => count_moves(args),
+        "permutation" => measure_permutation(args),
+        "pow2" => for_each_type(args, &["u64"], |type_name| {
+            inputs::with_type(type_name, Pow2 { args });
+        }),
+        "stability" => check_stability(args),
+        "stack" => measure_stack_depth(args),
+        "ties" => measure_tie_order(args),
+        analysis => exit_with_error(&format!(
+            "Unknown analysis '{analysis}', expected one of cardinality, comp, crossover, merges, \
+             moves, permutation, pow2, stability, stack, ties"
+        )),
+    }
+}
+
+// --- compare ---
+
+struct Compare<'a> {
+    args: &'a Args,
+    type_name: &'a str,
+    log_ratios: &'a mut Vec<f64>,
+}
+
+impl TypeVisitor for Compare<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let (name_a, name_b) = (
+            self.args.positional(1, "sort_a"),
+            self.args.positional(2, "sort_b"),
+        );
+        let (a, b) = (find_sort::<T>(name_a), find_sort::<T>(name_b));
+
+        for pattern_name in self.args.list("patterns", ANALYZE_PATTERNS) {
+            let pattern = pattern(&pattern_name);
+            let make_input = |len| transform(pattern(len));
+
+            // Only CrossoverFinder::measure is used, it times both sorts on the same inputs.
+            let finder = CrossoverFinder {
+                a: &a,
+                b: &b,
+                make_input: &make_input,
+                samples: self.args.num("samples", 30),
+            };
+
+            for len in self.args.lens(ANALYZE_LENS) {
+                let ratio = finder.measure(len);
+                self.log_ratios.push(ratio.ratio.ln());
+
+                println!(
+                    "{}-{pattern_name}-{len}: {name_a}/{name_b}: {:.3} [{:.3}, {:.3}]{}",
+                    self.type_name,
+                    ratio.ratio,
+                    ratio.ci_low,
+                    ratio.ci_high,
+                    if ratio.is_significant() { "" } else { " ~" }
+                );
+            }
+        }
+    }
+}
+
+fn compare(args: &Args) {
+    let mut log_ratios = Vec::new();
+
+    for_each_type(args, &["i32", "u64", "string"], |type_name| {
+        let compare = Compare {
+            args,
+            type_name,
+            log_ratios: &mut log_ratios,
+        };
+        inputs::with_type(type_name, compare);
+    });
+
+    if !log_ratios.is_empty() {
+        let geo_mean = (log_ratios.iter().sum::<f64>() / log_ratios.len() as f64).exp();
+        println!(
+            "\nGeometric mean of {} ratios: {geo_mean:.3}, below 1 means {} is faster. ~ marks ratios that are not significant.",
+            log_ratios.len(),
+            args.positional(1, "sort_a")
+        );
+    }
+}
+
+// --- graph ---
+
+fn graph(args: &Args) {
+    if args.positional.len() < 2 {
+        exit_with_error("Missing argument <result.json>");
+    }
+
+    let mut cmd = Command::new("python");
+    cmd.arg("util/graph_bench_result/graph_all.py")
+        .args(&args.positional[1..]);
+    run_in_repo_root(cmd);
+}
+
+// --- workload ---
+
+#[cfg(feature = "instrument_audit")]
+fn workload(args: &Args) {
+    use sort_research_rs::instrument::audit;
+
+    let path = args.positional(1, "audit log");
+    let invocations = audit::read_log(Path::new(path))
+        .unwrap_or_else(|err| exit_with_error(&format!("Failed to read '{path}': {err}")));
+
+    let mut per_sort = HashMap::<&str, usize>::new();
+    for invocation in &invocations {
+        *per_sort.entry(&invocation.sort_name).or_default() += 1;
+    }
+    let mut per_sort = per_sort.into_iter().collect::<Vec<_>>();
+    per_sort.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    for (sort_name, count) in per_sort {
+        println!("{sort_name}: {count} invocations");
+    }
+
+    match audit::workload_histogram(invocations.iter().map(|invocation| invocation.len)) {
+        Some(histogram) => println!("\nBENCH_WORKLOAD={histogram}"),
+        None => exit_with_error(&format!("No invocations with len > 0 in '{path}'")),
+    }
+}
+
+#[cfg(not(feature = "instrument_audit"))]
+fn workload(_args: &Args) {
+    exit_with_error(
+        "workload needs the instrument_audit feature, build with --features instrument_audit",
+    );
+}
+
+// --- verify ---
+
+struct Verify<'a> {
+    certificate: &'a Certificate,
+    differences: &'a mut Vec<String>,
+}
+
+impl TypeVisitor for Verify<'_> {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let certificate = self.certificate;
+        let Some(entry) = registry::find::<T>(&certificate.sort_name) else {
+            self.differences
+                .push("the sort is not enabled in this build".to_string());
+            return;
+        };
+
+        let source = certificate.source;
+        let pattern_input = match source.generator {
+            Generator::Fuzz => fuzz_input(source.seed, source.max_len),
+            Generator::Stress => stress::Case::new(source.seed, source.max_len).input,
+        };
+        let input = transform(pattern_input);
+
+        match run_certified(
+            &entry,
+            &certificate.type_name,
+            source,
+            &input,
+            certificate.descending,
+        ) {
+            Ok(recomputed) => self
+                .differences
+                .extend(certificate.differences(&recomputed)),
+            Err(error) => self.differences.push(error),
+        }
+    }
+}
+
+fn verify_certificates(args: &Args) {
+    let path = args.positional(1, "certificates");
+    let certificates = certificate::read(Path::new(path))
+        .unwrap_or_else(|err| exit_with_error(&format!("Failed to read '{path}': {err}")));
+
+    let mut failed = 0;
+    for certificate in &certificates {
+        let mut differences = Vec::new();
+        let verify = Verify {
+            certificate,
+            differences: &mut differences,
+        };
+        if !inputs::with_type(&certificate.type_name, verify) {
+            differences.push(format!("unknown type '{}'", certificate.type_name));
+        }
+
+        if !differences.is_empty() {
+            failed += 1;
+            println!(
+                "{}-{}: FAILED {} --seed={} --max-len={}: {}",
+                certificate.sort_name,
+                certificate.type_name,
+                certificate.source.generator.name(),
+                certificate.source.seed,
+                certificate.source.max_len,
+                differences.join(", ")
+            );
+        }
+    }
+
+    println!(
+        "{} certificates, {} ok, {failed} failed",
+        certificates.len(),
+        certificates.len() - failed
+    );
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+// --- list ---
+
+struct List;
+
+impl TypeVisitor for List {
+    fn visit<T: Ord + Clone + Debug + 'static>(self, _transform: fn(Vec<i32>) -> Vec<T>) {
+        for entry in registry::sorts::<T>() {
+            println!("{}", entry.name());
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1));
+
+    match args.positional.first().map(String::as_str) {
+        Some("bench") => bench(&args),
+        Some("test") => test(&args),
+        Some("fuzz") => fuzz(&args),
+        Some("replay") => replay(&args),
+        Some("corpus") => corpus(&args),
+        Some("export") => export(&args),
+        Some("analyze") => analyze(&args),
+        Some("compare") => compare(&args),
+        Some("graph") => graph(&args),
+        Some("workload") => workload(&args),
+        Some("verify") => verify_certificates(&args),
+        Some("list") => {
+            inputs::with_type("i32", List);
+        }
+        _ => exit_with_error(USAGE),
+    }
+}
@@ -0,0 +1,85 @@
+//! Finds the input length at which one sort overtakes another, see analysis::crossover.
+//!
+//! cargo run --release --bin crossover -- <sort_a> <sort_b> <type> <pattern> [min_len] [max_len]
+//!
+//! Eg. cargo run --release --bin crossover -- rust_std_unstable rust_ipnsort_unstable u64 random
+
+use std::env;
+use std::process;
+
+use sort_research_rs::analysis::crossover::CrossoverFinder;
+use sort_research_rs::analysis::inputs::{self, PatternFn, TypeVisitor};
+use sort_research_rs::registry;
+
+struct Run<'a> {
+    sort_a: &'a str,
+    sort_b: &'a str,
+    pattern: PatternFn,
+    min_len: usize,
+    max_len: usize,
+}
+
+impl TypeVisitor for Run<'_> {
+    fn visit<T: Ord + Clone + std::fmt::Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let find = |name: &str| {
+            registry::find::<T>(name).unwrap_or_else(|| {
+                eprintln!("Unknown sort '{name}', make sure its feature is enabled");
+                process::exit(1);
+            })
+        };
+
+        let a = find(self.sort_a);
+        let b = find(self.sort_b);
+        let pattern = self.pattern;
+        let make_input = |len| transform(pattern(len));
+
+        let finder = CrossoverFinder {
+            a: &a,
+            b: &b,
+            make_input: &make_input,
+            samples: 30,
+        };
+
+        let crossover = finder.find(self.min_len, self.max_len);
+        println!("{}", crossover.describe(self.sort_a, self.sort_b));
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.len() < 4 {
+        eprintln!("Usage: crossover <sort_a> <sort_b> <type> <pattern> [min_len] [max_len]");
+        process::exit(1);
+    }
+
+    let (sort_a, sort_b, type_name, pattern_name) = (&args[0], &args[1], &args[2], &args[3]);
+    let parse_len = |idx: usize, default: usize| {
+        args.get(idx)
+            .map(|val| val.parse::<usize>().expect("Invalid len"))
+            .unwrap_or(default)
+    };
+
+    let pattern = inputs::pattern_by_name(pattern_name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown pattern '{pattern_name}', expected one of {}",
+            inputs::PATTERN_NAMES.join(", ")
+        );
+        process::exit(1);
+    });
+
+    let run = Run {
+        sort_a,
+        sort_b,
+        pattern,
+        min_len: parse_len(4, 2),
+        max_len: parse_len(5, 1_000_000),
+    };
+
+    if !inputs::with_type(type_name, run) {
+        eprintln!(
+            "Unknown type '{type_name}', expected one of {}",
+            inputs::TYPE_NAMES.join(", ")
+        );
+        process::exit(1);
+    }
+}
@@ -0,0 +1,25 @@
+//! Prints the CPU specific code paths selected by the enabled sorts as JSON object, see
+//! sort_research_rs::dispatch. Used by util/run_benchmarks.py to store them with the results.
+//!
+//! cargo run --release --bin dispatch_info
+
+use sort_research_rs::dispatch;
+
+fn json_str(val: &str) -> String {
+    format!("\"{}\"", val.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn main() {
+    let entries = dispatch::selected_targets()
+        .into_iter()
+        .map(|target| {
+            format!(
+                "  {}: {}",
+                json_str(&target.sort_name),
+                json_str(&target.target)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    println!("{{\n{}\n}}", entries.join(",\n"));
+}
@@ -0,0 +1,90 @@
+//! Flags sorts that are significantly slower or faster at power of two lengths, see
+//! analysis::pow2_bias.
+//!
+//! cargo run --release --bin pow2_bias -- <type> <pattern> [sort...]
+//!
+//! Without sort names all sorts enabled in the build are scanned.
+
+use std::env;
+use std::process;
+
+use sort_research_rs::analysis::inputs::{self, PatternFn, TypeVisitor};
+use sort_research_rs::analysis::pow2_bias::{self, Pow2Config};
+use sort_research_rs::registry;
+
+struct Scan<'a> {
+    sort_names: &'a [String],
+    pattern: PatternFn,
+}
+
+impl TypeVisitor for Scan<'_> {
+    fn visit<T: Ord + Clone + std::fmt::Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let entries = registry::sorts::<T>()
+            .into_iter()
+            .filter(|entry| {
+                self.sort_names.is_empty()
+                    || self.sort_names.iter().any(|name| name == entry.name())
+            })
+            .collect::<Vec<_>>();
+
+        if entries.is_empty() {
+            eprintln!("No matching sorts, make sure their features are enabled");
+            process::exit(1);
+        }
+
+        let pattern = self.pattern;
+        let make_input = |len| transform(pattern(len));
+        let config = Pow2Config::default();
+
+        for entry in &entries {
+            let results = pow2_bias::scan(entry, &make_input, &config);
+
+            for result in &results {
+                let ratio = result.vs_neighbors;
+                println!(
+                    "{}: len {:>8} vs neighbors: {:.3} [{:.3}, {:.3}]{}",
+                    entry.name(),
+                    ratio.len,
+                    ratio.ratio,
+                    ratio.ci_low,
+                    ratio.ci_high,
+                    if result.is_cliff { " <- cliff" } else { "" }
+                );
+            }
+
+            let cliff_count = results.iter().filter(|result| result.is_cliff).count();
+            println!("{}: {cliff_count} cliffs\n", entry.name());
+        }
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.len() < 2 {
+        eprintln!("Usage: pow2_bias <type> <pattern> [sort...]");
+        process::exit(1);
+    }
+
+    let (type_name, pattern_name) = (&args[0], &args[1]);
+
+    let pattern = inputs::pattern_by_name(pattern_name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown pattern '{pattern_name}', expected one of {}",
+            inputs::PATTERN_NAMES.join(", ")
+        );
+        process::exit(1);
+    });
+
+    let scan = Scan {
+        sort_names: &args[2..],
+        pattern,
+    };
+
+    if !inputs::with_type(type_name, scan) {
+        eprintln!(
+            "Unknown type '{type_name}', expected one of {}",
+            inputs::TYPE_NAMES.join(", ")
+        );
+        process::exit(1);
+    }
+}
@@ -0,0 +1,96 @@
+//! Sorts a single input and exits, for tools that observe a whole process such as
+//! util/run_cachegrind.py.
+//!
+//! sort_once <sort> <type> <pattern> <len> [--no-sort]
+//! sort_once --list <type>
+//!
+//! With `--no-sort` everything but the sort itself is done, subtracting such a run from a regular
+//! run leaves the cost of the sort. Set `OVERRIDE_SEED` to get the same input in every process.
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+
+use sort_research_rs::analysis::inputs::{self, PatternFn, TypeVisitor};
+use sort_research_rs::registry;
+
+struct List;
+
+impl TypeVisitor for List {
+    fn visit<T: Ord + Clone + std::fmt::Debug + 'static>(self, _transform: fn(Vec<i32>) -> Vec<T>) {
+        for entry in registry::sorts::<T>() {
+            println!("{}", entry.name());
+        }
+    }
+}
+
+struct SortOnce<'a> {
+    sort_name: &'a str,
+    pattern: PatternFn,
+    len: usize,
+    skip_sort: bool,
+}
+
+impl TypeVisitor for SortOnce<'_> {
+    fn visit<T: Ord + Clone + std::fmt::Debug + 'static>(self, transform: fn(Vec<i32>) -> Vec<T>) {
+        let entry = registry::find::<T>(self.sort_name).unwrap_or_else(|| {
+            eprintln!(
+                "Unknown sort '{}', make sure its feature is enabled",
+                self.sort_name
+            );
+            process::exit(1);
+        });
+
+        let mut v = transform((self.pattern)(self.len));
+        if !self.skip_sort {
+            entry.sort(black_box(v.as_mut_slice()));
+        }
+        black_box(v);
+    }
+}
+
+fn exit_unknown_type(type_name: &str) -> ! {
+    eprintln!(
+        "Unknown type '{type_name}', expected one of {}",
+        inputs::TYPE_NAMES.join(", ")
+    );
+    process::exit(1);
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if args.len() == 2 && args[0] == "--list" {
+        if !inputs::with_type(&args[1], List) {
+            exit_unknown_type(&args[1]);
+        }
+        return;
+    }
+
+    if args.len() < 4 {
+        eprintln!("Usage: sort_once <sort> <type> <pattern> <len> [--no-sort]");
+        eprintln!("       sort_once --list <type>");
+        process::exit(1);
+    }
+
+    let (sort_name, type_name, pattern_name) = (&args[0], &args[1], &args[2]);
+
+    let pattern = inputs::pattern_by_name(pattern_name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown pattern '{pattern_name}', expected one of {}",
+            inputs::PATTERN_NAMES.join(", ")
+        );
+        process::exit(1);
+    });
+
+    let sort_once = SortOnce {
+        sort_name,
+        pattern,
+        len: args[3].parse().expect("Invalid len"),
+        skip_sort: args.get(4).is_some_and(|arg| arg == "--no-sort"),
+    };
+
+    if !inputs::with_type(type_name, sort_once) {
+        exit_unknown_type(type_name);
+    }
+}
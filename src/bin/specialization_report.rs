@@ -0,0 +1,74 @@
+//! Instantiates the Rust sorts for a matrix of element types and prints which of their type
+//! dependent code paths were taken, see ipnsort/src/specialization.rs. Shows whether the fast
+//! paths intended for a type, eg. the sorting network small-sort for integers, actually fire.
+//!
+//! cargo run --release --bin specialization_report --features instrument_specialization
+//!
+//! Sorts without counters are listed as not instrumented.
+
+use std::mem;
+use std::rc::Rc;
+
+use sort_research_rs::instrument::specialization::{self, PathCounts};
+use sort_research_rs::registry;
+
+use sort_test_tools::dyn_types::{dyn_val_from_i32, DynTrait};
+use sort_test_tools::ffi_types::FFIOneKibiByte;
+use sort_test_tools::patterns;
+
+/// Covers the insertion sort only path, a single small-sort and inputs with partitions.
+const TEST_LENS: &[usize] = &[16, 32, 200, 10_000];
+
+/// Order preserving.
+fn shift_i32_to_u64(val: i32) -> u64 {
+    (val as i64 - i32::MIN as i64) as u64
+}
+
+fn report<T: Ord + 'static>(type_name: &str, transform: impl Fn(Vec<i32>) -> Vec<T>) {
+    println!("{type_name} ({} bytes):", mem::size_of::<T>());
+
+    let entries = registry::sorts::<T>()
+        .into_iter()
+        .filter(|entry| entry.name().starts_with("rust_"))
+        .collect::<Vec<_>>();
+
+    for entry in &entries {
+        let mut total = PathCounts::default();
+        for &len in TEST_LENS {
+            for pattern in [patterns::random, patterns::ascending] {
+                let mut v = transform(pattern(len));
+                let ((), counts) = specialization::record(|| entry.sort(&mut v));
+                total.merge(&counts);
+            }
+        }
+
+        let taken = total
+            .taken()
+            .map(|path| format!("{}: {}", path.name(), total.count(path)))
+            .collect::<Vec<_>>();
+
+        if taken.is_empty() {
+            println!("  {}: not instrumented", entry.name());
+        } else {
+            println!("  {}: {}", entry.name(), taken.join(", "));
+        }
+    }
+
+    println!();
+}
+
+fn main() {
+    report("i32", |v| v);
+    report("u64", |v| v.into_iter().map(shift_i32_to_u64).collect());
+    report("string", |v| {
+        v.into_iter()
+            .map(|val| format!("{:010}", shift_i32_to_u64(val)))
+            .collect()
+    });
+    report("1k", |v| v.into_iter().map(FFIOneKibiByte::new).collect());
+    report("rc_dyn", |v| {
+        v.into_iter()
+            .map(|val| -> Rc<dyn DynTrait> { dyn_val_from_i32(val).into() })
+            .collect()
+    });
+}
@@ -18,13 +18,13 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
   CompWrapper<T, F>::cmp_fn_local = cmp_fn;
   CompWrapper<T, F>::ctx_local = ctx;
 
-  try {
+  FFI_TRY {
     blocked_double_pivot_check_mosqrt::sort(
         reinterpret_cast<CompWrapper<T, F>*>(data),
         reinterpret_cast<CompWrapper<T, F>*>(data) + len,
         std::less<CompWrapper<T, F>>{});
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -33,9 +33,15 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
 extern "C" {
 // --- i32 ---
 
-void blockquicksort_unstable_i32(int32_t* data, size_t len) {
-  blocked_double_pivot_check_mosqrt::sort(data, data + len,
-                                          std::less<int32_t>{});
+uint32_t blockquicksort_unstable_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    blocked_double_pivot_check_mosqrt::sort(data, data + len,
+                                            std::less<int32_t>{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t blockquicksort_unstable_i32_by(int32_t* data,
@@ -49,9 +55,15 @@ uint32_t blockquicksort_unstable_i32_by(int32_t* data,
 
 // --- u64 ---
 
-void blockquicksort_unstable_u64(uint64_t* data, size_t len) {
-  blocked_double_pivot_check_mosqrt::sort(data, data + len,
-                                          std::less<uint64_t>{});
+uint32_t blockquicksort_unstable_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    blocked_double_pivot_check_mosqrt::sort(data, data + len,
+                                            std::less<uint64_t>{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t blockquicksort_unstable_u64_by(uint64_t* data,
@@ -65,10 +77,16 @@ uint32_t blockquicksort_unstable_u64_by(uint64_t* data,
 
 // --- ffi_string ---
 
-void blockquicksort_unstable_ffi_string(FFIString* data, size_t len) {
-  blocked_double_pivot_check_mosqrt::sort(
-      reinterpret_cast<FFIStringCpp*>(data),
-      reinterpret_cast<FFIStringCpp*>(data) + len, std::less<FFIStringCpp>{});
+uint32_t blockquicksort_unstable_ffi_string(FFIString* data, size_t len) {
+  FFI_TRY {
+    blocked_double_pivot_check_mosqrt::sort(
+        reinterpret_cast<FFIStringCpp*>(data),
+        reinterpret_cast<FFIStringCpp*>(data) + len, std::less<FFIStringCpp>{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t blockquicksort_unstable_ffi_string_by(
@@ -81,10 +99,16 @@ uint32_t blockquicksort_unstable_ffi_string_by(
 
 // --- f128 ---
 
-void blockquicksort_unstable_f128(F128* data, size_t len) {
-  blocked_double_pivot_check_mosqrt::sort(
-      reinterpret_cast<F128Cpp*>(data), reinterpret_cast<F128Cpp*>(data) + len,
-      std::less<F128Cpp>{});
+uint32_t blockquicksort_unstable_f128(F128* data, size_t len) {
+  FFI_TRY {
+    blocked_double_pivot_check_mosqrt::sort(
+        reinterpret_cast<F128Cpp*>(data), reinterpret_cast<F128Cpp*>(data) + len,
+        std::less<F128Cpp>{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t blockquicksort_unstable_f128_by(F128* data,
@@ -98,11 +122,17 @@ uint32_t blockquicksort_unstable_f128_by(F128* data,
 
 // --- 1k ---
 
-void blockquicksort_unstable_1k(FFIOneKibiByte* data, size_t len) {
-  blocked_double_pivot_check_mosqrt::sort(
-      reinterpret_cast<FFIOneKiloByteCpp*>(data),
-      reinterpret_cast<FFIOneKiloByteCpp*>(data) + len,
-      std::less<FFIOneKiloByteCpp>{});
+uint32_t blockquicksort_unstable_1k(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    blocked_double_pivot_check_mosqrt::sort(
+        reinterpret_cast<FFIOneKiloByteCpp*>(data),
+        reinterpret_cast<FFIOneKiloByteCpp*>(data) + len,
+        std::less<FFIOneKiloByteCpp>{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t blockquicksort_unstable_1k_by(
@@ -10,8 +10,14 @@
 extern "C" {
 // --- i32 ---
 
-void intel_avx512_i32(int32_t* data, size_t len) {
-  avx512_qsort(data, len);
+uint32_t intel_avx512_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    avx512_qsort(data, len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t intel_avx512_i32_by(int32_t* data,
@@ -20,14 +26,19 @@ uint32_t intel_avx512_i32_by(int32_t* data,
                                                   const int32_t&,
                                                   uint8_t*),
                              uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- u64 ---
 
-void intel_avx512_u64(uint64_t* data, size_t len) {
-  avx512_qsort(reinterpret_cast<int64_t*>(data), len);
+uint32_t intel_avx512_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    avx512_qsort(reinterpret_cast<int64_t*>(data), len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t intel_avx512_u64_by(uint64_t* data,
@@ -36,14 +47,13 @@ uint32_t intel_avx512_u64_by(uint64_t* data,
                                                   const uint64_t&,
                                                   uint8_t*),
                              uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- ffi_string ---
 
-void intel_avx512_ffi_string(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t intel_avx512_ffi_string(FFIString* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t intel_avx512_ffi_string_by(FFIString* data,
@@ -52,14 +62,13 @@ uint32_t intel_avx512_ffi_string_by(FFIString* data,
                                                          const FFIString&,
                                                          uint8_t*),
                                     uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- f128 ---
 
-void intel_avx512_f128(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t intel_avx512_f128(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t intel_avx512_f128_by(F128* data,
@@ -68,14 +77,13 @@ uint32_t intel_avx512_f128_by(F128* data,
                                                    const F128&,
                                                    uint8_t*),
                               uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- 1k ---
 
-void intel_avx512_1k(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t intel_avx512_1k(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t intel_avx512_1k_by(FFIOneKibiByte* data,
@@ -84,7 +92,18 @@ uint32_t intel_avx512_1k_by(FFIOneKibiByte* data,
                                                  const FFIOneKibiByte&,
                                                  uint8_t*),
                             uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
+}
+
+// --- dispatch ---
+
+// x86-simd-sort has no runtime dispatch in this version, the AVX512 path is always taken. Building
+// on a CPU without AVX512 fails.
+const char* intel_avx512_dispatch_target() {
+#if defined(__AVX512VBMI2__)
+  return "AVX512+VBMI2";
+#else
+  return "AVX512";
+#endif
 }
 }  // extern "C"
@@ -12,10 +12,10 @@
 
 template <typename T, typename F>
 uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
-  try {
+  FFI_TRY {
     ips4o::sort(data, data + len, make_compare_fn<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -24,8 +24,14 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
 extern "C" {
 // --- i32 ---
 
-void ips4o_unstable_i32(int32_t* data, size_t len) {
-  ips4o::sort(data, data + len);
+uint32_t ips4o_unstable_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    ips4o::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t ips4o_unstable_i32_by(int32_t* data,
@@ -39,8 +45,14 @@ uint32_t ips4o_unstable_i32_by(int32_t* data,
 
 // --- u64 ---
 
-void ips4o_unstable_u64(uint64_t* data, size_t len) {
-  ips4o::sort(data, data + len);
+uint32_t ips4o_unstable_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    ips4o::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t ips4o_unstable_u64_by(uint64_t* data,
@@ -54,9 +66,15 @@ uint32_t ips4o_unstable_u64_by(uint64_t* data,
 
 // --- ffi_string ---
 
-void ips4o_unstable_ffi_string(FFIString* data, size_t len) {
-  ips4o::sort(reinterpret_cast<FFIStringCpp*>(data),
-              reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t ips4o_unstable_ffi_string(FFIString* data, size_t len) {
+  FFI_TRY {
+    ips4o::sort(reinterpret_cast<FFIStringCpp*>(data),
+                reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t ips4o_unstable_ffi_string_by(FFIString* data,
@@ -70,9 +88,15 @@ uint32_t ips4o_unstable_ffi_string_by(FFIString* data,
 
 // --- f128 ---
 
-void ips4o_unstable_f128(F128* data, size_t len) {
-  ips4o::sort(reinterpret_cast<F128Cpp*>(data),
-              reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t ips4o_unstable_f128(F128* data, size_t len) {
+  FFI_TRY {
+    ips4o::sort(reinterpret_cast<F128Cpp*>(data),
+                reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t ips4o_unstable_f128_by(F128* data,
@@ -86,9 +110,15 @@ uint32_t ips4o_unstable_f128_by(F128* data,
 
 // --- 1k ---
 
-void ips4o_unstable_1k(FFIOneKibiByte* data, size_t len) {
-  ips4o::sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
-              reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t ips4o_unstable_1k(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    ips4o::sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
+                reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t ips4o_unstable_1k_by(FFIOneKibiByte* data,
@@ -8,10 +8,10 @@
 
 template <typename T, typename F>
 uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
-  try {
+  FFI_TRY {
     pdqsort(data, data + len, make_compare_fn<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -20,8 +20,14 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
 extern "C" {
 // --- i32 ---
 
-void pdqsort_unstable_i32(int32_t* data, size_t len) {
-  pdqsort(data, data + len);
+uint32_t pdqsort_unstable_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    pdqsort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t pdqsort_unstable_i32_by(int32_t* data,
@@ -35,8 +41,14 @@ uint32_t pdqsort_unstable_i32_by(int32_t* data,
 
 // --- u64 ---
 
-void pdqsort_unstable_u64(uint64_t* data, size_t len) {
-  pdqsort(data, data + len);
+uint32_t pdqsort_unstable_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    pdqsort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t pdqsort_unstable_u64_by(uint64_t* data,
@@ -50,9 +62,15 @@ uint32_t pdqsort_unstable_u64_by(uint64_t* data,
 
 // --- ffi_string ---
 
-void pdqsort_unstable_ffi_string(FFIString* data, size_t len) {
-  pdqsort(reinterpret_cast<FFIStringCpp*>(data),
-          reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t pdqsort_unstable_ffi_string(FFIString* data, size_t len) {
+  FFI_TRY {
+    pdqsort(reinterpret_cast<FFIStringCpp*>(data),
+            reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t pdqsort_unstable_ffi_string_by(FFIString* data,
@@ -66,9 +84,15 @@ uint32_t pdqsort_unstable_ffi_string_by(FFIString* data,
 
 // --- f128 ---
 
-void pdqsort_unstable_f128(F128* data, size_t len) {
-  pdqsort(reinterpret_cast<F128Cpp*>(data),
-          reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t pdqsort_unstable_f128(F128* data, size_t len) {
+  FFI_TRY {
+    pdqsort(reinterpret_cast<F128Cpp*>(data),
+            reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t pdqsort_unstable_f128_by(F128* data,
@@ -82,9 +106,15 @@ uint32_t pdqsort_unstable_f128_by(F128* data,
 
 // --- 1k ---
 
-void pdqsort_unstable_1k(FFIOneKibiByte* data, size_t len) {
-  pdqsort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
-          reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t pdqsort_unstable_1k(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    pdqsort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
+            reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t pdqsort_unstable_1k_by(FFIOneKibiByte* data,
@@ -39,7 +39,7 @@ using powersort_4way = algorithms::powersort_4way<
 
 template <typename T, template <typename> class SortT, typename F>
 uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
-  try {
+  FFI_TRY {
     // Powersort does not provide a way to specify a custom comparator function,
     // so we have to wrap it inside a type with custom comparison function.
     CompWrapper<T, F>::cmp_fn_local = cmp_fn;
@@ -49,11 +49,8 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
     SortT<CompWrapper<T, F>*>{}.sort(
         reinterpret_cast<CompWrapper<T, F>*>(data),
         reinterpret_cast<CompWrapper<T, F>*>(data + len));
-  } catch (const std::exception& exc) {
-    // fprintf(stderr, "[ERROR]: %s\n", exc.what());
-    return 1;
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -62,9 +59,15 @@ uint32_t sort_by_impl(T* data, size_t len, F cmp_fn, uint8_t* ctx) noexcept {
 extern "C" {
 // --- i32 ---
 
-void powersort_stable_i32(int32_t* data, size_t len) {
-  // Uses default configuration.
-  powersort<int32_t*>{}.sort(data, data + len);
+uint32_t powersort_stable_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    // Uses default configuration.
+    powersort<int32_t*>{}.sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_stable_i32_by(int32_t* data,
@@ -78,9 +81,15 @@ uint32_t powersort_stable_i32_by(int32_t* data,
 
 // --- u64 ---
 
-void powersort_stable_u64(uint64_t* data, size_t len) {
-  // Uses default configuration.
-  powersort<uint64_t*>{}.sort(data, data + len);
+uint32_t powersort_stable_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    // Uses default configuration.
+    powersort<uint64_t*>{}.sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_stable_u64_by(uint64_t* data,
@@ -94,9 +103,15 @@ uint32_t powersort_stable_u64_by(uint64_t* data,
 
 // --- ffi_string ---
 
-void powersort_stable_ffi_string(FFIString* data, size_t len) {
-  powersort<FFIStringCpp*>{}.sort(reinterpret_cast<FFIStringCpp*>(data),
-                                  reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t powersort_stable_ffi_string(FFIString* data, size_t len) {
+  FFI_TRY {
+    powersort<FFIStringCpp*>{}.sort(reinterpret_cast<FFIStringCpp*>(data),
+                                    reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_stable_ffi_string_by(FFIString* data,
@@ -111,9 +126,15 @@ uint32_t powersort_stable_ffi_string_by(FFIString* data,
 
 // --- f128 ---
 
-void powersort_stable_f128(F128* data, size_t len) {
-  powersort<F128Cpp*>{}.sort(reinterpret_cast<F128Cpp*>(data),
-                             reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t powersort_stable_f128(F128* data, size_t len) {
+  FFI_TRY {
+    powersort<F128Cpp*>{}.sort(reinterpret_cast<F128Cpp*>(data),
+                               reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_stable_f128_by(F128* data,
@@ -128,10 +149,16 @@ uint32_t powersort_stable_f128_by(F128* data,
 
 // --- 1k ---
 
-void powersort_stable_1k(FFIOneKibiByte* data, size_t len) {
-  powersort<FFIOneKiloByteCpp*>{}.sort(
-      reinterpret_cast<FFIOneKiloByteCpp*>(data),
-      reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t powersort_stable_1k(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    powersort<FFIOneKiloByteCpp*>{}.sort(
+        reinterpret_cast<FFIOneKiloByteCpp*>(data),
+        reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_stable_1k_by(FFIOneKibiByte* data,
@@ -148,9 +175,15 @@ uint32_t powersort_stable_1k_by(FFIOneKibiByte* data,
 
 // --- i32 ---
 
-void powersort_4way_stable_i32(int32_t* data, size_t len) {
-  // Uses default configuration.
-  powersort_4way<int32_t*>{}.sort(data, data + len);
+uint32_t powersort_4way_stable_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    // Uses default configuration.
+    powersort_4way<int32_t*>{}.sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_4way_stable_i32_by(int32_t* data,
@@ -164,9 +197,15 @@ uint32_t powersort_4way_stable_i32_by(int32_t* data,
 
 // --- u64 ---
 
-void powersort_4way_stable_u64(uint64_t* data, size_t len) {
-  // Uses default configuration.
-  powersort_4way<uint64_t*>{}.sort(data, data + len);
+uint32_t powersort_4way_stable_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    // Uses default configuration.
+    powersort_4way<uint64_t*>{}.sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_4way_stable_u64_by(uint64_t* data,
@@ -180,10 +219,16 @@ uint32_t powersort_4way_stable_u64_by(uint64_t* data,
 
 // --- ffi_string ---
 
-void powersort_4way_stable_ffi_string(FFIString* data, size_t len) {
-  powersort_4way<FFIStringCpp*>{}.sort(
-      reinterpret_cast<FFIStringCpp*>(data),
-      reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t powersort_4way_stable_ffi_string(FFIString* data, size_t len) {
+  FFI_TRY {
+    powersort_4way<FFIStringCpp*>{}.sort(
+        reinterpret_cast<FFIStringCpp*>(data),
+        reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_4way_stable_ffi_string_by(
@@ -197,9 +242,15 @@ uint32_t powersort_4way_stable_ffi_string_by(
 
 // --- f128 ---
 
-void powersort_4way_stable_f128(F128* data, size_t len) {
-  powersort_4way<F128Cpp*>{}.sort(reinterpret_cast<F128Cpp*>(data),
-                                  reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t powersort_4way_stable_f128(F128* data, size_t len) {
+  FFI_TRY {
+    powersort_4way<F128Cpp*>{}.sort(reinterpret_cast<F128Cpp*>(data),
+                                    reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_4way_stable_f128_by(F128* data,
@@ -214,10 +265,16 @@ uint32_t powersort_4way_stable_f128_by(F128* data,
 
 // --- 1k ---
 
-void powersort_4way_stable_1k(FFIOneKibiByte* data, size_t len) {
-  powersort_4way<FFIOneKiloByteCpp*>{}.sort(
-      reinterpret_cast<FFIOneKiloByteCpp*>(data),
-      reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t powersort_4way_stable_1k(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    powersort_4way<FFIOneKiloByteCpp*>{}.sort(
+        reinterpret_cast<FFIOneKiloByteCpp*>(data),
+        reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t powersort_4way_stable_1k_by(FFIOneKibiByte* data,
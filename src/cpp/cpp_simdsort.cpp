@@ -13,8 +13,14 @@
 extern "C" {
 // --- i32 ---
 
-void simdsort_avx2_i32(int32_t* data, size_t len) {
-  avx2_pivotonlast_sort(data, len);
+uint32_t simdsort_avx2_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    avx2_pivotonlast_sort(data, len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t simdsort_avx2_i32_by(int32_t* data,
@@ -23,14 +29,13 @@ uint32_t simdsort_avx2_i32_by(int32_t* data,
                                                    const int32_t&,
                                                    uint8_t*),
                               uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- u64 ---
 
-void simdsort_avx2_u64(uint64_t* data, size_t len) {
-  printf("Not supported\n");
+uint32_t simdsort_avx2_u64(uint64_t* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t simdsort_avx2_u64_by(uint64_t* data,
@@ -39,14 +44,13 @@ uint32_t simdsort_avx2_u64_by(uint64_t* data,
                                                    const uint64_t&,
                                                    uint8_t*),
                               uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- ffi_string ---
 
-void simdsort_avx2_ffi_string(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t simdsort_avx2_ffi_string(FFIString* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t simdsort_avx2_ffi_string_by(FFIString* data,
@@ -55,14 +59,13 @@ uint32_t simdsort_avx2_ffi_string_by(FFIString* data,
                                                           const FFIString&,
                                                           uint8_t*),
                                      uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- f128 ---
 
-void simdsort_avx2_f128(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t simdsort_avx2_f128(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t simdsort_avx2_f128_by(F128* data,
@@ -71,14 +74,13 @@ uint32_t simdsort_avx2_f128_by(F128* data,
                                                     const F128&,
                                                     uint8_t*),
                                uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- 1k ---
 
-void simdsort_avx2_1k(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t simdsort_avx2_1k(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t simdsort_avx2_1k_by(FFIOneKibiByte* data,
@@ -87,7 +89,13 @@ uint32_t simdsort_avx2_1k_by(FFIOneKibiByte* data,
                                                   const FFIOneKibiByte&,
                                                   uint8_t*),
                              uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
+}
+
+// --- dispatch ---
+
+// simd-sort only has an AVX2 implementation, which is always taken.
+const char* simdsort_avx2_dispatch_target() {
+  return "AVX2";
 }
 }  // extern "C"
@@ -19,7 +19,7 @@ struct CompareLambda {
     const CompResult comp_result = cmp_fn(a, b, ctx);
 
     if (comp_result.is_panic) {
-      throw std::runtime_error("panic in Rust comparison function");
+      FFI_THROW(RustPanicException());
     }
 
     return comp_result.cmp_result == -1;
@@ -34,10 +34,10 @@ uint32_t sort_stable_by_impl(T* data,
                              size_t len,
                              CompResult (*cmp_fn)(const T&, const T&, uint8_t*),
                              uint8_t* ctx) {
-  try {
+  FFI_TRY {
     std::stable_sort(data, data + len, CompareLambda<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -50,10 +50,10 @@ uint32_t sort_unstable_by_impl(T* data,
                                                     const T&,
                                                     uint8_t*),
                                uint8_t* ctx) {
-  try {
+  FFI_TRY {
     std::sort(data, data + len, CompareLambda<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -64,8 +64,14 @@ uint32_t sort_unstable_by_impl(T* data,
 extern "C" {
 // --- i32 ---
 
-void MAKE_FUNC_NAME(sort_stable, i32)(int32_t* data, size_t len) {
-  std::stable_sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, i32)(int32_t* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, i32_by)(
@@ -76,8 +82,14 @@ uint32_t MAKE_FUNC_NAME(sort_stable, i32_by)(
   return sort_stable_by_impl(data, len, cmp_fn, ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, i32)(int32_t* data, size_t len) {
-  std::sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, i32)(int32_t* data, size_t len) {
+  FFI_TRY {
+    std::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, i32_by)(
@@ -90,8 +102,14 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, i32_by)(
 
 // --- u64 ---
 
-void MAKE_FUNC_NAME(sort_stable, u64)(uint64_t* data, size_t len) {
-  std::stable_sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, u64)(uint64_t* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, u64_by)(
@@ -102,8 +120,14 @@ uint32_t MAKE_FUNC_NAME(sort_stable, u64_by)(
   return sort_stable_by_impl(data, len, cmp_fn, ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, u64)(uint64_t* data, size_t len) {
-  std::sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, u64)(uint64_t* data, size_t len) {
+  FFI_TRY {
+    std::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, u64_by)(
@@ -116,8 +140,8 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, u64_by)(
 
 // --- FFIString ---
 
-void MAKE_FUNC_NAME(sort_stable, ffi_string)(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string)(FFIString* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string_by)(
@@ -125,12 +149,12 @@ uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string_by)(
     size_t len,
     CompResult (*cmp_fn)(const FFIString&, const FFIString&, uint8_t*),
     uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
-void MAKE_FUNC_NAME(sort_unstable, ffi_string)(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string)(FFIString* data,
+                                                   size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string_by)(
@@ -138,14 +162,13 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string_by)(
     size_t len,
     CompResult (*cmp_fn)(const FFIString&, const FFIString&, uint8_t*),
     uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- f128 ---
 
-void MAKE_FUNC_NAME(sort_stable, f128)(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_stable, f128)(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, f128_by)(F128* data,
@@ -154,12 +177,11 @@ uint32_t MAKE_FUNC_NAME(sort_stable, f128_by)(F128* data,
                                                                    const F128&,
                                                                    uint8_t*),
                                               uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
-void MAKE_FUNC_NAME(sort_unstable, f128)(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_unstable, f128)(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, f128_by)(
@@ -167,14 +189,13 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, f128_by)(
     size_t len,
     CompResult (*cmp_fn)(const F128&, const F128&, uint8_t*),
     uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- 1k ---
 
-void MAKE_FUNC_NAME(sort_stable, 1k)(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_stable, 1k)(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable,
@@ -184,12 +205,11 @@ uint32_t MAKE_FUNC_NAME(sort_stable,
                                                     const FFIOneKibiByte&,
                                                     uint8_t*),
                                uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
-void MAKE_FUNC_NAME(sort_unstable, 1k)(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t MAKE_FUNC_NAME(sort_unstable, 1k)(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable,
@@ -199,7 +219,6 @@ uint32_t MAKE_FUNC_NAME(sort_unstable,
                                                     const FFIOneKibiByte&,
                                                     uint8_t*),
                                uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 }  // extern "C"
@@ -10,10 +10,10 @@ uint32_t sort_stable_by_impl(T* data,
                              size_t len,
                              F cmp_fn,
                              uint8_t* ctx) noexcept {
-  try {
+  FFI_TRY {
     std::stable_sort(data, data + len, make_compare_fn<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -24,10 +24,10 @@ uint32_t sort_unstable_by_impl(T* data,
                                size_t len,
                                F cmp_fn,
                                uint8_t* ctx) noexcept {
-  try {
+  FFI_TRY {
     std::sort(data, data + len, make_compare_fn<T>(cmp_fn, ctx));
-  } catch (...) {
-    return 1;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
   }
 
   return 0;
@@ -37,13 +37,23 @@ uint32_t sort_unstable_by_impl(T* data,
 #define MAKE_FUNC_NAME(name, suffix) name##_sys_##suffix
 #elif defined(STD_LIB_LIBCXX)
 #define MAKE_FUNC_NAME(name, suffix) name##_libcxx_##suffix
+#elif defined(STD_LIB_GNU)
+#define MAKE_FUNC_NAME(name, suffix) name##_gnu_##suffix
+#elif defined(STD_LIB_MSVC)
+#define MAKE_FUNC_NAME(name, suffix) name##_msvc_##suffix
 #endif
 
 extern "C" {
 // --- i32 ---
 
-void MAKE_FUNC_NAME(sort_stable, i32)(int32_t* data, size_t len) {
-  std::stable_sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, i32)(int32_t* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, i32_by)(
@@ -54,8 +64,14 @@ uint32_t MAKE_FUNC_NAME(sort_stable, i32_by)(
   return sort_stable_by_impl(data, len, cmp_fn, ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, i32)(int32_t* data, size_t len) {
-  std::sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, i32)(int32_t* data, size_t len) {
+  FFI_TRY {
+    std::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, i32_by)(
@@ -68,8 +84,14 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, i32_by)(
 
 // --- u64 ---
 
-void MAKE_FUNC_NAME(sort_stable, u64)(uint64_t* data, size_t len) {
-  std::stable_sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, u64)(uint64_t* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, u64_by)(
@@ -80,8 +102,14 @@ uint32_t MAKE_FUNC_NAME(sort_stable, u64_by)(
   return sort_stable_by_impl(data, len, cmp_fn, ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, u64)(uint64_t* data, size_t len) {
-  std::sort(data, data + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, u64)(uint64_t* data, size_t len) {
+  FFI_TRY {
+    std::sort(data, data + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, u64_by)(
@@ -94,9 +122,15 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, u64_by)(
 
 // --- FFIString ---
 
-void MAKE_FUNC_NAME(sort_stable, ffi_string)(FFIString* data, size_t len) {
-  std::stable_sort(reinterpret_cast<FFIStringCpp*>(data),
-                   reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string)(FFIString* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(reinterpret_cast<FFIStringCpp*>(data),
+                     reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string_by)(
@@ -108,9 +142,16 @@ uint32_t MAKE_FUNC_NAME(sort_stable, ffi_string_by)(
                              ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, ffi_string)(FFIString* data, size_t len) {
-  std::sort(reinterpret_cast<FFIStringCpp*>(data),
-            reinterpret_cast<FFIStringCpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string)(FFIString* data,
+                                                   size_t len) {
+  FFI_TRY {
+    std::sort(reinterpret_cast<FFIStringCpp*>(data),
+              reinterpret_cast<FFIStringCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string_by)(
@@ -124,9 +165,15 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, ffi_string_by)(
 
 // --- f128 ---
 
-void MAKE_FUNC_NAME(sort_stable, f128)(F128* data, size_t len) {
-  std::stable_sort(reinterpret_cast<F128Cpp*>(data),
-                   reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, f128)(F128* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(reinterpret_cast<F128Cpp*>(data),
+                     reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable, f128_by)(F128* data,
@@ -139,9 +186,15 @@ uint32_t MAKE_FUNC_NAME(sort_stable, f128_by)(F128* data,
                              ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, f128)(F128* data, size_t len) {
-  std::sort(reinterpret_cast<F128Cpp*>(data),
-            reinterpret_cast<F128Cpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, f128)(F128* data, size_t len) {
+  FFI_TRY {
+    std::sort(reinterpret_cast<F128Cpp*>(data),
+              reinterpret_cast<F128Cpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable, f128_by)(
@@ -155,9 +208,15 @@ uint32_t MAKE_FUNC_NAME(sort_unstable, f128_by)(
 
 // --- 1k ---
 
-void MAKE_FUNC_NAME(sort_stable, 1k)(FFIOneKibiByte* data, size_t len) {
-  std::stable_sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
-                   reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_stable, 1k)(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    std::stable_sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
+                     reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_stable,
@@ -171,9 +230,15 @@ uint32_t MAKE_FUNC_NAME(sort_stable,
                              cmp_fn, ctx);
 }
 
-void MAKE_FUNC_NAME(sort_unstable, 1k)(FFIOneKibiByte* data, size_t len) {
-  std::sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
-            reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+uint32_t MAKE_FUNC_NAME(sort_unstable, 1k)(FFIOneKibiByte* data, size_t len) {
+  FFI_TRY {
+    std::sort(reinterpret_cast<FFIOneKiloByteCpp*>(data),
+              reinterpret_cast<FFIOneKiloByteCpp*>(data) + len);
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t MAKE_FUNC_NAME(sort_unstable,
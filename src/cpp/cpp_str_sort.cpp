@@ -0,0 +1,99 @@
+#include <stdint.h>
+
+#include <algorithm>
+#include <string_view>
+#include <vector>
+
+#include "shared.h"
+
+namespace {
+std::string_view as_str(const FFIStr& s) {
+  return std::string_view{s.data, s.len};
+}
+
+// Byte at depth, or -1 if the string is shorter. Shorter strings sort first,
+// which matches the lexicographic byte order of Rust str.
+int byte_at(const FFIStr& s, size_t depth) {
+  return depth < s.len ? static_cast<uint8_t>(s.data[depth]) : -1;
+}
+
+// All strings in data share the first depth bytes.
+void insertion_sort_from(FFIStr* data, size_t len, size_t depth) {
+  for (size_t i = 1; i < len; ++i) {
+    const FFIStr tmp = data[i];
+    const auto tmp_tail = as_str(tmp).substr(depth);
+
+    size_t j = i;
+    while (j > 0 && tmp_tail < as_str(data[j - 1]).substr(depth)) {
+      data[j] = data[j - 1];
+      --j;
+    }
+    data[j] = tmp;
+  }
+}
+
+constexpr size_t INSERTION_SORT_THRESHOLD = 32;
+
+// Recursion depth is bounded by the longest common prefix.
+void msd_radix_sort_impl(FFIStr* data,
+                         size_t len,
+                         size_t depth,
+                         FFIStr* scratch) {
+  if (len < INSERTION_SORT_THRESHOLD) {
+    insertion_sort_from(data, len, depth);
+    return;
+  }
+
+  // Bucket 0 holds the strings that end before depth.
+  size_t counts[257] = {};
+  for (size_t i = 0; i < len; ++i) {
+    counts[byte_at(data[i], depth) + 1] += 1;
+  }
+
+  size_t offsets[257];
+  size_t offset = 0;
+  for (size_t b = 0; b < 257; ++b) {
+    offsets[b] = offset;
+    offset += counts[b];
+  }
+
+  for (size_t i = 0; i < len; ++i) {
+    scratch[offsets[byte_at(data[i], depth) + 1]++] = data[i];
+  }
+  std::copy(scratch, scratch + len, data);
+
+  // The strings in bucket 0 are all equal.
+  size_t start = counts[0];
+  for (size_t b = 1; b < 257; ++b) {
+    if (counts[b] > 1) {
+      msd_radix_sort_impl(data + start, counts[b], depth + 1, scratch);
+    }
+    start += counts[b];
+  }
+}
+}  // namespace
+
+extern "C" {
+uint32_t cpp_std_sort_str(FFIStr* data, size_t len) {
+  FFI_TRY {
+    std::sort(data, data + len, [](const FFIStr& a, const FFIStr& b) {
+      return as_str(a) < as_str(b);
+    });
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
+}
+
+uint32_t cpp_msd_radix_sort_str(FFIStr* data, size_t len) {
+  FFI_TRY {
+    std::vector<FFIStr> scratch(len);
+    msd_radix_sort_impl(data, len, 0, scratch.data());
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
+}
+}  // extern "C"
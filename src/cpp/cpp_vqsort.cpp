@@ -1,4 +1,5 @@
 #include "thirdparty/highway/sort/vqsort.h"
+#include "thirdparty/highway/targets.h"
 
 #include <stdexcept>
 
@@ -9,8 +10,14 @@
 extern "C" {
 // --- i32 ---
 
-void vqsort_i32(int32_t* data, size_t len) {
-  hwy::Sorter{}(data, len, hwy::SortAscending{});
+uint32_t vqsort_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    hwy::Sorter{}(data, len, hwy::SortAscending{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t vqsort_i32_by(int32_t* data,
@@ -19,14 +26,19 @@ uint32_t vqsort_i32_by(int32_t* data,
                                             const int32_t&,
                                             uint8_t*),
                        uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- u64 ---
 
-void vqsort_u64(uint64_t* data, size_t len) {
-  hwy::Sorter{}(data, len, hwy::SortAscending{});
+uint32_t vqsort_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    hwy::Sorter{}(data, len, hwy::SortAscending{});
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t vqsort_u64_by(uint64_t* data,
@@ -35,14 +47,13 @@ uint32_t vqsort_u64_by(uint64_t* data,
                                             const uint64_t&,
                                             uint8_t*),
                        uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- ffi_string ---
 
-void vqsort_ffi_string(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t vqsort_ffi_string(FFIString* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t vqsort_ffi_string_by(FFIString* data,
@@ -51,14 +62,13 @@ uint32_t vqsort_ffi_string_by(FFIString* data,
                                                    const FFIString&,
                                                    uint8_t*),
                               uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- f128 ---
 
-void vqsort_f128(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t vqsort_f128(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t vqsort_f128_by(F128* data,
@@ -67,14 +77,13 @@ uint32_t vqsort_f128_by(F128* data,
                                              const F128&,
                                              uint8_t*),
                         uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- 1k ---
 
-void vqsort_1k(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t vqsort_1k(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t vqsort_1k_by(FFIOneKibiByte* data,
@@ -83,7 +92,14 @@ uint32_t vqsort_1k_by(FFIOneKibiByte* data,
                                            const FFIOneKibiByte&,
                                            uint8_t*),
                       uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
+}
+
+// --- dispatch ---
+
+// vqsort is called via HWY_NAMESPACE and not HWY_DYNAMIC_DISPATCH, so the target is fixed at
+// compile time by -march=native, and not by the CPU the benchmarks run on.
+const char* vqsort_dispatch_target() {
+  return hwy::TargetName(HWY_STATIC_TARGET);
 }
 }  // extern "C"
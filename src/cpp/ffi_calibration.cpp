@@ -0,0 +1,57 @@
+#include <stdint.h>
+
+#include "shared.h"
+
+// Performs comp_count comparisons of adjacent elements via the Rust callback,
+// without moving any elements. Used to measure the per comparison cost of
+// calling back into Rust, see other::ffi_calibration.
+template <typename T>
+uint32_t null_sort_by_impl(T* data,
+                           size_t len,
+                           size_t comp_count,
+                           CompResult (*cmp_fn)(const T&, const T&, uint8_t*),
+                           uint8_t* ctx,
+                           size_t* less_count) noexcept {
+  FFI_TRY {
+    auto is_less = make_compare_fn<T>(cmp_fn, ctx);
+
+    size_t count = 0;
+    if (len >= 2) {
+      size_t idx = 0;
+      for (size_t i = 0; i < comp_count; ++i) {
+        count += is_less(data[idx], data[idx + 1]);
+        idx = (idx + 2 < len) ? idx + 1 : 0;
+      }
+    }
+
+    *less_count = count;
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
+}
+
+extern "C" {
+uint32_t ffi_null_sort_i32_by(int32_t* data,
+                              size_t len,
+                              size_t comp_count,
+                              CompResult (*cmp_fn)(const int32_t&,
+                                                   const int32_t&,
+                                                   uint8_t*),
+                              uint8_t* ctx,
+                              size_t* less_count) {
+  return null_sort_by_impl(data, len, comp_count, cmp_fn, ctx, less_count);
+}
+
+uint32_t ffi_null_sort_u64_by(uint64_t* data,
+                              size_t len,
+                              size_t comp_count,
+                              CompResult (*cmp_fn)(const uint64_t&,
+                                                   const uint64_t&,
+                                                   uint8_t*),
+                              uint8_t* ctx,
+                              size_t* less_count) {
+  return null_sort_by_impl(data, len, comp_count, cmp_fn, ctx, less_count);
+}
+}  // extern "C"
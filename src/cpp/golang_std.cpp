@@ -33,24 +33,24 @@ cmp_fn_ptr_t<T> make_compare_fn_go(CompResult (*cmp_fn)(const T&,
   };
 }
 
-#define NOT_IMPL(STABILITY, TYPE_NAME, TYPE)                          \
-  void golang_std_##STABILITY##_##TYPE_NAME(TYPE* data, size_t len) { \
-    printf("Not supported\n");                                        \
-  }                                                                   \
-                                                                      \
-  uint32_t golang_std_##STABILITY##_##TYPE_NAME##_by(                 \
-      TYPE* data, size_t len,                                         \
-      CompResult (*cmp_fn)(const TYPE&, const TYPE&, uint8_t*),       \
-      uint8_t* ctx) {                                                 \
-    printf("Not supported\n");                                        \
-    return 1;                                                         \
+#define NOT_IMPL(STABILITY, TYPE_NAME, TYPE)                              \
+  uint32_t golang_std_##STABILITY##_##TYPE_NAME(TYPE* data, size_t len) { \
+    return FFI_UNSUPPORTED;                                               \
+  }                                                                       \
+                                                                          \
+  uint32_t golang_std_##STABILITY##_##TYPE_NAME##_by(                     \
+      TYPE* data, size_t len,                                             \
+      CompResult (*cmp_fn)(const TYPE&, const TYPE&, uint8_t*),           \
+      uint8_t* ctx) {                                                     \
+    return FFI_UNSUPPORTED;                                               \
   }
 
 #define IMPL(STABILITY, TYPE_NAME, TYPE, SORT_NAME_BASE)                    \
-  void golang_std_##STABILITY##_##TYPE_NAME(TYPE* data, size_t len) {       \
+  uint32_t golang_std_##STABILITY##_##TYPE_NAME(TYPE* data, size_t len) {   \
     SORT_NAME_BASE(GoSlice{/*data:*/ reinterpret_cast<void*>(data),         \
                            /*len:*/ static_cast<GoInt>(len),                \
                            /*cap:*/ static_cast<GoInt>(len)});              \
+    return FFI_OK;                                                          \
   }                                                                         \
                                                                             \
   uint32_t golang_std_##STABILITY##_##TYPE_NAME##_by(                       \
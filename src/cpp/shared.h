@@ -2,8 +2,28 @@
 
 #include <stddef.h>
 #include <stdint.h>
+// printf and free, MSVC doesn't include these transitively.
+#include <stdio.h>
+#include <stdlib.h>
+
+#include <new>
+#include <stdexcept>
 
 extern "C" {
+// Return codes of the exported sort functions. Must match the FFI_* constants
+// in ffi_util.rs. Code without exceptions, eg. C sorts, returns FFI_BAD_ALLOC
+// directly if an allocation fails, it is reported separately from a panic in
+// the comparison function. Sorts that can't handle a type return
+// FFI_UNSUPPORTED from its stubs.
+enum FFIErrorCode {
+  FFI_OK = 0,
+  FFI_RUST_PANIC = 1,
+  FFI_BAD_ALLOC = 2,
+  FFI_STD_EXCEPTION = 3,
+  FFI_UNKNOWN_EXCEPTION = 4,
+  FFI_UNSUPPORTED = 5
+};
+
 struct CompResult {
   int8_t cmp_result;
   bool is_panic;
@@ -15,6 +35,12 @@ struct FFIString {
   size_t capacity;
 };
 
+// Borrowed UTF-8 string, the foreign side may only permute these values.
+struct FFIStr {
+  const char* data;
+  size_t len;
+};
+
 struct F128 {
   double x;
   double y;
@@ -25,6 +51,51 @@ struct FFIOneKibiByte {
 };
 }
 
+// Thrown by the comparison wrappers if the Rust comparison function panicked.
+struct RustPanicException : public std::runtime_error {
+  RustPanicException()
+      : std::runtime_error("panic in Rust comparison function") {}
+};
+
+// The sorts are built with -fno-exceptions if the Rust side is built with
+// panic=abort. A panic in the Rust comparison function then aborts before it
+// returns, and so does a failed allocation in C++. The try blocks run as plain
+// code and the catch blocks are dead code.
+#if defined(__cpp_exceptions) || defined(__EXCEPTIONS) || defined(_CPPUNWIND)
+#define FFI_HAS_EXCEPTIONS
+#endif
+
+#ifdef FFI_HAS_EXCEPTIONS
+#define FFI_TRY try
+#define FFI_CATCH_ALL catch (...)
+#define FFI_THROW(exception) throw exception
+#else
+#define FFI_TRY if (true)
+#define FFI_CATCH_ALL else
+#define FFI_THROW(exception) abort()
+#endif
+
+// Letting a C++ exception unwind into Rust is UB, so every exported function
+// catches everything and maps it to an error code with this function. Must
+// only be called from inside a catch block.
+inline uint32_t ffi_error_code_from_current_exception() {
+#ifdef FFI_HAS_EXCEPTIONS
+  try {
+    throw;
+  } catch (const RustPanicException&) {
+    return FFI_RUST_PANIC;
+  } catch (const std::bad_alloc&) {
+    return FFI_BAD_ALLOC;
+  } catch (const std::exception&) {
+    return FFI_STD_EXCEPTION;
+  } catch (...) {
+    return FFI_UNKNOWN_EXCEPTION;
+  }
+#else
+  return FFI_UNKNOWN_EXCEPTION;
+#endif
+}
+
 #if __cplusplus >= 201703L
 #include <string_view>
 
@@ -147,7 +218,7 @@ struct CompWrapper {
     const auto comp_result = cmp_fn_local(_value, other._value, ctx_local);
 
     if (comp_result.is_panic) {
-      throw std::runtime_error{"panic in Rust comparison function"};
+      FFI_THROW(RustPanicException{});
     }
 
     switch (comp_result.cmp_result) {
@@ -158,7 +229,7 @@ struct CompWrapper {
       case 1:
         return std::strong_ordering::greater;
       default:
-        throw std::runtime_error{"Unknown cmp_result value"};
+        FFI_THROW(std::runtime_error{"Unknown cmp_result value"});
     }
   }
 
@@ -171,7 +242,7 @@ auto make_compare_fn(F cmp_fn, uint8_t* ctx) {
     const auto comp_result = cmp_fn(a, b, ctx);
 
     if (comp_result.is_panic) {
-      throw std::runtime_error{"panic in Rust comparison function"};
+      FFI_THROW(RustPanicException{});
     }
 
     return comp_result.cmp_result == -1;
@@ -199,7 +270,30 @@ CMPFUNC* make_compare_fn_c(CompResult (*cmp_fn)(const T&, const T&, uint8_t*),
     const auto comp_result = cmp_fn_local(a, b, ctx_local);
 
     if (comp_result.is_panic) {
-      throw std::runtime_error{"panic in Rust comparison function"};
+      FFI_THROW(RustPanicException{});
+    }
+
+    return comp_result.cmp_result;
+  };
+}
+
+// Three-way comparison callback as provided by rust_fn_cmp_threeway. Takes the
+// element pointers as is, so no copy of T is needed to call it.
+typedef CompResult CMPFUNC_THREEWAY(const void* a, const void* b, uint8_t* ctx);
+
+inline CMPFUNC* make_compare_fn_c_threeway(CMPFUNC_THREEWAY* cmp_fn,
+                                           uint8_t* ctx) {
+  thread_local static CMPFUNC_THREEWAY* cmp_fn_local = nullptr;
+  thread_local static uint8_t* ctx_local = nullptr;
+
+  cmp_fn_local = cmp_fn;
+  ctx_local = ctx;
+
+  return [](const void* a_ptr, const void* b_ptr) -> int {
+    const auto comp_result = cmp_fn_local(a_ptr, b_ptr, ctx_local);
+
+    if (comp_result.is_panic) {
+      FFI_THROW(RustPanicException{});
     }
 
     return comp_result.cmp_result;
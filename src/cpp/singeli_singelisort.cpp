@@ -14,11 +14,17 @@ size_t aux_alloc_size(size_t len) {
 extern "C" {
 // --- i32 ---
 
-void singelisort_i32(int32_t* data, size_t len) {
-  std::vector<int32_t> aux_memory{};
-  aux_memory.reserve(aux_alloc_size(len));
-  sort32(data, static_cast<uint64_t>(len), aux_memory.data(),
-         aux_memory.capacity() * sizeof(int32_t));
+uint32_t singelisort_i32(int32_t* data, size_t len) {
+  FFI_TRY {
+    std::vector<int32_t> aux_memory{};
+    aux_memory.reserve(aux_alloc_size(len));
+    sort32(data, static_cast<uint64_t>(len), aux_memory.data(),
+           aux_memory.capacity() * sizeof(int32_t));
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t singelisort_i32_by(int32_t* data,
@@ -27,17 +33,22 @@ uint32_t singelisort_i32_by(int32_t* data,
                                                  const int32_t&,
                                                  uint8_t*),
                             uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- u64 ---
 
-void singelisort_u64(uint64_t* data, size_t len) {
-  std::vector<uint64_t> aux_memory{};
-  aux_memory.reserve(aux_alloc_size(len));
-  sort_u64(data, static_cast<uint64_t>(len), aux_memory.data(),
-           aux_memory.capacity() * sizeof(uint64_t));
+uint32_t singelisort_u64(uint64_t* data, size_t len) {
+  FFI_TRY {
+    std::vector<uint64_t> aux_memory{};
+    aux_memory.reserve(aux_alloc_size(len));
+    sort_u64(data, static_cast<uint64_t>(len), aux_memory.data(),
+             aux_memory.capacity() * sizeof(uint64_t));
+  } FFI_CATCH_ALL {
+    return ffi_error_code_from_current_exception();
+  }
+
+  return FFI_OK;
 }
 
 uint32_t singelisort_u64_by(uint64_t* data,
@@ -46,14 +57,13 @@ uint32_t singelisort_u64_by(uint64_t* data,
                                                  const uint64_t&,
                                                  uint8_t*),
                             uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- ffi_string ---
 
-void singelisort_ffi_string(FFIString* data, size_t len) {
-  printf("Not supported\n");
+uint32_t singelisort_ffi_string(FFIString* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t singelisort_ffi_string_by(FFIString* data,
@@ -62,14 +72,13 @@ uint32_t singelisort_ffi_string_by(FFIString* data,
                                                         const FFIString&,
                                                         uint8_t*),
                                    uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- f128 ---
 
-void singelisort_f128(F128* data, size_t len) {
-  printf("Not supported\n");
+uint32_t singelisort_f128(F128* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t singelisort_f128_by(F128* data,
@@ -78,14 +87,13 @@ uint32_t singelisort_f128_by(F128* data,
                                                   const F128&,
                                                   uint8_t*),
                              uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 
 // --- 1k ---
 
-void singelisort_1k(FFIOneKibiByte* data, size_t len) {
-  printf("Not supported\n");
+uint32_t singelisort_1k(FFIOneKibiByte* data, size_t len) {
+  return FFI_UNSUPPORTED;
 }
 
 uint32_t singelisort_1k_by(FFIOneKibiByte* data,
@@ -94,7 +102,6 @@ uint32_t singelisort_1k_by(FFIOneKibiByte* data,
                                                 const FFIOneKibiByte&,
                                                 uint8_t*),
                            uint8_t* ctx) {
-  printf("Not supported\n");
-  return 1;
+  return FFI_UNSUPPORTED;
 }
 }  // extern "C"
@@ -0,0 +1,86 @@
+//! Reports which CPU specific code path the SIMD sorts take in the current build and on the current
+//! machine.
+//!
+//! Results of vqsort or x86-simd-sort on two machines can only be compared if both used the same
+//! instruction set, eg. AVX2 vs AVX512 changes the picture entirely. util/run_benchmarks.py stores
+//! this information next to the results, see src/bin/dispatch_info.rs.
+
+/// The code path selected for a single sort implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DispatchTarget {
+    /// [`sort_test_tools::Sort::name`] of the implementation, or `rust` for the target features
+    /// the Rust sorts are compiled with.
+    pub sort_name: String,
+    pub target: String,
+}
+
+// Only a selection, the ones that change the codegen of the small-sorts and partitions.
+#[allow(unused_macros)]
+macro_rules! enabled_target_features {
+    ($($feature:tt),*) => {{
+        let mut features = Vec::new();
+        $(
+            if cfg!(target_feature = $feature) {
+                features.push($feature);
+            }
+        )*
+        features
+    }};
+}
+
+/// Target features the Rust sorts are compiled with. Unlike the C++ SIMD sorts they are not
+/// compiled with -march=native by default, this changes with eg. RUSTFLAGS="-Ctarget-cpu=native".
+pub fn rust_target_features() -> Vec<&'static str> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    return enabled_target_features!(
+        "sse4.2",
+        "popcnt",
+        "bmi1",
+        "bmi2",
+        "avx",
+        "avx2",
+        "avx512f",
+        "avx512vl",
+        "avx512vbmi2"
+    );
+
+    #[cfg(target_arch = "aarch64")]
+    return enabled_target_features!("neon", "sve", "sve2");
+
+    #[allow(unreachable_code)]
+    Vec::new()
+}
+
+/// Returns the selected code path of every enabled sort that has CPU specific code paths, plus
+/// one entry named `rust` for [`rust_target_features`].
+pub fn selected_targets() -> Vec<DispatchTarget> {
+    #[allow(unused_mut)]
+    let mut targets = vec![DispatchTarget {
+        sort_name: "rust".into(),
+        target: rust_target_features().join(","),
+    }];
+
+    #[allow(unused_macros)]
+    macro_rules! register {
+        ($sort_impl_path:path) => {{
+            use sort_test_tools::Sort;
+            use $sort_impl_path::*;
+
+            targets.push(DispatchTarget {
+                sort_name: SortImpl::name(),
+                target: dispatch_target(),
+            });
+        }};
+    }
+
+    #[cfg(feature = "cpp_simdsort")]
+    register!(crate::other::cpp_simdsort);
+
+    #[cfg(feature = "cpp_vqsort")]
+    register!(crate::other::cpp_vqsort);
+
+    #[cfg(feature = "cpp_intel_avx512")]
+    register!(crate::other::cpp_intel_avx512);
+
+    targets
+}
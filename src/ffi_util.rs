@@ -1,8 +1,85 @@
 #![allow(dead_code, unused_macros)] // Dependent on optional features.
 
+use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 
-use sort_test_tools::ffi_types::CompResult;
+use sort_test_tools::ffi_types::{CompResult, FFIStr};
+
+thread_local! {
+    static PANIC_PAYLOAD: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+}
+
+// Return codes of the exported C and C++ sort functions. Must match FFIErrorCode in shared.h.
+pub(crate) const FFI_OK: u32 = 0;
+pub(crate) const FFI_RUST_PANIC: u32 = 1;
+pub(crate) const FFI_BAD_ALLOC: u32 = 2;
+pub(crate) const FFI_STD_EXCEPTION: u32 = 3;
+pub(crate) const FFI_UNKNOWN_EXCEPTION: u32 = 4;
+pub(crate) const FFI_UNSUPPORTED: u32 = 5;
+
+/// How an allocation failure reported by a foreign sort function is handled.
+///
+/// C++ sorts report `std::bad_alloc`, C sorts and plugins return `FFI_BAD_ALLOC` themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AllocFailurePolicy {
+    /// Panic with a message naming the sort function. This is the default.
+    Panic,
+    /// Print the message and abort the process, like Rust does by default if an allocation fails.
+    Abort,
+}
+
+static ABORT_ON_ALLOC_FAILURE: AtomicBool = AtomicBool::new(false);
+
+/// Sets the allocation failure policy for all threads.
+pub fn set_alloc_failure_policy(policy: AllocFailurePolicy) {
+    ABORT_ON_ALLOC_FAILURE.store(policy == AllocFailurePolicy::Abort, AtomicOrdering::Relaxed);
+}
+
+pub fn alloc_failure_policy() -> AllocFailurePolicy {
+    if ABORT_ON_ALLOC_FAILURE.load(AtomicOrdering::Relaxed) {
+        AllocFailurePolicy::Abort
+    } else {
+        AllocFailurePolicy::Panic
+    }
+}
+
+fn handle_alloc_failure(fn_name: &str) -> ! {
+    let msg = format!("{fn_name}: allocation failed");
+
+    match alloc_failure_policy() {
+        AllocFailurePolicy::Panic => panic!("{msg}"),
+        AllocFailurePolicy::Abort => {
+            eprintln!("{msg}");
+            std::process::abort();
+        }
+    }
+}
+
+/// Must be called after every call into a foreign sort function.
+///
+/// If the comparison function panicked, the original panic is resumed with its payload, so the
+/// panic message and type are the same as they would be for a Rust sort. C++ exceptions are caught
+/// on the C++ side and turned into a panic here, naming the function that failed.
+pub(crate) fn handle_ffi_ret_code(ret_code: u32, fn_name: &str) {
+    // Always take the payload, so that it doesn't leak into the next sort call on this thread.
+    let payload = PANIC_PAYLOAD.with(|payload| payload.borrow_mut().take());
+
+    match ret_code {
+        FFI_OK => {}
+        FFI_RUST_PANIC => match payload {
+            Some(payload) => std::panic::resume_unwind(payload),
+            None => panic!("Panic in comparison function"),
+        },
+        FFI_BAD_ALLOC => handle_alloc_failure(fn_name),
+        FFI_STD_EXCEPTION => panic!("{fn_name}: C++ exception derived from std::exception"),
+        FFI_UNKNOWN_EXCEPTION => panic!("{fn_name}: unknown C++ exception"),
+        FFI_UNSUPPORTED => panic!("{fn_name}: not supported"),
+        _ => panic!("{fn_name}: unknown error code {ret_code}"),
+    }
+}
 
 pub(crate) unsafe extern "C" fn rust_fn_cmp<T, F: FnMut(&T, &T) -> Ordering>(
     a: &T,
@@ -21,7 +98,15 @@ pub(crate) unsafe extern "C" fn rust_fn_cmp<T, F: FnMut(&T, &T) -> Ordering>(
             is_panic: false,
         },
         Err(err) => {
-            eprintln!("Panic during compare call: {err:?}");
+            // Keep the first payload, the foreign sort is expected to stop calling the comparison
+            // function once it sees is_panic.
+            PANIC_PAYLOAD.with(|payload| {
+                let mut payload = payload.borrow_mut();
+                if payload.is_none() {
+                    *payload = Some(err);
+                }
+            });
+
             CompResult {
                 cmp_result: 0,
                 is_panic: true,
@@ -30,6 +115,85 @@ pub(crate) unsafe extern "C" fn rust_fn_cmp<T, F: FnMut(&T, &T) -> Ordering>(
     }
 }
 
+/// Like `rust_fn_cmp` but without `catch_unwind`, a panic in the comparison function aborts the
+/// process instead of being resumed once the foreign sort returned. `is_panic` is always false.
+///
+/// Only used to measure what `catch_unwind` costs per call, see `other::ffi_calibration`.
+pub(crate) unsafe extern "C" fn rust_fn_cmp_abort_on_panic<T, F: FnMut(&T, &T) -> Ordering>(
+    a: &T,
+    b: &T,
+    ctx: *mut u8,
+) -> CompResult {
+    // Dropped only if the comparison function unwinds, the panic hook has printed the message by
+    // then.
+    struct AbortOnUnwind;
+
+    impl Drop for AbortOnUnwind {
+        fn drop(&mut self) {
+            eprintln!("Panic in comparison function called from a foreign sort, aborting");
+            std::process::abort();
+        }
+    }
+
+    let compare_fn = std::mem::transmute::<*mut u8, *mut F>(ctx);
+
+    let panic_flag = AbortOnUnwind;
+    let val = (*compare_fn)(a, b);
+    std::mem::forget(panic_flag);
+
+    CompResult {
+        cmp_result: match val {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        },
+        is_panic: false,
+    }
+}
+
+/// Like `rust_fn_cmp` but takes untyped element pointers, matching the shape of C comparators such
+/// as the one used by `qsort`. `cmp_result` is -1, 0 or 1 so C sorts that distinguish between less,
+/// equal and more, eg. for stability, see the same information the Rust comparison produced.
+pub(crate) unsafe extern "C" fn rust_fn_cmp_threeway<T, F: FnMut(&T, &T) -> Ordering>(
+    a: *const c_void,
+    b: *const c_void,
+    ctx: *mut u8,
+) -> CompResult {
+    rust_fn_cmp::<T, F>(&*(a as *const T), &*(b as *const T), ctx)
+}
+
+/// Sorts `v` with a C or C++ function that gets the strings as [`FFIStr`] (pointer, len) pairs.
+///
+/// The foreign function can only permute the pairs, so every `&str` written back is one that was
+/// passed in and UTF-8 validity is preserved. Debug builds check that the result is a permutation.
+pub(crate) fn sort_str_slice_ffi(
+    v: &mut [&str],
+    sort_fn: unsafe extern "C" fn(*mut FFIStr, usize) -> u32,
+    fn_name: &str,
+) {
+    let mut ffi_strs = v.iter().map(|s| FFIStr::new(s)).collect::<Vec<_>>();
+
+    // SAFETY: ffi_strs is a valid allocation of ffi_strs.len() values.
+    let ret_code = unsafe { sort_fn(ffi_strs.as_mut_ptr(), ffi_strs.len()) };
+    handle_ffi_ret_code(ret_code, fn_name);
+
+    debug_assert!({
+        let addr_len = |s: &str| (s.as_ptr() as usize, s.len());
+        let mut before = v.iter().map(|s| addr_len(s)).collect::<Vec<_>>();
+        let mut after = ffi_strs
+            .iter()
+            .map(|s| addr_len(s.as_str()))
+            .collect::<Vec<_>>();
+        before.sort_unstable();
+        after.sort_unstable();
+        before == after
+    });
+
+    for (dst, src) in v.iter_mut().zip(ffi_strs) {
+        *dst = src.as_str();
+    }
+}
+
 macro_rules! make_cpp_sort_by {
     ($name:ident, $data:expr, $compare:expr, $type:ty) => {
         unsafe {
@@ -46,59 +210,114 @@ macro_rules! make_cpp_sort_by {
             let cmp_fn_ptr = std::mem::transmute::<*mut u8, *mut F>(cmp_fn_ctx);
             let _cmp_fn_box = Box::from_raw(cmp_fn_ptr);
 
-            if ret_code != 0 {
-                panic!("Panic in comparison function");
-            }
+            crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!($name));
         }
     };
 }
 
+macro_rules! make_cpp_sort_by_threeway {
+    ($name:ident, $data:expr, $compare:expr, $type:ty) => {
+        unsafe {
+            let cmp_fn_ctx =
+                std::mem::transmute::<*mut F, *mut u8>(Box::into_raw(Box::new($compare)));
+            let ret_code = $name(
+                $data.as_mut_ptr(),
+                $data.len(),
+                crate::ffi_util::rust_fn_cmp_threeway::<$type, F>,
+                cmp_fn_ctx,
+            );
+
+            // drop the compare function.
+            let cmp_fn_ptr = std::mem::transmute::<*mut u8, *mut F>(cmp_fn_ctx);
+            let _cmp_fn_box = Box::from_raw(cmp_fn_ptr);
+
+            crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!($name));
+        }
+    };
+}
+
+// The signature of the comparison callback passed to the `_by` functions.
+macro_rules! ffi_cmp_fn_ty {
+    (typed, $type:ty) => {
+        unsafe extern "C" fn(&$type, &$type, *mut u8) -> CompResult
+    };
+    (threeway, $type:ty) => {
+        unsafe extern "C" fn(*const std::ffi::c_void, *const std::ffi::c_void, *mut u8) -> CompResult
+    };
+}
+
+macro_rules! ffi_sort_by {
+    (typed, $($args:tt)*) => {
+        make_cpp_sort_by!($($args)*)
+    };
+    (threeway, $($args:tt)*) => {
+        make_cpp_sort_by_threeway!($($args)*)
+    };
+}
+
+/// Generates a `SortImpl` for a sort implemented in C or C++.
+///
+/// By default the `_by` functions are expected to take a `rust_fn_cmp` callback. Passing
+/// `threeway` as third argument selects the `rust_fn_cmp_threeway` callback instead, for sorts
+/// with a C style `int (*)(const void*, const void*)` comparator interface.
+///
+/// The non `_by` functions for i32 and u64 are expected to compare natively, without calling
+/// back into Rust, so that `sort` measures the sort itself and not the FFI callback overhead.
+/// Run the benchmarks with `BENCH_CALLBACK=1` to measure the `_by` path alongside.
 macro_rules! ffi_sort_impl {
     (
         $name:expr,
         $sort_name_prefix:ident
+    ) => {
+        ffi_sort_impl!($name, $sort_name_prefix, typed);
+    };
+    (
+        $name:expr,
+        $sort_name_prefix:ident,
+        $cmp_style:ident
     ) => {
         use std::cmp::Ordering;
 
         use sort_test_tools::ffi_types::{CompResult, FFIOneKibiByte, FFIString, F128};
 
-        sort_impl!($name);
+        // The suffix is set by build.rs for LTO and PGO builds of the C and C++ code.
+        sort_impl!(concat!($name, env!("CPP_SORT_VARIANT_SUFFIX")));
 
         paste::paste! {
             extern "C" {
-                fn [<$sort_name_prefix _i32>](data: *mut i32, len: usize);
+                fn [<$sort_name_prefix _i32>](data: *mut i32, len: usize) -> u32;
                 fn [<$sort_name_prefix _i32_by>](
                     data: *mut i32,
                     len: usize,
-                    cmp_fn: unsafe extern "C" fn(&i32, &i32, *mut u8) -> CompResult,
+                    cmp_fn: ffi_cmp_fn_ty!($cmp_style, i32),
                     cmp_fn_ctx: *mut u8,
                 ) -> u32;
-                fn [<$sort_name_prefix _u64>](data: *mut u64, len: usize);
+                fn [<$sort_name_prefix _u64>](data: *mut u64, len: usize) -> u32;
                 fn [<$sort_name_prefix _u64_by>](
                     data: *mut u64,
                     len: usize,
-                    cmp_fn: unsafe extern "C" fn(&u64, &u64, *mut u8) -> CompResult,
+                    cmp_fn: ffi_cmp_fn_ty!($cmp_style, u64),
                     cmp_fn_ctx: *mut u8,
                 ) -> u32;
-                fn [<$sort_name_prefix _ffi_string>](data: *mut FFIString, len: usize);
+                fn [<$sort_name_prefix _ffi_string>](data: *mut FFIString, len: usize) -> u32;
                 fn [<$sort_name_prefix _ffi_string_by>](
                     data: *mut FFIString,
                     len: usize,
-                    cmp_fn: unsafe extern "C" fn(&FFIString, &FFIString, *mut u8) -> CompResult,
+                    cmp_fn: ffi_cmp_fn_ty!($cmp_style, FFIString),
                     cmp_fn_ctx: *mut u8,
                 ) -> u32;
-                fn [<$sort_name_prefix _f128>](data: *mut F128, len: usize);
+                fn [<$sort_name_prefix _f128>](data: *mut F128, len: usize) -> u32;
                 fn [<$sort_name_prefix _f128_by>](
                     data: *mut F128,
                     len: usize,
-                    cmp_fn: unsafe extern "C" fn(&F128, &F128, *mut u8) -> CompResult,
+                    cmp_fn: ffi_cmp_fn_ty!($cmp_style, F128),
                     cmp_fn_ctx: *mut u8,
                 ) -> u32;
-                fn [<$sort_name_prefix _1k>](data: *mut FFIOneKibiByte, len: usize);
+                fn [<$sort_name_prefix _1k>](data: *mut FFIOneKibiByte, len: usize) -> u32;
                 fn [<$sort_name_prefix _1k_by>](
                     data: *mut FFIOneKibiByte,
                     len: usize,
-                    cmp_fn: unsafe extern "C" fn(&FFIOneKibiByte, &FFIOneKibiByte, *mut u8) -> CompResult,
+                    cmp_fn: ffi_cmp_fn_ty!($cmp_style, FFIOneKibiByte),
                     cmp_fn_ctx: *mut u8,
                 ) -> u32;
             }
@@ -120,61 +339,66 @@ macro_rules! ffi_sort_impl {
 
             impl CppSort for i32 {
                 fn sort(data: &mut [Self]) {
-                    unsafe {
-                        [<$sort_name_prefix _i32>](data.as_mut_ptr(), data.len());
-                    }
+                    let ret_code = unsafe {
+                        [<$sort_name_prefix _i32>](data.as_mut_ptr(), data.len())
+                    };
+                    crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!([<$sort_name_prefix _i32>]));
                 }
 
                 fn sort_by<F: FnMut(&Self, &Self) -> Ordering>(data: &mut [Self], compare: F) {
-                    make_cpp_sort_by!([<$sort_name_prefix _i32_by>], data, compare, Self);
+                    ffi_sort_by!($cmp_style, [<$sort_name_prefix _i32_by>], data, compare, Self);
                 }
             }
 
             impl CppSort for u64 {
                 fn sort(data: &mut [Self]) {
-                    unsafe {
-                        [<$sort_name_prefix _u64>](data.as_mut_ptr(), data.len());
-                    }
+                    let ret_code = unsafe {
+                        [<$sort_name_prefix _u64>](data.as_mut_ptr(), data.len())
+                    };
+                    crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!([<$sort_name_prefix _u64>]));
                 }
 
                 fn sort_by<F: FnMut(&Self, &Self) -> Ordering>(data: &mut [Self], compare: F) {
-                    make_cpp_sort_by!([<$sort_name_prefix _u64_by>], data, compare, Self);
+                    ffi_sort_by!($cmp_style, [<$sort_name_prefix _u64_by>], data, compare, Self);
                 }
             }
 
             impl CppSort for FFIString {
                 fn sort(data: &mut [Self]) {
-                    unsafe {
-                        [<$sort_name_prefix _ffi_string>](data.as_mut_ptr(), data.len());
-                    }
+                    let ret_code = unsafe {
+                        [<$sort_name_prefix _ffi_string>](data.as_mut_ptr(), data.len())
+                    };
+                    crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!([<$sort_name_prefix _ffi_string>]));
                 }
 
                 fn sort_by<F: FnMut(&Self, &Self) -> Ordering>(data: &mut [Self], compare: F) {
-                    make_cpp_sort_by!([<$sort_name_prefix _ffi_string_by>], data, compare, Self);
+                    ffi_sort_by!($cmp_style, [<$sort_name_prefix _ffi_string_by>], data, compare, Self);
                 }
             }
 
             impl CppSort for F128 {
                 fn sort(data: &mut [Self]) {
-                    unsafe {
-                        [<$sort_name_prefix _f128>](data.as_mut_ptr(), data.len());
-                    }
+                    let ret_code = unsafe {
+                        [<$sort_name_prefix _f128>](data.as_mut_ptr(), data.len())
+                    };
+                    crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!([<$sort_name_prefix _f128>]));
                 }
 
                 fn sort_by<F: FnMut(&Self, &Self) -> Ordering>(data: &mut [Self], compare: F) {
-                    make_cpp_sort_by!([<$sort_name_prefix _f128_by>], data, compare, Self);
+                    ffi_sort_by!($cmp_style, [<$sort_name_prefix _f128_by>], data, compare, Self);
                 }
             }
 
             impl CppSort for FFIOneKibiByte {
                 fn sort(data: &mut [Self]) {
-                    unsafe {
-                        [<$sort_name_prefix _1k>](data.as_mut_ptr(), data.len());
-                    }
+                    let ret_code = unsafe {
+                        [<$sort_name_prefix _1k>](data.as_mut_ptr(), data.len())
+                    };
+                    crate::ffi_util::handle_ffi_ret_code(ret_code, stringify!([<$sort_name_prefix _1k>]));
                 }
 
                 fn sort_by<F: FnMut(&Self, &Self) -> Ordering>(data: &mut [Self], compare: F) {
-                    make_cpp_sort_by!([<$sort_name_prefix _1k_by>], data, compare, Self);
+                    ffi_sort_by!($cmp_style, [<$sort_name_prefix _1k_by>], data, compare, Self);
                 }
             }
 
@@ -0,0 +1,309 @@
+//! Allocation profiles of sort invocations, how many allocations of which size and for how long.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOC: TracingAllocator = TracingAllocator;
+//!
+//! let ((), stats) = alloc::record(|| entry.sort(&mut v));
+//! println!("{} {}", stats.allocs, stats.peak_bytes);
+//! ```
+//!
+//! Unlike the other instrumentation this works for every sort, it only needs the allocations to
+//! go through one of the hooks. [`TracingAllocator`] sees the allocations of the Rust sorts.
+//! [`interpose_malloc!`] replaces `malloc` and friends of the whole binary with tracing versions,
+//! that also covers the C and C++ sorts, and the Rust sorts through the system allocator. It
+//! forwards to the internal glibc functions and is only available with glibc on Linux. Use one or
+//! the other, not both.
+//!
+//! The hooks don't allocate themselves. Lifetimes are tracked for up to [`MAX_LIVE`] allocations
+//! at a time, which is plenty for a sort, anything beyond that is counted but has no lifetime.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Maximum number of live allocations with a tracked lifetime.
+pub const MAX_LIVE: usize = 64;
+
+/// Statistics collected for one or more sort invocations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Allocations, including the new allocation of a reallocation.
+    pub allocs: usize,
+    pub reallocs: usize,
+    pub frees: usize,
+    /// Sum of the sizes of all allocations.
+    pub total_bytes: usize,
+    /// Largest amount of memory allocated inside [`record`] and not yet freed at any one point.
+    pub peak_bytes: usize,
+    pub max_alloc: usize,
+    /// Allocation count by size, index `i` counts the sizes in `[2^i, 2^(i+1))`. Index 0 also
+    /// counts zero sized allocations and the last index everything from 2 GiB on.
+    pub size_histogram: [usize; 32],
+    /// Sum of the lifetimes of the allocations that were freed inside [`record`].
+    pub lifetime_total: Duration,
+    /// Number of allocations that make up `lifetime_total`.
+    pub lifetime_count: usize,
+}
+
+impl AllocStats {
+    pub fn mean_lifetime(&self) -> Duration {
+        self.lifetime_total / self.lifetime_count.max(1) as u32
+    }
+
+    /// Allocations smaller than `limit` bytes.
+    pub fn allocs_below(&self, limit: usize) -> usize {
+        self.size_histogram[..size_bucket(limit)].iter().sum()
+    }
+
+    /// Adds the counts of `other`, to aggregate several invocations. The peak is the maximum of
+    /// both.
+    pub fn merge(&mut self, other: &AllocStats) {
+        self.allocs += other.allocs;
+        self.reallocs += other.reallocs;
+        self.frees += other.frees;
+        self.total_bytes += other.total_bytes;
+        self.peak_bytes = self.peak_bytes.max(other.peak_bytes);
+        self.max_alloc = self.max_alloc.max(other.max_alloc);
+        for i in 0..self.size_histogram.len() {
+            self.size_histogram[i] += other.size_histogram[i];
+        }
+        self.lifetime_total += other.lifetime_total;
+        self.lifetime_count += other.lifetime_count;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LiveAlloc {
+    addr: usize,
+    size: usize,
+    start: Instant,
+}
+
+struct Tracker {
+    stats: AllocStats,
+    live_bytes: usize,
+    live: [Option<LiveAlloc>; MAX_LIVE],
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Tracker>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the allocation statistics of everything that allocated inside it on this
+/// thread, which includes code other than the sort if `f` does more than sorting.
+///
+/// Calls to `record` do not nest, the inner call takes all allocations made while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let tracker = Tracker {
+        stats: AllocStats::default(),
+        live_bytes: 0,
+        live: [None; MAX_LIVE],
+    };
+
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(tracker));
+    let result = f();
+    let stats = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let stats = current.take().map(|tracker| tracker.stats);
+        *current = prev;
+        stats.unwrap_or_default()
+    });
+
+    (result, stats)
+}
+
+fn size_bucket(size: usize) -> usize {
+    (size.max(1).ilog2() as usize).min(31)
+}
+
+#[inline(always)]
+fn with_current(f: impl FnOnce(&mut Tracker)) {
+    // try_with and try_borrow_mut, the hooks can be called during thread teardown and from inside
+    // record while the tracker is borrowed.
+    let _ = CURRENT.try_with(|current| {
+        if let Ok(mut current) = current.try_borrow_mut() {
+            if let Some(tracker) = current.as_mut() {
+                f(tracker);
+            }
+        }
+    });
+}
+
+/// Reports a new allocation of `size` bytes at `ptr`.
+pub fn on_alloc(ptr: *mut u8, size: usize) {
+    if ptr.is_null() {
+        return;
+    }
+
+    with_current(|tracker| {
+        let stats = &mut tracker.stats;
+        stats.allocs += 1;
+        stats.total_bytes += size;
+        stats.max_alloc = stats.max_alloc.max(size);
+        stats.size_histogram[size_bucket(size)] += 1;
+
+        tracker.live_bytes += size;
+        stats.peak_bytes = stats.peak_bytes.max(tracker.live_bytes);
+
+        if let Some(slot) = tracker.live.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(LiveAlloc {
+                addr: ptr.addr(),
+                size,
+                start: Instant::now(),
+            });
+        }
+    });
+}
+
+/// Reports that the allocation at `ptr` was freed. Allocations made before [`record`] was called
+/// are only counted as freed.
+pub fn on_free(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+
+    with_current(|tracker| {
+        tracker.stats.frees += 1;
+
+        let slot = tracker
+            .live
+            .iter_mut()
+            .find(|slot| slot.is_some_and(|live| live.addr == ptr.addr()));
+        if let Some(slot) = slot {
+            let live = slot.take().unwrap();
+            tracker.live_bytes -= live.size;
+            tracker.stats.lifetime_total += live.start.elapsed();
+            tracker.stats.lifetime_count += 1;
+        }
+    });
+}
+
+/// Reports that the allocation at `old_ptr` was moved to `new_ptr` with `new_size` bytes.
+pub fn on_realloc(old_ptr: *mut u8, new_ptr: *mut u8, new_size: usize) {
+    if new_ptr.is_null() {
+        // The old allocation is still valid.
+        return;
+    }
+
+    with_current(|tracker| tracker.stats.reallocs += 1);
+    on_free(old_ptr);
+    on_alloc(new_ptr, new_size);
+}
+
+/// [`System`] with allocation tracing, see the module docs.
+pub struct TracingAllocator;
+
+unsafe impl GlobalAlloc for TracingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        on_alloc(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        on_alloc(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        on_free(ptr);
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        on_realloc(ptr, new_ptr, new_size);
+        new_ptr
+    }
+}
+
+/// Defines tracing versions of `malloc`, `calloc`, `realloc`, `free`, `posix_memalign`,
+/// `aligned_alloc` and `memalign` in the binary that expands it. Must be expanded in the binary
+/// crate, an unreferenced definition in a library is not guaranteed to be linked.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[macro_export]
+macro_rules! interpose_malloc {
+    () => {
+        mod interposed_malloc {
+            use std::ffi::{c_int, c_void};
+
+            use sort_research_rs::instrument::alloc::{on_alloc, on_free, on_realloc};
+
+            extern "C" {
+                fn __libc_malloc(size: usize) -> *mut c_void;
+                fn __libc_calloc(count: usize, size: usize) -> *mut c_void;
+                fn __libc_realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+                fn __libc_free(ptr: *mut c_void);
+                fn __libc_memalign(align: usize, size: usize) -> *mut c_void;
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn malloc(size: usize) -> *mut c_void {
+                let ptr = __libc_malloc(size);
+                on_alloc(ptr.cast(), size);
+                ptr
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn calloc(count: usize, size: usize) -> *mut c_void {
+                let ptr = __libc_calloc(count, size);
+                on_alloc(ptr.cast(), count.saturating_mul(size));
+                ptr
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+                let new_ptr = __libc_realloc(ptr, size);
+                if ptr.is_null() {
+                    on_alloc(new_ptr.cast(), size);
+                } else {
+                    on_realloc(ptr.cast(), new_ptr.cast(), size);
+                }
+                new_ptr
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn free(ptr: *mut c_void) {
+                on_free(ptr.cast());
+                __libc_free(ptr);
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn memalign(align: usize, size: usize) -> *mut c_void {
+                let ptr = __libc_memalign(align, size);
+                on_alloc(ptr.cast(), size);
+                ptr
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn aligned_alloc(align: usize, size: usize) -> *mut c_void {
+                memalign(align, size)
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn posix_memalign(
+                out: *mut *mut c_void,
+                align: usize,
+                size: usize,
+            ) -> c_int {
+                // EINVAL, see man posix_memalign.
+                if !align.is_power_of_two() || align % std::mem::size_of::<usize>() != 0 {
+                    return 22;
+                }
+
+                let ptr = memalign(align, size);
+                if ptr.is_null() {
+                    // ENOMEM
+                    return 12;
+                }
+
+                *out = ptr;
+                0
+            }
+        }
+    };
+}
@@ -0,0 +1,233 @@
+//! A log of every sort invocation, to learn the sort workload of a real application.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! audit::log_to_ring(100_000);
+//! run_application();
+//! let histogram = audit::workload_histogram(audit::take_ring().iter().map(|call| call.len));
+//! ```
+//!
+//! Or without code changes, `SORT_AUDIT_LOG=<file>` appends every invocation to the file, one line
+//! each, and `sort-research workload <file>` turns it into a histogram. Either histogram can be
+//! passed to `BENCH_OTHER=workload` as `BENCH_WORKLOAD`, so the benchmarks sort at the sizes the
+//! application does.
+//!
+//! Every sort that is called through its `Sort` impl is logged, which includes all calls through
+//! the registry except for plugins. Calling the `sort` functions of the modules directly bypasses
+//! the log. While no log is active an invocation costs one atomic load.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::mem;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, Once};
+use std::time::{Duration, Instant};
+
+/// If set, invocations are appended to the file it names, from the first sort on.
+pub const LOG_ENV_VAR: &str = "SORT_AUDIT_LOG";
+
+/// One sort invocation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invocation {
+    pub sort_name: String,
+    /// `size_of` the element type.
+    pub type_size: usize,
+    pub len: usize,
+    /// Whether the sort name lacks `unstable`, the naming convention of the test suite. The guard
+    /// only knows the name, the registry declares stability per entry, see `SortEntry::is_stable`.
+    pub stable: bool,
+    pub duration: Duration,
+}
+
+// One line of the log file, tab separated: name, type size, len, stable or unstable, nanoseconds.
+impl fmt::Display for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.sort_name,
+            self.type_size,
+            self.len,
+            if self.stable { "stable" } else { "unstable" },
+            self.duration.as_nanos()
+        )
+    }
+}
+
+impl FromStr for Invocation {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid audit log line '{line}'");
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [sort_name, type_size, len, stability, nanos] = fields[..] else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            sort_name: sort_name.to_string(),
+            type_size: type_size.parse().map_err(|_| invalid())?,
+            len: len.parse().map_err(|_| invalid())?,
+            stable: match stability {
+                "stable" => true,
+                "unstable" => false,
+                _ => return Err(invalid()),
+            },
+            duration: Duration::from_nanos(nanos.parse().map_err(|_| invalid())?),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Log {
+    ring: Option<(usize, VecDeque<Invocation>)>,
+    file: Option<LineWriter<File>>,
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static LOG: Mutex<Log> = Mutex::new(Log {
+    ring: None,
+    file: None,
+});
+
+static FROM_ENV: Once = Once::new();
+
+fn lock_log() -> MutexGuard<'static, Log> {
+    // A panic while holding the lock can't leave the log in an inconsistent state.
+    LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn update_active(log: &Log) {
+    ACTIVE.store(log.ring.is_some() || log.file.is_some(), Ordering::Relaxed);
+}
+
+/// Appends all following invocations to the file at `path`, in addition to the ring buffer if
+/// one is active.
+pub fn log_to_file(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    let mut log = lock_log();
+    log.file = Some(LineWriter::new(file));
+    update_active(&log);
+
+    Ok(())
+}
+
+/// Keeps the last `capacity` invocations in memory, see [`take_ring`].
+pub fn log_to_ring(capacity: usize) {
+    let mut log = lock_log();
+    log.ring = Some((capacity, VecDeque::with_capacity(capacity.min(1 << 16))));
+    update_active(&log);
+}
+
+/// Ends logging to the file and the ring buffer, the invocations in the ring are dropped.
+pub fn stop() {
+    let mut log = lock_log();
+    *log = Log::default();
+    update_active(&log);
+}
+
+/// Returns the invocations in the ring buffer, oldest first, and empties it.
+pub fn take_ring() -> Vec<Invocation> {
+    match &mut lock_log().ring {
+        Some((_, entries)) => mem::take(entries).into(),
+        None => Vec::new(),
+    }
+}
+
+/// Reads a log written by [`log_to_file`].
+pub fn read_log(path: &Path) -> io::Result<Vec<Invocation>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.is_empty()))
+        .map(|line| {
+            line?
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        })
+        .collect()
+}
+
+/// A histogram of `lens` in the `weight:min-max` format of `other::workload`, with one bin per
+/// power of two, or `None` if `lens` is empty. Invocations with len 0 are left out, there is
+/// nothing to benchmark about them.
+pub fn workload_histogram(lens: impl IntoIterator<Item = usize>) -> Option<String> {
+    let mut counts = [0usize; usize::BITS as usize];
+    for len in lens.into_iter().filter(|&len| len > 0) {
+        counts[len.ilog2() as usize] += 1;
+    }
+
+    let bins = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(exp, count)| {
+            let min_len = 1usize << exp;
+            let max_len = min_len + (min_len - 1);
+            format!("{count}:{min_len}-{max_len}")
+        })
+        .collect::<Vec<_>>();
+
+    (!bins.is_empty()).then(|| bins.join(","))
+}
+
+/// Logs the invocation of a sort when dropped, created by `sort_impl!`.
+pub struct AuditGuard {
+    sort_name: &'static str,
+    type_size: usize,
+    len: usize,
+    start: Instant,
+}
+
+impl AuditGuard {
+    /// `None` if no log is active.
+    #[inline]
+    pub fn start<T>(sort_name: &'static str, len: usize) -> Option<Self> {
+        FROM_ENV.call_once(|| {
+            if let Some(path) = std::env::var_os(LOG_ENV_VAR) {
+                if let Err(err) = log_to_file(Path::new(&path)) {
+                    eprintln!("Failed to open {LOG_ENV_VAR}={path:?}: {err}");
+                }
+            }
+        });
+
+        ACTIVE.load(Ordering::Relaxed).then(|| Self {
+            sort_name,
+            type_size: mem::size_of::<T>(),
+            len,
+            start: Instant::now(),
+        })
+    }
+}
+
+impl Drop for AuditGuard {
+    fn drop(&mut self) {
+        let invocation = Invocation {
+            sort_name: self.sort_name.to_string(),
+            type_size: self.type_size,
+            len: self.len,
+            stable: !self.sort_name.contains("unstable"),
+            duration: self.start.elapsed(),
+        };
+
+        let mut log = lock_log();
+        if let Some(file) = &mut log.file {
+            // The log is best effort, a full disk must not fail the sort.
+            let _ = writeln!(file, "{invocation}");
+        }
+        if let Some((capacity, entries)) = &mut log.ring {
+            if entries.len() >= *capacity {
+                entries.pop_front();
+            }
+            if *capacity > 0 {
+                entries.push_back(invocation);
+            }
+        }
+    }
+}
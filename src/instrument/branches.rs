@@ -0,0 +1,189 @@
+//! Comparison outcome statistics per `is_less` call site, to explain branch prediction behavior.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), stats) = branches::record(|| unstable::rust_std::sort(&mut v));
+//! for (site, site_stats) in &stats.sites {
+//!     println!("{site}: {:.2}", site_stats.entropy());
+//! }
+//! ```
+//!
+//! Only call sites wrapped in `branch_outcome!` report anything, currently all `is_less` calls of
+//! `stable::rust_std` and `unstable::rust_std`. A site is identified by the source location of the
+//! macro invocation.
+//!
+//! The entropy of the outcomes is what a predictor without history is up against, the conditional
+//! entropy given the previous outcome and the simulated two-bit counter show how much of that a
+//! simple history based predictor recovers. A site with high entropy that is compiled to a
+//! conditional branch causes branch misses, which is the case branchless variants avoid. The
+//! predicted miss rate can be compared to `perf stat -e branch-misses` for the same pattern.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::Location;
+
+/// Outcome statistics of a single call site.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SiteStats {
+    pub calls: u64,
+    /// How many calls returned true.
+    pub taken: u64,
+    /// How many times the outcome differed from the previous outcome at the same site.
+    pub transitions: u64,
+    /// Mispredictions of a two-bit saturating counter, as used by simple hardware predictors.
+    pub predicted_misses: u64,
+    // Calls that had a previous outcome, and how many of those had a taken previous outcome.
+    pairs: u64,
+    pairs_after_taken: u64,
+    transitions_after_taken: u64,
+    prev: Option<bool>,
+    counter: u8,
+}
+
+fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+
+    -(p * p.log2() + (1.0 - p) * (1.0 - p).log2())
+}
+
+impl SiteStats {
+    fn on_outcome(&mut self, outcome: bool) {
+        self.calls += 1;
+        self.taken += outcome as u64;
+
+        if let Some(prev) = self.prev {
+            self.pairs += 1;
+            self.pairs_after_taken += prev as u64;
+            if prev != outcome {
+                self.transitions += 1;
+                self.transitions_after_taken += prev as u64;
+            }
+        }
+        self.prev = Some(outcome);
+
+        // Counter values 0 and 1 predict false, 2 and 3 predict true.
+        self.predicted_misses += ((self.counter >= 2) != outcome) as u64;
+        self.counter = if outcome {
+            (self.counter + 1).min(3)
+        } else {
+            self.counter.saturating_sub(1)
+        };
+    }
+
+    pub fn taken_ratio(&self) -> f64 {
+        self.taken as f64 / self.calls.max(1) as f64
+    }
+
+    /// Shannon entropy of the outcome in bits, ignoring order.
+    pub fn entropy(&self) -> f64 {
+        binary_entropy(self.taken_ratio())
+    }
+
+    /// Entropy in bits of an outcome given the previous outcome at the same site.
+    pub fn conditional_entropy(&self) -> f64 {
+        let cond = |transitions: u64, total: u64| {
+            if total == 0 {
+                return 0.0;
+            }
+            (total as f64 / self.pairs as f64) * binary_entropy(transitions as f64 / total as f64)
+        };
+
+        cond(self.transitions_after_taken, self.pairs_after_taken)
+            + cond(
+                self.transitions - self.transitions_after_taken,
+                self.pairs - self.pairs_after_taken,
+            )
+    }
+
+    /// Mean length of runs of equal outcomes.
+    pub fn mean_run_len(&self) -> f64 {
+        // Every call without a previous outcome starts a run as well.
+        let runs = self.transitions + (self.calls - self.pairs);
+        self.calls as f64 / runs.max(1) as f64
+    }
+
+    pub fn predicted_miss_ratio(&self) -> f64 {
+        self.predicted_misses as f64 / self.calls.max(1) as f64
+    }
+
+    /// Adds the counts of `other`, the history of the two is not connected.
+    pub fn merge(&mut self, other: &SiteStats) {
+        self.calls += other.calls;
+        self.taken += other.taken;
+        self.transitions += other.transitions;
+        self.predicted_misses += other.predicted_misses;
+        self.pairs += other.pairs;
+        self.pairs_after_taken += other.pairs_after_taken;
+        self.transitions_after_taken += other.transitions_after_taken;
+    }
+}
+
+/// Source location of a call site, eg. `src/unstable/rust_std.rs:424`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Site {
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Statistics collected for all call sites that ran inside [`record`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BranchStats {
+    pub sites: BTreeMap<Site, SiteStats>,
+}
+
+impl BranchStats {
+    pub fn merge(&mut self, other: &BranchStats) {
+        for (site, site_stats) in &other.sites {
+            self.sites.entry(*site).or_default().merge(site_stats);
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<BranchStats>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the branch statistics of all instrumented sorts that ran inside it on
+/// this thread.
+///
+/// Calls to `record` do not nest, the inner call takes all statistics recorded while it is
+/// active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, BranchStats) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(BranchStats::default()));
+    let result = f();
+    let stats = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let stats = current.take().unwrap_or_default();
+        *current = prev;
+        stats
+    });
+
+    (result, stats)
+}
+
+#[track_caller]
+pub(crate) fn on_outcome(outcome: bool) {
+    let location = Location::caller();
+
+    CURRENT.with(|current| {
+        if let Some(stats) = current.borrow_mut().as_mut() {
+            let site = Site {
+                file: location.file(),
+                line: location.line(),
+                column: location.column(),
+            };
+            stats.sites.entry(site).or_default().on_outcome(outcome);
+        }
+    });
+}
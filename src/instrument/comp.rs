@@ -0,0 +1,56 @@
+//! Comparison counting, for any sort including the C and C++ ones.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let mut cmp = CountingCmp::new(u64::cmp);
+//! entry.sort_by(&mut v, |a, b| cmp.compare(a, b));
+//! println!("{}", cmp.count());
+//! ```
+//!
+//! [`CountingCmp`] counts the calls of the comparison function passed to `sort_by`. Sorts that are
+//! called through `sort` compare with `Ord` instead, [`InstrumentedElem`] counts those
+//! comparisons in the elements themselves. Both work without any cooperation of the sort and
+//! without a cargo feature.
+
+use std::cmp::Ordering;
+
+/// Wraps a comparison function and counts how often it is called.
+pub struct CountingCmp<F> {
+    compare: F,
+    count: u64,
+}
+
+impl<F> CountingCmp<F> {
+    pub fn new(compare: F) -> Self {
+        Self { compare, count: 0 }
+    }
+
+    #[inline]
+    pub fn compare<T>(&mut self, a: &T, b: &T) -> Ordering
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.count += 1;
+        (self.compare)(a, b)
+    }
+
+    /// Number of comparisons since creation or the last [`CountingCmp::reset`].
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+sort_test_tools::instrumented_type!(
+    /// Element whose `Ord` implementation counts every comparison it takes part in, generated by
+    /// `sort_test_tools::instrumented_type!`, which also counts its clones, moves and drops.
+    ///
+    /// Equality and order are those of the wrapped value. The count lives in a `Cell`, so a sort
+    /// that compares a copy of an element and then moves the stale copy back loses counts, the same
+    /// way it would lose any other change made through interior mutability.
+    pub InstrumentedElem
+);
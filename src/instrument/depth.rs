@@ -0,0 +1,88 @@
+//! The deepest recursion a sort reaches.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), depth) = depth::record(|| unstable::rust_std::sort(&mut v));
+//! assert!(depth.max <= depth::log2_bound(v.len()));
+//! ```
+//!
+//! Only sorts that mark their recursive function with `recursion_depth!` report anything,
+//! currently `unstable::rust_std`. It recurses into the shorter side of a partition and loops on
+//! the longer one, so every level at least halves the len, which bounds the depth by
+//! [`log2_bound`]. The stack usage of the sorts that aren't instrumented is measured by
+//! `analysis::stack_depth`.
+
+use std::cell::Cell;
+
+/// Recursion depth of one or more sort invocations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecursionDepth {
+    /// The deepest nesting of recursive calls, 1 if the sort entered its recursive function but
+    /// never recursed.
+    pub max: usize,
+    /// The number of calls of the recursive function.
+    pub calls: usize,
+}
+
+impl RecursionDepth {
+    /// Combines the depths of `other`, to aggregate several invocations.
+    pub fn merge(&mut self, other: &RecursionDepth) {
+        self.max = self.max.max(other.max);
+        self.calls += other.calls;
+    }
+}
+
+/// `floor(log2(len)) + 1`, the depth of a recursion in which every level at least halves the len.
+pub fn log2_bound(len: usize) -> usize {
+    len.max(1).ilog2() as usize + 1
+}
+
+thread_local! {
+    static CURRENT_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static RECORDED: Cell<Option<RecursionDepth>> = const { Cell::new(None) };
+}
+
+/// Calls `f` and returns the recursion depth of all instrumented sorts that ran inside it on this
+/// thread.
+///
+/// Calls to `record` do not nest, the inner call takes all calls recorded while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, RecursionDepth) {
+    let prev = RECORDED.with(|recorded| recorded.replace(Some(RecursionDepth::default())));
+    let result = f();
+    let depth = RECORDED.with(|recorded| recorded.replace(prev).unwrap_or_default());
+
+    (result, depth)
+}
+
+/// Increments the depth of this thread while alive, created by `recursion_depth!`.
+pub(crate) struct DepthGuard;
+
+impl DepthGuard {
+    #[inline(always)]
+    pub(crate) fn enter() -> Self {
+        let depth = CURRENT_DEPTH.with(|current| {
+            let depth = current.get() + 1;
+            current.set(depth);
+            depth
+        });
+
+        RECORDED.with(|recorded| {
+            if let Some(mut stats) = recorded.get() {
+                stats.max = stats.max.max(depth);
+                stats.calls += 1;
+                recorded.set(Some(stats));
+            }
+        });
+
+        Self
+    }
+}
+
+impl Drop for DepthGuard {
+    // Also runs when unwinding from a panic in the comparison function, the depth stays balanced.
+    #[inline(always)]
+    fn drop(&mut self) {
+        CURRENT_DEPTH.with(|current| current.set(current.get() - 1));
+    }
+}
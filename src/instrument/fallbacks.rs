@@ -0,0 +1,109 @@
+//! How often the fallback layers of a sort trigger, the paths taken when the fast path didn't
+//! work out.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), counts) = fallbacks::record(|| unstable::rust_std::sort(&mut v));
+//! println!("{}", counts.count(Fallback::Heapsort));
+//! ```
+//!
+//! Only sorts that report their fallbacks with `fallback!` count anything, currently
+//! `stable::rust_std` and `unstable::rust_std`. Counting costs a thread local access per
+//! fallback, which is negligible as long as they are rare, and that is what this is meant to show.
+
+use std::cell::RefCell;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fallback {
+    /// `unstable::rust_std` ran out of imbalanced partitions and heapsorted a sub-slice.
+    Heapsort,
+    /// `unstable::rust_std` shuffled some elements after an imbalanced partition.
+    BreakPatterns,
+    /// The sub-slice looked sorted to `unstable::rust_std`, but the partial insertion sort gave up
+    /// and the work it did is wasted.
+    PartialInsertionBailout,
+    /// `stable::rust_std` found a natural run shorter than the minimum run length and extended it
+    /// with insertion sort.
+    ShortRunExtension,
+}
+
+impl Fallback {
+    pub const ALL: [Fallback; 4] = [
+        Fallback::Heapsort,
+        Fallback::BreakPatterns,
+        Fallback::PartialInsertionBailout,
+        Fallback::ShortRunExtension,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Fallback::Heapsort => "heapsort",
+            Fallback::BreakPatterns => "break_patterns",
+            Fallback::PartialInsertionBailout => "partial_insertion_bailout",
+            Fallback::ShortRunExtension => "short_run_extension",
+        }
+    }
+}
+
+/// Counters collected for one or more sort invocations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FallbackCounts {
+    /// How often each fallback triggered, indexed by the position in [`Fallback::ALL`].
+    pub counts: [usize; Fallback::ALL.len()],
+    /// In how many of the merged invocations each fallback triggered at least once, indexed like
+    /// `counts`. 1 or 0 for a single invocation.
+    pub invocations: [usize; Fallback::ALL.len()],
+}
+
+impl FallbackCounts {
+    pub fn count(&self, fallback: Fallback) -> usize {
+        self.counts[fallback as usize]
+    }
+
+    pub fn invocations(&self, fallback: Fallback) -> usize {
+        self.invocations[fallback as usize]
+    }
+
+    /// Adds the counts of `other`, to aggregate several invocations.
+    pub fn merge(&mut self, other: &FallbackCounts) {
+        for i in 0..self.counts.len() {
+            self.counts[i] += other.counts[i];
+            self.invocations[i] += other.invocations[i];
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<FallbackCounts>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the fallbacks of all instrumented sorts that ran inside it on this
+/// thread, counted as a single invocation.
+///
+/// Calls to `record` do not nest, the inner call takes all fallbacks recorded while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, FallbackCounts) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(FallbackCounts::default()));
+    let result = f();
+    let mut counts = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let counts = current.take().unwrap_or_default();
+        *current = prev;
+        counts
+    });
+
+    for i in 0..counts.counts.len() {
+        counts.invocations[i] = (counts.counts[i] > 0) as usize;
+    }
+
+    (result, counts)
+}
+
+#[inline(always)]
+pub(crate) fn on_fallback(fallback: Fallback) {
+    CURRENT.with(|current| {
+        if let Some(counts) = current.borrow_mut().as_mut() {
+            counts.counts[fallback as usize] += 1;
+        }
+    });
+}
@@ -0,0 +1,96 @@
+//! How far insertion sort moves each element, the distribution of insertion distances.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), stats) = insertion::record(|| unstable::rust_std::sort(&mut v));
+//! println!("{:.2}", stats.mean_displacement());
+//! ```
+//!
+//! Only sorts that report their insertions with `insertion_displacement!` count anything,
+//! currently the insertion sort steps of `stable::rust_std` and `unstable::rust_std`, including
+//! the partial insertion sort of the latter. If most elements move by 0 or 1 positions a
+//! small-sort can check the neighbor first and skip the loop, if they move far, a binary search
+//! for the insertion point or an unguarded loop behind a sentinel pays off.
+
+use std::cell::RefCell;
+
+/// Insertion distances collected for one or more sort invocations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InsertionStats {
+    /// How many elements were moved by each distance, indexed by the distance. An element that was
+    /// already in place counts as distance 0.
+    pub displacements: Vec<usize>,
+}
+
+impl InsertionStats {
+    /// The number of inserted elements.
+    pub fn insertions(&self) -> usize {
+        self.displacements.iter().sum()
+    }
+
+    pub fn mean_displacement(&self) -> f64 {
+        let total = self
+            .displacements
+            .iter()
+            .enumerate()
+            .map(|(distance, count)| distance * count)
+            .sum::<usize>();
+
+        total as f64 / self.insertions().max(1) as f64
+    }
+
+    pub fn max_displacement(&self) -> usize {
+        self.displacements.len().saturating_sub(1)
+    }
+
+    /// The share of insertions that moved the element by at most `distance` positions.
+    pub fn share_within(&self, distance: usize) -> f64 {
+        let within = self.displacements.iter().take(distance + 1).sum::<usize>();
+        within as f64 / self.insertions().max(1) as f64
+    }
+
+    /// Adds the counts of `other`, to aggregate several invocations.
+    pub fn merge(&mut self, other: &InsertionStats) {
+        if self.displacements.len() < other.displacements.len() {
+            self.displacements.resize(other.displacements.len(), 0);
+        }
+
+        for (count, other_count) in self.displacements.iter_mut().zip(&other.displacements) {
+            *count += other_count;
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<InsertionStats>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the insertion distances of all instrumented sorts that ran inside it on
+/// this thread.
+///
+/// Calls to `record` do not nest, the inner call takes all insertions recorded while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, InsertionStats) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(InsertionStats::default()));
+    let result = f();
+    let stats = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let stats = current.take().unwrap_or_default();
+        *current = prev;
+        stats
+    });
+
+    (result, stats)
+}
+
+#[inline(always)]
+pub(crate) fn on_insert(displacement: usize) {
+    CURRENT.with(|current| {
+        if let Some(stats) = current.borrow_mut().as_mut() {
+            if stats.displacements.len() <= displacement {
+                stats.displacements.resize(displacement + 1, 0);
+            }
+            stats.displacements[displacement] += 1;
+        }
+    });
+}
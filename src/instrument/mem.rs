@@ -0,0 +1,104 @@
+//! Memory access traces of sort implementations, for offline cache simulation and visualizing the
+//! access patterns of merges and partitions.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), trace) = mem::record(v.as_ptr(), || {
+//!     stable::rust_std::sort_by(&mut v, |a, b| {
+//!         mem::on_read(a);
+//!         mem::on_read(b);
+//!         a.cmp(b)
+//!     })
+//! });
+//! ```
+//!
+//! The Rust std sorts report every element move, be it a copy, write, swap or reversal, through
+//! [`crate::instrument::ptr`]. Reads done by comparisons are only visible to the comparison
+//! function, which has to report them itself as above.
+
+use std::cell::RefCell;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemAccess {
+    pub kind: AccessKind,
+    /// Byte offset relative to the start of the sorted slice. Accesses to scratch buffers and
+    /// the stack show up as large offsets.
+    pub offset: isize,
+    /// Size of the access in bytes.
+    pub size: usize,
+}
+
+/// All accesses that happened inside [`record`], in program order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemTrace {
+    pub accesses: Vec<MemAccess>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Vec<(AccessKind, usize, usize)>>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the memory accesses of all instrumented sorts that ran inside it on this
+/// thread, with addresses relative to `base`.
+///
+/// Calls to `record` do not nest, the inner call takes all accesses recorded while it is active.
+pub fn record<T, R>(base: *const T, f: impl FnOnce() -> R) -> (R, MemTrace) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let raw = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let raw = current.take().unwrap_or_default();
+        *current = prev;
+        raw
+    });
+
+    let base = base as usize;
+    let accesses = raw
+        .into_iter()
+        .map(|(kind, addr, size)| MemAccess {
+            kind,
+            offset: addr.wrapping_sub(base) as isize,
+            size,
+        })
+        .collect();
+
+    (result, MemTrace { accesses })
+}
+
+#[inline(always)]
+fn on_access(kind: AccessKind, addr: usize, size: usize) {
+    CURRENT.with(|current| {
+        if let Some(accesses) = current.borrow_mut().as_mut() {
+            accesses.push((kind, addr, size));
+        }
+    });
+}
+
+/// Reports a read of `count` elements starting at `ptr`.
+pub fn on_read_n<T>(ptr: *const T, count: usize) {
+    on_access(
+        AccessKind::Read,
+        ptr as usize,
+        count * std::mem::size_of::<T>(),
+    );
+}
+
+/// Reports a write of `count` elements starting at `ptr`.
+pub fn on_write_n<T>(ptr: *const T, count: usize) {
+    on_access(
+        AccessKind::Write,
+        ptr as usize,
+        count * std::mem::size_of::<T>(),
+    );
+}
+
+pub fn on_read<T>(ptr: *const T) {
+    on_read_n(ptr, 1);
+}
@@ -0,0 +1,89 @@
+//! Opt-in instrumentation hooks for the Rust sort implementations in this repository.
+//!
+//! Every kind of instrumentation is behind its own cargo feature, so regular benchmarks are not
+//! affected by it.
+
+#[cfg(feature = "instrument_runs")]
+pub mod runs;
+
+#[cfg(feature = "instrument_branches")]
+pub mod branches;
+
+#[cfg(feature = "instrument_mem")]
+pub mod mem;
+
+#[cfg(feature = "instrument_phases")]
+pub mod phases;
+
+#[cfg(feature = "instrument_alloc")]
+pub mod alloc;
+
+#[cfg(feature = "instrument_fallbacks")]
+pub mod fallbacks;
+
+#[cfg(feature = "instrument_depth")]
+pub mod depth;
+
+#[cfg(feature = "instrument_insertion")]
+pub mod insertion;
+
+#[cfg(feature = "instrument_audit")]
+pub mod audit;
+
+#[cfg(feature = "instrument_specialization")]
+pub use ipnsort::specialization;
+
+pub mod ptr;
+
+pub mod comp;
+
+/// Evaluates the comparison `$is_less` and reports its outcome to `instrument::branches` if the
+/// `instrument_branches` feature is enabled.
+macro_rules! branch_outcome {
+    ($is_less:expr) => {{
+        let outcome: bool = $is_less;
+        #[cfg(feature = "instrument_branches")]
+        crate::instrument::branches::on_outcome(outcome);
+        outcome
+    }};
+}
+
+/// Reports that `instrument::fallbacks::Fallback::$fallback` triggered if the
+/// `instrument_fallbacks` feature is enabled.
+macro_rules! fallback {
+    ($fallback:ident) => {
+        #[cfg(feature = "instrument_fallbacks")]
+        crate::instrument::fallbacks::on_fallback(
+            crate::instrument::fallbacks::Fallback::$fallback,
+        );
+    };
+}
+
+/// Evaluates `$body` and adds the time it took to `instrument::phases::Phase::$phase` if the
+/// `instrument_phases` feature is enabled.
+macro_rules! timed_phase {
+    ($phase:ident, $body:expr) => {{
+        #[cfg(feature = "instrument_phases")]
+        let _timer =
+            crate::instrument::phases::PhaseTimer::start(crate::instrument::phases::Phase::$phase);
+        $body
+    }};
+}
+
+/// Counts the enclosing function as one level of recursion in `instrument::depth` until it returns,
+/// if the `instrument_depth` feature is enabled.
+macro_rules! recursion_depth {
+    () => {
+        #[cfg(feature = "instrument_depth")]
+        let _depth_guard = crate::instrument::depth::DepthGuard::enter();
+    };
+}
+
+/// Reports that an insertion sort step moved an element by `$displacement` positions to
+/// `instrument::insertion` if the `instrument_insertion` feature is enabled.
+macro_rules! insertion_displacement {
+    ($displacement:expr) => {
+        #[cfg(feature = "instrument_insertion")]
+        crate::instrument::insertion::on_insert($displacement);
+    };
+}
@@ -0,0 +1,147 @@
+//! Wall-clock time spent in the phases of a sort, such as run detection or partitioning.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), stats) = phases::record(|| unstable::rust_std::sort(&mut v));
+//! println!("{:.2}", stats.fraction(Phase::Partition));
+//! ```
+//!
+//! Only sorts that wrap their phases in `timed_phase!` report anything, currently
+//! `stable::rust_std` and `unstable::rust_std`. Phases don't nest, everything outside of them,
+//! eg. allocations, pivot selection and the recursion itself, is counted as
+//! [`PhaseStats::untimed`]. Reading the clock costs some tens of nanoseconds, so small inputs
+//! overstate the phases that are entered often, such as the small-sort.
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Detecting and exploiting presorted input, natural runs in `stable::rust_std` and the
+    /// partial insertion sort in `unstable::rust_std`.
+    RunDetection,
+    /// Insertion sort of short slices, and extension of short runs.
+    SmallSort,
+    Merge,
+    Partition,
+    /// Heapsort after too many imbalanced partitions.
+    Fallback,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::RunDetection,
+        Phase::SmallSort,
+        Phase::Merge,
+        Phase::Partition,
+        Phase::Fallback,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::RunDetection => "run detection",
+            Phase::SmallSort => "small sort",
+            Phase::Merge => "merge",
+            Phase::Partition => "partition",
+            Phase::Fallback => "fallback",
+        }
+    }
+}
+
+/// Statistics collected for one or more sort invocations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PhaseStats {
+    /// Time spent in [`record`].
+    pub total: Duration,
+    /// Time spent in each phase, indexed by the position in [`Phase::ALL`].
+    pub times: [Duration; Phase::ALL.len()],
+    /// How often each phase was entered, indexed like `times`.
+    pub counts: [usize; Phase::ALL.len()],
+}
+
+impl PhaseStats {
+    pub fn time(&self, phase: Phase) -> Duration {
+        self.times[phase as usize]
+    }
+
+    pub fn count(&self, phase: Phase) -> usize {
+        self.counts[phase as usize]
+    }
+
+    /// Share of the total time spent in `phase`.
+    pub fn fraction(&self, phase: Phase) -> f64 {
+        self.time(phase).as_secs_f64() / self.total.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Time not attributed to any phase.
+    pub fn untimed(&self) -> Duration {
+        self.total.saturating_sub(self.times.iter().sum())
+    }
+
+    /// Adds the times and counts of `other`, to aggregate several invocations.
+    pub fn merge(&mut self, other: &PhaseStats) {
+        self.total += other.total;
+        for i in 0..self.times.len() {
+            self.times[i] += other.times[i];
+            self.counts[i] += other.counts[i];
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<PhaseStats>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the phase times of all instrumented sorts that ran inside it on this
+/// thread.
+///
+/// Calls to `record` do not nest, the inner call takes all phases recorded while it is active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, PhaseStats) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(PhaseStats::default()));
+    let start = Instant::now();
+    let result = f();
+    let total = start.elapsed();
+    let stats = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let mut stats = current.take().unwrap_or_default();
+        stats.total = total;
+        *current = prev;
+        stats
+    });
+
+    (result, stats)
+}
+
+/// Adds the time until it is dropped to its phase. Doesn't read the clock if nothing is recorded.
+pub(crate) struct PhaseTimer {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl PhaseTimer {
+    #[inline(always)]
+    pub(crate) fn start(phase: Phase) -> Self {
+        let is_recording = CURRENT.with(|current| current.borrow().is_some());
+
+        Self {
+            phase,
+            start: is_recording.then(Instant::now),
+        }
+    }
+}
+
+impl Drop for PhaseTimer {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            CURRENT.with(|current| {
+                if let Some(stats) = current.borrow_mut().as_mut() {
+                    stats.times[self.phase as usize] += elapsed;
+                    stats.counts[self.phase as usize] += 1;
+                }
+            });
+        }
+    }
+}
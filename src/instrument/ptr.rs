@@ -0,0 +1,106 @@
+//! Replacement for `std::ptr` in the Rust sorts of this repository.
+//!
+//! Without the `instrument_mem` feature this is `std::ptr`. With it, the functions that move
+//! elements also report their reads and writes to [`super::mem`]. [`slice_swap`] and
+//! [`slice_reverse`] stand in for the slice methods of the same name, which can't be traced.
+
+pub use std::ptr::*;
+
+#[cfg(feature = "instrument_mem")]
+pub use traced::{copy, copy_nonoverlapping, read, swap, write};
+
+/// [`slice::swap`] that reports its reads and writes with the `instrument_mem` feature.
+#[inline(always)]
+pub fn slice_swap<T>(v: &mut [T], a: usize, b: usize) {
+    #[cfg(feature = "instrument_mem")]
+    {
+        use crate::instrument::mem::{on_read_n, on_write_n};
+
+        let (x, y) = (&v[a] as *const T, &v[b] as *const T);
+        on_read_n(x, 1);
+        on_read_n(y, 1);
+        on_write_n(x, 1);
+        on_write_n(y, 1);
+    }
+
+    v.swap(a, b);
+}
+
+/// [`slice::reverse`] that reports its reads and writes with the `instrument_mem` feature.
+#[inline(always)]
+pub fn slice_reverse<T>(v: &mut [T]) {
+    #[cfg(feature = "instrument_mem")]
+    {
+        use crate::instrument::mem::{on_read_n, on_write_n};
+
+        on_read_n(v.as_ptr(), v.len());
+        on_write_n(v.as_ptr(), v.len());
+    }
+
+    v.reverse();
+}
+
+#[cfg(feature = "instrument_mem")]
+mod traced {
+    use crate::instrument::mem::{on_read_n, on_write_n};
+
+    /// Traced [`std::ptr::read`].
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`std::ptr::read`].
+    #[inline(always)]
+    pub unsafe fn read<T>(src: *const T) -> T {
+        on_read_n(src, 1);
+        std::ptr::read(src)
+    }
+
+    /// Traced [`std::ptr::copy`].
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`std::ptr::copy`].
+    #[inline(always)]
+    pub unsafe fn copy<T>(src: *const T, dst: *mut T, count: usize) {
+        on_read_n(src, count);
+        on_write_n(dst, count);
+        std::ptr::copy(src, dst, count);
+    }
+
+    /// Traced [`std::ptr::copy_nonoverlapping`].
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`std::ptr::copy_nonoverlapping`].
+    #[inline(always)]
+    pub unsafe fn copy_nonoverlapping<T>(src: *const T, dst: *mut T, count: usize) {
+        on_read_n(src, count);
+        on_write_n(dst, count);
+        std::ptr::copy_nonoverlapping(src, dst, count);
+    }
+
+    /// Traced [`std::ptr::write`].
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`std::ptr::write`].
+    #[inline(always)]
+    pub unsafe fn write<T>(dst: *mut T, src: T) {
+        on_write_n(dst, 1);
+        std::ptr::write(dst, src);
+    }
+
+    /// Traced [`std::ptr::swap`].
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`std::ptr::swap`].
+    #[inline(always)]
+    pub unsafe fn swap<T>(x: *mut T, y: *mut T) {
+        on_read_n(x, 1);
+        on_read_n(y, 1);
+        on_write_n(x, 1);
+        on_write_n(y, 1);
+        std::ptr::swap(x, y);
+    }
+}
@@ -0,0 +1,82 @@
+//! Run detection and merge statistics for run-adaptive merge sorts.
+//!
+//! Usage:
+//!
+//! ```ignore
+//! let ((), stats) = runs::record(|| stable::rust_std::sort(&mut v));
+//! println!("{:?}", stats.run_lens);
+//! ```
+//!
+//! Only sorts that call the hooks in this module report anything, currently `stable::rust_std`.
+//! Inputs that are short enough to be handled by insertion sort alone report no runs.
+
+use std::cell::RefCell;
+
+/// Statistics collected for a single sort invocation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunStats {
+    /// Lengths of the natural runs in the order they were detected, before any extension with
+    /// insertion sort.
+    pub run_lens: Vec<usize>,
+    /// How many runs were too short and extended with insertion sort.
+    pub extended_runs: usize,
+    /// How many merges of two adjacent runs were performed.
+    pub merges: usize,
+    /// Sum of the lengths of all merged regions.
+    pub merged_elements: usize,
+}
+
+impl RunStats {
+    pub fn mean_run_len(&self) -> f64 {
+        if self.run_lens.is_empty() {
+            return 0.0;
+        }
+
+        self.run_lens.iter().sum::<usize>() as f64 / self.run_lens.len() as f64
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<RunStats>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` and returns the run statistics of all instrumented sorts that ran inside it on this
+/// thread.
+///
+/// Calls to `record` do not nest, the inner call takes all statistics recorded while it is
+/// active.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, RunStats) {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(RunStats::default()));
+    let result = f();
+    let stats = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let stats = current.take().unwrap_or_default();
+        *current = prev;
+        stats
+    });
+
+    (result, stats)
+}
+
+#[inline(always)]
+fn with_current(f: impl FnOnce(&mut RunStats)) {
+    CURRENT.with(|current| {
+        if let Some(stats) = current.borrow_mut().as_mut() {
+            f(stats);
+        }
+    });
+}
+
+pub(crate) fn on_run(natural_len: usize, extended: bool) {
+    with_current(|stats| {
+        stats.run_lens.push(natural_len);
+        stats.extended_runs += extended as usize;
+    });
+}
+
+pub(crate) fn on_merge(len: usize) {
+    with_current(|stats| {
+        stats.merges += 1;
+        stats.merged_elements += len;
+    });
+}
@@ -0,0 +1,88 @@
+//! k-way merge as a series of two-way merges with the galloping mode of timsort.
+//!
+//! Same rounds as [`super::pairwise`]. Once one side of a two-way merge wins [`MIN_GALLOP`] times in
+//! a row, the merge assumes it keeps winning and finds the end of its streak with an exponential
+//! search, then moves the whole streak at once. Runs that only overlap in a small range, eg. the
+//! chunks of almost sorted input, are merged with few comparisons. Runs that interleave finely
+//! pay for the searches that end after a few elements.
+
+use super::pairwise::merge_rounds;
+
+/// Consecutive wins of one side after which the merge switches to galloping. Timsort starts with
+/// the same value, but adapts it to the input. `BENCH_OTHER=min_gallop` sweeps other values.
+pub const MIN_GALLOP: usize = 7;
+
+/// Merges the sorted `runs` into one sorted `Vec`. Stable, equal elements keep the order of their
+/// runs.
+pub fn merge<T: Ord>(runs: Vec<Vec<T>>) -> Vec<T> {
+    merge_with_min_gallop(runs, MIN_GALLOP)
+}
+
+/// [`merge`] that switches to galloping after `min_gallop` consecutive wins instead of
+/// [`MIN_GALLOP`], `usize::MAX` never gallops.
+///
+/// # Panics
+///
+/// Panics if `min_gallop` is 0.
+pub fn merge_with_min_gallop<T: Ord>(runs: Vec<Vec<T>>, min_gallop: usize) -> Vec<T> {
+    assert!(min_gallop > 0, "min_gallop must be at least 1");
+
+    merge_rounds(runs, |left, right| merge_two(left, right, min_gallop))
+}
+
+/// Merges the sorted `left` and `right`, preferring `left` on ties.
+fn merge_two<T: Ord>(left: Vec<T>, right: Vec<T>, min_gallop: usize) -> Vec<T> {
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+
+    let mut left_wins = 0;
+    let mut right_wins = 0;
+
+    while let (Some(l), Some(r)) = (left.as_slice().first(), right.as_slice().first()) {
+        if r < l {
+            out.push(right.next().unwrap());
+            right_wins += 1;
+            left_wins = 0;
+
+            if right_wins >= min_gallop {
+                let l = &left.as_slice()[0];
+                let streak = gallop(right.as_slice(), |r| r < l);
+                out.extend(right.by_ref().take(streak));
+                right_wins = 0;
+            }
+        } else {
+            out.push(left.next().unwrap());
+            left_wins += 1;
+            right_wins = 0;
+
+            if left_wins >= min_gallop {
+                let r = &right.as_slice()[0];
+                let streak = gallop(left.as_slice(), |l| l <= r);
+                out.extend(left.by_ref().take(streak));
+                left_wins = 0;
+            }
+        }
+    }
+
+    out.extend(left);
+    out.extend(right);
+
+    out
+}
+
+/// Length of the prefix of `v` for which `pred` is true, `pred` must be true for a prefix and
+/// false for the rest. Searches exponentially from the start, so a short prefix is cheap to find.
+/// Benchmarked in isolation by `BENCH_OTHER=partition_point`.
+pub fn gallop<T>(v: &[T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let mut lo = 0;
+    let mut step = 1;
+
+    while lo + step <= v.len() && pred(&v[lo + step - 1]) {
+        lo += step;
+        step *= 2;
+    }
+
+    let hi = (lo + step).min(v.len());
+    lo + v[lo..hi].partition_point(pred)
+}
@@ -0,0 +1,100 @@
+//! k-way merge with a binary heap of the run heads.
+//!
+//! Taking the smallest head and replacing it with the next element of its run sifts the new head
+//! down, which compares it to both children on every level. Also used lazily by
+//! `other::sorted_iter`.
+
+use std::cmp::Ordering;
+use std::collections::binary_heap::PeekMut;
+use std::collections::BinaryHeap;
+use std::{mem, vec};
+
+/// Merges the sorted `runs` into one sorted `Vec`. Stable, equal elements keep the order of their
+/// runs.
+pub fn merge<T: Ord>(runs: Vec<Vec<T>>) -> Vec<T> {
+    merge_iter(runs.into_iter().map(Vec::into_iter).collect()).collect()
+}
+
+/// Lazy version of [`merge`], every call to `next` does one heap operation.
+pub fn merge_iter<T: Ord>(runs: Vec<vec::IntoIter<T>>) -> impl Iterator<Item = T> {
+    KWayMerge::new(runs)
+}
+
+/// Head of a run in the merge heap. Ordered in reverse so that [`BinaryHeap`] pops the smallest
+/// element, equal elements by ascending run index, which keeps the merge stable.
+struct Head<T> {
+    value: T,
+    run_idx: usize,
+}
+
+impl<T: Ord> Ord for Head<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .value
+            .cmp(&self.value)
+            .then_with(|| other.run_idx.cmp(&self.run_idx))
+    }
+}
+
+impl<T: Ord> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Head<T> {}
+
+struct KWayMerge<T> {
+    runs: Vec<vec::IntoIter<T>>,
+    heap: BinaryHeap<Head<T>>,
+}
+
+impl<T: Ord> KWayMerge<T> {
+    fn new(mut runs: Vec<vec::IntoIter<T>>) -> Self {
+        let heap = runs
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(run_idx, run)| {
+                Some(Head {
+                    value: run.next()?,
+                    run_idx,
+                })
+            })
+            .collect();
+
+        Self { runs, heap }
+    }
+}
+
+impl<T: Ord> Iterator for KWayMerge<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // A single run doesn't need the heap, which is the common case for short inputs in
+        // other::sorted_iter.
+        if self.runs.len() == 1 {
+            return self
+                .heap
+                .pop()
+                .map(|head| head.value)
+                .or_else(|| self.runs[0].next());
+        }
+
+        let mut head = self.heap.peek_mut()?;
+        match self.runs[head.run_idx].next() {
+            Some(value) => Some(mem::replace(&mut head.value, value)),
+            None => Some(PeekMut::pop(head).value),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.heap.len() + self.runs.iter().map(|run| run.len()).sum::<usize>();
+        (len, Some(len))
+    }
+}
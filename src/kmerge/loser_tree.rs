@@ -0,0 +1,100 @@
+//! k-way merge with a tree of losers.
+//!
+//! The k runs are the leaves of a binary tournament tree, every inner node stores the run that
+//! lost the match played there and the root the overall winner. After the winner's head is taken
+//! only the matches on the path from its leaf to the root are replayed, against the stored losers.
+
+use std::vec;
+
+/// Merges the sorted `runs` into one sorted `Vec`. Stable, equal elements keep the order of their
+/// runs.
+pub fn merge<T: Ord>(runs: Vec<Vec<T>>) -> Vec<T> {
+    let len = runs.iter().map(|run| run.len()).sum();
+    let mut out = Vec::with_capacity(len);
+
+    if runs.is_empty() {
+        return out;
+    }
+
+    let mut tree = LoserTree::new(runs);
+    while let Some(val) = tree.pop() {
+        out.push(val);
+    }
+
+    out
+}
+
+struct LoserTree<T> {
+    runs: Vec<vec::IntoIter<T>>,
+    /// Current first element of each run, `None` once the run is exhausted.
+    heads: Vec<Option<T>>,
+    /// Node `n` has the children `2n` and `2n + 1`, nodes `k..2k` are the leaves of run `n - k`.
+    /// Inner nodes store the loser of their match, node 0 the overall winner.
+    nodes: Vec<usize>,
+}
+
+impl<T: Ord> LoserTree<T> {
+    fn new(runs: Vec<Vec<T>>) -> Self {
+        let mut runs = runs.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+        let heads = runs.iter_mut().map(Iterator::next).collect();
+        let k = runs.len();
+
+        let mut tree = Self {
+            runs,
+            heads,
+            nodes: vec![0; k],
+        };
+        tree.nodes[0] = tree.play(1);
+
+        tree
+    }
+
+    /// Plays all matches in the subtree of `node` and returns its winner.
+    fn play(&mut self, node: usize) -> usize {
+        let k = self.runs.len();
+        if node >= k {
+            return node - k;
+        }
+
+        let left = self.play(2 * node);
+        let right = self.play(2 * node + 1);
+        let (winner, loser) = if self.beats(right, left) {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        self.nodes[node] = loser;
+
+        winner
+    }
+
+    /// Whether the head of run `a` goes before the head of run `b`. Exhausted runs lose against
+    /// everything, ties go to the earlier run.
+    #[inline]
+    fn beats(&self, a: usize, b: usize) -> bool {
+        match (&self.heads[a], &self.heads[b]) {
+            (Some(a_head), Some(b_head)) => a_head < b_head || (a_head == b_head && a < b),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let k = self.runs.len();
+        let mut winner = self.nodes[0];
+
+        let val = self.heads[winner].take()?;
+        self.heads[winner] = self.runs[winner].next();
+
+        let mut node = (winner + k) / 2;
+        while node > 0 {
+            if self.beats(self.nodes[node], winner) {
+                std::mem::swap(&mut self.nodes[node], &mut winner);
+            }
+            node /= 2;
+        }
+        self.nodes[0] = winner;
+
+        Some(val)
+    }
+}
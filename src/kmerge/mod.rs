@@ -0,0 +1,59 @@
+//! Merging k sorted runs into one sorted output, as the last step of an external sort, a
+//! sort-merge join or a chunked sort such as `other::sorted_iter`.
+//!
+//! The strategies differ in how many comparisons each output element costs and how predictable
+//! they are:
+//!
+//! - [`loser_tree`] replays one path of a tournament tree, exactly *log2(k)* comparisons per
+//!   element, with the same access pattern no matter which run wins.
+//! - [`heap`] keeps the run heads in a binary heap, up to *2 log2(k)* comparisons per element.
+//! - [`pairwise`] merges the runs two at a time in *log2(k)* rounds, one comparison per element and
+//!   round, but every round moves all elements.
+//! - [`galloping`] is `pairwise` with the galloping mode of timsort, which skips ahead with an
+//!   exponential search once one side keeps winning. Wins on runs that don't interleave much.
+//!
+//! All strategies are stable, equal elements keep the order of their runs. `BENCH_OTHER=kmerge`
+//! compares them for different run counts.
+
+pub mod galloping;
+pub mod heap;
+pub mod loser_tree;
+pub mod pairwise;
+
+pub type MergeFn<T> = fn(Vec<Vec<T>>) -> Vec<T>;
+
+/// All merge strategies by name.
+pub fn strategies<T: Ord>() -> [(&'static str, MergeFn<T>); 4] {
+    [
+        ("loser_tree", loser_tree::merge),
+        ("heap", heap::merge),
+        ("pairwise", pairwise::merge),
+        ("galloping", galloping::merge),
+    ]
+}
+
+/// Splits `v` into `run_count` runs of about equal length and sorts each of them, to turn the
+/// patterns into run sets. Produces fewer runs if `v` is shorter than `run_count`.
+///
+/// # Panics
+///
+/// Panics if `run_count` is 0.
+pub fn split_into_runs<T: Ord>(v: Vec<T>, run_count: usize) -> Vec<Vec<T>> {
+    assert!(run_count > 0, "run_count must not be 0");
+
+    let run_len = v.len().div_ceil(run_count).max(1);
+    let mut iter = v.into_iter();
+    let mut runs = Vec::with_capacity(run_count);
+
+    loop {
+        let mut run = iter.by_ref().take(run_len).collect::<Vec<_>>();
+        if run.is_empty() {
+            break;
+        }
+
+        run.sort();
+        runs.push(run);
+    }
+
+    runs
+}
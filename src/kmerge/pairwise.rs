@@ -0,0 +1,62 @@
+//! k-way merge as a series of two-way merges.
+//!
+//! Every round merges neighboring runs, halving the number of runs, like the bottom-up levels of a
+//! merge sort. A two-way merge is a single comparison per element with a simple loop, but all
+//! elements are moved in each of the *log2(k)* rounds.
+
+use std::vec;
+
+/// Merges the sorted `runs` into one sorted `Vec`. Stable, equal elements keep the order of their
+/// runs.
+pub fn merge<T: Ord>(runs: Vec<Vec<T>>) -> Vec<T> {
+    merge_rounds(runs, merge_two)
+}
+
+/// Merges neighboring runs with `merge_two` until only one is left. `merge_two` must prefer its
+/// first argument on ties for the result to be stable.
+pub(crate) fn merge_rounds<T>(
+    mut runs: Vec<Vec<T>>,
+    mut merge_two: impl FnMut(Vec<T>, Vec<T>) -> Vec<T>,
+) -> Vec<T> {
+    while runs.len() > 1 {
+        let mut iter = runs.into_iter();
+        runs = Vec::with_capacity(iter.len().div_ceil(2));
+
+        while let Some(left) = iter.next() {
+            match iter.next() {
+                Some(right) => runs.push(merge_two(left, right)),
+                None => runs.push(left),
+            }
+        }
+    }
+
+    runs.pop().unwrap_or_default()
+}
+
+/// Merges the sorted `left` and `right`, preferring `left` on ties.
+fn merge_two<T: Ord>(left: Vec<T>, right: Vec<T>) -> Vec<T> {
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+
+    let (mut left_head, mut right_head) = (left.next(), right.next());
+    while let (Some(l), Some(r)) = (&left_head, &right_head) {
+        if r < l {
+            out.push(right_head.take().unwrap());
+            right_head = right.next();
+        } else {
+            out.push(left_head.take().unwrap());
+            left_head = left.next();
+        }
+    }
+
+    push_rest(&mut out, left_head, left);
+    push_rest(&mut out, right_head, right);
+
+    out
+}
+
+fn push_rest<T>(out: &mut Vec<T>, head: Option<T>, rest: vec::IntoIter<T>) {
+    out.extend(head);
+    out.extend(rest);
+}
@@ -16,6 +16,7 @@
     auto_traits,
     generic_const_exprs
 )]
+#![cfg_attr(feature = "strict_provenance", feature(strict_provenance_lints))]
 
 macro_rules! sort_impl {
     ($name:expr) => {
@@ -31,6 +32,8 @@ macro_rules! sort_impl {
             where
                 T: Ord,
             {
+                #[cfg(feature = "instrument_audit")]
+                let _audit = crate::instrument::audit::AuditGuard::start::<T>($name, arr.len());
                 sort(arr);
             }
 
@@ -39,6 +42,8 @@ macro_rules! sort_impl {
             where
                 F: FnMut(&T, &T) -> std::cmp::Ordering,
             {
+                #[cfg(feature = "instrument_audit")]
+                let _audit = crate::instrument::audit::AuditGuard::start::<T>($name, arr.len());
                 sort_by(arr, compare);
             }
         }
@@ -58,6 +63,27 @@ macro_rules! force_print {
 #[macro_use]
 pub mod ffi_util;
 
+#[macro_use]
+pub mod instrument;
+
+pub mod registry;
+
+pub mod analysis;
+
+pub mod dispatch;
+
+pub mod kmerge;
+
+// Software prefetch hints for the prefetch variants of the Rust std sorts.
+pub mod prefetch;
+
+// The oracles and comparator faults of the test suite, for fuzzers, plugins and other users of
+// this crate.
+pub use sort_test_tools::{fault, verify};
+
+#[cfg(all(feature = "plugin", unix))]
+pub mod plugin;
+
 // Copy the stdlib implementations to have comparable builds.
 // The stdlib is compiled with unknown optimizations such as PGO.
 pub mod other;
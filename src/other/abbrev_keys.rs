@@ -0,0 +1,92 @@
+//! Experiment: sorting wide keys by abbreviated keys, as databases do.
+//!
+//! Comparing two strings means following two pointers and comparing bytes until they differ, and
+//! comparing two `u128` takes two comparisons and a branch on a 64-bit machine. An abbreviated key
+//! is a `u64` that preserves the order of the full keys: if `a < b` then
+//! `abbrev(a) <= abbrev(b)`. Sorting `(abbrev, index)` pairs compares the `u64` first and only
+//! looks at the full keys if the abbreviations are equal. For strings the abbreviation is the
+//! first 8 bytes, for `u128` the upper 64 bits.
+//!
+//! The benefit depends on how often the abbreviations decide the comparison, strings with a long
+//! common prefix get nothing out of it but pay for the pairs. For `u128` the pairs are as large as
+//! the keys and the final permutation adds a random access per element, the comparisons have to
+//! be expensive enough to make up for that. `BENCH_OTHER=abbrev_keys` compares all three cases to
+//! sorting the keys directly.
+
+use std::cmp::Ordering;
+
+use crate::other::permute::apply_permutation;
+
+/// Keys with an order-preserving `u64` abbreviation.
+pub trait AbbrevKey: Ord {
+    /// Must not be greater than the abbreviation of any key greater than `self`.
+    fn abbrev(&self) -> u64;
+}
+
+impl AbbrevKey for str {
+    fn abbrev(&self) -> u64 {
+        // Missing bytes are 0, which sorts a string before all longer strings with the same prefix.
+        let mut bytes = [0u8; 8];
+        let len = self.len().min(8);
+        bytes[..len].copy_from_slice(&self.as_bytes()[..len]);
+        u64::from_be_bytes(bytes)
+    }
+}
+
+impl AbbrevKey for String {
+    fn abbrev(&self) -> u64 {
+        self.as_str().abbrev()
+    }
+}
+
+impl<T: AbbrevKey + ?Sized> AbbrevKey for &T {
+    fn abbrev(&self) -> u64 {
+        (**self).abbrev()
+    }
+}
+
+impl AbbrevKey for u128 {
+    fn abbrev(&self) -> u64 {
+        (self >> 64) as u64
+    }
+}
+
+impl AbbrevKey for u64 {
+    fn abbrev(&self) -> u64 {
+        *self
+    }
+}
+
+impl AbbrevKey for i32 {
+    fn abbrev(&self) -> u64 {
+        // Flipping the sign bit keeps the order of negative values.
+        ((*self as u32) ^ (1 << 31)) as u64
+    }
+}
+
+/// Stable sort of `v` by `(abbrev, full key, original position)`.
+pub fn sort<T: AbbrevKey>(v: &mut [T]) {
+    let mut keys = abbreviated_keys(v);
+    keys.sort_by(|a, b| cmp_abbreviated(v, a, b));
+
+    let mut indices = keys.into_iter().map(|(_, i)| i).collect::<Vec<_>>();
+    apply_permutation(&mut indices, v);
+}
+
+/// Unstable sort of `v` by `(abbrev, full key)`.
+pub fn sort_unstable<T: AbbrevKey>(v: &mut [T]) {
+    let mut keys = abbreviated_keys(v);
+    keys.sort_unstable_by(|a, b| cmp_abbreviated(v, a, b));
+
+    let mut indices = keys.into_iter().map(|(_, i)| i).collect::<Vec<_>>();
+    apply_permutation(&mut indices, v);
+}
+
+fn abbreviated_keys<T: AbbrevKey>(v: &[T]) -> Vec<(u64, usize)> {
+    v.iter().map(T::abbrev).zip(0..).collect()
+}
+
+#[inline]
+fn cmp_abbreviated<T: AbbrevKey>(v: &[T], a: &(u64, usize), b: &(u64, usize)) -> Ordering {
+    a.0.cmp(&b.0).then_with(|| v[a.1].cmp(&v[b.1]))
+}
@@ -0,0 +1,69 @@
+//! Sorting many small slices, eg. the rows of every group in a database query.
+//!
+//! A single large sort spends most of its time in partitions or merges, while a batch of slices
+//! with 4 to 32 elements each is dominated by the fixed cost per call: length checks, the
+//! dispatch to the small-sort and mispredicted branches on every slice end. `BENCH_OTHER=batch_sort`
+//! splits the input into such slices and compares sorting them with every enabled sort, against
+//! the plain insertion sort in this module.
+
+use std::cmp::Ordering;
+
+/// Sorts every slice in `slices` with `sort_fn`.
+#[inline]
+pub fn sort_batch_with<T>(slices: &mut [&mut [T]], mut sort_fn: impl FnMut(&mut [T])) {
+    for slice in slices.iter_mut() {
+        sort_fn(slice);
+    }
+}
+
+/// Sorts every slice in `slices`, not preserving the order of equal elements.
+pub fn sort_batch<T: Ord>(slices: &mut [&mut [T]]) {
+    sort_batch_with(slices, |slice| slice.sort_unstable());
+}
+
+/// Sorts every slice in `slices` with `compare`, not preserving the order of equal elements.
+pub fn sort_batch_by<T, F: FnMut(&T, &T) -> Ordering>(slices: &mut [&mut [T]], mut compare: F) {
+    sort_batch_with(slices, |slice| slice.sort_unstable_by(&mut compare));
+}
+
+/// Sorts every slice in `slices`, preserving the order of equal elements.
+pub fn sort_batch_stable<T: Ord>(slices: &mut [&mut [T]]) {
+    sort_batch_with(slices, |slice| slice.sort());
+}
+
+/// Sorts every slice in `slices` with [`insertion_sort`], the baseline for short slices.
+pub fn sort_batch_insertion<T: Ord>(slices: &mut [&mut [T]]) {
+    sort_batch_with(slices, insertion_sort);
+}
+
+/// Stable insertion sort without any special cases, quadratic in the slice length.
+pub fn insertion_sort<T: Ord>(v: &mut [T]) {
+    insertion_sort_by(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Same as [`insertion_sort`] with `is_less`, shared by the sorts that finish short slices with it.
+pub fn insertion_sort_by<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && is_less(&v[j], &v[j - 1]) {
+            v.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+/// Splits `v` into consecutive slices with the lengths produced by `next_len`, the last slice may
+/// be shorter.
+pub fn split_into_batch<T>(v: &mut [T], mut next_len: impl FnMut() -> usize) -> Vec<&mut [T]> {
+    let mut slices = Vec::new();
+    let mut rest = v;
+
+    while !rest.is_empty() {
+        let len = next_len().clamp(1, rest.len());
+        let (slice, tail) = rest.split_at_mut(len);
+        slices.push(slice);
+        rest = tail;
+    }
+
+    slices
+}
@@ -0,0 +1,251 @@
+//! Branchless building blocks shared by the small-sorts, partitions and merges in this repository.
+//!
+//! These are extracted here so they can be benchmarked and inspected in isolation, eg. via
+//! `BENCH_OTHER=branchless` or by looking at the generated assembly of the `#[inline(never)]`
+//! wrappers at the end of this file.
+
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr;
+
+/// Picks `if_true` or `if_false` based on `cond`.
+///
+/// By limiting the select to pointers, we are guaranteed good cmov code-gen regardless of the size
+/// of `T`.
+#[inline(always)]
+pub fn select<T>(cond: bool, if_true: *const T, if_false: *const T) -> *const T {
+    if cond {
+        if_true
+    } else {
+        if_false
+    }
+}
+
+/// Swaps the values pointed to by `a_ptr` and `b_ptr` if `should_swap` is true, without branching
+/// on `should_swap`.
+///
+/// # Safety
+///
+/// `a_ptr` and `b_ptr` must be valid for reads and writes and properly aligned. They may point to
+/// the same element, which the Lomuto partitions rely on, but must not partially overlap.
+#[inline(always)]
+pub unsafe fn branchless_swap<T>(a_ptr: *mut T, b_ptr: *mut T, should_swap: bool) {
+    // This is a branchless version of swap if.
+    // The equivalent code with a branch would be:
+    //
+    // if should_swap {
+    //     ptr::swap_nonoverlapping(a_ptr, b_ptr, 1);
+    // }
+
+    // Give ourselves some scratch space to work with.
+    // We do not have to worry about drops: `MaybeUninit` does nothing when dropped.
+    let mut tmp = MaybeUninit::<T>::uninit();
+
+    // The goal is to generate cmov instructions here.
+    let a_swap_ptr = select(should_swap, b_ptr, a_ptr) as *mut T;
+    let b_swap_ptr = select(should_swap, a_ptr, b_ptr) as *mut T;
+
+    // SAFETY: See function safety comment.
+    unsafe {
+        ptr::copy_nonoverlapping(b_swap_ptr, tmp.as_mut_ptr(), 1);
+        ptr::copy(a_swap_ptr, a_ptr, 1);
+        ptr::copy_nonoverlapping(tmp.as_ptr(), b_ptr, 1);
+    }
+}
+
+/// Swap two values in the slice pointed to by `v_base` at the position `a_pos` and `b_pos` if the
+/// value at position `b_pos` is less than the one at position `a_pos`.
+///
+/// This is the compare-exchange primitive of sorting networks.
+///
+/// # Safety
+///
+/// `a_pos` and `b_pos` each added to `v_base` must yield valid and properly aligned pointers into
+/// the same allocation. `a_pos` and `b_pos` must be different.
+#[inline(always)]
+pub unsafe fn swap_if_less<T, F>(v_base: *mut T, a_pos: usize, b_pos: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    debug_assert!(a_pos != b_pos);
+
+    // SAFETY: See function safety comment.
+    unsafe {
+        let v_a = v_base.add(a_pos);
+        let v_b = v_base.add(b_pos);
+
+        // PANIC SAFETY: if is_less panics, no scratch memory was created and the slice should
+        // still be in a well defined state, without duplicates.
+
+        // Important to only swap if it is more and not if it is equal. is_less should return false
+        // for equal, so we don't swap.
+        let should_swap = is_less(&*v_b, &*v_a);
+
+        // The goal is to generate cmov instructions here.
+        let left_swap = select(should_swap, v_b, v_a);
+        let right_swap = select(should_swap, v_a, v_b);
+
+        let right_swap_tmp = ManuallyDrop::new(ptr::read(right_swap));
+        ptr::copy(left_swap, v_a, 1);
+        ptr::copy_nonoverlapping(&*right_swap_tmp, v_b, 1);
+    }
+}
+
+/// Sorts `v[0..2]` with a single compare-exchange.
+///
+/// Panics if `v.len() < 2`.
+#[inline(always)]
+pub fn sort2<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(v.len() >= 2);
+
+    // SAFETY: We checked the len.
+    unsafe {
+        swap_if_less(v.as_mut_ptr(), 0, 1, is_less);
+    }
+}
+
+/// Sorts `v[0..3]` with an optimal sorting network of three compare-exchanges.
+///
+/// Panics if `v.len() < 3`.
+#[inline(always)]
+pub fn sort3<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(v.len() >= 3);
+
+    let v_base = v.as_mut_ptr();
+
+    // SAFETY: We checked the len.
+    unsafe {
+        swap_if_less(v_base, 0, 1, is_less);
+        swap_if_less(v_base, 1, 2, is_less);
+        swap_if_less(v_base, 0, 1, is_less);
+    }
+}
+
+/// Sorts `v[0..4]` with an optimal sorting network of five compare-exchanges, see
+/// <https://bertdobbelaere.github.io/sorting_networks.html>.
+///
+/// This is not stable, see [`sort4_stable`] for a stable alternative.
+///
+/// Panics if `v.len() < 4`.
+#[inline(always)]
+pub fn sort4<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(v.len() >= 4);
+
+    let v_base = v.as_mut_ptr();
+
+    // SAFETY: We checked the len.
+    unsafe {
+        swap_if_less(v_base, 0, 2, is_less);
+        swap_if_less(v_base, 1, 3, is_less);
+        swap_if_less(v_base, 0, 1, is_less);
+        swap_if_less(v_base, 2, 3, is_less);
+        swap_if_less(v_base, 1, 2, is_less);
+    }
+}
+
+/// Stably sorts the 4 elements starting at `v_base` into `dst[0..4]`.
+///
+/// Algorithm by Orson Peters, only does 5 instead of 6 comparisons compared to a stable
+/// transposition 4 element sorting-network, and always copies each element exactly once.
+///
+/// # Safety
+///
+/// The caller MUST guarantee that `v_base` is valid for 4 reads and `dst` is valid for 4 writes,
+/// and that they don't overlap.
+#[inline(always)]
+pub unsafe fn sort4_stable<T, F>(v_base: *const T, dst: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // SAFETY: all pointers have offset at most 3 from v_base and dst, and are
+    // thus in-bounds by the precondition.
+    unsafe {
+        // Stably create two pairs a <= b and c <= d.
+        let c1 = is_less(&*v_base.add(1), &*v_base);
+        let c2 = is_less(&*v_base.add(3), &*v_base.add(2));
+        let a = v_base.add(c1 as usize);
+        let b = v_base.add(!c1 as usize);
+        let c = v_base.add(2 + c2 as usize);
+        let d = v_base.add(2 + (!c2 as usize));
+
+        // Compare (a, c) and (b, d) to identify max/min. We're left with two
+        // unknown elements, but because we are a stable sort we must know which
+        // one is leftmost and which one is rightmost.
+        // c3, c4 | min max unknown_left unknown_right
+        //  0,  0 |  a   d    b         c
+        //  0,  1 |  a   b    c         d
+        //  1,  0 |  c   d    a         b
+        //  1,  1 |  c   b    a         d
+        let c3 = is_less(&*c, &*a);
+        let c4 = is_less(&*d, &*b);
+        let min = select(c3, c, a);
+        let max = select(c4, b, d);
+        let unknown_left = select(c3, a, select(c4, c, b));
+        let unknown_right = select(c4, d, select(c3, b, c));
+
+        // Sort the last two unknown elements.
+        let c5 = is_less(&*unknown_right, &*unknown_left);
+        let lo = select(c5, unknown_right, unknown_left);
+        let hi = select(c5, unknown_left, unknown_right);
+
+        ptr::copy_nonoverlapping(min, dst, 1);
+        ptr::copy_nonoverlapping(lo, dst.add(1), 1);
+        ptr::copy_nonoverlapping(hi, dst.add(2), 1);
+        ptr::copy_nonoverlapping(max, dst.add(3), 1);
+    }
+}
+
+// --- Outlined instantiations ---
+//
+// The primitives above are all `inline(always)`, which makes them hard to inspect in isolation.
+// These wrappers give them a stable symbol for tools like cargo-show-asm and for the
+// microbenchmarks.
+
+/// Applies [`sort2`] to every full chunk of 2 elements in `v`.
+#[inline(never)]
+pub fn sort2_chunks<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for chunk in v.chunks_exact_mut(2) {
+        sort2(chunk, is_less);
+    }
+}
+
+/// Applies [`sort3`] to every full chunk of 3 elements in `v`.
+#[inline(never)]
+pub fn sort3_chunks<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for chunk in v.chunks_exact_mut(3) {
+        sort3(chunk, is_less);
+    }
+}
+
+/// Applies [`sort4`] to every full chunk of 4 elements in `v`.
+#[inline(never)]
+pub fn sort4_chunks<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for chunk in v.chunks_exact_mut(4) {
+        sort4(chunk, is_less);
+    }
+}
+
+/// Applies [`sort4_stable`] to every full chunk of 4 elements in `v`.
+#[inline(never)]
+pub fn sort4_stable_chunks<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let mut dst = MaybeUninit::<[T; 4]>::uninit();
+    let dst_ptr = dst.as_mut_ptr() as *mut T;
+
+    for chunk in v.chunks_exact_mut(4) {
+        // SAFETY: `chunk` is valid for 4 reads and writes, and `dst` is a separate stack array
+        // valid for 4 writes. `sort4_stable` copies each element exactly once, so copying `dst`
+        // back into `chunk` leaves no duplicates. If `is_less` panics nothing was written yet.
+        unsafe {
+            sort4_stable(chunk.as_ptr(), dst_ptr, is_less);
+            ptr::copy_nonoverlapping(dst_ptr, chunk.as_mut_ptr(), 4);
+        }
+    }
+}
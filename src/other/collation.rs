@@ -0,0 +1,152 @@
+//! A locale-aware string comparison modeled after the Unicode collation algorithm, the very
+//! expensive end of the comparison functions a sort sees in practice.
+//!
+//! Like ICU, the comparison has three levels that are only consulted if the ones before are equal:
+//! the base letters, then the accents, then the case. Whitespace and punctuation are ignored on all
+//! three. Equal strings on all levels are ordered by their code points, so the order is total and
+//! only equal strings compare equal. Every level walks both strings and maps every char, a
+//! comparison costs many times a memcmp, which is the regime in which a sort that does fewer
+//! comparisons wins even if it moves more. Contractions, expansions and the tailorings of the
+//! languages are not modeled, the accented letters of Latin-1 are enough for the cost.
+//!
+//! [`sort_key`] computes the same order as a byte string once per element, like ICU's
+//! `getSortKey`. `BENCH_OTHER=collation` compares both.
+
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy)]
+struct Weights {
+    /// The base letter, 0 for ignorable chars.
+    primary: u32,
+    /// The accent, 1 for none.
+    secondary: u8,
+    /// 1 for lower and 2 for upper case.
+    tertiary: u8,
+}
+
+const IGNORABLE: Weights = Weights {
+    primary: 0,
+    secondary: 0,
+    tertiary: 0,
+};
+
+/// Compares `a` and `b` in collation order, see the module docs.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    compare_level(a, b, |w| w.primary)
+        .then_with(|| compare_level(a, b, |w| w.secondary as u32))
+        .then_with(|| compare_level(a, b, |w| w.tertiary as u32))
+        .then_with(|| a.cmp(b))
+}
+
+/// Byte string whose order is the order of [`compare`].
+pub fn sort_key(s: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(s.len() * 6 + 3);
+
+    // Every weight is non-zero in its first byte, so the 0 that ends a level is less than any
+    // weight and a string that is a prefix of another on a level sorts first.
+    for weights in s.chars().map(weights).filter(|w| w.primary != 0) {
+        key.extend_from_slice(&(weights.primary + 0x1_0000).to_be_bytes()[1..]);
+    }
+    key.push(0);
+
+    let levels: [fn(Weights) -> u8; 2] = [|w| w.secondary, |w| w.tertiary];
+    for level in levels {
+        key.extend(
+            s.chars()
+                .map(weights)
+                .map(level)
+                .filter(|weight| *weight != 0),
+        );
+        key.push(0);
+    }
+
+    // UTF-8 orders like the code points.
+    key.extend_from_slice(s.as_bytes());
+
+    key
+}
+
+fn compare_level(a: &str, b: &str, level: impl Fn(Weights) -> u32 + Copy) -> Ordering {
+    let a_weights = a
+        .chars()
+        .map(weights)
+        .map(level)
+        .filter(|weight| *weight != 0);
+    let b_weights = b
+        .chars()
+        .map(weights)
+        .map(level)
+        .filter(|weight| *weight != 0);
+
+    a_weights.cmp(b_weights)
+}
+
+fn weights(c: char) -> Weights {
+    if c.is_whitespace() || c.is_ascii_punctuation() {
+        return IGNORABLE;
+    }
+
+    let tertiary = if c.is_uppercase() { 2 } else { 1 };
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    let (base, secondary) = decompose(lower);
+
+    let primary = match base {
+        '0'..='9' => base as u32 - '0' as u32 + 1,
+        'a'..='z' => base as u32 - 'a' as u32 + 11,
+        // All other chars sort after the Latin letters, by code point.
+        _ => base as u32 + 0x100,
+    };
+
+    Weights {
+        primary,
+        secondary,
+        tertiary,
+    }
+}
+
+/// The base letter and the accent of a lower case char.
+fn decompose(c: char) -> (char, u8) {
+    const NONE: u8 = 1;
+    const GRAVE: u8 = 2;
+    const ACUTE: u8 = 3;
+    const CIRCUMFLEX: u8 = 4;
+    const TILDE: u8 = 5;
+    const DIAERESIS: u8 = 6;
+    const RING: u8 = 7;
+    const CEDILLA: u8 = 8;
+    const STROKE: u8 = 9;
+    const SHARP: u8 = 10;
+
+    match c {
+        'à' => ('a', GRAVE),
+        'á' => ('a', ACUTE),
+        'â' => ('a', CIRCUMFLEX),
+        'ã' => ('a', TILDE),
+        'ä' => ('a', DIAERESIS),
+        'å' => ('a', RING),
+        'ç' => ('c', CEDILLA),
+        'è' => ('e', GRAVE),
+        'é' => ('e', ACUTE),
+        'ê' => ('e', CIRCUMFLEX),
+        'ë' => ('e', DIAERESIS),
+        'ì' => ('i', GRAVE),
+        'í' => ('i', ACUTE),
+        'î' => ('i', CIRCUMFLEX),
+        'ï' => ('i', DIAERESIS),
+        'ñ' => ('n', TILDE),
+        'ò' => ('o', GRAVE),
+        'ó' => ('o', ACUTE),
+        'ô' => ('o', CIRCUMFLEX),
+        'õ' => ('o', TILDE),
+        'ö' => ('o', DIAERESIS),
+        'ø' => ('o', STROKE),
+        'ß' => ('s', SHARP),
+        'ù' => ('u', GRAVE),
+        'ú' => ('u', ACUTE),
+        'û' => ('u', CIRCUMFLEX),
+        'ü' => ('u', DIAERESIS),
+        'ý' => ('y', ACUTE),
+        'ÿ' => ('y', DIAERESIS),
+        _ => (c, NONE),
+    }
+}
@@ -1 +1,13 @@
 ffi_sort_impl!("cpp_intel_avx512", intel_avx512);
+
+extern "C" {
+    fn intel_avx512_dispatch_target() -> *const std::ffi::c_char;
+}
+
+/// Name of the SIMD target the sort uses on this machine.
+pub fn dispatch_target() -> String {
+    // SAFETY: Returns a pointer to a static nul terminated string.
+    unsafe { std::ffi::CStr::from_ptr(intel_avx512_dispatch_target()) }
+        .to_string_lossy()
+        .into_owned()
+}
@@ -1 +1,13 @@
 ffi_sort_impl!("cpp_simdsort", simdsort_avx2);
+
+extern "C" {
+    fn simdsort_avx2_dispatch_target() -> *const std::ffi::c_char;
+}
+
+/// Name of the SIMD target the sort uses on this machine.
+pub fn dispatch_target() -> String {
+    // SAFETY: Returns a pointer to a static nul terminated string.
+    unsafe { std::ffi::CStr::from_ptr(simdsort_avx2_dispatch_target()) }
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,21 @@
+//! C++ string sorts that get `&str` values as (pointer, len) pairs and compare the bytes directly,
+//! instead of calling back into Rust for every comparison.
+
+use sort_test_tools::ffi_types::FFIStr;
+
+use crate::ffi_util::sort_str_slice_ffi;
+
+extern "C" {
+    fn cpp_std_sort_str(data: *mut FFIStr, len: usize) -> u32;
+    fn cpp_msd_radix_sort_str(data: *mut FFIStr, len: usize) -> u32;
+}
+
+/// `std::sort` with `std::string_view` comparison.
+pub fn std_sort(v: &mut [&str]) {
+    sort_str_slice_ffi(v, cpp_std_sort_str, "cpp_std_sort_str");
+}
+
+/// Most significant digit first radix sort, one byte per level.
+pub fn msd_radix_sort(v: &mut [&str]) {
+    sort_str_slice_ffi(v, cpp_msd_radix_sort_str, "cpp_msd_radix_sort_str");
+}
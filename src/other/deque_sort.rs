@@ -0,0 +1,156 @@
+//! Sorting a [`VecDeque`] in place, without [`VecDeque::make_contiguous`] or a copy into a `Vec`.
+//!
+//! The elements of a deque are stored in up to two slices, see [`VecDeque::as_mut_slices`]. Both
+//! slices are sorted on their own, and then merged across the wrap-around with the SymMerge
+//! algorithm by Kim and Kutzner, which merges by rotating sub-ranges and needs no buffer. The
+//! rotations are done with [`VecDeque::swap`] on logical indices, so the merge doesn't care where
+//! the buffer wraps around. `BENCH_OTHER=deque_sort` compares this to sorting after
+//! `make_contiguous` and to copying into a `Vec` and back.
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// Sorts the deque, preserving the order of equal elements.
+pub fn sort<T: Ord>(d: &mut VecDeque<T>) {
+    sort_impl(d, true, &mut T::cmp);
+}
+
+/// Sorts the deque with `compare`, preserving the order of equal elements.
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(d: &mut VecDeque<T>, mut compare: F) {
+    sort_impl(d, true, &mut compare);
+}
+
+/// Sorts the deque, not preserving the order of equal elements.
+pub fn sort_unstable<T: Ord>(d: &mut VecDeque<T>) {
+    sort_impl(d, false, &mut T::cmp);
+}
+
+/// Sorts the deque with `compare`, not preserving the order of equal elements.
+pub fn sort_unstable_by<T, F: FnMut(&T, &T) -> Ordering>(d: &mut VecDeque<T>, mut compare: F) {
+    sort_impl(d, false, &mut compare);
+}
+
+fn sort_impl<T, F: FnMut(&T, &T) -> Ordering>(d: &mut VecDeque<T>, stable: bool, compare: &mut F) {
+    let (front, back) = d.as_mut_slices();
+    let mid = front.len();
+
+    for slice in [front, back] {
+        if stable {
+            slice.sort_by(&mut *compare);
+        } else {
+            slice.sort_unstable_by(&mut *compare);
+        }
+    }
+
+    // The merge is stable, so it can be used for both.
+    let is_less = &mut |a: &T, b: &T| compare(a, b) == Ordering::Less;
+    if mid > 0 && mid < d.len() && is_less(&d[mid], &d[mid - 1]) {
+        sym_merge(d, 0, mid, d.len(), is_less);
+    }
+}
+
+/// Merges the sorted ranges `d[a..m]` and `d[m..b]` in place, stable.
+///
+/// See "Stable Minimum Storage Merging by Symmetric Comparisons" by Pok-Son Kim and Arne Kutzner.
+/// Uses *O*(*m* \* log(*n* / *m*)) comparisons and *O*(*n* \* log(*n*)) swaps, where *m* is the
+/// length of the shorter range.
+fn sym_merge<T, F: FnMut(&T, &T) -> bool>(
+    d: &mut VecDeque<T>,
+    a: usize,
+    m: usize,
+    b: usize,
+    is_less: &mut F,
+) {
+    // A single element on either side is inserted with a binary search, the general case below
+    // would do the same with more overhead.
+    if m - a == 1 {
+        let (mut i, mut j) = (m, b);
+        while i < j {
+            let h = i + (j - i) / 2;
+            if is_less(&d[h], &d[a]) {
+                i = h + 1;
+            } else {
+                j = h;
+            }
+        }
+
+        for k in a..i - 1 {
+            d.swap(k, k + 1);
+        }
+        return;
+    }
+
+    if b - m == 1 {
+        let (mut i, mut j) = (a, m);
+        while i < j {
+            let h = i + (j - i) / 2;
+            if !is_less(&d[m], &d[h]) {
+                i = h + 1;
+            } else {
+                j = h;
+            }
+        }
+
+        for k in (i + 1..=m).rev() {
+            d.swap(k, k - 1);
+        }
+        return;
+    }
+
+    let mid = a + (b - a) / 2;
+    let n = mid + m;
+    let (mut start, mut r) = if m > mid { (n - b, mid) } else { (a, m) };
+    let p = n - 1;
+
+    while start < r {
+        let c = start + (r - start) / 2;
+        if !is_less(&d[p - c], &d[c]) {
+            start = c + 1;
+        } else {
+            r = c;
+        }
+    }
+
+    let end = n - start;
+    if start < m && m < end {
+        rotate(d, start, m, end);
+    }
+    if a < start && start < mid {
+        sym_merge(d, a, start, mid, is_less);
+    }
+    if mid < end && end < b {
+        sym_merge(d, mid, end, b, is_less);
+    }
+}
+
+/// Swaps the ranges `d[a..m]` and `d[m..b]`, by reversing both and then the whole range.
+fn rotate<T>(d: &mut VecDeque<T>, a: usize, m: usize, b: usize) {
+    reverse(d, a, m);
+    reverse(d, m, b);
+    reverse(d, a, b);
+}
+
+fn reverse<T>(d: &mut VecDeque<T>, mut lo: usize, mut hi: usize) {
+    while lo + 1 < hi {
+        hi -= 1;
+        d.swap(lo, hi);
+        lo += 1;
+    }
+}
+
+/// Creates a deque with the elements of `v` in order, whose storage wraps around after the first
+/// `split` elements. `as_slices` returns `(&v[..split], &v[split..])`, unless `split` is 0 or
+/// `v.len()`, then the deque is contiguous. For tests and benchmarks.
+pub fn wrapped_deque<T>(mut v: Vec<T>, split: usize) -> VecDeque<T> {
+    let mut d = VecDeque::with_capacity(v.len());
+    let back = v.split_off(split);
+
+    for elem in back {
+        d.push_back(elem);
+    }
+    for elem in v.into_iter().rev() {
+        d.push_front(elem);
+    }
+
+    d
+}
@@ -0,0 +1,184 @@
+//! Calibration of the cost of calling the Rust comparison function from C and C++.
+//!
+//! [`ffi_null_sort_by`] and [`rust_null_sort_by`] both perform exactly `comp_count` comparisons of
+//! adjacent elements without moving any of them. The difference in runtime divided by
+//! `comp_count` is the per comparison overhead of the FFI callback, including `catch_unwind`. That
+//! is the correction factor for cross-language measurements that go through `sort_by`.
+//!
+//! The overhead has two parts, the call through a function pointer across the language boundary
+//! and the wrapper that turns a panic into a flag for the foreign side, see [`PanicStrategy`].
+//! [`rust_callback_null_sort_by`] calls the same wrapper from Rust, which separates the two. The
+//! difference between both strategies is what `catch_unwind` costs per call, the price of resuming
+//! panics of the comparison function instead of aborting the process.
+
+use std::cmp::Ordering;
+use std::hint::black_box;
+
+use sort_test_tools::ffi_types::CompResult;
+
+use crate::ffi_util::{
+    handle_ffi_ret_code, rust_fn_cmp, rust_fn_cmp_abort_on_panic, FFI_OK, FFI_RUST_PANIC,
+};
+
+type CmpFn<T> = unsafe extern "C" fn(&T, &T, *mut u8) -> CompResult;
+
+extern "C" {
+    fn ffi_null_sort_i32_by(
+        data: *mut i32,
+        len: usize,
+        comp_count: usize,
+        cmp_fn: CmpFn<i32>,
+        cmp_fn_ctx: *mut u8,
+        less_count: *mut usize,
+    ) -> u32;
+    fn ffi_null_sort_u64_by(
+        data: *mut u64,
+        len: usize,
+        comp_count: usize,
+        cmp_fn: CmpFn<u64>,
+        cmp_fn_ctx: *mut u8,
+        less_count: *mut usize,
+    ) -> u32;
+}
+
+/// How the callback handed to the foreign side deals with a panic of the comparison function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicStrategy {
+    /// What all FFI sorts use. Every call is wrapped in `catch_unwind`, a panic is reported to the
+    /// foreign side as a flag, which stops the sort, and resumed once the sort returned.
+    CatchUnwind,
+    /// No `catch_unwind`, a panic aborts the process. The flag is still returned and checked.
+    AbortOnPanic,
+}
+
+impl PanicStrategy {
+    fn callback<T, F: FnMut(&T, &T) -> Ordering>(self) -> CmpFn<T> {
+        match self {
+            Self::CatchUnwind => rust_fn_cmp::<T, F>,
+            Self::AbortOnPanic => rust_fn_cmp_abort_on_panic::<T, F>,
+        }
+    }
+}
+
+trait FfiNullSort: Sized {
+    fn null_sort_by<F: FnMut(&Self, &Self) -> Ordering>(
+        data: &mut [Self],
+        comp_count: usize,
+        panic_strategy: PanicStrategy,
+        compare: F,
+    ) -> usize;
+}
+
+impl<T> FfiNullSort for T {
+    default fn null_sort_by<F: FnMut(&T, &T) -> Ordering>(
+        _data: &mut [T],
+        _comp_count: usize,
+        _panic_strategy: PanicStrategy,
+        _compare: F,
+    ) -> usize {
+        panic!("Type not supported");
+    }
+}
+
+macro_rules! impl_ffi_null_sort {
+    ($type:ty, $fn_name:ident) => {
+        impl FfiNullSort for $type {
+            fn null_sort_by<F: FnMut(&Self, &Self) -> Ordering>(
+                data: &mut [Self],
+                comp_count: usize,
+                panic_strategy: PanicStrategy,
+                mut compare: F,
+            ) -> usize {
+                let mut less_count = 0;
+
+                // SAFETY: compare outlives the call and the C++ side only reads data.
+                let ret_code = unsafe {
+                    $fn_name(
+                        data.as_mut_ptr(),
+                        data.len(),
+                        comp_count,
+                        panic_strategy.callback::<$type, F>(),
+                        &mut compare as *mut F as *mut u8,
+                        &mut less_count,
+                    )
+                };
+                handle_ffi_ret_code(ret_code, stringify!($fn_name));
+
+                less_count
+            }
+        }
+    };
+}
+
+impl_ffi_null_sort!(i32, ffi_null_sort_i32_by);
+impl_ffi_null_sort!(u64, ffi_null_sort_u64_by);
+
+/// Calls `compare` `comp_count` times from C++, returns how often it returned `Less`.
+///
+/// Only i32 and u64 are supported.
+pub fn ffi_null_sort_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    comp_count: usize,
+    panic_strategy: PanicStrategy,
+    compare: F,
+) -> usize {
+    FfiNullSort::null_sort_by(v, comp_count, panic_strategy, compare)
+}
+
+/// Same access pattern and callback as [`ffi_null_sort_by`], but calls the callback from Rust,
+/// through a function pointer the compiler can't see through.
+#[inline(never)]
+pub fn rust_callback_null_sort_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    comp_count: usize,
+    panic_strategy: PanicStrategy,
+    mut compare: F,
+) -> usize {
+    let len = v.len();
+    if len < 2 {
+        return 0;
+    }
+
+    let cmp_fn = black_box(panic_strategy.callback::<T, F>());
+    let ctx = &mut compare as *mut F as *mut u8;
+
+    let mut less_count = 0;
+    let mut ret_code = FFI_OK;
+    let mut idx = 0;
+    for _ in 0..comp_count {
+        // SAFETY: compare outlives the loop, like it outlives the call of the C++ function.
+        let comp_result = unsafe { cmp_fn(&v[idx], &v[idx + 1], ctx) };
+        if comp_result.is_panic {
+            ret_code = FFI_RUST_PANIC;
+            break;
+        }
+
+        less_count += (comp_result.cmp_result == -1) as usize;
+        idx = if idx + 2 < len { idx + 1 } else { 0 };
+    }
+    handle_ffi_ret_code(ret_code, "rust_callback_null_sort_by");
+
+    less_count
+}
+
+/// Same access pattern as [`ffi_null_sort_by`], but calls `compare` directly from Rust.
+#[inline(never)]
+pub fn rust_null_sort_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    comp_count: usize,
+    mut compare: F,
+) -> usize {
+    let len = v.len();
+    if len < 2 {
+        return 0;
+    }
+
+    let mut less_count = 0;
+    let mut idx = 0;
+    for _ in 0..comp_count {
+        less_count += (compare(&v[idx], &v[idx + 1]) == Ordering::Less) as usize;
+        idx = if idx + 2 < len { idx + 1 } else { 0 };
+    }
+
+    less_count
+}
@@ -0,0 +1,58 @@
+//! Order-preserving maps from floats to unsigned integers of the same width, the keys that radix
+//! and SIMD sorts of floats sort instead of the floats.
+//!
+//! The order of the keys is the order of `total_cmp`: `-NaN < -inf < .. < -0.0 < 0.0 < .. < inf <
+//! NaN`, with NaNs ordered by their payload. Negative values have all bits flipped, which reverses
+//! their order, positive values only the sign bit, which puts them above all negative ones. The map
+//! is a bijection, every bit pattern round trips, including the payload of NaNs.
+
+/// A float with an order-preserving key, see the module docs.
+pub trait FloatKey: Copy {
+    /// The unsigned integer of the same width.
+    type Bits: Copy + Ord;
+
+    fn to_ordered_bits(self) -> Self::Bits;
+
+    fn from_ordered_bits(bits: Self::Bits) -> Self;
+}
+
+/// The key of `x`, whose order as an integer is the order of `total_cmp`.
+#[inline]
+pub fn to_ordered_bits<F: FloatKey>(x: F) -> F::Bits {
+    x.to_ordered_bits()
+}
+
+/// The float with the key `bits`, the reverse of [`to_ordered_bits`].
+#[inline]
+pub fn from_ordered_bits<F: FloatKey>(bits: F::Bits) -> F {
+    F::from_ordered_bits(bits)
+}
+
+macro_rules! float_key_impl {
+    ($($float:ty, $bits:ty, $signed:ty),*) => {
+        $(impl FloatKey for $float {
+            type Bits = $bits;
+
+            #[inline]
+            fn to_ordered_bits(self) -> $bits {
+                const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+
+                let bits = self.to_bits();
+                // All ones for negative values, 0 for positive ones.
+                let mask = ((bits as $signed) >> (<$bits>::BITS - 1)) as $bits;
+                bits ^ (mask | SIGN)
+            }
+
+            #[inline]
+            fn from_ordered_bits(bits: $bits) -> $float {
+                const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+
+                // The sign bit of the key is set for positive values.
+                let mask = ((!bits as $signed) >> (<$bits>::BITS - 1)) as $bits;
+                <$float>::from_bits(bits ^ (mask | SIGN))
+            }
+        })*
+    };
+}
+
+float_key_impl!(f32, u32, i32, f64, u64, i64);
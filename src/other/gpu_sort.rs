@@ -0,0 +1,331 @@
+//! Bitonic sort of `u32` and `f32` keys on the GPU via wgpu, to find the len at which the GPU
+//! beats the CPU sorts.
+//!
+//! The input is padded to the next power of two with `u32::MAX`, uploaded to a storage buffer,
+//! sorted with one compute dispatch per stage of the bitonic network and copied back. The
+//! network has *O*(*n* \* log(*n*)^2) comparators, but they all run in parallel. Every call
+//! reports the time of the three steps separately in [`GpuTimings`], for small inputs the transfers
+//! dominate. `BENCH_OTHER=gpu_sort` compares the total and the kernel time to the CPU sorts.
+
+use std::num::NonZeroU64;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::OnceCell;
+use wgpu::util::DeviceExt;
+
+use crate::other::float_key::FloatKey;
+
+const WORKGROUP_SIZE: u32 = 256;
+
+// Workgroups are spread over two dimensions, the limit per dimension is 65535.
+const MAX_WORKGROUPS_X: u32 = 32768;
+
+// Size of the (j, k) pair in the uniform buffer.
+const PARAMS_SIZE: u64 = 8;
+
+const SHADER: &str = r#"
+struct Params {
+    j: u32,
+    k: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let i = id.x + id.y * num_workgroups.x * 256u;
+    let l = i ^ params.j;
+
+    if (l > i && l < arrayLength(&data)) {
+        let a = data[i];
+        let b = data[l];
+        let ascending = (i & params.k) == 0u;
+
+        if ((a > b) == ascending) {
+            data[i] = b;
+            data[l] = a;
+        }
+    }
+}
+"#;
+
+/// Time spent in the steps of a single GPU sort.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpuTimings {
+    /// Copying the input, including the padding, to the GPU.
+    pub upload: Duration,
+    /// Running the bitonic network.
+    pub sort: Duration,
+    /// Copying the result back.
+    pub download: Duration,
+}
+
+impl GpuTimings {
+    pub fn total(&self) -> Duration {
+        self.upload + self.sort + self.download
+    }
+}
+
+pub struct GpuSorter {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    adapter_name: String,
+}
+
+/// Returns the sorter for the default high performance adapter, or `None` if there is no GPU.
+///
+/// Creating the device and compiling the shader takes a while, so this is done only once.
+pub fn sorter() -> Option<&'static GpuSorter> {
+    static SORTER: OnceCell<Option<GpuSorter>> = OnceCell::new();
+
+    SORTER
+        .get_or_init(|| pollster::block_on(GpuSorter::new()))
+        .as_ref()
+}
+
+impl GpuSorter {
+    async fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await?;
+
+        // The default limits cap storage buffers at 128 MiB, the adapter usually allows more.
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("gpu_sort"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: adapter.limits(),
+                },
+                None,
+            )
+            .await
+            .ok()?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("bitonic"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bitonic"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: NonZeroU64::new(PARAMS_SIZE),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("bitonic"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("bitonic"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "main",
+        });
+
+        Some(Self {
+            device,
+            queue,
+            pipeline,
+            bind_group_layout,
+            adapter_name: adapter.get_info().name,
+        })
+    }
+
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    /// Sorts `v` in ascending order.
+    pub fn sort_u32(&self, v: &mut [u32]) -> GpuTimings {
+        let mut timings = GpuTimings::default();
+        if v.len() < 2 {
+            return timings;
+        }
+
+        let padded_len = v.len().next_power_of_two();
+        let size = (padded_len * std::mem::size_of::<u32>()) as u64;
+        assert!(
+            size <= self.device.limits().max_storage_buffer_binding_size as u64,
+            "Input of len {} is too large for the storage buffers of this GPU",
+            v.len()
+        );
+
+        let start = Instant::now();
+        let mut padded = Vec::with_capacity(padded_len);
+        padded.extend_from_slice(v);
+        padded.resize(padded_len, u32::MAX);
+
+        let data = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("data"),
+                contents: bytemuck::cast_slice(&padded),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            });
+        self.queue.submit(None);
+        self.device.poll(wgpu::Maintain::Wait);
+        timings.upload = start.elapsed();
+
+        let start = Instant::now();
+        self.run_network(&data, padded_len as u32);
+        timings.sort = start.elapsed();
+
+        let start = Instant::now();
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(&data, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        self.device.poll(wgpu::Maintain::Wait);
+        {
+            let mapped = slice.get_mapped_range();
+            let sorted: &[u32] = bytemuck::cast_slice(&mapped);
+            // The padding is u32::MAX and sorted to the end.
+            v.copy_from_slice(&sorted[..v.len()]);
+        }
+        staging.unmap();
+        timings.download = start.elapsed();
+
+        timings
+    }
+
+    /// Sorts `v` in the order of [`f32::total_cmp`].
+    ///
+    /// The keys are mapped to `u32` with the same order on the CPU, the time for that is counted
+    /// as upload and download.
+    pub fn sort_f32(&self, v: &mut [f32]) -> GpuTimings {
+        let start = Instant::now();
+        let mut keys = v.iter().map(|&x| x.to_ordered_bits()).collect::<Vec<_>>();
+        let to_keys = start.elapsed();
+
+        let mut timings = self.sort_u32(&mut keys);
+
+        let start = Instant::now();
+        for (x, key) in v.iter_mut().zip(keys) {
+            *x = f32::from_ordered_bits(key);
+        }
+
+        timings.upload += to_keys;
+        timings.download += start.elapsed();
+        timings
+    }
+
+    fn run_network(&self, data: &wgpu::Buffer, len: u32) {
+        // One (j, k) pair per stage, each at an offset the device accepts for dynamic uniforms.
+        let stride =
+            (self.device.limits().min_uniform_buffer_offset_alignment as u64).max(PARAMS_SIZE);
+        let mut stages = Vec::new();
+        let mut k = 2;
+        while k <= len {
+            let mut j = k / 2;
+            while j > 0 {
+                stages.push((j, k));
+                j /= 2;
+            }
+            k *= 2;
+        }
+
+        let mut params = vec![0u8; stages.len() * stride as usize];
+        for (i, (j, k)) in stages.iter().enumerate() {
+            let offset = i * stride as usize;
+            params[offset..offset + 4].copy_from_slice(&j.to_ne_bytes());
+            params[offset + 4..offset + 8].copy_from_slice(&k.to_ne_bytes());
+        }
+
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("params"),
+                contents: &params,
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bitonic"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: data.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &params,
+                        offset: 0,
+                        size: NonZeroU64::new(PARAMS_SIZE),
+                    }),
+                },
+            ],
+        });
+
+        let workgroups = len.div_ceil(WORKGROUP_SIZE);
+        let workgroups_x = workgroups.min(MAX_WORKGROUPS_X);
+        let workgroups_y = workgroups.div_ceil(workgroups_x);
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            // WebGPU makes the writes of a dispatch visible to the following dispatches.
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("bitonic"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+
+            for i in 0..stages.len() {
+                pass.set_bind_group(0, &bind_group, &[(i as u64 * stride) as u32]);
+                pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+            }
+        }
+
+        self.queue.submit(Some(encoder.finish()));
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+}
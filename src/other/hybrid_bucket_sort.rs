@@ -0,0 +1,64 @@
+//! Experiment: does distributing the elements into buckets by the high bits of their keys, and
+//! then sorting each bucket with a comparison sort, beat sorting the whole slice?
+//!
+//! One counting pass builds a histogram of the next [`BUCKET_BITS`] bits below the prefix that all
+//! keys share, a second pass moves the elements into their buckets in a scratch copy, and every
+//! bucket is then sorted with [`slice::sort_unstable`]. With high entropy keys the buckets are
+//! `len / 256` long, which saves about 8 levels of comparisons at the cost of two linear passes and
+//! a copy. With low entropy the buckets are either few and large, or hold only equal keys, and the
+//! passes are pure overhead. `BENCH_OTHER=hybrid_bucket_sort` compares it to sorting directly, for
+//! i32 and u64 at several entropy levels.
+
+use crate::other::abbrev_keys::AbbrevKey;
+
+/// The number of buckets is `2^BUCKET_BITS`, the histogram fits into L1 cache.
+pub const BUCKET_BITS: u32 = 8;
+
+/// Below this len the histogram costs more than the comparisons it saves.
+const MIN_BUCKET_SORT_LEN: usize = 256;
+
+/// Sorts `v` by bucketing on the order-preserving [`AbbrevKey`] and sorting the buckets, not
+/// preserving the order of equal elements.
+pub fn sort_unstable<T: AbbrevKey + Copy>(v: &mut [T]) {
+    if v.len() < MIN_BUCKET_SORT_LEN {
+        v.sort_unstable();
+        return;
+    }
+
+    let (min, max) = v.iter().fold((u64::MAX, 0), |(min, max), elem| {
+        let key = elem.abbrev();
+        (min.min(key), max.max(key))
+    });
+
+    // All keys between min and max share the prefix of min and max, bucket by the bits below it.
+    let varying_bits = u64::BITS - (min ^ max).leading_zeros();
+    let shift = varying_bits.saturating_sub(BUCKET_BITS);
+    let bucket_mask = (1 << BUCKET_BITS) - 1;
+    let bucket_of = |elem: &T| ((elem.abbrev() >> shift) & bucket_mask) as usize;
+
+    let mut offsets = [0usize; 1 << BUCKET_BITS];
+    for elem in v.iter() {
+        offsets[bucket_of(elem)] += 1;
+    }
+
+    let mut bucket_start = 0;
+    for offset in offsets.iter_mut() {
+        let bucket_len = *offset;
+        *offset = bucket_start;
+        bucket_start += bucket_len;
+    }
+    let bucket_starts = offsets;
+
+    let scratch = v.to_vec();
+    for elem in scratch {
+        let bucket = bucket_of(&elem);
+        v[offsets[bucket]] = elem;
+        offsets[bucket] += 1;
+    }
+
+    // After the scatter `offsets` holds the end of every bucket. A bucket of equal keys is found
+    // to be sorted in one pass.
+    for (start, end) in bucket_starts.into_iter().zip(offsets) {
+        v[start..end].sort_unstable();
+    }
+}
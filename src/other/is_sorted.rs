@@ -0,0 +1,91 @@
+//! Checking whether a slice is already sorted, the pre-pass some sorts run before sorting and that
+//! callers can run themselves before calling a sort.
+//!
+//! The check costs `len - 1` comparisons on a sorted input and exits at the first pair out of
+//! order otherwise, so it is almost free on random inputs and wasted on inputs that are sorted up
+//! to their end. `BENCH_OTHER=is_sorted` measures the checks, and every sort of the registry behind
+//! [`is_sorted_simd`], to compare with the plain sorts.
+//!
+//! - `slice::is_sorted` is the baseline, one branch per pair.
+//! - [`is_sorted_by_chunked`] checks blocks of [`CHUNK_LEN`] pairs without branching and only exits
+//!   at the end of a block. For the integer types the loop over a block auto-vectorizes.
+//! - [`is_sorted_simd`] compares whole vectors with `std::simd` for the integer types, and falls
+//!   back to [`is_sorted_by_chunked`] for all others.
+
+use std::simd::prelude::*;
+
+/// The number of pairs [`is_sorted_by_chunked`] checks between two exits.
+pub const CHUNK_LEN: usize = 16;
+
+/// Whether no element of `v` is less than the one before it.
+pub fn is_sorted_by_chunked<T, F: FnMut(&T, &T) -> bool>(v: &[T], is_less: &mut F) -> bool {
+    let mut start = 0;
+    while start + CHUNK_LEN < v.len() {
+        // CHUNK_LEN + 1 elements hold CHUNK_LEN pairs, the last element starts the next block.
+        let chunk = &v[start..=start + CHUNK_LEN];
+        let out_of_order = chunk
+            .windows(2)
+            .fold(false, |acc, pair| acc | is_less(&pair[1], &pair[0]));
+
+        if out_of_order {
+            return false;
+        }
+
+        start += CHUNK_LEN;
+    }
+
+    v[start..]
+        .windows(2)
+        .all(|pair| !is_less(&pair[1], &pair[0]))
+}
+
+/// Whether no element of `v` is less than the one before it, see the module docs.
+pub fn is_sorted_simd<T: Ord>(v: &[T]) -> bool {
+    T::is_sorted_simd(v)
+}
+
+trait IsSortedSimd: Sized {
+    fn is_sorted_simd(v: &[Self]) -> bool;
+}
+
+impl<T: Ord> IsSortedSimd for T {
+    default fn is_sorted_simd(v: &[T]) -> bool {
+        is_sorted_by_chunked(v, &mut |a, b| a.lt(b))
+    }
+}
+
+macro_rules! is_sorted_simd_impl {
+    ($($t:ty, $lanes:expr),*) => {
+        $(impl IsSortedSimd for $t {
+            fn is_sorted_simd(v: &[Self]) -> bool {
+                const LANES: usize = $lanes;
+
+                // Each vector is compared with the one that starts an element later, two
+                // overlapping unaligned loads. Four vectors are checked between two exits.
+                let out_of_order_at = |start: usize| {
+                    let a = Simd::<$t, LANES>::from_slice(&v[start..]);
+                    let b = Simd::<$t, LANES>::from_slice(&v[start + 1..]);
+                    b.simd_lt(a)
+                };
+
+                let mut start = 0;
+                while start + 4 * LANES < v.len() {
+                    let out_of_order = out_of_order_at(start)
+                        | out_of_order_at(start + LANES)
+                        | out_of_order_at(start + 2 * LANES)
+                        | out_of_order_at(start + 3 * LANES);
+
+                    if out_of_order.any() {
+                        return false;
+                    }
+
+                    start += 4 * LANES;
+                }
+
+                v[start..].windows(2).all(|pair| pair[0] <= pair[1])
+            }
+        })*
+    };
+}
+
+is_sorted_simd_impl!(i32, 8, u32, 8, i64, 4, u64, 4);
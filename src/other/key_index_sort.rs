@@ -0,0 +1,111 @@
+//! Experiment, a specialization of `sort_unstable_by_key` for small `Copy` keys.
+//!
+//! `slice::sort_unstable_by_key` calls the key function twice per comparison and moves the
+//! elements themselves, which is expensive for large elements. If the key is small and `Copy`,
+//! [`sort_unstable_by_key`] instead extracts `(key, index)` pairs once, sorts those with ipnsort
+//! and moves every element once at the end by following the cycles of the permutation. It costs
+//! an allocation of `len` pairs, which std's unstable sort otherwise never does.
+//!
+//! [`sort_direct`] is the unspecialized version, ipnsort with the key function in the comparator,
+//! and [`sort_key_index`] always takes the specialized path. `BENCH_OTHER=key_index_sort` compares
+//! them on [`Record`]s of 64, 256 and 1024 bytes, to find out whether and from which element size
+//! std should add the specialization. The thresholds below are only a starting point.
+
+use std::mem;
+
+/// Keys larger than this are sorted directly, the pairs would be as expensive to move as the
+/// elements.
+pub const MAX_KEY_SIZE: usize = 16;
+
+/// Elements smaller than this are sorted directly.
+pub const MIN_ELEM_SIZE: usize = 64;
+
+/// Inputs up to this len are insertion sorted by ipnsort, not worth an allocation.
+pub const MIN_LEN: usize = 20;
+
+/// An element of `4 + 4 * N` bytes that is sorted by its `key`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record<const N: usize> {
+    pub key: i32,
+    pub payload: [u32; N],
+}
+
+impl<const N: usize> Record<N> {
+    /// Record with the sort key `key`, the payload identifies the record by `id`.
+    pub fn new(id: u32, key: i32) -> Self {
+        Self {
+            key,
+            payload: [id; N],
+        }
+    }
+}
+
+/// Sorts `v` by the key extracted with `f`, but might not preserve the order of equal elements.
+/// Takes the key index path if `K` is `Copy` and the sizes of `K`, `T` and `v` are within the
+/// thresholds, see [`uses_key_index`].
+pub fn sort_unstable_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    <K as KeyIndexSort>::sort_by_key(v, f);
+}
+
+/// Returns `true` if [`sort_unstable_by_key`] sorts `len` elements of `T` with a `Copy` key `K`
+/// by sorting `(key, index)` pairs.
+pub const fn uses_key_index<T, K>(len: usize) -> bool {
+    mem::size_of::<K>() <= MAX_KEY_SIZE
+        && mem::size_of::<T>() >= MIN_ELEM_SIZE
+        && len > MIN_LEN
+        && len <= u32::MAX as usize
+}
+
+/// Sorts `v` by the key extracted with `f`, calling `f` twice per comparison and moving the
+/// elements during the sort.
+pub fn sort_direct<T, K: Ord>(v: &mut [T], mut f: impl FnMut(&T) -> K) {
+    ipnsort::sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// Sorts `v` by the key extracted with `f`, calling `f` once per element and moving every element
+/// once. Panics if `v` is longer than `u32::MAX`.
+pub fn sort_key_index<T, K: Ord + Copy>(v: &mut [T], f: impl FnMut(&T) -> K) {
+    assert!(v.len() <= u32::MAX as usize);
+
+    // u32 indices keep the pairs of a u32 key at 8 bytes.
+    let mut keys = v.iter().map(f).zip(0u32..).collect::<Vec<(K, u32)>>();
+    ipnsort::sort_by(&mut keys, |a, b| a.0.cmp(&b.0));
+
+    apply_permutation(v, &mut keys);
+}
+
+trait KeyIndexSort: Ord + Sized {
+    fn sort_by_key<T, F: FnMut(&T) -> Self>(v: &mut [T], f: F);
+}
+
+impl<K: Ord> KeyIndexSort for K {
+    default fn sort_by_key<T, F: FnMut(&T) -> Self>(v: &mut [T], f: F) {
+        sort_direct(v, f);
+    }
+}
+
+impl<K: Ord + Copy> KeyIndexSort for K {
+    fn sort_by_key<T, F: FnMut(&T) -> Self>(v: &mut [T], f: F) {
+        if uses_key_index::<T, K>(v.len()) {
+            sort_key_index(v, f);
+        } else {
+            sort_direct(v, f);
+        }
+    }
+}
+
+/// Like `permute::apply_permutation`, with the indices stored next to the keys.
+fn apply_permutation<T, K>(v: &mut [T], keys: &mut [(K, u32)]) {
+    for start in 0..keys.len() {
+        let mut current = start;
+
+        while keys[current].1 as usize != start {
+            let next = keys[current].1 as usize;
+            v.swap(current, next);
+            keys[current].1 = current as u32;
+            current = next;
+        }
+
+        keys[current].1 = current as u32;
+    }
+}
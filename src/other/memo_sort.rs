@@ -0,0 +1,89 @@
+//! Experiment: does caching the results of an expensive comparison function ever pay off?
+//!
+//! The elements are not sorted directly, instead a permutation of their indices is sorted and
+//! applied at the end. That gives every element a stable identity for the whole sort, and the
+//! result of comparing two elements is cached in a hash map keyed on the index pair. Each element
+//! pair is compared with the user provided function at most once.
+//!
+//! A good comparison sort rarely compares the same pair twice, so the cache mostly costs a hash
+//! map insertion per comparison. [`MemoStats`] reports how many comparisons were actually answered
+//! by the cache, `BENCH_OTHER=memo_sort` compares the runtime against the plain sorts for a cheap
+//! and an expensive comparison function.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::other::permute::apply_permutation;
+
+/// Comparison counts of a single memoized sort.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// Number of comparisons performed by the underlying sort.
+    pub comparisons: usize,
+    /// Number of comparisons answered by the cache, without calling the comparison function.
+    pub hits: usize,
+}
+
+impl MemoStats {
+    pub fn hit_ratio(&self) -> f64 {
+        self.hits as f64 / self.comparisons.max(1) as f64
+    }
+}
+
+/// Stable sort of `v` with `compare`, via [`slice::sort_by`] on the indices.
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) -> MemoStats {
+    memo_sort_by(v, compare, |indices, compare| indices.sort_by(compare))
+}
+
+/// Unstable sort of `v` with `compare`, via [`slice::sort_unstable_by`] on the indices.
+pub fn sort_unstable_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) -> MemoStats {
+    memo_sort_by(v, compare, |indices, compare| {
+        indices.sort_unstable_by(compare)
+    })
+}
+
+fn memo_sort_by<T, F, S>(v: &mut [T], mut compare: F, sort_indices: S) -> MemoStats
+where
+    F: FnMut(&T, &T) -> Ordering,
+    S: FnOnce(&mut [usize], &mut dyn FnMut(&usize, &usize) -> Ordering),
+{
+    let mut stats = MemoStats::default();
+    if v.len() < 2 {
+        return stats;
+    }
+
+    // Only the result for the pair with the smaller index first is stored, the mirrored
+    // comparison is its reverse.
+    let mut cache = HashMap::<(usize, usize), Ordering>::new();
+    let mut indices = (0..v.len()).collect::<Vec<_>>();
+
+    {
+        let v = &*v;
+        sort_indices(&mut indices, &mut |&a, &b| {
+            stats.comparisons += 1;
+
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let ord = match cache.get(&(lo, hi)) {
+                Some(&ord) => {
+                    stats.hits += 1;
+                    ord
+                }
+                None => {
+                    let ord = compare(&v[lo], &v[hi]);
+                    cache.insert((lo, hi), ord);
+                    ord
+                }
+            };
+
+            if a <= b {
+                ord
+            } else {
+                ord.reverse()
+            }
+        });
+    }
+
+    apply_permutation(&mut indices, v);
+
+    stats
+}
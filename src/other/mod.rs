@@ -1,3 +1,63 @@
+pub mod branchless;
+
+// Experiment, sorts with a cache for the results of the comparison function.
+pub mod memo_sort;
+
+// Sorting many small slices.
+pub mod batch_sort;
+
+// Sorting at many sizes drawn from a histogram, like a real application.
+pub mod workload;
+
+// Sorting a VecDeque without making it contiguous.
+pub mod deque_sort;
+
+// Sorted output for an iterator, chunked sort and k-way merge with kmerge::heap.
+pub mod sorted_iter;
+
+// Checking whether a slice is already sorted, the pre-pass of adaptive sorts.
+pub mod is_sorted;
+
+// Applying a permutation, in place by following its cycles or through a buffer.
+pub mod permute;
+
+// Sorting structs by a single field, comparing direct, argsort and cached key strategies.
+pub mod row_sort;
+
+// Experiment, sort_unstable_by_key that sorts (key, index) pairs for small Copy keys.
+pub mod key_index_sort;
+
+// Experiment, stable sort by radix sorting (key, index) pairs of integer keys.
+pub mod radix_key_sort;
+
+// Sorting network with input independent memory accesses and branches.
+pub mod oblivious_sort;
+
+// Experiment, sorting wide keys by order-preserving u64 prefixes first.
+pub mod abbrev_keys;
+
+// Order-preserving integer keys of floats, for radix and SIMD sorts of floats.
+pub mod float_key;
+
+// Locale-aware string comparison, an expensive comparison function modeled after ICU collation.
+#[cfg(feature = "collation")]
+pub mod collation;
+
+// Sorting by composite keys, chained comparators vs encoded keys.
+pub mod multi_key;
+
+// Experiment, bucketing by the high bits of the keys before a comparison sort.
+pub mod hybrid_bucket_sort;
+
+// Experiment, sorting as much as fits into a time budget, leaving a sorted prefix.
+pub mod time_limited_sort;
+
+// The k smallest or largest elements, heap vs selection vs sorting everything.
+pub mod top_k;
+
+// Type-erased sorts on raw bytes and an element size, like qsort.
+pub mod type_erased;
+
 #[cfg(feature = "rust_radsort")]
 pub mod rust_radsort;
 
@@ -20,6 +80,14 @@ pub mod cpp_intel_avx512;
 #[cfg(feature = "singeli_singelisort")]
 pub mod singeli_singelisort;
 
+// Call C++ string sorts via FFI, passing &str as (pointer, len) pairs.
+#[cfg(feature = "cpp_str_sort")]
+pub mod cpp_str_sort;
+
+// Measure the FFI comparison callback overhead.
+#[cfg(feature = "ffi_calibration")]
+pub mod ffi_calibration;
+
 #[cfg(feature = "evolution")]
 pub mod sort_evolution;
 
@@ -32,5 +100,15 @@ pub mod partition_point;
 #[cfg(feature = "partition")]
 pub mod partition;
 
+#[cfg(feature = "rotate")]
+pub mod rotate;
+
 #[cfg(feature = "selection")]
 pub mod selection;
+
+// The stable merge sort the test oracles compare against instead of the std sorts.
+pub use sort_test_tools::reference_sort;
+
+// Bitonic sort on the GPU via wgpu.
+#[cfg(feature = "gpu")]
+pub mod gpu_sort;
@@ -0,0 +1,75 @@
+//! Sorting by composite keys, the order of SQL `ORDER BY a, b`.
+//!
+//! There are two ways to go about it:
+//!
+//! - Chaining the comparators, compare the first field and only compare the second if the first
+//!   is equal. Whether the second comparison runs depends on the data, which adds a hard to
+//!   predict branch to every comparison.
+//! - Encoding all fields into a single key whose plain order is the composite order, and sorting
+//!   by that. Fixed width fields fit into an integer, here `(f64, u64)` into a `u128`. Variable
+//!   width fields need a byte string, here `(u32, String)` into a `Vec<u8>` that is computed once
+//!   per element.
+//!
+//! `BENCH_OTHER=multi_key` compares them.
+
+use std::cmp::Ordering;
+
+use crate::other::float_key;
+
+/// Stable sort of `v` by `(u32, String)`, comparing field by field.
+pub fn sort_chained_u32_str(v: &mut [(u32, String)]) {
+    v.sort_by(cmp_u32_str);
+}
+
+/// Unstable sort of `v` by `(u32, String)`, comparing field by field.
+pub fn sort_chained_u32_str_unstable(v: &mut [(u32, String)]) {
+    v.sort_unstable_by(cmp_u32_str);
+}
+
+/// Stable sort of `v` by `(u32, String)`, via the keys of [`encode_u32_str`].
+pub fn sort_encoded_u32_str(v: &mut [(u32, String)]) {
+    v.sort_by_cached_key(encode_u32_str);
+}
+
+/// Stable sort of `v` by `(f64, u64)`, comparing field by field. The `f64` is ordered by
+/// [`f64::total_cmp`].
+pub fn sort_chained_f64_u64(v: &mut [(f64, u64)]) {
+    v.sort_by(cmp_f64_u64);
+}
+
+/// Unstable sort of `v` by `(f64, u64)`, comparing field by field.
+pub fn sort_chained_f64_u64_unstable(v: &mut [(f64, u64)]) {
+    v.sort_unstable_by(cmp_f64_u64);
+}
+
+/// Stable sort of `v` by `(f64, u64)`, via the keys of [`encode_f64_u64`].
+pub fn sort_encoded_f64_u64(v: &mut [(f64, u64)]) {
+    v.sort_by_key(encode_f64_u64);
+}
+
+/// Unstable sort of `v` by `(f64, u64)`, via the keys of [`encode_f64_u64`].
+pub fn sort_encoded_f64_u64_unstable(v: &mut [(f64, u64)]) {
+    v.sort_unstable_by_key(encode_f64_u64);
+}
+
+fn cmp_u32_str(a: &(u32, String), b: &(u32, String)) -> Ordering {
+    a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1))
+}
+
+fn cmp_f64_u64(a: &(f64, u64), b: &(f64, u64)) -> Ordering {
+    a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1))
+}
+
+/// Byte string whose order is the order of `(u32, String)`. The `u32` is big endian so that its
+/// bytes compare like the number, the string follows as is.
+pub fn encode_u32_str(key: &(u32, String)) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(4 + key.1.len());
+    encoded.extend_from_slice(&key.0.to_be_bytes());
+    encoded.extend_from_slice(key.1.as_bytes());
+    encoded
+}
+
+/// `u128` whose order is the order of `(f64, u64)` with [`f64::total_cmp`] for the `f64`.
+pub fn encode_f64_u64(key: &(f64, u64)) -> u128 {
+    ((float_key::to_ordered_bits(key.0) as u128) << 64) | key.1 as u128
+}
@@ -0,0 +1,104 @@
+//! Data-oblivious sorting with Batcher's odd-even mergesort, for cryptographic code that must not
+//! leak the input through its memory access or branch patterns.
+//!
+//! A sorting network is a fixed sequence of compare-exchange operations on pairs of positions,
+//! which only depends on the input len, see [`comparators`]. Every operation reads and writes both
+//! positions, whether they are swapped or not. [`sort`] additionally selects the result with a
+//! mask instead of a branch, for the integer types implementing [`ObliviousOrd`]. [`sort_by`]
+//! works for any type, but whether it branches on the comparison result is up to the compiler.
+//!
+//! The network has *O*(*n* \* log(*n*)^2) comparators, so this is much slower than the other sorts
+//! in this repository and only useful if the runtime must not depend on the values.
+
+use std::cmp::Ordering;
+use std::hint::black_box;
+use std::ptr;
+
+/// Integers that can be swapped into order without data dependent branches.
+pub trait ObliviousOrd: Copy {
+    /// Swaps `a` and `b` if `b < a`.
+    fn cond_swap(a: &mut Self, b: &mut Self);
+}
+
+macro_rules! impl_oblivious_ord {
+    ($($t:ty),*) => {$(
+        impl ObliviousOrd for $t {
+            #[inline(always)]
+            fn cond_swap(a: &mut Self, b: &mut Self) {
+                let (x, y) = (*a, *b);
+                // All bits set if the values have to be swapped. black_box keeps the compiler
+                // from turning the select back into a branch.
+                let mask = black_box(<$t>::wrapping_sub(0, (y < x) as $t));
+                let diff = (x ^ y) & mask;
+                *a = x ^ diff;
+                *b = y ^ diff;
+            }
+        }
+    )*};
+}
+
+impl_oblivious_ord!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Sorts `v` with the same memory accesses and branches for all inputs of the same len.
+pub fn sort<T: ObliviousOrd>(v: &mut [T]) {
+    for_each_comparator(v.len(), |i, j| {
+        let (left, right) = v.split_at_mut(j);
+        T::cond_swap(&mut left[i], &mut right[0]);
+    });
+}
+
+/// Sorts `v` with `compare`, with the same memory accesses for all inputs of the same len.
+///
+/// This sort is not stable.
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    let ptr = v.as_mut_ptr();
+
+    for_each_comparator(v.len(), |i, j| {
+        // SAFETY: `for_each_comparator` only yields `i < j < v.len()`. Both elements are read
+        // after the comparison and written back before anything else can observe `v`, so a panic
+        // in `compare` leaves `v` intact and no element is duplicated or lost.
+        unsafe {
+            let a = ptr.add(i);
+            let b = ptr.add(j);
+            let swap = compare(&*b, &*a) == Ordering::Less;
+
+            let x = ptr::read(a);
+            let y = ptr::read(b);
+            let (lo, hi) = if swap { (y, x) } else { (x, y) };
+            ptr::write(a, lo);
+            ptr::write(b, hi);
+        }
+    });
+}
+
+/// Returns the compare-exchange operations of the network for `len` elements, in the order they
+/// are applied. Each pair `(i, j)` has `i < j`, and puts the smaller value at `i`.
+pub fn comparators(len: usize) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for_each_comparator(len, |i, j| pairs.push((i, j)));
+
+    pairs
+}
+
+/// Batcher's odd-even mergesort, generalized to any `len` by leaving out the comparators that
+/// would reach beyond the end. See Knuth, TAOCP Vol. 3, Section 5.3.4.
+fn for_each_comparator(len: usize, mut f: impl FnMut(usize, usize)) {
+    let mut p = 1;
+    while p < len {
+        let mut k = p;
+        while k > 0 {
+            let mut j = k % p;
+            while j + k < len {
+                for i in 0..k.min(len - j - k) {
+                    // Only compare within the same merge of two sorted blocks of len p.
+                    if (i + j) / (p * 2) == (i + j + k) / (p * 2) {
+                        f(i + j, i + j + k);
+                    }
+                }
+                j += k * 2;
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+}
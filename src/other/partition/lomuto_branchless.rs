@@ -1,30 +1,8 @@
-use core::mem::{self, ManuallyDrop};
-use core::ptr;
+use core::mem;
 
-partition_impl!("lomuto_branchless");
-
-/// Swap two values in array pointed to by a and b if b is less than a.
-#[inline(always)]
-pub unsafe fn branchless_swap<T>(x: *mut T, y: *mut T, should_swap: bool) {
-    // SAFETY: the caller must guarantee that `x` and `y` are valid for writes and properly aligned,
-    // and part of the same allocation.
-
-    // This is a branchless version of swap if.
-    // The equivalent code with a branch would be:
-    //
-    // if should_swap {
-    //     ptr::swap(x, y);
-    // }
-
-    // The goal is to generate cmov instructions here.
-    let x_swap = if should_swap { y } else { x };
-    let y_swap = if should_swap { x } else { y };
+use crate::other::branchless::branchless_swap;
 
-    let y_swap_copy = ManuallyDrop::new(ptr::read(y_swap));
-
-    ptr::copy(x_swap, x, 1);
-    ptr::copy_nonoverlapping(&*y_swap_copy, y, 1);
-}
+partition_impl!("lomuto_branchless");
 
 fn partition<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize {
     let len = v.len();
@@ -109,3 +109,5 @@ pub mod lomuto_nanosort;
 pub mod small_partition;
 pub mod stable_2side_fill;
 pub mod sum_is_less;
+pub mod three_way_bentley_mcilroy;
+pub mod three_way_dijkstra;
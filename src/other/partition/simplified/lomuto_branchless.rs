@@ -1,29 +1,4 @@
-use core::mem::ManuallyDrop;
-use core::ptr;
-
-/// Swap two values pointed to by `x` and `y` if `should_swap` is true.
-#[inline(always)]
-pub unsafe fn branchless_swap<T>(x: *mut T, y: *mut T, should_swap: bool) {
-    // This is a branchless version of swap if.
-    // The equivalent code with a branch would be:
-    //
-    // if should_swap {
-    //     ptr::swap(x, y);
-    // }
-
-    // SAFETY: the caller must guarantee that `x` and `y` are valid for writes
-    // and properly aligned.
-    unsafe {
-        // The goal is to generate cmov instructions here.
-        let x_swap = if should_swap { y } else { x };
-        let y_swap = if should_swap { x } else { y };
-
-        let y_swap_copy = ManuallyDrop::new(ptr::read(y_swap));
-
-        ptr::copy(x_swap, x, 1);
-        ptr::copy_nonoverlapping(&*y_swap_copy, y, 1);
-    }
-}
+use crate::other::branchless::branchless_swap;
 
 fn partition<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize {
     let len = v.len();
@@ -0,0 +1,67 @@
+//! Three-way partition of Bentley and McIlroy, "Engineering a Sort Function" (1993). A Hoare
+//! partition that swaps the elements equal to the pivot to the two ends as it finds them, and
+//! moves them into the middle at the end. Unlike the Dutch national flag algorithm, elements that
+//! are not equal to the pivot are swapped at most once, which makes it cheaper on inputs with few
+//! duplicates.
+
+partition_impl!("three_way_bentley_mcilroy");
+
+fn partition<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize {
+    partition_three_way(v, pivot, is_less).0
+}
+
+/// Partitions `v` into the elements less than, equal to and greater than `pivot`, and returns the
+/// start of the equal and of the greater elements.
+pub fn partition_three_way<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    pivot: &T,
+    is_less: &mut F,
+) -> (usize, usize) {
+    let len = v.len();
+
+    // v[..a] == pivot, v[a..b] < pivot, v[b..c] unknown, v[c..d] > pivot, v[d..] == pivot.
+    let mut a = 0;
+    let mut b = 0;
+    let mut c = len;
+    let mut d = len;
+
+    loop {
+        while b < c && !is_less(pivot, &v[b]) {
+            if !is_less(&v[b], pivot) {
+                v.swap(a, b);
+                a += 1;
+            }
+            b += 1;
+        }
+
+        while b < c && !is_less(&v[c - 1], pivot) {
+            if !is_less(pivot, &v[c - 1]) {
+                d -= 1;
+                v.swap(c - 1, d);
+            }
+            c -= 1;
+        }
+
+        if b == c {
+            break;
+        }
+
+        v.swap(b, c - 1);
+        b += 1;
+        c -= 1;
+    }
+
+    // Move the equal elements from the ends to the middle.
+    let left_eq = a.min(b - a);
+    swap_ranges(v, 0, b - left_eq, left_eq);
+    let right_eq = (len - d).min(d - c);
+    swap_ranges(v, c, len - right_eq, right_eq);
+
+    (b - a, len - (d - c))
+}
+
+fn swap_ranges<T>(v: &mut [T], a: usize, b: usize, count: usize) {
+    for i in 0..count {
+        v.swap(a + i, b + i);
+    }
+}
@@ -0,0 +1,37 @@
+//! Three-way partition, the Dutch national flag algorithm of Dijkstra. Elements equal to the
+//! pivot end up between the smaller and the larger ones, so a quicksort can skip them, at the cost
+//! of a second comparison for every element that is not less than the pivot.
+
+partition_impl!("three_way_dijkstra");
+
+fn partition<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize {
+    partition_three_way(v, pivot, is_less).0
+}
+
+/// Partitions `v` into the elements less than, equal to and greater than `pivot`, and returns the
+/// start of the equal and of the greater elements.
+pub fn partition_three_way<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    pivot: &T,
+    is_less: &mut F,
+) -> (usize, usize) {
+    // v[..lt] < pivot, v[lt..i] == pivot, v[i..gt] unknown, v[gt..] > pivot.
+    let mut lt = 0;
+    let mut i = 0;
+    let mut gt = v.len();
+
+    while i < gt {
+        if is_less(&v[i], pivot) {
+            v.swap(lt, i);
+            lt += 1;
+            i += 1;
+        } else if is_less(pivot, &v[i]) {
+            gt -= 1;
+            v.swap(i, gt);
+        } else {
+            i += 1;
+        }
+    }
+
+    (lt, gt)
+}
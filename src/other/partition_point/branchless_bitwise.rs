@@ -23,5 +23,5 @@ where
         b = b.wrapping_add((should_add as usize) << k);
     }
 
-    b + 1
+    b.wrapping_add(1)
 }
@@ -37,5 +37,5 @@ where
     }
 
     // [i+1, i+1+n) contains our result, and n == 1.
-    i + 1
+    i.wrapping_add(1)
 }
@@ -0,0 +1,43 @@
+partition_point_impl!("branchless_prefetch");
+
+pub fn partition_point<T, P>(arr: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    // Same search as branchless_clean. The next midpoint depends on the outcome of the current
+    // comparison, so both candidates are prefetched before it, which hides the cache misses of
+    // arrays that don't fit into the cache at the cost of twice the memory traffic.
+    let mut n = arr.len() + 1;
+    let mut i = (-1isize) as usize;
+    let base = arr.as_ptr();
+
+    while n > 1 {
+        // For the SAFETY argument see branchless_clean.
+        let mid = i.wrapping_add(n / 2);
+        unsafe { core::intrinsics::assume(mid < arr.len()) }
+
+        let next_half = (n - n / 2) / 2;
+        prefetch(base.wrapping_add(i.wrapping_add(next_half)));
+        prefetch(base.wrapping_add(mid.wrapping_add(next_half)));
+
+        i = core::hint::black_box(if pred(&arr[mid]) { mid } else { i });
+        n -= n / 2;
+    }
+
+    i.wrapping_add(1)
+}
+
+/// Prefetching is a hint, it never faults, even for addresses outside of `arr`. No-op on targets
+/// other than x86-64.
+#[inline(always)]
+fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: _mm_prefetch does not access memory in the sense of the memory model.
+    unsafe {
+        use core::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr.cast());
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
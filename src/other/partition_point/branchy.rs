@@ -0,0 +1,22 @@
+partition_point_impl!("branchy");
+
+pub fn partition_point<T, P>(arr: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    // Textbook binary search, the branch on the predicate is as unpredictable as the searched
+    // value. Unlike std it does not stop early on equality, which a partition point can't do.
+    let mut lo = 0;
+    let mut hi = arr.len();
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&arr[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
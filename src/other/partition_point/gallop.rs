@@ -0,0 +1,10 @@
+partition_point_impl!("gallop");
+
+pub fn partition_point<T, P>(arr: &[T], pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    // The exponential search of the galloping merge, twice the comparisons of a binary search for
+    // a partition point in the middle, but only log2 of the distance from the start.
+    crate::kmerge::galloping::gallop(arr, pred)
+}
@@ -0,0 +1,51 @@
+//! Merge path search, the split of the stable merge of `a` and `b` after `diagonal` elements.
+//!
+//! Parallel and branchless merges split their output into independent parts by searching along
+//! the diagonals of the merge matrix, each search is a partition point over the indices of `a`.
+//! Returns the number of elements of `a` in the first `diagonal` elements of the merge, ties go to
+//! `a`. Panics if `diagonal > a.len() + b.len()`.
+
+/// Branchy binary search over the diagonal.
+pub fn merge_path_branchy<T: Ord>(a: &[T], b: &[T], diagonal: usize) -> usize {
+    let (mut lo, mut hi) = search_range(a, b, diagonal);
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        // a[mid] goes before the last element of b taken with mid elements of a, so more than mid
+        // elements of a are taken.
+        if a[mid] <= b[diagonal - mid - 1] {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+/// Binary search over the diagonal in the style of branchless_clean, the same number of
+/// iterations for every diagonal of the same length.
+pub fn merge_path_branchless<T: Ord>(a: &[T], b: &[T], diagonal: usize) -> usize {
+    let (lo, hi) = search_range(a, b, diagonal);
+
+    // There are hi - lo + 1 possible outcomes, [i+1, i+1+n) contains the result.
+    let mut n = hi - lo + 1;
+    let mut i = lo.wrapping_sub(1);
+
+    while n > 1 {
+        let mid = i.wrapping_add(n / 2);
+        let takes_more_a = a[mid] <= b[diagonal - mid - 1];
+        i = core::hint::black_box(if takes_more_a { mid } else { i });
+        n -= n / 2;
+    }
+
+    i.wrapping_add(1)
+}
+
+/// Range of the possible results, at least `diagonal - b.len()` and at most `a.len()` elements of
+/// `a`.
+fn search_range<T>(a: &[T], b: &[T], diagonal: usize) -> (usize, usize) {
+    assert!(diagonal <= a.len() + b.len(), "diagonal out of bounds");
+
+    (diagonal.saturating_sub(b.len()), diagonal.min(a.len()))
+}
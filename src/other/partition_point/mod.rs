@@ -40,4 +40,9 @@ macro_rules! partition_point_impl {
 
 pub mod branchless_bitwise;
 pub mod branchless_clean;
+pub mod branchless_prefetch;
+pub mod branchy;
+pub mod gallop;
 pub mod std;
+
+pub mod merge_path;
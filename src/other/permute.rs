@@ -0,0 +1,82 @@
+//! Applying a permutation to a slice, the last step of argsort and of every sort that orders
+//! `(key, index)` pairs instead of the elements, such as `row_sort`, `memo_sort` and
+//! `radix_key_sort`.
+//!
+//! [`apply_permutation`] follows the cycles of the permutation and swaps the elements in place,
+//! `len` swaps at most and no allocation, but the reads jump around `v` in the order of the
+//! cycles. [`apply_permutation_out_of_place`] gathers the elements into a buffer in output order
+//! and copies it back, one random read and one sequential write per element, at the cost of a
+//! buffer of `len` elements. `BENCH_OTHER=permute` compares them for the permutations that sort the
+//! patterns.
+
+use std::ptr;
+
+/// Moves `v[indices[i]]` to `v[i]` for every `i`, by following the cycles of the permutation.
+/// `indices` is left as identity permutation.
+///
+/// # Panics
+///
+/// Panics if `indices` is not a permutation of `0..v.len()`, `v` is then left in an unspecified
+/// order.
+pub fn apply_permutation<T>(indices: &mut [usize], v: &mut [T]) {
+    assert_eq!(indices.len(), v.len(), "indices and v differ in len");
+
+    for start in 0..indices.len() {
+        let mut current = start;
+
+        while indices[current] != start {
+            let next = indices[current];
+            // Only a position that was already placed points to itself here, the walk would never
+            // get back to `start`.
+            assert_ne!(next, current, "indices is not a permutation");
+
+            v.swap(current, next);
+            indices[current] = current;
+            current = next;
+        }
+
+        indices[current] = current;
+    }
+}
+
+/// Moves `v[indices[i]]` to `v[i]` for every `i`, by gathering the elements into a buffer in their
+/// new order and copying it back.
+///
+/// # Panics
+///
+/// Panics if `indices` is not a permutation of `0..v.len()`, before `v` is changed.
+pub fn apply_permutation_out_of_place<T>(indices: &[usize], v: &mut [T]) {
+    assert!(
+        is_permutation(indices, v.len()),
+        "indices is not a permutation of 0..{}",
+        v.len()
+    );
+
+    let len = v.len();
+    let mut buf = Vec::<T>::with_capacity(len);
+    let src = v.as_mut_ptr();
+    let dst = buf.as_mut_ptr();
+
+    // SAFETY: Every index is in bounds and appears exactly once, so every element of `v` is copied
+    // to `buf` exactly once and all of `buf[..len]` is initialized. Copying it back over `v`
+    // restores `v` to `len` distinct, initialized elements. Nothing can panic in between, and the
+    // len of `buf` stays 0, so no element is dropped or duplicated.
+    unsafe {
+        for (i, &index) in indices.iter().enumerate() {
+            ptr::copy_nonoverlapping(src.add(index), dst.add(i), 1);
+        }
+        ptr::copy_nonoverlapping(dst, src, len);
+    }
+}
+
+/// Whether `indices` contains every index of `0..len` exactly once.
+pub fn is_permutation(indices: &[usize], len: usize) -> bool {
+    if indices.len() != len {
+        return false;
+    }
+
+    let mut seen = vec![false; len];
+    indices
+        .iter()
+        .all(|&index| index < len && !std::mem::replace(&mut seen[index], true))
+}
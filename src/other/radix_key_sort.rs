@@ -0,0 +1,111 @@
+//! Experiment: stable sort by an integer key, by radix sorting `(key, index)` pairs and moving
+//! every element once at the end.
+//!
+//! This is how databases sort rows by an integer column. The keys are extracted once, a least
+//! significant digit radix sort orders the pairs in one pass per key byte, without comparisons,
+//! and the elements are permuted by the sorted indices. Bytes that are the same in all keys, such
+//! as the high bytes of small values, are skipped. LSD radix sort is stable, so equal keys keep
+//! the order of their elements.
+//!
+//! The cost is linear in the len but high per element, two histogram and scatter passes over 16
+//! byte pairs per key byte plus the permutation, and `2 * len` pairs of memory. Against a
+//! comparison sort that moves the elements it wins for large elements and long inputs.
+//! `BENCH_OTHER=row_sort` compares it with the other ways to sort rows by their `i32` key, the len
+//! at which it overtakes `rust_std_stable` is the crossover to look for.
+
+use crate::other::permute::apply_permutation;
+
+/// Inputs up to this len are sorted by comparisons, the histograms cost more than they save.
+pub const MIN_RADIX_LEN: usize = 64;
+
+/// Integer keys with an order-preserving mapping to `u64`.
+pub trait RadixKey: Copy + Ord {
+    /// Must be greater for a greater key and equal for an equal one.
+    fn radix_key(self) -> u64;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                fn radix_key(self) -> u64 {
+                    self as u64
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $unsigned:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                fn radix_key(self) -> u64 {
+                    // Flipping the sign bit keeps the order of negative values.
+                    ((self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1))) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+/// Stable sort of `v` by the key extracted with `f`, calling `f` once per element.
+pub fn sort_by_key<T, K: RadixKey>(v: &mut [T], mut f: impl FnMut(&T) -> K) {
+    if v.len() <= MIN_RADIX_LEN {
+        v.sort_by_key(f);
+        return;
+    }
+
+    let pairs = v
+        .iter()
+        .enumerate()
+        .map(|(i, elem)| (f(elem).radix_key(), i))
+        .collect::<Vec<_>>();
+
+    let mut indices = radix_sort_pairs(pairs)
+        .into_iter()
+        .map(|(_, i)| i)
+        .collect::<Vec<_>>();
+    apply_permutation(&mut indices, v);
+}
+
+/// LSD radix sort of `pairs` by the key, one pass per byte that is not the same in all keys.
+fn radix_sort_pairs(mut pairs: Vec<(u64, usize)>) -> Vec<(u64, usize)> {
+    const BYTES: usize = u64::BITS as usize / 8;
+
+    // The histograms of all bytes in a single pass over the keys.
+    let mut counts = [[0usize; 256]; BYTES];
+    for &(key, _) in &pairs {
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[(key >> (byte * 8)) as u8 as usize] += 1;
+        }
+    }
+
+    let mut scratch = pairs.clone();
+    for (byte, count) in counts.iter().enumerate() {
+        // All keys have the same value in this byte, the pass would not change the order.
+        if count.contains(&pairs.len()) {
+            continue;
+        }
+
+        let mut offsets = [0usize; 256];
+        let mut offset = 0;
+        for (bucket_offset, &bucket_count) in offsets.iter_mut().zip(count) {
+            *bucket_offset = offset;
+            offset += bucket_count;
+        }
+
+        for &pair in &pairs {
+            let bucket = (pair.0 >> (byte * 8)) as u8 as usize;
+            scratch[offsets[bucket]] = pair;
+            offsets[bucket] += 1;
+        }
+
+        std::mem::swap(&mut pairs, &mut scratch);
+    }
+
+    pairs
+}
@@ -0,0 +1,23 @@
+rotate_impl!("rotate_gries_mills");
+
+pub fn rotate_left<T>(v: &mut [T], mid: usize) {
+    // The blocks left to rotate are v[start..start + left] and the `right` elements after it.
+    let mut start = 0;
+    let mut left = mid;
+    let mut right = v.len() - mid;
+
+    while left > 0 && right > 0 {
+        if left <= right {
+            // A B1 B2 -> B1 A B2, with |B1| == |A|. B1 is in place, A B2 remain.
+            let (a, b) = v[start..].split_at_mut(left);
+            a.swap_with_slice(&mut b[..left]);
+            start += left;
+            right -= left;
+        } else {
+            // A1 A2 B -> A1 B A2, with |A2| == |B|. A2 is in place, A1 B remain.
+            let (a, b) = v[start..start + left + right].split_at_mut(left);
+            a[left - right..].swap_with_slice(b);
+            left -= right;
+        }
+    }
+}
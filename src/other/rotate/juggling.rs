@@ -0,0 +1,48 @@
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+rotate_impl!("rotate_juggling");
+
+pub fn rotate_left<T>(v: &mut [T], mid: usize) {
+    let len = v.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+
+    // Element `i` comes from `(i + mid) % len`, which splits the indices into `gcd(len, mid)`
+    // cycles. Each cycle starts at one of the first `gcd` indices.
+    let cycles = gcd(len, mid);
+    let v_ptr = v.as_mut_ptr();
+
+    for cycle_start in 0..cycles {
+        // SAFETY: All indices are `< len`. The cycle leaves a hole that is filled by the last copy,
+        // and nothing in between can panic.
+        unsafe {
+            let tmp = ManuallyDrop::new(ptr::read(v_ptr.add(cycle_start)));
+
+            let mut hole = cycle_start;
+            loop {
+                let mut next = hole + mid;
+                if next >= len {
+                    next -= len;
+                }
+                if next == cycle_start {
+                    break;
+                }
+
+                ptr::copy_nonoverlapping(v_ptr.add(next), v_ptr.add(hole), 1);
+                hole = next;
+            }
+
+            ptr::copy_nonoverlapping(&*tmp, v_ptr.add(hole), 1);
+        }
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
@@ -0,0 +1,47 @@
+//! Rotation algorithms, the building block of in-place merges. A merge without a buffer of the
+//! size of the runs moves blocks past each other by rotating them, the rotation speed bounds that
+//! of the whole sort.
+//!
+//! All of them rotate in place without allocating and never compare elements, they can't panic:
+//!
+//! - `triple_reversal`, reverses both blocks and then the whole slice. Every element is moved
+//!   twice, but all accesses are sequential.
+//! - `gries_mills`, swaps the shorter block with the adjacent end of the longer one, which puts
+//!   one block in its final place, and continues with the rest.
+//! - `juggling`, moves every element directly to its final place by following the `gcd(len, mid)`
+//!   cycles of the rotation. The fewest moves, but strided accesses.
+//! - `piston`, like `gries_mills` but swaps the shorter block with the far end of the longer one,
+//!   so the shorter block stays at the same size and moves through the longer one.
+//! - `std`, `slice::rotate_left`, which uses a stack buffer for short blocks.
+
+pub trait Rotate {
+    fn name() -> String;
+
+    /// Rotates `v` so that `v[mid]` becomes the first element, same as `slice::rotate_left`.
+    /// Panics if `mid > v.len()`.
+    fn rotate_left<T>(v: &mut [T], mid: usize);
+}
+
+macro_rules! rotate_impl {
+    ($name:expr) => {
+        pub struct RotateImpl;
+
+        impl crate::other::rotate::Rotate for RotateImpl {
+            fn name() -> String {
+                $name.into()
+            }
+
+            #[inline]
+            fn rotate_left<T>(v: &mut [T], mid: usize) {
+                assert!(mid <= v.len());
+                rotate_left(v, mid);
+            }
+        }
+    };
+}
+
+pub mod gries_mills;
+pub mod juggling;
+pub mod piston;
+pub mod std;
+pub mod triple_reversal;
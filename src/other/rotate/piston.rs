@@ -0,0 +1,28 @@
+rotate_impl!("rotate_piston");
+
+pub fn rotate_left<T>(v: &mut [T], mid: usize) {
+    // The blocks left to rotate are v[start..start + left] and v[start + left..end].
+    let mut start = 0;
+    let mut end = v.len();
+    let mut left = mid;
+
+    loop {
+        let right = end - start - left;
+        if left == 0 || right == 0 {
+            break;
+        }
+
+        let (a, b) = v[start..end].split_at_mut(left);
+        if left <= right {
+            // A B1 B2 -> B2 B1 A, with |B2| == |A|. A is in place, B2 B1 remain, the left block
+            // keeps its len.
+            a.swap_with_slice(&mut b[right - left..]);
+            end -= left;
+        } else {
+            // A1 A2 B -> B A2 A1, with |A1| == |B|. B is in place, A2 A1 remain.
+            a[..right].swap_with_slice(b);
+            start += right;
+            left -= right;
+        }
+    }
+}
@@ -0,0 +1,5 @@
+rotate_impl!("rotate_std");
+
+pub fn rotate_left<T>(v: &mut [T], mid: usize) {
+    v.rotate_left(mid);
+}
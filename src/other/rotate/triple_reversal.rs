@@ -0,0 +1,8 @@
+rotate_impl!("rotate_triple_reversal");
+
+pub fn rotate_left<T>(v: &mut [T], mid: usize) {
+    // (A^r B^r)^r = B A
+    v[..mid].reverse();
+    v[mid..].reverse();
+    v.reverse();
+}
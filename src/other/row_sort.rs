@@ -0,0 +1,66 @@
+//! Sorting rows, structs with many fields, by a single projected field.
+//!
+//! Analytics workloads rarely sort plain integers, they sort records by one of their columns. With
+//! a 64 byte [`Row`] every move in the sort copies a cache line, while the comparison only reads 4
+//! bytes of it. There are three ways to go about it, all stable:
+//!
+//! - [`sort_direct`] sorts the rows themselves, comparing the projected keys.
+//! - [`sort_argsort`] sorts the row indices by the projected key and moves every row once at the
+//!   end. The comparisons go through the indices, which costs an indirection into the rows.
+//! - [`sort_key_cached`] extracts `(key, index)` pairs once, sorts those and then moves the rows,
+//!   the same approach as [`slice::sort_by_cached_key`]. The sort only moves small pairs and
+//!   doesn't touch the rows, in exchange for the memory of the pairs.
+//!
+//! `BENCH_OTHER=row_sort` compares them.
+
+use crate::other::permute::apply_permutation;
+
+/// A typical record of an analytics table, 64 bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub id: u64,
+    pub key: i32,
+    pub category: u32,
+    pub price: f64,
+    pub quantity: u64,
+    pub timestamp: u64,
+    pub payload: [u8; 24],
+}
+
+impl Row {
+    /// Row with the sort key `key`, all other fields are derived from `id`.
+    pub fn new(id: u64, key: i32) -> Self {
+        Self {
+            id,
+            key,
+            category: (id % 97) as u32,
+            price: id as f64 * 0.25,
+            quantity: id.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 48,
+            timestamp: 1_600_000_000 + id,
+            payload: [id as u8; 24],
+        }
+    }
+}
+
+/// Sorts `v` by the key extracted with `f`, moving the elements during the sort.
+pub fn sort_direct<T, K: Ord>(v: &mut [T], f: impl FnMut(&T) -> K) {
+    v.sort_by_key(f);
+}
+
+/// Sorts `v` by the key extracted with `f`, by sorting the indices and moving every element once.
+pub fn sort_argsort<T, K: Ord>(v: &mut [T], mut f: impl FnMut(&T) -> K) {
+    let mut indices = (0..v.len()).collect::<Vec<_>>();
+    indices.sort_by_key(|&i| f(&v[i]));
+
+    apply_permutation(&mut indices, v);
+}
+
+/// Sorts `v` by the key extracted with `f`, calling `f` once per element.
+pub fn sort_key_cached<T, K: Ord>(v: &mut [T], f: impl FnMut(&T) -> K) {
+    // The index makes every pair unique, so the unstable sort gives a stable result.
+    let mut keys = v.iter().map(f).zip(0..).collect::<Vec<(K, usize)>>();
+    keys.sort_unstable();
+
+    let mut indices = keys.into_iter().map(|(_, i)| i).collect::<Vec<_>>();
+    apply_permutation(&mut indices, v);
+}
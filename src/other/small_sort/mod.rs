@@ -1,8 +1,17 @@
 pub mod sort4_stable_orson;
 pub mod sort4_unstable_branchy;
 pub mod sort4_unstable_cmp_swap;
+pub mod sort4_unstable_neon;
 pub mod sort4_unstable_ptr_select;
 
+pub mod sort8_unstable_neon;
+
 pub mod sort10_unstable_cmp_swaps;
 pub mod sort10_unstable_experimental;
 pub mod sort10_unstable_ptr_select;
+
+// Candidate small-sorts for hybrid sorts behind a common trait.
+pub mod zoo;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
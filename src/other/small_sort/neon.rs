@@ -0,0 +1,65 @@
+//! NEON building blocks for the `*_neon` small-sorts. A vector holds four `i32`, every sorting
+//! network layer is one `min` and one `max` of the vector and a shuffled copy of itself, followed
+//! by a shuffle that picks the lanes that take the min and the ones that take the max.
+
+use core::arch::aarch64::*;
+
+/// Compare-exchanges lanes (0, 1) and (2, 3).
+#[inline(always)]
+fn minmax_pairs(v: int32x4_t) -> int32x4_t {
+    let swapped = vrev64q_s32(v);
+    vtrn1q_s32(vminq_s32(v, swapped), vmaxq_s32(v, swapped))
+}
+
+/// Compare-exchanges lanes (0, 2) and (1, 3).
+#[inline(always)]
+fn minmax_halves(v: int32x4_t) -> int32x4_t {
+    let swapped = vextq_s32::<2>(v, v);
+    vcombine_s32(
+        vget_low_s32(vminq_s32(v, swapped)),
+        vget_low_s32(vmaxq_s32(v, swapped)),
+    )
+}
+
+/// Compare-exchanges lanes (1, 2).
+#[inline(always)]
+fn minmax_middle(v: int32x4_t) -> int32x4_t {
+    let rotated = vextq_s32::<1>(v, v);
+    let min = vminq_s32(v, rotated);
+    let max = vmaxq_s32(v, rotated);
+    vcopyq_laneq_s32::<2, 1>(vcopyq_laneq_s32::<1, 1>(v, min), max)
+}
+
+/// Sorts the lanes of `v`, the same network as the scalar path of `sort4_unstable_neon`.
+#[inline(always)]
+pub fn sort4(v: int32x4_t) -> int32x4_t {
+    minmax_middle(minmax_halves(minmax_pairs(v)))
+}
+
+/// Merges the sorted `a` and `b` into the sorted lanes of both, the smaller four in the first one.
+/// A bitonic merge, `b` is reversed so that `a` followed by `b` rises and then falls.
+#[inline(always)]
+pub fn merge4(a: int32x4_t, b: int32x4_t) -> (int32x4_t, int32x4_t) {
+    let b_pairs_reversed = vrev64q_s32(b);
+    let b_reversed = vextq_s32::<2>(b_pairs_reversed, b_pairs_reversed);
+
+    let lo = vminq_s32(a, b_reversed);
+    let hi = vmaxq_s32(a, b_reversed);
+
+    (
+        minmax_pairs(minmax_halves(lo)),
+        minmax_pairs(minmax_halves(hi)),
+    )
+}
+
+/// SAFETY: `ptr` must be valid for 4 reads.
+#[inline(always)]
+pub unsafe fn load(ptr: *const i32) -> int32x4_t {
+    unsafe { vld1q_s32(ptr) }
+}
+
+/// SAFETY: `ptr` must be valid for 4 writes.
+#[inline(always)]
+pub unsafe fn store(ptr: *mut i32, v: int32x4_t) {
+    unsafe { vst1q_s32(ptr, v) }
+}
@@ -1,57 +1,8 @@
 use core::cmp::Ordering;
-use core::mem::MaybeUninit;
-use core::ptr;
 
-sort_impl!("sort4_unstable_cmp_swap");
-
-/// Swap two values in array pointed to by a_ptr and b_ptr if b is less than a.
-#[inline(always)]
-pub unsafe fn branchless_swap<T>(a_ptr: *mut T, b_ptr: *mut T, should_swap: bool) {
-    // SAFETY: the caller must guarantee that `a_ptr` and `b_ptr` are valid for writes
-    // and properly aligned, and part of the same allocation, and do not alias.
-
-    // This is a branchless version of swap if.
-    // The equivalent code with a branch would be:
-    //
-    // if should_swap {
-    //     ptr::swap_nonoverlapping(a_ptr, b_ptr, 1);
-    // }
-
-    // Give ourselves some scratch space to work with.
-    // We do not have to worry about drops: `MaybeUninit` does nothing when dropped.
-    let mut tmp = MaybeUninit::<T>::uninit();
-
-    // The goal is to generate cmov instructions here.
-    let a_swap_ptr = if should_swap { b_ptr } else { a_ptr };
-    let b_swap_ptr = if should_swap { a_ptr } else { b_ptr };
+use crate::other::branchless;
 
-    ptr::copy_nonoverlapping(b_swap_ptr, tmp.as_mut_ptr(), 1);
-    ptr::copy(a_swap_ptr, a_ptr, 1);
-    ptr::copy_nonoverlapping(tmp.as_ptr(), b_ptr, 1);
-}
-
-/// Swap two values in array pointed to by a_ptr and b_ptr if b is less than a.
-#[inline(always)]
-pub unsafe fn swap_if_less<T, F>(arr_ptr: *mut T, a: usize, b: usize, is_less: &mut F)
-where
-    F: FnMut(&T, &T) -> bool,
-{
-    // SAFETY: the caller must guarantee that `a` and `b` each added to `arr_ptr` yield valid
-    // pointers into `arr_ptr`, and are properly aligned, and part of the same allocation, and do
-    // not alias. `a` and `b` must be different numbers.
-    debug_assert!(a != b);
-
-    let a_ptr = arr_ptr.add(a);
-    let b_ptr = arr_ptr.add(b);
-
-    // PANIC SAFETY: if is_less panics, no scratch memory was created and the slice should still be
-    // in a well defined state, without duplicates.
-
-    // Important to only swap if it is more and not if it is equal. is_less should return false for
-    // equal, so we don't swap.
-    let should_swap = is_less(&*b_ptr, &*a_ptr);
-    branchless_swap(a_ptr, b_ptr, should_swap);
-}
+sort_impl!("sort4_unstable_cmp_swap");
 
 // Never inline this function to avoid code bloat. It still optimizes nicely and has practically no
 // performance impact.
@@ -63,19 +14,9 @@ where
     // SAFETY: caller must ensure v.len() >= 4.
     assert!(v.len() == 4);
 
-    let arr_ptr = v.as_mut_ptr();
-
     // Optimal sorting network see:
     // https://bertdobbelaere.github.io/sorting_networks.html.
-
-    // We checked the len.
-    unsafe {
-        swap_if_less(arr_ptr, 0, 2, is_less);
-        swap_if_less(arr_ptr, 1, 3, is_less);
-        swap_if_less(arr_ptr, 0, 1, is_less);
-        swap_if_less(arr_ptr, 2, 3, is_less);
-        swap_if_less(arr_ptr, 1, 2, is_less);
-    }
+    branchless::sort4(v, is_less);
 }
 
 fn sort_impl<T, F>(v: &mut [T], is_less: &mut F)
@@ -0,0 +1,67 @@
+use core::cmp::Ordering;
+
+use crate::other::branchless::swap_if_less;
+
+sort_impl!("sort4_unstable_neon");
+
+// Only `sort` of `i32` on aarch64 is vectorized, everything else goes through the same scalar
+// network as `sort4_unstable_cmp_swap`, to compare the two on the same machine.
+trait Sort4Neon: Sized {
+    fn sort4(v: &mut [Self]);
+}
+
+impl<T: Ord> Sort4Neon for T {
+    default fn sort4(v: &mut [Self]) {
+        sort4_network(v, &mut |a, b| a.lt(b));
+    }
+}
+
+impl Sort4Neon for i32 {
+    fn sort4(v: &mut [Self]) {
+        assert!(v.len() == 4);
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            use crate::other::small_sort::neon;
+
+            // SAFETY: We checked the len.
+            unsafe {
+                let arr_ptr = v.as_mut_ptr();
+                neon::store(arr_ptr, neon::sort4(neon::load(arr_ptr)));
+            }
+        }
+
+        #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+        sort4_network(v, &mut |a, b| a.lt(b));
+    }
+}
+
+#[inline(never)]
+fn sort4_network<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(v.len() == 4);
+
+    let arr_ptr = v.as_mut_ptr();
+
+    // We checked the len.
+    unsafe {
+        swap_if_less(arr_ptr, 0, 1, is_less);
+        swap_if_less(arr_ptr, 2, 3, is_less);
+        swap_if_less(arr_ptr, 0, 2, is_less);
+        swap_if_less(arr_ptr, 1, 3, is_less);
+        swap_if_less(arr_ptr, 1, 2, is_less);
+    }
+}
+
+fn sort<T: Ord>(v: &mut [T]) {
+    T::sort4(v);
+}
+
+fn sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort4_network(v, &mut |a, b| compare(a, b) == Ordering::Less);
+}
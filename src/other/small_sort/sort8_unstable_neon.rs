@@ -0,0 +1,90 @@
+use core::cmp::Ordering;
+
+use crate::other::branchless::swap_if_less;
+
+sort_impl!("sort8_unstable_neon");
+
+// Sorts two vectors of four and merges them with a bitonic merge, 11 vector min/max pairs instead
+// of the 19 comparisons of the scalar network. Only `sort` of `i32` on aarch64 is vectorized.
+trait Sort8Neon: Sized {
+    fn sort8(v: &mut [Self]);
+}
+
+impl<T: Ord> Sort8Neon for T {
+    default fn sort8(v: &mut [Self]) {
+        sort8_network(v, &mut |a, b| a.lt(b));
+    }
+}
+
+impl Sort8Neon for i32 {
+    fn sort8(v: &mut [Self]) {
+        assert!(v.len() == 8);
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            use crate::other::small_sort::neon;
+
+            // SAFETY: We checked the len.
+            unsafe {
+                let arr_ptr = v.as_mut_ptr();
+                let a = neon::sort4(neon::load(arr_ptr));
+                let b = neon::sort4(neon::load(arr_ptr.add(4)));
+
+                let (lo, hi) = neon::merge4(a, b);
+                neon::store(arr_ptr, lo);
+                neon::store(arr_ptr.add(4), hi);
+            }
+        }
+
+        #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+        sort8_network(v, &mut |a, b| a.lt(b));
+    }
+}
+
+#[inline(never)]
+fn sort8_network<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // SAFETY: caller must ensure v.len() >= 8.
+    assert!(v.len() == 8);
+
+    let arr_ptr = v.as_mut_ptr();
+
+    // Optimal sorting network see:
+    // https://bertdobbelaere.github.io/sorting_networks.html.
+
+    // We checked the len.
+    unsafe {
+        swap_if_less(arr_ptr, 0, 2, is_less);
+        swap_if_less(arr_ptr, 1, 3, is_less);
+        swap_if_less(arr_ptr, 4, 6, is_less);
+        swap_if_less(arr_ptr, 5, 7, is_less);
+        swap_if_less(arr_ptr, 0, 4, is_less);
+        swap_if_less(arr_ptr, 1, 5, is_less);
+        swap_if_less(arr_ptr, 2, 6, is_less);
+        swap_if_less(arr_ptr, 3, 7, is_less);
+        swap_if_less(arr_ptr, 0, 1, is_less);
+        swap_if_less(arr_ptr, 2, 3, is_less);
+        swap_if_less(arr_ptr, 4, 5, is_less);
+        swap_if_less(arr_ptr, 6, 7, is_less);
+        swap_if_less(arr_ptr, 2, 4, is_less);
+        swap_if_less(arr_ptr, 3, 5, is_less);
+        swap_if_less(arr_ptr, 1, 4, is_less);
+        swap_if_less(arr_ptr, 3, 6, is_less);
+        swap_if_less(arr_ptr, 1, 2, is_less);
+        swap_if_less(arr_ptr, 3, 4, is_less);
+        swap_if_less(arr_ptr, 5, 6, is_less);
+    }
+}
+
+fn sort<T: Ord>(v: &mut [T]) {
+    T::sort8(v);
+}
+
+fn sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort8_network(v, &mut |a, b| compare(a, b) == Ordering::Less);
+}
@@ -0,0 +1,32 @@
+//! Hybrid of a sorting network and insertion sort: the first 16 elements are sorted by Batcher's
+//! odd-even merge network, 63 comparators against the 60 of the best known network, unrolled, and
+//! the rest are inserted one by one. Shorter inputs use insertion sort alone.
+
+small_sort_impl!("zoo_hybrid_sort16", 32);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    if v.len() < 16 {
+        super::insertion_sort_shift_left(v, 1, is_less);
+        return;
+    }
+
+    let v_base = v.as_mut_ptr();
+    // SAFETY: `v` holds at least 16 elements.
+    unsafe {
+        network! {
+            v_base, is_less,
+            (0, 1) (2, 3) (4, 5) (6, 7) (8, 9) (10, 11) (12, 13) (14, 15)
+            (0, 2) (1, 3) (4, 6) (5, 7) (8, 10) (9, 11) (12, 14) (13, 15)
+            (1, 2) (5, 6) (9, 10) (13, 14) (0, 4)
+            (1, 5) (2, 6) (3, 7) (8, 12) (9, 13) (10, 14) (11, 15)
+            (2, 4) (3, 5) (10, 12) (11, 13)
+            (1, 2) (3, 4) (5, 6) (9, 10) (11, 12) (13, 14) (0, 8)
+            (1, 9) (2, 10) (3, 11) (4, 12) (5, 13) (6, 14) (7, 15)
+            (4, 8) (5, 9) (6, 10) (7, 11)
+            (2, 4) (3, 5) (6, 8) (7, 9) (10, 12) (11, 13)
+            (1, 2) (3, 4) (5, 6) (7, 8) (9, 10) (11, 12) (13, 14)
+        }
+    }
+
+    super::insertion_sort_shift_left(v, 16, is_less);
+}
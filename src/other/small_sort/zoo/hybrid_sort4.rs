@@ -0,0 +1,25 @@
+//! Hybrid of a sorting network and insertion sort: the first 4 elements are sorted by the optimal
+//! network of 5 comparators, unrolled, and the rest are inserted one by one. Shorter inputs use
+//! insertion sort alone.
+
+small_sort_impl!("zoo_hybrid_sort4", 32);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    if v.len() < 4 {
+        super::insertion_sort_shift_left(v, 1, is_less);
+        return;
+    }
+
+    let v_base = v.as_mut_ptr();
+    // SAFETY: `v` holds at least 4 elements.
+    unsafe {
+        network! {
+            v_base, is_less,
+            (0, 1) (2, 3)
+            (0, 2) (1, 3)
+            (1, 2)
+        }
+    }
+
+    super::insertion_sort_shift_left(v, 4, is_less);
+}
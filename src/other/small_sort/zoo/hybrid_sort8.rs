@@ -0,0 +1,28 @@
+//! Hybrid of a sorting network and insertion sort: the first 8 elements are sorted by Batcher's
+//! odd-even merge network, 19 comparators which is optimal for 8, unrolled, and the rest are
+//! inserted one by one. Shorter inputs use insertion sort alone.
+
+small_sort_impl!("zoo_hybrid_sort8", 32);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    if v.len() < 8 {
+        super::insertion_sort_shift_left(v, 1, is_less);
+        return;
+    }
+
+    let v_base = v.as_mut_ptr();
+    // SAFETY: `v` holds at least 8 elements.
+    unsafe {
+        network! {
+            v_base, is_less,
+            (0, 1) (2, 3) (4, 5) (6, 7)
+            (0, 2) (1, 3) (4, 6) (5, 7)
+            (1, 2) (5, 6) (0, 4)
+            (1, 5) (2, 6) (3, 7)
+            (2, 4) (3, 5)
+            (1, 2) (3, 4) (5, 6)
+        }
+    }
+
+    super::insertion_sort_shift_left(v, 8, is_less);
+}
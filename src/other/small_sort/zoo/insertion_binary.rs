@@ -0,0 +1,17 @@
+//! Binary insertion sort, the insertion point is found by binary search and the gap opened with a
+//! rotate. Fewer comparisons than a linear scan, log2(i) instead of up to i for the element at i,
+//! but the search branches on every comparison and the rotate moves the same elements. Wins when
+//! comparisons are expensive, eg. for strings.
+
+small_sort_impl!("zoo_insertion_binary", 32);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for i in 1..v.len() {
+        let (sorted, rest) = v.split_at(i);
+        let tail = &rest[0];
+
+        // Insert after all equal elements, like the linear scan.
+        let pos = sorted.partition_point(|elem| !is_less(tail, elem));
+        v[pos..=i].rotate_right(1);
+    }
+}
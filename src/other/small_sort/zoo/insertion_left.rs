@@ -0,0 +1,7 @@
+//! Insertion sort, every element is shifted left by a linear scan. The baseline of the zoo.
+
+small_sort_impl!("zoo_insertion_left", 32);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    super::insertion_sort_shift_left(v, 1, is_less);
+}
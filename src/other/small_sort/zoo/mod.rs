@@ -0,0 +1,135 @@
+//! Candidate small-sorts behind a common trait, so the small-sort of a hybrid sort can be picked by
+//! measurement and swapped without touching the rest of the sort.
+//!
+//! Every candidate lives in its own file and implements [`SmallSort`] via `small_sort_impl!`. A
+//! sort that is generic over `S: SmallSort` calls `S::sort_by` for every sub-slice of at most
+//! `S::max_len()` elements. Candidates are benchmarked with `BENCH_OTHER=small_sort_zoo` and tested
+//! against all permutations of small lens in tests/main.rs.
+
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+pub trait SmallSort {
+    fn name() -> String;
+
+    /// The largest len the candidate is designed for, `sort` and `sort_by` panic above it.
+    fn max_len() -> usize;
+
+    fn sort<T: Ord>(v: &mut [T]);
+
+    fn sort_by<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F);
+}
+
+macro_rules! small_sort_impl {
+    ($name:expr, $max_len:expr) => {
+        pub struct SmallSortImpl;
+
+        impl crate::other::small_sort::zoo::SmallSort for SmallSortImpl {
+            fn name() -> String {
+                $name.into()
+            }
+
+            fn max_len() -> usize {
+                $max_len
+            }
+
+            #[inline]
+            fn sort<T>(v: &mut [T])
+            where
+                T: Ord,
+            {
+                assert!(v.len() <= $max_len);
+                small_sort(v, &mut |a, b| a.lt(b))
+            }
+
+            #[inline]
+            fn sort_by<T, F>(v: &mut [T], is_less: &mut F)
+            where
+                F: FnMut(&T, &T) -> bool,
+            {
+                assert!(v.len() <= $max_len);
+                small_sort(v, is_less)
+            }
+        }
+    };
+}
+
+/// Unrolls a sorting network into one branchless compare-exchange per comparator, written as
+/// `network! { v_base, is_less, (0, 1) (2, 3) ... }`.
+///
+/// `v_base` has to be valid for every index of the network.
+macro_rules! network {
+    ($v_base:expr, $is_less:expr, $(($a:literal, $b:literal))*) => {
+        $(crate::other::branchless::swap_if_less($v_base, $a, $b, $is_less);)*
+    };
+}
+
+pub mod hybrid_sort16;
+pub mod hybrid_sort4;
+pub mod hybrid_sort8;
+pub mod insertion_binary;
+pub mod insertion_left;
+pub mod network_table;
+
+/// Sorts `v` assuming `v[..offset]` is already sorted, by inserting each following element.
+fn insertion_sort_shift_left<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    offset: usize,
+    is_less: &mut F,
+) {
+    let v_base = v.as_mut_ptr();
+    for i in offset.max(1)..v.len() {
+        // SAFETY: `0 < i < len`.
+        unsafe { insert_tail(v_base, i, is_less) };
+    }
+}
+
+/// Moves `v[tail]` left until `v[..=tail]` is sorted, assuming `v[..tail]` is sorted.
+///
+/// # Safety
+///
+/// `tail > 0` and `v_base` must be valid for `tail + 1` elements.
+unsafe fn insert_tail<T, F: FnMut(&T, &T) -> bool>(v_base: *mut T, tail: usize, is_less: &mut F) {
+    // SAFETY: See function safety comment.
+    unsafe {
+        let tail_ptr = v_base.add(tail);
+        let mut sift = tail_ptr.sub(1);
+        if !is_less(&*tail_ptr, &*sift) {
+            return;
+        }
+
+        // The element is only read from `tmp` from here on, if `is_less` panics the guard fills
+        // the hole with it, so the slice holds every element exactly once.
+        let tmp = ManuallyDrop::new(ptr::read(tail_ptr));
+        let mut hole = InsertionHole {
+            src: &*tmp,
+            dst: tail_ptr,
+        };
+
+        loop {
+            ptr::copy_nonoverlapping(sift, hole.dst, 1);
+            hole.dst = sift;
+
+            if sift == v_base {
+                break;
+            }
+
+            sift = sift.sub(1);
+            if !is_less(&tmp, &*sift) {
+                break;
+            }
+        }
+    }
+}
+
+struct InsertionHole<T> {
+    src: *const T,
+    dst: *mut T,
+}
+
+impl<T> Drop for InsertionHole<T> {
+    fn drop(&mut self) {
+        // SAFETY: `dst` is the hole left by the element in `src`.
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dst, 1) };
+    }
+}
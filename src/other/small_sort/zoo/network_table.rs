@@ -0,0 +1,184 @@
+//! Sorting networks read from a table, one per len up to 16. The comparators are Batcher's
+//! odd-even merge sort for 16 elements, pruned to the len and with the comparators removed that are
+//! redundant for it, 19 for 8 elements, 63 for 16.
+//!
+//! The loop over the table is not unrolled, unlike the networks of the hybrid candidates, which
+//! keeps the code small but adds the index loads to every compare-exchange.
+
+use crate::other::branchless::swap_if_less;
+
+small_sort_impl!("zoo_network_table", 16);
+
+fn small_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+
+    let v_base = v.as_mut_ptr();
+    for &(a, b) in NETWORKS[len] {
+        // SAFETY: All indices of the network for `len` are below `len`, and `a != b`.
+        unsafe { swap_if_less(v_base, a as usize, b as usize, is_less) };
+    }
+}
+
+/// The network for each len, indexed by the len, one line per layer of independent comparators.
+#[rustfmt::skip]
+const NETWORKS: [&[(u8, u8)]; 17] = [
+    &[],
+    &[],
+    // 1 comparator.
+    &[
+        (0, 1),
+    ],
+    // 3 comparators.
+    &[
+        (0, 1),
+        (0, 2),
+        (1, 2),
+    ],
+    // 5 comparators.
+    &[
+        (0, 1), (2, 3),
+        (0, 2), (1, 3),
+        (1, 2),
+    ],
+    // 9 comparators.
+    &[
+        (0, 1), (2, 3),
+        (0, 2), (1, 3),
+        (1, 2), (0, 4),
+        (2, 4),
+        (1, 2), (3, 4),
+    ],
+    // 12 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5),
+        (0, 2), (1, 3),
+        (1, 2), (0, 4),
+        (1, 5), (2, 4),
+        (3, 5), (1, 2),
+        (3, 4),
+    ],
+    // 16 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5),
+        (0, 2), (1, 3), (4, 6),
+        (1, 2), (5, 6), (0, 4),
+        (1, 5), (2, 6),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6),
+    ],
+    // 19 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7),
+        (0, 2), (1, 3), (4, 6), (5, 7),
+        (1, 2), (5, 6), (0, 4),
+        (1, 5), (2, 6), (3, 7),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6),
+    ],
+    // 27 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7),
+        (0, 2), (1, 3), (4, 6), (5, 7),
+        (1, 2), (5, 6), (0, 4),
+        (1, 5), (2, 6), (3, 7),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6), (0, 8),
+        (4, 8),
+        (2, 4), (6, 8),
+        (1, 2), (3, 4), (5, 6), (7, 8),
+    ],
+    // 32 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9),
+        (0, 2), (1, 3), (4, 6), (5, 7),
+        (1, 2), (5, 6), (0, 4),
+        (1, 5), (2, 6), (3, 7),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6), (0, 8),
+        (1, 9), (4, 8),
+        (5, 9), (2, 4),
+        (3, 5), (6, 8), (7, 9), (1, 2),
+        (3, 4), (5, 6), (7, 8),
+    ],
+    // 37 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10),
+        (1, 2), (5, 6), (9, 10), (0, 4),
+        (1, 5), (2, 6), (3, 7),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6), (0, 8),
+        (1, 9), (2, 10), (4, 8),
+        (5, 9), (6, 10), (2, 4),
+        (3, 5), (6, 8), (7, 9), (1, 2),
+        (3, 4), (5, 6), (7, 8), (9, 10),
+    ],
+    // 41 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10), (9, 11),
+        (1, 2), (5, 6), (9, 10), (0, 4),
+        (1, 5), (2, 6), (3, 7),
+        (2, 4), (3, 5),
+        (1, 2), (3, 4), (5, 6), (0, 8),
+        (1, 9), (2, 10), (3, 11), (4, 8),
+        (5, 9), (6, 10), (7, 11), (2, 4),
+        (3, 5), (6, 8), (7, 9), (1, 2),
+        (3, 4), (5, 6), (7, 8), (9, 10),
+    ],
+    // 48 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10), (9, 11),
+        (1, 2), (5, 6), (9, 10), (0, 4),
+        (1, 5), (2, 6), (3, 7), (8, 12),
+        (2, 4), (3, 5), (10, 12),
+        (1, 2), (3, 4), (5, 6), (9, 10), (11, 12), (0, 8),
+        (1, 9), (2, 10), (3, 11), (4, 12),
+        (4, 8), (5, 9), (6, 10), (7, 11),
+        (2, 4), (3, 5), (6, 8), (7, 9), (10, 12),
+        (1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12),
+    ],
+    // 53 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10), (9, 11),
+        (1, 2), (5, 6), (9, 10), (0, 4),
+        (1, 5), (2, 6), (3, 7), (8, 12), (9, 13),
+        (2, 4), (3, 5), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (9, 10), (11, 12), (0, 8),
+        (1, 9), (2, 10), (3, 11), (4, 12), (5, 13),
+        (4, 8), (5, 9), (6, 10), (7, 11),
+        (2, 4), (3, 5), (6, 8), (7, 9), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12),
+    ],
+    // 59 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10), (9, 11), (12, 14),
+        (1, 2), (5, 6), (9, 10), (13, 14), (0, 4),
+        (1, 5), (2, 6), (3, 7), (8, 12), (9, 13), (10, 14),
+        (2, 4), (3, 5), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (9, 10), (11, 12), (13, 14), (0, 8),
+        (1, 9), (2, 10), (3, 11), (4, 12), (5, 13), (6, 14),
+        (4, 8), (5, 9), (6, 10), (7, 11),
+        (2, 4), (3, 5), (6, 8), (7, 9), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (13, 14),
+    ],
+    // 63 comparators.
+    &[
+        (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13), (14, 15),
+        (0, 2), (1, 3), (4, 6), (5, 7), (8, 10), (9, 11), (12, 14), (13, 15),
+        (1, 2), (5, 6), (9, 10), (13, 14), (0, 4),
+        (1, 5), (2, 6), (3, 7), (8, 12), (9, 13), (10, 14), (11, 15),
+        (2, 4), (3, 5), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (9, 10), (11, 12), (13, 14), (0, 8),
+        (1, 9), (2, 10), (3, 11), (4, 12), (5, 13), (6, 14), (7, 15),
+        (4, 8), (5, 9), (6, 10), (7, 11),
+        (2, 4), (3, 5), (6, 8), (7, 9), (10, 12), (11, 13),
+        (1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (13, 14),
+    ],
+];
@@ -0,0 +1,47 @@
+//! Sorted output for an iterator, without collecting it into a `Vec` and sorting that.
+//!
+//! The input is read in chunks of a fixed length, every chunk is sorted with [`slice::sort`] and
+//! the sorted chunks are merged lazily with a binary heap holding the current head of each chunk.
+//! Sorting a chunk that fits into the cache is faster than sorting the whole input, in exchange
+//! every output element costs a heap operation with *O*(log(*k*)) comparisons for *k* chunks.
+//! `BENCH_OTHER=sorted_iter` compares this to collecting the input and sorting it.
+//!
+//! All input is consumed before the first element is returned, the smallest element may be the
+//! last one. The result is stable, equal elements are returned in input order.
+
+use crate::kmerge::heap;
+
+/// Number of elements sorted at once by [`sorted_iter`].
+pub const DEFAULT_CHUNK_LEN: usize = 4096;
+
+/// Returns the elements of `iter` in ascending order, preserving the order of equal elements.
+pub fn sorted_iter<T: Ord>(iter: impl IntoIterator<Item = T>) -> impl Iterator<Item = T> {
+    sorted_iter_with_chunk_len(iter, DEFAULT_CHUNK_LEN)
+}
+
+/// Same as [`sorted_iter`], sorting `chunk_len` elements at once.
+///
+/// # Panics
+///
+/// Panics if `chunk_len` is 0.
+pub fn sorted_iter_with_chunk_len<T: Ord>(
+    iter: impl IntoIterator<Item = T>,
+    chunk_len: usize,
+) -> impl Iterator<Item = T> {
+    assert!(chunk_len > 0, "chunk_len must not be 0");
+
+    let mut iter = iter.into_iter();
+    let mut chunks = Vec::new();
+
+    loop {
+        let mut chunk = iter.by_ref().take(chunk_len).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            break;
+        }
+
+        chunk.sort();
+        chunks.push(chunk.into_iter());
+    }
+
+    heap::merge_iter(chunks)
+}
@@ -0,0 +1,115 @@
+//! Experiment: sort as much of a slice as fits into a time budget, eg. 2ms of a frame.
+//!
+//! [`sort_for`] is an incremental quicksort, after Paredes and Navarro. It always partitions the
+//! leftmost unsorted segment and sorts it once it's small, so the slice fills up with its final
+//! sorted prefix from the left, one small segment at a time, while the rest stays partitioned
+//! around the pivots on the stack. Sorting the whole slice this way costs about as much as a
+//! plain quicksort, stopping early leaves a prefix that is already in its final order.
+//!
+//! The budget is checked between partitions and small-sorts, the first partition of a long slice
+//! is linear in its len and can overshoot a short budget on its own. The pivot is the median of
+//! three, inputs built against it degrade to quadratic time, there is no fallback as the budget
+//! caps the time anyway. `BENCH_OTHER=time_limited_sort` prints the sorted prefix len per budget
+//! and compares the runtime without a limit to [`slice::sort_unstable`].
+
+use std::time::{Duration, Instant};
+
+/// Segments up to this len are sorted in one step.
+const SMALL_SORT_THRESHOLD: usize = 32;
+
+/// The result of a time-limited sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// `v[..sorted_prefix]` holds the smallest elements of `v` in sorted order, in the positions
+    /// they would have after sorting all of `v`.
+    pub sorted_prefix: usize,
+}
+
+impl Progress {
+    /// Whether all of the `len` elements are sorted.
+    pub fn is_complete(&self, len: usize) -> bool {
+        self.sorted_prefix == len
+    }
+}
+
+/// Sorts `v` for at most about `budget`, not preserving the order of equal elements.
+///
+/// The sort can be continued with another call on `v[progress.sorted_prefix..]`, which starts
+/// partitioning the rest from scratch.
+pub fn sort_for<T: Ord>(budget: Duration, v: &mut [T]) -> Progress {
+    let deadline = Instant::now().checked_add(budget);
+    let timed_out = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+    let mut sorted_prefix = 0;
+    // Ends of the segments that are left to sort, the last one starts at `sorted_prefix`. All but
+    // the first end are the positions of pivots that are in their final place.
+    let mut segment_ends = vec![v.len()];
+
+    while let Some(&end) = segment_ends.last() {
+        if timed_out() {
+            break;
+        }
+
+        let segment = &mut v[sorted_prefix..end];
+        if segment.len() <= SMALL_SORT_THRESHOLD {
+            segment.sort_unstable();
+            segment_ends.pop();
+            // Skip the pivot that bounded the segment.
+            sorted_prefix = if segment_ends.is_empty() {
+                end
+            } else {
+                end + 1
+            };
+        } else {
+            let mid = partition(segment);
+            segment_ends.push(sorted_prefix + mid);
+        }
+    }
+
+    Progress { sorted_prefix }
+}
+
+/// Partitions `v` around the median of three of its elements and returns the final position of
+/// that pivot, elements left of it are less than or equal and right of it greater than or equal.
+/// Equal elements are swapped across both sides, which keeps the partitions balanced.
+fn partition<T: Ord>(v: &mut [T]) -> usize {
+    let len = v.len();
+    let pivot_pos = median_of_three(v, len / 4, len / 2, len / 4 * 3);
+    v.swap(0, pivot_pos);
+
+    let (pivot, rest) = v.split_first_mut().unwrap();
+    let mut l = 0;
+    let mut r = rest.len();
+
+    // rest[..l] <= pivot and rest[r..] >= pivot.
+    loop {
+        while l < r && rest[l] < *pivot {
+            l += 1;
+        }
+        while l < r && rest[r - 1] > *pivot {
+            r -= 1;
+        }
+        // If a single element is left it is equal to the pivot, it can stay on either side.
+        if l + 1 >= r {
+            break;
+        }
+
+        rest.swap(l, r - 1);
+        l += 1;
+        r -= 1;
+    }
+
+    // v[1..=l] <= pivot, swapping the pivot to l puts it between the sides.
+    v.swap(0, l);
+    l
+}
+
+fn median_of_three<T: Ord>(v: &[T], a: usize, b: usize, c: usize) -> usize {
+    if (v[a] < v[b]) == (v[b] < v[c]) {
+        b
+    } else if (v[a] < v[b]) == (v[a] < v[c]) {
+        c
+    } else {
+        a
+    }
+}
@@ -0,0 +1,127 @@
+//! The `k` smallest or largest elements of a slice, in sorted order.
+//!
+//! Three strategies, see [`Strategy`]: a bounded max-heap of the `k` smallest elements seen so far,
+//! [`slice::select_nth_unstable`] followed by sorting the prefix, and sorting everything. The heap
+//! makes one pass and only rarely touches the heap once it holds small elements, which wins for
+//! small `k`, selection is linear and wins for large `k`. Sorting everything is the baseline that
+//! both have to beat. `BENCH_OTHER=top_k` compares them across `k / len` ratios, [`bottom_k`] and
+//! [`top_k`] pick a strategy by that ratio.
+
+use std::cmp::Ordering;
+
+/// Up to `len / HEAP_MAX_RATIO` the heap is used, above it selection.
+pub const HEAP_MAX_RATIO: usize = 64;
+
+/// How [`bottom_k_with`] finds the smallest elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// A max-heap of the `k` smallest elements seen so far in `v[..k]`, *O*(*n* \* log(*k*)).
+    Heap,
+    /// `select_nth_unstable` at `k - 1` and sorting the prefix, *O*(*n* + *k* \* log(*k*)).
+    Select,
+    /// `sort_unstable` of the whole slice, *O*(*n* \* log(*n*)).
+    FullSort,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Heap, Strategy::Select, Strategy::FullSort];
+
+    /// The strategy [`bottom_k`] uses.
+    pub fn for_len(len: usize, k: usize) -> Self {
+        if k <= len / HEAP_MAX_RATIO {
+            Strategy::Heap
+        } else {
+            Strategy::Select
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Heap => "heap",
+            Strategy::Select => "select",
+            Strategy::FullSort => "full_sort",
+        }
+    }
+}
+
+/// Moves the `k` smallest elements of `v` to its front in ascending order and returns them. The
+/// order of the remaining elements is unspecified, as is the order of equal elements. A `k`
+/// larger than `v.len()` is clamped.
+pub fn bottom_k<T: Ord>(v: &mut [T], k: usize) -> &mut [T] {
+    bottom_k_with(Strategy::for_len(v.len(), k), v, k, T::cmp)
+}
+
+/// Moves the `k` largest elements of `v` to its front in descending order and returns them, see
+/// [`bottom_k`].
+pub fn top_k<T: Ord>(v: &mut [T], k: usize) -> &mut [T] {
+    bottom_k_with(Strategy::for_len(v.len(), k), v, k, |a: &T, b: &T| b.cmp(a))
+}
+
+/// [`bottom_k`] with the given strategy and order.
+pub fn bottom_k_with<T, F>(strategy: Strategy, v: &mut [T], k: usize, mut compare: F) -> &mut [T]
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(v.len());
+    if k == 0 {
+        return &mut v[..0];
+    }
+
+    match strategy {
+        Strategy::Heap => heap_bottom_k(v, k, &mut |a, b| compare(a, b) == Ordering::Less),
+        Strategy::Select => {
+            v.select_nth_unstable_by(k - 1, &mut compare);
+            v[..k].sort_unstable_by(compare);
+        }
+        Strategy::FullSort => v.sort_unstable_by(compare),
+    }
+
+    &mut v[..k]
+}
+
+fn heap_bottom_k<T, F>(v: &mut [T], k: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in (0..k / 2).rev() {
+        sift_down(&mut v[..k], i, is_less);
+    }
+
+    // The root is the largest of the k smallest so far, anything smaller replaces it.
+    for i in k..v.len() {
+        if is_less(&v[i], &v[0]) {
+            v.swap(0, i);
+            sift_down(&mut v[..k], 0, is_less);
+        }
+    }
+
+    // Heapsort the max-heap into ascending order.
+    for end in (1..k).rev() {
+        v.swap(0, end);
+        sift_down(&mut v[..end], 0, is_less);
+    }
+}
+
+/// Restores the max-heap property of `heap` below `node`.
+fn sift_down<T, F>(heap: &mut [T], mut node: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    loop {
+        let mut child = 2 * node + 1;
+        if child >= heap.len() {
+            break;
+        }
+
+        if child + 1 < heap.len() && is_less(&heap[child], &heap[child + 1]) {
+            child += 1;
+        }
+
+        if !is_less(&heap[node], &heap[child]) {
+            break;
+        }
+
+        heap.swap(node, child);
+        node = child;
+    }
+}
@@ -0,0 +1,215 @@
+//! Type-erased entry points of the Rust std sorts, the `qsort` interface for elements only known by
+//! their size at runtime.
+//!
+//! Interpreters and databases sort values whose layout is decided at runtime, so they can't
+//! instantiate a generic sort per element type. [`sort_raw`] and [`sort_unstable_raw`] take a
+//! pointer, a len, an element size and a comparison function on element pointers, see
+//! [`Strategy`] for the two ways to get there from the generic sorts:
+//!
+//! - Sizes made of one to four words of 1, 2, 4, 8 or 16 bytes are sorted as `[word; n]` arrays,
+//!   only the comparison goes through the dynamic function. This is how `qsort` implementations
+//!   specialize their swaps by size.
+//! - All other sizes are sorted indirectly, by sorting the element indices and permuting the
+//!   elements once at the end, at the cost of `len` indices of memory.
+//!
+//! `BENCH_OTHER=type_erased` compares both to the monomorphized sort of the same type, which gives
+//! the price of type erasure.
+
+use std::cmp::Ordering;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+
+use crate::{stable, unstable};
+
+/// How [`sort_raw_with`] gets from raw bytes to a generic sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Sort the elements as arrays of up to four words, falls back to `Indirect` for other sizes.
+    SizeClass,
+    /// Sort the indices of the elements and permute the elements by them.
+    Indirect,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 2] = [Strategy::SizeClass, Strategy::Indirect];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::SizeClass => "size_class",
+            Strategy::Indirect => "indirect",
+        }
+    }
+}
+
+/// Stable sort of the `len` elements of `elem_size` bytes at `ptr`, ordered by `cmp`.
+///
+/// `cmp` is called with pointers to two elements. They are aligned to the largest power of two
+/// that divides both `elem_size` and the address of `ptr`, up to `align_of::<u128>()`, so they can
+/// be cast to any type with that alignment or less. They may point into a scratch buffer instead of
+/// `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and writes of `len * elem_size` bytes, and the elements must stay
+/// valid if their bytes are moved to another position, as for any Rust type. If `cmp` panics the
+/// elements are left in an unspecified order, each of them exactly once.
+pub unsafe fn sort_raw(
+    ptr: *mut u8,
+    len: usize,
+    elem_size: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) {
+    sort_raw_with(Strategy::SizeClass, true, ptr, len, elem_size, cmp);
+}
+
+/// Unstable sort of the `len` elements of `elem_size` bytes at `ptr`, see [`sort_raw`].
+///
+/// # Safety
+///
+/// See [`sort_raw`].
+pub unsafe fn sort_unstable_raw(
+    ptr: *mut u8,
+    len: usize,
+    elem_size: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) {
+    sort_raw_with(Strategy::SizeClass, false, ptr, len, elem_size, cmp);
+}
+
+/// [`sort_raw`] or [`sort_unstable_raw`] with the given strategy.
+///
+/// # Safety
+///
+/// See [`sort_raw`].
+pub unsafe fn sort_raw_with(
+    strategy: Strategy,
+    stable: bool,
+    ptr: *mut u8,
+    len: usize,
+    elem_size: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) {
+    if len < 2 || elem_size == 0 {
+        return;
+    }
+    debug_assert!(len.checked_mul(elem_size).is_some());
+
+    if strategy == Strategy::SizeClass && sort_size_class(stable, ptr, len, elem_size, cmp) {
+        return;
+    }
+
+    sort_indirect(stable, ptr, len, elem_size, cmp);
+}
+
+macro_rules! size_class_arms {
+    ($word_size:expr, $words:expr, $stable:expr, $ptr:expr, $len:expr, $cmp:expr; $($word:ty),*) => {
+        match ($word_size, $words) {
+            $(
+                (size, 1) if size == mem::size_of::<$word>() => {
+                    sort_words::<$word, 1>($stable, $ptr, $len, $cmp)
+                }
+                (size, 2) if size == mem::size_of::<$word>() => {
+                    sort_words::<$word, 2>($stable, $ptr, $len, $cmp)
+                }
+                (size, 3) if size == mem::size_of::<$word>() => {
+                    sort_words::<$word, 3>($stable, $ptr, $len, $cmp)
+                }
+                (size, 4) if size == mem::size_of::<$word>() => {
+                    sort_words::<$word, 4>($stable, $ptr, $len, $cmp)
+                }
+            )*
+            _ => return false,
+        }
+    };
+}
+
+/// Sorts the elements as arrays of the largest word that divides the element size and the address
+/// of `ptr`, returns false if the element is more than four of those words.
+unsafe fn sort_size_class(
+    stable: bool,
+    ptr: *mut u8,
+    len: usize,
+    elem_size: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) -> bool {
+    let word_size = [
+        (mem::size_of::<u128>(), mem::align_of::<u128>()),
+        (mem::size_of::<u64>(), mem::align_of::<u64>()),
+        (mem::size_of::<u32>(), mem::align_of::<u32>()),
+        (mem::size_of::<u16>(), mem::align_of::<u16>()),
+    ]
+    .into_iter()
+    .find(|&(size, align)| elem_size.is_multiple_of(size) && (ptr as usize).is_multiple_of(align))
+    .map_or(1, |(size, _)| size);
+
+    size_class_arms!(
+        word_size, elem_size / word_size, stable, ptr, len, cmp;
+        u8, u16, u32, u64, u128
+    );
+
+    true
+}
+
+/// Sorts the elements as `[W; N]`. `MaybeUninit` keeps padding bytes of the elements valid.
+unsafe fn sort_words<W: Copy, const N: usize>(
+    stable: bool,
+    ptr: *mut u8,
+    len: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) {
+    // SAFETY: The caller guarantees that `ptr` is valid for `len * N * size_of::<W>()` bytes and
+    // that it is aligned for `W`. Arrays of `MaybeUninit` accept any bytes and are moved bitwise.
+    let v = std::slice::from_raw_parts_mut(ptr as *mut [MaybeUninit<W>; N], len);
+    let compare = |a: &[MaybeUninit<W>; N], b: &[MaybeUninit<W>; N]| {
+        cmp(a.as_ptr().cast(), b.as_ptr().cast())
+    };
+
+    if stable {
+        stable::rust_std::sort_by(v, compare);
+    } else {
+        unstable::rust_std::sort_by(v, compare);
+    }
+}
+
+/// Sorts the indices of the elements and moves every element once along the cycles of the
+/// permutation, with one element of scratch.
+unsafe fn sort_indirect(
+    stable: bool,
+    ptr: *mut u8,
+    len: usize,
+    elem_size: usize,
+    cmp: &mut dyn FnMut(*const u8, *const u8) -> Ordering,
+) {
+    let elem = |i: usize| ptr.add(i * elem_size);
+
+    let mut indices = (0..len).collect::<Vec<_>>();
+    let compare = |&a: &usize, &b: &usize| cmp(elem(a), elem(b));
+    if stable {
+        stable::rust_std::sort_by(&mut indices, compare);
+    } else {
+        unstable::rust_std::sort_by(&mut indices, compare);
+    }
+
+    // The same walk as other::permute::apply_permutation, with copies instead of swaps.
+    let mut buf = Vec::<MaybeUninit<u8>>::with_capacity(elem_size);
+    let tmp = buf.as_mut_ptr().cast::<u8>();
+    for start in 0..len {
+        if indices[start] == start {
+            continue;
+        }
+
+        ptr::copy_nonoverlapping(elem(start), tmp, elem_size);
+        let mut current = start;
+        loop {
+            let next = indices[current];
+            indices[current] = current;
+            if next == start {
+                ptr::copy_nonoverlapping(tmp, elem(current), elem_size);
+                break;
+            }
+
+            ptr::copy_nonoverlapping(elem(next), elem(current), elem_size);
+            current = next;
+        }
+    }
+}
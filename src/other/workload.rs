@@ -0,0 +1,105 @@
+//! Simulates an application that calls sort at many different sizes, instead of benchmarking one
+//! size at a time. The sizes are drawn from a histogram, eg. "90% of the calls sort less than 20
+//! elements, 9% less than 1000 and 1% up to a million", given as `weight:min-max` bins:
+//!
+//! `90:1-19,9:20-999,1:1000-1000000`
+//!
+//! The weights are relative, they don't have to add up to 100. Within a bin the sizes are uniformly
+//! distributed, a single size can be written as `weight:len`. `BENCH_OTHER=workload` splits each
+//! input into slices with sizes from the histogram in `BENCH_WORKLOAD` and sorts every slice, the
+//! total element count divided by the time is the aggregate throughput of the workload.
+
+use std::str::FromStr;
+
+use sort_test_tools::xorshift::XorShift;
+
+/// Used if `BENCH_WORKLOAD` is not set.
+pub const DEFAULT_HISTOGRAM: &str = "90:1-19,9:20-999,1:1000-1000000";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bin {
+    pub weight: f64,
+    pub min_len: usize,
+    pub max_len: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeHistogram {
+    bins: Vec<Bin>,
+}
+
+impl SizeHistogram {
+    pub fn bins(&self) -> &[Bin] {
+        &self.bins
+    }
+
+    /// The largest size that can be drawn.
+    pub fn max_len(&self) -> usize {
+        self.bins.iter().map(|bin| bin.max_len).max().unwrap()
+    }
+
+    /// Returns a generator of sizes distributed like the histogram. The same `seed` yields the
+    /// same sizes, so every sort gets the same sequence of calls.
+    pub fn sampler(&self, seed: u64) -> impl FnMut() -> usize + '_ {
+        let total_weight = self.bins.iter().map(|bin| bin.weight).sum::<f64>();
+        let mut rng = XorShift::new(seed | 1);
+
+        move || {
+            let mut pick = rng.unit() * total_weight;
+            let bin = self
+                .bins
+                .iter()
+                .find(|bin| {
+                    pick -= bin.weight;
+                    pick < 0.0
+                })
+                .unwrap_or(self.bins.last().unwrap());
+
+            bin.min_len + rng.below(bin.max_len - bin.min_len + 1)
+        }
+    }
+}
+
+impl FromStr for SizeHistogram {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_num = |num: &str| {
+            num.trim()
+                .replace('_', "")
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid len '{num}' in '{s}'"))
+        };
+
+        let mut bins = Vec::new();
+        for bin in s.split(',') {
+            let (weight, lens) = bin
+                .split_once(':')
+                .ok_or_else(|| format!("Expected weight:min-max, got '{bin}'"))?;
+
+            let weight = weight
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| weight.is_finite() && *weight > 0.0)
+                .ok_or_else(|| format!("Invalid weight '{weight}' in '{bin}'"))?;
+
+            let (min_len, max_len) = match lens.split_once('-') {
+                Some((min_len, max_len)) => (parse_num(min_len)?, parse_num(max_len)?),
+                None => (parse_num(lens)?, parse_num(lens)?),
+            };
+
+            if min_len == 0 || min_len > max_len {
+                return Err(format!("Expected 1 <= min <= max in '{bin}'"));
+            }
+
+            bins.push(Bin {
+                weight,
+                min_len,
+                max_len,
+            });
+        }
+
+        Ok(Self { bins })
+    }
+}
@@ -0,0 +1,169 @@
+//! Runtime loading of sort implementations from shared libraries.
+//!
+//! Set `SORT_PLUGINS` to a `:` separated list of shared library paths, and every library is
+//! loaded with `dlopen` the first time the registry is queried. Loaded plugins show up in
+//! [`crate::registry::sorts`] like any built-in sort, so they can be benchmarked and compared
+//! without modifying this crate.
+//!
+//! A plugin has to export these three C functions, see `util/sort_plugin/sort_plugin.h`:
+//!
+//! ```c
+//! // Name used for benchmarks and tests. Should contain "unstable" if the sort is not stable.
+//! const char* sort_name(void);
+//!
+//! void sort_u64(uint64_t* data, size_t len);
+//!
+//! // Sorts len elements of elem_size bytes each, using cmp_fn to compare them. Elements may only
+//! // be moved around with memcpy-like copies. If cmp_fn returns is_panic the plugin must stop
+//! // calling it, leave data as a permutation of its original elements and return 1. If an
+//! // allocation fails return 2, see AllocFailurePolicy in ffi_util. Otherwise return 0.
+//! uint32_t sort_by_cmp(void* data,
+//!                      size_t len,
+//!                      size_t elem_size,
+//!                      CompResult (*cmp_fn)(const void* a, const void* b, uint8_t* ctx),
+//!                      uint8_t* ctx);
+//! ```
+
+use std::any::TypeId;
+use std::cmp::Ordering;
+use std::env;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::OnceLock;
+
+use sort_test_tools::ffi_types::CompResult;
+
+use crate::ffi_util::{handle_ffi_ret_code, rust_fn_cmp_threeway};
+use crate::registry::SortEntry;
+
+type SortNameFn = unsafe extern "C" fn() -> *const c_char;
+type SortU64Fn = unsafe extern "C" fn(*mut u64, usize);
+type CmpFn = unsafe extern "C" fn(*const c_void, *const c_void, *mut u8) -> CompResult;
+type SortByCmpFn = unsafe extern "C" fn(*mut c_void, usize, usize, CmpFn, *mut u8) -> u32;
+
+// Not using a crate for this, the three functions needed are part of every unix libc.
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlerror() -> *const c_char;
+}
+
+const RTLD_NOW: c_int = 2;
+
+struct Plugin {
+    name: String,
+    sort_u64: SortU64Fn,
+    sort_by_cmp: SortByCmpFn,
+}
+
+fn last_dl_error() -> String {
+    // SAFETY: dlerror returns either null or a valid C string.
+    unsafe {
+        let err = dlerror();
+        if err.is_null() {
+            "unknown error".into()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    }
+}
+
+fn load_plugin(path: &str) -> Plugin {
+    let path_c = CString::new(path).unwrap();
+
+    // SAFETY: Loading a library runs its initializers, by setting SORT_PLUGINS the user trusts
+    // these libraries. The symbols are cast to the types documented in the module doc comment.
+    // The library is never closed, so the function pointers stay valid for the rest of the
+    // process.
+    unsafe {
+        let handle = dlopen(path_c.as_ptr(), RTLD_NOW);
+        if handle.is_null() {
+            panic!("Failed to load sort plugin {path}: {}", last_dl_error());
+        }
+
+        let symbol = |name: &CStr| -> *mut c_void {
+            let sym = dlsym(handle, name.as_ptr());
+            if sym.is_null() {
+                panic!("Sort plugin {path} does not export {name:?}");
+            }
+            sym
+        };
+
+        let sort_name = std::mem::transmute::<*mut c_void, SortNameFn>(symbol(c"sort_name"));
+        let sort_u64 = std::mem::transmute::<*mut c_void, SortU64Fn>(symbol(c"sort_u64"));
+        let sort_by_cmp = std::mem::transmute::<*mut c_void, SortByCmpFn>(symbol(c"sort_by_cmp"));
+
+        let name = CStr::from_ptr(sort_name()).to_string_lossy().into_owned();
+
+        Plugin {
+            name,
+            sort_u64,
+            sort_by_cmp,
+        }
+    }
+}
+
+fn plugins() -> &'static [Plugin] {
+    static PLUGINS: OnceLock<Vec<Plugin>> = OnceLock::new();
+
+    PLUGINS.get_or_init(|| {
+        env::var("SORT_PLUGINS")
+            .map(|paths| {
+                paths
+                    .split(':')
+                    .filter(|path| !path.is_empty())
+                    .map(load_plugin)
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+impl Plugin {
+    fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(&self, v: &mut [T], mut compare: F) {
+        if v.len() < 2 || std::mem::size_of::<T>() == 0 {
+            return;
+        }
+
+        // SAFETY: The plugin only permutes the elements of `v` and `compare` outlives the call.
+        let ret_code = unsafe {
+            (self.sort_by_cmp)(
+                v.as_mut_ptr() as *mut c_void,
+                v.len(),
+                std::mem::size_of::<T>(),
+                rust_fn_cmp_threeway::<T, F>,
+                &mut compare as *mut F as *mut u8,
+            )
+        };
+
+        handle_ffi_ret_code(ret_code, &self.name);
+    }
+
+    fn sort<T: Ord + 'static>(&self, v: &mut [T]) {
+        if TypeId::of::<T>() == TypeId::of::<u64>() {
+            // SAFETY: We just checked that T is u64.
+            unsafe {
+                (self.sort_u64)(v.as_mut_ptr() as *mut u64, v.len());
+            }
+        } else {
+            self.sort_by(v, |a, b| a.cmp(b));
+        }
+    }
+}
+
+/// Registry entries for all plugins listed in `SORT_PLUGINS`.
+pub fn entries<T: Ord + 'static>() -> Vec<SortEntry<T>> {
+    plugins()
+        .iter()
+        .map(|plugin| {
+            SortEntry::new(
+                plugin.name.clone(),
+                // The plugin interface declares stability by the name, see the module docs.
+                !plugin.name.contains("unstable"),
+                true,
+                Box::new(move |v| plugin.sort(v)),
+                Box::new(move |v, compare| plugin.sort_by(v, compare)),
+            )
+        })
+        .collect()
+}
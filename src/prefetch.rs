@@ -0,0 +1,51 @@
+//! Software prefetch hints for the prefetch variants of the Rust std sorts.
+//!
+//! The merge of `stable::rust_std` and the block partition of `unstable::rust_std` have a
+//! `PREFETCH` const parameter. With it set they prefetch the element [`DISTANCE_BYTES`] ahead of
+//! the one they read, on top of what the hardware prefetcher does for their sequential accesses.
+//! The `prefetch` feature registers the variants as `rust_std_stable_prefetch` and
+//! `rust_std_unstable_prefetch`, so every benchmark run has them next to the regular sorts.
+//! Elsewhere the parameter is false and the hints are compiled out.
+
+/// How far ahead of the current element the kernels prefetch, eight cache lines. Far enough to
+/// cover the latency of a memory access at the speed the kernels consume elements, close enough
+/// to not evict the lines before they are used.
+pub const DISTANCE_BYTES: usize = 512;
+
+/// Hints that the cache line of `ptr` will be read soon. Doesn't fault on any address, only the
+/// computation of `ptr` has to stay free of UB, see [`ahead`] and [`behind`].
+#[inline(always)]
+pub fn prefetch_read<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: SSE is part of the x86-64 baseline, and a prefetch has no side effects besides
+    // the cache.
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr.cast());
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    // SAFETY: PRFM is a hint and doesn't access memory architecturally.
+    unsafe {
+        std::arch::asm!(
+            "prfm pldl1keep, [{ptr}]",
+            ptr = in(reg) ptr,
+            options(nostack, preserves_flags, readonly)
+        );
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    let _ = ptr;
+}
+
+/// `ptr` moved [`DISTANCE_BYTES`] forward, may point past the slice.
+#[inline(always)]
+pub fn ahead<T>(ptr: *const T) -> *const T {
+    ptr.cast::<u8>().wrapping_add(DISTANCE_BYTES).cast()
+}
+
+/// `ptr` moved [`DISTANCE_BYTES`] backward, may point before the slice.
+#[inline(always)]
+pub fn behind<T>(ptr: *const T) -> *const T {
+    ptr.cast::<u8>().wrapping_sub(DISTANCE_BYTES).cast()
+}
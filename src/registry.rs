@@ -0,0 +1,357 @@
+//! Runtime list of all sort implementations that are enabled in the current build.
+//!
+//! Every C, C++, Go and third-party Rust sort has its own cargo feature, see Cargo.toml, so a
+//! build only includes the implementations whose toolchains and dependencies are available.
+//! Consumers such as the benchmarks iterate over [`sorts`] instead of hard-coding the list, and
+//! adapt to the enabled features automatically.
+
+use std::cmp::Ordering;
+
+use sort_test_tools::Sort;
+
+#[allow(unused_imports)]
+use crate::{other, stable, unstable};
+
+type SortFn<T> = Box<dyn Fn(&mut [T])>;
+type SortByFn<T> = Box<dyn Fn(&mut [T], &mut dyn FnMut(&T, &T) -> Ordering)>;
+
+/// A type-erased sort implementation for element type `T`.
+///
+/// [`SortEntry::sort`] costs one indirect call per invocation, the sort calls `Ord` directly.
+/// [`SortEntry::sort_by`] erases the comparison to a `&mut dyn FnMut`, which costs an indirect call
+/// per comparison and so differs between sorts. Measurements that depend on it call the [`Sort`]
+/// impls directly, see [`visit_sorts`].
+pub struct SortEntry<T> {
+    name: String,
+    /// Equal elements keep their order, see [`crate::analysis::stability`] for the check.
+    stable: bool,
+    /// The whole slice is sorted, for every len, with both `sort` and `sort_by`. Registry-wide
+    /// checks only make sense for these.
+    general: bool,
+    sort_fn: SortFn<T>,
+    sort_by_fn: SortByFn<T>,
+}
+
+impl<T> SortEntry<T> {
+    pub fn new(
+        name: String,
+        stable: bool,
+        general: bool,
+        sort_fn: SortFn<T>,
+        sort_by_fn: SortByFn<T>,
+    ) -> Self {
+        Self {
+            name,
+            stable,
+            general,
+            sort_fn,
+            sort_by_fn,
+        }
+    }
+
+    pub fn from_sort_impl<S: Sort>(stable: bool, general: bool) -> Self
+    where
+        T: Ord + 'static,
+    {
+        Self::new(
+            S::name(),
+            stable,
+            general,
+            Box::new(|v| S::sort(v)),
+            Box::new(|v, compare| S::sort_by(v, compare)),
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the sort is declared stable.
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    /// Whether the entry is a general-purpose sort. The selection entries only put the median in
+    /// place, they are benchmarked next to the sorts but don't sort. The radix and SIMD sorts only
+    /// sort their key types by value and panic in `sort_by`.
+    pub fn is_general(&self) -> bool {
+        self.general
+    }
+
+    #[inline]
+    pub fn sort(&self, v: &mut [T]) {
+        (self.sort_fn)(v);
+    }
+
+    #[inline]
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&self, v: &mut [T], mut compare: F) {
+        (self.sort_by_fn)(v, &mut compare);
+    }
+}
+
+/// Returns all sort implementations enabled in this build, in a stable order. The fixed-size
+/// small-sort kernels are not part of it, see [`small_sort_kernels`].
+pub fn sorts<T: Ord + 'static>() -> Vec<SortEntry<T>> {
+    struct Collect<T>(Vec<SortEntry<T>>);
+
+    impl<T: Ord + 'static> SortVisitor for Collect<T> {
+        fn visit<S: Sort>(&mut self, stable: bool, general: bool) {
+            self.0.push(SortEntry::from_sort_impl::<S>(stable, general));
+        }
+    }
+
+    let mut collect = Collect(Vec::new());
+    visit_sorts(&mut collect);
+
+    #[cfg(all(feature = "plugin", unix))]
+    collect.0.extend(crate::plugin::entries::<T>());
+
+    collect.0
+}
+
+/// Receives the [`Sort`] impls of the registry as types, for consumers that instantiate code per
+/// sort, eg. to hand every sort the same comparison in different forms. A [`SortEntry`] has
+/// already erased the comparison to a trait object.
+pub trait SortVisitor {
+    fn visit<S: Sort>(&mut self, stable: bool, general: bool);
+}
+
+/// Calls `visitor` for every [`Sort`] impl of [`sorts`], in the same order. Plugins are loaded at
+/// runtime and have no [`Sort`] impl, they are not visited.
+pub fn visit_sorts<V: SortVisitor>(visitor: &mut V) {
+    // Every entry declares its stability. Entries that are not general-purpose sorts are marked
+    // `special`, see [`SortEntry::is_general`].
+    macro_rules! visit {
+        ($sort_impl_path:path, $stable:expr, $general:expr) => {{
+            use $sort_impl_path::*;
+
+            visitor.visit::<SortImpl>($stable, $general);
+        }};
+    }
+
+    macro_rules! stable {
+        ($sort_impl_path:path) => {
+            visit!($sort_impl_path, true, true)
+        };
+        ($sort_impl_path:path, special) => {
+            visit!($sort_impl_path, true, false)
+        };
+    }
+
+    macro_rules! unstable {
+        ($sort_impl_path:path) => {
+            visit!($sort_impl_path, false, true)
+        };
+        ($sort_impl_path:path, special) => {
+            visit!($sort_impl_path, false, false)
+        };
+    }
+
+    // --- Stable sorts ---
+
+    stable!(stable::rust_std);
+
+    #[cfg(feature = "prefetch")]
+    stable!(stable::rust_std_prefetch);
+
+    #[cfg(feature = "cpp_std_sys")]
+    stable!(stable::cpp_std_sys);
+
+    #[cfg(feature = "cpp_std_libcxx")]
+    stable!(stable::cpp_std_libcxx);
+
+    #[cfg(feature = "cpp_std_gnu")]
+    stable!(stable::cpp_std_gnu);
+
+    #[cfg(feature = "cpp_std_msvc")]
+    stable!(stable::cpp_std_msvc);
+
+    #[cfg(feature = "cpp_std_gcc4_3")]
+    stable!(stable::cpp_std_gcc4_3);
+
+    #[cfg(feature = "cpp_powersort")]
+    stable!(stable::cpp_powersort);
+
+    #[cfg(feature = "cpp_powersort")]
+    stable!(stable::cpp_powersort_4way);
+
+    #[cfg(feature = "cpp_wikisort")]
+    stable!(stable::cpp_wikisort);
+
+    #[cfg(feature = "c_fluxsort")]
+    stable!(stable::c_fluxsort);
+
+    #[cfg(feature = "golang_std")]
+    stable!(stable::golang_std);
+
+    #[cfg(feature = "rust_wpwoodjr")]
+    stable!(stable::rust_wpwoodjr);
+
+    #[cfg(feature = "rust_glidesort")]
+    stable!(stable::rust_glidesort);
+
+    // #[cfg(feature = "rust_driftsort")]
+    // stable!(stable::rust_driftsort);
+
+    #[cfg(feature = "rust_tinysort")]
+    stable!(stable::rust_tinysort);
+
+    #[cfg(feature = "rust_grailsort")]
+    stable!(stable::rust_grailsort);
+
+    #[cfg(feature = "rust_dupsort")]
+    stable!(stable::rust_dupsort);
+
+    #[cfg(feature = "rust_branchless_merge_exp")]
+    stable!(stable::rust_branchless_merge_exp);
+
+    // --- Unstable sorts ---
+
+    unstable!(unstable::rust_ipnsort);
+
+    unstable!(unstable::rust_std);
+
+    #[cfg(feature = "prefetch")]
+    unstable!(unstable::rust_std_prefetch);
+
+    #[cfg(feature = "pivot_strategies")]
+    {
+        unstable!(unstable::rust_std_pivot::median3);
+        unstable!(unstable::rust_std_pivot::ninther);
+        unstable!(unstable::rust_std_pivot::pseudo_median27);
+        unstable!(unstable::rust_std_pivot::random);
+    }
+
+    #[cfg(feature = "rust_dmsort")]
+    unstable!(unstable::rust_dmsort);
+
+    #[cfg(feature = "rust_crumsort_rs")]
+    unstable!(unstable::rust_crumsort_rs);
+
+    #[cfg(feature = "rust_tinysort")]
+    unstable!(unstable::rust_tinysort);
+
+    #[cfg(feature = "rust_introsort")]
+    unstable!(unstable::rust_introsort);
+
+    #[cfg(feature = "cpp_pdqsort")]
+    unstable!(unstable::cpp_pdqsort);
+
+    #[cfg(feature = "cpp_ips4o")]
+    unstable!(unstable::cpp_ips4o);
+
+    #[cfg(feature = "cpp_blockquicksort")]
+    unstable!(unstable::cpp_blockquicksort);
+
+    #[cfg(feature = "cpp_gerbens_qsort")]
+    unstable!(unstable::cpp_gerbens_qsort);
+
+    #[cfg(feature = "cpp_nanosort")]
+    unstable!(unstable::cpp_nanosort);
+
+    #[cfg(feature = "c_std_sys")]
+    unstable!(unstable::c_std_sys);
+
+    #[cfg(feature = "c_crumsort")]
+    unstable!(unstable::c_crumsort);
+
+    #[cfg(feature = "cpp_std_sys")]
+    unstable!(unstable::cpp_std_sys);
+
+    #[cfg(feature = "cpp_std_libcxx")]
+    unstable!(unstable::cpp_std_libcxx);
+
+    #[cfg(feature = "cpp_std_gnu")]
+    unstable!(unstable::cpp_std_gnu);
+
+    #[cfg(feature = "cpp_std_msvc")]
+    unstable!(unstable::cpp_std_msvc);
+
+    #[cfg(feature = "cpp_std_gcc4_3")]
+    unstable!(unstable::cpp_std_gcc4_3);
+
+    #[cfg(feature = "golang_std")]
+    unstable!(unstable::golang_std);
+
+    // --- Other sorts ---
+
+    #[cfg(feature = "rust_radsort")]
+    stable!(other::rust_radsort, special);
+
+    #[cfg(feature = "rust_afsort")]
+    unstable!(other::rust_afsort, special);
+
+    #[cfg(feature = "cpp_simdsort")]
+    unstable!(other::cpp_simdsort, special);
+
+    #[cfg(feature = "cpp_vqsort")]
+    unstable!(other::cpp_vqsort, special);
+
+    #[cfg(feature = "cpp_intel_avx512")]
+    unstable!(other::cpp_intel_avx512, special);
+
+    #[cfg(feature = "singeli_singelisort")]
+    unstable!(other::singeli_singelisort, special);
+
+    #[cfg(feature = "evolution")]
+    {
+        stable!(other::sort_evolution::stable::timsort_evo0);
+        stable!(other::sort_evolution::stable::timsort_evo1);
+        stable!(other::sort_evolution::stable::timsort_evo2);
+        stable!(other::sort_evolution::stable::timsort_evo3);
+        stable!(other::sort_evolution::stable::timsort_evo4);
+
+        unstable!(other::sort_evolution::unstable::quicksort_evo0);
+        unstable!(other::sort_evolution::unstable::quicksort_stack_evo0);
+
+        unstable!(other::sort_evolution::other::bucket_btree);
+        unstable!(other::sort_evolution::other::bucket_hash);
+        unstable!(other::sort_evolution::other::bucket_match);
+        unstable!(other::sort_evolution::other::bucket_branchless);
+        unstable!(other::sort_evolution::other::bucket_phf);
+    }
+
+    #[cfg(feature = "selection")]
+    {
+        unstable!(other::selection::rust_ipnsort, special);
+        unstable!(other::selection::rust_std, special);
+    }
+}
+
+/// Returns the small-sort kernels enabled in this build, with the len they sort. They panic for
+/// any other len, so they are kept out of [`sorts`].
+#[cfg(feature = "small_sort")]
+pub fn small_sort_kernels<T: Ord + 'static>() -> Vec<(usize, SortEntry<T>)> {
+    macro_rules! kernel {
+        ($len:expr, $name:ident, $stable:expr) => {{
+            use other::small_sort::$name::*;
+
+            ($len, SortEntry::from_sort_impl::<SortImpl>($stable, false))
+        }};
+    }
+
+    vec![
+        kernel!(4, sort4_unstable_cmp_swap, false),
+        kernel!(4, sort4_unstable_ptr_select, false),
+        kernel!(4, sort4_unstable_branchy, false),
+        kernel!(4, sort4_stable_orson, true),
+        kernel!(4, sort4_unstable_neon, false),
+        kernel!(8, sort8_unstable_neon, false),
+        kernel!(10, sort10_unstable_cmp_swaps, false),
+        kernel!(10, sort10_unstable_experimental, false),
+        kernel!(10, sort10_unstable_ptr_select, false),
+    ]
+}
+
+/// Same as [`sorts`] without the entries that are not general-purpose sorts, for the checks that
+/// run every sort on arbitrary inputs.
+pub fn general_sorts<T: Ord + 'static>() -> Vec<SortEntry<T>> {
+    let mut entries = sorts::<T>();
+    entries.retain(SortEntry::is_general);
+    entries
+}
+
+/// Looks up a single sort implementation by its [`Sort::name`].
+pub fn find<T: Ord + 'static>(name: &str) -> Option<SortEntry<T>> {
+    sorts::<T>().into_iter().find(|entry| entry.name() == name)
+}
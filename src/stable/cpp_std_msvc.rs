@@ -0,0 +1 @@
+ffi_sort_impl!("cpp_std_msvc_stable", sort_stable_msvc);
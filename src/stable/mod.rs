@@ -1,5 +1,13 @@
+#[cfg_attr(
+    feature = "strict_provenance",
+    deny(fuzzy_provenance_casts, lossy_provenance_casts)
+)]
 pub mod rust_std;
 
+// rust_std with software prefetching in its kernels.
+#[cfg(feature = "prefetch")]
+pub mod rust_std_prefetch;
+
 #[cfg(feature = "rust_wpwoodjr")]
 pub mod rust_wpwoodjr;
 
@@ -15,6 +23,13 @@ pub mod rust_tinysort;
 #[cfg(feature = "rust_grailsort")]
 pub mod rust_grailsort;
 
+#[cfg(feature = "rust_dupsort")]
+pub mod rust_dupsort;
+
+// The branchless merge techniques of quadsort and fluxsort in Rust.
+#[cfg(feature = "rust_branchless_merge_exp")]
+pub mod rust_branchless_merge_exp;
+
 // Call stdlib std::sort_stable sort via FFI.
 #[cfg(feature = "cpp_std_sys")]
 pub mod cpp_std_sys;
@@ -23,6 +38,14 @@ pub mod cpp_std_sys;
 #[cfg(feature = "cpp_std_libcxx")]
 pub mod cpp_std_libcxx;
 
+// Call stdlib std::sort_stable sort via FFI.
+#[cfg(feature = "cpp_std_gnu")]
+pub mod cpp_std_gnu;
+
+// Call stdlib std::sort_stable sort via FFI.
+#[cfg(feature = "cpp_std_msvc")]
+pub mod cpp_std_msvc;
+
 // Call stdlib std::sort_stable sort via FFI.
 #[cfg(feature = "cpp_std_gcc4_3")]
 pub mod cpp_std_gcc4_3;
@@ -0,0 +1,269 @@
+//! Experiment: the branchless merge techniques of quadsort and fluxsort by Igor van den Hoven, in
+//! Rust.
+//!
+//! `c_fluxsort` is often faster than the Rust stable sorts, but it differs from them in algorithm
+//! and compiler at once. This sort takes only the techniques, so comparing it to `c_fluxsort` and
+//! `rust_std_stable` attributes the difference to one or the other:
+//!
+//! - Blocks of four elements are sorted by a stable odd-even transposition network of branchless
+//!   conditional swaps, as `parity_swap_four` does.
+//! - Runs are merged bottom-up, alternating between the input and a buffer of the same len. Each
+//!   pass merges from one into the other, the ping-pong merge, so no pass copies the runs back.
+//! - Runs of equal len are merged with the parity merge, from the front and the back at the same
+//!   time. Neither side needs a bounds check, after `len / 2` steps each the two sides meet.
+//! - Every merge step selects the source pointer instead of branching on the comparison, which
+//!   compiles to a conditional move.
+//! - Two runs that are already in order are copied without merging, which makes presorted inputs
+//!   cost one comparison per merge.
+//!
+//! The parity merge compares elements after one side has copied them, types with interior
+//! mutability use the forward merge instead, see [`Freeze`].
+
+use std::cmp::Ordering;
+use std::mem;
+use std::ptr;
+
+use crate::other::batch_sort::insertion_sort_by;
+use crate::other::branchless::swap_if_less;
+
+sort_impl!("rust_branchless_merge_exp_stable");
+
+pub fn sort<T: Ord>(v: &mut [T]) {
+    merge_sort(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    merge_sort(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Can the type have interior mutability, this is checked by testing if T is Freeze. Same as in
+/// other::partition::small_partition.
+///
+/// # Safety
+///
+/// Implementors must not have interior mutability, a shared reference to the value must not be
+/// able to change it. The parity merge copies an element into the output and may compare the
+/// original afterwards, a change made by the comparison function at that point would be lost, for a
+/// type like Mutex<Option<Box<str>>> that is a double free. Only implement it for types that hold
+/// no `UnsafeCell` outside of an indirection.
+unsafe auto trait Freeze {}
+
+impl<T: ?Sized> !Freeze for std::cell::UnsafeCell<T> {}
+unsafe impl<T: ?Sized> Freeze for std::marker::PhantomData<T> {}
+unsafe impl<T: ?Sized> Freeze for *const T {}
+unsafe impl<T: ?Sized> Freeze for *mut T {}
+unsafe impl<T: ?Sized> Freeze for &T {}
+unsafe impl<T: ?Sized> Freeze for &mut T {}
+
+const BLOCK_LEN: usize = 4;
+
+fn merge_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    if len < 2 || mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let v_ptr = v.as_mut_ptr();
+    let blocks_end = len - (len % BLOCK_LEN);
+    for start in (0..blocks_end).step_by(BLOCK_LEN) {
+        // SAFETY: `start + BLOCK_LEN <= len`.
+        unsafe { sort4_stable(v_ptr.add(start), is_less) };
+    }
+    insertion_sort_by(&mut v[blocks_end..], is_less);
+
+    if len <= BLOCK_LEN {
+        return;
+    }
+
+    let mut buf = Vec::<T>::with_capacity(len);
+    let scratch = buf.as_mut_ptr();
+
+    let mut src = v_ptr;
+    let mut dst = scratch;
+    let mut run_len = BLOCK_LEN;
+    while run_len < len {
+        // If the comparison function panics while the scratch holds the elements, they are copied
+        // back, `v` may hold duplicates at that point.
+        let guard = (src == scratch).then(|| CopyOnDrop {
+            src: scratch,
+            dst: v_ptr,
+            len,
+        });
+
+        // SAFETY: `src` and `dst` are valid for `len` elements and don't overlap, and `src` holds
+        // each element exactly once.
+        unsafe { merge_pass(src, dst, len, run_len, is_less) };
+        mem::forget(guard);
+
+        mem::swap(&mut src, &mut dst);
+        run_len *= 2;
+    }
+
+    if src == scratch {
+        // SAFETY: The scratch holds all elements, see above.
+        unsafe { ptr::copy_nonoverlapping(scratch, v_ptr, len) };
+    }
+}
+
+/// Merges the pairs of sorted runs of `run_len` in `src` into `dst`, the last run may be shorter.
+unsafe fn merge_pass<T, F: FnMut(&T, &T) -> bool>(
+    src: *const T,
+    dst: *mut T,
+    len: usize,
+    run_len: usize,
+    is_less: &mut F,
+) {
+    let mut start = 0;
+    while start < len {
+        let mid = (start + run_len).min(len);
+        let end = (mid + run_len).min(len);
+        let (left, right, out) = (src.add(start), src.add(mid), dst.add(start));
+
+        if mid == end || !is_less(&*right, &*left.add(mid - start - 1)) {
+            // Already in order, or a single run at the end.
+            ptr::copy_nonoverlapping(left, out, end - start);
+        } else if end - mid == run_len {
+            T::merge_equal(left, run_len, out, is_less);
+        } else {
+            merge_forward(left, mid - start, right, end - mid, out, is_less);
+        }
+
+        start = end;
+    }
+}
+
+trait MergeEqual: Sized {
+    /// Merges the sorted runs `src[..half]` and `src[half..half * 2]` into `dst`.
+    unsafe fn merge_equal<F: FnMut(&Self, &Self) -> bool>(
+        src: *const Self,
+        half: usize,
+        dst: *mut Self,
+        is_less: &mut F,
+    );
+}
+
+impl<T> MergeEqual for T {
+    default unsafe fn merge_equal<F: FnMut(&T, &T) -> bool>(
+        src: *const T,
+        half: usize,
+        dst: *mut T,
+        is_less: &mut F,
+    ) {
+        merge_forward(src, half, src.add(half), half, dst, is_less);
+    }
+}
+
+impl<T: Freeze> MergeEqual for T {
+    unsafe fn merge_equal<F: FnMut(&T, &T) -> bool>(
+        src: *const T,
+        half: usize,
+        dst: *mut T,
+        is_less: &mut F,
+    ) {
+        parity_merge(src, half, dst, is_less);
+    }
+}
+
+/// The parity merge of quadsort: `half` steps from the front and `half` steps from the back fill
+/// `dst` without any bounds check.
+///
+/// After `k` steps from the front, `k` elements of the two runs were taken, so the front reads at
+/// most `half - 1` elements into either run, the same holds for the back. With a comparison
+/// function that is not a total order the two sides can take the same element, which is detected
+/// once they meet.
+unsafe fn parity_merge<T, F: FnMut(&T, &T) -> bool>(
+    src: *const T,
+    half: usize,
+    dst: *mut T,
+    is_less: &mut F,
+) {
+    let mut left = src;
+    let mut right = src.add(half);
+    let mut out = dst;
+
+    let mut left_rev = src.add(half - 1);
+    let mut right_rev = src.add(half * 2 - 1);
+    let mut out_rev = dst.add(half * 2 - 1);
+
+    for _ in 0..half {
+        // The left element goes first if equal.
+        let take_left = !is_less(&*right, &*left);
+        let from = if take_left { left } else { right };
+        ptr::copy_nonoverlapping(from, out, 1);
+        left = left.add(take_left as usize);
+        right = right.add(!take_left as usize);
+        out = out.add(1);
+
+        // The right element goes last if equal.
+        let take_right = !is_less(&*right_rev, &*left_rev);
+        let from = if take_right { right_rev } else { left_rev };
+        ptr::copy_nonoverlapping(from, out_rev, 1);
+        right_rev = right_rev.wrapping_sub(take_right as usize);
+        left_rev = left_rev.wrapping_sub(!take_right as usize);
+        out_rev = out_rev.wrapping_sub(1);
+    }
+
+    if left != left_rev.wrapping_add(1) || right != right_rev.wrapping_add(1) {
+        panic_on_ord_violation();
+    }
+}
+
+/// Branchless merge of `left[..left_len]` and `right[..right_len]` into `dst`, with a bounds check
+/// per step. Every element is compared only before it is copied.
+unsafe fn merge_forward<T, F: FnMut(&T, &T) -> bool>(
+    mut left: *const T,
+    left_len: usize,
+    mut right: *const T,
+    right_len: usize,
+    mut dst: *mut T,
+    is_less: &mut F,
+) {
+    let left_end = left.add(left_len);
+    let right_end = right.add(right_len);
+
+    while left < left_end && right < right_end {
+        let take_left = !is_less(&*right, &*left);
+        let from = if take_left { left } else { right };
+        ptr::copy_nonoverlapping(from, dst, 1);
+        left = left.add(take_left as usize);
+        right = right.add(!take_left as usize);
+        dst = dst.add(1);
+    }
+
+    let left_rest = left_end.offset_from(left) as usize;
+    ptr::copy_nonoverlapping(left, dst, left_rest);
+    ptr::copy_nonoverlapping(
+        right,
+        dst.add(left_rest),
+        right_end.offset_from(right) as usize,
+    );
+}
+
+/// Sorts `v[..4]` with the rounds (0, 1) (2, 3), (1, 2), (0, 1) (2, 3), (1, 2). Only adjacent
+/// elements are swapped and only if they are out of order, so the network is stable.
+unsafe fn sort4_stable<T, F: FnMut(&T, &T) -> bool>(v: *mut T, is_less: &mut F) {
+    swap_if_less(v, 0, 1, is_less);
+    swap_if_less(v, 2, 3, is_less);
+    swap_if_less(v, 1, 2, is_less);
+    swap_if_less(v, 0, 1, is_less);
+    swap_if_less(v, 2, 3, is_less);
+    swap_if_less(v, 1, 2, is_less);
+}
+
+struct CopyOnDrop<T> {
+    src: *const T,
+    dst: *mut T,
+    len: usize,
+}
+
+impl<T> Drop for CopyOnDrop<T> {
+    fn drop(&mut self) {
+        // SAFETY: `src` holds every element once and `dst` is valid for `len` writes.
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dst, self.len) };
+    }
+}
+
+#[inline(never)]
+fn panic_on_ord_violation() -> ! {
+    panic!("Ord violation");
+}
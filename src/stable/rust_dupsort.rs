@@ -0,0 +1,198 @@
+//! Experiment: a stable sort that detects inputs with few distinct values and exploits them.
+//!
+//! A merge sort does *O*(*n* \* log(*n*)) comparisons no matter how many distinct values there
+//! are. With *k* distinct values a quicksort that splits every slice into less, equal and greater
+//! than the pivot and never looks at the equal range again, needs only *O*(*n* \* log(*k*)). The
+//! partitions use a buffer of len *n* to stay stable.
+//!
+//! Large inputs are sampled first, if less than half of the sampled elements are distinct the
+//! three-way quicksort is used, otherwise the sort is the same as `stable::rust_std`. The
+//! low-cardinality patterns of `EXTRA_PATTERNS=1` show the difference, see the README.
+
+use std::cmp::Ordering;
+use std::ptr;
+
+use crate::other::batch_sort::insertion_sort_by;
+
+sort_impl!("rust_dupsort_stable");
+
+pub fn sort<T: Ord>(v: &mut [T]) {
+    stable_sort(v, &mut T::cmp);
+}
+
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    stable_sort(v, &mut compare);
+}
+
+// Below this len the sampling doesn't pay off.
+const MIN_DETECT_LEN: usize = 1024;
+
+const SAMPLE_LEN: usize = 64;
+
+// Slices up to this len are sorted with insertion sort by the three-way quicksort.
+const SMALL_SORT_THRESHOLD: usize = 20;
+
+fn stable_sort<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: &mut F) {
+    if v.len() < MIN_DETECT_LEN || !has_many_duplicates(v, &mut is_less_by(compare)) {
+        fallback_sort(v, compare);
+        return;
+    }
+
+    let len = v.len();
+    let mut buf = Vec::<T>::with_capacity(len);
+    let mut classes = vec![Class::Equal; len];
+
+    // Beyond 2 * log2(len) levels the pivots were bad, the slices are then handed to the merge sort
+    // which is stable and guarantees *O*(*n* \* log(*n*)).
+    let limit = 2 * (usize::BITS - len.leading_zeros());
+    quicksort(v, &mut buf, &mut classes, limit, compare);
+}
+
+fn fallback_sort<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: &mut F) {
+    crate::stable::rust_std::sort_by(v, compare);
+}
+
+// Every call of `is_less` is one call of `compare`, the comparison counts stay comparable to the
+// other sorts.
+fn is_less_by<T, F: FnMut(&T, &T) -> Ordering>(compare: &mut F) -> impl FnMut(&T, &T) -> bool + '_ {
+    |a, b| compare(a, b) == Ordering::Less
+}
+
+/// Sorts the elements at `SAMPLE_LEN` evenly spaced positions by index and returns `true` if at
+/// most half of them are distinct.
+fn has_many_duplicates<T, F: FnMut(&T, &T) -> bool>(v: &[T], is_less: &mut F) -> bool {
+    let step = v.len() / SAMPLE_LEN;
+    let mut sample: [usize; SAMPLE_LEN] = std::array::from_fn(|i| i * step);
+
+    // Insertion sort by index, the sample is too short to bother with anything else.
+    for i in 1..SAMPLE_LEN {
+        let mut j = i;
+        while j > 0 && is_less(&v[sample[j]], &v[sample[j - 1]]) {
+            sample.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+
+    let distinct = 1 + sample
+        .windows(2)
+        .filter(|w| is_less(&v[w[0]], &v[w[1]]))
+        .count();
+
+    distinct * 2 <= SAMPLE_LEN
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Less = 0,
+    Equal = 1,
+    Greater = 2,
+}
+
+fn quicksort<T, F: FnMut(&T, &T) -> Ordering>(
+    mut v: &mut [T],
+    buf: &mut Vec<T>,
+    classes: &mut [Class],
+    mut limit: u32,
+    compare: &mut F,
+) {
+    loop {
+        let len = v.len();
+
+        if len <= SMALL_SORT_THRESHOLD {
+            insertion_sort_by(v, &mut is_less_by(compare));
+            return;
+        }
+
+        if limit == 0 {
+            fallback_sort(v, compare);
+            return;
+        }
+        limit -= 1;
+
+        let pivot_pos = median_of_three(v, &mut is_less_by(compare));
+        let (lt_len, eq_len) = partition3(v, buf, &mut classes[..len], pivot_pos, compare);
+
+        // The equal elements are in their final position. Recurse into the shorter side and loop
+        // on the longer one, to bound the stack depth.
+        let (left, rest) = v.split_at_mut(lt_len);
+        let right = &mut rest[eq_len..];
+
+        if left.len() < right.len() {
+            quicksort(left, buf, classes, limit, compare);
+            v = right;
+        } else {
+            quicksort(right, buf, classes, limit, compare);
+            v = left;
+        }
+    }
+}
+
+fn median_of_three<T, F: FnMut(&T, &T) -> bool>(v: &[T], is_less: &mut F) -> usize {
+    let len = v.len();
+    let (a, b, c) = (len / 4, len / 2, len / 4 * 3);
+
+    let ab = is_less(&v[a], &v[b]);
+    let bc = is_less(&v[b], &v[c]);
+    if ab == bc {
+        return b;
+    }
+
+    let ac = is_less(&v[a], &v[c]);
+    if ab == ac {
+        c
+    } else {
+        a
+    }
+}
+
+/// Stable partition of `v` into the elements less than, equal to and greater than
+/// `v[pivot_pos]`. Returns the len of the less and equal ranges.
+///
+/// Each element is compared to the pivot once. All comparisons are done before any element is
+/// moved, so a panic in `compare` leaves `v` untouched, and changes made by `compare` through
+/// interior mutability are not lost by moving a stale copy.
+fn partition3<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    buf: &mut Vec<T>,
+    classes: &mut [Class],
+    pivot_pos: usize,
+    compare: &mut F,
+) -> (usize, usize) {
+    let len = v.len();
+    let mut counts = [0usize; 3];
+
+    {
+        let pivot = &v[pivot_pos];
+        for (elem, class) in v.iter().zip(classes.iter_mut()) {
+            *class = match compare(elem, pivot) {
+                Ordering::Less => Class::Less,
+                Ordering::Equal => Class::Equal,
+                Ordering::Greater => Class::Greater,
+            };
+            counts[*class as usize] += 1;
+        }
+    }
+
+    let mut offsets = [0, counts[0], counts[0] + counts[1]];
+
+    // SAFETY: `buf` has capacity for at least `len` elements and its len stays 0, so it never
+    // drops the copies. Every element of `v` is copied to a distinct position of `buf`, the
+    // offsets partition 0..len. `v` is then overwritten with the permuted bitwise copies, which
+    // leaves each element exactly once in `v`. No user code runs between the first copy and the
+    // copy back.
+    unsafe {
+        debug_assert!(buf.capacity() >= len);
+        let src = v.as_mut_ptr();
+        let dst = buf.as_mut_ptr();
+
+        for (i, &class) in classes.iter().enumerate() {
+            let offset = &mut offsets[class as usize];
+            ptr::copy_nonoverlapping(src.add(i), dst.add(*offset), 1);
+            *offset += 1;
+        }
+
+        ptr::copy_nonoverlapping(dst, src, len);
+    }
+
+    (counts[0], counts[1])
+}
@@ -2,7 +2,9 @@
 
 use std::cmp::Ordering;
 use std::mem::{self, size_of};
-use std::ptr;
+
+use crate::instrument::ptr;
+use crate::prefetch;
 
 sort_impl!("rust_std_stable");
 
@@ -37,7 +39,7 @@ pub fn sort<T>(arr: &mut [T])
 where
     T: Ord,
 {
-    merge_sort(arr, |a, b| a.lt(b));
+    merge_sort::<_, _, false>(arr, |a, b| a.lt(b));
 }
 
 /// Sorts the slice with a comparator function.
@@ -90,7 +92,7 @@ pub fn sort_by<T, F>(arr: &mut [T], mut compare: F)
 where
     F: FnMut(&T, &T) -> Ordering,
 {
-    merge_sort(arr, |a, b| compare(a, b) == Ordering::Less);
+    merge_sort::<_, _, false>(arr, |a, b| compare(a, b) == Ordering::Less);
 }
 
 /// This merge sort borrows some (but not all) ideas from TimSort, which is described in detail
@@ -105,7 +107,9 @@ where
 /// 2. for every `i` in `2..runs.len()`: `runs[i - 2].len > runs[i - 1].len + runs[i].len`
 ///
 /// The invariants ensure that the total running time is *O*(*n* \* log(*n*)) worst-case.
-fn merge_sort<T, F>(v: &mut [T], mut is_less: F)
+///
+/// With `PREFETCH` the merges prefetch ahead of their reads from `v`, see [`crate::prefetch`].
+pub(crate) fn merge_sort<T, F, const PREFETCH: bool>(v: &mut [T], mut is_less: F)
 where
     F: FnMut(&T, &T) -> bool,
 {
@@ -124,9 +128,11 @@ where
     // Short arrays get sorted in-place via insertion sort to avoid allocations.
     if len <= MAX_INSERTION {
         if len >= 2 {
-            for i in (0..len - 1).rev() {
-                insert_head(&mut v[i..], &mut is_less);
-            }
+            timed_phase!(SmallSort, {
+                for i in (0..len - 1).rev() {
+                    insert_head(&mut v[i..], &mut is_less);
+                }
+            });
         }
         return;
     }
@@ -145,30 +151,51 @@ where
     let mut end = len;
     while end > 0 {
         // Find the next natural run, and reverse it if it's strictly descending.
-        let mut start = end - 1;
-        if start > 0 {
-            start -= 1;
-            unsafe {
-                if is_less(v.get_unchecked(start + 1), v.get_unchecked(start)) {
-                    while start > 0 && is_less(v.get_unchecked(start), v.get_unchecked(start - 1)) {
-                        start -= 1;
-                    }
-                    v[start..end].reverse();
-                } else {
-                    while start > 0 && !is_less(v.get_unchecked(start), v.get_unchecked(start - 1))
+        let mut start = timed_phase!(RunDetection, {
+            let mut start = end - 1;
+            if start > 0 {
+                start -= 1;
+                unsafe {
+                    if branch_outcome!(is_less(v.get_unchecked(start + 1), v.get_unchecked(start)))
                     {
-                        start -= 1;
+                        while start > 0
+                            && branch_outcome!(is_less(
+                                v.get_unchecked(start),
+                                v.get_unchecked(start - 1)
+                            ))
+                        {
+                            start -= 1;
+                        }
+                        ptr::slice_reverse(&mut v[start..end]);
+                    } else {
+                        while start > 0
+                            && !branch_outcome!(is_less(
+                                v.get_unchecked(start),
+                                v.get_unchecked(start - 1)
+                            ))
+                        {
+                            start -= 1;
+                        }
                     }
                 }
             }
-        }
+            start
+        });
+
+        #[cfg(feature = "instrument_runs")]
+        crate::instrument::runs::on_run(end - start, start > 0 && end - start < MIN_RUN);
 
         // Insert some more elements into the run if it's too short. Insertion sort is faster than
         // merge sort on short sequences, so this significantly improves performance.
-        while start > 0 && end - start < MIN_RUN {
-            start -= 1;
-            insert_head(&mut v[start..end], &mut is_less);
+        if start > 0 && end - start < MIN_RUN {
+            fallback!(ShortRunExtension);
         }
+        timed_phase!(SmallSort, {
+            while start > 0 && end - start < MIN_RUN {
+                start -= 1;
+                insert_head(&mut v[start..end], &mut is_less);
+            }
+        });
 
         // Push this run onto the stack.
         runs.push(Run {
@@ -181,14 +208,18 @@ where
         while let Some(r) = collapse(&runs) {
             let left = runs[r + 1];
             let right = runs[r];
-            unsafe {
-                merge(
+
+            #[cfg(feature = "instrument_runs")]
+            crate::instrument::runs::on_merge(left.len + right.len);
+
+            timed_phase!(Merge, unsafe {
+                merge::<_, _, PREFETCH>(
                     &mut v[left.start..right.start + right.len],
                     left.len,
                     buf.as_mut_ptr(),
                     &mut is_less,
                 );
-            }
+            });
             runs[r] = Run {
                 start: left.start,
                 len: left.len + right.len,
@@ -247,7 +278,7 @@ fn insert_head<T, F>(v: &mut [T], is_less: &mut F)
 where
     F: FnMut(&T, &T) -> bool,
 {
-    if v.len() >= 2 && is_less(&v[1], &v[0]) {
+    if v.len() >= 2 && branch_outcome!(is_less(&v[1], &v[0])) {
         unsafe {
             // There are three ways to implement insertion here:
             //
@@ -285,14 +316,17 @@ where
             ptr::copy_nonoverlapping(&v[1], &mut v[0], 1);
 
             for i in 2..v.len() {
-                if !is_less(&v[i], &*tmp) {
+                if !branch_outcome!(is_less(&v[i], &*tmp)) {
                     break;
                 }
                 ptr::copy_nonoverlapping(&v[i], &mut v[i - 1], 1);
                 hole.dest = &mut v[i];
             }
+            insertion_displacement!(hole.dest.offset_from(v.as_ptr()) as usize);
             // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
         }
+    } else {
+        insertion_displacement!(0);
     }
 
     // When dropped, copies from `src` into `dest`.
@@ -317,7 +351,7 @@ where
 ///
 /// The two slices must be non-empty and `mid` must be in bounds. Buffer `buf` must be long enough
 /// to hold a copy of the shorter slice. Also, `T` must not be a zero-sized type.
-unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+unsafe fn merge<T, F, const PREFETCH: bool>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
 where
     F: FnMut(&T, &T) -> bool,
 {
@@ -368,9 +402,15 @@ where
             // Consume the lesser side.
             // If equal, prefer the left run to maintain stability.
 
+            // The shorter run was just copied to `buf` and is in the cache, only the longer run is
+            // read from memory.
+            if PREFETCH {
+                prefetch::prefetch_read(prefetch::ahead(right));
+            }
+
             // SAFETY: left and right must be valid and part of v same for out.
             unsafe {
-                let is_l = is_less(&*right, &**left);
+                let is_l = branch_outcome!(is_less(&*right, &**left));
                 let to_copy = if is_l { right } else { *left };
                 ptr::copy_nonoverlapping(to_copy, *out, 1);
                 *out = out.add(1);
@@ -400,9 +440,13 @@ where
             // Consume the greater side.
             // If equal, prefer the right run to maintain stability.
 
+            if PREFETCH {
+                prefetch::prefetch_read(prefetch::behind(*left));
+            }
+
             // SAFETY: left and right must be valid and part of v same for out.
             unsafe {
-                let is_l = is_less(&*right.sub(1), &*left.sub(1));
+                let is_l = branch_outcome!(is_less(&*right.sub(1), &*left.sub(1)));
                 *left = left.sub(is_l as usize);
                 *right = right.sub(!is_l as usize);
                 let to_copy = if is_l { *left } else { *right };
@@ -0,0 +1,23 @@
+//! `stable::rust_std` with software prefetching in the merges, see [`crate::prefetch`].
+
+use std::cmp::Ordering;
+
+use super::rust_std::merge_sort;
+
+sort_impl!("rust_std_stable_prefetch");
+
+#[inline]
+pub fn sort<T>(arr: &mut [T])
+where
+    T: Ord,
+{
+    merge_sort::<_, _, true>(arr, |a, b| a.lt(b));
+}
+
+#[inline]
+pub fn sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort::<_, _, true>(arr, |a, b| compare(a, b) == Ordering::Less);
+}
@@ -1 +1 @@
-ffi_sort_impl!("c_crumsort_unstable", crumsort_unstable);
+ffi_sort_impl!("c_crumsort_unstable", crumsort_unstable, threeway);
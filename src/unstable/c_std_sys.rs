@@ -1 +1 @@
-ffi_sort_impl!("c_std_sys_unstable", qsort_unstable);
+ffi_sort_impl!("c_std_sys_unstable", qsort_unstable, threeway);
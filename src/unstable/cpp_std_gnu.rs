@@ -0,0 +1 @@
+ffi_sort_impl!("cpp_std_gnu_unstable", sort_unstable_gnu);
@@ -0,0 +1 @@
+ffi_sort_impl!("cpp_std_msvc_unstable", sort_unstable_msvc);
@@ -1,6 +1,18 @@
 pub mod rust_ipnsort;
+#[cfg_attr(
+    feature = "strict_provenance",
+    deny(fuzzy_provenance_casts, lossy_provenance_casts)
+)]
 pub mod rust_std;
 
+// rust_std with software prefetching in its kernels.
+#[cfg(feature = "prefetch")]
+pub mod rust_std_prefetch;
+
+// rust_std with other pivot selections.
+#[cfg(feature = "pivot_strategies")]
+pub mod rust_std_pivot;
+
 #[cfg(feature = "rust_dmsort")]
 pub mod rust_dmsort;
 
@@ -49,6 +61,14 @@ pub mod cpp_std_sys;
 #[cfg(feature = "cpp_std_libcxx")]
 pub mod cpp_std_libcxx;
 
+// Call stdlib std::sort sort via FFI.
+#[cfg(feature = "cpp_std_gnu")]
+pub mod cpp_std_gnu;
+
+// Call stdlib std::sort sort via FFI.
+#[cfg(feature = "cpp_std_msvc")]
+pub mod cpp_std_msvc;
+
 // Call stdlib std::sort sort via FFI.
 #[cfg(feature = "cpp_std_gcc4_3")]
 pub mod cpp_std_gcc4_3;
@@ -9,7 +9,9 @@
 use std::cmp;
 use std::cmp::Ordering;
 use std::mem::{self, MaybeUninit};
-use std::ptr;
+
+use crate::instrument::ptr;
+use crate::prefetch;
 
 sort_impl!("rust_std_unstable");
 
@@ -44,7 +46,7 @@ pub fn sort<T>(arr: &mut [T])
 where
     T: Ord,
 {
-    quicksort(arr, |a, b| a.lt(b));
+    quicksort::<_, _, Adaptive, false>(arr, |a, b| a.lt(b));
 }
 
 /// Sorts the slice with a comparator function, but might not preserve the order of equal
@@ -98,7 +100,7 @@ pub fn sort_by<T, F>(arr: &mut [T], mut compare: F)
 where
     F: FnMut(&T, &T) -> Ordering,
 {
-    quicksort(arr, |a, b| compare(a, b) == Ordering::Less);
+    quicksort::<_, _, Adaptive, false>(arr, |a, b| compare(a, b) == Ordering::Less);
 }
 
 /// When dropped, copies from `src` into `dest`.
@@ -141,7 +143,7 @@ where
     // See comments below for further detail.
     unsafe {
         // If the first two elements are out-of-order...
-        if len >= 2 && is_less(v.get_unchecked(1), v.get_unchecked(0)) {
+        if len >= 2 && branch_outcome!(is_less(v.get_unchecked(1), v.get_unchecked(0))) {
             // Read the first element into a stack-allocated variable. If a following comparison
             // operation panics, `hole` will get dropped and automatically write the element back
             // into the slice.
@@ -154,7 +156,7 @@ where
             ptr::copy_nonoverlapping(v.add(1), v.add(0), 1);
 
             for i in 2..len {
-                if !is_less(&*v.add(i), &*tmp) {
+                if !branch_outcome!(is_less(&*v.add(i), &*tmp)) {
                     break;
                 }
 
@@ -162,7 +164,10 @@ where
                 ptr::copy_nonoverlapping(v.add(i), v.add(i - 1), 1);
                 hole.dest = v.add(i);
             }
+            insertion_displacement!(hole.dest.offset_from(v) as usize);
             // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
+        } else {
+            insertion_displacement!(0);
         }
     }
 }
@@ -190,7 +195,8 @@ where
     // See comments below for further detail.
     unsafe {
         // If the last two elements are out-of-order...
-        if len >= 2 && is_less(v.get_unchecked(len - 1), v.get_unchecked(len - 2)) {
+        if len >= 2 && branch_outcome!(is_less(v.get_unchecked(len - 1), v.get_unchecked(len - 2)))
+        {
             // Read the last element into a stack-allocated variable. If a following comparison
             // operation panics, `hole` will get dropped and automatically write the element back
             // into the slice.
@@ -203,7 +209,7 @@ where
             ptr::copy_nonoverlapping(v.add(len - 2), v.add(len - 1), 1);
 
             for i in (0..len - 2).rev() {
-                if !is_less(&*tmp, &*v.add(i)) {
+                if !branch_outcome!(is_less(&*tmp, &*v.add(i))) {
                     break;
                 }
 
@@ -211,7 +217,10 @@ where
                 ptr::copy_nonoverlapping(v.add(i), v.add(i + 1), 1);
                 hole.dest = v.add(i);
             }
+            insertion_displacement!(len - 1 - hole.dest.offset_from(v) as usize);
             // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
+        } else {
+            insertion_displacement!(0);
         }
     }
 }
@@ -237,7 +246,7 @@ where
         // All our subsequent indexing is only in the range `0 <= index < len`
         unsafe {
             // Find the next pair of adjacent out-of-order elements.
-            while i < len && !is_less(v.get_unchecked(i), v.get_unchecked(i - 1)) {
+            while i < len && !branch_outcome!(is_less(v.get_unchecked(i), v.get_unchecked(i - 1))) {
                 i += 1;
             }
         }
@@ -253,7 +262,7 @@ where
         }
 
         // Swap the found pair of elements. This puts them in correct order.
-        v.swap(i - 1, i);
+        ptr::slice_swap(v, i - 1, i);
 
         // Shift the smaller element to the left.
         shift_tail(&mut v[..i], is_less);
@@ -290,17 +299,17 @@ where
             }
 
             // Choose the greater child.
-            if child + 1 < v.len() && is_less(&v[child], &v[child + 1]) {
+            if child + 1 < v.len() && branch_outcome!(is_less(&v[child], &v[child + 1])) {
                 child += 1;
             }
 
             // Stop if the invariant holds at `node`.
-            if !is_less(&v[node], &v[child]) {
+            if !branch_outcome!(is_less(&v[node], &v[child])) {
                 break;
             }
 
             // Swap `node` with the greater child, move one step down, and continue sifting.
-            v.swap(node, child);
+            ptr::slice_swap(v, node, child);
             node = child;
         }
     };
@@ -312,7 +321,7 @@ where
 
     // Pop maximal elements from the heap.
     for i in (1..v.len()).rev() {
-        v.swap(0, i);
+        ptr::slice_swap(v, 0, i);
         sift_down(&mut v[..i], 0);
     }
 }
@@ -325,8 +334,10 @@ where
 /// Partitioning is performed block-by-block in order to minimize the cost of branching operations.
 /// This idea is presented in the [BlockQuicksort][pdf] paper.
 ///
+/// With `PREFETCH` the block scans prefetch ahead of their reads, see [`crate::prefetch`].
+///
 /// [pdf]: https://drops.dagstuhl.de/opus/volltexte/2016/6389/pdf/LIPIcs-ESA-2016-38.pdf
-fn partition_in_blocks<T, F>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize
+fn partition_in_blocks<T, F, const PREFETCH: bool>(v: &mut [T], pivot: &T, is_less: &mut F) -> usize
 where
     F: FnMut(&T, &T) -> bool,
 {
@@ -408,6 +419,10 @@ where
             let mut elem = l;
 
             for i in 0..block_l {
+                if PREFETCH {
+                    prefetch::prefetch_read(prefetch::ahead(elem));
+                }
+
                 // SAFETY: The unsafety operations below involve the usage of the `offset`.
                 //         According to the conditions required by the function, we satisfy them because:
                 //         1. `offsets_l` is stack-allocated, and thus considered separate allocated object.
@@ -421,7 +436,7 @@ where
                 unsafe {
                     // Branchless comparison.
                     *end_l = i as u8;
-                    end_l = end_l.offset(!is_less(&*elem, pivot) as isize);
+                    end_l = end_l.offset(!branch_outcome!(is_less(&*elem, pivot)) as isize);
                     elem = elem.offset(1);
                 }
             }
@@ -434,6 +449,10 @@ where
             let mut elem = r;
 
             for i in 0..block_r {
+                if PREFETCH {
+                    prefetch::prefetch_read(prefetch::behind(elem));
+                }
+
                 // SAFETY: The unsafety operations below involve the usage of the `offset`.
                 //         According to the conditions required by the function, we satisfy them because:
                 //         1. `offsets_r` is stack-allocated, and thus considered separate allocated object.
@@ -449,7 +468,7 @@ where
                     // Branchless comparison.
                     elem = elem.offset(-1);
                     *end_r = i as u8;
-                    end_r = end_r.offset(is_less(&*elem, pivot) as isize);
+                    end_r = end_r.offset(branch_outcome!(is_less(&*elem, pivot)) as isize);
                 }
             }
         }
@@ -584,13 +603,17 @@ where
 ///
 /// 1. Number of elements smaller than `v[pivot]`.
 /// 2. True if `v` was already partitioned.
-fn partition<T, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> (usize, bool)
+fn partition<T, F, const PREFETCH: bool>(
+    v: &mut [T],
+    pivot: usize,
+    is_less: &mut F,
+) -> (usize, bool)
 where
     F: FnMut(&T, &T) -> bool,
 {
     let (mid, was_partitioned) = {
         // Place the pivot at the beginning of slice.
-        v.swap(0, pivot);
+        ptr::slice_swap(v, 0, pivot);
         let (pivot, v) = v.split_at_mut(1);
         let pivot = &mut pivot[0];
 
@@ -615,18 +638,18 @@ where
         //                     From here we know that `r` must be at least `r == l` which was shown to be valid from the first one.
         unsafe {
             // Find the first element greater than or equal to the pivot.
-            while l < r && is_less(v.get_unchecked(l), pivot) {
+            while l < r && branch_outcome!(is_less(v.get_unchecked(l), pivot)) {
                 l += 1;
             }
 
             // Find the last element smaller that the pivot.
-            while l < r && !is_less(v.get_unchecked(r - 1), pivot) {
+            while l < r && !branch_outcome!(is_less(v.get_unchecked(r - 1), pivot)) {
                 r -= 1;
             }
         }
 
         (
-            l + partition_in_blocks(&mut v[l..r], pivot, is_less),
+            l + partition_in_blocks::<_, _, PREFETCH>(&mut v[l..r], pivot, is_less),
             l >= r,
         )
 
@@ -636,7 +659,7 @@ where
     };
 
     // Place the pivot between the two partitions.
-    v.swap(0, mid);
+    ptr::slice_swap(v, 0, mid);
 
     (mid, was_partitioned)
 }
@@ -650,7 +673,7 @@ where
     F: FnMut(&T, &T) -> bool,
 {
     // Place the pivot at the beginning of slice.
-    v.swap(0, pivot);
+    ptr::slice_swap(v, 0, pivot);
     let (pivot, v) = v.split_at_mut(1);
     let pivot = &mut pivot[0];
 
@@ -674,12 +697,12 @@ where
         //                     From here we know that `r` must be at least `r == l` which was shown to be valid from the first one.
         unsafe {
             // Find the first element greater than the pivot.
-            while l < r && !is_less(pivot, v.get_unchecked(l)) {
+            while l < r && !branch_outcome!(is_less(pivot, v.get_unchecked(l))) {
                 l += 1;
             }
 
             // Find the last element equal to the pivot.
-            while l < r && is_less(pivot, v.get_unchecked(r - 1)) {
+            while l < r && branch_outcome!(is_less(pivot, v.get_unchecked(r - 1))) {
                 r -= 1;
             }
 
@@ -743,21 +766,49 @@ fn break_patterns<T>(v: &mut [T]) {
                 other -= len;
             }
 
-            v.swap(pos - 1 + i, other);
+            ptr::slice_swap(v, pos - 1 + i, other);
         }
     }
 }
 
+/// How [`quicksort`] picks the pivot of each partition. [`Adaptive`] is the choice of std, the
+/// alternatives are in src/unstable/rust_std_pivot/.
+pub trait PivotStrategy {
+    /// Chooses a pivot in `v` and returns the index and `true` if the slice is likely already
+    /// sorted.
+    ///
+    /// Elements in `v` might be reordered in the process.
+    fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool;
+}
+
+/// Median-of-three below 50 elements, the median of three medians-of-three above.
+pub struct Adaptive;
+
+impl PivotStrategy for Adaptive {
+    #[inline]
+    fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        choose_pivot::<_, _, 50>(v, is_less)
+    }
+}
+
 /// Chooses a pivot in `v` and returns the index and `true` if the slice is likely already sorted.
 ///
+/// Slices of at least `SHORTEST_MEDIAN_OF_MEDIANS` elements use the median-of-medians method,
+/// shorter slices the simple median-of-three method.
+///
 /// Elements in `v` might be reordered in the process.
-fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+pub(crate) fn choose_pivot<T, F, const SHORTEST_MEDIAN_OF_MEDIANS: usize>(
+    v: &mut [T],
+    is_less: &mut F,
+) -> (usize, bool)
 where
     F: FnMut(&T, &T) -> bool,
 {
-    // Minimum length to choose the median-of-medians method.
-    // Shorter slices use the simple median-of-three method.
-    const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
     // Maximum number of swaps that can be performed in this function.
     const MAX_SWAPS: usize = 4 * 3;
 
@@ -780,7 +831,7 @@ where
         // references. Thus the `v.get_unchecked` calls are safe, as is the `ptr::swap`
         // call.
         let mut sort2 = |a: &mut usize, b: &mut usize| unsafe {
-            if is_less(v.get_unchecked(*b), v.get_unchecked(*a)) {
+            if branch_outcome!(is_less(v.get_unchecked(*b), v.get_unchecked(*a))) {
                 ptr::swap(a, b);
                 swaps += 1;
             }
@@ -815,7 +866,7 @@ where
     } else {
         // The maximum number of swaps was performed. Chances are the slice is descending or mostly
         // descending, so reversing will probably help sort it faster.
-        v.reverse();
+        ptr::slice_reverse(v);
         (len - 1 - b, true)
     }
 }
@@ -826,10 +877,16 @@ where
 ///
 /// `limit` is the number of allowed imbalanced partitions before switching to `heapsort`. If zero,
 /// this function will immediately switch to heapsort.
-fn recurse<'a, T, F>(mut v: &'a mut [T], is_less: &mut F, mut pred: Option<&'a T>, mut limit: u32)
-where
+fn recurse<'a, T, F, P: PivotStrategy, const PREFETCH: bool>(
+    mut v: &'a mut [T],
+    is_less: &mut F,
+    mut pred: Option<&'a T>,
+    mut limit: u32,
+) where
     F: FnMut(&T, &T) -> bool,
 {
+    recursion_depth!();
+
     // Slices of up to this length get sorted using insertion sort.
     const MAX_INSERTION: usize = 20;
 
@@ -843,43 +900,47 @@ where
 
         // Very short slices get sorted using insertion sort.
         if len <= MAX_INSERTION {
-            insertion_sort(v, is_less);
+            timed_phase!(SmallSort, insertion_sort(v, is_less));
             return;
         }
 
         // If too many bad pivot choices were made, simply fall back to heapsort in order to
         // guarantee `O(n * log(n))` worst-case.
         if limit == 0 {
-            heapsort(v, is_less);
+            fallback!(Heapsort);
+            timed_phase!(Fallback, heapsort(v, is_less));
             return;
         }
 
         // If the last partitioning was imbalanced, try breaking patterns in the slice by shuffling
         // some elements around. Hopefully we'll choose a better pivot this time.
         if !was_balanced {
+            fallback!(BreakPatterns);
             break_patterns(v);
             limit -= 1;
         }
 
         // Choose a pivot and try guessing whether the slice is already sorted.
-        let (pivot, likely_sorted) = choose_pivot(v, is_less);
+        let (pivot, likely_sorted) = P::choose_pivot(v, is_less);
 
         // If the last partitioning was decently balanced and didn't shuffle elements, and if pivot
         // selection predicts the slice is likely already sorted...
         if was_balanced && was_partitioned && likely_sorted {
             // Try identifying several out-of-order elements and shifting them to correct
             // positions. If the slice ends up being completely sorted, we're done.
-            if partial_insertion_sort(v, is_less) {
+            if timed_phase!(RunDetection, partial_insertion_sort(v, is_less)) {
                 return;
             }
+
+            fallback!(PartialInsertionBailout);
         }
 
         // If the chosen pivot is equal to the predecessor, then it's the smallest element in the
         // slice. Partition the slice into elements equal to and elements greater than the pivot.
         // This case is usually hit when the slice contains many duplicate elements.
         if let Some(p) = pred {
-            if !is_less(p, &v[pivot]) {
-                let mid = partition_equal(v, pivot, is_less);
+            if !branch_outcome!(is_less(p, &v[pivot])) {
+                let mid = timed_phase!(Partition, partition_equal(v, pivot, is_less));
 
                 // Continue sorting elements greater than the pivot.
                 v = &mut v[mid..];
@@ -888,7 +949,7 @@ where
         }
 
         // Partition the slice.
-        let (mid, was_p) = partition(v, pivot, is_less);
+        let (mid, was_p) = timed_phase!(Partition, partition::<_, _, PREFETCH>(v, pivot, is_less));
         was_balanced = cmp::min(mid, len - mid) >= len / 8;
         was_partitioned = was_p;
 
@@ -901,18 +962,21 @@ where
         // calls and consume less stack space. Then just continue with the longer side (this is
         // akin to tail recursion).
         if left.len() < right.len() {
-            recurse(left, is_less, pred, limit);
+            recurse::<_, _, P, PREFETCH>(left, is_less, pred, limit);
             v = right;
             pred = Some(pivot);
         } else {
-            recurse(right, is_less, Some(pivot), limit);
+            recurse::<_, _, P, PREFETCH>(right, is_less, Some(pivot), limit);
             v = left;
         }
     }
 }
 
 /// Sorts `v` using pattern-defeating quicksort, which is *O*(*n* \* log(*n*)) worst-case.
-pub fn quicksort<T, F>(v: &mut [T], mut is_less: F)
+///
+/// `P` chooses the pivots. With `PREFETCH` the partitions prefetch ahead of their reads, see
+/// [`crate::prefetch`].
+pub fn quicksort<T, F, P: PivotStrategy, const PREFETCH: bool>(v: &mut [T], mut is_less: F)
 where
     F: FnMut(&T, &T) -> bool,
 {
@@ -924,5 +988,5 @@ where
     // Limit the number of imbalanced partitions to `floor(log2(len)) + 1`.
     let limit = usize::BITS - v.len().leading_zeros();
 
-    recurse(v, &mut is_less, None, limit);
+    recurse::<_, _, P, PREFETCH>(v, &mut is_less, None, limit);
 }
@@ -0,0 +1 @@
+pivot_sort_impl!("rust_std_unstable_pivot_median3", super::Median3);
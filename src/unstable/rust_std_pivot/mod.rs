@@ -0,0 +1,167 @@
+//! `unstable::rust_std` with other pivot selections, to compare them without forking the sort.
+//!
+//! Each strategy implements [`PivotStrategy`] and is registered as its own sort,
+//! `rust_std_unstable_pivot_<strategy>`, everything else is the same as in `rust_std_unstable`,
+//! which uses [`super::rust_std::Adaptive`]. Like there the strategies, except for random, report
+//! a slice as likely sorted if the sampled elements were in order, and reverse it if they were all
+//! in descending order.
+
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use sort_test_tools::xorshift::XorShift;
+
+use super::rust_std::{choose_pivot, PivotStrategy};
+
+macro_rules! pivot_sort_impl {
+    ($name:expr, $strategy:ty) => {
+        sort_impl!($name);
+
+        #[inline]
+        pub fn sort<T>(arr: &mut [T])
+        where
+            T: Ord,
+        {
+            crate::unstable::rust_std::quicksort::<_, _, $strategy, false>(arr, |a, b| a.lt(b));
+        }
+
+        #[inline]
+        pub fn sort_by<T, F>(arr: &mut [T], mut compare: F)
+        where
+            F: FnMut(&T, &T) -> std::cmp::Ordering,
+        {
+            crate::unstable::rust_std::quicksort::<_, _, $strategy, false>(arr, |a, b| {
+                compare(a, b) == std::cmp::Ordering::Less
+            });
+        }
+    };
+}
+
+pub mod median3;
+pub mod ninther;
+pub mod pseudo_median27;
+pub mod random;
+
+/// The median of the elements at 1/4, 2/4 and 3/4 of the slice, at every len.
+pub struct Median3;
+
+impl PivotStrategy for Median3 {
+    #[inline]
+    fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        choose_pivot::<_, _, { usize::MAX }>(v, is_less)
+    }
+}
+
+/// Tukey's ninther, the median of the medians-of-three around 1/4, 2/4 and 3/4 of the slice, at
+/// every len.
+pub struct Ninther;
+
+impl PivotStrategy for Ninther {
+    #[inline]
+    fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        choose_pivot::<_, _, 0>(v, is_less)
+    }
+}
+
+/// The median of three ninthers, 27 samples from the neighborhoods of nine evenly spaced
+/// positions. Slices too short for nine disjoint neighborhoods use the ninther.
+pub struct PseudoMedian27;
+
+impl PivotStrategy for PseudoMedian27 {
+    fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        // 13 medians-of-three, each can swap up to 3 times.
+        const MAX_SWAPS: usize = 13 * 3;
+
+        let len = v.len();
+        let step = len / 10;
+        if step < 3 {
+            return Ninther::choose_pivot(v, is_less);
+        }
+
+        let mut sampler = Sampler {
+            v,
+            is_less,
+            swaps: 0,
+        };
+
+        let mut ninthers = [0; 3];
+        for (group, ninther) in ninthers.iter_mut().enumerate() {
+            let mut medians = [1, 2, 3].map(|i| sampler.median_adjacent(step * (group * 3 + i)));
+            let [a, b, c] = &mut medians;
+            sampler.sort3(a, b, c);
+            *ninther = *b;
+        }
+
+        let [mut a, mut b, mut c] = ninthers;
+        sampler.sort3(&mut a, &mut b, &mut c);
+
+        let swaps = sampler.swaps;
+        if swaps < MAX_SWAPS {
+            (b, swaps == 0)
+        } else {
+            // Every sample was in descending order, see rust_std::choose_pivot.
+            v.reverse();
+            (len - 1 - b, true)
+        }
+    }
+}
+
+/// A uniformly random element, from a per-thread generator seeded differently in every process.
+/// An adversary can't predict the pivots, but they are also never better than a random sample.
+pub struct Random;
+
+impl PivotStrategy for Random {
+    fn choose_pivot<T, F>(v: &mut [T], _is_less: &mut F) -> (usize, bool)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        thread_local! {
+            static RNG: RefCell<XorShift> =
+                RefCell::new(XorShift::new(RandomState::new().build_hasher().finish()));
+        }
+
+        (RNG.with_borrow_mut(|rng| rng.below(v.len())), false)
+    }
+}
+
+/// Sorts sample indices by the elements they point to, counting the swaps like
+/// rust_std::choose_pivot.
+struct Sampler<'a, T, F> {
+    v: &'a [T],
+    is_less: &'a mut F,
+    swaps: usize,
+}
+
+impl<T, F: FnMut(&T, &T) -> bool> Sampler<'_, T, F> {
+    /// Swaps indices so that `v[a] <= v[b]`.
+    fn sort2(&mut self, a: &mut usize, b: &mut usize) {
+        if branch_outcome!((self.is_less)(&self.v[*b], &self.v[*a])) {
+            std::mem::swap(a, b);
+            self.swaps += 1;
+        }
+    }
+
+    /// Swaps indices so that `v[a] <= v[b] <= v[c]`.
+    fn sort3(&mut self, a: &mut usize, b: &mut usize, c: &mut usize) {
+        self.sort2(a, b);
+        self.sort2(b, c);
+        self.sort2(a, b);
+    }
+
+    /// The index of the median of `v[pos - 1], v[pos], v[pos + 1]`.
+    fn median_adjacent(&mut self, pos: usize) -> usize {
+        let (mut a, mut b, mut c) = (pos - 1, pos, pos + 1);
+        self.sort3(&mut a, &mut b, &mut c);
+        b
+    }
+}
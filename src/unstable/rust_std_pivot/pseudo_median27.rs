@@ -0,0 +1,4 @@
+pivot_sort_impl!(
+    "rust_std_unstable_pivot_pseudo_median27",
+    super::PseudoMedian27
+);
@@ -0,0 +1,24 @@
+//! `unstable::rust_std` with software prefetching in the block partition, see
+//! [`crate::prefetch`].
+
+use std::cmp::Ordering;
+
+use super::rust_std::{quicksort, Adaptive};
+
+sort_impl!("rust_std_unstable_prefetch");
+
+#[inline]
+pub fn sort<T>(arr: &mut [T])
+where
+    T: Ord,
+{
+    quicksort::<_, _, Adaptive, true>(arr, |a, b| a.lt(b));
+}
+
+#[inline]
+pub fn sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort::<_, _, Adaptive, true>(arr, |a, b| compare(a, b) == Ordering::Less);
+}
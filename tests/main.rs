@@ -1,5 +1,2848 @@
-use sort_test_tools::instantiate_sort_tests;
+use sort_test_tools::{instantiate_sort_tests, reference_sort};
 
 type TestSort = sort_research_rs::unstable::rust_ipnsort::SortImpl;
 
 instantiate_sort_tests!(TestSort);
+
+// The alloc bound of the contract test, interposing malloc also sees the C and C++ sorts.
+#[cfg(all(feature = "instrument_alloc", target_os = "linux", target_env = "gnu"))]
+sort_research_rs::interpose_malloc!();
+
+#[cfg(all(
+    feature = "instrument_alloc",
+    not(all(target_os = "linux", target_env = "gnu"))
+))]
+#[global_allocator]
+static ALLOC: sort_research_rs::instrument::alloc::TracingAllocator =
+    sort_research_rs::instrument::alloc::TracingAllocator;
+
+// Every sort has to handle the worst-case input synthesized against itself. Not run under miri,
+// the adversary inputs for quadratic sorts are expensive.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn adversary_inputs() {
+    use sort_research_rs::analysis::adversary;
+    use sort_research_rs::registry;
+
+    for entry in registry::general_sorts::<i32>() {
+        for len in [0, 1, 2, 3, 10, 20, 50, 100, 500, 2_000] {
+            let input = adversary::synthesize_for(&entry, len);
+
+            let mut expected = input.clone();
+            reference_sort::sort(&mut expected);
+            assert_eq!(expected, (0..len as i32).collect::<Vec<_>>());
+
+            let mut actual = input;
+            entry.sort(&mut actual);
+            assert_eq!(actual, expected, "{} len: {len}", entry.name());
+        }
+    }
+}
+
+// The deque sorts have to give the same result as a stable slice sort, for every position of the
+// wrap-around in the storage.
+#[test]
+fn deque_sort() {
+    use sort_research_rs::other::deque_sort;
+
+    for len in [0usize, 1, 2, 3, 7, 20, 100, 1_000] {
+        for split in [0, len.min(1), len / 3, len / 2, len.saturating_sub(1), len] {
+            // Few distinct keys, the second tuple field checks stability.
+            let input = keyed_with_index(len);
+            let mut expected = input.clone();
+            reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+            let mut d = deque_sort::wrapped_deque(input.clone(), split);
+            if 0 < split && split < len {
+                assert_eq!(d.as_slices().1.len(), len - split);
+            }
+            deque_sort::sort_by(&mut d, |a, b| a.0.cmp(&b.0));
+            assert!(d.iter().eq(expected.iter()), "len: {len} split: {split}");
+
+            let mut d = deque_sort::wrapped_deque(input, split);
+            deque_sort::sort_unstable(&mut d);
+            assert!(d.iter().eq(expected.iter()), "len: {len} split: {split}");
+        }
+    }
+}
+
+#[test]
+fn sorted_iter() {
+    use sort_research_rs::other::sorted_iter;
+
+    for len in [0usize, 1, 2, 15, 16, 17, 100, 5_000] {
+        let input = keyed_with_index(len);
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+        // Ordering only by the key, the index checks stability.
+        let keyed = input.iter().map(|&(key, i)| Keyed(key, i));
+        let actual = sorted_iter::sorted_iter_with_chunk_len(keyed, 16)
+            .map(|Keyed(key, i)| (key, i))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected, "len: {len}");
+
+        let actual = sorted_iter::sorted_iter(input.iter().copied()).collect::<Vec<_>>();
+        let mut expected = input;
+        reference_sort::sort(&mut expected);
+        assert_eq!(actual, expected, "len: {len}");
+    }
+}
+
+/// Pairs of one of 17 keys in a scrambled order and the position, which checks stability.
+fn keyed_with_index(len: usize) -> Vec<(usize, usize)> {
+    (0..len).map(|i| ((i * 7919 + 13) % 17, i)).collect()
+}
+
+/// Ordered only by the first field, the second one checks stability.
+#[derive(Clone)]
+struct Keyed(usize, usize);
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[test]
+fn kmerge() {
+    use sort_research_rs::kmerge;
+
+    for len in [0usize, 1, 2, 15, 100, 5_000] {
+        for run_count in [1, 2, 3, 7, 64] {
+            // Few distinct keys, the index checks stability.
+            let input = keyed_with_index(len);
+            let mut expected = input.clone();
+            reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+            let runs = kmerge::split_into_runs(input, run_count)
+                .into_iter()
+                .map(|run| run.into_iter().map(|(key, i)| Keyed(key, i)).collect())
+                .collect::<Vec<_>>();
+            assert!(runs.len() <= run_count);
+
+            for (name, merge_fn) in kmerge::strategies() {
+                let actual = merge_fn(runs.clone())
+                    .into_iter()
+                    .map(|Keyed(key, i)| (key, i))
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected, "{name} len: {len} run_count: {run_count}");
+            }
+
+            for min_gallop in [1, 2, usize::MAX] {
+                let actual = kmerge::galloping::merge_with_min_gallop(runs.clone(), min_gallop)
+                    .into_iter()
+                    .map(|Keyed(key, i)| (key, i))
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected, "min_gallop: {min_gallop} len: {len}");
+            }
+        }
+    }
+}
+
+#[test]
+fn permute() {
+    use sort_research_rs::other::permute;
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 2, 20, 1_000] {
+        for pattern in [patterns::random, patterns::ascending, patterns::descending] {
+            // The permutation that sorts the pattern, applied to strings that name their position.
+            let keys = pattern(len);
+            let mut indices = (0..len).collect::<Vec<_>>();
+            reference_sort::sort_by_key(&mut indices, |&i| keys[i]);
+
+            let input = (0..len).map(|i| i.to_string()).collect::<Vec<_>>();
+            let expected = indices
+                .iter()
+                .map(|&i| input[i].clone())
+                .collect::<Vec<_>>();
+
+            let mut actual = input.clone();
+            permute::apply_permutation_out_of_place(&indices, &mut actual);
+            assert_eq!(actual, expected);
+
+            let mut actual = input;
+            permute::apply_permutation(&mut indices, &mut actual);
+            assert_eq!(actual, expected);
+            assert!(indices.iter().enumerate().all(|(i, &index)| i == index));
+        }
+    }
+
+    for not_permutation in [vec![1, 1], vec![1, 2, 1], vec![0, 3, 1]] {
+        let mut v = vec![0u8; not_permutation.len()];
+        assert!(!permute::is_permutation(&not_permutation, v.len()));
+
+        let result = std::panic::catch_unwind(move || {
+            permute::apply_permutation(&mut not_permutation.clone(), &mut v)
+        });
+        assert!(result.is_err());
+    }
+}
+
+// Every input of up to 4 elements drawn from 0..4, which covers all orders and all duplicate
+// patterns. Strings, so a lost or doubled element would show up as a wrong value or a double free.
+#[test]
+fn branchless() {
+    use sort_research_rs::other::branchless;
+
+    type Network = fn(&mut [String]);
+    let networks: [(&str, usize, Network); 3] = [
+        ("sort2", 2, |v| branchless::sort2(v, &mut |a, b| a < b)),
+        ("sort3", 3, |v| branchless::sort3(v, &mut |a, b| a < b)),
+        ("sort4", 4, |v| branchless::sort4(v, &mut |a, b| a < b)),
+    ];
+
+    for (name, len, network) in networks {
+        for n in 0..4usize.pow(len as u32) {
+            let input = (0..len)
+                .map(|i| ((n / 4usize.pow(i as u32)) % 4).to_string())
+                .collect::<Vec<_>>();
+            let mut expected = input.clone();
+            reference_sort::sort(&mut expected);
+
+            let mut actual = input.clone();
+            network(&mut actual);
+            assert_eq!(actual, expected, "{name} input: {input:?}");
+        }
+    }
+
+    // sort4_stable has to keep equal keys in their input order.
+    for n in 0..4usize.pow(4) {
+        let input = (0..4)
+            .map(|i| ((n / 4usize.pow(i as u32)) % 4, i.to_string()))
+            .collect::<Vec<_>>();
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |(key, _)| *key);
+
+        let mut actual = input.clone();
+        branchless::sort4_stable_chunks(&mut actual, &mut |a, b| a.0 < b.0);
+        assert_eq!(actual, expected, "sort4_stable input: {input:?}");
+    }
+
+    // The chunk wrappers sort every full chunk and leave the remainder alone.
+    let input = (0..11).rev().map(|i| i.to_string()).collect::<Vec<_>>();
+    let mut actual = input.clone();
+    branchless::sort4_chunks(&mut actual, &mut |a, b| a < b);
+    assert_eq!(actual[..4], ["10", "7", "8", "9"]);
+    assert_eq!(actual[4..8], ["3", "4", "5", "6"]);
+    assert_eq!(actual[8..], input[8..]);
+
+    // branchless_swap with both pointers on the same element, as in the Lomuto partitions.
+    let mut v = vec![String::from("a"), String::from("b")];
+    for should_swap in [false, true] {
+        let v_base = v.as_mut_ptr();
+        // SAFETY: Both pointers are in-bounds.
+        unsafe {
+            branchless::branchless_swap(v_base, v_base, should_swap);
+        }
+        assert_eq!(v, ["a", "b"]);
+    }
+    for (should_swap, expected) in [(false, ["a", "b"]), (true, ["b", "a"])] {
+        let v_base = v.as_mut_ptr();
+        // SAFETY: Both pointers are in-bounds.
+        unsafe {
+            branchless::branchless_swap(v_base, v_base.add(1), should_swap);
+        }
+        assert_eq!(v, expected);
+    }
+}
+
+// All row sort strategies are stable, so they have to agree with a stable sort by the key.
+#[test]
+fn row_sort() {
+    use sort_research_rs::other::radix_key_sort;
+    use sort_research_rs::other::row_sort::{self, Row};
+
+    type Strategy = fn(&mut [Row], fn(&Row) -> i32);
+    let strategies: [(&str, Strategy); 4] = [
+        ("direct", |v, f| row_sort::sort_direct(v, f)),
+        ("argsort", |v, f| row_sort::sort_argsort(v, f)),
+        ("key_cached", |v, f| row_sort::sort_key_cached(v, f)),
+        ("radix_key", |v, f| radix_key_sort::sort_by_key(v, f)),
+    ];
+
+    for len in [0usize, 1, 2, 20, 64, 65, 1_000] {
+        let rows = keyed_with_index(len)
+            .into_iter()
+            .map(|(key, i)| Row::new(i as u64, key as i32))
+            .collect::<Vec<_>>();
+        let mut expected = rows.clone();
+        reference_sort::sort_by_key(&mut expected, |row| row.key);
+
+        for (name, strategy) in strategies {
+            let mut actual = rows.clone();
+            strategy(&mut actual, |row| row.key);
+            assert_eq!(actual, expected, "{name} len: {len}");
+        }
+    }
+}
+
+// Signed and wide keys, whose order the radix key mapping has to preserve.
+#[test]
+fn radix_key_sort() {
+    use sort_research_rs::other::radix_key_sort;
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 65, 1_000, 10_000] {
+        let keys = patterns::random(len);
+        let input = keys
+            .iter()
+            .enumerate()
+            .map(|(i, &key)| ((key as i64) << 24, i))
+            .collect::<Vec<_>>();
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+        let mut actual = input.clone();
+        radix_key_sort::sort_by_key(&mut actual, |&(key, _)| key);
+        assert_eq!(actual, expected);
+
+        // 256 distinct keys, the index checks stability.
+        let small_key = |&(key, _): &(i64, usize)| (key >> 24) as u8;
+        let mut actual = input;
+        radix_key_sort::sort_by_key(&mut actual, small_key);
+        assert!(actual
+            .windows(2)
+            .all(|w| (small_key(&w[0]), w[0].1) < (small_key(&w[1]), w[1].1)));
+    }
+}
+
+// The radix sorts take the digits of a key by shifting its value, which is the same on every byte
+// order. Keys that only differ in one byte at each position would be ordered wrongly by a sort
+// that read its digits from memory on a target with the other byte order. `cargo xtask cross-test`
+// runs these tests on big-endian s390x.
+#[test]
+fn radix_key_types() {
+    use sort_research_rs::other::radix_key_sort::{self, RadixKey};
+    use sort_test_tools::patterns;
+
+    fn check<K: RadixKey + std::fmt::Debug>(keys: &[K]) {
+        let input = keys.iter().copied().zip(0..).collect::<Vec<(K, usize)>>();
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+        let mut actual = input;
+        radix_key_sort::sort_by_key(&mut actual, |&(key, _)| key);
+        assert_eq!(actual, expected);
+    }
+
+    macro_rules! check_types {
+        ($($t:ty),*) => {$(
+            // The mapping to u64 has to preserve the order, checked for every value of the 8 and
+            // 16 bit types and for 65537 evenly spaced values of the wider ones.
+            let (min, max) = (<$t>::MIN as i128, <$t>::MAX as i128);
+            let values = (0..=65_536)
+                .map(|i| (min + ((max - min) as u128 * i / 65_536) as i128) as $t)
+                .collect::<Vec<_>>();
+            assert!(values
+                .windows(2)
+                .all(|w| w[0] == w[1] || w[0].radix_key() < w[1].radix_key()));
+
+            let byte_keys = (0..std::mem::size_of::<$t>())
+                .flat_map(|pos| [0x01u64, 0x7F, 0x80, 0xFF].map(|byte| (byte << (pos * 8)) as $t))
+                .chain([<$t>::MIN, <$t>::MAX, <$t>::default(), !<$t>::default()])
+                .collect::<Vec<_>>();
+
+            for len in [0, 10, 65, 1_000] {
+                // Random values spread over all bytes, with the byte keys mixed in.
+                let keys = patterns::random(len)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, val)| match i % 3 {
+                        0 => byte_keys[i % byte_keys.len()],
+                        _ => (val as i64).wrapping_mul(0x0101_0101_0101) as $t,
+                    })
+                    .collect::<Vec<_>>();
+                check(&keys);
+            }
+        )*};
+    }
+
+    check_types!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+}
+
+// Floats in the total order of `total_cmp`, from random bit patterns, so every class of value
+// occurs, and the special values.
+#[test]
+#[cfg(feature = "rust_radsort")]
+fn radsort_float_keys() {
+    use sort_test_tools::patterns;
+
+    let special_f64 = [
+        f64::NAN,
+        -f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        0.0,
+        -0.0,
+        f64::MIN_POSITIVE,
+        f64::from_bits(1),
+        f64::MAX,
+        f64::MIN,
+    ];
+
+    for len in [0, 1, 2, 20, 1_000, 10_000] {
+        let random = patterns::random(len);
+
+        let mut input_f32 = random
+            .iter()
+            .map(|&val| f32::from_bits(val as u32))
+            .collect::<Vec<_>>();
+        input_f32.extend(special_f64.map(|val| val as f32));
+        let mut expected = input_f32.clone();
+        reference_sort::sort_by(&mut expected, f32::total_cmp);
+        let mut actual = input_f32;
+        radsort::sort(&mut actual);
+        assert!(actual
+            .iter()
+            .map(|val| val.to_bits())
+            .eq(expected.iter().map(|val| val.to_bits())));
+
+        let mut input_f64 = random
+            .windows(2)
+            .map(|w| f64::from_bits(((w[0] as u32 as u64) << 32) | w[1] as u32 as u64))
+            .collect::<Vec<_>>();
+        input_f64.extend(special_f64);
+        let mut expected = input_f64.clone();
+        reference_sort::sort_by(&mut expected, f64::total_cmp);
+        let mut actual = input_f64;
+        radsort::sort(&mut actual);
+        assert!(actual
+            .iter()
+            .map(|val| val.to_bits())
+            .eq(expected.iter().map(|val| val.to_bits())));
+
+        let mut input_i64 = random
+            .iter()
+            .map(|&val| (val as i64).wrapping_mul(0x0101_0101_0101))
+            .collect::<Vec<_>>();
+        let mut expected = input_i64.clone();
+        reference_sort::sort(&mut expected);
+        radsort::sort(&mut input_i64);
+        assert_eq!(input_i64, expected);
+    }
+}
+
+// The special values, NaNs with several payloads and the boundaries between the classes of values.
+// For all pairs the order of the keys is the order of `total_cmp`, and every value round trips.
+#[test]
+fn float_key_edge_cases() {
+    use sort_research_rs::other::float_key::{from_ordered_bits, to_ordered_bits};
+    use sort_test_tools::patterns;
+
+    let special_f32 = [
+        f32::NAN,
+        -f32::NAN,
+        f32::from_bits(0x7F80_0001),
+        f32::from_bits(0xFF80_0001),
+        f32::from_bits(u32::MAX),
+        f32::from_bits(i32::MAX as u32),
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::MAX,
+        f32::MIN,
+        f32::MIN_POSITIVE,
+        -f32::MIN_POSITIVE,
+        f32::from_bits(1),
+        f32::from_bits(0x8000_0001),
+        f32::from_bits(0x007F_FFFF),
+        f32::from_bits(0x807F_FFFF),
+        1.0,
+        -1.0,
+        0.0,
+        -0.0,
+    ];
+
+    let special_f64 = [
+        f64::NAN,
+        -f64::NAN,
+        f64::from_bits(0x7FF0_0000_0000_0001),
+        f64::from_bits(0xFFF0_0000_0000_0001),
+        f64::from_bits(u64::MAX),
+        f64::from_bits(i64::MAX as u64),
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::MAX,
+        f64::MIN,
+        f64::MIN_POSITIVE,
+        -f64::MIN_POSITIVE,
+        f64::from_bits(1),
+        f64::from_bits(0x8000_0000_0000_0001),
+        f64::from_bits(0x000F_FFFF_FFFF_FFFF),
+        f64::from_bits(0x800F_FFFF_FFFF_FFFF),
+        1.0,
+        -1.0,
+        0.0,
+        -0.0,
+    ];
+
+    // The NaNs with the largest payloads are the extremes of the order.
+    assert_eq!(to_ordered_bits(f32::from_bits(u32::MAX)), 0);
+    assert_eq!(to_ordered_bits(f32::from_bits(i32::MAX as u32)), u32::MAX);
+    assert_eq!(to_ordered_bits(f64::from_bits(u64::MAX)), 0);
+    assert_eq!(to_ordered_bits(f64::from_bits(i64::MAX as u64)), u64::MAX);
+
+    let random = patterns::random(if cfg!(miri) { 100 } else { 10_000 });
+    let values_f32 = special_f32
+        .into_iter()
+        .chain(random.iter().map(|&val| f32::from_bits(val as u32)))
+        .collect::<Vec<_>>();
+    let values_f64 = special_f64
+        .into_iter()
+        .chain(
+            random
+                .windows(2)
+                .map(|w| f64::from_bits(((w[0] as u32 as u64) << 32) | w[1] as u32 as u64)),
+        )
+        .collect::<Vec<_>>();
+
+    for &a in &values_f32 {
+        assert_eq!(
+            from_ordered_bits::<f32>(to_ordered_bits(a)).to_bits(),
+            a.to_bits()
+        );
+        for &b in &special_f32 {
+            assert_eq!(to_ordered_bits(a).cmp(&to_ordered_bits(b)), a.total_cmp(&b));
+        }
+    }
+
+    for &a in &values_f64 {
+        assert_eq!(
+            from_ordered_bits::<f64>(to_ordered_bits(a)).to_bits(),
+            a.to_bits()
+        );
+        for &b in &special_f64 {
+            assert_eq!(to_ordered_bits(a).cmp(&to_ordered_bits(b)), a.total_cmp(&b));
+        }
+    }
+
+    // Sorting by the keys is sorting by `total_cmp`.
+    let mut expected = values_f64.clone();
+    reference_sort::sort_by(&mut expected, f64::total_cmp);
+    let mut actual = values_f64;
+    reference_sort::sort_by_key(&mut actual, |&x| to_ordered_bits(x));
+    assert!(actual
+        .iter()
+        .map(|val| val.to_bits())
+        .eq(expected.iter().map(|val| val.to_bits())));
+}
+
+// Every key maps to an f32 that maps back to the key, and consecutive keys map to consecutive
+// values in the order of `total_cmp`, so the map is a bijection that preserves the order. All 2^32
+// keys are checked, in chunks spread over all cores. With reduced test sizes only every 4099th key.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn float_key_f32_exhaustive() {
+    use std::cmp::Ordering;
+    use std::sync::atomic::{self, AtomicU64};
+    use std::thread;
+
+    use sort_research_rs::other::float_key::{from_ordered_bits, to_ordered_bits};
+
+    const CHUNK_LEN: u64 = 1 << 24;
+    let step = if cfg!(feature = "reduced_test_sizes") {
+        4099
+    } else {
+        1
+    };
+
+    let next_chunk = AtomicU64::new(0);
+    let check_chunks = || loop {
+        let start = next_chunk.fetch_add(CHUNK_LEN, atomic::Ordering::Relaxed);
+        if start > u32::MAX as u64 {
+            break;
+        }
+
+        for key in (start..start + CHUNK_LEN).step_by(step) {
+            let key = key as u32;
+            let x = from_ordered_bits::<f32>(key);
+            assert!(to_ordered_bits(x) == key, "key: {key:#x}");
+
+            if let Some(next) = key.checked_add(1) {
+                let y = from_ordered_bits::<f32>(next);
+                assert!(x.total_cmp(&y) == Ordering::Less, "key: {key:#x}");
+            }
+        }
+    };
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(check_chunks);
+        }
+    });
+}
+
+// The key index path has to sort like the direct one, and only be taken for small Copy keys of
+// large elements.
+#[test]
+fn key_index_sort() {
+    use sort_research_rs::other::key_index_sort::{self, Record};
+
+    type Strategy = fn(&mut [Record<15>], fn(&Record<15>) -> i32);
+    let strategies: [(&str, Strategy); 3] = [
+        ("direct", |v, f| key_index_sort::sort_direct(v, f)),
+        ("key_index", |v, f| key_index_sort::sort_key_index(v, f)),
+        ("by_key", |v, f| key_index_sort::sort_unstable_by_key(v, f)),
+    ];
+
+    for len in [0usize, 1, 2, 20, 21, 1_000] {
+        let records = keyed_with_index(len)
+            .into_iter()
+            .map(|(key, i)| Record::<15>::new(i as u32, key as i32))
+            .collect::<Vec<_>>();
+        let mut expected = records.clone();
+        reference_sort::sort_by_key(&mut expected, |record| (record.key, record.payload[0]));
+
+        for (name, strategy) in strategies {
+            let mut actual = records.clone();
+            strategy(&mut actual, |record| record.key);
+            assert!(
+                actual.windows(2).all(|w| w[0].key <= w[1].key),
+                "{name} len: {len}"
+            );
+
+            // Unstable, so only the set of records per key has to match.
+            reference_sort::sort_by_key(&mut actual, |record| (record.key, record.payload[0]));
+            assert_eq!(actual, expected, "{name} len: {len}");
+        }
+    }
+
+    assert!(key_index_sort::uses_key_index::<Record<15>, i32>(21));
+    assert!(!key_index_sort::uses_key_index::<Record<15>, i32>(20));
+    assert!(!key_index_sort::uses_key_index::<u64, i32>(1_000));
+    assert!(!key_index_sort::uses_key_index::<Record<15>, [u64; 4]>(
+        1_000
+    ));
+
+    // String keys aren't Copy and always take the direct path.
+    let mut strings = (0..100)
+        .map(|i| (i * 7919 % 100).to_string())
+        .collect::<Vec<_>>();
+    let mut expected = strings.clone();
+    reference_sort::sort(&mut expected);
+    key_index_sort::sort_unstable_by_key(&mut strings, |s| s.clone());
+    assert_eq!(strings, expected);
+}
+
+// Every strategy has to produce the same order as a stable sort with chained comparators, including
+// for negative zero and NaN.
+#[test]
+fn multi_key() {
+    use sort_research_rs::other::multi_key;
+    use sort_test_tools::patterns;
+
+    type U32StrSort = fn(&mut [(u32, String)]);
+    type F64U64Sort = fn(&mut [(f64, u64)]);
+
+    let floats = [
+        f64::NAN,
+        -f64::NAN,
+        -0.0,
+        0.0,
+        -1.5,
+        1.5,
+        f64::INFINITY,
+        f64::MIN,
+    ];
+
+    for len in [0usize, 1, 2, 20, 1_000] {
+        let values = patterns::random_uniform(len, 0..=99);
+
+        let u32_str = values
+            .iter()
+            .map(|&val| ((val % 4) as u32, format!("{}", val / 4)))
+            .collect::<Vec<_>>();
+        let mut expected = u32_str.clone();
+        reference_sort::sort_by(&mut expected, |a, b| {
+            a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1))
+        });
+
+        let u32_str_fns: [U32StrSort; 3] = [
+            multi_key::sort_chained_u32_str,
+            multi_key::sort_chained_u32_str_unstable,
+            multi_key::sort_encoded_u32_str,
+        ];
+        for test_fn in u32_str_fns {
+            let mut actual = u32_str.clone();
+            test_fn(&mut actual);
+            assert_eq!(actual, expected, "len: {len}");
+        }
+
+        let f64_u64 = values
+            .iter()
+            .map(|&val| (floats[val as usize % floats.len()], (val / 8) as u64))
+            .collect::<Vec<_>>();
+        let mut expected = f64_u64.clone();
+        reference_sort::sort_by(&mut expected, |a, b| {
+            a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1))
+        });
+
+        let f64_u64_fns: [F64U64Sort; 4] = [
+            multi_key::sort_chained_f64_u64,
+            multi_key::sort_chained_f64_u64_unstable,
+            multi_key::sort_encoded_f64_u64,
+            multi_key::sort_encoded_f64_u64_unstable,
+        ];
+        for test_fn in f64_u64_fns {
+            let mut actual = f64_u64.clone();
+            test_fn(&mut actual);
+            // Compare the bits, NaN != NaN.
+            let bits =
+                |v: &[(f64, u64)]| v.iter().map(|x| (x.0.to_bits(), x.1)).collect::<Vec<_>>();
+            assert_eq!(bits(&actual), bits(&expected), "len: {len}");
+        }
+    }
+}
+
+// The network has to sort every input, and has to compare the same positions in the same order for
+// every input of the same len.
+#[test]
+fn oblivious_sort() {
+    use sort_research_rs::other::oblivious_sort;
+    use sort_test_tools::patterns;
+
+    // By the 0-1 principle a network sorts all inputs if it sorts all inputs of zeros and ones.
+    for len in 0..=12 {
+        for bits in 0u32..(1 << len) {
+            let mut v = (0..len).map(|i| (bits >> i) & 1).collect::<Vec<_>>();
+            oblivious_sort::sort(&mut v);
+            assert!(
+                v.windows(2).all(|w| w[0] <= w[1]),
+                "len: {len} bits: {bits:b}"
+            );
+        }
+    }
+
+    let mut edge = [i64::MAX, -1, i64::MIN, 0, 1, i64::MIN + 1, i64::MAX - 1];
+    oblivious_sort::sort(&mut edge);
+    assert_eq!(
+        edge,
+        [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX]
+    );
+
+    for len in [0usize, 1, 2, 3, 17, 64, 100, 1_000] {
+        let inputs: [Vec<i32>; 4] = [
+            patterns::random(len),
+            patterns::ascending(len),
+            patterns::descending(len),
+            patterns::all_equal(len),
+        ];
+
+        let expected_trace = oblivious_sort::comparators(len)
+            .into_iter()
+            .map(|(i, j)| (j, i))
+            .collect::<Vec<_>>();
+
+        for input in inputs {
+            let mut expected = input.clone();
+            reference_sort::sort(&mut expected);
+
+            let mut actual = input.clone();
+            oblivious_sort::sort(&mut actual);
+            assert_eq!(actual, expected, "len: {len}");
+
+            let mut actual = input;
+            let base = actual.as_ptr() as usize;
+            let idx =
+                |elem: &i32| (elem as *const i32 as usize - base) / std::mem::size_of::<i32>();
+            let mut trace = Vec::new();
+            oblivious_sort::sort_by(&mut actual, |a, b| {
+                trace.push((idx(a), idx(b)));
+                a.cmp(b)
+            });
+            assert_eq!(actual, expected, "len: {len}");
+            assert_eq!(trace, expected_trace, "len: {len}");
+        }
+    }
+}
+
+#[test]
+fn abbrev_keys() {
+    use sort_research_rs::other::abbrev_keys::{self, AbbrevKey};
+    use sort_test_tools::patterns;
+
+    // Order-preserving, including strings that are prefixes of each other or contain 0 bytes.
+    let mut strs = [
+        "",
+        "\0",
+        "a",
+        "a\0",
+        "abcdefgh",
+        "abcdefgh\0",
+        "abcdefghi",
+        "b",
+    ];
+    reference_sort::sort(&mut strs);
+    assert!(strs.windows(2).all(|w| w[0].abbrev() <= w[1].abbrev()));
+    assert!(u128::MAX.abbrev() > (u64::MAX as u128).abbrev());
+
+    for len in [0, 1, 2, 20, 500] {
+        // Only the last two characters differ, so the abbreviations are all equal.
+        let strings = patterns::random_uniform(len, 0..=99)
+            .into_iter()
+            .map(|val| format!("/home/user/{val:02}"))
+            .collect::<Vec<_>>();
+
+        let mut expected = strings.clone();
+        reference_sort::sort(&mut expected);
+
+        let mut actual = strings.iter().map(String::as_str).collect::<Vec<_>>();
+        abbrev_keys::sort(&mut actual);
+        assert_eq!(actual, expected);
+
+        let mut actual = strings;
+        abbrev_keys::sort_unstable(&mut actual);
+        assert_eq!(actual, expected);
+
+        let wide = patterns::random(len)
+            .into_iter()
+            .map(|val| (val as u128) << (val & 127))
+            .collect::<Vec<_>>();
+
+        let mut expected = wide.clone();
+        reference_sort::sort(&mut expected);
+
+        let mut actual = wide;
+        abbrev_keys::sort(&mut actual);
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn workload_histogram() {
+    use sort_research_rs::other::workload::{self, SizeHistogram};
+
+    let histogram = "90:1-19,9:20-999,1:1_000".parse::<SizeHistogram>().unwrap();
+    assert_eq!(histogram.bins().len(), 3);
+    assert_eq!(histogram.max_len(), 1_000);
+
+    for invalid in ["", "90", "x:1-2", "0:1-2", "1:0-2", "1:3-2", "1:1-y"] {
+        assert!(invalid.parse::<SizeHistogram>().is_err(), "{invalid}");
+    }
+    assert!(workload::DEFAULT_HISTOGRAM.parse::<SizeHistogram>().is_ok());
+
+    let sizes = std::iter::repeat_with(histogram.sampler(7))
+        .take(10_000)
+        .collect::<Vec<_>>();
+    assert!(sizes.iter().all(|len| (1..=1_000).contains(len)));
+
+    // Within a few percent of the weights.
+    let count = |range: std::ops::RangeInclusive<usize>| {
+        sizes.iter().filter(|len| range.contains(len)).count()
+    };
+    assert!((8_700..9_300).contains(&count(1..=19)));
+    assert!((700..1_100).contains(&count(20..=999)));
+    assert!((50..150).contains(&count(1_000..=1_000)));
+
+    let same_seed = std::iter::repeat_with(histogram.sampler(7)).take(10_000);
+    assert!(same_seed.eq(sizes.iter().copied()));
+}
+
+#[test]
+fn time_limited_sort() {
+    use std::time::Duration;
+
+    use sort_research_rs::other::time_limited_sort;
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 2, 31, 32, 33, 1_000, 100_000] {
+        for input in [
+            patterns::random(len),
+            patterns::random_uniform(len, 0..2),
+            patterns::all_equal(len),
+            patterns::ascending(len),
+            patterns::descending(len),
+            patterns::pipe_organ(len),
+        ] {
+            let mut expected = input.clone();
+            reference_sort::sort(&mut expected);
+
+            let mut actual = input.clone();
+            let progress = time_limited_sort::sort_for(Duration::MAX, &mut actual);
+            assert!(progress.is_complete(len));
+            assert_eq!(actual, expected);
+
+            // A budget that runs out part way leaves a final prefix and a permutation of the rest,
+            // which a second call finishes.
+            let mut actual = input.clone();
+            let progress = time_limited_sort::sort_for(Duration::from_micros(20), &mut actual);
+            let prefix = progress.sorted_prefix;
+            assert_eq!(actual[..prefix], expected[..prefix]);
+            reference_sort::sort(&mut actual[prefix..]);
+            assert_eq!(actual, expected);
+
+            let mut actual = input;
+            let progress = time_limited_sort::sort_for(Duration::ZERO, &mut actual);
+            assert_eq!(progress.sorted_prefix, 0);
+        }
+    }
+}
+
+#[test]
+fn top_k() {
+    use sort_research_rs::other::top_k::{self, Strategy};
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 2, 64, 1_000, 10_000] {
+        for input in [
+            patterns::random(len),
+            patterns::random_uniform(len, 0..3),
+            patterns::ascending(len),
+            patterns::descending(len),
+        ] {
+            let mut ascending = input.clone();
+            reference_sort::sort(&mut ascending);
+            let descending = ascending.iter().rev().copied().collect::<Vec<_>>();
+
+            for k in [0, 1, 2, len / 64, len / 2, len, len + 1] {
+                let expected_len = k.min(len);
+
+                for strategy in Strategy::ALL {
+                    let mut v = input.clone();
+                    let bottom = top_k::bottom_k_with(strategy, &mut v, k, i32::cmp);
+                    assert_eq!(bottom, &ascending[..expected_len], "{strategy:?} k={k}");
+
+                    // The rest is still a permutation of the input.
+                    reference_sort::sort(&mut v);
+                    assert_eq!(v, ascending);
+                }
+
+                let mut v = input.clone();
+                assert_eq!(top_k::bottom_k(&mut v, k), &ascending[..expected_len]);
+                let mut v = input.clone();
+                assert_eq!(top_k::top_k(&mut v, k), &descending[..expected_len]);
+            }
+        }
+    }
+}
+
+// Element sizes of every size class and some that are sorted indirectly, with (key, index) pairs
+// compared by key only to check the stability.
+#[test]
+fn type_erased() {
+    use sort_research_rs::other::type_erased::{self, Strategy};
+    use std::cmp::Ordering;
+
+    fn check<T: Ord + Clone + std::fmt::Debug>(keys: &[i32], make: impl Fn(i32) -> T) {
+        let input = keys
+            .iter()
+            .enumerate()
+            .map(|(i, &key)| (make(key), i))
+            .collect::<Vec<_>>();
+        let mut expected = input.clone();
+        reference_sort::sort_by(&mut expected, |a, b| a.0.cmp(&b.0));
+
+        for strategy in Strategy::ALL {
+            for stable in [true, false] {
+                let mut v = input.clone();
+                let mut cmp = |a: *const u8, b: *const u8| unsafe {
+                    (*a.cast::<(T, usize)>())
+                        .0
+                        .cmp(&(*b.cast::<(T, usize)>()).0)
+                };
+                unsafe {
+                    type_erased::sort_raw_with(
+                        strategy,
+                        stable,
+                        v.as_mut_ptr().cast(),
+                        v.len(),
+                        std::mem::size_of::<(T, usize)>(),
+                        &mut cmp,
+                    );
+                }
+
+                if stable {
+                    assert_eq!(v, expected, "{strategy:?}");
+                } else {
+                    let keys = |v: &[(T, usize)]| v.iter().map(|e| e.0.clone()).collect::<Vec<_>>();
+                    assert_eq!(keys(&v), keys(&expected), "{strategy:?}");
+                    reference_sort::sort_by_key(&mut v, |e| e.1);
+                    assert_eq!(v, input);
+                }
+            }
+        }
+    }
+
+    for len in [0, 1, 2, 20, 1_000] {
+        let keys = sort_test_tools::patterns::random_uniform(len, 0..10);
+        check(&keys, |key| key as u8);
+        check(&keys, |key| key as u128);
+        check(&keys, |key| [key as u8; 9]);
+        check(&keys, |key| [key as u64; 5]);
+        check(&keys, |key| key.to_string());
+    }
+
+    // Five words of a single byte have no size class and take the indirect path.
+    let mut v = vec![[3u8, 0, 0, 0, 0], [1, 0, 0, 0, 0], [2, 0, 0, 0, 0]];
+    let mut cmp = |a: *const u8, b: *const u8| unsafe { (*a).cmp(&*b) };
+    unsafe { type_erased::sort_raw(v.as_mut_ptr().cast(), 3, 5, &mut cmp) };
+    assert_eq!(v, [[1, 0, 0, 0, 0], [2, 0, 0, 0, 0], [3, 0, 0, 0, 0]]);
+
+    let mut cmp = |_: *const u8, _: *const u8| -> Ordering { unreachable!() };
+    unsafe {
+        type_erased::sort_unstable_raw(std::ptr::NonNull::dangling().as_ptr(), 0, 8, &mut cmp)
+    };
+}
+
+#[test]
+fn hybrid_bucket_sort() {
+    use sort_research_rs::other::hybrid_bucket_sort;
+    use sort_test_tools::patterns;
+
+    let inputs = [0, 1, 255, 256, 257, 1_000, 10_000]
+        .into_iter()
+        .flat_map(|len| {
+            [
+                patterns::random(len),
+                patterns::random_uniform(len, 0..2),
+                patterns::random_uniform(len, -300..300),
+                patterns::all_equal(len),
+                patterns::descending(len),
+            ]
+        });
+
+    for input in inputs {
+        let mut expected = input.clone();
+        reference_sort::sort(&mut expected);
+
+        let mut actual = input.clone();
+        hybrid_bucket_sort::sort_unstable(&mut actual);
+        assert_eq!(actual, expected);
+
+        // Keys that only differ in the high bits, and the extremes.
+        let wide_input = input
+            .iter()
+            .map(|&val| (val as u64).rotate_right(8))
+            .chain([0, u64::MAX])
+            .collect::<Vec<_>>();
+        let mut expected = wide_input.clone();
+        reference_sort::sort(&mut expected);
+
+        let mut actual = wide_input;
+        hybrid_bucket_sort::sort_unstable(&mut actual);
+        assert_eq!(actual, expected);
+    }
+}
+
+sort_test_tools::instrumented_type!(Tracked);
+
+#[test]
+fn instrumented_type() {
+    use sort_test_tools::instrumented::Counts;
+    use sort_test_tools::{patterns, Sort};
+
+    let mut v = [3, 1, 2].into_iter().map(Tracked::new).collect::<Vec<_>>();
+    Tracked::observe_all(&v);
+    Tracked::<i32>::reset_counts();
+
+    // A swap moves two elements, which is seen once they are observed again.
+    v.swap(0, 2);
+    assert_eq!(Tracked::<i32>::counts().moves, 0);
+    Tracked::observe_all(&v);
+    assert_eq!(Tracked::<i32>::counts().moves, 2);
+
+    assert!(v[1] < v[0]);
+    let copy = v[2].clone();
+    assert_eq!(copy.comp_count(), 0);
+    assert_eq!(copy.into_inner(), 3);
+    drop(v);
+    assert_eq!(
+        Tracked::<i32>::counts(),
+        Counts {
+            clones: 1,
+            moves: 2,
+            comparisons: 1,
+            drops: 3,
+        }
+    );
+
+    // The counts belong to the macro invocation, every `T` shares them.
+    Tracked::<i32>::reset_counts();
+    drop(Tracked::new(1u64));
+    assert_eq!(Tracked::<i32>::counts().drops, 1);
+
+    for len in [0, 1, 2, 20, 500] {
+        // A permutation of 0..len, every element that is not at its index has to move.
+        let mut input = (0..len).collect::<Vec<_>>();
+        patterns::shuffle(&mut input);
+        let misplaced = input.iter().enumerate();
+        let misplaced = misplaced.filter(|(idx, val)| idx != *val).count();
+
+        let mut v = input.into_iter().map(Tracked::new).collect::<Vec<_>>();
+        Tracked::observe_all(&v);
+        Tracked::<usize>::reset_counts();
+
+        TestSort::sort(&mut v);
+        Tracked::observe_all(&v);
+
+        let counts = Tracked::<usize>::counts();
+        assert_eq!(counts.comparisons, Tracked::total_comp_count(&v));
+        assert!(counts.moves >= misplaced as u64, "len: {len}");
+        assert_eq!(counts.clones + counts.drops, 0);
+    }
+}
+
+#[test]
+fn comparison_counting() {
+    use sort_research_rs::instrument::comp::{CountingCmp, InstrumentedElem};
+    use sort_test_tools::{patterns, Sort};
+
+    for len in [0, 1, 2, 20, 500] {
+        let mut v = patterns::random(len)
+            .into_iter()
+            .map(InstrumentedElem::new)
+            .collect::<Vec<_>>();
+
+        // Both count the same comparisons, once in the comparison function and once in the
+        // elements.
+        let mut cmp = CountingCmp::new(InstrumentedElem::<i32>::cmp);
+        TestSort::sort_by(&mut v, |a, b| cmp.compare(a, b));
+
+        assert_eq!(InstrumentedElem::total_comp_count(&v), cmp.count());
+        assert!(cmp.count() >= len.saturating_sub(1) as u64);
+        assert!(v.windows(2).all(|w| w[0].value() <= w[1].value()));
+
+        cmp.reset();
+        assert_eq!(cmp.count(), 0);
+    }
+}
+
+// The newest snapshot survives the ring buffer wrapping around, and reads back unchanged.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_ring() {
+    use sort_research_rs::analysis::snapshot::{Snapshot, SnapshotRing};
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("sort-research-test-{}", std::process::id()));
+    let mut ring = SnapshotRing::new(&dir, 3).unwrap();
+
+    let mut snapshots = (0..5)
+        .map(|i| Snapshot {
+            sequence: 0,
+            sort_name: "rust_std_stable".into(),
+            type_name: "i32".into(),
+            descending: i % 2 == 1,
+            label: format!("--seed={i}"),
+            input: (0..i).rev().collect(),
+        })
+        .collect::<Vec<_>>();
+    for snapshot in &mut snapshots {
+        ring.record(snapshot).unwrap();
+    }
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+    assert_eq!(
+        SnapshotRing::latest(&dir).unwrap().as_ref(),
+        snapshots.last()
+    );
+
+    // A new run starts with an empty dir.
+    SnapshotRing::new(&dir, 3).unwrap();
+    assert_eq!(SnapshotRing::latest(&dir).unwrap(), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// A corrupt len prefix is an error, and doesn't allocate what it claims.
+#[test]
+fn snapshot_corrupt_len() {
+    use std::io::ErrorKind;
+
+    use sort_research_rs::analysis::snapshot::Snapshot;
+
+    let snapshot = Snapshot {
+        sequence: 7,
+        sort_name: "rust_std_stable".into(),
+        type_name: "i32".into(),
+        descending: false,
+        label: String::new(),
+        input: Vec::new(),
+    };
+    let mut bytes = Vec::new();
+    snapshot.write_to(&mut bytes).unwrap();
+    assert_eq!(Snapshot::read_from(&mut bytes.as_slice()).unwrap(), snapshot);
+
+    // The sort name len follows the magic, the sequence number and the descending flag.
+    let mut corrupt = bytes.clone();
+    corrupt[17..21].copy_from_slice(&u32::MAX.to_le_bytes());
+    let err = Snapshot::read_from(&mut corrupt.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // The input len is last, the input is empty.
+    let input_len_pos = bytes.len() - 8;
+    for (input_len, kind) in [
+        (1u64 << 60, ErrorKind::UnexpectedEof),
+        (u64::MAX, ErrorKind::InvalidData),
+    ] {
+        let mut corrupt = bytes.clone();
+        corrupt[input_len_pos..].copy_from_slice(&input_len.to_le_bytes());
+        let err = Snapshot::read_from(&mut corrupt.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), kind, "input len: {input_len}");
+    }
+}
+
+// Every input in corpus/ has to be sorted correctly by every sort, not only the one it was found
+// with. Not run under miri, the inputs are long.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn corpus() {
+    use sort_research_rs::analysis::corpus::{self, CorpusEntry};
+    use sort_research_rs::analysis::inputs::{self, TypeVisitor};
+    use sort_research_rs::registry;
+    use sort_research_rs::verify;
+
+    struct Replay<'a>(&'a CorpusEntry);
+
+    impl TypeVisitor for Replay<'_> {
+        fn visit<T: Ord + Clone + std::fmt::Debug + 'static>(
+            self,
+            transform: fn(Vec<i32>) -> Vec<T>,
+        ) {
+            let snapshot = &self.0.snapshot;
+            let input = transform(snapshot.input.clone());
+            let compare = |a: &T, b: &T| {
+                if snapshot.descending {
+                    b.cmp(a)
+                } else {
+                    a.cmp(b)
+                }
+            };
+
+            for entry in registry::general_sorts::<T>() {
+                let mut actual = input.clone();
+                if snapshot.descending {
+                    entry.sort_by(&mut actual, compare);
+                } else {
+                    entry.sort(&mut actual);
+                }
+
+                assert!(
+                    verify::is_sorted_by(&actual, compare)
+                        && verify::same_elements(&input, &actual),
+                    "{} corpus/{}.bin",
+                    entry.name(),
+                    self.0.name
+                );
+            }
+        }
+    }
+
+    for corpus_entry in corpus::load(&corpus::dir()).unwrap() {
+        let type_name = &corpus_entry.snapshot.type_name;
+        assert!(inputs::with_type(type_name, Replay(&corpus_entry)));
+    }
+
+    // Names end up in benchmark names, which are split at '-'.
+    let dir = std::env::temp_dir().join(format!("sort-research-corpus-{}", std::process::id()));
+    let snapshot = sort_research_rs::analysis::snapshot::Snapshot {
+        sequence: 7,
+        sort_name: "rust_std_stable".into(),
+        type_name: "i32".into(),
+        descending: true,
+        label: "test".into(),
+        input: vec![3, 1, 2],
+    };
+    assert!(corpus::add(&dir, "bad-name", &snapshot).is_err());
+    corpus::add(&dir, "good_name_1", &snapshot).unwrap();
+    assert!(corpus::add(&dir, "good_name_1", &snapshot).is_err());
+
+    let loaded = corpus::load(&dir).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].name, "good_name_1");
+    assert_eq!(loaded[0].snapshot.input, snapshot.input);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// Every registered sort has to behave as its name declares. Not run under miri, the inputs are
+// long to make the unstable sorts reorder equal elements.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn declared_stability() {
+    use sort_research_rs::analysis::stability::{self, Stability};
+    use sort_research_rs::registry;
+
+    for entry in registry::general_sorts::<u64>() {
+        let classification = stability::classify(&entry);
+        assert!(
+            !classification.is_failure(),
+            "{}: {:?}",
+            entry.name(),
+            classification
+        );
+
+        if entry.name() == "rust_std_stable" {
+            assert_eq!(classification.observed, Stability::Stable);
+        }
+    }
+}
+
+// Every registered sort has to keep every clause of the contract it claims. The conformance report
+// is written to CONTRACT_REPORT, by default contract_report.md in the tmp dir of the test target.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn contract() {
+    use sort_research_rs::analysis::contract::{self, Clause, Verdict};
+    use sort_research_rs::registry;
+
+    let results = registry::general_sorts::<u64>()
+        .iter()
+        .map(contract::check)
+        .collect::<Vec<_>>();
+
+    let path = std::env::var_os("CONTRACT_REPORT")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| {
+            std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("contract_report.md")
+        });
+    contract::write_report(&path, &results).unwrap();
+    println!("Contract report: {}", path.display());
+
+    let failures = results
+        .iter()
+        .filter(|result| result.is_failure())
+        .map(|result| result.to_string())
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+
+    let rust_std = results
+        .iter()
+        .find(|result| result.name == "rust_std_stable")
+        .unwrap();
+    for clause in Clause::ALL {
+        assert!(
+            matches!(
+                rust_std.verdict(clause),
+                Verdict::Pass | Verdict::NotChecked(_)
+            ),
+            "{rust_std}"
+        );
+    }
+}
+
+// The contract has to catch a sort that breaks it, here one that claims to be stable but isn't,
+// and one that duplicates an element.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn contract_violations() {
+    use sort_research_rs::analysis::contract::{self, Clause, Verdict};
+    use sort_research_rs::registry::SortEntry;
+
+    let not_stable = SortEntry::<u64>::new(
+        "rust_not_really_stable".into(),
+        true,
+        true,
+        Box::new(|v| v.sort_unstable()),
+        Box::new(|v, compare| v.sort_unstable_by(compare)),
+    );
+    let result = contract::check(&not_stable);
+    assert!(matches!(result.verdict(Clause::Stable), Verdict::Fail(_)));
+    assert_eq!(result.verdict(Clause::Sorted), &Verdict::Pass);
+    assert_eq!(result.verdict(Clause::SameElements), &Verdict::Pass);
+
+    fn duplicate_first(v: &mut [u64]) {
+        if v.len() >= 2 {
+            v[1] = v[0];
+        }
+    }
+    let duplicating = SortEntry::<u64>::new(
+        "rust_duplicating_unstable".into(),
+        false,
+        true,
+        Box::new(|v| {
+            v.sort_unstable();
+            duplicate_first(v);
+        }),
+        Box::new(|v, compare| {
+            v.sort_unstable_by(compare);
+            duplicate_first(v);
+        }),
+    );
+    let result = contract::check(&duplicating);
+    assert!(matches!(
+        result.verdict(Clause::SameElements),
+        Verdict::Fail(_)
+    ));
+    assert_eq!(result.verdict(Clause::Stable), &Verdict::NotClaimed);
+    assert!(result.is_failure());
+}
+
+// Every sort has to use O(log n) stack, also on the inputs synthesized against it. The bound is the
+// depth limit of introsort, 2 * log2(len) levels of at most the stack used at len 1024 each. Sorts
+// like ipnsort that recurse into the left instead of the shorter side rely on that limit, a linear
+// recursion would need 64 times the stack.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stack_depth() {
+    use sort_research_rs::analysis::stack_depth;
+    use sort_research_rs::registry;
+
+    for entry in registry::general_sorts::<i32>() {
+        let (_, small) = stack_depth::worst_case(&entry, 1 << 10);
+        let (input_name, large) = stack_depth::worst_case(&entry, 1 << 16);
+        assert!(small > 0, "{}", entry.name());
+        assert!(
+            large <= stack_depth::depth_limit_bound(small, 1 << 16),
+            "{}: {small} bytes at len 1024, {large} bytes at len 65536 on {input_name}",
+            entry.name()
+        );
+    }
+
+    #[cfg(feature = "instrument_depth")]
+    {
+        use sort_research_rs::analysis::adversary;
+        use sort_research_rs::instrument::depth;
+        use sort_research_rs::unstable::rust_std;
+
+        let entry = registry::find::<i32>("rust_std_unstable").unwrap();
+        for len in [0, 1, 20, 21, 1_000, 1 << 16] {
+            for mut v in [
+                adversary::synthesize_for(&entry, len),
+                sort_test_tools::patterns::random_uniform(len, 0..2),
+            ] {
+                let ((), recursion) = depth::record(|| rust_std::sort(&mut v));
+                assert!(recursion.max <= depth::log2_bound(len), "{recursion:?}");
+            }
+        }
+    }
+}
+
+// Painting sees stack the comparison function can't, the bound is the same as for stack_depth. The
+// sort thread has its own fixed size stack, so this doesn't depend on the test thread stack.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn painted_stack_depth() {
+    use sort_research_rs::analysis::stack_depth;
+    use sort_research_rs::registry;
+
+    for entry in registry::general_sorts::<i32>() {
+        let (_, small) = stack_depth::worst_case_painted(&entry, 1 << 10);
+        let (input_name, large) = stack_depth::worst_case_painted(&entry, 1 << 16);
+        let (small, large) = (small.unwrap(), large.unwrap());
+        assert!(
+            large <= stack_depth::depth_limit_bound(small, 1 << 16),
+            "{}: {small} bytes at len 1024, {large} bytes at len 65536 on {input_name}",
+            entry.name()
+        );
+    }
+}
+
+#[test]
+fn cardinality_inputs() {
+    use sort_research_rs::analysis::cardinality;
+
+    assert_eq!(cardinality::distinct_counts(0), [1]);
+    assert_eq!(cardinality::distinct_counts(1), [1]);
+    assert_eq!(cardinality::distinct_counts(5), [1, 2, 4, 5]);
+    assert_eq!(cardinality::distinct_counts(8), [1, 2, 4, 8]);
+
+    for distinct in cardinality::distinct_counts(1_000) {
+        let mut v = cardinality::input(1_000, distinct);
+        reference_sort::sort(&mut v);
+        v.dedup();
+        assert!(v.len() <= distinct && v.len() > distinct / 2, "{distinct}");
+    }
+}
+
+#[test]
+fn tie_order() {
+    use sort_research_rs::analysis::tie_order::{self, TieOrder};
+    use sort_research_rs::registry;
+
+    let tagged = |pairs: &[(u64, u64)]| {
+        pairs
+            .iter()
+            .map(|&(key, pos)| (key << 32) | pos)
+            .collect::<Vec<_>>()
+    };
+
+    let order = tie_order::tie_order(&tagged(&[(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)]));
+    assert_eq!(order.tie_pairs, 4);
+    assert_eq!(order.distance(), 0.0);
+
+    let order = tie_order::tie_order(&tagged(&[(0, 1), (0, 0), (1, 4), (1, 2), (1, 3)]));
+    assert_eq!(
+        order,
+        TieOrder {
+            tie_pairs: 4,
+            inverted_pairs: 3
+        }
+    );
+
+    let keys = sort_test_tools::patterns::random_uniform(1_000, 0..=9);
+    let stable = registry::find::<u64>("rust_std_stable").unwrap();
+    assert_eq!(tie_order::measure(&stable, &keys).unwrap().distance(), 0.0);
+}
+
+#[test]
+fn output_permutation() {
+    use sort_research_rs::analysis::permutation::{self, CycleStats};
+    use sort_research_rs::registry;
+
+    assert_eq!(CycleStats::of(&[]), CycleStats::default());
+    assert_eq!(
+        CycleStats::of(&[1, 2, 0, 3, 5, 4]),
+        CycleStats {
+            len: 6,
+            fixed_points: 1,
+            cycles: 2,
+            longest_cycle: 3,
+            displacement: 6,
+        }
+    );
+    assert!(std::panic::catch_unwind(|| CycleStats::of(&[1, 1])).is_err());
+
+    let tagged = |pairs: &[(u64, u64)]| {
+        pairs
+            .iter()
+            .map(|&(key, pos)| (key << 32) | pos)
+            .collect::<Vec<_>>()
+    };
+
+    // The input keys are 1, 0, 0, 1, 1.
+    let perm = permutation::output_permutation(&tagged(&[(0, 2), (0, 1), (1, 0), (1, 3), (1, 4)]));
+    assert_eq!(perm.whole.cycles, 1);
+    assert_eq!(perm.whole.longest_cycle, 2);
+    assert_eq!(perm.ties.fixed_points, 3);
+    assert_eq!(perm.ties.cycles, 1);
+    assert_eq!((perm.stable_tie_groups, perm.tie_groups), (1, 2));
+
+    let keys = sort_test_tools::patterns::random_uniform(1_000, 0..=9);
+    let stable = registry::find::<u64>("rust_std_stable").unwrap();
+    let perm = permutation::measure(&stable, &keys).unwrap();
+    assert_eq!(perm.ties.fixed_points, 1_000);
+    assert_eq!(perm.stable_tie_groups, perm.tie_groups);
+    assert_eq!(perm.tie_groups, 10);
+    assert!(perm.whole.cycles > 0);
+}
+
+#[test]
+fn fault_injector() {
+    use sort_research_rs::fault::{Fault, FaultInjector, InjectedPanic, Trigger};
+    use std::cmp::Ordering::{Equal, Greater, Less};
+    use std::panic::{self, AssertUnwindSafe};
+
+    let mut injector = FaultInjector::with_seed(1)
+        .inject(Trigger::Every(3), Fault::Reverse)
+        .inject(Trigger::From(6), Fault::Result(Equal));
+    let results = (0..8)
+        .map(|_| injector.compare(&1, &2, i32::cmp))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        [Less, Less, Greater, Less, Less, Greater, Equal, Equal]
+    );
+    assert_eq!((injector.calls(), injector.injected()), (8, 4));
+
+    // The same seed gives the same comparisons.
+    let sample = |seed| {
+        let mut injector =
+            FaultInjector::with_seed(seed).inject(Trigger::Probability(0.5), Fault::Reverse);
+        (0..64)
+            .map(|_| injector.compare(&1, &2, i32::cmp))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sample(3), sample(3));
+    assert!(sample(3).contains(&Less) && sample(3).contains(&Greater));
+
+    let mut injector = FaultInjector::panic_in_first(10);
+    let mut v = (0..100).rev().collect::<Vec<i32>>();
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+        v.sort_by(injector.wrap(i32::cmp));
+    }))
+    .unwrap_err();
+    let call = payload.downcast_ref::<InjectedPanic>().unwrap().call;
+    assert!(call < 10 && injector.calls() == call + 1);
+}
+
+#[test]
+fn stress() {
+    use sort_research_rs::analysis::stress::{Case, Checkpoint};
+    use sort_research_rs::registry::{self, SortEntry};
+
+    let ipnsort = registry::find::<i32>("rust_ipnsort_unstable").unwrap();
+    // Overwrites the largest element, caught unless the injected panic interrupts the sort.
+    let broken = SortEntry::<i32>::new(
+        "rust_broken".to_string(),
+        true,
+        true,
+        Box::new(|v| {
+            v.sort_unstable();
+            if let Some(last) = v.last_mut() {
+                *last = last.wrapping_add(1);
+            }
+        }),
+        Box::new(|v, compare| {
+            v.sort_unstable_by(|a, b| compare(a, b));
+            if let Some(last) = v.last_mut() {
+                *last = last.wrapping_add(1);
+            }
+        }),
+    );
+
+    let max_len = if cfg!(miri) { 50 } else { 10_000 };
+    for seed in 0..if cfg!(miri) { 5 } else { 200 } {
+        let case = Case::new(seed, max_len);
+        assert_eq!(case.input, Case::new(seed, max_len).input);
+        assert!(case.input.len() <= max_len);
+
+        assert_eq!(case.check(&ipnsort), None, "STRESS_SEED={seed}");
+        if case.fault.is_none() && !case.input.is_empty() {
+            assert!(case.check(&broken).is_some(), "STRESS_SEED={seed}");
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("stress_checkpoint_{}", std::process::id()));
+    let checkpoint = Checkpoint {
+        next_seed: 7,
+        cases: 6,
+        sort_calls: 42,
+        elements: 1_000,
+        failures: 1,
+        elapsed_secs: 60,
+        running: Some("STRESS_SEED=7 rust_ipnsort_unstable".to_string()),
+    };
+    checkpoint.save(&path).unwrap();
+    assert_eq!(Checkpoint::load(&path).unwrap(), Some(checkpoint));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(Checkpoint::load(&path).unwrap(), None);
+}
+
+#[test]
+fn test_sizes() {
+    use sort_test_tools::test_sizes::{self, SizeProfile};
+
+    assert_eq!(
+        test_sizes::parse_lengths("100, 3,1_000,3").unwrap(),
+        [3, 100, 1_000]
+    );
+    for invalid in ["", "1,", "x", "-1", "1", "0,100"] {
+        assert!(test_sizes::parse_lengths(invalid).is_err(), "{invalid}");
+    }
+
+    for name in ["quick", "default", "thorough", "miri"] {
+        let profile = name.parse::<SizeProfile>().unwrap();
+        for lengths in [profile.lengths(), profile.stress_lengths()] {
+            assert!(lengths.windows(2).all(|w| w[0] < w[1]), "{name}");
+        }
+    }
+    assert!("large".parse::<SizeProfile>().is_err());
+
+    let lengths = test_sizes::lengths();
+    assert_eq!(
+        test_sizes::without_largest(lengths.len() + 1),
+        &lengths[..1]
+    );
+}
+
+#[test]
+fn verify() {
+    use sort_research_rs::verify;
+    use sort_test_tools::patterns;
+
+    assert!(verify::is_sorted_by::<i32>(&[], i32::cmp));
+    assert!(verify::is_sorted_by(&[1, 1, 2, 3], i32::cmp));
+    assert!(!verify::is_sorted_by(&[1, 3, 2], i32::cmp));
+    assert!(verify::is_sorted_by(&[3, 2, 2, 1], |a: &i32, b| b.cmp(a)));
+
+    assert!(verify::same_elements(&[3, 1, 2, 1], &[1, 1, 2, 3]));
+    assert!(!verify::same_elements(&[3, 1, 2, 1], &[1, 2, 2, 3]));
+    assert!(!verify::same_elements(&[1, 2], &[1, 2, 2]));
+
+    // (key, occurrence), only the key is compared.
+    let orig = [(2, 0), (1, 0), (2, 1), (1, 1)];
+    let by_key = |a: &(i32, i32), b: &(i32, i32)| a.0.cmp(&b.0);
+    assert!(verify::is_stable_sorted(
+        &orig,
+        &[(1, 0), (1, 1), (2, 0), (2, 1)],
+        by_key
+    ));
+    assert!(!verify::is_stable_sorted(
+        &orig,
+        &[(1, 1), (1, 0), (2, 0), (2, 1)],
+        by_key
+    ));
+    assert!(!verify::is_stable_sorted(
+        &orig,
+        &[(1, 0), (1, 1), (2, 0), (2, 0)],
+        by_key
+    ));
+
+    for len in [0, 1, 2, 17, 1_000] {
+        let orig = patterns::random_uniform(len, 0..=9)
+            .into_iter()
+            .zip(0..)
+            .collect::<Vec<(i32, usize)>>();
+
+        let mut stable = orig.clone();
+        reference_sort::sort_by_key(&mut stable, |x| x.0);
+        assert!(verify::is_sorted_by(&stable, by_key_usize));
+        assert!(verify::same_elements(&orig, &stable));
+        assert!(verify::is_stable_sorted(&orig, &stable, by_key_usize));
+
+        // Reversing the equal elements keeps the result sorted but not stable.
+        let mut reversed = stable.clone();
+        reference_sort::sort_by(&mut reversed, |a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        assert!(verify::is_sorted_by(&reversed, by_key_usize));
+        assert!(verify::same_elements(&orig, &reversed));
+        assert_eq!(
+            verify::is_stable_sorted(&orig, &reversed, by_key_usize),
+            reversed == stable
+        );
+    }
+
+    fn by_key_usize(a: &(i32, usize), b: &(i32, usize)) -> std::cmp::Ordering {
+        a.0.cmp(&b.0)
+    }
+}
+
+// Other tests sort concurrently and may land in the ring as well, so only the invocations with the
+// lens of this test are checked.
+#[test]
+#[cfg(feature = "instrument_audit")]
+fn audit_log() {
+    use sort_research_rs::instrument::audit::{self, Invocation};
+    use sort_research_rs::registry;
+
+    audit::log_to_ring(1 << 16);
+    let stable = registry::find::<i32>("rust_std_stable").unwrap();
+    let unstable = registry::find::<i32>("rust_std_unstable").unwrap();
+    stable.sort(&mut sort_test_tools::patterns::random(12_345));
+    unstable.sort_by(&mut sort_test_tools::patterns::random(12_346), |a, b| {
+        b.cmp(a)
+    });
+    let invocations = audit::take_ring();
+    audit::stop();
+
+    let find = |len| {
+        invocations
+            .iter()
+            .find(|invocation| invocation.len == len)
+            .unwrap_or_else(|| panic!("no invocation with len {len} in {invocations:?}"))
+    };
+    assert_eq!(
+        (find(12_345).sort_name.as_str(), find(12_345).stable),
+        ("rust_std_stable", true)
+    );
+    assert_eq!(
+        (find(12_346).sort_name.as_str(), find(12_346).stable),
+        ("rust_std_unstable", false)
+    );
+    assert_eq!(find(12_345).type_size, std::mem::size_of::<i32>());
+
+    let line = find(12_345).to_string();
+    assert_eq!(line.parse::<Invocation>().as_ref(), Ok(find(12_345)));
+    assert!("rust_std_stable\t8\tx\tstable\t1"
+        .parse::<Invocation>()
+        .is_err());
+
+    assert_eq!(audit::workload_histogram([]), None);
+    assert_eq!(
+        audit::workload_histogram([0, 1, 5, 6, 7, 1000]).as_deref(),
+        Some("1:1-1,3:4-7,1:512-1023")
+    );
+}
+
+// A descending input moves every element, so the writes of a complete trace cover the whole slice.
+// The unstable sort takes it apart with a reversal and swaps alone, which have to be traced too.
+#[test]
+#[cfg(feature = "instrument_mem")]
+fn mem_trace() {
+    use sort_research_rs::instrument::mem::{self, AccessKind};
+    use sort_research_rs::{stable, unstable};
+
+    type SortFn = fn(&mut [u64]);
+    let sorts: [(&str, SortFn); 2] = [
+        ("stable", |v| stable::rust_std::sort_by(v, |a, b| a.cmp(b))),
+        ("unstable", |v| {
+            unstable::rust_std::sort_by(v, |a, b| a.cmp(b))
+        }),
+    ];
+    for (name, sort) in sorts {
+        for len in [2, 15, 100, 1_000] {
+            let mut v = (0..len as u64).rev().collect::<Vec<_>>();
+            let ((), trace) = mem::record(v.as_ptr(), || sort(&mut v));
+            assert!(v.windows(2).all(|w| w[0] <= w[1]));
+
+            let size = std::mem::size_of::<u64>() as isize;
+            let mut written = vec![false; len];
+            for access in &trace.accesses {
+                if access.kind != AccessKind::Write {
+                    continue;
+                }
+                let end = access.offset + access.size as isize;
+                for offset in
+                    (access.offset.max(0)..end.min(len as isize * size)).step_by(size as usize)
+                {
+                    written[(offset / size) as usize] = true;
+                }
+            }
+            assert!(written.iter().all(|&w| w), "{name} len: {len}");
+        }
+    }
+}
+
+// Comparison counts are deterministic, so the search is reproducible from its seed and the score of
+// the input it returns can be checked. It never returns an input worse than its starting point.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn perf_fuzz() {
+    use sort_research_rs::analysis::perf_fuzz::{self, Objective, SearchConfig};
+    use sort_research_rs::instrument::comp::CountingCmp;
+    use sort_research_rs::registry;
+
+    let entry = registry::find::<i32>("rust_std_unstable").unwrap();
+    for len in [0, 1, 2, 200] {
+        let config = SearchConfig {
+            iterations: 200,
+            ..SearchConfig::new(len, Objective::Comparisons)
+        };
+        let result = perf_fuzz::search(&entry, &config);
+        assert_eq!(result.input.len(), len);
+        assert!(result.score >= result.initial_score, "len: {len}");
+
+        let mut cmp = CountingCmp::new(i32::cmp);
+        let mut v = result.input.clone();
+        entry.sort_by(&mut v, |a, b| cmp.compare(a, b));
+        assert_eq!(cmp.count() as f64, result.score, "len: {len}");
+
+        let again = perf_fuzz::search(&entry, &config);
+        assert_eq!((again.input, again.score), (result.input, result.score));
+    }
+}
+
+#[test]
+fn certificate() {
+    use sort_research_rs::analysis::certificate::{self, Certificate, Generator, Source};
+    use sort_research_rs::registry;
+
+    let entry = registry::find::<i32>("rust_std_stable").unwrap();
+    let source = Source {
+        generator: Generator::Fuzz,
+        seed: 7,
+        max_len: 1_000,
+    };
+    let input = sort_test_tools::patterns::random(500);
+
+    let certify = |descending| {
+        let run = certificate::run(&entry, &input, descending);
+        Certificate::new(source, entry.name(), "i32", descending, &input, &run)
+    };
+    let ascending = certify(false);
+    assert!(ascending.comparisons > 0);
+    assert_eq!(ascending.differences(&certify(false)), Vec::<String>::new());
+
+    let mut expected = input.clone();
+    reference_sort::sort(&mut expected);
+    assert_eq!(ascending.input_hash, certificate::hash_elements(&input));
+    assert_eq!(ascending.output_hash, certificate::hash_elements(&expected));
+
+    let descending = certify(true);
+    let differences = ascending.differences(&descending);
+    assert!(differences
+        .iter()
+        .any(|difference| difference.starts_with("output hash")));
+    assert!(!differences
+        .iter()
+        .any(|difference| difference.starts_with("input hash")));
+
+    let line = ascending.to_string();
+    assert_eq!(line.parse::<Certificate>().as_ref(), Ok(&ascending));
+    assert!(line
+        .replace("ascending", "sideways")
+        .parse::<Certificate>()
+        .is_err());
+}
+
+#[test]
+fn shard() {
+    use sort_research_rs::analysis::shard::Shard;
+
+    let shards = (1..=4)
+        .map(|index| format!("{index}/4").parse::<Shard>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(shards[2].to_string(), "3/4");
+    for invalid in ["0/4", "5/4", "3", "a/4", "1/0"] {
+        assert!(invalid.parse::<Shard>().is_err(), "{invalid}");
+    }
+
+    // Every seed and every benchmark is in exactly one shard.
+    for seed in 0..100 {
+        let owners = shards.iter().filter(|shard| shard.contains_seed(seed));
+        assert_eq!(owners.count(), 1);
+    }
+    for (shard, seed) in shards.iter().zip([10, 11, 12, 13]) {
+        let next = shard.next_seed(seed);
+        assert!(next >= seed && next < seed + 4 && shard.contains_seed(next));
+    }
+
+    for len in [1, 10, 100, 1_000, 10_000] {
+        let name = |sort| format!("{sort}-hot-u64-random-{len}");
+        let owners = shards
+            .iter()
+            .filter(|shard| shard.contains_bench(&name("rust_std_stable")))
+            .collect::<Vec<_>>();
+        assert_eq!(owners.len(), 1);
+        // All sorts of one input run in the same shard.
+        assert!(owners[0].contains_bench(&name("c_crumsort_unstable")));
+    }
+
+    assert!((0..10).all(|seed| Shard::ALL.contains_seed(seed)));
+}
+
+#[test]
+fn merge_sim() {
+    use sort_research_rs::analysis::merge_sim::{self, Policy};
+
+    for policy in Policy::ALL {
+        assert_eq!(policy.name().parse::<Policy>(), Ok(policy));
+        assert_eq!(merge_sim::simulate(policy, &[]).merges, 0);
+        assert_eq!(merge_sim::simulate(policy, &[1_000]).merged_elements, 0);
+        // Four equal runs have one optimal merge tree.
+        assert_eq!(merge_sim::simulate(policy, &[8; 4]).merged_elements, 64);
+    }
+    assert!("shellsort".parse::<Policy>().is_err());
+
+    assert_eq!(
+        merge_sim::natural_run_lens(&[1, 2, 2, 0, 5, 4, 3, 2]),
+        vec![3, 2, 3]
+    );
+    assert_eq!(merge_sim::with_min_run(&[1, 2, 5, 1, 1], 3), vec![3, 5, 2]);
+
+    for seed in 0..20 {
+        let run_lens = merge_sim::random_run_lens(500, 1 << (seed % 12), seed);
+        let len = run_lens.iter().sum::<u64>();
+        let bound = merge_sim::entropy_bound(&run_lens);
+
+        for policy in Policy::ALL {
+            let cost = merge_sim::simulate(policy, &run_lens);
+            assert_eq!(cost.len, len);
+            assert_eq!(cost.merges, run_lens.len() as u64 - 1, "{policy}");
+            assert!(cost.merged_elements as f64 >= bound, "{policy}");
+            // The stack stays logarithmic in the number of runs.
+            assert!(cost.max_stack_len <= 2 * (run_lens.len().ilog2() as usize + 2));
+        }
+
+        // The guarantee of powersort, at most n * H + 2n.
+        let powersort = merge_sim::simulate(Policy::Powersort, &run_lens);
+        assert!(powersort.merged_elements as f64 <= bound + 2.0 * len as f64);
+    }
+}
+
+// The kernels only accept their own len, registry-wide checks must not run them.
+#[test]
+#[cfg(feature = "small_sort")]
+fn small_sort_kernels() {
+    use sort_research_rs::registry;
+
+    let names = registry::sorts::<u32>()
+        .iter()
+        .map(|entry| entry.name().to_string())
+        .collect::<Vec<_>>();
+
+    for (len, entry) in registry::small_sort_kernels::<u32>() {
+        assert!(!names.iter().any(|name| name == entry.name()));
+        assert!(!entry.is_general());
+
+        // By the 0-1 principle this covers all inputs of the networks.
+        for bits in 0..(1u32 << len) {
+            let input = (0..len).map(|i| (bits >> i) & 1).collect::<Vec<_>>();
+            let mut actual = input.clone();
+            entry.sort(&mut actual);
+            assert!(actual.windows(2).all(|w| w[0] <= w[1]), "{} {input:?}", entry.name());
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "small_sort")]
+fn small_sort_zoo() {
+    use sort_research_rs::other::small_sort::zoo::{self, SmallSort};
+
+    fn check<S: SmallSort>(_small_sort: S) {
+        let check_input = |input: &[u32]| {
+            let mut expected = input.to_vec();
+            reference_sort::sort(&mut expected);
+            let mut actual = input.to_vec();
+            S::sort(&mut actual);
+            assert_eq!(actual, expected, "{} input: {input:?}", S::name());
+        };
+
+        // All permutations, by Heap's algorithm.
+        for len in 0..=S::max_len().min(8) {
+            let mut perm = (0..len as u32).collect::<Vec<_>>();
+            let mut counters = vec![0; len];
+            check_input(&perm);
+            let mut i = 0;
+            while i < len {
+                if counters[i] < i {
+                    perm.swap(if i % 2 == 0 { 0 } else { counters[i] }, i);
+                    check_input(&perm);
+                    counters[i] += 1;
+                    i = 0;
+                } else {
+                    counters[i] = 0;
+                    i += 1;
+                }
+            }
+        }
+
+        // By the 0-1 principle this covers all inputs of the networks.
+        for len in 0..=S::max_len().min(16) {
+            for bits in 0..(1u32 << len) {
+                check_input(&(0..len).map(|i| (bits >> i) & 1).collect::<Vec<_>>());
+            }
+        }
+
+        for len in 0..=S::max_len() {
+            for seed in 0..20 {
+                let input = (0..len as u32)
+                    .map(|i| (i ^ seed).wrapping_mul(2_654_435_761) >> 29)
+                    .collect::<Vec<_>>();
+                check_input(&input);
+            }
+        }
+
+        // A panic in the comparison function leaves every element in the slice exactly once.
+        for len in [S::max_len() / 2, S::max_len()] {
+            for panic_after in [0, len / 2, len * 2] {
+                let input = (0..len).rev().map(|i| i.to_string()).collect::<Vec<_>>();
+                let mut actual = input.clone();
+                let mut comparisons = 0;
+                let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    S::sort_by(&mut actual, &mut |a: &String, b: &String| {
+                        comparisons += 1;
+                        assert!(comparisons <= panic_after);
+                        a < b
+                    });
+                }));
+
+                let mut expected = input;
+                reference_sort::sort(&mut expected);
+                reference_sort::sort(&mut actual);
+                assert_eq!(actual, expected, "{} len: {len}", S::name());
+            }
+        }
+    }
+
+    check(zoo::insertion_left::SmallSortImpl);
+    check(zoo::insertion_binary::SmallSortImpl);
+    check(zoo::network_table::SmallSortImpl);
+    check(zoo::hybrid_sort4::SmallSortImpl);
+    check(zoo::hybrid_sort8::SmallSortImpl);
+    check(zoo::hybrid_sort16::SmallSortImpl);
+}
+
+// Every pivot strategy has to sort its own worst-case input, and the heapsort fallback has to keep
+// it within a small multiple of n log n comparisons.
+#[test]
+#[cfg(feature = "pivot_strategies")]
+fn pivot_strategies() {
+    use sort_research_rs::analysis::adversary;
+    use sort_research_rs::registry;
+
+    for strategy in ["median3", "ninther", "pseudo_median27", "random"] {
+        let name = format!("rust_std_unstable_pivot_{strategy}");
+        let entry = registry::find::<i32>(&name).unwrap();
+
+        for len in [0, 1, 20, 21, 30, 100, 1_000, 10_000] {
+            let mut input = adversary::synthesize_for(&entry, len);
+
+            let mut comparisons = 0;
+            entry.sort_by(&mut input, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(input, (0..len as i32).collect::<Vec<_>>(), "{name}");
+
+            let n_log_n = len as f64 * (len as f64).log2().max(1.0);
+            assert!(
+                comparisons as f64 <= 4.0 * n_log_n,
+                "{name} len: {len} comparisons: {comparisons}"
+            );
+        }
+    }
+}
+
+// Every complete partition scheme has to put exactly the elements less than the pivot first and
+// keep every element, including for pivots that are not in the slice. Empty slices are left out,
+// quicksorts never partition them and several schemes compute `len - 1` up front. Not covered:
+// hoare_crumsort_rs is unfinished, lomuto_iterleaved returns wrong counts for many lens,
+// hybrid_block_partition from its block path at 4096 elements on, small_partition is limited to
+// 128 elements, sum_is_less only counts, and the cyclic permutations of hoare_block_butterfly and
+// hybrid_bitset_partition copy an element onto itself, which the debug precondition checks of
+// `ptr::copy_nonoverlapping` reject.
+#[test]
+#[cfg(feature = "partition")]
+fn partition() {
+    use sort_research_rs::other::partition::{self, Partition};
+    use sort_test_tools::patterns;
+
+    fn check_input<T: Ord + Clone + std::fmt::Debug, P: Partition>(input: &[T], pivot: &T) {
+        let mut actual = input.to_vec();
+        let lt_len = P::partition(&mut actual, pivot);
+
+        let name = P::name();
+        let expected_lt_len = input.iter().filter(|elem| *elem < pivot).count();
+        assert_eq!(lt_len, expected_lt_len, "{name} len: {}", input.len());
+        assert!(actual[..lt_len].iter().all(|elem| elem < pivot), "{name}");
+        assert!(actual[lt_len..].iter().all(|elem| elem >= pivot), "{name}");
+
+        let mut expected = input.to_vec();
+        reference_sort::sort(&mut expected);
+        reference_sort::sort(&mut actual);
+        assert_eq!(actual, expected, "{name}");
+    }
+
+    fn check<P: Partition>(_partition: P) {
+        let pattern_providers: [fn(usize) -> Vec<i32>; 6] = [
+            patterns::random,
+            |len| patterns::random_uniform(len, 0..2),
+            |len| patterns::random_uniform(len, 0..20),
+            patterns::all_equal,
+            patterns::ascending,
+            patterns::descending,
+        ];
+
+        for len in [1, 2, 3, 7, 31, 32, 33, 100, 255, 1_000, 5_000] {
+            for pattern_provider in pattern_providers {
+                let input = pattern_provider(len);
+                let middle = input.get(len / 2).copied().unwrap_or_default();
+
+                for pivot in [middle, i32::MIN, i32::MAX] {
+                    check_input::<_, P>(&input, &pivot);
+
+                    let input_str = input
+                        .iter()
+                        .map(|val| format!("{val:011}"))
+                        .collect::<Vec<_>>();
+                    check_input::<_, P>(&input_str, &format!("{pivot:011}"));
+                }
+            }
+        }
+    }
+
+    check(partition::hoare_block::PartitionImpl);
+    check(partition::hoare_branchy::PartitionImpl);
+    check(partition::hoare_branchy_cyclic::PartitionImpl);
+    check(partition::hoare_crumsort::PartitionImpl);
+    check(partition::lomuto_branchless::PartitionImpl);
+    check(partition::lomuto_branchless_cyclic::PartitionImpl);
+    check(partition::lomuto_branchless_cyclic_opt::PartitionImpl);
+    check(partition::lomuto_branchy::PartitionImpl);
+    check(partition::lomuto_nanosort::PartitionImpl);
+    check(partition::stable_2side_fill::PartitionImpl);
+    check(partition::three_way_bentley_mcilroy::PartitionImpl);
+    check(partition::three_way_dijkstra::PartitionImpl);
+
+    // The equal elements end up in the middle.
+    for partition_three_way in [
+        partition::three_way_bentley_mcilroy::partition_three_way,
+        partition::three_way_dijkstra::partition_three_way,
+    ] {
+        let mut v = patterns::random_uniform(1_000, 0..5);
+        let eq_len = v.iter().filter(|val| **val == 2).count();
+
+        let (eq_start, gt_start) = partition_three_way(&mut v, &2, &mut |a, b| a.lt(b));
+        assert_eq!(gt_start - eq_start, eq_len);
+        assert!(v[..eq_start].iter().all(|val| *val < 2));
+        assert!(v[eq_start..gt_start].iter().all(|val| *val == 2));
+        assert!(v[gt_start..].iter().all(|val| *val > 2));
+    }
+}
+
+// Every search returns what slice::partition_point returns, for every value on inputs with
+// duplicates, including the empty input and predicates that hold for all or no elements.
+#[test]
+#[cfg(feature = "partition_point")]
+fn partition_point() {
+    use sort_research_rs::other::partition_point::{
+        branchless_bitwise, branchless_clean, branchless_prefetch, branchy, gallop, std,
+    };
+
+    type PartitionPointFn = fn(&[i32], &mut dyn FnMut(&i32) -> bool) -> usize;
+    let searches: [(&str, PartitionPointFn); 6] = [
+        ("branchless_bitwise", |v, pred| {
+            branchless_bitwise::partition_point(v, pred)
+        }),
+        ("branchless_clean", |v, pred| {
+            branchless_clean::partition_point(v, pred)
+        }),
+        ("branchless_prefetch", |v, pred| {
+            branchless_prefetch::partition_point(v, pred)
+        }),
+        ("branchy", |v, pred| branchy::partition_point(v, pred)),
+        ("gallop", |v, pred| gallop::partition_point(v, pred)),
+        ("std", |v, pred| std::partition_point(v, pred)),
+    ];
+
+    let max_len = if cfg!(miri) { 8 } else { 70 };
+    for len in (0..=max_len).chain([1_000, 4_097]) {
+        // Each value twice, the values below and above all elements select none and all of them.
+        let v = (0..len as i32).map(|i| i / 2).collect::<Vec<_>>();
+        for val in -1..=(len as i32 / 2 + 1) {
+            let expected = v.partition_point(|&elem| elem < val);
+            for (name, search) in searches {
+                let actual = search(&v, &mut |&elem| elem < val);
+                assert_eq!(actual, expected, "{name} len: {len} val: {val}");
+            }
+        }
+
+        for (name, search) in searches {
+            assert_eq!(search(&v, &mut |_| true), len, "{name} len: {len}");
+            assert_eq!(search(&v, &mut |_| false), 0, "{name} len: {len}");
+        }
+    }
+}
+
+// The merge path split agrees with counting the elements of `a` in a stable merge, on every
+// diagonal of inputs with ties between and within `a` and `b`.
+#[test]
+#[cfg(feature = "partition_point")]
+fn merge_path() {
+    use sort_research_rs::other::partition_point::merge_path;
+
+    fn naive_split(a: &[i32], b: &[i32], diagonal: usize) -> usize {
+        let (mut i, mut j) = (0, 0);
+        while i + j < diagonal {
+            if j == b.len() || (i < a.len() && a[i] <= b[j]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        i
+    }
+
+    let max_len = if cfg!(miri) { 6 } else { 24 };
+    for a_len in 0..=max_len {
+        for b_len in 0..=max_len {
+            let a = (0..a_len as i32).map(|i| i / 2).collect::<Vec<_>>();
+            let b = (0..b_len as i32).map(|i| i / 3 + 1).collect::<Vec<_>>();
+            for diagonal in 0..=a_len + b_len {
+                let expected = naive_split(&a, &b, diagonal);
+                let context = format!("a: {a:?} b: {b:?} diagonal: {diagonal}");
+                assert_eq!(
+                    merge_path::merge_path_branchy(&a, &b, diagonal),
+                    expected,
+                    "{context}"
+                );
+                assert_eq!(
+                    merge_path::merge_path_branchless(&a, &b, diagonal),
+                    expected,
+                    "{context}"
+                );
+            }
+        }
+    }
+
+    let result = std::panic::catch_unwind(|| merge_path::merge_path_branchy(&[1], &[2], 3));
+    assert!(result.is_err());
+}
+
+#[test]
+#[cfg(feature = "rotate")]
+fn rotate() {
+    use sort_research_rs::other::rotate::{self, Rotate};
+
+    fn check<R: Rotate>(_rotate: R) {
+        for len in (0..=40).chain([100, 997, 1_024]) {
+            for mid in 0..=len {
+                let input = (0..len).collect::<Vec<_>>();
+                let mut expected = input.clone();
+                expected.rotate_left(mid);
+
+                let mut actual = input.clone();
+                R::rotate_left(&mut actual, mid);
+                assert_eq!(actual, expected, "{} len: {len} mid: {mid}", R::name());
+
+                // Every element is moved exactly once, none is duplicated or dropped.
+                let to_strings =
+                    |v: &[usize]| v.iter().map(|val| val.to_string()).collect::<Vec<_>>();
+                let mut actual_str = to_strings(&input);
+                R::rotate_left(&mut actual_str, mid);
+                assert_eq!(actual_str, to_strings(&expected), "{}", R::name());
+            }
+        }
+
+        let mut zsts = vec![(); 10];
+        R::rotate_left(&mut zsts, 3);
+    }
+
+    check(rotate::gries_mills::RotateImpl);
+    check(rotate::juggling::RotateImpl);
+    check(rotate::piston::RotateImpl);
+    check(rotate::std::RotateImpl);
+    check(rotate::triple_reversal::RotateImpl);
+}
+
+#[test]
+fn is_sorted() {
+    use sort_research_rs::other::is_sorted;
+
+    fn check<T: Ord + std::fmt::Debug>(v: &[T]) {
+        let expected = v.is_sorted();
+        assert_eq!(is_sorted::is_sorted_simd(v), expected, "{v:?}");
+        assert_eq!(
+            is_sorted::is_sorted_by_chunked(v, &mut |a, b| a < b),
+            expected,
+            "{v:?}"
+        );
+    }
+
+    // Every position of the pair out of order, in the vector loop and the tail.
+    for len in 0..100 {
+        let ascending = (0..len as i32).collect::<Vec<_>>();
+        check(&ascending);
+
+        for pos in 0..len {
+            for val in [i32::MIN, i32::MAX] {
+                let mut v = ascending.clone();
+                v[pos] = val;
+
+                check(&v);
+                check(&v.iter().map(|&val| val as i64).collect::<Vec<_>>());
+                check(&v.iter().map(|&val| val as u32).collect::<Vec<_>>());
+                check(&v.iter().map(|val| format!("{val:011}")).collect::<Vec<_>>());
+            }
+        }
+    }
+}
+
+// The memoized sorts call the comparison function at most once per element pair, and answer every
+// other comparison from the cache.
+#[test]
+fn memo_sort() {
+    use std::collections::HashSet;
+
+    use sort_research_rs::other::memo_sort;
+
+    for len in [0, 1, 2, 20, 1_000] {
+        let input = keyed_with_index(len);
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+        // The elements stay in place while the indices are sorted, so their addresses identify them.
+        let mut pairs = HashSet::new();
+        let mut calls = 0;
+        let mut v = input.clone();
+        let stats = memo_sort::sort_by(&mut v, |a, b| {
+            calls += 1;
+            let (a_addr, b_addr) = (a as *const _ as usize, b as *const _ as usize);
+            assert!(pairs.insert((a_addr.min(b_addr), a_addr.max(b_addr))));
+            a.0.cmp(&b.0)
+        });
+        assert_eq!(v, expected, "len: {len}");
+        assert_eq!(stats.comparisons - stats.hits, calls);
+        assert_eq!(stats.comparisons == 0, len < 2);
+
+        // The positions are distinct, so comparing them too makes the unstable result unique.
+        let mut v = input;
+        let stats = memo_sort::sort_unstable_by(&mut v, |a, b| a.cmp(b));
+        assert_eq!(v, expected, "len: {len}");
+        assert!(stats.hits <= stats.comparisons);
+        assert!((0.0..=1.0).contains(&stats.hit_ratio()));
+    }
+}
+
+// Every slice of a batch is sorted on its own, no element crosses a slice boundary.
+#[test]
+fn batch_sort() {
+    use sort_research_rs::other::batch_sort;
+    use sort_test_tools::xorshift::XorShift;
+
+    type BatchSortFn = fn(&mut [&mut [(usize, usize)]]);
+    let sorts: [(&str, BatchSortFn); 4] = [
+        ("sort_batch", batch_sort::sort_batch),
+        ("sort_batch_by", |slices| {
+            batch_sort::sort_batch_by(slices, |a, b| a.cmp(b))
+        }),
+        ("sort_batch_stable", batch_sort::sort_batch_stable),
+        ("sort_batch_insertion", batch_sort::sort_batch_insertion),
+    ];
+
+    for len in [0, 1, 31, 1_000] {
+        let input = keyed_with_index(len);
+
+        let mut rng = XorShift::new(len as u64);
+        let mut v = input.clone();
+        let slice_lens = batch_sort::split_into_batch(&mut v, || 4 + rng.below(29))
+            .iter()
+            .map(|slice| slice.len())
+            .collect::<Vec<_>>();
+        assert_eq!(slice_lens.iter().sum::<usize>(), len);
+        if let Some((_, init)) = slice_lens.split_last() {
+            assert!(init.iter().all(|slice_len| (4..=32).contains(slice_len)));
+        }
+
+        for (name, sort) in sorts {
+            let mut rng = XorShift::new(len as u64);
+            let mut v = input.clone();
+            sort(&mut batch_sort::split_into_batch(&mut v, || {
+                4 + rng.below(29)
+            }));
+
+            let mut start = 0;
+            for &slice_len in &slice_lens {
+                let mut expected = input[start..start + slice_len].to_vec();
+                reference_sort::sort(&mut expected);
+                assert_eq!(v[start..start + slice_len], expected, "{name} len: {len}");
+                start += slice_len;
+            }
+        }
+
+        // Equal keys keep their order, the positions are not compared.
+        let stable_sorts: [fn(&mut [&mut [Keyed]]); 2] = [
+            batch_sort::sort_batch_stable,
+            batch_sort::sort_batch_insertion,
+        ];
+        for sort in stable_sorts {
+            let mut v = input
+                .iter()
+                .map(|&(key, i)| Keyed(key, i))
+                .collect::<Vec<_>>();
+            sort(&mut v.chunks_mut(16).collect::<Vec<_>>());
+
+            for (sorted, unsorted) in v.chunks(16).zip(input.chunks(16)) {
+                let mut expected = unsorted.to_vec();
+                reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+                assert!(sorted.iter().map(|&Keyed(key, i)| (key, i)).eq(expected));
+            }
+        }
+    }
+}
+
+// With 17 distinct keys the stable three-way partitions need less than half the comparisons of the
+// merge sort. Short inputs and many distinct values go to the merge sort, which has to work too.
+#[test]
+#[cfg(feature = "rust_dupsort")]
+fn dupsort() {
+    use sort_research_rs::instrument::comp::CountingCmp;
+    use sort_research_rs::stable::{rust_dupsort, rust_std};
+
+    for len in [0, 1, 20, 1_023, 1_024, 10_000] {
+        let input = keyed_with_index(len);
+        let mut expected = input.clone();
+        reference_sort::sort_by_key(&mut expected, |&(key, _)| key);
+
+        let mut cmp = CountingCmp::new(|a: &(usize, usize), b: &(usize, usize)| a.0.cmp(&b.0));
+        let mut v = input.clone();
+        rust_dupsort::sort_by(&mut v, |a, b| cmp.compare(a, b));
+        assert_eq!(v, expected, "len: {len}");
+
+        if len == 10_000 {
+            let dupsort_count = cmp.count();
+            cmp.reset();
+            let mut v = input;
+            rust_std::sort_by(&mut v, |a, b| cmp.compare(a, b));
+            assert!(dupsort_count < cmp.count() / 2, "{dupsort_count} {}", cmp.count());
+        }
+    }
+
+    let input = sort_test_tools::patterns::random(10_000);
+    let mut v = input.clone();
+    rust_dupsort::sort(&mut v);
+    let mut expected = input;
+    reference_sort::sort(&mut expected);
+    assert_eq!(v, expected);
+}
+
+// The reference sort is the oracle of all other tests, so it is checked without any other sort:
+// the result has to be ordered, hold every input element once, and keep equal keys in input order.
+#[test]
+fn reference_sort() {
+    use sort_test_tools::patterns;
+
+    fn check(keys: &[u8]) {
+        // Tagged with the input position, which every property below is checked with.
+        let input = keys.iter().copied().zip(0..).collect::<Vec<(u8, usize)>>();
+        let sorted = reference_sort::sorted_by(input.clone(), |a, b| a.0.cmp(&b.0));
+
+        let mut seen = vec![false; input.len()];
+        for &(key, pos) in &sorted {
+            assert_eq!(key, keys[pos]);
+            assert!(!std::mem::replace(&mut seen[pos], true), "{keys:?}");
+        }
+        assert!(seen.iter().all(|&seen| seen), "{keys:?}");
+
+        for pair in sorted.windows(2) {
+            assert!(pair[0].0 <= pair[1].0, "{keys:?}");
+            if pair[0].0 == pair[1].0 {
+                assert!(pair[0].1 < pair[1].1, "{keys:?}");
+            }
+        }
+    }
+
+    // Every input of up to 7 elements with 4 distinct keys, 21844 inputs.
+    let max_len = if cfg!(miri) { 4 } else { 7 };
+    for len in 0..=max_len {
+        for idx in 0..4usize.pow(len) {
+            let keys = (0..len)
+                .map(|i| (idx / 4usize.pow(i) % 4) as u8)
+                .collect::<Vec<_>>();
+            check(&keys);
+        }
+    }
+
+    for len in [20, 100, 1_000] {
+        let keys = patterns::random_uniform(len, 0..=255)
+            .into_iter()
+            .map(|val| val as u8)
+            .collect::<Vec<_>>();
+        check(&keys);
+    }
+
+    let mut v = vec![3, 1, 2, 1];
+    reference_sort::sort(&mut v);
+    assert_eq!(v, [1, 1, 2, 3]);
+
+    let mut v = vec!["b", "a", "c"];
+    reference_sort::sort_by(&mut v, |a, b| b.cmp(a));
+    assert_eq!(v, ["c", "b", "a"]);
+}
+
+#[test]
+fn shuffle_and_sample() {
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 2, 20, 1_000] {
+        let mut v = (0..len).collect::<Vec<_>>();
+        patterns::shuffle(&mut v);
+        let mut sorted = v.clone();
+        reference_sort::sort(&mut sorted);
+        assert!(sorted.iter().copied().eq(0..len));
+
+        // The seed is fixed per process, so is the permutation for a len.
+        let mut again = (0..len).collect::<Vec<_>>();
+        patterns::shuffle(&mut again);
+        assert_eq!(again, v);
+
+        for k in [0, len / 2, len] {
+            let indices = patterns::sample(len, k);
+            assert_eq!(indices, patterns::sample(len, k));
+            assert_eq!(indices.len(), k);
+
+            let mut distinct = indices.clone();
+            reference_sort::sort(&mut distinct);
+            distinct.dedup();
+            assert_eq!(distinct.len(), k);
+            assert!(indices.iter().all(|&idx| idx < len));
+        }
+    }
+
+    // Not the identity, the chance of that is 1 / 1000!.
+    let mut v = (0..1_000).collect::<Vec<_>>();
+    patterns::shuffle(&mut v);
+    assert!(v.iter().copied().ne(0..1_000));
+
+    assert!(std::panic::catch_unwind(|| patterns::sample(2, 3)).is_err());
+}
+
+// Every key but the largest occurs exactly once, as many as fit into the len.
+#[test]
+fn few_keys_single_intruder() {
+    use sort_test_tools::patterns;
+
+    for len in [0, 1, 2, 3, 20, 1_000] {
+        for key_count in [1, 2, 4, 16] {
+            let v = patterns::few_keys_single_intruder(len, key_count);
+            assert_eq!(v.len(), len);
+
+            let intruders = (key_count - 1).min(len);
+            for key in 0..key_count as i32 - 1 {
+                let count = v.iter().filter(|&&val| val == key).count();
+                let expected = (key < intruders as i32) as usize;
+                assert_eq!(count, expected, "len: {len} key_count: {key_count} key: {key}");
+            }
+            let largest = v.iter().filter(|&&val| val == key_count as i32 - 1).count();
+            assert_eq!(largest, len - intruders);
+        }
+    }
+
+    // Without a key there is nothing to fill the len with.
+    for pattern in [
+        patterns::few_keys_blocks,
+        patterns::few_keys_alternating,
+        patterns::few_keys_single_intruder,
+    ] {
+        assert!(std::panic::catch_unwind(|| pattern(10, 0)).is_err());
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn pattern_export_import() {
+    use sort_test_tools::patterns;
+
+    let random_sorted: fn(usize) -> Vec<i32> = |len| patterns::random_sorted(len, 50.0);
+
+    // The values only depend on the seed, not on the process seed or the caches.
+    let process_values = patterns::random(100);
+    let seeded = patterns::with_seed(patterns::random, 100, 1234);
+    assert_eq!(seeded, patterns::with_seed(patterns::random, 100, 1234));
+    assert_ne!(seeded, patterns::with_seed(patterns::random, 100, 1235));
+    assert_eq!(patterns::random(100), process_values);
+    assert_eq!(
+        patterns::with_seed(random_sorted, 100, 7),
+        patterns::with_seed(random_sorted, 100, 7)
+    );
+
+    let path = std::env::temp_dir().join(format!(
+        "sort_research_pattern_export_{}.txt",
+        std::process::id()
+    ));
+
+    for (name, pattern, len) in [
+        ("random", patterns::random as fn(usize) -> Vec<i32>, 1_000),
+        ("random_s50", random_sorted, 1_000),
+        ("ascending", patterns::ascending, 0),
+    ] {
+        let exported = patterns::export(&path, (name, pattern), len, 42).unwrap();
+        assert_eq!(exported.values, patterns::with_seed(pattern, len, 42));
+        assert_eq!(exported.name, name);
+        assert_eq!(exported.seed, 42);
+
+        let imported = patterns::import(&path).unwrap();
+        assert_eq!(imported, exported);
+    }
+
+    for invalid in [
+        "",
+        "random\n",
+        "# sort-research pattern v1\npattern: random\nlen: 2\nseed: 1\nrng: std\n5\n",
+        "# sort-research pattern v1\npattern: random\nlen: 1\nseed: 1\nrng: std\nx\n",
+        "# sort-research pattern v1\npattern: random\nlen: 1\nrng: std\n5\n",
+    ] {
+        std::fs::write(&path, invalid).unwrap();
+        let err = patterns::import(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{invalid:?}");
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn comparison_trace() {
+    use sort_test_tools::patterns;
+    use sort_test_tools::trace::ComparisonTrace;
+    use sort_test_tools::Sort;
+
+    for len in [0, 1, 2, 20, 500] {
+        let input = patterns::random_uniform(len, 0..50);
+        let mut v = input.clone();
+        let (trace, result) = ComparisonTrace::record::<i32, TestSort>(&mut v);
+        assert!(result.is_ok());
+
+        let mut expected = input.clone();
+        reference_sort::sort(&mut expected);
+        assert_eq!(v, expected);
+        assert_eq!(trace.calls.is_empty(), len < 2, "len: {len}");
+
+        // The sort only sees the results, so replaying them on the positions moves the positions
+        // the same way, which gives the argsort of the input.
+        let mut indices = (0..len).collect::<Vec<_>>();
+        let replay = trace.replay(&indices);
+        TestSort::sort_by(&mut indices, replay);
+        assert!(indices.iter().map(|&idx| input[idx]).eq(expected));
+
+        let path = std::env::temp_dir().join(format!("comparison_trace_{}", std::process::id()));
+        trace.save(&path).unwrap();
+        assert_eq!(ComparisonTrace::load(&path).unwrap(), trace);
+        std::fs::remove_file(&path).unwrap();
+
+        if let Some(last) = trace.calls.len().checked_sub(1) {
+            let mut truncated = trace.clone();
+            truncated.calls.truncate(last);
+
+            let result = std::panic::catch_unwind(|| {
+                let mut v = input.clone();
+                let replay = truncated.replay(&v);
+                TestSort::sort_by(&mut v, replay);
+            });
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "collation")]
+fn collation() {
+    use std::cmp::Ordering;
+
+    use sort_research_rs::other::collation;
+    use sort_test_tools::patterns;
+
+    // Base letters first, then accents, then case, then the ignored punctuation.
+    let ordered = [
+        "", "-", "0", "9", "a", "A", "á", "Á", "a-b", "ab", "àb", "b", "cote", "Cote", "coté",
+        "côte", "Côte", "cotes", "strase", "straße", "z", "Ω",
+    ];
+    for (i, a) in ordered.iter().enumerate() {
+        for (j, b) in ordered.iter().enumerate() {
+            assert_eq!(collation::compare(a, b), i.cmp(&j), "{a:?} {b:?}");
+            assert_eq!(
+                collation::sort_key(a).cmp(&collation::sort_key(b)),
+                i.cmp(&j),
+                "{a:?} {b:?}"
+            );
+        }
+    }
+
+    // The sort keys have the order of the comparison on strings of many levels of ties.
+    let alphabet = ['a', 'A', 'á', 'e', 'é', 'É', 'ß', 's', '-', ' ', '1', 'Ω'];
+    let strings = patterns::random_uniform(if cfg!(miri) { 100 } else { 2_000 }, 0..i32::MAX)
+        .into_iter()
+        .map(|val| {
+            let len = val as usize % 5;
+            (0..len)
+                .map(|i| alphabet[(val as usize >> (4 * i + 3)) % alphabet.len()])
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut by_compare = strings.clone();
+    reference_sort::sort_by(&mut by_compare, |a, b| collation::compare(a, b));
+    let mut by_key = strings.clone();
+    reference_sort::sort_by_key(&mut by_key, |s| collation::sort_key(s));
+    assert_eq!(by_compare, by_key);
+
+    for pair in by_compare.windows(2) {
+        let expected = if pair[0] == pair[1] {
+            Ordering::Equal
+        } else {
+            Ordering::Less
+        };
+        assert_eq!(collation::compare(&pair[0], &pair[1]), expected);
+        assert_eq!(collation::compare(&pair[1], &pair[0]), expected.reverse());
+    }
+}
+
+// The C++ string sorts compare the bytes, which is the order of `str`. Many shared prefixes, empty
+// strings and multi-byte characters, which the radix sort has to order by their first byte.
+#[test]
+#[cfg(feature = "cpp_str_sort")]
+fn cpp_str_sort() {
+    use sort_research_rs::other::cpp_str_sort;
+    use sort_test_tools::patterns;
+
+    let alphabet = ['a', 'b', 'B', '0', ' ', 'é', 'ß', 'Ω', '\0'];
+    for len in [0, 1, 2, 3, 20, 100, 1_000] {
+        let strings = patterns::random_uniform(len, 0..i32::MAX)
+            .into_iter()
+            .map(|val| {
+                let str_len = val as usize % 7;
+                (0..str_len)
+                    .map(|i| alphabet[(val as usize >> (3 * i + 3)) % alphabet.len()])
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        let input = strings.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let mut expected = input.clone();
+        reference_sort::sort(&mut expected);
+
+        for (name, sort_fn) in [
+            ("cpp_std_sort_str", cpp_str_sort::std_sort as fn(&mut [&str])),
+            ("cpp_msd_radix_sort_str", cpp_str_sort::msd_radix_sort),
+        ] {
+            let mut actual = input.clone();
+            sort_fn(&mut actual);
+            assert_eq!(actual, expected, "{name} len: {len}");
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "ffi_calibration")]
+fn ffi_calibration_panic_strategy() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use sort_research_rs::other::ffi_calibration::{self, PanicStrategy};
+    use sort_test_tools::patterns;
+
+    let mut v = patterns::random(100);
+    let comp_count = 1_000;
+    let expected = ffi_calibration::rust_null_sort_by(&mut v, comp_count, |a, b| a.cmp(b));
+
+    for panic_strategy in [PanicStrategy::CatchUnwind, PanicStrategy::AbortOnPanic] {
+        let less_count =
+            ffi_calibration::ffi_null_sort_by(&mut v, comp_count, panic_strategy, |a, b| a.cmp(b));
+        assert_eq!(less_count, expected);
+
+        let less_count = ffi_calibration::rust_callback_null_sort_by(
+            &mut v,
+            comp_count,
+            panic_strategy,
+            |a, b| a.cmp(b),
+        );
+        assert_eq!(less_count, expected);
+    }
+
+    // With catch_unwind the panic stops the calls and is resumed with its payload. Without it the
+    // process would abort.
+    for through_ffi in [true, false] {
+        let mut calls = 0;
+        let mut compare = |a: &i32, b: &i32| {
+            calls += 1;
+            if calls == 10 {
+                panic!("calibration panic");
+            }
+            a.cmp(b)
+        };
+
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            let strategy = PanicStrategy::CatchUnwind;
+            if through_ffi {
+                ffi_calibration::ffi_null_sort_by(&mut v, comp_count, strategy, &mut compare);
+            } else {
+                ffi_calibration::rust_callback_null_sort_by(
+                    &mut v,
+                    comp_count,
+                    strategy,
+                    &mut compare,
+                );
+            }
+        }))
+        .unwrap_err();
+
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"calibration panic"));
+        assert_eq!(calls, 10);
+    }
+}
+
+// A foreign sort that can't handle a type has to say so, not report a panic in the comparison
+// function.
+#[test]
+#[cfg(feature = "c_std_sys")]
+fn ffi_unsupported() {
+    use std::panic;
+
+    use sort_research_rs::unstable::c_std_sys;
+    use sort_test_tools::ffi_types::FFIString;
+
+    let mut v = ["b", "a"].map(|s| FFIString::new(s.into()));
+    let payload = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        c_std_sys::sort_by(&mut v, |a, b| a.cmp(b));
+    }))
+    .unwrap_err();
+
+    let msg = payload.downcast_ref::<String>().unwrap();
+    assert_eq!(msg, "qsort_unstable_ffi_string_by: not supported");
+}
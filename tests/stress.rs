@@ -0,0 +1,273 @@
+//! Runs every registered sort on randomized inputs for hours, see analysis::stress.
+//!
+//! cargo test --release --features stress --test stress
+//!
+//! Configured with environment variables:
+//!
+//! - `STRESS_DURATION` how long to run, eg. `30s`, `90m` or `8h`, defaults to `1h`.
+//! - `STRESS_MAX_LEN` the largest input, defaults to 10^8.
+//! - `STRESS_SORTS` comma separated sort names, defaults to all sorts.
+//! - `STRESS_CHECKPOINT` the checkpoint file, defaults to `stress_checkpoint` in the target tmp
+//!   dir. An existing checkpoint is continued, delete it to start over.
+//! - `STRESS_SEED` runs the cases from this seed on, without reading or writing the checkpoint.
+//!   `STRESS_CASES` limits the number of cases, eg. `STRESS_SEED=123 STRESS_CASES=1` reproduces
+//!   a failure.
+//! - `STRESS_SHARD` runs only the cases of one part of the seeds, eg. `3/16` on the third of 16
+//!   machines, see analysis::shard. Each machine keeps its own checkpoint.
+//! - `STRESS_CERTIFICATES` appends a certificate of every sort call without a fault to this file,
+//!   see analysis::certificate. `sort-research verify <file>` checks them on another machine.
+//!
+//! The inputs of failed cases are written as snapshot files next to the checkpoint, they can be
+//! replayed and added to corpus/ with `sort-research replay` and `sort-research corpus add`.
+
+use std::env;
+use std::fs;
+use std::io::BufWriter;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use sort_research_rs::analysis::certificate::{
+    self, Certificate, CertificateWriter, Generator, Source,
+};
+use sort_research_rs::analysis::shard::Shard;
+use sort_research_rs::analysis::snapshot::Snapshot;
+use sort_research_rs::analysis::stress::{self, Case, Checkpoint};
+use sort_research_rs::registry::{self, SortEntry};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(60);
+
+// Set while a sort runs with a faulty comparator, the panics are expected and not printed.
+static EXPECT_PANIC: AtomicBool = AtomicBool::new(false);
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(val) => val
+            .replace('_', "")
+            .parse()
+            .unwrap_or_else(|_| exit_with_error(&format!("Invalid {key}: {val}"))),
+        Err(_) => default,
+    }
+}
+
+fn parse_duration(val: &str) -> Option<Duration> {
+    let split_pos = val.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = val.split_at(split_pos);
+    let num = num.parse::<u64>().ok()?;
+
+    let secs = match unit {
+        "s" => num,
+        "m" => num * 60,
+        "h" => num * 60 * 60,
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(secs))
+}
+
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{msg}");
+    process::exit(1);
+}
+
+fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) {
+    if let Err(err) = checkpoint.save(path) {
+        exit_with_error(&format!("Failed to write {}: {err}", path.display()));
+    }
+}
+
+fn save_failure(dir: &Path, case: &Case, entry: &SortEntry<i32>) {
+    let snapshot = Snapshot {
+        sequence: 0,
+        sort_name: entry.name().to_string(),
+        type_name: "i32".to_string(),
+        descending: false,
+        label: format!(
+            "STRESS_SEED={} {} fault: {:?}",
+            case.seed,
+            case.label,
+            case.fault_for(entry)
+        ),
+        input: case.input.clone(),
+    };
+
+    let path = dir.join(format!("stress_{}_{}.bin", case.seed, entry.name()));
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::File::create(&path))
+        .and_then(|file| snapshot.write_to(&mut BufWriter::new(file)));
+
+    match result {
+        Ok(()) => println!("input: {}", path.display()),
+        Err(err) => eprintln!("Failed to write {}: {err}", path.display()),
+    }
+}
+
+/// Sorts the input again with a counting comparison function, the check already passed.
+fn write_certificate(
+    certificates: &mut CertificateWriter,
+    case: &Case,
+    max_len: usize,
+    entry: &SortEntry<i32>,
+) {
+    let source = Source {
+        generator: Generator::Stress,
+        seed: case.seed,
+        max_len,
+    };
+    let run = certificate::run(entry, &case.input, false);
+    let certificate = Certificate::new(source, entry.name(), "i32", false, &case.input, &run);
+
+    if let Err(err) = certificates.write(&certificate) {
+        exit_with_error(&format!("Failed to write certificate: {err}"));
+    }
+}
+
+fn print_progress(checkpoint: &Checkpoint) {
+    println!(
+        "[{}s] cases: {} sort calls: {} elements: {} failures: {}",
+        checkpoint.elapsed_secs,
+        checkpoint.cases,
+        checkpoint.sort_calls,
+        checkpoint.elements,
+        checkpoint.failures
+    );
+}
+
+fn main() {
+    let duration = env::var("STRESS_DURATION").map_or(Duration::from_secs(60 * 60), |val| {
+        parse_duration(&val)
+            .unwrap_or_else(|| exit_with_error(&format!("Invalid STRESS_DURATION: {val}")))
+    });
+    let max_len = env_or("STRESS_MAX_LEN", stress::DEFAULT_MAX_LEN);
+    let max_cases = env_or("STRESS_CASES", u64::MAX);
+    let shard = env_or("STRESS_SHARD", Shard::ALL);
+
+    let checkpoint_path = env::var("STRESS_CHECKPOINT").map_or_else(
+        |_| Path::new(env!("CARGO_TARGET_TMPDIR")).join("stress_checkpoint"),
+        PathBuf::from,
+    );
+    let failure_dir = checkpoint_path.with_file_name("stress_failures");
+    let mut certificates = env::var("STRESS_CERTIFICATES").ok().map(|path| {
+        CertificateWriter::append(Path::new(&path))
+            .unwrap_or_else(|err| exit_with_error(&format!("Failed to open {path}: {err}")))
+    });
+
+    let mut sorts = registry::general_sorts::<i32>();
+    if let Ok(names) = env::var("STRESS_SORTS") {
+        let names = names.split(',').collect::<Vec<_>>();
+        if let Some(unknown) = names
+            .iter()
+            .find(|name| !sorts.iter().any(|entry| entry.name() == **name))
+        {
+            exit_with_error(&format!("Unknown sort: {unknown}"));
+        }
+        sorts.retain(|entry| names.contains(&entry.name()));
+    }
+
+    let resume = env::var("STRESS_SEED").is_err();
+    let mut checkpoint = if let Ok(seed) = env::var("STRESS_SEED") {
+        let next_seed = seed
+            .parse()
+            .unwrap_or_else(|_| exit_with_error(&format!("Invalid STRESS_SEED: {seed}")));
+        Checkpoint {
+            next_seed,
+            ..Checkpoint::default()
+        }
+    } else {
+        match Checkpoint::load(&checkpoint_path) {
+            Ok(checkpoint) => checkpoint.unwrap_or_default(),
+            Err(err) => exit_with_error(&format!("Failed to read checkpoint: {err}")),
+        }
+    };
+
+    // The previous run ended during this sort call, most likely it crashed the process.
+    let mut run_failures = 0;
+    if let Some(running) = checkpoint.running.take() {
+        println!("FAILED: the previous run stopped during {running}, continuing after it");
+        checkpoint.failures += 1;
+        checkpoint.next_seed += 1;
+        run_failures += 1;
+    }
+
+    println!(
+        "Checkpoint: {}\nSorts: {}\nShard: {shard}\nStarting at seed {}, running for {}s",
+        checkpoint_path.display(),
+        sorts.len(),
+        checkpoint.next_seed,
+        duration.as_secs()
+    );
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !EXPECT_PANIC.load(Ordering::Relaxed) {
+            default_hook(info);
+        }
+    }));
+
+    let start = Instant::now();
+    let start_elapsed_secs = checkpoint.elapsed_secs;
+    let mut last_progress = Instant::now();
+
+    for _ in 0..max_cases {
+        if start.elapsed() >= duration {
+            break;
+        }
+
+        checkpoint.next_seed = shard.next_seed(checkpoint.next_seed);
+        let case = Case::new(checkpoint.next_seed, max_len);
+
+        for entry in &sorts {
+            checkpoint.running = Some(format!("STRESS_SEED={} {}", case.seed, entry.name()));
+            checkpoint.elapsed_secs = start_elapsed_secs + start.elapsed().as_secs();
+            if resume {
+                save_checkpoint(&checkpoint_path, &checkpoint);
+            }
+
+            EXPECT_PANIC.store(case.fault_for(entry).is_some(), Ordering::Relaxed);
+            let error = case.check(entry);
+            EXPECT_PANIC.store(false, Ordering::Relaxed);
+
+            if let Some(error) = error {
+                println!(
+                    "FAILED: STRESS_SEED={} {}, len {} {}: {error}",
+                    case.seed,
+                    entry.name(),
+                    case.input.len(),
+                    case.label
+                );
+                save_failure(&failure_dir, &case, entry);
+                checkpoint.failures += 1;
+                run_failures += 1;
+            } else if let Some(certificates) = certificates.as_mut() {
+                if case.fault_for(entry).is_none() {
+                    write_certificate(certificates, &case, max_len, entry);
+                }
+            }
+
+            checkpoint.sort_calls += 1;
+            checkpoint.elements += case.input.len() as u64;
+        }
+
+        checkpoint.running = None;
+        checkpoint.cases += 1;
+        checkpoint.next_seed += 1;
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            checkpoint.elapsed_secs = start_elapsed_secs + start.elapsed().as_secs();
+            print_progress(&checkpoint);
+        }
+    }
+
+    checkpoint.elapsed_secs = start_elapsed_secs + start.elapsed().as_secs();
+    if resume {
+        save_checkpoint(&checkpoint_path, &checkpoint);
+    }
+    print_progress(&checkpoint);
+
+    if run_failures > 0 {
+        exit_with_error(&format!("{run_failures} sort calls failed"));
+    }
+}
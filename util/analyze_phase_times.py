@@ -0,0 +1,60 @@
+"""
+Summarize the output of `MEASURE_PHASES=1 cargo bench --features=instrument_phases`.
+
+Prints the share of each phase per pattern, so that differences between patterns can be
+attributed to a phase, eg. merge vs run detection.
+
+Usage: python analyze_phase_times.py <bench_output.txt> [sort_name]
+"""
+
+import collections
+import re
+import sys
+
+STAT_REGEX = re.compile(r"([a-z ]+): ([0-9.]+)(%|ns)")
+
+
+def parse_phase_times(phase_data):
+    # Result layout:
+    # { sort_name (eg. rust_std_stable):
+    #   { (type, pattern, test_len):
+    #     { stat_name (eg. merge): value
+    result = collections.defaultdict(dict)
+
+    for line in phase_data.splitlines():
+        if "-phases-" not in line or "no phase data" in line:
+            continue
+
+        sort_name, _, rest = line.partition("-phases-")
+        entry, _, stats_str = rest.partition(": ")
+
+        ty, pattern, test_len = entry.rsplit("-", 2)
+
+        stats = {}
+        for stat_name, val, _ in STAT_REGEX.findall(stats_str):
+            stats[stat_name.strip()] = float(val)
+
+        result[sort_name][(ty, pattern, int(test_len))] = stats
+
+    return result
+
+
+def print_table(sort_name, entries):
+    print(f"{sort_name}:")
+    for (ty, pattern, test_len), stats in sorted(entries.items()):
+        key = f"{ty}-{pattern}-{test_len}"
+        key_pad = " " * max(1, 40 - len(key))
+        total = f"total: {stats.pop('mean total'):>12.0f}ns"
+        cols = "  ".join(f"{name}: {val:>5.1f}%" for name, val in stats.items())
+        print(f"  [{key}]{key_pad}{total}  {cols}")
+
+
+if __name__ == "__main__":
+    with open(sys.argv[1], "r") as phase_data_file:
+        phase_times = parse_phase_times(phase_data_file.read())
+
+    filter_name = sys.argv[2] if len(sys.argv) > 2 else None
+
+    for sort_name, entries in sorted(phase_times.items()):
+        if filter_name is None or sort_name == filter_name:
+            print_table(sort_name, entries)
@@ -0,0 +1,54 @@
+"""
+Summarize the output of `MEASURE_RUNS=1 cargo bench --features=instrument_runs`.
+
+Usage: python analyze_run_stats.py <bench_output.txt> [sort_name]
+"""
+
+import collections
+import sys
+
+
+def parse_run_stats(run_data):
+    # Result layout:
+    # { sort_name (eg. rust_std_stable):
+    #   { (type, pattern, test_len):
+    #     { stat_name (eg. mean runs): value
+    result = collections.defaultdict(dict)
+
+    for line in run_data.splitlines():
+        if "-runs-" not in line or "no run data" in line:
+            continue
+
+        sort_name, _, rest = line.partition("-runs-")
+        entry, _, stats_str = rest.partition(": ")
+
+        ty, pattern, test_len = entry.rsplit("-", 2)
+
+        stats = {}
+        for part in stats_str.split("mean ")[1:]:
+            stat_name, _, val = part.rpartition(":")
+            stats[stat_name.strip()] = float(val.strip())
+
+        result[sort_name][(ty, pattern, int(test_len))] = stats
+
+    return result
+
+
+def print_table(sort_name, entries):
+    print(f"{sort_name}:")
+    for (ty, pattern, test_len), stats in sorted(entries.items()):
+        key = f"{ty}-{pattern}-{test_len}"
+        key_pad = " " * max(1, 40 - len(key))
+        cols = "  ".join(f"{name}: {val:>10.1f}" for name, val in stats.items())
+        print(f"  [{key}]{key_pad}{cols}")
+
+
+if __name__ == "__main__":
+    with open(sys.argv[1], "r") as run_data_file:
+        run_stats = parse_run_stats(run_data_file.read())
+
+    filter_name = sys.argv[2] if len(sys.argv) > 2 else None
+
+    for sort_name, entries in sorted(run_stats.items()):
+        if filter_name is None or sort_name == filter_name:
+            print_table(sort_name, entries)
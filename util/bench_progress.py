@@ -0,0 +1,329 @@
+"""Live progress of a benchmark run, and its results streamed to disk as the benchmarks complete,
+see run_benchmarks.py.
+
+The results of criterion only reach the results file through `critcmp --export` once the whole run
+is done. A run that takes days and is interrupted, by a crash, a reboot or Ctrl-C, would lose
+everything. Every completed benchmark is appended to `<test_name>.partial.jsonl` right away, in the
+format of the results file, and `run_benchmarks.py --recover <test_name>` turns it into
+`<test_name>.json`. The file is removed once the run completed and the results file is written.
+"""
+
+import json
+import os
+import re
+import subprocess
+import sys
+import threading
+import time
+
+
+class ResultsSink:
+    """Appends the results of completed benchmarks to a JSON lines file, one object per line. The
+    first line holds the metadata of the run, every following line a benchmark, as the entry of
+    `critcmp --export` under "benchmark", or a failure under "failure". Can be used from multiple
+    threads."""
+
+    def __init__(self, path, baseline, crit_dir, header):
+        self.path = path
+        self.baseline = baseline
+        self.crit_dir = crit_dir
+        self.lock = threading.Lock()
+        # Criterion shortens and deduplicates the directory names, map them by the full id.
+        self.crit_dirs = {}
+        self.file = open(path, "w", encoding="utf-8")
+        self._append({"header": header})
+
+    def add_benchmark(self, name):
+        """Reads the results of `name` from the criterion baseline and appends them, returns the
+        entry or None if criterion saved no results."""
+        with self.lock:
+            entry = self._read_criterion_entry(name)
+            if entry is not None:
+                self._append({"name": name, "benchmark": entry})
+
+            return entry
+
+    def add_failure(self, name, status):
+        with self.lock:
+            self._append({"name": name, "failure": {"status": status}})
+
+    def close(self, remove):
+        with self.lock:
+            self.file.close()
+            if remove:
+                os.remove(self.path)
+
+    def _append(self, obj):
+        # One write and fsync per line, a crash leaves at most the last line incomplete.
+        self.file.write(json.dumps(obj) + "\n")
+        self.file.flush()
+        os.fsync(self.file.fileno())
+
+    def _read_criterion_entry(self, name):
+        if name not in self.crit_dirs:
+            self._scan_crit_dirs()
+
+        dir_name = self.crit_dirs.get(name)
+        if dir_name is None:
+            return None
+
+        base_dir = os.path.join(self.crit_dir, dir_name, self.baseline)
+        with open(os.path.join(base_dir, "benchmark.json"), "r", encoding="utf-8") as file:
+            benchmark = json.load(file)
+        with open(os.path.join(base_dir, "estimates.json"), "r", encoding="utf-8") as file:
+            estimates = json.load(file)
+
+        return {
+            "baseline": self.baseline,
+            "fullname": f"{self.baseline}/{name}",
+            "criterion_benchmark_v1": benchmark,
+            "criterion_estimates_v1": estimates,
+        }
+
+    def _scan_crit_dirs(self):
+        if not os.path.isdir(self.crit_dir):
+            return
+
+        known_dirs = set(self.crit_dirs.values())
+        for entry in os.scandir(self.crit_dir):
+            if entry.name in known_dirs:
+                continue
+
+            benchmark_path = os.path.join(entry.path, self.baseline, "benchmark.json")
+            if not os.path.exists(benchmark_path):
+                continue
+
+            with open(benchmark_path, "r", encoding="utf-8") as file:
+                self.crit_dirs[json.load(file)["full_id"]] = entry.name
+
+
+def load_partial(path):
+    """Returns the results written by a ResultsSink in the format of the results file, marked with
+    "partial": true. An incomplete last line is skipped."""
+    results = {"benchmarks": {}, "partial": True}
+    failures = {}
+
+    with open(path, "r", encoding="utf-8") as file:
+        for line in file:
+            try:
+                obj = json.loads(line)
+            except json.JSONDecodeError:
+                continue
+
+            if "header" in obj:
+                results |= obj["header"]
+            elif "benchmark" in obj:
+                results["benchmarks"][obj["name"]] = obj["benchmark"]
+            elif "failure" in obj:
+                failures[obj["name"]] = obj["failure"]
+
+    if failures:
+        results["failures"] = failures
+
+    return results
+
+
+def format_duration(seconds):
+    seconds = int(seconds)
+    if seconds >= 3600:
+        return f"{seconds // 3600}h{seconds % 3600 // 60:02}m"
+    if seconds >= 60:
+        return f"{seconds // 60}m{seconds % 60:02}s"
+
+    return f"{seconds}s"
+
+
+class ProgressDisplay:
+    """Shows the completed benchmarks, the ETA and the most recent benchmarks with severe outliers.
+
+    On a terminal this is a footer below the benchmark output, redrawn every second, and the
+    `Benchmarking <name>: ...` status lines of criterion are replaced by the name of the running
+    benchmark. Otherwise a progress line is printed after every completed benchmark."""
+
+    RECENT_OUTLIERS = 3
+
+    def __init__(self, total, out=sys.stdout):
+        self.total = total
+        self.out = out
+        self.is_tty = out.isatty()
+        self.lock = threading.Lock()
+        self.start_time = time.monotonic()
+        self.completed = 0
+        self.failed = 0
+        self.running = None
+        self.recent_outliers = []
+        self.footer_lines = 0
+        self.stop_event = threading.Event()
+
+        if self.is_tty:
+            self.ticker = threading.Thread(target=self._tick, daemon=True)
+            self.ticker.start()
+
+    def output(self, line):
+        """Passes a line of the benchmark output through."""
+        with self.lock:
+            if self.is_tty and line.startswith("Benchmarking "):
+                return
+
+            self._clear_footer()
+            self.out.write(line)
+            self._draw_footer()
+
+    def start(self, name):
+        with self.lock:
+            self.running = name
+            self._clear_footer()
+            self._draw_footer()
+
+    def complete(self, name, failed=False, severe_outliers=None):
+        """Records a completed benchmark. `severe_outliers` is the share of measurements criterion
+        classified as severe outliers, in percent."""
+        with self.lock:
+            self.completed += 1
+            self.failed += failed
+            self.running = None
+            if severe_outliers:
+                self.recent_outliers.append((name, severe_outliers))
+                self.recent_outliers = self.recent_outliers[-self.RECENT_OUTLIERS :]
+
+            self._clear_footer()
+            if not self.is_tty:
+                self.out.write(self._status() + "\n")
+            self._draw_footer()
+
+    def close(self):
+        self.stop_event.set()
+        with self.lock:
+            # Leaves the final status on a terminal, otherwise it was printed by complete.
+            if self.is_tty:
+                self._clear_footer()
+                self.out.write(self._status() + "\n")
+            self.out.flush()
+
+    def _tick(self):
+        while not self.stop_event.wait(1.0):
+            with self.lock:
+                self._clear_footer()
+                self._draw_footer()
+
+    def _status(self):
+        elapsed = time.monotonic() - self.start_time
+        status = f"[{self.completed}/{self.total or '?'}]"
+        if self.total:
+            status += f" {100 * self.completed / self.total:.1f}%"
+        status += f" elapsed {format_duration(elapsed)}"
+
+        if self.total and self.completed:
+            remaining = max(self.total - self.completed, 0)
+            status += f", ETA {format_duration(elapsed / self.completed * remaining)}"
+        if self.failed:
+            status += f", {self.failed} failed"
+
+        return status
+
+    def _footer(self):
+        lines = [self._status()]
+        if self.running is not None:
+            lines.append(f"  running {self.running}")
+        for name, percent in self.recent_outliers:
+            lines.append(f"  outliers {name}: {percent:.1f}% severe")
+
+        return lines
+
+    def _clear_footer(self):
+        # Moves to the start of the first footer line and clears to the end of the screen.
+        if self.footer_lines:
+            self.out.write(f"\x1b[{self.footer_lines}F\x1b[J")
+            self.footer_lines = 0
+
+    def _draw_footer(self):
+        if self.is_tty:
+            lines = self._footer()
+            self.out.write("\n".join(lines) + "\n")
+            self.footer_lines = len(lines)
+        self.out.flush()
+
+
+def count_benchmarks(bench_cmd, env):
+    """Number of benchmarks `bench_cmd` runs, from criterion's `--list`, or None if listing
+    fails."""
+    list_cmd = bench_cmd[: bench_cmd.index("--") + 1] + ["--list"]
+    list_env = dict(env)
+    list_env.pop("BENCH_ISOLATE", None)
+
+    result = subprocess.run(list_cmd, capture_output=True, env=list_env, text=True)
+    if result.returncode != 0:
+        return None
+
+    return sum(1 for line in result.stdout.splitlines() if line.endswith(": bench"))
+
+
+def run_with_progress(bench_cmd, env, sink):
+    """Runs `bench_cmd`, passes its output through a ProgressDisplay and appends every completed
+    benchmark to `sink`. Returns the benchmarks that crashed, keyed by name, see run_isolated in
+    benches/modules/util.rs."""
+    display = ProgressDisplay(count_benchmarks(bench_cmd, env))
+
+    failures = {}
+    # Criterion copies the results to the baseline only after printing them, a benchmark is read
+    # from disk once the next one starts or the process exited.
+    pending = None
+    severe_outliers = 0.0
+    prev_line = ""
+
+    def finish_pending():
+        nonlocal pending
+        if pending is not None:
+            sink.add_benchmark(pending)
+            display.complete(pending, severe_outliers=severe_outliers)
+            pending = None
+
+    with subprocess.Popen(
+        bench_cmd, stdout=subprocess.PIPE, env=env, text=True, bufsize=1
+    ) as proc:
+        for line in proc.stdout:
+            display.output(line)
+            stripped = line.rstrip("\n")
+
+            # Benchmarking <name>: Warming up for 2.0000 s
+            match = re.match(r"^Benchmarking (\S+): ", stripped)
+            if match is not None:
+                if match.group(1) != pending:
+                    finish_pending()
+                if match.group(1) != display.running:
+                    display.start(match.group(1))
+                continue
+
+            # <name> time:   [1.2 µs 1.3 µs 1.4 µs], the name is on the line before if it is long.
+            # The time line of a comparison to a previous baseline has percentages.
+            match = re.match(r"^(\S*)\s+time:\s+\[([^%\]]*)\]$", stripped)
+            if match is not None:
+                finish_pending()
+                pending = match.group(1) or prev_line.strip()
+                severe_outliers = 0.0
+                continue
+
+            #   3 (3.00%) high severe
+            match = re.match(r"^\s+\d+ \((\S+)%\) (?:low|high) severe$", stripped)
+            if match is not None and pending is not None:
+                severe_outliers += float(match.group(1))
+
+            # <name>: failed: signal: 11 (SIGSEGV)
+            match = re.match(r"^(\S+): failed: (.*)$", stripped)
+            if match is not None:
+                name, status = match.groups()
+                failures[name] = {"status": status}
+                sink.add_failure(name, status)
+                display.complete(name, failed=True)
+
+            if stripped:
+                prev_line = stripped
+
+        finish_pending()
+
+    display.close()
+
+    if proc.returncode != 0:
+        raise subprocess.CalledProcessError(proc.returncode, bench_cmd)
+
+    return failures
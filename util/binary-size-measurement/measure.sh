@@ -10,15 +10,21 @@ function measure_binary_size_type_impl() {
     mkdir -p out
     BIN_PATH="target/$1/binary-size-measurement"
 
+    # $4 is the kernel inlining strategy, see the inline_kernels feature of ipnsort.
+    FEATURES="$2"
+    if [ "$4" != "default" ]; then
+        FEATURES="$FEATURES,$4"
+    fi
+
     tmpfile=$(mktemp)
 
-    cargo bloat --profile=$1 --features $2 --no-relative-size -n 0 --message-format json > out/baseline_$2_$1.json 2> "$tmpfile"
+    cargo bloat --profile=$1 --features $FEATURES --no-relative-size -n 0 --message-format json > out/baseline_$2_$4_$1.json 2> "$tmpfile"
 
     if [ $? -ne 0 ]; then
         cat "$tmpfile"
     fi
 
-    cargo bloat --profile=$1 --features $2,sort_inst --no-relative-size -n 0 --message-format json > out/with_sort_$2_$1.json 2> "$tmpfile"
+    cargo bloat --profile=$1 --features $FEATURES,sort_inst --no-relative-size -n 0 --message-format json > out/with_sort_$2_$4_$1.json 2> "$tmpfile"
 
     if [ $? -ne 0 ]; then
         cat "$tmpfile"
@@ -26,22 +32,32 @@ function measure_binary_size_type_impl() {
 
     rm "$tmpfile"
 
-    BINARY_SIZE=$(python eval_bloat.py out/baseline_$2_$1.json out/with_sort_$2_$1.json)
-    RESULT_TABLE="$RESULT_TABLE$1 $3 $BINARY_SIZE\n"
+    BINARY_SIZE=$(python eval_bloat.py out/baseline_$2_$4_$1.json out/with_sort_$2_$4_$1.json)
+    RESULT_TABLE="$RESULT_TABLE$1 $4 $3 $BINARY_SIZE\n"
 }
 
 function measure_binary_size() {
     RESULT_TABLE="$RESULT_TABLE----------------------------\n"
-    measure_binary_size_type_impl "$1" "type_u64" "u64"
-    measure_binary_size_type_impl "$1" "type_string" "string"
+    for INLINING in $INLINING_STRATEGIES; do
+        measure_binary_size_type_impl "$1" "type_u64" "u64" "$INLINING"
+        measure_binary_size_type_impl "$1" "type_string" "string" "$INLINING"
+    done
 }
 
-measure_binary_size "release"
-
 set +u
 PARAM_1="$1"
+PARAM_2="$2"
 set -u
 
+# Pass inlining as second parameter to also measure the inline_kernels and outline_kernels
+# features, eg. ./measure.sh all inlining.
+INLINING_STRATEGIES="default"
+if [ "$PARAM_2" = "inlining" ]; then
+    INLINING_STRATEGIES="default inline_kernels outline_kernels"
+fi
+
+measure_binary_size "release"
+
 if [ "$PARAM_1" = "all" ]; then
     measure_binary_size "release_lto_thin"
     measure_binary_size "release_lto_thin_opt_level_s"
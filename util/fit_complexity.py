@@ -0,0 +1,130 @@
+"""
+Fits benchmark times or comparison counts to the model c1 * n + c2 * n * log2(n), per sort, type
+and pattern, and reports the constants with the goodness-of-fit.
+
+Raw times only compare within one machine, the constants are more transferable. c2 is the cost per
+element and level of recursion, c1 captures the linear passes, eg. run detection or the small-sort.
+
+Usage:
+    # Times, from a result file written by run_benchmarks.py
+    python util/fit_complexity.py my_test_zen3.json
+
+    # Comparisons, from the output of MEASURE_COMP=1 cargo bench
+    python util/fit_complexity.py comp_counts.txt
+"""
+
+import json
+import math
+import sys
+
+
+class Fit:
+    def __init__(self, c1, c2, r_squared, max_rel_err):
+        self.c1 = c1
+        self.c2 = c2
+        self.r_squared = r_squared
+        # Largest relative deviation of a single measurement from the model.
+        self.max_rel_err = max_rel_err
+
+
+def fit_n_log_n(points):
+    """
+    Least squares fit of y = c1 * n + c2 * n * log2(n) for points [(n, y)].
+
+    Each point is weighted by 1/y^2, ie. the relative error is minimized. Otherwise the largest
+    sizes alone would determine the fit.
+    """
+    # Normal equations for the two basis functions a = n and b = n * log2(n).
+    saa = sab = sbb = say = sby = 0.0
+    for n, y in points:
+        w = 1.0 / (y * y)
+        a = n
+        b = n * math.log2(n)
+        saa += w * a * a
+        sab += w * a * b
+        sbb += w * b * b
+        say += w * a * y
+        sby += w * b * y
+
+    det = saa * sbb - sab * sab
+    if abs(det) < 1e-12 * saa * sbb:
+        return None
+
+    c1 = (say * sbb - sby * sab) / det
+    c2 = (saa * sby - sab * say) / det
+
+    def predict(n):
+        return c1 * n + c2 * n * math.log2(n)
+
+    ss_res = sum((y - predict(n)) ** 2 for n, y in points)
+    mean_y = sum(y for _, y in points) / len(points)
+    ss_tot = sum((y - mean_y) ** 2 for _, y in points)
+    r_squared = 1.0 - (ss_res / ss_tot) if ss_tot > 0.0 else 1.0
+    max_rel_err = max(abs(y - predict(n)) / y for n, y in points)
+
+    return Fit(c1, c2, r_squared, max_rel_err)
+
+
+def parse_times(path):
+    with open(path, "r") as file:
+        bench_result = json.load(file)
+
+    groups = {}
+    for benchmark, value in bench_result["benchmarks"].items():
+        # <sort>-<hot|cold>-<type>-<pattern>-<len>
+        entry_parts = benchmark.split("-")
+        test_len = int(entry_parts[-1])
+        key = (entry_parts[0], entry_parts[2], "-".join(entry_parts[3:-1]))
+        bench_time = value["criterion_estimates_v1"]["median"]["point_estimate"]
+
+        groups.setdefault(key, []).append((test_len, bench_time))
+
+    return groups
+
+
+def parse_comp_counts(path):
+    groups = {}
+    with open(path, "r") as file:
+        for line in file:
+            # <sort>-comp-<type>-<pattern>-<len>: mean comparisons: <count>
+            name, sep, rest = line.partition(": mean comparisons: ")
+            if not sep:
+                continue
+
+            entry_parts = name.strip().split("-")
+            test_len = int(entry_parts[-1])
+            key = (entry_parts[0], entry_parts[2], "-".join(entry_parts[3:-1]))
+
+            groups.setdefault(key, []).append((test_len, float(rest)))
+
+    return groups
+
+
+def analyze(groups, unit):
+    print(
+        f"{'[sort-type-pattern]'.ljust(60)} {('c1 ' + unit).rjust(12)} {('c2 ' + unit).rjust(12)} {'R^2'.rjust(8)} {'max err'.rjust(8)}"
+    )
+
+    for (sort_name, ty, pattern), points in sorted(groups.items()):
+        # Len 0 and 1 don't sort anything.
+        points = [(n, y) for n, y in points if n >= 2 and y > 0]
+        if len(points) < 3:
+            continue
+
+        name = f"[{sort_name}-{ty}-{pattern}]".ljust(60)
+        fit = fit_n_log_n(points)
+        if fit is None:
+            print(f"{name} degenerate sizes")
+            continue
+
+        print(
+            f"{name} {fit.c1:12.4f} {fit.c2:12.4f} {fit.r_squared:8.4f} {fit.max_rel_err * 100:7.1f}%"
+        )
+
+
+if __name__ == "__main__":
+    path = sys.argv[1]
+    if path.endswith(".json"):
+        analyze(parse_times(path), "ns")
+    else:
+        analyze(parse_comp_counts(path), "cmp")
@@ -16,6 +16,14 @@ def detail_get_cpu_info(name):
         cpu_boost_ghz = 2.0
         cpu_arch = "Icestorm"
         os_name = "MacOS"
+    elif "avalanche" in name_lower:
+        cpu_boost_ghz = 3.5
+        cpu_arch = "Avalanche"
+        os_name = "MacOS"
+    elif "blizzard" in name_lower:
+        cpu_boost_ghz = 2.4
+        cpu_arch = "Blizzard"
+        os_name = "MacOS"
     elif "a53" in name_lower:
         cpu_boost_ghz = 1.9
         cpu_arch = "Cortex-A53"
@@ -186,16 +186,22 @@ def extract_groups(bench_result_iter, splice_filter_fn):
 
 
 def type_size(type_name):
-    if type_name == "i32":
+    if type_name in ("i32", "cell_u32"):
         return 4
     elif type_name == "u64":
         return 8
     elif type_name == "string":
         return 24
-    elif type_name == "f128":
+    elif type_name in ("f128", "u128", "i128"):
         return 16
     elif type_name == "1k":
         return 1_000
+    elif type_name in ("rc_dyn", "box_dyn"):
+        return 16
+    elif type_name == "bytes":
+        return 24
+    elif type_name == "bytes64":
+        return 64
 
     raise Exception(f"Unknown type: {type_name}")
 
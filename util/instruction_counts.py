@@ -0,0 +1,199 @@
+"""
+Measures the instructions each sort retires per input, and compares them to a baseline to catch
+regressions in CI. Unlike runtimes the counts are deterministic, the same build sorting the same
+inputs gives the same counts on every run and every machine with the same instruction set.
+
+Two backends:
+
+- perf, the default. Runs the benchmarks with MEASURE_INSTRUCTIONS=1, which reads the hardware
+  instruction counter around every sort call, see src/analysis/instructions.rs. Fast, but needs
+  Linux and a PMU, which most virtual machines don't provide.
+- callgrind. Runs src/bin/sort_once.rs under valgrind once with and once without the sort and
+  subtracts the counts, like util/run_cachegrind.py. Works everywhere valgrind does, about 50x
+  slower.
+
+Usage:
+    # Writes the counts to main.json
+    python util/instruction_counts.py main --sorts "rust_std_unstable,rust_ipnsort_unstable"
+
+    # Writes the counts to pr.json and exits with 1 if any count is more than 1% above main.json
+    python util/instruction_counts.py pr --sorts "..." --baseline main.json --max-regression 1
+
+Run this program with the repo root dir as the current working directory. The inputs use a fixed
+OVERRIDE_SEED, the baseline and the compared run have to use the same backend and options.
+"""
+
+import argparse
+import json
+import os
+import re
+import subprocess
+import sys
+import tempfile
+
+from run_cachegrind import SORT_ONCE_PATH, build_sort_once, list_sorts
+
+# Any fixed value works, it only has to be the same for the baseline and the compared run.
+INPUT_SEED = "3245894712"
+
+
+def measure_perf(args):
+    """Returns {bench_name: mean instructions} for all combinations selected by args."""
+
+    def alternatives(val):
+        return f"({val.replace(',', '|')})" if val else "[^-]+"
+
+    bench_regex = (
+        f"^{alternatives(args.sorts)}-instructions-{alternatives(args.types)}-"
+        f"{alternatives(args.patterns)}-{alternatives(args.lens)}$"
+    )
+
+    cmd = ["cargo", "bench", "--bench", "bench"]
+    if args.features:
+        cmd += ["--features", args.features]
+
+    env = dict(
+        os.environ,
+        MEASURE_INSTRUCTIONS="1",
+        BENCH_REGEX=bench_regex,
+        OVERRIDE_SEED=INPUT_SEED,
+    )
+    result = subprocess.run(cmd, check=True, capture_output=True, env=env)
+
+    counts = {}
+    line_regex = re.compile(r"^(\S+-instructions-\S+): mean instructions: (\d+)$")
+    for line in result.stdout.decode("utf-8").splitlines():
+        match = line_regex.match(line)
+        if match:
+            counts[match.group(1)] = int(match.group(2))
+            print(line)
+
+    return counts
+
+
+def run_callgrind(args):
+    """Returns the total instruction count of running sort_once with args under callgrind."""
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        out_path = os.path.join(tmp_dir, "callgrind.out")
+        subprocess.run(
+            [
+                "valgrind",
+                "--tool=callgrind",
+                f"--callgrind-out-file={out_path}",
+                SORT_ONCE_PATH,
+            ]
+            + args,
+            check=True,
+            capture_output=True,
+            env=dict(os.environ, OVERRIDE_SEED=INPUT_SEED),
+        )
+
+        with open(out_path, "r") as out_file:
+            for line in out_file:
+                # Older valgrind versions write summary, newer ones totals.
+                if line.startswith(("summary:", "totals:")):
+                    return int(line.split()[1])
+
+    raise Exception(f"No instruction count in the callgrind output for {args}")
+
+
+def measure_callgrind(args):
+    """Returns {bench_name: instructions} for all combinations selected by args."""
+    build_sort_once(args.features)
+
+    counts = {}
+    for ty in args.types.split(","):
+        sort_names = args.sorts.split(",") if args.sorts else list_sorts(ty)
+
+        for sort_name in sort_names:
+            for pattern in args.patterns.split(","):
+                for test_len in args.lens.split(","):
+                    sort_args = [sort_name, ty, pattern, test_len]
+                    count = run_callgrind(sort_args) - run_callgrind(
+                        sort_args + ["--no-sort"]
+                    )
+
+                    name = f"{sort_name}-instructions-{ty}-{pattern}-{test_len}"
+                    counts[name] = count
+                    print(f"{name}: instructions: {count}")
+
+    return counts
+
+
+def compare(baseline, result, max_regression):
+    """Prints the changes against baseline, returns True if none is above max_regression %."""
+    if baseline["backend"] != result["backend"]:
+        print(
+            f"The baseline was measured with {baseline['backend']}, not {result['backend']}"
+        )
+        return False
+
+    passed = True
+    for name, count in sorted(result["instructions"].items()):
+        baseline_count = baseline["instructions"].get(name)
+        if baseline_count is None:
+            print(f"{name}: not in the baseline")
+            continue
+
+        change = (count / max(baseline_count, 1) - 1) * 100
+        if change > max_regression:
+            print(f"REGRESSION {name}: {baseline_count} -> {count} ({change:+.2f}%)")
+            passed = False
+        elif change != 0:
+            print(f"{name}: {baseline_count} -> {count} ({change:+.2f}%)")
+
+    return passed
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(
+        description="Tool for measuring deterministic instruction counts of the sorts"
+    )
+    parser.add_argument("test_name", help="Test name, results go into <test_name>.json")
+    parser.add_argument("--backend", choices=["perf", "callgrind"], default="perf")
+    parser.add_argument(
+        "--sorts",
+        default="",
+        help="Comma separated sort names, defaults to all sorts enabled in the build",
+    )
+    parser.add_argument("--types", default="i32,u64")
+    parser.add_argument(
+        "--patterns", default="random,random_d20,ascending,descending,saws_long"
+    )
+    parser.add_argument("--lens", default="20,1000,100000")
+    parser.add_argument("--features", default="")
+    parser.add_argument(
+        "--baseline", help="Result file of an earlier run to compare against"
+    )
+    parser.add_argument(
+        "--max-regression",
+        type=float,
+        default=1.0,
+        help="Largest accepted increase in percent, with --baseline",
+    )
+    args = parser.parse_args()
+
+    if not os.path.exists("build.rs"):
+        print(
+            "Please make sure to run this program with the repo root dir as the current working directory."
+        )
+        sys.exit(1)
+
+    if args.backend == "perf":
+        counts = measure_perf(args)
+    else:
+        counts = measure_callgrind(args)
+
+    result = {"name": args.test_name, "backend": args.backend, "instructions": counts}
+
+    out_file_name = f"{args.test_name}.json"
+    with open(out_file_name, "w", encoding="utf-8") as out_file:
+        json.dump(result, out_file, indent=2)
+    print(f"\nWrote results to {out_file_name}")
+
+    if args.baseline:
+        with open(args.baseline, "r", encoding="utf-8") as baseline_file:
+            baseline = json.load(baseline_file)
+
+        if not compare(baseline, result, args.max_regression):
+            sys.exit(1)
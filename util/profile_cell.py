@@ -0,0 +1,120 @@
+"""
+Records a perf profile of a single benchmark cell and renders it as flamegraph.
+
+A cell is one (sort, type, pattern, len) combination, eg. rust_std_unstable-hot-u64-random-10000.
+The benchmark binary runs only that cell, with criterion in --profile-time mode, which repeats it
+without any analysis overhead. The profile and the flamegraph are stored in
+<test_name>_profiles/ next to <test_name>.json, so they can be looked up when a cell shows a
+regression.
+
+Usage:
+    python util/profile_cell.py my_test_zen3 rust_std_unstable u64 random 10000
+
+Needs Linux perf and inferno, cargo install inferno. Run this program with the repo root dir as the
+current working directory. Additional cargo features can be passed with --features.
+"""
+
+import argparse
+import json
+import os
+import subprocess
+import sys
+
+
+def build_bench_binary(features):
+    cmd = ["cargo", "bench", "--bench", "bench", "--no-run", "--message-format=json"]
+    if features:
+        cmd += ["--features", features]
+
+    # Without debug info perf can't unwind the stack, and the flamegraph would be flat.
+    result = subprocess.run(
+        cmd,
+        check=True,
+        capture_output=True,
+        env=dict(os.environ, CARGO_PROFILE_BENCH_DEBUG="true"),
+    )
+
+    for line in result.stdout.decode("utf-8").splitlines():
+        message = json.loads(line)
+        if message.get("reason") == "compiler-artifact" and message["target"]["name"] == "bench":
+            if message.get("executable"):
+                return message["executable"]
+
+    sys.stderr.write("Failed to find the benchmark executable in the cargo output\n")
+    sys.exit(1)
+
+
+def record_profile(bench_exe, cell_name, perf_data_path, profile_time):
+    subprocess.run(
+        [
+            "perf",
+            "record",
+            "--call-graph",
+            "dwarf",
+            "-F",
+            "997",
+            "-o",
+            perf_data_path,
+            "--",
+            bench_exe,
+            "--bench",
+            "--profile-time",
+            str(profile_time),
+        ],
+        check=True,
+        env=dict(os.environ, BENCH_REGEX=f"^{cell_name}$"),
+    )
+
+
+def render_flamegraph(perf_data_path, svg_path, title):
+    perf_script = subprocess.run(
+        ["perf", "script", "-i", perf_data_path], check=True, capture_output=True
+    )
+    collapsed = subprocess.run(
+        ["inferno-collapse-perf"],
+        input=perf_script.stdout,
+        check=True,
+        capture_output=True,
+    )
+    with open(svg_path, "wb") as svg_file:
+        subprocess.run(
+            ["inferno-flamegraph", "--title", title],
+            input=collapsed.stdout,
+            check=True,
+            stdout=svg_file,
+        )
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(
+        description="Tool for profiling a single benchmark cell"
+    )
+    parser.add_argument("test_name", help="Output goes into <test_name>_profiles/")
+    parser.add_argument("sort_name", help="eg. rust_std_unstable")
+    parser.add_argument("type", help="eg. u64")
+    parser.add_argument("pattern", help="eg. random")
+    parser.add_argument("len", type=int)
+    parser.add_argument(
+        "--profile-time", type=int, default=10, help="Seconds to run the cell for"
+    )
+    parser.add_argument("--features", default="")
+    args = parser.parse_args()
+
+    if not os.path.exists("build.rs"):
+        print(
+            "Please make sure to run this program with the repo root dir as the current working directory."
+        )
+        sys.exit(1)
+
+    cell_name = f"{args.sort_name}-hot-{args.type}-{args.pattern}-{args.len}"
+
+    out_dir = f"{args.test_name}_profiles"
+    os.makedirs(out_dir, exist_ok=True)
+    perf_data_path = os.path.join(out_dir, f"{cell_name}.perf.data")
+    svg_path = os.path.join(out_dir, f"{cell_name}.svg")
+
+    bench_exe = build_bench_binary(args.features)
+    record_profile(bench_exe, cell_name, perf_data_path, args.profile_time)
+    render_flamegraph(perf_data_path, svg_path, cell_name)
+
+    print(f"\nWrote profile to {perf_data_path} and flamegraph to {svg_path}")
@@ -1,10 +1,14 @@
 import subprocess
 import sys
 import os
+import platform
 import shutil
 import argparse
 import json
+import re
+import secrets
 
+from bench_progress import ResultsSink, load_partial, run_with_progress
 from graph_bench_result.cpu_info import get_cpu_info
 
 
@@ -24,7 +28,181 @@ def check_for_correct_dir():
         sys.exit(1)
 
 
-def run_benchmarks(test_name, bench_name_overwrite):
+def collect_dispatch_info():
+    """Returns the SIMD code paths the sorts take on this machine, see src/dispatch.rs."""
+    result = subprocess.run(
+        [
+            "cargo",
+            "run",
+            "--quiet",
+            "--release",
+            "--features",
+            "cold_benchmarks",
+            "--bin",
+            "dispatch_info",
+        ],
+        check=True,
+        capture_output=True,
+        env=os.environ,
+    )
+
+    return json.loads(result.stdout.decode("utf-8"))
+
+
+def pick_pattern_seed():
+    """Fixes the seed of the random patterns for all runs of this invocation, so that the sorts of
+    all variants see the same inputs, and returns it for the results metadata. Uses OVERRIDE_SEED
+    if it is set, see sort_test_tools/src/patterns.rs."""
+    if "OVERRIDE_SEED" not in os.environ:
+        # secrets reads the OS entropy source, same as RANDOM_SEED_FROM_OS=1 would.
+        os.environ.pop("RANDOM_SEED_FROM_OS", None)
+        os.environ["OVERRIDE_SEED"] = str(secrets.randbits(64))
+
+    return {
+        "seed": int(os.environ["OVERRIDE_SEED"]),
+        "rng": os.environ.get("PATTERN_RNG", "std"),
+    }
+
+
+def run_for_output(cmd):
+    """Returns the stdout of cmd, or None if it can't be run, the metadata is best effort."""
+    try:
+        result = subprocess.run(cmd, check=True, capture_output=True)
+    except (OSError, subprocess.CalledProcessError):
+        return None
+
+    return result.stdout.decode("utf-8").strip()
+
+
+def collect_rustc_info():
+    """Version of rustc and LLVM, as printed by `rustc -vV`."""
+    output = run_for_output(["rustc", "-vV"])
+    if output is None:
+        return None
+
+    lines = output.splitlines()
+    info = {"version": lines[0]}
+    for line in lines[1:]:
+        key, _, value = line.partition(": ")
+        info[key.lower().replace("-", "_").replace(" ", "_")] = value
+
+    return info
+
+
+def collect_target_info():
+    """RUSTFLAGS and the target features they enable, eg. with -Ctarget-cpu=native."""
+    rustflags = os.environ.get("RUSTFLAGS", "")
+    target_cpu = None
+    for flag in re.findall(r"target-cpu=(\S+)", rustflags):
+        target_cpu = flag
+
+    cfg = run_for_output(["rustc", "--print", "cfg", *rustflags.split()]) or ""
+    target_features = re.findall(r'^target_feature="(.*)"$', cfg, re.MULTILINE)
+
+    return {
+        "rustflags": rustflags,
+        "target_cpu": target_cpu,
+        "target_features": target_features,
+    }
+
+
+def collect_cpu_info():
+    """Model and microcode revision of the CPU, as far as the OS reports them."""
+    info = {"arch": platform.machine(), "model": platform.processor(), "microcode": None}
+
+    if sys.platform == "linux":
+        # The first core is representative, later lines repeat the keys.
+        fields = {}
+        with open("/proc/cpuinfo", "r", encoding="utf-8") as cpuinfo:
+            for line in cpuinfo:
+                key, _, value = line.partition(":")
+                fields.setdefault(key.strip(), value.strip())
+
+        # aarch64 has no model name, only the part number of the core.
+        info["model"] = fields.get("model name", fields.get("CPU part", info["model"]))
+        info["microcode"] = fields.get("microcode")
+    elif sys.platform == "darwin":
+        info["model"] = run_for_output(
+            ["sysctl", "-n", "machdep.cpu.brand_string"]
+        )
+
+    return info
+
+
+def collect_git_info():
+    """Commit of the benchmarked code, and whether the working tree had changes on top."""
+    commit = run_for_output(["git", "rev-parse", "HEAD"])
+    status = run_for_output(["git", "status", "--porcelain", "--untracked-files=no"])
+
+    return {"commit": commit, "dirty": bool(status)}
+
+
+def collect_metadata(bench_cmd):
+    """Everything needed to tell whether two results files are comparable."""
+    features = bench_cmd[bench_cmd.index("--features") + 1].split(",")
+
+    return {
+        "rustc": collect_rustc_info(),
+        "cargo_features": features,
+        "target": collect_target_info(),
+        "cpu": collect_cpu_info(),
+        "os": platform.platform(),
+        "git": collect_git_info(),
+    }
+
+
+def collect_fallback_counts():
+    """Returns how often the fallback layers of the Rust std sorts triggered, keyed by the name of
+    the matching timing benchmark, see src/instrument/fallbacks.rs."""
+    env = dict(os.environ)
+    env["MEASURE_FALLBACKS"] = "1"
+    if "BENCH_REGEX" in env:
+        env["BENCH_REGEX"] = env["BENCH_REGEX"].replace("-hot-", "-fallbacks-")
+
+    result = subprocess.run(
+        [
+            "cargo",
+            "bench",
+            "--features",
+            "cold_benchmarks,instrument_fallbacks",
+            "--bench",
+            "bench",
+        ],
+        check=True,
+        capture_output=True,
+        env=env,
+    )
+
+    # <name>: runs: 10 heapsort: 0 in 0 break_patterns: 3 in 2 ...
+    fallback_counts = {}
+    for line in result.stdout.decode("utf-8").splitlines():
+        match = re.match(r"^(\S+-fallbacks-\S+): runs: (\d+)(.*)$", line)
+        if match is None:
+            continue
+
+        name, runs, rest = match.groups()
+        entry = {"runs": int(runs)}
+        for fallback, count, invocations in re.findall(r" (\w+): (\d+) in (\d+)", rest):
+            entry[fallback] = {"count": int(count), "invocations": int(invocations)}
+
+        fallback_counts[name.replace("-fallbacks-", "-hot-")] = entry
+
+    return fallback_counts
+
+
+def parse_shard(shard):
+    """Returns (index, count) of `<index>/<count>`, index counting from 1, see
+    src/analysis/shard.rs."""
+    match = re.fullmatch(r"(\d+)/(\d+)", shard)
+    if match is None or not 1 <= int(match.group(1)) <= int(match.group(2)):
+        raise argparse.ArgumentTypeError(
+            f"invalid shard '{shard}', expected <index>/<count> eg. 3/16"
+        )
+
+    return int(match.group(1)), int(match.group(2))
+
+
+def run_benchmarks(test_name, bench_name_overwrite, with_fallbacks, isolate, features):
     # Clean target/criterion a messy one can cause issues when exporting with critcmp.
     # We made sure we are in the current dir earlier.
     cur_dir = os.path.abspath(os.getcwd())
@@ -42,26 +220,39 @@ def run_benchmarks(test_name, bench_name_overwrite):
             )
             sys.exit(1)
 
-    subprocess.run(
-        [
-            "cargo",
-            "bench",
-            "--features",
-            "cold_benchmarks",
-            "--bench",
-            "bench",
-            "--",
-            "--warm-up-time",
-            "2",
-            "--measurement-time",
-            "4",
-            "--noplot",
-            "--save-baseline",
-            test_name,
-        ],
-        check=True,
-        env=os.environ,
-    )
+    bench_cmd = [
+        "cargo",
+        "bench",
+        "--features",
+        ",".join(["cold_benchmarks"] + features),
+        "--bench",
+        "bench",
+        "--",
+        "--warm-up-time",
+        "2",
+        "--measurement-time",
+        "4",
+        "--noplot",
+        "--save-baseline",
+        test_name,
+    ]
+
+    # Collected before the run, so the results streamed during the run have them as well.
+    run_info = {
+        "name": test_name,
+        "metadata": collect_metadata(bench_cmd),
+        "patterns": pick_pattern_seed(),
+        "dispatch": collect_dispatch_info(),
+    }
+    if "BENCH_SHARD" in os.environ:
+        run_info["shard"] = os.environ["BENCH_SHARD"]
+
+    env = dict(os.environ)
+    if isolate:
+        env["BENCH_ISOLATE"] = "1"
+
+    sink = ResultsSink(partial_file_name(test_name), test_name, crit_dir, run_info)
+    failures = run_with_progress(bench_cmd, env, sink)
 
     critcmp_result = subprocess.run(
         ["critcmp", "--export", test_name], capture_output=True
@@ -70,22 +261,48 @@ def run_benchmarks(test_name, bench_name_overwrite):
     if critcmp_result.returncode != 0:
         critcmp_result_stderr = critcmp_result.stderr.decode("utf-8")
         sys.stderr.write(f"\n[Error] Failed to export results with critcmp: {critcmp_result_stderr}")
+        sys.stderr.write(f"The results so far are in {sink.path}, see --recover\n")
         sys.exit(critcmp_result.returncode)
 
-    bench_results = critcmp_result.stdout.decode("utf-8")
+    bench_results = json.loads(critcmp_result.stdout.decode("utf-8"))
+    bench_results |= run_info
+    if with_fallbacks:
+        bench_results["fallbacks"] = collect_fallback_counts()
+    if failures:
+        bench_results["failures"] = failures
+        print(f"\n{len(failures)} benchmarks failed: {', '.join(failures)}")
 
     out_file_name = f"{test_name}.json"
     with open(out_file_name, "w+") as result_file:
-        result_file.write(bench_results)
+        result_file.write(json.dumps(bench_results, indent=2))
 
+    sink.close(remove=True)
     print(f"\nWrote results to {out_file_name}")
     return out_file_name
 
 
-def run_benchmarks_variant(test_name, variant):
+def partial_file_name(test_name):
+    return f"{test_name}.partial.jsonl"
+
+
+def recover_partial_results(test_name):
+    """Writes `<test_name>.json` from the results streamed by an interrupted run, see
+    bench_progress.py. The fallback counts of --fallbacks are missing."""
+    results = load_partial(partial_file_name(test_name))
+
+    out_name = f"{test_name}.json"
+    with open(out_name, "w+", encoding="utf-8") as out_file:
+        out_file.write(json.dumps(results, indent=2))
+
+    print(f"Wrote {len(results['benchmarks'])} recovered results to {out_name}")
+
+
+def run_benchmarks_variant(test_name, variant, with_fallbacks, isolate):
     variant_name = variant["name"]
     setup_cmd = variant["setup_cmd"]
     bench_name_overwrite = variant["BENCH_NAME_OVERWRITE"]
+    # Optional, cargo features added to the build of this variant.
+    features = variant.get("features", [])
 
     if bench_name_overwrite != "":
         os.environ["BENCH_NAME_OVERWRITE"] = bench_name_overwrite
@@ -100,7 +317,9 @@ def run_benchmarks_variant(test_name, variant):
 
     print(f"Running test: {full_test_name}")
 
-    return run_benchmarks(full_test_name, bench_name_overwrite)
+    return run_benchmarks(
+        full_test_name, bench_name_overwrite, with_fallbacks, isolate, features
+    )
 
 
 def combine_out_files(test_name, out_file_names):
@@ -113,11 +332,28 @@ def combine_out_files(test_name, out_file_names):
         open(out_file_names[0], "r", encoding="utf-8").read()
     )
 
-    for out_file_name in out_file_names[1:]:
+    # The variants can differ in the checked out commit or RUSTFLAGS, keep the metadata of each.
+    variant_metadata = {}
+
+    for out_file_name in out_file_names:
         parsed_result = json.loads(
             open(out_file_name, "r", encoding="utf-8").read()
         )
+        variant_metadata[os.path.splitext(out_file_name)[0]] = parsed_result["metadata"]
+
+        if out_file_name == out_file_names[0]:
+            continue
+
         combined_result["benchmarks"] |= parsed_result["benchmarks"]
+        combined_result["dispatch"] |= parsed_result["dispatch"]
+        if "fallbacks" in parsed_result:
+            combined_result.setdefault("fallbacks", {})
+            combined_result["fallbacks"] |= parsed_result["fallbacks"]
+        if "failures" in parsed_result:
+            combined_result.setdefault("failures", {})
+            combined_result["failures"] |= parsed_result["failures"]
+
+    combined_result["variant_metadata"] = variant_metadata
 
     with open(out_name, "w+", encoding="utf-8") as out_file:
         out_file.write(json.dumps(combined_result, indent=2))
@@ -129,10 +365,52 @@ def combine_out_files(test_name, out_file_names):
         os.remove(out_file_name)
 
 
-if __name__ == "__main__":
-    check_for_critcmp()
-    check_for_correct_dir()
+def merge_shard_files(test_name, shard_file_names):
+    """Combines the results files of the shards of one run, eg. from different machines, into
+    `<test_name>.json`. The shards have to be complete and use the same pattern seed."""
+    results = [
+        json.loads(open(file_name, "r", encoding="utf-8").read())
+        for file_name in shard_file_names
+    ]
+
+    shards = [parse_shard(result.get("shard", "1/1")) for result in results]
+    counts = {count for _, count in shards}
+    if len(counts) != 1:
+        sys.exit(f"The shard files are from runs with different shard counts: {sorted(counts)}")
+    count = counts.pop()
+    missing = set(range(1, count + 1)) - {index for index, _ in shards}
+    if missing:
+        sys.exit(f"Missing shards {', '.join(f'{index}/{count}' for index in sorted(missing))}")
+
+    seeds = {result["patterns"]["seed"] for result in results}
+    if len(seeds) != 1:
+        sys.exit(
+            f"The shards use different pattern seeds {sorted(seeds)}, set OVERRIDE_SEED to the same value on all machines"
+        )
 
+    combined_result = results[0]
+    combined_result.pop("shard", None)
+    combined_result["shard_metadata"] = {}
+    for (index, _), result in zip(shards, results):
+        combined_result["shard_metadata"][f"{index}/{count}"] = result["metadata"]
+        if result is results[0]:
+            continue
+
+        combined_result["benchmarks"] |= result["benchmarks"]
+        combined_result["dispatch"] |= result["dispatch"]
+        for key in ["fallbacks", "failures"]:
+            if key in result:
+                combined_result.setdefault(key, {})
+                combined_result[key] |= result[key]
+
+    out_name = f"{test_name}.json"
+    with open(out_name, "w+", encoding="utf-8") as out_file:
+        out_file.write(json.dumps(combined_result, indent=2))
+
+    print(f"Wrote merged results of {count} shards to {out_name}")
+
+
+if __name__ == "__main__":
     variants_help = """"List of variants of the code that should be tested.
 E.g. `--variants path/to/variants.json`
 JSON format:
@@ -151,19 +429,70 @@ JSON format:
         }
     ]
 }
+A variant can add cargo features to its build with "features": ["a", "b"], see
+util/ord_check_variants.json.
 """
 
     parser = argparse.ArgumentParser(
         description="Tool for running and collecting benchmark results"
     )
     parser.add_argument("--variants", dest="variants_file", help=variants_help)
+    parser.add_argument(
+        "--fallbacks",
+        action="store_true",
+        help="Also record how often the fallbacks of the Rust std sorts trigger, in a second run with the instrument_fallbacks feature",
+    )
+    parser.add_argument(
+        "--isolate",
+        action="store_true",
+        help="Run each benchmark in its own process, a crashing sort is recorded under failures instead of ending the run",
+    )
+    parser.add_argument(
+        "--shard",
+        type=parse_shard,
+        help="Only run the i-th of n disjoint parts of the benchmarks, eg. 3/16, for splitting a run across identical machines. Needs OVERRIDE_SEED, the results are named <test_name>_shard<i>of<n>.json",
+    )
+    parser.add_argument(
+        "--merge",
+        nargs="+",
+        metavar="SHARD_FILE",
+        help="Combine the results files of all shards of a run into <test_name>.json instead of running benchmarks",
+    )
     parser.add_argument(
         "test_name",
         nargs="?",
         help="Test name including CPU arch name, e.g. my_test_zen3",
     )
+    parser.add_argument(
+        "--recover",
+        action="store_true",
+        help="Write <test_name>.json from the results an interrupted run streamed to <test_name>.partial.jsonl instead of running benchmarks",
+    )
     args = parser.parse_args()
 
+    if args.recover:
+        if args.test_name is None:
+            sys.exit("--recover needs the test name of the interrupted run, including the variant")
+        recover_partial_results(args.test_name)
+        sys.exit(0)
+
+    if args.merge:
+        if args.test_name is None:
+            sys.exit("--merge needs the test name of the merged results")
+        merge_shard_files(args.test_name, args.merge)
+        sys.exit(0)
+
+    check_for_critcmp()
+    check_for_correct_dir()
+
+    if args.shard is not None:
+        # Every machine has to generate the same inputs, or the results of the shards aren't
+        # comparable.
+        if "OVERRIDE_SEED" not in os.environ:
+            sys.exit("--shard needs OVERRIDE_SEED, set to the same value on all machines")
+        shard_index, shard_count = args.shard
+        os.environ["BENCH_SHARD"] = f"{shard_index}/{shard_count}"
+
     default_variants = {
         "test_name": args.test_name,
         "variants": [
@@ -186,10 +515,18 @@ JSON format:
         for variant in variants["variants"]:
             subprocess.run(variant["setup_cmd"], shell=True, check=True)
 
-    test_name = variants["test_name"]
+    pattern_seed = pick_pattern_seed()
+    print(f"Pattern seed: {pattern_seed['seed']}, rng: {pattern_seed['rng']}")
+
+    # The test name of the variants file is a default, eg. without the CPU name.
+    test_name = args.test_name or variants["test_name"]
+    if args.shard is not None:
+        test_name = f"{test_name}_shard{shard_index}of{shard_count}"
     out_file_names = []
     for variant in variants["variants"]:
-        out_file_names.append(run_benchmarks_variant(test_name, variant))
+        out_file_names.append(
+            run_benchmarks_variant(test_name, variant, args.fallbacks, args.isolate)
+        )
 
     if len(out_file_names) > 1:
         combine_out_files(test_name, out_file_names)
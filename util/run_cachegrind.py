@@ -0,0 +1,153 @@
+"""
+Runs each sort on each pattern under cachegrind and adds the simulated D1 and LL miss rates to a
+result file, eg. the one written by run_benchmarks.py.
+
+Every measurement is a separate process that sorts one fixed input, see src/bin/sort_once.rs. The
+same input is generated once more without sorting it, and those counts are subtracted, so only the
+sort itself is measured. Unlike hardware counters the simulated cache behaves the same on every
+machine, which makes the results comparable across machines.
+
+Usage:
+    # Will add results to my_test_zen3.json, creating it if needed
+    python util/run_cachegrind.py my_test_zen3 --sorts "rust_std_unstable,rust_ipnsort_unstable"
+
+Run this program with the repo root dir as the current working directory. Additional cargo
+features can be passed with --features.
+"""
+
+import argparse
+import json
+import os
+import subprocess
+import sys
+import tempfile
+
+SORT_ONCE_PATH = os.path.join("target", "release", "sort_once")
+
+# Any fixed value works, it only has to be the same for the sort and the baseline run.
+INPUT_SEED = "3245894712"
+
+
+def build_sort_once(features):
+    cmd = ["cargo", "build", "--release", "--bin", "sort_once"]
+    if features:
+        cmd += ["--features", features]
+
+    subprocess.run(cmd, check=True)
+
+
+def list_sorts(ty):
+    result = subprocess.run(
+        [SORT_ONCE_PATH, "--list", ty], check=True, capture_output=True
+    )
+    return result.stdout.decode("utf-8").split()
+
+
+def run_cachegrind(args):
+    """Returns the summary event counts of running sort_once with args under cachegrind."""
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        out_path = os.path.join(tmp_dir, "cachegrind.out")
+        subprocess.run(
+            [
+                "valgrind",
+                "--tool=cachegrind",
+                "--cache-sim=yes",
+                f"--cachegrind-out-file={out_path}",
+                SORT_ONCE_PATH,
+            ]
+            + args,
+            check=True,
+            capture_output=True,
+            env=dict(os.environ, OVERRIDE_SEED=INPUT_SEED),
+        )
+
+        events = None
+        summary = None
+        with open(out_path, "r") as out_file:
+            for line in out_file:
+                if line.startswith("events:"):
+                    events = line.split()[1:]
+                elif line.startswith("summary:"):
+                    summary = [int(val) for val in line.split()[1:]]
+
+        return dict(zip(events, summary))
+
+
+def measure(sort_name, ty, pattern, test_len):
+    args = [sort_name, ty, pattern, str(test_len)]
+    with_sort = run_cachegrind(args)
+    baseline = run_cachegrind(args + ["--no-sort"])
+
+    counts = {event: with_sort[event] - baseline.get(event, 0) for event in with_sort}
+
+    accesses = counts["Dr"] + counts["Dw"]
+    d1_misses = counts["D1mr"] + counts["D1mw"]
+    ll_misses = counts["DLmr"] + counts["DLmw"]
+
+    return {
+        "instructions": counts["Ir"],
+        "data_accesses": accesses,
+        "d1_misses": d1_misses,
+        "ll_misses": ll_misses,
+        "d1_miss_rate": d1_misses / max(accesses, 1),
+        "ll_miss_rate": ll_misses / max(accesses, 1),
+    }
+
+
+def load_result(out_file_name, test_name):
+    if os.path.exists(out_file_name):
+        with open(out_file_name, "r", encoding="utf-8") as result_file:
+            return json.load(result_file)
+
+    return {"name": test_name, "benchmarks": {}}
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(
+        description="Tool for collecting simulated cache statistics with cachegrind"
+    )
+    parser.add_argument("test_name", help="Test name, results go into <test_name>.json")
+    parser.add_argument(
+        "--sorts",
+        default="",
+        help="Comma separated sort names, defaults to all sorts enabled in the build",
+    )
+    parser.add_argument("--types", default="i32,u64")
+    parser.add_argument(
+        "--patterns", default="random,random_d20,ascending,descending,saws_long"
+    )
+    parser.add_argument("--lens", default="1000,100000,1000000")
+    parser.add_argument("--features", default="")
+    args = parser.parse_args()
+
+    if not os.path.exists("build.rs"):
+        print(
+            "Please make sure to run this program with the repo root dir as the current working directory."
+        )
+        sys.exit(1)
+
+    build_sort_once(args.features)
+
+    out_file_name = f"{args.test_name}.json"
+    result = load_result(out_file_name, args.test_name)
+    cachegrind_results = result.setdefault("cachegrind", {})
+
+    for ty in args.types.split(","):
+        sort_names = args.sorts.split(",") if args.sorts else list_sorts(ty)
+
+        for sort_name in sort_names:
+            for pattern in args.patterns.split(","):
+                for test_len in args.lens.split(","):
+                    name = f"{sort_name}-cachegrind-{ty}-{pattern}-{test_len}"
+                    stats = measure(sort_name, ty, pattern, int(test_len))
+                    cachegrind_results[name] = stats
+
+                    print(
+                        f"{name}: D1 miss rate: {stats['d1_miss_rate'] * 100:.2f}% LL miss rate: {stats['ll_miss_rate'] * 100:.3f}%"
+                    )
+
+                    # Write after every measurement, a full run can take hours.
+                    with open(out_file_name, "w", encoding="utf-8") as out_file:
+                        json.dump(result, out_file, indent=2)
+
+    print(f"\nWrote results to {out_file_name}")
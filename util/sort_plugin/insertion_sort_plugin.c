@@ -0,0 +1,60 @@
+// Minimal example plugin, a stable insertion sort.
+//
+// cc -O2 -shared -fPIC insertion_sort_plugin.c -o libinsertion_sort_plugin.so
+// SORT_PLUGINS=$PWD/libinsertion_sort_plugin.so cargo bench --features plugin
+
+#include <stdlib.h>
+#include <string.h>
+
+#include "sort_plugin.h"
+
+const char* sort_name(void) {
+  return "c_insertion_sort_plugin_stable";
+}
+
+void sort_u64(uint64_t* data, size_t len) {
+  for (size_t i = 1; i < len; ++i) {
+    const uint64_t tmp = data[i];
+    size_t j = i;
+    while (j > 0 && tmp < data[j - 1]) {
+      data[j] = data[j - 1];
+      --j;
+    }
+    data[j] = tmp;
+  }
+}
+
+uint32_t sort_by_cmp(void* data,
+                     size_t len,
+                     size_t elem_size,
+                     CMPFUNC_THREEWAY* cmp_fn,
+                     uint8_t* ctx) {
+  char* bytes = (char*)data;
+  char* tmp = malloc(elem_size);
+  if (!tmp) {
+    return SORT_PLUGIN_ALLOC_FAILURE;
+  }
+
+  for (size_t i = 1; i < len; ++i) {
+    memcpy(tmp, bytes + (i * elem_size), elem_size);
+    size_t j = i;
+    while (j > 0) {
+      const CompResult res = cmp_fn(tmp, bytes + ((j - 1) * elem_size), ctx);
+      if (res.is_panic) {
+        // Put tmp back into the gap, so data stays a permutation.
+        memcpy(bytes + (j * elem_size), tmp, elem_size);
+        free(tmp);
+        return SORT_PLUGIN_COMPARISON_PANIC;
+      }
+      if (res.cmp_result != -1) {
+        break;
+      }
+      memcpy(bytes + (j * elem_size), bytes + ((j - 1) * elem_size), elem_size);
+      --j;
+    }
+    memcpy(bytes + (j * elem_size), tmp, elem_size);
+  }
+
+  free(tmp);
+  return SORT_PLUGIN_OK;
+}
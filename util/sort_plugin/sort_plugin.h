@@ -0,0 +1,47 @@
+// C ABI for sort plugins loaded at runtime with the `plugin` feature, see
+// src/plugin.rs.
+#pragma once
+
+#include <stddef.h>
+#include <stdint.h>
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Same layout as sort_test_tools::ffi_types::CompResult.
+typedef struct {
+  int8_t cmp_result;  // -1, 0 or 1
+  bool is_panic;
+} CompResult;
+
+// Return codes of sort_by_cmp, same values as FFIErrorCode in src/cpp/shared.h.
+enum {
+  SORT_PLUGIN_OK = 0,
+  SORT_PLUGIN_COMPARISON_PANIC = 1,
+  SORT_PLUGIN_ALLOC_FAILURE = 2
+};
+
+typedef CompResult CMPFUNC_THREEWAY(const void* a, const void* b, uint8_t* ctx);
+
+// Name used for benchmarks and tests. Should contain "unstable" if the sort is
+// not stable. The returned string must stay valid while the library is loaded.
+const char* sort_name(void);
+
+void sort_u64(uint64_t* data, size_t len);
+
+// Sorts len elements of elem_size bytes each. Elements may only be moved with
+// memcpy-like copies. If cmp_fn returns is_panic, stop calling it, leave data
+// as a permutation of its original elements and return
+// SORT_PLUGIN_COMPARISON_PANIC. Return SORT_PLUGIN_ALLOC_FAILURE if an
+// allocation fails, and SORT_PLUGIN_OK otherwise.
+uint32_t sort_by_cmp(void* data,
+                     size_t len,
+                     size_t elem_size,
+                     CMPFUNC_THREEWAY* cmp_fn,
+                     uint8_t* ctx);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
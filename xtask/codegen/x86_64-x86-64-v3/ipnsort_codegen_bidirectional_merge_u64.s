@@ -0,0 +1,139 @@
+pushq %rbp
+pushq %r15
+pushq %r14
+pushq %r13
+pushq %r12
+pushq %rbx
+subq $24, %rsp
+movq %rsi, %r10
+shrq %r10
+leaq (%rdi,%r10,8), %rax
+leaq (%rdi,%r10,8), %r8
+addq $-8, %r8
+leaq (%rdi,%rsi,8), %rcx
+addq $-8, %rcx
+leaq (%rdx,%rsi,8), %r9
+addq $-8, %r9
+cmpq $1, %r10
+je .LBB1_4
+movq %rsi, 16(%rsp)
+movabsq $576460752303423486, %r11
+movq %r10, 8(%rsp)
+andq %r10, %r11
+.LBB1_2:
+movq (%rax), %r15
+movq (%rdi), %r12
+xorl %ebx, %ebx
+xorl %r14d, %r14d
+cmpq %r12, %r15
+setae %bl
+cmovbq %r15, %r12
+setb %r14b
+movq %r12, (%rdx)
+movq (%rcx), %r12
+movq (%r8), %r13
+cmpq %r13, %r12
+movl $0, %r15d
+adcq $-1, %r15
+cmpq %r13, %r12
+cmovaq %r12, %r13
+movl $0, %r12d
+sbbq %r12, %r12
+movq %r13, (%r9)
+movq (%rax,%r14,8), %r13
+movq (%rdi,%rbx,8), %rbp
+xorl %r10d, %r10d
+xorl %esi, %esi
+cmpq %rbp, %r13
+cmovbq %r13, %rbp
+movq %rbp, 8(%rdx)
+leaq (%rax,%r14,8), %rax
+leaq (%rdi,%rbx,8), %rdi
+movq (%rcx,%r15,8), %rbx
+leaq (%rcx,%r15,8), %rcx
+movq (%r8,%r12,8), %r14
+leaq (%r8,%r12,8), %r8
+setb %sil
+leaq (%rax,%rsi,8), %rax
+setae %r10b
+cmpq %r14, %rbx
+leaq (%rdi,%r10,8), %rdi
+movl $0, %esi
+adcq $-1, %rsi
+cmpq %r14, %rbx
+cmovaq %rbx, %r14
+movq %r14, -8(%r9)
+leaq (%rcx,%rsi,8), %rcx
+movl $0, %esi
+sbbq %rsi, %rsi
+leaq (%r8,%rsi,8), %r8
+addq $16, %rdx
+addq $-16, %r9
+addq $-2, %r11
+jne .LBB1_2
+testb $1, 8(%rsp)
+movq 16(%rsp), %rsi
+jne .LBB1_4
+addq $8, %r8
+testb $1, %sil
+jne .LBB1_6
+.LBB1_7:
+cmpq %r8, %rdi
+je .LBB1_8
+jmp .LBB1_10
+.LBB1_4:
+movq (%rax), %r10
+movq (%rdi), %r11
+xorl %ebx, %ebx
+xorl %r14d, %r14d
+cmpq %r11, %r10
+setae %bl
+setb %r14b
+cmovbq %r10, %r11
+movq %r11, (%rdx)
+movq (%rcx), %r10
+movq (%r8), %r11
+xorl %r15d, %r15d
+cmpq %r11, %r10
+movl $0, %r12d
+adcq $-1, %r12
+cmpq %r11, %r10
+cmovaq %r10, %r11
+leaq (%rax,%r14,8), %rax
+leaq (%rdi,%rbx,8), %rdi
+sbbq %r15, %r15
+addq $8, %rdx
+movq %r11, (%r9)
+leaq (%rcx,%r12,8), %rcx
+leaq (%r8,%r15,8), %r8
+addq $8, %r8
+testb $1, %sil
+je .LBB1_7
+.LBB1_6:
+xorl %esi, %esi
+xorl %r9d, %r9d
+cmpq %r8, %rdi
+setae %sil
+setb %r9b
+movq %rax, %r10
+cmovbq %rdi, %r10
+movq (%r10), %r10
+movq %r10, (%rdx)
+leaq (%rdi,%r9,8), %rdi
+leaq (%rax,%rsi,8), %rax
+cmpq %r8, %rdi
+jne .LBB1_10
+.LBB1_8:
+addq $8, %rcx
+cmpq %rcx, %rax
+jne .LBB1_10
+addq $24, %rsp
+popq %rbx
+popq %r12
+popq %r13
+popq %r14
+popq %r15
+popq %rbp
+retq
+.LBB1_10:
+callq *_RNvNtCs1wG3ThyD7BK_7ipnsort9smallsort22panic_on_ord_violation@GOTPCREL(%rip)
@@ -0,0 +1,65 @@
+testq %rsi, %rsi
+je .LBB2_1
+pushq %r14
+pushq %rbx
+movq (%rdi), %rcx
+leaq (%rdi,%rsi,8), %r8
+leaq (%rdi,%rsi,8), %r9
+addq $-8, %r9
+leaq 8(%rdi), %rsi
+movq (%rdx), %rdx
+xorl %eax, %eax
+cmpq %r9, %rsi
+jae .LBB2_3
+.LBB2_4:
+movq (%rsi), %r10
+xorl %r11d, %r11d
+cmpq %rdx, %r10
+setb %r11b
+movq (%rdi,%rax,8), %rbx
+movq %rbx, -8(%rsi)
+movq %r10, (%rdi,%rax,8)
+leaq (%rax,%r11), %r10
+movq 8(%rsi), %rbx
+cmpq %rdx, %rbx
+movq (%rdi,%r10,8), %r14
+movq %r14, (%rsi)
+movq %rbx, (%rdi,%r10,8)
+adcq %r11, %rax
+addq $16, %rsi
+cmpq %r9, %rsi
+jb .LBB2_4
+leaq -8(%rsi), %r9
+cmpq %r8, %rsi
+jne .LBB2_7
+jmp .LBB2_9
+.LBB2_1:
+xorl %eax, %eax
+retq
+.LBB2_3:
+movq %rdi, %r9
+cmpq %r8, %rsi
+je .LBB2_9
+.LBB2_7:
+movq %r9, %r10
+movq %rsi, %r9
+movq (%rsi), %rsi
+cmpq %rdx, %rsi
+movq (%rdi,%rax,8), %r11
+movq %r11, (%r10)
+movq %rsi, (%rdi,%rax,8)
+adcq $0, %rax
+leaq 8(%r9), %rsi
+cmpq %r8, %rsi
+jne .LBB2_7
+addq $-8, %rsi
+movq %rsi, %r9
+.LBB2_9:
+cmpq %rdx, %rcx
+movq (%rdi,%rax,8), %rdx
+movq %rdx, (%r9)
+movq %rcx, (%rdi,%rax,8)
+adcq $0, %rax
+popq %rbx
+popq %r14
+retq
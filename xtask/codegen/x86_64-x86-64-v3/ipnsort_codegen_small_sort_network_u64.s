@@ -0,0 +1,561 @@
+pushq %rbp
+pushq %r15
+pushq %r14
+pushq %r13
+pushq %r12
+pushq %rbx
+subq $328, %rsp
+movq %rsi, 8(%rsp)
+cmpq $2, %rsi
+jb .LBB3_31
+cmpq $32, 8(%rsp)
+ja .LBB3_33
+movq 8(%rsp), %rax
+movq %rax, %rcx
+shrq %rcx
+cmpq $18, %rax
+movq %rcx, %rdx
+cmovbq %rax, %rdx
+leaq (%rdi,%rcx,8), %r13
+movq %rax, %rbp
+movq %rcx, 48(%rsp)
+subq %rcx, %rbp
+movq %rdi, %r8
+movq %rdi, 16(%rsp)
+movq %r13, 56(%rsp)
+movq %rbp, 64(%rsp)
+.LBB3_3:
+cmpq $12, %rdx
+jbe .LBB3_4
+movq 96(%r8), %rax
+movq (%r8), %rsi
+cmpq %rsi, %rax
+movq %rsi, %r12
+cmovaq %rax, %r12
+movq %rdx, 40(%rsp)
+movq 8(%r8), %rdx
+cmovbq %rax, %rsi
+movq 80(%r8), %rax
+cmpq %rdx, %rax
+movq %rdx, %r13
+cmovaq %rax, %r13
+cmovbq %rax, %rdx
+movq 72(%r8), %rax
+movq 16(%r8), %r14
+cmpq %r14, %rax
+movq %r14, %rcx
+cmovaq %rax, %rcx
+cmovbq %rax, %r14
+movq 56(%r8), %rax
+movq 24(%r8), %rbx
+cmpq %rbx, %rax
+movq %rbx, %r10
+cmovaq %rax, %r10
+cmovbq %rax, %rbx
+movq 88(%r8), %rax
+movq 40(%r8), %rdi
+cmpq %rdi, %rax
+movq %rdi, %r15
+cmovaq %rax, %r15
+cmovbq %rax, %rdi
+movq %rdi, 32(%rsp)
+movq 64(%r8), %rax
+movq 48(%r8), %r11
+cmpq %r11, %rax
+movq %r11, %rbp
+cmovaq %rax, %rbp
+cmovbq %rax, %r11
+cmpq %rdx, %r11
+movq %rdx, %r9
+cmovaq %r11, %r9
+cmovaeq %rdx, %r11
+cmpq %r14, %rbx
+movq %r14, %rdi
+cmovaq %rbx, %rdi
+cmovaeq %r14, %rbx
+movq 32(%r8), %rax
+cmpq %rax, %r15
+movq %rax, %r14
+cmovaq %r15, %r14
+cmovaeq %rax, %r15
+cmpq %r10, %rcx
+movq %r10, %rax
+cmovaq %rcx, %rax
+cmovbq %rcx, %r10
+cmpq %rbp, %r13
+movq %rbp, %rdx
+cmovaq %r13, %rdx
+cmovbq %r13, %rbp
+cmpq %rsi, %r15
+movq %rsi, %rcx
+cmovaq %r15, %rcx
+cmovaeq %rsi, %r15
+cmpq %r11, %rbx
+movq %r11, %rsi
+cmovaq %rbx, %rsi
+movq %rsi, 24(%rsp)
+cmovaeq %r11, %rbx
+cmpq %rdi, %r9
+movq %rdi, %r13
+cmovaq %r9, %r13
+cmovbq %r9, %rdi
+cmpq %r10, %rbp
+movq %r10, %rsi
+cmovaq %rbp, %rsi
+cmovaeq %r10, %rbp
+cmpq %rax, %rdx
+movq %rax, %r9
+cmovaq %rdx, %r9
+cmovaeq %rax, %rdx
+cmpq %r14, %r12
+movq %r14, %r10
+cmovaq %r12, %r10
+cmovbq %r12, %r14
+cmpq %rcx, %r13
+movq %rcx, %r12
+cmovaq %r13, %r12
+cmovaeq %rcx, %r13
+movq 32(%rsp), %rax
+cmpq %rax, %rdx
+movq %rax, %r11
+cmovaq %rdx, %r11
+cmovaeq %rax, %rdx
+cmpq %rsi, %r14
+movq %rsi, %rax
+cmovaq %r14, %rax
+cmovaeq %rsi, %r14
+cmpq %r9, %r10
+movq %r9, %rcx
+cmovaq %r10, %rcx
+movq %rcx, 32(%rsp)
+cmovaeq %r9, %r10
+cmpq %r15, %rdx
+movq %r15, %rcx
+cmovaq %rdx, %rcx
+cmovaeq %r15, %rdx
+cmpq %rdi, %r14
+movq %rdi, %rsi
+cmovaq %r14, %rsi
+cmovaeq %rdi, %r14
+cmpq %r13, %rbp
+movq %r13, %r15
+cmovaq %rbp, %r15
+cmovbq %rbp, %r13
+cmpq %r12, %rax
+movq %r12, %r9
+cmovaq %rax, %r9
+cmovaeq %r12, %rax
+cmpq %r11, %r10
+movq %r11, %rbp
+cmovaq %r10, %rbp
+cmovaeq %r11, %r10
+cmpq %rdx, %rbx
+movq %rdx, %r11
+cmovaq %rbx, %r11
+cmovbq %rbx, %rdx
+movq 24(%rsp), %rdi
+cmpq %rdi, %rcx
+movq %rdi, %r12
+cmovaq %rcx, %r12
+cmovaeq %rdi, %rcx
+cmpq %rax, %r10
+movq %rax, %rdi
+cmovaq %r10, %rdi
+cmovaeq %rax, %r10
+cmpq %r15, %rsi
+movq %r15, %rbx
+cmovaq %rsi, %rbx
+cmovbq %rsi, %r15
+cmpq %rbp, %r9
+movq %rbp, %rax
+cmovaq %r9, %rax
+movq %rax, 24(%rsp)
+cmovbq %r9, %rbp
+cmpq %r11, %r14
+movq %r11, %r9
+cmovaq %r14, %r9
+cmovbq %r14, %r11
+cmpq %rcx, %r13
+movq %rcx, %r14
+cmovaq %r13, %r14
+cmovbq %r13, %rcx
+cmpq %r12, %r10
+movq %r12, %rsi
+cmovaq %r10, %rsi
+cmovaeq %r12, %r10
+cmpq %rdi, %rbp
+movq %rdi, %r12
+cmovaq %rbp, %r12
+cmovaeq %rdi, %rbp
+cmpq %r11, %rcx
+movq %r11, %rax
+cmovaq %rcx, %rax
+cmovaeq %r11, %rcx
+cmpq %r9, %r14
+movq %r9, %r13
+cmovaq %r14, %r13
+cmovaeq %r9, %r14
+cmpq %r10, %r15
+movq %r10, %r11
+cmovaq %r15, %r11
+cmovbq %r15, %r10
+movq 16(%rsp), %rdi
+cmpq %rsi, %rbx
+movq %rsi, %r9
+cmovaq %rbx, %r9
+cmovbq %rbx, %rsi
+cmpq %rax, %r14
+movq %rax, %r15
+cmovaq %r14, %r15
+cmovaeq %rax, %r14
+cmpq %r13, %r10
+movq %r13, %rbx
+cmovaq %r10, %rbx
+movq 32(%rsp), %rax
+movq %rax, 96(%r8)
+movq %rdx, (%r8)
+cmovaeq %r13, %r10
+movq 56(%rsp), %r13
+cmpq %rsi, %r11
+movq %rsi, %rdx
+cmovaq %r11, %rdx
+movq 24(%rsp), %rax
+movq %rax, 88(%r8)
+movq %r12, 80(%r8)
+cmovbq %r11, %rsi
+cmpq %r9, %rbp
+movq %r9, %rax
+cmovaq %rbp, %rax
+movq %rcx, 8(%r8)
+movq %r14, 16(%r8)
+cmovbq %rbp, %r9
+movq 64(%rsp), %rbp
+cmpq %r15, %r10
+movq %r15, %rcx
+cmovaq %r10, %rcx
+movq %rdx, 56(%r8)
+movq %r9, 64(%r8)
+cmovaeq %r15, %r10
+cmpq %rbx, %rsi
+movq %rbx, %rdx
+cmovaq %rsi, %rdx
+movq %rax, 72(%r8)
+movq %r10, 24(%r8)
+cmovaeq %rbx, %rsi
+movq %rcx, 32(%r8)
+movq %rsi, 40(%r8)
+movq %rdx, 48(%r8)
+movq 40(%rsp), %rdx
+movl $13, %ecx
+jmp .LBB3_6
+.LBB3_4:
+movl $1, %ecx
+cmpq $8, %rdx
+jbe .LBB3_6
+movq 24(%r8), %rax
+movq (%r8), %r9
+cmpq %r9, %rax
+movq %rdx, 40(%rsp)
+movq %r9, %rdx
+cmovaq %rax, %rdx
+movq 8(%r8), %rcx
+cmovbq %rax, %r9
+movq 56(%r8), %rax
+cmpq %rcx, %rax
+movq %rcx, %rdi
+cmovaq %rax, %rdi
+cmovbq %rax, %rcx
+movq 40(%r8), %rax
+movq 16(%r8), %r11
+cmpq %r11, %rax
+movq %r11, %r10
+cmovaq %rax, %r10
+cmovbq %rax, %r11
+movq 64(%r8), %rbx
+movq 32(%r8), %rsi
+cmpq %rsi, %rbx
+movq %rsi, %rax
+cmovaq %rbx, %rax
+cmovbq %rbx, %rsi
+cmpq %r9, %rdi
+movq %r9, %rbx
+cmovaq %rdi, %rbx
+cmovaeq %r9, %rdi
+cmpq %r11, %rsi
+movq %r11, %r14
+cmovaq %rsi, %r14
+cmovaeq %r11, %rsi
+cmpq %rdx, %rax
+movq %rdx, %r11
+cmovaq %rax, %r11
+cmovaeq %rdx, %rax
+movq 48(%r8), %rdx
+cmpq %r10, %rdx
+movq %r10, %r9
+cmovaq %rdx, %r9
+cmovbq %rdx, %r10
+cmpq %rdi, %rsi
+movq %rdi, %rdx
+cmovaq %rsi, %rdx
+cmovaeq %rdi, %rsi
+cmpq %rcx, %rax
+movq %rcx, %rdi
+cmovaq %rax, %rdi
+cmovaeq %rcx, %rax
+cmpq %r14, %r10
+movq %r14, %r13
+cmovaq %r10, %r13
+cmovaeq %r14, %r10
+cmpq %rbx, %r11
+movq %rbx, %r12
+cmovaq %r11, %r12
+cmovaeq %rbx, %r11
+cmpq %rax, %r10
+movq %rax, %r15
+cmovaq %r10, %r15
+cmovaeq %rax, %r10
+cmpq %rdi, %r9
+movq %rdi, %rcx
+cmovaq %r9, %rcx
+cmovbq %r9, %rdi
+cmpq %r13, %r11
+movq %r13, %r14
+cmovaq %r11, %r14
+cmovaeq %r13, %r11
+cmpq %rsi, %r10
+movq %rsi, %rbx
+cmovaq %r10, %rbx
+cmovaeq %rsi, %r10
+cmpq %rdx, %r15
+movq %rdx, %rsi
+cmovaq %r15, %rsi
+cmovaeq %rdx, %r15
+cmpq %rdi, %r11
+movq %rdi, %rax
+cmovaq %r11, %rax
+cmovaeq %rdi, %r11
+cmpq %rcx, %r12
+movq %rcx, %r13
+cmovaq %r12, %r13
+cmovbq %r12, %rcx
+cmpq %r15, %r11
+movq %r15, %r9
+cmovaq %r11, %r9
+cmovaeq %r15, %r11
+movq 16(%rsp), %rdi
+cmpq %rsi, %rax
+movq %rsi, %rdx
+cmovaq %rax, %rdx
+cmovaeq %rsi, %rax
+cmpq %rcx, %r14
+movq %rcx, %rsi
+cmovaq %r14, %rsi
+cmovbq %r14, %rcx
+cmpq %rbx, %r11
+movq %rbx, %r14
+cmovaq %r11, %r14
+movq %r10, (%r8)
+movq %r13, 64(%r8)
+movq 56(%rsp), %r13
+cmovaeq %rbx, %r11
+cmpq %r9, %rax
+movq %r9, %r10
+cmovaq %rax, %r10
+movq %rsi, 56(%r8)
+movq %r11, 8(%r8)
+cmovaeq %r9, %rax
+cmpq %rdx, %rcx
+movq %rdx, %rsi
+cmovaq %rcx, %rsi
+movq %r14, 16(%r8)
+movq %rax, 24(%r8)
+cmovaeq %rdx, %rcx
+movq 40(%rsp), %rdx
+movq %r10, 32(%r8)
+movq %rcx, 40(%r8)
+movq %rsi, 48(%r8)
+movl $9, %ecx
+.LBB3_6:
+cmpq %rdx, %rcx
+ja .LBB3_33
+jne .LBB3_8
+.LBB3_17:
+cmpq $18, 8(%rsp)
+jb .LBB3_31
+movq %rbp, %rdx
+cmpq %rdi, %r8
+movq %r13, %r8
+je .LBB3_3
+jmp .LBB3_19
+.LBB3_8:
+leaq (%r8,%rdx,8), %rsi
+shll $3, %ecx
+leaq (%r8,%rcx), %rax
+jmp .LBB3_9
+.LBB3_12:
+movq %r8, %r10
+.LBB3_15:
+movq %rdx, (%r10)
+.LBB3_16:
+addq $8, %rax
+addq $8, %rcx
+cmpq %rsi, %rax
+je .LBB3_17
+.LBB3_9:
+movq -8(%rax), %r9
+movq (%rax), %rdx
+cmpq %r9, %rdx
+jae .LBB3_16
+movq %rcx, %r10
+.LBB3_11:
+movq %r9, (%r8,%r10)
+cmpq $8, %r10
+je .LBB3_12
+movq -16(%r8,%r10), %r9
+addq $-8, %r10
+cmpq %r9, %rdx
+jb .LBB3_11
+addq %r8, %r10
+jmp .LBB3_15
+.LBB3_19:
+movq 8(%rsp), %rax
+leaq (%rsp,%rax,8), %r11
+addq $64, %r11
+leaq (%rdi,%rax,8), %rsi
+addq $-8, %rsi
+leaq -8(%r13), %r8
+movq 48(%rsp), %rbx
+cmpq $1, %rbx
+jne .LBB3_28
+leaq 72(%rsp), %r9
+movq %rdi, %r10
+jmp .LBB3_22
+.LBB3_28:
+andl $30, %ebx
+leaq 72(%rsp), %r9
+movq %rdi, %r10
+.LBB3_29:
+movq (%r13), %rdx
+movq (%r10), %rdi
+xorl %eax, %eax
+xorl %ecx, %ecx
+cmpq %rdi, %rdx
+setae %al
+setb %cl
+cmovbq %rdx, %rdi
+movq (%rsi), %r14
+movq (%r8), %r15
+cmpq %r15, %r14
+movl $0, %edx
+adcq $-1, %rdx
+cmpq %r15, %r14
+cmovaq %r14, %r15
+movq %rdi, (%r9)
+movl $0, %edi
+sbbq %rdi, %rdi
+movq %r15, (%r11)
+movq (%r13,%rcx,8), %r14
+movq (%r10,%rax,8), %r15
+xorl %r12d, %r12d
+movq %r13, %rbp
+xorl %r13d, %r13d
+cmpq %r15, %r14
+cmovbq %r14, %r15
+movq %r15, 8(%r9)
+leaq (%rbp,%rcx,8), %rcx
+leaq (%r10,%rax,8), %rax
+movq (%rsi,%rdx,8), %r14
+leaq (%rsi,%rdx,8), %rdx
+movq (%r8,%rdi,8), %rsi
+leaq (%r8,%rdi,8), %rdi
+setb %r13b
+leaq (%rcx,%r13,8), %r13
+setae %r12b
+cmpq %rsi, %r14
+leaq (%rax,%r12,8), %r10
+movl $0, %eax
+adcq $-1, %rax
+cmpq %rsi, %r14
+cmovaq %r14, %rsi
+movq %rsi, -8(%r11)
+leaq (%rdx,%rax,8), %rsi
+movl $0, %eax
+sbbq %rax, %rax
+leaq (%rdi,%rax,8), %r8
+addq $16, %r9
+addq $-16, %r11
+addq $-2, %rbx
+jne .LBB3_29
+testb $1, 48(%rsp)
+movq 16(%rsp), %rdi
+jne .LBB3_22
+addq $8, %r8
+testb $1, 8(%rsp)
+jne .LBB3_24
+.LBB3_25:
+cmpq %r8, %r10
+je .LBB3_26
+jmp .LBB3_27
+.LBB3_22:
+movq (%r13), %rax
+movq (%r10), %rcx
+xorl %edx, %edx
+xorl %r15d, %r15d
+cmpq %rcx, %rax
+setae %dl
+setb %r15b
+cmovbq %rax, %rcx
+movq %rcx, (%r9)
+movq (%rsi), %rax
+movq (%r8), %rcx
+xorl %ebx, %ebx
+cmpq %rcx, %rax
+movl $0, %r14d
+adcq $-1, %r14
+cmpq %rcx, %rax
+cmovaq %rax, %rcx
+leaq (%r13,%r15,8), %r13
+leaq (%r10,%rdx,8), %r10
+sbbq %rbx, %rbx
+addq $8, %r9
+movq %rcx, (%r11)
+leaq (%rsi,%r14,8), %rsi
+leaq (%r8,%rbx,8), %r8
+addq $8, %r8
+testb $1, 8(%rsp)
+je .LBB3_25
+.LBB3_24:
+xorl %eax, %eax
+xorl %ecx, %ecx
+cmpq %r8, %r10
+setae %al
+setb %cl
+movq %r13, %rdx
+cmovbq %r10, %rdx
+movq (%rdx), %rdx
+movq %rdx, (%r9)
+leaq (%r10,%rcx,8), %r10
+leaq (%r13,%rax,8), %r13
+cmpq %r8, %r10
+jne .LBB3_27
+.LBB3_26:
+addq $8, %rsi
+cmpq %rsi, %r13
+jne .LBB3_27
+movq 8(%rsp), %rdx
+shlq $3, %rdx
+leaq 72(%rsp), %rsi
+callq *memcpy@GOTPCREL(%rip)
+.LBB3_31:
+addq $328, %rsp
+popq %rbx
+popq %r12
+popq %r13
+popq %r14
+popq %r15
+popq %rbp
+retq
+.LBB3_33:
+ud2
+.LBB3_27:
+callq *_RNvNtCs1wG3ThyD7BK_7ipnsort9smallsort22panic_on_ord_violation@GOTPCREL(%rip)
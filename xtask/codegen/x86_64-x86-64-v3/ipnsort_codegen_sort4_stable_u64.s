@@ -0,0 +1,51 @@
+pushq %rbp
+pushq %r15
+pushq %r14
+pushq %rbx
+movq 8(%rdi), %rcx
+movq 24(%rdi), %rdx
+xorl %eax, %eax
+xorl %r9d, %r9d
+cmpq (%rdi), %rcx
+setae %al
+setb %r9b
+xorl %r8d, %r8d
+cmpq 16(%rdi), %rdx
+setb %r8b
+leaq (%rdi,%rax,8), %rdx
+leal 2(%r8), %r10d
+xorl $3, %r8d
+leaq (%rdi,%r8,8), %rcx
+movq (%rdi,%r10,8), %r11
+movq (%rdi,%r9,8), %rbx
+cmpq %rbx, %r11
+movq (%rdi,%r8,8), %r14
+movl %r10d, %r15d
+cmovbl %eax, %r15d
+movl %eax, %ebp
+cmovbl %r9d, %ebp
+cmovbl %r9d, %r10d
+cmovbq %r11, %rbx
+cmpq (%rdi,%rax,8), %r14
+cmovbl %r8d, %r15d
+leaq (%rdi,%r15,8), %rax
+movq (%rdi,%r15,8), %r8
+cmovael %ebp, %r10d
+cmovbq %rdx, %rcx
+cmpq (%rdi,%r10,8), %r8
+leaq (%rdi,%r10,8), %rdx
+movq %rbx, (%rsi)
+movq %rdx, %rdi
+cmovbq %rax, %rdi
+movq (%rdi), %rdi
+movq %rdi, 8(%rsi)
+cmovbq %rdx, %rax
+movq (%rax), %rax
+movq %rax, 16(%rsi)
+movq (%rcx), %rax
+movq %rax, 24(%rsi)
+popq %rbx
+popq %r14
+popq %r15
+popq %rbp
+retq
@@ -0,0 +1,72 @@
+pushq %r15
+pushq %r14
+pushq %r13
+pushq %r12
+pushq %rbx
+movq %rsi, %r9
+shrq %r9
+leaq (%rdi,%r9,8), %rax
+leaq (%rdi,%r9,8), %r8
+addq $-8, %r8
+leaq (%rdi,%rsi,8), %rcx
+addq $-8, %rcx
+leaq (%rdx,%rsi,8), %r10
+addq $-8, %r10
+negq %r9
+xorl %r11d, %r11d
+.LBB1_1:
+movq (%rax), %rbx
+movq (%rdi), %r14
+xorl %r15d, %r15d
+xorl %r12d, %r12d
+cmpq %r14, %rbx
+setae %r15b
+cmovbq %rbx, %r14
+setb %r12b
+movq %r14, (%rdx)
+movq (%rcx), %rbx
+movq (%r8), %r14
+cmpq %r14, %rbx
+movl $0, %r13d
+adcq $-1, %r13
+cmpq %r14, %rbx
+leaq (%rax,%r12,8), %rax
+leaq (%rdi,%r15,8), %rdi
+cmovaq %rbx, %r14
+movl $0, %ebx
+sbbq %rbx, %rbx
+addq $8, %rdx
+movq %r14, (%r10,%r11,8)
+leaq (%rcx,%r13,8), %rcx
+leaq (%r8,%rbx,8), %r8
+decq %r11
+cmpq %r11, %r9
+jne .LBB1_1
+addq $8, %r8
+testb $1, %sil
+je .LBB1_4
+xorl %esi, %esi
+xorl %r9d, %r9d
+cmpq %r8, %rdi
+setae %sil
+setb %r9b
+movq %rax, %r10
+cmovbq %rdi, %r10
+movq (%r10), %r10
+movq %r10, (%rdx)
+leaq (%rdi,%r9,8), %rdi
+leaq (%rax,%rsi,8), %rax
+.LBB1_4:
+cmpq %r8, %rdi
+jne .LBB1_7
+addq $8, %rcx
+cmpq %rcx, %rax
+jne .LBB1_7
+popq %rbx
+popq %r12
+popq %r13
+popq %r14
+popq %r15
+retq
+.LBB1_7:
+callq *_RNvNtCs1wG3ThyD7BK_7ipnsort9smallsort22panic_on_ord_violation@GOTPCREL(%rip)
@@ -0,0 +1,492 @@
+pushq %rbp
+pushq %r15
+pushq %r14
+pushq %r13
+pushq %r12
+pushq %rbx
+subq $328, %rsp
+movq %rdi, 8(%rsp)
+cmpq $2, %rsi
+jb .LBB3_26
+movq %rsi, %rbp
+cmpq $32, %rsi
+ja .LBB3_28
+movq %rbp, %r12
+shrq %r12
+cmpq $18, %rbp
+movq %r12, %rdx
+cmovbq %rbp, %rdx
+movq 8(%rsp), %r8
+leaq (%r8,%r12,8), %r15
+movq %rbp, %rax
+subq %r12, %rax
+movq %rax, 64(%rsp)
+movq %r15, 48(%rsp)
+movq %r12, 40(%rsp)
+movq %rbp, 56(%rsp)
+.LBB3_3:
+cmpq $12, %rdx
+jbe .LBB3_4
+movq 96(%r8), %rax
+movq (%r8), %r15
+movq 8(%r8), %rcx
+cmpq %r15, %rax
+movq %r15, %rsi
+cmovaq %rax, %rsi
+cmovbq %rax, %r15
+movq 80(%r8), %rax
+cmpq %rcx, %rax
+movq %rcx, %r12
+cmovaq %rax, %r12
+cmovbq %rax, %rcx
+movq 72(%r8), %rax
+movq 16(%r8), %r14
+cmpq %r14, %rax
+movq %r14, %r13
+cmovaq %rax, %r13
+cmovbq %rax, %r14
+movq 56(%r8), %rax
+movq 24(%r8), %rbx
+cmpq %rbx, %rax
+movq %rbx, %r10
+cmovaq %rax, %r10
+cmovbq %rax, %rbx
+movq 88(%r8), %rax
+movq 40(%r8), %rdi
+cmpq %rdi, %rax
+movq %rdi, %rbp
+cmovaq %rax, %rbp
+cmovbq %rax, %rdi
+movq %rdi, 24(%rsp)
+movq 64(%r8), %rax
+movq 48(%r8), %r11
+cmpq %r11, %rax
+movq %rdx, 32(%rsp)
+movq %r11, %rdx
+cmovaq %rax, %rdx
+cmovbq %rax, %r11
+cmpq %rcx, %r11
+movq %rcx, %r9
+cmovaq %r11, %r9
+cmovaeq %rcx, %r11
+cmpq %r14, %rbx
+movq %r14, %rcx
+cmovaq %rbx, %rcx
+cmovaeq %r14, %rbx
+movq 32(%r8), %rax
+cmpq %rax, %rbp
+movq %rax, %r14
+cmovaq %rbp, %r14
+cmovaeq %rax, %rbp
+cmpq %r10, %r13
+movq %r10, %rax
+cmovaq %r13, %rax
+cmovbq %r13, %r10
+cmpq %rdx, %r12
+movq %rdx, %r13
+cmovaq %r12, %r13
+cmovbq %r12, %rdx
+cmpq %r15, %rbp
+movq %r15, %rdi
+cmovaq %rbp, %rdi
+cmovaeq %r15, %rbp
+cmpq %r11, %rbx
+movq %r11, %r15
+cmovaq %rbx, %r15
+movq %r15, 16(%rsp)
+cmovaeq %r11, %rbx
+cmpq %rcx, %r9
+movq %rcx, %r12
+cmovaq %r9, %r12
+cmovbq %r9, %rcx
+cmpq %r10, %rdx
+movq %r10, %r9
+cmovaq %rdx, %r9
+cmovaeq %r10, %rdx
+cmpq %rax, %r13
+movq %rax, %r15
+cmovaq %r13, %r15
+cmovaeq %rax, %r13
+cmpq %r14, %rsi
+movq %r14, %r10
+cmovaq %rsi, %r10
+cmovbq %rsi, %r14
+cmpq %rdi, %r12
+movq %rdi, %rsi
+cmovaq %r12, %rsi
+cmovaeq %rdi, %r12
+movq 24(%rsp), %rax
+cmpq %rax, %r13
+movq %rax, %r11
+cmovaq %r13, %r11
+cmovaeq %rax, %r13
+cmpq %r9, %r14
+movq %r9, %rax
+cmovaq %r14, %rax
+cmovaeq %r9, %r14
+cmpq %r15, %r10
+movq %r15, %rdi
+cmovaq %r10, %rdi
+movq %rdi, 24(%rsp)
+cmovaeq %r15, %r10
+cmpq %rbp, %r13
+movq %rbp, %r15
+cmovaq %r13, %r15
+cmovaeq %rbp, %r13
+cmpq %rcx, %r14
+movq %rcx, %rdi
+cmovaq %r14, %rdi
+cmovaeq %rcx, %r14
+cmpq %r12, %rdx
+movq %r12, %rbp
+cmovaq %rdx, %rbp
+cmovbq %rdx, %r12
+cmpq %rsi, %rax
+movq %rsi, %r9
+cmovaq %rax, %r9
+cmovaeq %rsi, %rax
+cmpq %r11, %r10
+movq %r11, %rdx
+cmovaq %r10, %rdx
+cmovaeq %r11, %r10
+cmpq %r13, %rbx
+movq %r13, %r11
+cmovaq %rbx, %r11
+cmovbq %rbx, %r13
+movq 16(%rsp), %rsi
+cmpq %rsi, %r15
+movq %rsi, %rcx
+cmovaq %r15, %rcx
+cmovaeq %rsi, %r15
+cmpq %rax, %r10
+movq %rax, %rsi
+cmovaq %r10, %rsi
+cmovaeq %rax, %r10
+cmpq %rbp, %rdi
+movq %rbp, %rax
+cmovaq %rdi, %rax
+cmovbq %rdi, %rbp
+cmpq %rdx, %r9
+movq %rdx, %rdi
+cmovaq %r9, %rdi
+movq %rdi, 16(%rsp)
+cmovbq %r9, %rdx
+cmpq %r11, %r14
+movq %r11, %r9
+cmovaq %r14, %r9
+cmovbq %r14, %r11
+cmpq %r15, %r12
+movq %r15, %rbx
+cmovaq %r12, %rbx
+cmovbq %r12, %r15
+cmpq %rcx, %r10
+movq %rcx, %rdi
+cmovaq %r10, %rdi
+cmovaeq %rcx, %r10
+cmpq %rsi, %rdx
+movq %rsi, %r14
+cmovaq %rdx, %r14
+cmovaeq %rsi, %rdx
+cmpq %r11, %r15
+movq %r11, %r12
+cmovaq %r15, %r12
+cmovaeq %r11, %r15
+cmpq %r9, %rbx
+movq %r9, %r11
+cmovaq %rbx, %r11
+movq 24(%rsp), %rcx
+movq %rcx, 96(%r8)
+cmovaeq %r9, %rbx
+cmpq %r10, %rbp
+movq %r10, %r9
+cmovaq %rbp, %r9
+movq %r13, (%r8)
+cmovbq %rbp, %r10
+movq 56(%rsp), %rbp
+cmpq %rdi, %rax
+movq %rdi, %rcx
+cmovaq %rax, %rcx
+movq 16(%rsp), %rsi
+movq %rsi, 88(%r8)
+cmovbq %rax, %rdi
+cmpq %r12, %rbx
+movq %r12, %rsi
+cmovaq %rbx, %rsi
+movq %r14, 80(%r8)
+cmovaeq %r12, %rbx
+movq 40(%rsp), %r12
+cmpq %r11, %r10
+movq %r11, %rax
+cmovaq %r10, %rax
+movq %r15, 8(%r8)
+movq 48(%rsp), %r15
+cmovaeq %r11, %r10
+cmpq %rdi, %r9
+movq %rdi, %r11
+cmovaq %r9, %r11
+movq %rbx, 16(%r8)
+cmovbq %r9, %rdi
+cmpq %rcx, %rdx
+movq %rcx, %r9
+cmovaq %rdx, %r9
+movq %r11, 56(%r8)
+cmovbq %rdx, %rcx
+cmpq %rsi, %r10
+movq %rsi, %rdx
+cmovaq %r10, %rdx
+movq %rcx, 64(%r8)
+cmovaeq %rsi, %r10
+cmpq %rax, %rdi
+movq %rax, %rcx
+cmovaq %rdi, %rcx
+movq %r9, 72(%r8)
+movq %r10, 24(%r8)
+cmovaeq %rax, %rdi
+movq %rdx, 32(%r8)
+movq 32(%rsp), %rdx
+movq %rdi, 40(%r8)
+movq %rcx, 48(%r8)
+movl $13, %ecx
+jmp .LBB3_6
+.LBB3_4:
+movl $1, %ecx
+cmpq $8, %rdx
+jbe .LBB3_6
+movq 24(%r8), %rax
+movq (%r8), %r9
+movq 8(%r8), %rcx
+cmpq %r9, %rax
+movq %rdx, 32(%rsp)
+movq %r9, %rdx
+cmovaq %rax, %rdx
+cmovbq %rax, %r9
+movq 56(%r8), %rax
+cmpq %rcx, %rax
+movq %rcx, %rdi
+cmovaq %rax, %rdi
+cmovbq %rax, %rcx
+movq 40(%r8), %rax
+movq 16(%r8), %r11
+cmpq %r11, %rax
+movq %r11, %r10
+cmovaq %rax, %r10
+cmovbq %rax, %r11
+movq 64(%r8), %rbx
+movq 32(%r8), %rsi
+cmpq %rsi, %rbx
+movq %rsi, %rax
+cmovaq %rbx, %rax
+cmovbq %rbx, %rsi
+cmpq %r9, %rdi
+movq %r9, %rbx
+cmovaq %rdi, %rbx
+cmovaeq %r9, %rdi
+cmpq %r11, %rsi
+movq %r11, %r14
+cmovaq %rsi, %r14
+cmovaeq %r11, %rsi
+cmpq %rdx, %rax
+movq %rdx, %r11
+cmovaq %rax, %r11
+cmovaeq %rdx, %rax
+movq 48(%r8), %rdx
+cmpq %r10, %rdx
+movq %r10, %r9
+cmovaq %rdx, %r9
+cmovbq %rdx, %r10
+cmpq %rdi, %rsi
+movq %rdi, %rdx
+cmovaq %rsi, %rdx
+cmovaeq %rdi, %rsi
+cmpq %rcx, %rax
+movq %rcx, %rdi
+cmovaq %rax, %rdi
+cmovaeq %rcx, %rax
+cmpq %r14, %r10
+movq %r14, %r13
+cmovaq %r10, %r13
+cmovaeq %r14, %r10
+cmpq %rbx, %r11
+movq %rbx, %r12
+cmovaq %r11, %r12
+cmovaeq %rbx, %r11
+cmpq %rax, %r10
+movq %rax, %r15
+cmovaq %r10, %r15
+cmovaeq %rax, %r10
+cmpq %rdi, %r9
+movq %rdi, %rcx
+cmovaq %r9, %rcx
+cmovbq %r9, %rdi
+cmpq %r13, %r11
+movq %r13, %r14
+cmovaq %r11, %r14
+cmovaeq %r13, %r11
+cmpq %rsi, %r10
+movq %rsi, %rbx
+cmovaq %r10, %rbx
+cmovaeq %rsi, %r10
+cmpq %rdx, %r15
+movq %rdx, %rsi
+cmovaq %r15, %rsi
+cmovaeq %rdx, %r15
+cmpq %rdi, %r11
+movq %rdi, %rax
+cmovaq %r11, %rax
+cmovaeq %rdi, %r11
+cmpq %rcx, %r12
+movq %rcx, %rdi
+cmovaq %r12, %rdi
+cmovbq %r12, %rcx
+movq 40(%rsp), %r12
+cmpq %r15, %r11
+movq %r15, %r9
+cmovaq %r11, %r9
+cmovaeq %r15, %r11
+movq 48(%rsp), %r15
+cmpq %rsi, %rax
+movq %rsi, %rdx
+cmovaq %rax, %rdx
+movq %r10, (%r8)
+cmovaeq %rsi, %rax
+cmpq %rcx, %r14
+movq %rcx, %rsi
+cmovaq %r14, %rsi
+movq %rdi, 64(%r8)
+cmovbq %r14, %rcx
+cmpq %rbx, %r11
+movq %rbx, %rdi
+cmovaq %r11, %rdi
+movq %rsi, 56(%r8)
+cmovaeq %rbx, %r11
+cmpq %r9, %rax
+movq %r9, %rsi
+cmovaq %rax, %rsi
+movq %r11, 8(%r8)
+cmovaeq %r9, %rax
+cmpq %rdx, %rcx
+movq %rdx, %r9
+cmovaq %rcx, %r9
+movq %rdi, 16(%r8)
+movq %rax, 24(%r8)
+cmovaeq %rdx, %rcx
+movq 32(%rsp), %rdx
+movq %rsi, 32(%r8)
+movq %rcx, 40(%r8)
+movq %r9, 48(%r8)
+movl $9, %ecx
+.LBB3_6:
+cmpq %rdx, %rcx
+ja .LBB3_28
+jne .LBB3_8
+.LBB3_17:
+cmpq $18, %rbp
+jb .LBB3_26
+movq 64(%rsp), %rdx
+cmpq 8(%rsp), %r8
+movq %r15, %r8
+je .LBB3_3
+jmp .LBB3_19
+.LBB3_8:
+leaq (%r8,%rdx,8), %rsi
+shll $3, %ecx
+leaq (%r8,%rcx), %rax
+jmp .LBB3_9
+.LBB3_12:
+movq %r8, %rdi
+.LBB3_15:
+movq %rdx, (%rdi)
+.LBB3_16:
+addq $8, %rax
+addq $8, %rcx
+cmpq %rsi, %rax
+je .LBB3_17
+.LBB3_9:
+movq -8(%rax), %r9
+movq (%rax), %rdx
+cmpq %r9, %rdx
+jae .LBB3_16
+movq %rcx, %rdi
+.LBB3_11:
+movq %r9, (%r8,%rdi)
+cmpq $8, %rdi
+je .LBB3_12
+movq -16(%r8,%rdi), %r9
+addq $-8, %rdi
+cmpq %r9, %rdx
+jb .LBB3_11
+addq %r8, %rdi
+jmp .LBB3_15
+.LBB3_19:
+leaq -1(%rbp), %rdi
+movq 8(%rsp), %r9
+leaq (%r9,%rbp,8), %rsi
+addq $-8, %rsi
+leaq -8(%r15), %r8
+xorl %ecx, %ecx
+.LBB3_20:
+movq (%r15), %rax
+movq (%r9), %rdx
+xorl %r10d, %r10d
+xorl %r11d, %r11d
+cmpq %rdx, %rax
+setae %r10b
+setb %r11b
+cmovbq %rax, %rdx
+movq (%rsi), %rax
+movq (%r8), %rbx
+cmpq %rbx, %rax
+movl $0, %r14d
+adcq $-1, %r14
+cmpq %rbx, %rax
+cmovaq %rax, %rbx
+movq %rdx, 72(%rsp,%rcx,8)
+movl $0, %eax
+sbbq %rax, %rax
+incq %rcx
+leaq (%r15,%r11,8), %r15
+leaq (%r9,%r10,8), %r9
+movq %rbx, 72(%rsp,%rdi,8)
+leaq (%rsi,%r14,8), %rsi
+leaq (%r8,%rax,8), %r8
+decq %rdi
+cmpq %rcx, %r12
+jne .LBB3_20
+addq $8, %r8
+testb $1, %bpl
+je .LBB3_23
+xorl %eax, %eax
+xorl %edx, %edx
+cmpq %r8, %r9
+setae %al
+setb %dl
+movq %r15, %rdi
+cmovbq %r9, %rdi
+movq (%rdi), %rdi
+movq %rdi, 72(%rsp,%rcx,8)
+leaq (%r9,%rdx,8), %r9
+leaq (%r15,%rax,8), %r15
+.LBB3_23:
+cmpq %r8, %r9
+jne .LBB3_29
+addq $8, %rsi
+cmpq %rsi, %r15
+jne .LBB3_29
+shlq $3, %rbp
+leaq 72(%rsp), %rsi
+movq 8(%rsp), %rdi
+movq %rbp, %rdx
+callq *memcpy@GOTPCREL(%rip)
+.LBB3_26:
+addq $328, %rsp
+popq %rbx
+popq %r12
+popq %r13
+popq %r14
+popq %r15
+popq %rbp
+retq
+.LBB3_28:
+ud2
+.LBB3_29:
+callq *_RNvNtCs1wG3ThyD7BK_7ipnsort9smallsort22panic_on_ord_violation@GOTPCREL(%rip)
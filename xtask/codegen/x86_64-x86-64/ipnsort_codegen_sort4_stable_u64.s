@@ -0,0 +1,49 @@
+pushq %rbp
+pushq %r14
+pushq %rbx
+movq 8(%rdi), %rcx
+movq 24(%rdi), %r9
+xorl %eax, %eax
+xorl %r8d, %r8d
+cmpq (%rdi), %rcx
+setae %al
+setb %r8b
+xorl %edx, %edx
+cmpq 16(%rdi), %r9
+setb %dl
+leaq (%rdi,%rax,8), %rcx
+leal 2(%rdx), %r9d
+xorl $3, %edx
+movq (%rdi,%r9,8), %r10
+movq (%rdi,%r8,8), %r11
+cmpq %r11, %r10
+movq (%rdi,%rdx,8), %rbx
+movl %r9d, %r14d
+cmovbl %eax, %r14d
+movl %eax, %ebp
+cmovbl %r8d, %ebp
+cmovbl %r8d, %r9d
+cmovbq %r10, %r11
+cmpq (%rdi,%rax,8), %rbx
+leaq (%rdi,%rdx,8), %rax
+cmovbl %edx, %r14d
+leaq (%rdi,%r14,8), %rdx
+movq (%rdi,%r14,8), %r8
+cmovael %ebp, %r9d
+leaq (%rdi,%r9,8), %r10
+cmovbq %rcx, %rax
+cmpq (%rdi,%r9,8), %r8
+movq %r11, (%rsi)
+movq %r10, %rcx
+cmovbq %rdx, %rcx
+movq (%rcx), %rcx
+movq %rcx, 8(%rsi)
+cmovbq %r10, %rdx
+movq (%rdx), %rcx
+movq %rcx, 16(%rsi)
+movq (%rax), %rax
+movq %rax, 24(%rsi)
+popq %rbx
+popq %r14
+popq %rbp
+retq
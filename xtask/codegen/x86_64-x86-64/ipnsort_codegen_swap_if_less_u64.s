@@ -0,0 +1,9 @@
+movq (%rdi,%rdx,8), %rax
+movq (%rdi,%rsi,8), %rcx
+cmpq %rcx, %rax
+movq %rcx, %r8
+cmovaq %rax, %r8
+cmovbq %rax, %rcx
+movq %rcx, (%rdi,%rsi,8)
+movq %r8, (%rdi,%rdx,8)
+retq
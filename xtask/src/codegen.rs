@@ -0,0 +1,251 @@
+//! Codegen regression check for the hot loops of ipnsort.
+//!
+//! The kernels are built with the `codegen_inspect` feature of ipnsort, which exports never
+//! inlined u64 instances with unmangled names. Their assembly is extracted for every target CPU
+//! and compared to the snapshots in xtask/codegen/<arch>-<cpu>/. A kernel regresses if it has more
+//! conditional branches or fewer conditional selects (cmov, csel) than its snapshot, which is how
+//! branchless code silently turns into branchy code with a new rustc version. Other differences
+//! are reported but don't fail the check, register allocation and scheduling change all the time.
+//!
+//! The snapshots depend on the toolchain, after an intentional change or a toolchain update
+//! re-create them with `--bless` and check them in.
+
+use std::env::consts::ARCH;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use crate::repo_root;
+
+const KERNELS: &[&str] = &[
+    "ipnsort_codegen_swap_if_less_u64",
+    "ipnsort_codegen_sort4_stable_u64",
+    "ipnsort_codegen_small_sort_network_u64",
+    "ipnsort_codegen_partition_lomuto_branchless_cyclic_u64",
+    "ipnsort_codegen_bidirectional_merge_u64",
+];
+
+fn default_target_cpus() -> &'static [&'static str] {
+    match ARCH {
+        "x86_64" => &["x86-64", "x86-64-v3"],
+        "aarch64" => &["generic", "neoverse-n1"],
+        _ => &["generic"],
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Stats {
+    instructions: usize,
+    conditional_branches: usize,
+    selects: usize,
+}
+
+fn mnemonic(line: &str) -> Option<&str> {
+    if line.ends_with(':') {
+        return None;
+    }
+
+    line.split_whitespace().next()
+}
+
+fn is_conditional_branch(mnemonic: &str) -> bool {
+    match ARCH {
+        "aarch64" => {
+            mnemonic.starts_with("b.") || matches!(mnemonic, "cbz" | "cbnz" | "tbz" | "tbnz")
+        }
+        _ => mnemonic.starts_with('j') && mnemonic != "jmp",
+    }
+}
+
+fn is_select(mnemonic: &str) -> bool {
+    match ARCH {
+        "aarch64" => matches!(
+            mnemonic,
+            "csel" | "csinc" | "csinv" | "csneg" | "cset" | "csetm" | "cinc"
+        ),
+        _ => mnemonic.starts_with("cmov"),
+    }
+}
+
+fn stats(body: &[String]) -> Stats {
+    let mut stats = Stats::default();
+
+    for mnemonic in body.iter().filter_map(|line| mnemonic(line)) {
+        stats.instructions += 1;
+        stats.conditional_branches += is_conditional_branch(mnemonic) as usize;
+        stats.selects += is_select(mnemonic) as usize;
+    }
+
+    stats
+}
+
+/// Instructions and local labels of `symbol`, without comments and assembler directives.
+fn extract_function(asm: &str, symbol: &str) -> Option<Vec<String>> {
+    let comment_start = if ARCH == "aarch64" { "//" } else { "#" };
+    let mach_o_symbol = format!("_{symbol}:");
+    let elf_symbol = format!("{symbol}:");
+
+    let mut lines = asm.lines().map(str::trim);
+    lines.find(|line| *line == elf_symbol || *line == mach_o_symbol)?;
+
+    let body = lines
+        .take_while(|line| !line.starts_with(".cfi_endproc") && !line.starts_with(".Lfunc_end"))
+        .map(|line| line.split(comment_start).next().unwrap().trim())
+        .filter(|line| !line.is_empty() && (!line.starts_with('.') || line.ends_with(':')))
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+
+    Some(body)
+}
+
+fn emit_asm(root: &Path, target_cpu: &str) -> Result<String, String> {
+    let target_dir = root.join("target").join("xtask-codegen").join(target_cpu);
+
+    let status = Command::new(env!("CARGO"))
+        .current_dir(root)
+        .args([
+            "rustc",
+            "--release",
+            "--lib",
+            "--features",
+            "codegen_inspect",
+        ])
+        .arg("--manifest-path")
+        .arg(root.join("ipnsort").join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "--emit", "asm", "-C", "codegen-units=1"])
+        .arg(format!("-Ctarget-cpu={target_cpu}"))
+        .status()
+        .map_err(|err| format!("Failed to run cargo: {err}"))?;
+
+    if !status.success() {
+        return Err(format!("Building ipnsort for {target_cpu} failed"));
+    }
+
+    // Older builds can leave stale files behind, the most recent one is the current one.
+    let deps_dir = target_dir.join("release").join("deps");
+    let asm_path = fs::read_dir(&deps_dir)
+        .map_err(|err| format!("Failed to read {}: {err}", deps_dir.display()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "s")
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("ipnsort-"))
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .ok_or_else(|| format!("No assembly found in {}", deps_dir.display()))?;
+
+    fs::read_to_string(&asm_path)
+        .map_err(|err| format!("Failed to read {}: {err}", asm_path.display()))
+}
+
+fn snapshot_path(root: &Path, target_cpu: &str, kernel: &str) -> PathBuf {
+    root.join("xtask")
+        .join("codegen")
+        .join(format!("{ARCH}-{target_cpu}"))
+        .join(format!("{kernel}.s"))
+}
+
+/// Returns false if any kernel regressed or has no snapshot.
+fn check_target_cpu(root: &Path, target_cpu: &str, bless: bool) -> Result<bool, String> {
+    let asm = emit_asm(root, target_cpu)?;
+    let mut ok = true;
+
+    println!("\n[{ARCH}-{target_cpu}]");
+    println!(
+        "{:<56} {:>7} {:>9} {:>8}",
+        "kernel", "instrs", "branches", "selects"
+    );
+
+    for kernel in KERNELS {
+        let body = extract_function(&asm, kernel)
+            .ok_or_else(|| format!("{kernel} not found in the assembly for {target_cpu}"))?;
+        let current = stats(&body);
+        let path = snapshot_path(root, target_cpu, kernel);
+
+        let status = if bless {
+            fs::create_dir_all(path.parent().unwrap()).map_err(|err| err.to_string())?;
+            fs::write(&path, body.join("\n") + "\n").map_err(|err| err.to_string())?;
+            "blessed".to_string()
+        } else if let Ok(snapshot) = fs::read_to_string(&path) {
+            let snapshot = snapshot.lines().map(String::from).collect::<Vec<_>>();
+            let expected = stats(&snapshot);
+
+            if current.conditional_branches > expected.conditional_branches
+                || current.selects < expected.selects
+            {
+                ok = false;
+                format!(
+                    "REGRESSED, snapshot has {} branches and {} selects",
+                    expected.conditional_branches, expected.selects
+                )
+            } else if snapshot != body {
+                format!(
+                    "changed, snapshot has {} instructions",
+                    expected.instructions
+                )
+            } else {
+                "ok".to_string()
+            }
+        } else {
+            ok = false;
+            format!("no snapshot at {}, run with --bless", path.display())
+        };
+
+        println!(
+            "{kernel:<56} {:>7} {:>9} {:>8}  {status}",
+            current.instructions, current.conditional_branches, current.selects
+        );
+    }
+
+    Ok(ok)
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut target_cpus = default_target_cpus()
+        .iter()
+        .map(|cpu| cpu.to_string())
+        .collect::<Vec<_>>();
+    let mut bless = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bless" => bless = true,
+            "--target-cpu" => {
+                let Some(cpus) = args.next() else {
+                    eprintln!("--target-cpu needs a value, eg. x86-64-v3");
+                    return ExitCode::FAILURE;
+                };
+                target_cpus = cpus.split(',').map(String::from).collect();
+            }
+            _ => {
+                eprintln!("Unknown argument '{arg}'");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let root = repo_root();
+    let mut ok = true;
+
+    for target_cpu in &target_cpus {
+        match check_target_cpu(&root, target_cpu, bless) {
+            Ok(cpu_ok) => ok &= cpu_ok,
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("\nCodegen check failed");
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,197 @@
+//! Runs the tests of the Rust sorts on other targets, by default 32-bit x86 and ARM and 64-bit
+//! RISC-V and s390x. On the 32-bit targets `usize` is as wide as `u32`, which changes the overflow
+//! behavior of index and length math. RISC-V has no conditional move in the base ISA, so the code
+//! the branchless selects compile to is entirely different from x86 and ARM. s390x is big-endian,
+//! it catches radix sorts and key encodings that take the bytes of a key from memory instead of
+//! from its value.
+//!
+//! Uses `cross` if it is installed, it brings the linkers and QEMU in a container. Otherwise the
+//! target has to be installed with rustup, and the tests are linked with the cross gcc and run
+//! under qemu-user, both found by their Debian names, eg. riscv64-linux-gnu-gcc and qemu-riscv64.
+//! Already set `CARGO_TARGET_<TRIPLE>_LINKER` and `_RUNNER` variables take precedence.
+//!
+//! Only the pure Rust sorts are tested, the default features are off so none of the C and C++
+//! sorts are built. The `reduced_test_sizes` feature keeps the emulation time in the range of
+//! minutes. `--features` adds pure Rust features of the root crate, eg. `rust_radsort` for the
+//! float keys of the radix tests.
+
+use std::env;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use crate::repo_root;
+
+struct Target {
+    triple: &'static str,
+    /// Prefix of the cross gcc and the sysroot under /usr, `None` if the host toolchain can
+    /// link for the target.
+    gcc_prefix: Option<&'static str>,
+    /// qemu-user binary, `None` if an x86_64 Linux host runs the binaries natively.
+    qemu: Option<&'static str>,
+}
+
+const TARGETS: &[Target] = &[
+    Target {
+        triple: "i686-unknown-linux-gnu",
+        gcc_prefix: None,
+        qemu: None,
+    },
+    Target {
+        triple: "armv7-unknown-linux-gnueabihf",
+        gcc_prefix: Some("arm-linux-gnueabihf"),
+        qemu: Some("qemu-arm"),
+    },
+    Target {
+        triple: "riscv64gc-unknown-linux-gnu",
+        gcc_prefix: Some("riscv64-linux-gnu"),
+        qemu: Some("qemu-riscv64"),
+    },
+    Target {
+        triple: "s390x-unknown-linux-gnu",
+        gcc_prefix: Some("s390x-linux-gnu"),
+        qemu: Some("qemu-s390x"),
+    },
+];
+
+fn has_cross() -> bool {
+    Command::new("cross")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn test_command(
+    root: &Path,
+    target: &Target,
+    use_cross: bool,
+    manifest: &str,
+    features: &[String],
+) -> Command {
+    let mut features = features.to_vec();
+    features.push("reduced_test_sizes".to_string());
+
+    let mut cmd = Command::new(if use_cross { "cross" } else { env!("CARGO") });
+    cmd.current_dir(root)
+        .args(["test", "--release", "--target", target.triple])
+        .arg("--manifest-path")
+        .arg(root.join(manifest))
+        .args(["--no-default-features", "--features", &features.join(",")]);
+
+    if use_cross {
+        return cmd;
+    }
+
+    let env_prefix = format!(
+        "CARGO_TARGET_{}",
+        target.triple.to_uppercase().replace('-', "_")
+    );
+
+    if let Some(gcc_prefix) = target.gcc_prefix {
+        let linker_var = format!("{env_prefix}_LINKER");
+        if env::var_os(&linker_var).is_none() {
+            cmd.env(linker_var, format!("{gcc_prefix}-gcc"));
+        }
+
+        // The dynamic loader and libc of the target, as installed by the Debian cross packages.
+        if env::var_os("QEMU_LD_PREFIX").is_none() {
+            cmd.env("QEMU_LD_PREFIX", format!("/usr/{gcc_prefix}"));
+        }
+    }
+
+    if let Some(qemu) = target.qemu {
+        let runner_var = format!("{env_prefix}_RUNNER");
+        if env::var_os(&runner_var).is_none() {
+            cmd.env(runner_var, qemu);
+        }
+    }
+
+    cmd
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut targets = TARGETS.iter().collect::<Vec<_>>();
+    let mut features = Vec::new();
+    let mut test_args = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--target" => {
+                let Some(triples) = args.next() else {
+                    eprintln!("--target needs a value, eg. riscv64gc-unknown-linux-gnu");
+                    return ExitCode::FAILURE;
+                };
+
+                targets.clear();
+                for triple in triples.split(',') {
+                    let Some(target) = TARGETS.iter().find(|target| target.triple == triple) else {
+                        let known = TARGETS.iter().map(|target| target.triple);
+                        eprintln!(
+                            "Unknown target '{triple}', expected one of {}",
+                            known.collect::<Vec<_>>().join(", ")
+                        );
+                        return ExitCode::FAILURE;
+                    };
+                    targets.push(target);
+                }
+            }
+            "--features" => {
+                let Some(names) = args.next() else {
+                    eprintln!("--features needs a value, eg. rust_radsort");
+                    return ExitCode::FAILURE;
+                };
+
+                features.extend(names.split(',').map(String::from));
+            }
+            "--" => {
+                test_args.extend(args.by_ref().cloned());
+            }
+            _ => {
+                eprintln!("Unknown argument '{arg}'");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let root = repo_root();
+    let use_cross = has_cross();
+    if !use_cross {
+        println!("cross not found, using the rustup targets with qemu-user");
+    }
+
+    let mut failed = Vec::new();
+
+    for target in targets {
+        // ipnsort has its own test suite, the root crate tests it and the other Rust sorts.
+        for manifest in ["Cargo.toml", "ipnsort/Cargo.toml"] {
+            println!("\n[{} {manifest}]", target.triple);
+
+            // The features are those of the root crate.
+            let features = if manifest == "Cargo.toml" {
+                &features[..]
+            } else {
+                &[]
+            };
+            let mut cmd = test_command(&root, target, use_cross, manifest, features);
+            if !test_args.is_empty() {
+                cmd.arg("--").args(&test_args);
+            }
+
+            match cmd.status() {
+                Ok(status) if status.success() => {}
+                Ok(_) => failed.push(format!("{} {manifest}", target.triple)),
+                Err(err) => {
+                    eprintln!("Failed to run the tests: {err}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("\nCross tests failed for: {}", failed.join(", "));
+        ExitCode::FAILURE
+    }
+}
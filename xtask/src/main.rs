@@ -0,0 +1,40 @@
+//! Repository automation, run with `cargo xtask <command>` from the repository root.
+//!
+//! Commands:
+//!
+//! - `codegen [--target-cpu <cpu>[,<cpu>...]] [--bless]` Emits the assembly of the hot ipnsort
+//!   kernels and compares it to the checked in snapshots, see codegen.rs.
+//! - `cross-test [--target <triple>[,<triple>...]] [--features <a>[,<b>...]] [-- <test args>]` Runs
+//!   the tests of the Rust sorts on 32-bit, RISC-V and big-endian targets with cross or QEMU, see
+//!   cross_test.rs.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod codegen;
+mod cross_test;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match args.first().map(String::as_str) {
+        Some("codegen") => codegen::run(&args[1..]),
+        Some("cross-test") => cross_test::run(&args[1..]),
+        _ => {
+            eprintln!("Usage: cargo xtask codegen [--target-cpu <cpu>[,<cpu>...]] [--bless]");
+            eprintln!(
+                "       cargo xtask cross-test [--target <triple>[,<triple>...]] \
+                 [--features <a>[,<b>...]] [-- <test args>]"
+            );
+            ExitCode::FAILURE
+        }
+    }
+}